@@ -4,26 +4,181 @@ use serde::Deserialize;
 use std::env;
 use config::{Config, ConfigError, File};
 use std::convert::TryFrom;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct AppConfig {
     pub ipc_path: String,
+    /// HTTP(S) or WebSocket RPC endpoint; takes precedence over `ipc_path` when set
+    #[serde(default)]
+    pub rpc_url: Option<String>,
     pub future_block_offset: u64,
+    /// Retries for failed Ethereum RPC calls
+    #[serde(default)]
+    pub eth_retry: RetryConfig,
     pub state_file: String,
     pub script_file: String,
     pub default_total_counted_seats: usize,
     pub default_max_earner_seats: usize,
+    /// Most recent months of an earner's revenue that determine its raffle tickets
+    #[serde(default = "default_revenue_window_months")]
+    pub revenue_window_months: usize,
     pub default_qualified_majority_threshold: f64,
+    /// Share of a formal vote's eligible seats that must cast a counted vote for it
+    /// to pass; 0.0 disables the quorum
+    #[serde(default)]
+    pub default_quorum: f64,
     pub counted_vote_points: u32,
     pub uncounted_vote_points: u32,
+    /// Points for abstaining in a formal vote, capped at the seat's counted/uncounted points
+    #[serde(default = "default_abstain_points")]
+    pub abstain_points: u32,
+    /// Size in bytes above which saving the state file logs a warning
+    #[serde(default = "default_state_file_warn_bytes")]
+    pub state_file_warn_bytes: u64,
+    /// Timestamped copies of the state file kept in `state_backups/`; 0 disables them
+    #[serde(default = "default_state_backup_count")]
+    pub state_backup_count: usize,
+    /// Block formal votes until every assigned reviewer has signed off
+    #[serde(default)]
+    pub require_reviewer_sign_off: bool,
+    /// Let teams request changes to votes they have already cast while a vote is open
+    #[serde(default)]
+    pub allow_vote_changes: bool,
+    /// Hours after casting a vote during which a team may request to change it
+    #[serde(default = "default_vote_change_window_hours")]
+    pub vote_change_window_hours: u64,
+    /// Suggest the closest team or proposal name when a lookup by name fails
+    #[serde(default = "default_allow_fuzzy_match")]
+    pub allow_fuzzy_match: bool,
+    /// Only warn, instead of refusing, when a new proposal's title closely matches
+    /// one already in the epoch
+    #[serde(default)]
+    pub allow_similar_proposals: bool,
+    /// Per-token budget caps for an epoch, used to report remaining headroom
+    #[serde(default)]
+    pub epoch_budget_caps: HashMap<String, f64>,
+    /// Contract address of each ERC-20 token paid through the treasury Safe; ETH is sent natively
+    #[serde(default)]
+    pub token_addresses: HashMap<String, String>,
+    /// Chain the treasury Safe is deployed on, written into multisig payloads
+    #[serde(default = "default_safe_chain_id")]
+    pub safe_chain_id: u64,
+    /// Discord webhook that receives epoch, vote, raffle and payment notifications
+    #[serde(default)]
+    pub discord_webhook_url: Option<String>,
     pub telegram: TelegramConfig,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct RetryConfig {
+    /// Total tries for a call before giving up
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each further retry
+    #[serde(default = "default_retry_base_delay_ms")]
+    pub base_delay_ms: u64,
+    /// Longest delay between two tries, before jitter
+    #[serde(default = "default_retry_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Upper bound of the random delay added to each retry
+    #[serde(default = "default_retry_jitter_ms")]
+    pub jitter_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            base_delay_ms: default_retry_base_delay_ms(),
+            max_delay_ms: default_retry_max_delay_ms(),
+            jitter_ms: default_retry_jitter_ms(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct TelegramConfig {
     pub chat_id: String,
     #[serde(skip)]
     pub token: String,
+    /// Default timezone used to resolve date-only inputs from the chat (e.g. "Europe/Berlin")
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Timezone of individual chats, keyed by chat id; chats not listed use `timezone`
+    #[serde(default)]
+    pub chat_timezones: HashMap<String, String>,
+    /// Days after approval before an unpaid request is reported to the chat as overdue
+    #[serde(default = "default_overdue_payment_days")]
+    pub overdue_payment_days: u64,
+    /// Allow @-mentioning teams that have not yet voted; off by default to avoid accidental mass-pings
+    #[serde(default)]
+    pub allow_absent_voter_pings: bool,
+    /// Chats allowed to run commands, in addition to `chat_id`
+    #[serde(default)]
+    pub allowed_chat_ids: Vec<String>,
+    /// Let any chat run read-only commands such as /print_team_report
+    #[serde(default)]
+    pub public_read_only_commands: bool,
+}
+
+impl TelegramConfig {
+    /// Timezone used to resolve date-only inputs sent from `chat_id`.
+    pub fn timezone_for_chat(&self, chat_id: &str) -> &str {
+        self.chat_timezones.get(chat_id).unwrap_or(&self.timezone)
+    }
+}
+
+fn default_state_file_warn_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_state_backup_count() -> usize {
+    20
+}
+
+fn default_abstain_points() -> u32 {
+    5
+}
+
+fn default_revenue_window_months() -> usize {
+    3
+}
+
+fn default_allow_fuzzy_match() -> bool {
+    true
+}
+
+fn default_vote_change_window_hours() -> u64 {
+    24
+}
+
+fn default_retry_max_attempts() -> u32 {
+    3
+}
+
+fn default_retry_base_delay_ms() -> u64 {
+    500
+}
+
+fn default_retry_max_delay_ms() -> u64 {
+    10_000
+}
+
+fn default_retry_jitter_ms() -> u64 {
+    250
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_overdue_payment_days() -> u64 {
+    30
+}
+
+fn default_safe_chain_id() -> u64 {
+    1
 }
 
 impl AppConfig {
@@ -64,6 +219,11 @@ impl AppConfig {
 
         Ok(config)
     }
+
+    /// Connection string for the Ethereum node: `rpc_url` if set, otherwise `ipc_path`.
+    pub fn rpc_endpoint(&self) -> &str {
+        self.rpc_url.as_deref().unwrap_or(&self.ipc_path)
+    }
 }
 
 impl TryFrom<Config> for AppConfig {
@@ -72,17 +232,66 @@ impl TryFrom<Config> for AppConfig {
     fn try_from(config: Config) -> Result<Self, Self::Error> {
         Ok(Self {
             ipc_path: config.get_string("ipc_path")?,
+            rpc_url: config.get_string("rpc_url").ok().filter(|url| !url.is_empty()),
             future_block_offset: config.get_int("future_block_offset")? as u64,
+            eth_retry: RetryConfig {
+                max_attempts: config.get_int("eth_retry.max_attempts")
+                    .map(|attempts| attempts as u32)
+                    .unwrap_or_else(|_| default_retry_max_attempts()),
+                base_delay_ms: config.get_int("eth_retry.base_delay_ms")
+                    .map(|ms| ms as u64)
+                    .unwrap_or_else(|_| default_retry_base_delay_ms()),
+                max_delay_ms: config.get_int("eth_retry.max_delay_ms")
+                    .map(|ms| ms as u64)
+                    .unwrap_or_else(|_| default_retry_max_delay_ms()),
+                jitter_ms: config.get_int("eth_retry.jitter_ms")
+                    .map(|ms| ms as u64)
+                    .unwrap_or_else(|_| default_retry_jitter_ms()),
+            },
             state_file: config.get_string("state_file")?,
             script_file: config.get_string("script_file")?,
             default_total_counted_seats: config.get_int("default_total_counted_seats")? as usize,
             default_max_earner_seats: config.get_int("default_max_earner_seats")? as usize,
+            revenue_window_months: config.get_int("revenue_window_months")
+                .map(|months| months.max(1) as usize)
+                .unwrap_or_else(|_| default_revenue_window_months()),
             default_qualified_majority_threshold: config.get_float("default_qualified_majority_threshold")?,
+            default_quorum: config.get_float("default_quorum").unwrap_or(0.0),
             counted_vote_points: config.get_int("counted_vote_points")? as u32,
             uncounted_vote_points: config.get_int("uncounted_vote_points")? as u32,
+            abstain_points: config.get_int("abstain_points")
+                .map(|points| points as u32)
+                .unwrap_or_else(|_| default_abstain_points()),
+            state_file_warn_bytes: config.get_int("state_file_warn_bytes")
+                .map(|bytes| bytes as u64)
+                .unwrap_or_else(|_| default_state_file_warn_bytes()),
+            state_backup_count: config.get_int("state_backup_count")
+                .map(|count| count as usize)
+                .unwrap_or_else(|_| default_state_backup_count()),
+            require_reviewer_sign_off: config.get_bool("require_reviewer_sign_off").unwrap_or(false),
+            allow_vote_changes: config.get_bool("allow_vote_changes").unwrap_or(false),
+            vote_change_window_hours: config.get_int("vote_change_window_hours")
+                .map(|hours| hours as u64)
+                .unwrap_or_else(|_| default_vote_change_window_hours()),
+            allow_fuzzy_match: config.get_bool("allow_fuzzy_match").unwrap_or_else(|_| default_allow_fuzzy_match()),
+            allow_similar_proposals: config.get_bool("allow_similar_proposals").unwrap_or(false),
+            epoch_budget_caps: config.get::<HashMap<String, f64>>("epoch_budget_caps").unwrap_or_default(),
+            token_addresses: config.get::<HashMap<String, String>>("token_addresses").unwrap_or_default(),
+            safe_chain_id: config.get_int("safe_chain_id")
+                .map(|id| id as u64)
+                .unwrap_or_else(|_| default_safe_chain_id()),
+            discord_webhook_url: config.get_string("discord_webhook_url").ok().filter(|url| !url.is_empty()),
             telegram: TelegramConfig {
                 chat_id: config.get_string("telegram.chat_id")?,
                 token: String::new(),
+                timezone: config.get_string("telegram.timezone").unwrap_or_else(|_| default_timezone()),
+                chat_timezones: config.get::<HashMap<String, String>>("telegram.chat_timezones").unwrap_or_default(),
+                overdue_payment_days: config.get_int("telegram.overdue_payment_days")
+                    .map(|days| days as u64)
+                    .unwrap_or_else(|_| default_overdue_payment_days()),
+                allow_absent_voter_pings: config.get_bool("telegram.allow_absent_voter_pings").unwrap_or(false),
+                allowed_chat_ids: config.get::<Vec<String>>("telegram.allowed_chat_ids").unwrap_or_default(),
+                public_read_only_commands: config.get_bool("telegram.public_read_only_commands").unwrap_or(false),
             }
         })
     }
@@ -92,17 +301,39 @@ impl Default for AppConfig {
     fn default() -> Self {
         Self {
             ipc_path: "/tmp/reth.ipc".to_string(),
+            rpc_url: None,
             future_block_offset: 10,
+            eth_retry: RetryConfig::default(),
             state_file: "budget_system_state.json".to_string(),
             script_file: "input_script.json".to_string(),
             default_total_counted_seats: 7,
             default_max_earner_seats: 5,
+            revenue_window_months: default_revenue_window_months(),
             default_qualified_majority_threshold: 0.7,
+            default_quorum: 0.0,
             counted_vote_points: 5,
             uncounted_vote_points: 2,
+            abstain_points: default_abstain_points(),
+            state_file_warn_bytes: default_state_file_warn_bytes(),
+            state_backup_count: default_state_backup_count(),
+            require_reviewer_sign_off: false,
+            allow_vote_changes: false,
+            vote_change_window_hours: default_vote_change_window_hours(),
+            allow_fuzzy_match: default_allow_fuzzy_match(),
+            allow_similar_proposals: false,
+            epoch_budget_caps: HashMap::new(),
+            token_addresses: HashMap::new(),
+            safe_chain_id: default_safe_chain_id(),
+            discord_webhook_url: None,
             telegram: TelegramConfig {
                 chat_id: String::new(),
                 token: String::new(),
+                timezone: default_timezone(),
+                chat_timezones: HashMap::new(),
+                overdue_payment_days: default_overdue_payment_days(),
+                allow_absent_voter_pings: false,
+                allowed_chat_ids: Vec::new(),
+                public_read_only_commands: false,
             }
         }
     }
@@ -117,14 +348,39 @@ mod tests {
     fn test_app_config_defaults() {
         let config = AppConfig::default();
         assert_eq!(config.ipc_path, "/tmp/reth.ipc");
+        assert_eq!(config.rpc_url, None);
         assert_eq!(config.future_block_offset, 10);
+        assert_eq!(config.eth_retry.max_attempts, 3);
+        assert_eq!(config.eth_retry.base_delay_ms, 500);
+        assert_eq!(config.eth_retry.max_delay_ms, 10_000);
+        assert_eq!(config.eth_retry.jitter_ms, 250);
         assert_eq!(config.state_file, "budget_system_state.json");
         assert_eq!(config.script_file, "input_script.json");
         assert_eq!(config.default_total_counted_seats, 7);
         assert_eq!(config.default_max_earner_seats, 5);
+        assert_eq!(config.revenue_window_months, 3);
         assert_eq!(config.default_qualified_majority_threshold, 0.7);
+        assert_eq!(config.default_quorum, 0.0);
         assert_eq!(config.counted_vote_points, 5);
         assert_eq!(config.uncounted_vote_points, 2);
+        assert_eq!(config.abstain_points, 5);
+        assert_eq!(config.state_file_warn_bytes, 10 * 1024 * 1024);
+        assert_eq!(config.state_backup_count, 20);
+        assert!(!config.require_reviewer_sign_off);
+        assert!(!config.allow_vote_changes);
+        assert_eq!(config.vote_change_window_hours, 24);
+        assert!(config.allow_fuzzy_match);
+        assert!(!config.allow_similar_proposals);
+        assert!(config.epoch_budget_caps.is_empty());
+        assert!(config.token_addresses.is_empty());
+        assert_eq!(config.safe_chain_id, 1);
+        assert!(config.discord_webhook_url.is_none());
+        assert_eq!(config.telegram.timezone, "UTC");
+        assert!(config.telegram.chat_timezones.is_empty());
+        assert_eq!(config.telegram.overdue_payment_days, 30);
+        assert!(!config.telegram.allow_absent_voter_pings);
+        assert!(config.telegram.allowed_chat_ids.is_empty());
+        assert!(!config.telegram.public_read_only_commands);
     }
 
     #[test]
@@ -146,4 +402,23 @@ mod tests {
         env::remove_var("APP_STATE_FILE");
         env::remove_var("TELEGRAM_BOT_TOKEN");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_timezone_for_chat() {
+        let mut config = AppConfig::default().telegram;
+        assert_eq!(config.timezone_for_chat("-100"), "UTC");
+
+        config.chat_timezones.insert("-100".to_string(), "Europe/Berlin".to_string());
+        assert_eq!(config.timezone_for_chat("-100"), "Europe/Berlin");
+        assert_eq!(config.timezone_for_chat("42"), "UTC");
+    }
+
+    #[test]
+    fn test_rpc_endpoint_prefers_rpc_url() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.rpc_endpoint(), "/tmp/reth.ipc");
+
+        config.rpc_url = Some("https://eth.example.com".to_string());
+        assert_eq!(config.rpc_endpoint(), "https://eth.example.com");
+    }
+}
@@ -1,7 +1,7 @@
 // src/bin/robokitty_cli.rs
 
-use robokitty::{initialize_environment, initialize_system};
-use robokitty::commands::cli::{parse_cli_args, execute_command};
+use robokitty::{initialize_environment, initialize_system, enforce_deadlines};
+use robokitty::commands::cli::{parse_cli, execute_command};
 use robokitty::lock;
 use std::{env, io};
 
@@ -10,14 +10,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     initialize_environment();
     
     let args: Vec<String> = env::args().collect();
-    let command = parse_cli_args(&args)?;
+    let (command, dry_run) = parse_cli(&args)?;
 
     let (mut budget_system, config) = initialize_system().await?;
+    let mut stdout = io::stdout();
+
+    if dry_run {
+        return execute_command(&mut budget_system, command, &config, &mut stdout, true).await;
+    }
     
     lock::create_lock_file()?;
+    enforce_deadlines(&mut budget_system);
     
-    let mut stdout = io::stdout();
-    let result = execute_command(&mut budget_system, command, &config, &mut stdout).await;
+    let result = execute_command(&mut budget_system, command, &config, &mut stdout, false).await;
     
     budget_system.save_state()?;
     lock::remove_lock_file()?;
@@ -1,13 +1,16 @@
 // src/commands/cli.rs
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use std::{collections::HashMap, io::Write};
 use std::{fs, error::Error};
 
-use crate::core::models::VoteChoice;
+use crate::core::models::{EpochStatus, ProposalCategory, ProposalListFilter, ProposalStatus, SeatOutcome, VoteChoice};
 use crate::core::budget_system::BudgetSystem;
 use crate::app_config::AppConfig;
-use super::common::{BudgetRequestDetailsCommand, Command, CommandExecutor, UpdateTeamDetails, UpdateProposalDetails};
+use crate::services::export::ExportFormat;
+use crate::services::ethereum::validate_ethereum_address;
+use super::common::{BudgetRequestDetailsCommand, Command, CommandExecutor, UpdateTeamDetails, UpdateProposalDetails, ReportFormat, ExportKind};
 use clap::{Parser, Subcommand};
+use uuid::Uuid;
 
 #[derive(Parser)]
 #[command(name = "robokitty")]
@@ -15,6 +18,10 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Run without saving and list the changes the command would make
+    #[arg(short = 'n', long, global = true)]
+    dry_run: bool,
 }
 
 #[derive(Subcommand)]
@@ -59,6 +66,23 @@ pub enum Commands {
     RunScript {
         script_file_path: Option<String>,
     }, 
+    /// Add proposals from a JSON array (see schemas/import_proposals.schema.json)
+    ImportProposals {
+        /// Path to the JSON file; with --dry-run the proposals are validated without being added
+        #[arg(long)]
+        file: String,
+    },
+    /// Replace the current state with a backup from state_backups/, or with the
+    /// state file's .bak copy when no timestamp is given
+    RestoreBackup {
+        /// Backup timestamp as shown in the file name (state_<TIMESTAMP>.json)
+        #[arg(value_name = "TIMESTAMP")]
+        timestamp: Option<String>,
+
+        /// Replace the state (otherwise only the backup contents are shown)
+        #[arg(long)]
+        confirm: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -107,6 +131,88 @@ pub enum TeamCommands {
         /// New payment address 
         #[arg(long, value_name = "ADDRESS")]
         address: Option<String>,
+    },
+
+    /// Merge a team into another, keeping its name as an alias
+    Merge {
+        /// Team to merge and mark inactive
+        #[arg(value_name = "SOURCE")]
+        source: String,
+
+        /// Team that takes over the source's open proposals
+        #[arg(value_name = "TARGET")]
+        target: String,
+    },
+
+    /// Add an alias the team can also be looked up by
+    Alias {
+        /// Team name
+        #[arg(value_name = "TEAM")]
+        name: String,
+        /// Alternative name for the team
+        #[arg(value_name = "ALIAS")]
+        alias: String,
+    },
+
+    /// Archive a team, excluding it from raffles and votes
+    Archive {
+        /// Team name
+        #[arg(value_name = "TEAM")]
+        name: String,
+    },
+
+    /// Restore an archived team to its previous status
+    Unarchive {
+        /// Team name
+        #[arg(value_name = "TEAM")]
+        name: String,
+    },
+
+    /// Set or clear a team's region
+    Region {
+        /// Team name
+        #[arg(value_name = "TEAM")]
+        name: String,
+
+        /// Region name (omit to clear)
+        #[arg(value_name = "REGION")]
+        region: Option<String>,
+    },
+
+    /// Set or clear a team's Telegram contacts
+    Contacts {
+        /// Team name
+        #[arg(value_name = "TEAM")]
+        name: String,
+
+        /// Comma-separated Telegram handles (omit to clear)
+        #[arg(long, value_name = "HANDLES")]
+        handles: Option<String>,
+    },
+
+    /// List a team's proposals across epochs
+    Proposals {
+        /// Team name
+        #[arg(value_name = "TEAM")]
+        name: String,
+
+        /// Only list proposals from this epoch
+        #[arg(long, value_name = "EPOCH")]
+        epoch_name: Option<String>,
+    },
+
+    /// Show a team's approved budget requests and their payments across epochs
+    PaymentHistory {
+        /// Team name
+        #[arg(value_name = "TEAM")]
+        name: String,
+    },
+
+    /// List teams, optionally filtered by status
+    List {
+        /// Only list teams with this status (active, earner, supporter, inactive or archived)
+        #[arg(long, value_name = "STATUS")]
+        status: Option<String>,
     }
 }
 
@@ -127,6 +233,25 @@ pub enum EpochCommands {
         end_date: String,
     },
 
+    /// Create a planned epoch with another epoch's rewards
+    Clone {
+        /// Epoch to copy the rewards from
+        #[arg(value_name = "SOURCE")]
+        source: String,
+
+        /// Name of the new epoch
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Start date (RFC 3339)
+        #[arg(value_name = "START_DATE")]
+        start_date: String,
+
+        /// End date (RFC 3339)
+        #[arg(value_name = "END_DATE")]
+        end_date: String,
+    },
+
     /// Activate an epoch for proposals
     Activate {
         /// Epoch name to activate
@@ -150,6 +275,73 @@ pub enum EpochCommands {
         /// Optional epoch name (uses active if omitted)
         #[arg(value_name = "NAME")]
         epoch_name: Option<String>,
+
+        /// Retract any open proposals before closing
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Set proposal voting windows for an epoch
+    SetVotingWindows {
+        /// Optional epoch name (uses active if omitted)
+        #[arg(value_name = "NAME")]
+        epoch_name: Option<String>,
+
+        /// Minimum days between announcement and vote
+        #[arg(long, value_name = "DAYS")]
+        min_days_before_vote: Option<u64>,
+
+        /// Maximum days a proposal may wait for a decision
+        #[arg(long, value_name = "DAYS")]
+        max_days_to_vote: Option<u64>,
+    },
+
+    /// Recompute a closed epoch's team rewards from its votes
+    Recompute {
+        /// Epoch name
+        #[arg(value_name = "NAME")]
+        epoch_name: String,
+
+        /// Overwrite the stored rewards (otherwise only differences are shown)
+        #[arg(long)]
+        confirm: bool,
+    },
+
+    /// Force an epoch's status for recovery, bypassing the normal lifecycle
+    ForceStatus {
+        /// Epoch name
+        #[arg(value_name = "NAME")]
+        epoch_name: String,
+
+        /// New status (planned/active/closed)
+        #[arg(value_name = "STATUS")]
+        status: EpochStatus,
+
+        /// Apply the change (otherwise only the warnings are shown)
+        #[arg(long)]
+        confirm: bool,
+
+        /// Allow closing an epoch that still has actionable proposals
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Move the end date of an active epoch forward
+    Extend {
+        /// New end date (RFC 3339)
+        #[arg(value_name = "END_DATE")]
+        end_date: String,
+
+        /// Optional epoch name (uses active if omitted)
+        #[arg(long, value_name = "NAME")]
+        epoch_name: Option<String>,
+    },
+
+    /// List epochs with their status and dates
+    List {
+        /// Include closed epochs
+        #[arg(long)]
+        include_closed: bool,
     }
 }
 
@@ -196,6 +388,14 @@ pub enum ProposalCommands {
        /// Date published (YYYY-MM-DD)
        #[arg(long, value_name = "PUBLISHED")] 
        published_at: Option<String>,
+
+       /// Budget request category: infrastructure, research, marketing, operations, grant, loan or any other name
+       #[arg(long, value_name = "CATEGORY")]
+       category: Option<String>,
+
+       /// IPFS hash (CID) of the proposal document
+       #[arg(long, value_name = "CID")]
+       ipfs_hash: Option<String>,
    },
 
    /// Update an existing proposal 
@@ -235,6 +435,10 @@ pub enum ProposalCommands {
        /// Date published (YYYY-MM-DD)
        #[arg(long, value_name = "PUBLISHED")] 
        published_at: Option<String>,
+
+       /// Budget request category
+       #[arg(long, value_name = "CATEGORY")]
+       category: Option<String>,
    },
 
    /// Close a proposal
@@ -246,6 +450,39 @@ pub enum ProposalCommands {
        resolution: String,
    },
    
+   /// Reopen a closed, unpaid proposal in an open epoch
+   Reopen {
+       /// Proposal name
+       name: String,
+   },
+
+   /// Attach the IPFS hash (CID) of a proposal's document
+   PinDocument {
+       /// Proposal name
+       name: String,
+
+       /// IPFS hash, CIDv0 (Qm...) or CIDv1 (bafy...)
+       #[arg(value_name = "CID")]
+       ipfs_hash: String,
+   },
+
+   /// Close all open proposals in an epoch
+   BulkClose {
+       /// Epoch name
+       epoch_name: String,
+
+       /// Resolution (Approved/Rejected/Invalid/Duplicate/Retracted)
+       resolution: String,
+
+       /// Reason recorded in the log for each closure
+       #[arg(long)]
+       reason: Option<String>,
+
+       /// Close the proposals (otherwise only lists them)
+       #[arg(long)]
+       confirm: bool,
+   },
+
    /// Log payment for approved and unpaid proposals
    Pay {
     /// Proposal names to be marked as paid (comma separated)
@@ -259,7 +496,206 @@ pub enum ProposalCommands {
     /// Payment date (YYYY-MM-DD)  
     #[arg(long)]
     date: String,
-}
+
+    /// Record without checking the transaction on-chain (for historical imports)
+    #[arg(long)]
+    skip_verification: bool,
+},
+
+   /// Set interest rate, due date and repayment schedule for a loan proposal
+   LoanTerms {
+       /// Proposal name
+       name: String,
+
+       /// Interest rate as a fraction (e.g. 0.05 for 5%)
+       #[arg(long, value_name = "RATE")]
+       interest_rate: f64,
+
+       /// Due date (YYYY-MM-DD)
+       #[arg(long, value_name = "DUE")]
+       due: String,
+
+       /// Repayment schedule (AtMaturity/Monthly/Quarterly)
+       #[arg(long, value_name = "SCHEDULE", default_value = "AtMaturity")]
+       schedule: String,
+
+       /// Amounts repaid so far (format: ETH:100.5,USD:1000)
+       #[arg(long, value_name = "REPAID")]
+       repaid: Option<String>,
+   },
+
+   /// Split a proposal's budget request between several teams
+   Allocations {
+       /// Proposal name
+       name: String,
+
+       /// Team allocation (format: "Team Name=ETH:100.5,USD:1000"); repeat for each team
+       #[arg(long = "team", value_name = "ALLOCATION", required = true)]
+       teams: Vec<String>,
+   },
+
+   /// Log payment of some teams' allocations of a multi-team proposal
+   PayAllocation {
+       /// Proposal name
+       name: String,
+
+       /// Teams covered by the payment (comma separated)
+       #[arg(long, value_name = "TEAMS")]
+       teams: String,
+
+       /// Payment transaction hash
+       #[arg(long)]
+       tx: String,
+
+       /// Payment date (YYYY-MM-DD)
+       #[arg(long)]
+       date: String,
+
+       /// Record without checking the transaction on-chain
+       #[arg(long)]
+       skip_verification: bool,
+   },
+
+   /// Mark a paid-out loan as repaid
+   Repay {
+       /// Proposal name
+       name: String,
+
+       /// Repayment date (YYYY-MM-DD), defaults to today
+       #[arg(long)]
+       date: Option<String>,
+   },
+
+   /// List proposals in the active epoch with a title similar to the given one
+   CheckDuplicate {
+       /// Title of the proposal about to be added
+       title: String,
+   },
+
+   /// List proposals ten at a time
+   List {
+       /// open, approved, rejected, paid, unpaid or all
+       #[arg(long, default_value = "open")]
+       status: String,
+
+       /// Epoch name (defaults to the active epoch)
+       #[arg(long)]
+       epoch: Option<String>,
+
+       /// Only list proposals requesting funds for this team
+       #[arg(long)]
+       team: Option<String>,
+
+       /// Only list approved budget requests that have not been paid
+       #[arg(long)]
+       unpaid_only: bool,
+
+       /// Page number, starting at 1
+       #[arg(long, default_value_t = 1)]
+       page: usize,
+   },
+
+   /// Set the reviewers who must sign off before a vote
+   Reviewers {
+       /// Proposal name
+       name: String,
+
+       /// Reviewer names (comma separated, omit to clear)
+       #[arg(long, value_name = "REVIEWERS")]
+       reviewers: Option<String>,
+   },
+
+   /// Record a reviewer's sign-off
+   SignOff {
+       /// Proposal name
+       name: String,
+
+       /// Reviewer name
+       reviewer: String,
+
+       /// Sign-off date (YYYY-MM-DD, defaults to today)
+       #[arg(long, value_name = "DATE")]
+       date: Option<String>,
+   },
+
+   /// Create a reusable proposal template
+   TemplateCreate {
+       /// Template name
+       name: String,
+
+       /// Title pattern ({epoch} and {n} are replaced)
+       #[arg(long, value_name = "TITLE")]
+       title: String,
+
+       /// Requesting team name
+       #[arg(long)]
+       team: Option<String>,
+
+       /// Default amounts (format: ETH:100.5,USD:1000)
+       #[arg(long, value_name = "AMOUNTS")]
+       amounts: Option<String>,
+
+       /// Proposals from this template are loans
+       #[arg(long)]
+       loan: bool,
+
+       /// Tags (comma separated)
+       #[arg(long, value_name = "TAGS")]
+       tags: Option<String>,
+
+       /// Recur every N epochs
+       #[arg(long, value_name = "EPOCHS")]
+       every: Option<u32>,
+
+       /// Stop after this many proposals
+       #[arg(long, value_name = "COUNT", requires = "every")]
+       max: Option<u32>,
+   },
+
+   /// List proposal templates
+   Templates,
+
+   /// Create a proposal from a template
+   FromTemplate {
+       /// Template name
+       template: String,
+
+       /// Epoch name (uses active if omitted)
+       #[arg(long)]
+       epoch: Option<String>,
+
+       /// Override the generated title
+       #[arg(long)]
+       title: Option<String>,
+
+       /// Proposal URL
+       #[arg(long)]
+       url: Option<String>,
+
+       /// Override the requesting team
+       #[arg(long)]
+       team: Option<String>,
+
+       /// Override the amounts (format: ETH:100.5,USD:1000)
+       #[arg(long)]
+       amounts: Option<String>,
+
+       /// Start date (YYYY-MM-DD)
+       #[arg(long)]
+       start: Option<NaiveDate>,
+
+       /// End date (YYYY-MM-DD)
+       #[arg(long)]
+       end: Option<NaiveDate>,
+
+       /// Date announced (YYYY-MM-DD)
+       #[arg(long)]
+       announced_at: Option<NaiveDate>,
+
+       /// Date published (YYYY-MM-DD)
+       #[arg(long)]
+       published_at: Option<NaiveDate>,
+   }
 }
 
 #[derive(Subcommand)]
@@ -276,6 +712,10 @@ pub enum VoteCommands {
        /// Uncounted votes (format: Team3:Yes,Team4:No)
        #[arg(long, value_name = "UNCOUNTED")]  
        uncounted: String,
+
+       /// Vote cast by another team (format: "Team1=Team2" when Team2 voted for Team1); repeat for each delegation
+       #[arg(long = "delegate", value_name = "DELEGATION")]
+       delegations: Vec<String>,
        
        /// Vote opened date (YYYY-MM-DD)
        #[arg(long, value_name = "OPENED")]
@@ -284,6 +724,56 @@ pub enum VoteCommands {
        /// Vote closed date (YYYY-MM-DD)
        #[arg(long, value_name = "CLOSED")]
        closed: Option<String>,
+
+       /// Voting deadline (YYYY-MM-DD, end of day UTC)
+       #[arg(long, value_name = "DEADLINE")]
+       deadline: Option<String>,
+   },
+
+   /// Close open votes whose deadline has passed
+   CloseExpired,
+
+   /// Request a change to a team's vote while the vote is open
+   RequestChange {
+       /// Vote ID
+       vote_id: Uuid,
+
+       /// Team name
+       team: String,
+
+       /// New choice (Yes/No)
+       choice: VoteChoice,
+
+       /// Reason for the change
+       #[arg(long, value_name = "REASON")]
+       reason: String,
+   },
+
+   /// Approve a team's pending vote change
+   ApproveChange {
+       /// Vote ID
+       vote_id: Uuid,
+
+       /// Team name
+       team: String,
+   },
+
+   /// List pending vote change requests
+   ChangeRequests {
+       /// Vote ID
+       vote_id: Uuid,
+   },
+
+   /// Reopen the closed vote on an unpaid proposal in an open epoch; every seat votes again
+   Reopen {
+       /// Proposal name
+       name: String,
+   },
+
+   /// Mention teams that have not voted yet in the configured Telegram chat
+   PingAbsent {
+       /// Proposal name
+       name: String,
    }
 }
 
@@ -301,6 +791,80 @@ pub enum RaffleCommands {
        /// Excluded teams (comma separated)
        #[arg(long, value_name = "EXCLUDED")]
        excluded: Option<String>,
+   },
+
+   /// Replace a proposal's raffle with a new draw on fresh randomness, keeping its seats and exclusions
+   Rerun {
+       /// Proposal name
+       name: String,
+
+       /// Block offset
+       #[arg(long, value_name = "OFFSET")]
+       block_offset: Option<u64>,
+   },
+
+   /// List teams excluded from a proposal's raffle
+   Excluded {
+       /// Proposal name
+       name: String,
+   },
+
+   /// List the deciding (counted) teams selected by a proposal's raffle
+   Deciders {
+       /// Proposal name
+       name: String,
+   },
+
+   /// Show a proposal's raffle: its configuration, team snapshots and seats
+   Show {
+       /// Proposal name
+       name: String,
+   },
+
+   /// Show raffle eligibility and seat chances for the current epoch without creating a raffle
+   Eligibility {
+       /// Excluded teams (comma separated)
+       #[arg(long, value_name = "EXCLUDED")]
+       excluded: Option<String>,
+   },
+
+   /// Set the default max earner seats for new raffles
+   MaxEarnerSeats {
+       /// Maximum number of earner seats
+       #[arg(value_name = "SEATS")]
+       seats: usize,
+   },
+
+   /// Manually move a team between counted, uncounted and excluded before voting
+   Reassign {
+       /// Proposal name
+       proposal: String,
+
+       /// Team name
+       team: String,
+
+       /// New outcome (counted/uncounted/excluded)
+       outcome: SeatOutcome,
+
+       /// Reason recorded in the raffle's audit log
+       #[arg(long, value_name = "REASON")]
+       reason: String,
+   },
+
+   /// Replay a raffle from its recorded randomness and check the result
+   Verify {
+       /// Proposal name
+       name: String,
+
+       /// Also re-fetch the block randomness from the node
+       #[arg(long)]
+       on_chain: bool,
+   },
+
+   /// Print a Markdown audit of a raffle's randomness, ticket scores and seats
+   Audit {
+       /// Proposal name
+       name: String,
    }
 }
 
@@ -310,7 +874,11 @@ pub enum ReportCommands {
    Team,
 
    /// Print epoch state report
-   EpochState,
+   EpochState {
+       /// Output format (text or json)
+       #[arg(long, default_value = "text")]
+       format: ReportFormat,
+   },
 
    /// Print team vote participation
    TeamParticipation {
@@ -324,6 +892,12 @@ pub enum ReportCommands {
        epoch_name: Option<String>,
    },
 
+   /// Print proposal counts and amounts per budget category
+   Categories {
+       #[arg(long, value_name = "EPOCH")]
+       epoch_name: Option<String>,
+   },
+
    /// Generate closed proposals report
    ClosedProposals {
        #[arg(value_name = "EPOCH")]
@@ -334,6 +908,16 @@ pub enum ReportCommands {
    EndOfEpoch {
        #[arg(value_name = "EPOCH")] 
        epoch_name: String,
+
+       /// Extra output next to the Markdown report: csv (one file per table) or json
+       #[arg(long, default_value = "markdown")]
+       format: ReportFormat,
+   },
+
+   /// Preview the end of epoch report without saving it
+   PreviewEndOfEpoch {
+       #[arg(value_name = "EPOCH")]
+       epoch_name: String,
    },
 
    /// Generate unpaid requests report
@@ -353,12 +937,218 @@ pub enum ReportCommands {
         /// Output file path
         #[arg(long, value_name = "PATH")]
         output: Option<String>,
+
+        /// Output format (json, csv or markdown)
+        #[arg(long, default_value = "json")]
+        format: ReportFormat,
     },
 
+   /// Generate a Safe transaction builder batch paying the epoch's unpaid approved proposals
+   MultisigPayload {
+        /// Epoch name
+        #[arg(value_name = "EPOCH")]
+        epoch_name: String,
+
+        /// Output file path
+        #[arg(long, value_name = "PATH")]
+        output: Option<String>,
+   },
+
    /// Generate report for specific proposal
    ForProposal {
        #[arg(value_name = "PROPOSAL")]
        proposal_name: String,
+
+       /// Epoch the proposal belongs to (needed when the title exists in several epochs)
+       #[arg(long, value_name = "EPOCH")]
+       epoch_name: Option<String>,
+
+       /// Sections to include, comma separated (summary, details, budget, raffle, voting)
+       #[arg(long, value_name = "SECTIONS")]
+       sections: Option<String>,
+   },
+
+   /// Print teams with consecutive epochs of vote absence
+   AbsenceStreaks {
+       /// Only show teams with at least this many consecutive absences
+       #[arg(long, value_name = "EPOCHS", default_value_t = 1)]
+       min_streak: usize,
+   },
+
+   /// Print approved requests still unpaid after a number of days
+   OverduePayments {
+       /// Days since approval after which a payment counts as overdue
+       #[arg(long, value_name = "DAYS", default_value_t = 30)]
+       days: u64,
+   },
+
+   /// List all approved requests that are still unpaid, longest pending first
+   PendingPayments {
+       /// Only include requests from this epoch
+       #[arg(long, value_name = "EPOCH")]
+       epoch: Option<String>,
+
+       /// Only include requests pending at least this many days
+       #[arg(long, value_name = "DAYS")]
+       min_days: Option<u64>,
+   },
+
+   /// Export key epoch metrics as JSON for visual reports
+   Infographic {
+       /// Epoch name
+       #[arg(value_name = "EPOCH")]
+       epoch_name: String,
+   },
+
+   /// Print approved loans with expected and actual repayments
+   Loans,
+
+   /// Print loans that have been paid out but not yet repaid
+   OutstandingLoans {
+       /// Only include loans from this epoch
+       #[arg(long, value_name = "EPOCH")]
+       epoch: Option<String>,
+   },
+
+   /// Print committed, paid and reward pool totals for an epoch
+   Treasury {
+       /// Epoch name
+       #[arg(value_name = "EPOCH")]
+       epoch_name: String,
+
+       /// Output format (text or json)
+       #[arg(long, default_value = "text")]
+       format: ReportFormat,
+   },
+
+   /// Generate a markdown report of unpaid, outstanding and repaid loans
+   LoanTracking {
+       /// File to save the report to, relative to the reports directory
+       #[arg(long, value_name = "PATH")]
+       output: Option<String>,
+   },
+
+   /// Print the state file size and what it contains
+   StateSize,
+
+   /// Recalculate team points from the votes and list any inconsistencies
+   VerifyPoints,
+
+   /// Print teams grouped by region
+   Geography,
+
+   /// Show what changed between two state files
+   StateDiff {
+       /// Older state file
+       #[arg(value_name = "PATH_A")]
+       path_a: String,
+
+       /// Newer state file
+       #[arg(value_name = "PATH_B")]
+       path_b: String,
+   },
+
+   /// Export payments, proposals or unpaid requests as CSV or TSV
+   Export {
+       /// What to export (payments, proposals or unpaid)
+       #[arg(value_name = "KIND")]
+       kind: ExportKind,
+
+       /// Epoch name (required for payments)
+       #[arg(long, value_name = "EPOCH")]
+       epoch_name: Option<String>,
+
+       /// Output format (csv or tsv)
+       #[arg(long, default_value = "csv")]
+       format: ExportFormat,
+
+       /// Output file path
+       #[arg(long, value_name = "PATH")]
+       output: Option<String>,
+   },
+
+   /// Export budget flow data (Sankey diagram JSON)
+   BudgetFlow {
+        /// Epoch name
+        #[arg(value_name = "EPOCH")]
+        epoch_name: String,
+
+        /// Output file path
+        #[arg(long, value_name = "PATH")]
+        output: Option<String>,
+   },
+
+   /// Show remaining budget per token for an epoch
+   Headroom {
+       /// Epoch name
+       #[arg(value_name = "EPOCH")]
+       epoch_name: String,
+
+       /// Budget caps as token:amount pairs (defaults to the configured caps)
+       #[arg(long)]
+       caps: Option<String>,
+   },
+
+   /// Aggregate all epochs into a single report
+   AllEpochs {
+       /// Only include closed epochs
+       #[arg(long)]
+       only_closed: bool,
+
+       /// File name under reports/ to save the report to
+       #[arg(long, value_name = "PATH")]
+       output: Option<String>,
+   },
+
+   /// Compare formal vote participation across epochs
+   VoteTrends {
+       /// Only include closed epochs
+       #[arg(long)]
+       only_closed: bool,
+
+       /// File name under reports/ to save the report to
+       #[arg(long, value_name = "PATH")]
+       output: Option<String>,
+   },
+
+   /// Show cumulative approved and paid amounts against the budget caps over time
+   Burndown {
+       /// Epoch name
+       #[arg(value_name = "EPOCH")]
+       epoch_name: String,
+
+       /// Budget caps as token:amount pairs (defaults to the configured caps)
+       #[arg(long)]
+       caps: Option<String>,
+
+       /// Write the data points as JSON for charting
+       #[arg(long, value_name = "PATH")]
+       output: Option<String>,
+   },
+
+   /// Show proposal approval rates by team status and over recent epochs
+   ApprovalRates {
+       /// Number of recent epochs in the trend
+       #[arg(long, value_name = "EPOCHS")]
+       recent_epochs: Option<usize>,
+   },
+
+   /// Show percentiles of proposal waiting time (announcement to resolution)
+   WaitingTimes {
+       /// Limit to one epoch (defaults to all epochs)
+       #[arg(long, value_name = "EPOCH")]
+       epoch_name: Option<String>,
+   },
+
+   /// Generate a team's points, participation and payments across epochs
+   TeamPerformance {
+       /// Team name
+       #[arg(value_name = "TEAM")]
+       team_name: String,
+
+       /// Output file path (defaults to reports/<epoch>/<team>_performance.md)
+       #[arg(long, value_name = "PATH")]
+       output: Option<String>,
    },
 }
 
@@ -398,16 +1188,7 @@ pub enum ImportCommands {
 
 
 fn parse_eth_address(addr: &str) -> Result<String, String> {
-    if !addr.starts_with("0x") {
-        return Err("Ethereum address must start with 0x".into());
-    }
-    if addr.len() != 42 {
-        return Err("Ethereum address must be 42 characters long".into());
-    }
-    // Basic hex check
-    if !addr[2..].chars().all(|c| c.is_ascii_hexdigit()) {
-        return Err("Invalid hex characters in address".into());
-    }
+    validate_ethereum_address(addr)?;
     Ok(addr.to_string())
 }
 
@@ -422,7 +1203,8 @@ fn parse_votes(votes_str: &str) -> Result<HashMap<String, VoteChoice>, Box<dyn E
             let choice = match parts[1].to_lowercase().as_str() {
                 "yes" => VoteChoice::Yes,
                 "no" => VoteChoice::No,
-                _ => return Err(format!("Invalid vote choice: {}. Must be Yes or No", parts[1]).into()),
+                "abstain" => VoteChoice::Abstain,
+                _ => return Err(format!("Invalid vote choice: {}. Must be Yes, No or Abstain", parts[1]).into()),
             };
             Ok((parts[0].to_string(), choice))
         })
@@ -432,6 +1214,7 @@ fn parse_votes(votes_str: &str) -> Result<HashMap<String, VoteChoice>, Box<dyn E
 
 impl Cli {
     pub fn into_command(self) -> Result<Command, Box<dyn Error>> {
+        let dry_run = self.dry_run;
         match self.command {
 
             Commands::Team { command } => match command {
@@ -468,6 +1251,36 @@ impl Cli {
                             address
                         }
                     })
+                },
+                TeamCommands::Merge { source, target } => {
+                    Ok(Command::MergeTeams { source_name: source, target_name: target })
+                },
+                TeamCommands::Alias { name, alias } => {
+                    Ok(Command::AddTeamAlias { team_name: name, alias })
+                },
+                TeamCommands::Archive { name } => {
+                    Ok(Command::ArchiveTeam { team_name: name })
+                },
+                TeamCommands::Unarchive { name } => {
+                    Ok(Command::UnarchiveTeam { team_name: name })
+                },
+                TeamCommands::Region { name, region } => {
+                    Ok(Command::UpdateTeamRegion { team_name: name, region })
+                },
+                TeamCommands::Contacts { name, handles } => {
+                    Ok(Command::SetTeamContacts {
+                        team_name: name,
+                        contacts: handles.map(|h| h.split(',').map(String::from).collect()).unwrap_or_default(),
+                    })
+                },
+                TeamCommands::Proposals { name, epoch_name } => {
+                    Ok(Command::ListTeamProposals { team_name: name, epoch_name })
+                },
+                TeamCommands::PaymentHistory { name } => {
+                    Ok(Command::PrintTeamPaymentHistory { team_name: name })
+                },
+                TeamCommands::List { status } => {
+                    Ok(Command::ListTeams { status_filter: status })
                 }
             },
 
@@ -479,19 +1292,47 @@ impl Cli {
                         .with_timezone(&Utc);
                     Ok(Command::CreateEpoch { name, start_date: start, end_date: end })
                 },
+                EpochCommands::Clone { source, name, start_date, end_date } => {
+                    let start = DateTime::parse_from_rfc3339(&start_date)?
+                        .with_timezone(&Utc);
+                    let end = DateTime::parse_from_rfc3339(&end_date)?
+                        .with_timezone(&Utc);
+                    Ok(Command::CloneEpoch { source_epoch_name: source, new_name: name, start_date: start, end_date: end })
+                },
                 EpochCommands::Activate { name } => {
                     Ok(Command::ActivateEpoch { name })
                 },
                 EpochCommands::SetReward { token, amount } => {
                     Ok(Command::SetEpochReward { token, amount }) 
                 },
-                EpochCommands::Close { epoch_name } => {
-                    Ok(Command::CloseEpoch { epoch_name })
+                EpochCommands::Close { epoch_name, force } => {
+                    if force {
+                        Ok(Command::ForceCloseEpoch { epoch_name })
+                    } else {
+                        Ok(Command::CloseEpoch { epoch_name })
+                    }
+                },
+                EpochCommands::SetVotingWindows { epoch_name, min_days_before_vote, max_days_to_vote } => {
+                    Ok(Command::SetEpochVotingWindows { epoch_name, min_days_before_vote, max_days_to_vote })
+                },
+                EpochCommands::Recompute { epoch_name, confirm } => {
+                    Ok(Command::RecomputeEpochFromVotes { epoch_name, confirm })
+                },
+                EpochCommands::ForceStatus { epoch_name, status, confirm, force } => {
+                    Ok(Command::SetEpochStatus { epoch_name, status, confirm, force })
+                },
+                EpochCommands::Extend { end_date, epoch_name } => {
+                    let new_end_date = DateTime::parse_from_rfc3339(&end_date)?
+                        .with_timezone(&Utc);
+                    Ok(Command::ExtendEpoch { epoch_name, new_end_date })
+                },
+                EpochCommands::List { include_closed } => {
+                    Ok(Command::ListEpochs { include_closed })
                 }
             },
 
             Commands::Proposal { command } => match command {
-                ProposalCommands::Add { title, url, team, amounts, start, end, loan, address, announced_at, published_at } => {
+                ProposalCommands::Add { title, url, team, amounts, start, end, loan, address, announced_at, published_at, category, ipfs_hash } => {
                     let published = published_at.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?;
                     let announced = match (announced_at, &published) {
                         (Some(d), _) => Some(NaiveDate::parse_from_str(&d, "%Y-%m-%d")?),
@@ -507,7 +1348,13 @@ impl Cli {
                             end_date: end.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?,
                             is_loan: loan,
                             payment_address: address,
+                            category: category.map(|c| c.parse::<ProposalCategory>()).transpose()?,
+                            ipfs_hash,
                         })
+                    } else if category.is_some() {
+                        return Err("--category needs a budget request (--team or --amounts)".into());
+                    } else if ipfs_hash.is_some() {
+                        return Err("--ipfs-hash needs a budget request (--team or --amounts); use `proposal pin-document` instead".into());
                     } else {
                         None
                     };
@@ -524,8 +1371,17 @@ impl Cli {
                 ProposalCommands::Close { name, resolution } => {
                     Ok(Command::CloseProposal { proposal_name: name, resolution })
                 },
+                ProposalCommands::Reopen { name } => {
+                    Ok(Command::SetProposalStatus { proposal_name: name, status: ProposalStatus::Reopened })
+                },
+                ProposalCommands::PinDocument { name, ipfs_hash } => {
+                    Ok(Command::PinProposalDocument { proposal_name: name, ipfs_hash })
+                },
+                ProposalCommands::BulkClose { epoch_name, resolution, reason, confirm } => {
+                    Ok(Command::BulkCloseProposals { epoch_name, resolution, reason, confirm })
+                },
                 ProposalCommands::Update { 
-                    name, title, url, team, amounts, start, end, loan, address, announced_at, published_at 
+                    name, title, url, team, amounts, start, end, loan, address, announced_at, published_at, category
                 } => {
                     let published = published_at.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?;
                     let announced = announced_at.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?;
@@ -539,6 +1395,8 @@ impl Cli {
                             end_date: end.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?,
                             is_loan: loan,
                             payment_address: address,
+                            category: None,
+                            ipfs_hash: None,
                         })
                     } else {
                         None
@@ -553,29 +1411,146 @@ impl Cli {
                             announced_at: announced,
                             published_at: published,
                             resolved_at: None,
+                            category: category.map(|c| c.parse::<ProposalCategory>()).transpose()?,
                         }
                     })
                 },
-                ProposalCommands::Pay { proposals, tx, date } => {
+                ProposalCommands::Pay { proposals, tx, date, skip_verification } => {
                     let payment_date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")?;
                     let proposal_names = proposals.split(',').map(String::from).collect();
                     Ok(Command::LogPayment {
                         payment_tx: tx,
                         payment_date,
                         proposal_names,
+                        skip_verification,
+                    })
+                },
+                ProposalCommands::LoanTerms { name, interest_rate, due, schedule, repaid } => {
+                    Ok(Command::SetProposalLoanTerms {
+                        proposal_name: name,
+                        interest_rate,
+                        due_date: NaiveDate::parse_from_str(&due, "%Y-%m-%d")?,
+                        repayment_schedule: schedule,
+                        repaid_amounts: repaid.map(|r| parse_amounts(&r)).transpose()?,
+                    })
+                },
+                ProposalCommands::Allocations { name, teams } => {
+                    let mut allocations = HashMap::new();
+                    for allocation in teams {
+                        let (team, amounts) = allocation.split_once('=')
+                            .ok_or("Invalid allocation format. Expected \"Team Name=token:amount,...\"")?;
+                        allocations.insert(team.trim().to_string(), parse_amounts(amounts)?);
+                    }
+                    Ok(Command::SetProposalTeamAllocations { proposal_name: name, allocations })
+                },
+                ProposalCommands::PayAllocation { name, teams, tx, date, skip_verification } => {
+                    Ok(Command::LogAllocationPayment {
+                        payment_tx: tx,
+                        payment_date: NaiveDate::parse_from_str(&date, "%Y-%m-%d")?,
+                        proposal_name: name,
+                        team_names: teams.split(',').map(|t| t.trim().to_string()).collect(),
+                        skip_verification,
+                    })
+                },
+                ProposalCommands::Repay { name, date } => {
+                    Ok(Command::RecordLoanRepayment {
+                        proposal_name: name,
+                        repaid_at: date.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?,
+                    })
+                },
+                ProposalCommands::List { status, epoch, team, unpaid_only, page } => {
+                    let status = if status.eq_ignore_ascii_case("all") {
+                        None
+                    } else {
+                        Some(status.parse::<ProposalListFilter>()?)
+                    };
+                    Ok(Command::ListProposals { epoch_name: epoch, status, team, unpaid_only, page: Some(page) })
+                },
+                ProposalCommands::CheckDuplicate { title } => {
+                    Ok(Command::CheckDuplicateProposal { title })
+                },
+                ProposalCommands::Reviewers { name, reviewers } => {
+                    Ok(Command::SetProposalReviewers {
+                        proposal_name: name,
+                        reviewers: reviewers
+                            .map(|r| r.split(',').map(|s| s.trim().to_string()).collect())
+                            .unwrap_or_default(),
+                    })
+                },
+                ProposalCommands::SignOff { name, reviewer, date } => {
+                    Ok(Command::RecordReviewerSignOff {
+                        proposal_name: name,
+                        reviewer,
+                        signed_off_at: date.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?,
+                    })
+                },
+                ProposalCommands::TemplateCreate { name, title, team, amounts, loan, tags, every, max } => {
+                    Ok(Command::CreateProposalTemplate {
+                        name,
+                        title_pattern: title,
+                        team,
+                        amounts: amounts.map(|a| parse_amounts(&a)).transpose()?.unwrap_or_default(),
+                        is_loan: loan,
+                        tags: tags.map(|t| t.split(',').map(|tag| tag.trim().to_string()).collect()).unwrap_or_default(),
+                        recurrence_interval_epochs: every,
+                        max_occurrences: max,
+                    })
+                },
+                ProposalCommands::Templates => {
+                    Ok(Command::ListProposalTemplates)
+                },
+                ProposalCommands::FromTemplate { template, epoch, title, url, team, amounts, start, end, announced_at, published_at } => {
+                    Ok(Command::CreateProposalFromTemplate {
+                        template_name: template,
+                        epoch_name: epoch,
+                        title,
+                        url,
+                        team,
+                        amounts: amounts.map(|a| parse_amounts(&a)).transpose()?,
+                        start_date: start,
+                        end_date: end,
+                        announced_at,
+                        published_at,
                     })
                 }
             },
 
             Commands::Vote { command } => match command {
-                VoteCommands::Process { name, counted, uncounted, opened, closed } => {
+                VoteCommands::Process { name, counted, uncounted, delegations, opened, closed, deadline } => {
+                    let mut delegates = HashMap::new();
+                    for delegation in delegations {
+                        let (delegator, delegate) = delegation.split_once('=')
+                            .ok_or("Invalid delegation format. Expected \"Delegator=Delegate\"")?;
+                        delegates.insert(delegator.trim().to_string(), delegate.trim().to_string());
+                    }
                     Ok(Command::CreateAndProcessVote {
                         proposal_name: name,
                         counted_votes: parse_votes(&counted)?,
                         uncounted_votes: parse_votes(&uncounted)?,
+                        delegations: delegates,
                         vote_opened: opened.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?,
                         vote_closed: closed.map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d")).transpose()?,
+                        deadline: deadline
+                            .map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d"))
+                            .transpose()?
+                            .map(|d| Utc.from_utc_datetime(&d.and_hms_opt(23, 59, 59).unwrap())),
                     })
+                },
+                VoteCommands::CloseExpired => Ok(Command::CloseExpiredVotes),
+                VoteCommands::RequestChange { vote_id, team, choice, reason } => {
+                    Ok(Command::RequestVoteChange { vote_id, team_name: team, new_choice: choice, reason })
+                },
+                VoteCommands::ApproveChange { vote_id, team } => {
+                    Ok(Command::ApproveVoteChange { vote_id, team_name: team })
+                },
+                VoteCommands::ChangeRequests { vote_id } => {
+                    Ok(Command::ListVoteChangeRequests { vote_id })
+                },
+                VoteCommands::Reopen { name } => {
+                    Ok(Command::ReopenVote { proposal_name: name })
+                },
+                VoteCommands::PingAbsent { name } => {
+                    Ok(Command::PingAbsentVoters { proposal_name: name })
                 }
             },
 
@@ -586,6 +1561,35 @@ impl Cli {
                         block_offset,
                         excluded_teams: excluded.map(|e| e.split(',').map(String::from).collect()),
                     })
+                },
+                RaffleCommands::Rerun { name, block_offset } => {
+                    Ok(Command::RerunRaffle { proposal_name: name, block_offset })
+                },
+                RaffleCommands::Excluded { name } => {
+                    Ok(Command::ListExcludedTeams { proposal_name: name })
+                },
+                RaffleCommands::Deciders { name } => {
+                    Ok(Command::ShowDecidingTeams { proposal_name: name })
+                },
+                RaffleCommands::Show { name } => {
+                    Ok(Command::ShowRaffle { proposal_name: name })
+                },
+                RaffleCommands::Eligibility { excluded } => {
+                    Ok(Command::PrintRaffleEligibility {
+                        excluded_teams: excluded.map(|e| e.split(',').map(String::from).collect()),
+                    })
+                },
+                RaffleCommands::MaxEarnerSeats { seats } => {
+                    Ok(Command::SetMaxEarnerSeatsGlobally { max_earner_seats: seats })
+                },
+                RaffleCommands::Reassign { proposal, team, outcome, reason } => {
+                    Ok(Command::ReassignRaffleSeats { proposal_name: proposal, team_name: team, new_outcome: outcome, reason })
+                },
+                RaffleCommands::Verify { name, on_chain } => {
+                    Ok(Command::VerifyRaffle { proposal_name: name, on_chain })
+                },
+                RaffleCommands::Audit { name } => {
+                    Ok(Command::GenerateRaffleAuditReport { proposal_name: name })
                 }
             },
 
@@ -593,8 +1597,8 @@ impl Cli {
                 ReportCommands::Team => {
                     Ok(Command::PrintTeamReport)
                 },
-                ReportCommands::EpochState => {
-                    Ok(Command::PrintEpochState)
+                ReportCommands::EpochState { format } => {
+                    Ok(Command::PrintEpochState { format })
                 },
                 ReportCommands::TeamParticipation { team_name, epoch_name } => {
                     Ok(Command::PrintTeamVoteParticipation { team_name, epoch_name })
@@ -602,24 +1606,107 @@ impl Cli {
                 ReportCommands::Points { epoch_name } => {
                     Ok(Command::PrintPointReport { epoch_name })
                 },
-                ReportCommands::EndOfEpoch { epoch_name } => {
-                    Ok(Command::GenerateEndOfEpochReport { epoch_name })
+                ReportCommands::Categories { epoch_name } => {
+                    Ok(Command::PrintCategoryBreakdown { epoch_name })
+                },
+                ReportCommands::EndOfEpoch { epoch_name, format } => {
+                    Ok(Command::GenerateEndOfEpochReport { epoch_name, format })
+                },
+                ReportCommands::PreviewEndOfEpoch { epoch_name } => {
+                    Ok(Command::PreviewEndOfEpochReport { epoch_name })
                 },
                 ReportCommands::UnpaidRequests { output_path, epoch_name } => {
                     Ok(Command::GenerateUnpaidRequestsReport { output_path, epoch_name })
                 },
-                ReportCommands::EpochPayments { epoch_name, output } => {
+                ReportCommands::EpochPayments { epoch_name, output, format } => {
                     Ok(Command::GenerateEpochPaymentsReport { 
                         epoch_name, 
-                        output_path: output 
+                        output_path: output,
+                        format,
                     })
                 },
-                ReportCommands::ForProposal { proposal_name } => {
-                    Ok(Command::GenerateReportForProposal { proposal_name })
+                ReportCommands::MultisigPayload { epoch_name, output } => {
+                    Ok(Command::GenerateMultisigPayload { epoch_name, output_path: output })
+                },
+                ReportCommands::ForProposal { proposal_name, epoch_name, sections } => {
+                    Ok(Command::GenerateReportForProposal { proposal_name, epoch_name, sections })
                 },
                 ReportCommands::ClosedProposals { epoch_name } => {
                     Ok(Command::GenerateReportsForClosedProposals { epoch_name })
                 },
+                ReportCommands::AbsenceStreaks { min_streak } => {
+                    Ok(Command::PrintAbsenceStreaks { min_streak })
+                },
+                ReportCommands::OverduePayments { days } => {
+                    Ok(Command::PrintOverduePayments { days })
+                },
+                ReportCommands::PendingPayments { epoch, min_days } => {
+                    Ok(Command::ListPendingPayments { epoch_name: epoch, min_days_pending: min_days })
+                },
+                ReportCommands::Infographic { epoch_name } => {
+                    Ok(Command::ExportEpochInfographicData { epoch_name })
+                },
+                ReportCommands::Loans => {
+                    Ok(Command::PrintLoansOutstanding)
+                },
+                ReportCommands::OutstandingLoans { epoch } => {
+                    Ok(Command::PrintOutstandingLoans { epoch_name: epoch })
+                },
+                ReportCommands::Treasury { epoch_name, format } => {
+                    Ok(Command::PrintTreasuryReport { epoch_name, format })
+                },
+                ReportCommands::LoanTracking { output } => {
+                    Ok(Command::GenerateLoanReport { output_path: output })
+                },
+                ReportCommands::StateSize => {
+                    Ok(Command::PrintStateSize)
+                },
+                ReportCommands::VerifyPoints => {
+                    Ok(Command::VerifyPoints)
+                },
+                ReportCommands::Geography => {
+                    Ok(Command::PrintGeographicDistribution)
+                },
+                ReportCommands::StateDiff { path_a, path_b } => {
+                    Ok(Command::DiffStateFiles { path_a, path_b })
+                },
+                ReportCommands::Export { kind, epoch_name, format, output } => {
+                    Ok(Command::Export { kind, epoch_name, format, output_path: output })
+                },
+                ReportCommands::BudgetFlow { epoch_name, output } => {
+                    Ok(Command::ExportBudgetFlowData { epoch_name, output_path: output })
+                },
+                ReportCommands::Headroom { epoch_name, caps } => {
+                    let mut caps: Vec<(String, f64)> = caps.map(|c| parse_amounts(&c)).transpose()?
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect();
+                    caps.sort_by(|a, b| a.0.cmp(&b.0));
+                    Ok(Command::PrintBudgetHeadroom { epoch_name, caps })
+                },
+                ReportCommands::AllEpochs { only_closed, output } => {
+                    Ok(Command::GenerateAllEpochsReport { only_closed, output_path: output })
+                },
+                ReportCommands::VoteTrends { only_closed, output } => {
+                    Ok(Command::GenerateVoteTrendsReport { only_closed, output_path: output })
+                },
+                ReportCommands::Burndown { epoch_name, caps, output } => {
+                    let mut caps: Vec<(String, f64)> = caps.map(|c| parse_amounts(&c)).transpose()?
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect();
+                    caps.sort_by(|a, b| a.0.cmp(&b.0));
+                    Ok(Command::ShowBudgetBurndown { epoch_name, caps, output_path: output })
+                },
+                ReportCommands::ApprovalRates { recent_epochs } => {
+                    Ok(Command::PrintApprovalRatesByStatus { recent_epochs })
+                },
+                ReportCommands::WaitingTimes { epoch_name } => {
+                    Ok(Command::PrintProposalWaitingPercentiles { epoch_name })
+                },
+                ReportCommands::TeamPerformance { team_name, output } => {
+                    Ok(Command::GenerateTeamPerformanceReport { team_name, output_path: output })
+                },
             },
 
             Commands::Import { command } => match command {
@@ -679,12 +1766,53 @@ impl Cli {
             Commands::RunScript { script_file_path } => {
                 Ok(Command::RunScript { script_file_path })
             },
+
+            Commands::ImportProposals { file } => {
+                Ok(Command::ImportProposals { file_path: file, dry_run })
+            },
+            Commands::RestoreBackup { timestamp, confirm } => {
+                Ok(Command::RestoreBackup { timestamp, confirm })
+            },
         }
     }
 }
 
 
+/// Runs a command from the command line. With `dry_run` the command runs in full
+/// against a snapshot of the state, nothing is saved, and the changes it would have
+/// made are listed afterwards. Each command, including every command of a script, is
+/// echoed before it runs. A command whose preconditions fail is rejected before the
+/// dry run starts.
 pub async fn execute_command<W: Write + Send + 'static>(
+    budget_system: &mut BudgetSystem,
+    command: Command,
+    config: &AppConfig,
+    output: &mut W,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    if !dry_run {
+        return run_command(budget_system, command, config, output).await;
+    }
+
+    let plan = budget_system.validate_command(&command)?;
+    writeln!(output, "{}", plan)?;
+    budget_system.start_dry_run();
+    let result = run_command(budget_system, command, config, output).await;
+    let diff = budget_system.finish_dry_run();
+
+    let changes = diff.change_lines();
+    if changes.is_empty() {
+        writeln!(output, "\n[dry-run] No changes; nothing was saved")?;
+    } else {
+        writeln!(output, "\n[dry-run] Nothing was saved. The command would:")?;
+        for change in changes {
+            writeln!(output, "  - {}", change)?;
+        }
+    }
+    result
+}
+
+async fn run_command<W: Write + Send + 'static>(
     budget_system: &mut BudgetSystem,
     command: Command,
     config: &AppConfig,
@@ -695,7 +1823,32 @@ pub async fn execute_command<W: Write + Send + 'static>(
             let script_path = script_file_path.unwrap_or_else(|| config.script_file.clone());
             let script_commands = read_script_commands(&script_path)?;
             for cmd in script_commands {
+                let dry_run = budget_system.is_dry_run();
+                if dry_run {
+                    let plan = budget_system.validate_command(&cmd)?;
+                    writeln!(output, "{}", plan)?;
+                }
                 budget_system.execute_command_with_streaming(cmd, output).await?;
+                if dry_run {
+                    writeln!(output)?;
+                }
+            }
+            Ok(())
+        },
+        Command::PingAbsentVoters { proposal_name } => {
+            match budget_system.absent_voter_ping_message(&proposal_name)? {
+                Some(message) if budget_system.is_dry_run() => {
+                    writeln!(output, "Would post to the configured chat:\n{}", message)?;
+                },
+                Some(message) => {
+                    crate::services::telegram::post_to_chat(
+                        &config.telegram.token,
+                        &config.telegram.chat_id,
+                        crate::escape_markdown(&message),
+                    ).await?;
+                    writeln!(output, "Posted to the configured chat:\n{}", message)?;
+                },
+                None => writeln!(output, "No teams with a Telegram contact are waiting to vote on '{}'", proposal_name)?,
             }
             Ok(())
         },
@@ -706,8 +1859,14 @@ pub async fn execute_command<W: Write + Send + 'static>(
 }
 
 pub fn parse_cli_args(args: &[String]) -> Result<Command, Box<dyn Error>> {
+    parse_cli(args).map(|(command, _)| command)
+}
+
+/// Parses the command line into a command and whether `--dry-run` was given.
+pub fn parse_cli(args: &[String]) -> Result<(Command, bool), Box<dyn Error>> {
     let cli = Cli::parse_from(args);
-    cli.into_command()
+    let dry_run = cli.dry_run;
+    Ok((cli.into_command()?, dry_run))
 }
 
 fn parse_amounts(amounts_str: &str) -> Result<HashMap<String, f64>, Box<dyn Error>> {
@@ -858,6 +2017,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_team_region_command() {
+        let cmd = parse_cli_args(&args(&["team", "region", "Engineering", "EMEA"])).unwrap();
+        match cmd {
+            Command::UpdateTeamRegion { team_name, region } => {
+                assert_eq!(team_name, "Engineering");
+                assert_eq!(region, Some("EMEA".to_string()));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["team", "region", "Engineering"])).unwrap();
+        assert!(matches!(cmd, Command::UpdateTeamRegion { region: None, .. }));
+    }
+
+    #[test]
+    fn test_team_proposals_command() {
+        let cmd = parse_cli_args(&args(&["team", "proposals", "Engineering", "--epoch-name", "Epoch 1"])).unwrap();
+        match cmd {
+            Command::ListTeamProposals { team_name, epoch_name } => {
+                assert_eq!(team_name, "Engineering");
+                assert_eq!(epoch_name, Some("Epoch 1".to_string()));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["team", "proposals", "Engineering"])).unwrap();
+        assert!(matches!(cmd, Command::ListTeamProposals { epoch_name: None, .. }));
+    }
+
+    #[test]
+    fn test_team_list_command() {
+        let cmd = parse_cli_args(&args(&["team", "list"])).unwrap();
+        assert!(matches!(cmd, Command::ListTeams { status_filter: None }));
+
+        let cmd = parse_cli_args(&args(&["team", "list", "--status", "earner"])).unwrap();
+        match cmd {
+            Command::ListTeams { status_filter } => assert_eq!(status_filter, Some("earner".to_string())),
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_team_contacts_command() {
+        let cmd = parse_cli_args(&args(&["team", "contacts", "Engineering", "--handles", "@alice,bob"])).unwrap();
+        match cmd {
+            Command::SetTeamContacts { team_name, contacts } => {
+                assert_eq!(team_name, "Engineering");
+                assert_eq!(contacts, vec!["@alice".to_string(), "bob".to_string()]);
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["team", "contacts", "Engineering"])).unwrap();
+        assert!(matches!(cmd, Command::SetTeamContacts { ref contacts, .. } if contacts.is_empty()));
+    }
+
     #[test]
     fn test_team_add_invalid_revenue() {
         let args = args(&[
@@ -965,6 +2181,134 @@ mod tests {
             },
             _ => panic!("Wrong command type"),
         }
+
+        let cmd = parse_cli_args(&[
+            "robokitty".to_string(), "epoch".to_string(), "close".to_string(),
+            "Q1-2024".to_string(), "--force".to_string()
+        ]).unwrap();
+        match cmd {
+            Command::ForceCloseEpoch { epoch_name } => assert_eq!(epoch_name, Some("Q1-2024".to_string())),
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_epoch_set_voting_windows_command() {
+        let args = args(&[
+            "epoch",
+            "set-voting-windows",
+            "--min-days-before-vote", "3",
+            "--max-days-to-vote", "14"
+        ]);
+
+        let cmd = parse_cli_args(&args).unwrap();
+
+        match cmd {
+            Command::SetEpochVotingWindows { epoch_name, min_days_before_vote, max_days_to_vote } => {
+                assert_eq!(epoch_name, None);
+                assert_eq!(min_days_before_vote, Some(3));
+                assert_eq!(max_days_to_vote, Some(14));
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_epoch_recompute_command() {
+        let cmd = parse_cli_args(&args(&["epoch", "recompute", "Q1-2024"])).unwrap();
+        match cmd {
+            Command::RecomputeEpochFromVotes { epoch_name, confirm } => {
+                assert_eq!(epoch_name, "Q1-2024");
+                assert!(!confirm);
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["epoch", "recompute", "Q1-2024", "--confirm"])).unwrap();
+        assert!(matches!(cmd, Command::RecomputeEpochFromVotes { confirm: true, .. }));
+    }
+
+    #[test]
+    fn test_epoch_force_status_command() {
+        let cmd = parse_cli_args(&args(&["epoch", "force-status", "Q1-2024", "closed"])).unwrap();
+        match cmd {
+            Command::SetEpochStatus { epoch_name, status, confirm, force } => {
+                assert_eq!(epoch_name, "Q1-2024");
+                assert_eq!(status, EpochStatus::Closed);
+                assert!(!confirm);
+                assert!(!force);
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["epoch", "force-status", "Q1-2024", "Active", "--confirm", "--force"])).unwrap();
+        assert!(matches!(cmd, Command::SetEpochStatus { status: EpochStatus::Active, confirm: true, force: true, .. }));
+    }
+
+    #[test]
+    fn test_team_merge_command() {
+        let cmd = parse_cli_args(&args(&["team", "merge", "Old Team", "New Team"])).unwrap();
+        match cmd {
+            Command::MergeTeams { source_name, target_name } => {
+                assert_eq!(source_name, "Old Team");
+                assert_eq!(target_name, "New Team");
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_team_alias_command() {
+        let cmd = parse_cli_args(&args(&["team", "alias", "Meta-Governance", "MetaGov"])).unwrap();
+        assert!(matches!(cmd, Command::AddTeamAlias { team_name, alias } if team_name == "Meta-Governance" && alias == "MetaGov"));
+    }
+
+    #[test]
+    fn test_team_archive_commands() {
+        let cmd = parse_cli_args(&args(&["team", "archive", "Old Team"])).unwrap();
+        assert!(matches!(cmd, Command::ArchiveTeam { team_name } if team_name == "Old Team"));
+        let cmd = parse_cli_args(&args(&["team", "unarchive", "Old Team"])).unwrap();
+        assert!(matches!(cmd, Command::UnarchiveTeam { team_name } if team_name == "Old Team"));
+    }
+
+    #[test]
+    fn test_epoch_clone_command() {
+        let cmd = parse_cli_args(&args(&["epoch", "clone", "Q1-2024", "Q2-2024", "2024-04-01T00:00:00Z", "2024-06-30T23:59:59Z"])).unwrap();
+        match cmd {
+            Command::CloneEpoch { source_epoch_name, new_name, start_date, end_date } => {
+                assert_eq!(source_epoch_name, "Q1-2024");
+                assert_eq!(new_name, "Q2-2024");
+                assert_eq!(start_date.to_rfc3339(), "2024-04-01T00:00:00+00:00");
+                assert_eq!(end_date.to_rfc3339(), "2024-06-30T23:59:59+00:00");
+            },
+            _ => panic!("Wrong command type"),
+        }
+        assert!(parse_cli_args(&args(&["epoch", "clone", "Q1-2024", "Q2-2024", "2024-04-01", "2024-06-30"])).is_err());
+    }
+
+    #[test]
+    fn test_epoch_extend_command() {
+        let cmd = parse_cli_args(&args(&["epoch", "extend", "2024-04-05T00:00:00Z", "--epoch-name", "Q1-2024"])).unwrap();
+        match cmd {
+            Command::ExtendEpoch { epoch_name, new_end_date } => {
+                assert_eq!(epoch_name, Some("Q1-2024".to_string()));
+                assert_eq!(new_end_date.to_rfc3339(), "2024-04-05T00:00:00+00:00");
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["epoch", "extend", "2024-04-05T00:00:00Z"])).unwrap();
+        assert!(matches!(cmd, Command::ExtendEpoch { epoch_name: None, .. }));
+        assert!(parse_cli_args(&args(&["epoch", "extend", "next week"])).is_err());
+    }
+
+    #[test]
+    fn test_epoch_list_command() {
+        let cmd = parse_cli_args(&args(&["epoch", "list"])).unwrap();
+        assert!(matches!(cmd, Command::ListEpochs { include_closed: false }));
+
+        let cmd = parse_cli_args(&args(&["epoch", "list", "--include-closed"])).unwrap();
+        assert!(matches!(cmd, Command::ListEpochs { include_closed: true }));
     }
 
     #[test]
@@ -1155,6 +2499,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_proposal_category_flags() {
+        let cmd = parse_cli_args(&args(&[
+            "proposal", "add", "--title", "Audit", "--amounts", "ETH:10", "--category", "research"
+        ])).unwrap();
+        match cmd {
+            Command::AddProposal { budget_request_details, .. } => {
+                assert_eq!(budget_request_details.unwrap().category, Some(ProposalCategory::Research));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let result = parse_cli_args(&args(&["proposal", "add", "--title", "Audit", "--category", "research"]));
+        assert!(result.unwrap_err().to_string().contains("needs a budget request"));
+
+        let cmd = parse_cli_args(&args(&["proposal", "update", "Audit", "--category", "Security"])).unwrap();
+        match cmd {
+            Command::UpdateProposal { updates, .. } => {
+                assert_eq!(updates.category, Some(ProposalCategory::Other("Security".to_string())));
+                assert!(updates.budget_request_details.is_none());
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["report", "categories", "--epoch-name", "Q1"])).unwrap();
+        assert!(matches!(cmd, Command::PrintCategoryBreakdown { epoch_name: Some(name) } if name == "Q1"));
+    }
+
+    #[test]
+    fn test_proposal_ipfs_hash_flags() {
+        let cid = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+        let cmd = parse_cli_args(&args(&["proposal", "pin-document", "Audit", cid])).unwrap();
+        assert!(matches!(cmd, Command::PinProposalDocument { proposal_name, ipfs_hash } if proposal_name == "Audit" && ipfs_hash == cid));
+
+        let cmd = parse_cli_args(&args(&["proposal", "add", "--title", "Audit", "--amounts", "ETH:10", "--ipfs-hash", cid])).unwrap();
+        match cmd {
+            Command::AddProposal { budget_request_details, .. } => {
+                assert_eq!(budget_request_details.unwrap().ipfs_hash.as_deref(), Some(cid));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let result = parse_cli_args(&args(&["proposal", "add", "--title", "Audit", "--ipfs-hash", cid]));
+        assert!(result.unwrap_err().to_string().contains("pin-document"));
+    }
+
     #[test]
     fn test_proposal_update_command() {
         let args = args(&[
@@ -1200,12 +2590,156 @@ mod tests {
         let cmd = parse_cli_args(&args).unwrap();
         
         match cmd {
-            Command::CloseProposal { 
-                proposal_name, 
-                resolution 
-            } => {
-                assert_eq!(proposal_name, "test-proposal");
-                assert_eq!(resolution, "Approved");
+            Command::CloseProposal { 
+                proposal_name, 
+                resolution 
+            } => {
+                assert_eq!(proposal_name, "test-proposal");
+                assert_eq!(resolution, "Approved");
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_proposal_bulk_close_command() {
+        let cmd = parse_cli_args(&args(&["proposal", "bulk-close", "Q1-2024", "Rejected"])).unwrap();
+        match cmd {
+            Command::BulkCloseProposals { epoch_name, resolution, reason, confirm } => {
+                assert_eq!(epoch_name, "Q1-2024");
+                assert_eq!(resolution, "Rejected");
+                assert!(reason.is_none());
+                assert!(!confirm);
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&[
+            "proposal", "bulk-close", "Q1-2024", "Invalid", "--reason", "Epoch ended", "--confirm"
+        ])).unwrap();
+        match cmd {
+            Command::BulkCloseProposals { reason, confirm, .. } => {
+                assert_eq!(reason.as_deref(), Some("Epoch ended"));
+                assert!(confirm);
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_proposal_loan_terms_command() {
+        let cmd = parse_cli_args(&args(&[
+            "proposal", "loan-terms", "test-proposal",
+            "--interest-rate", "0.05", "--due", "2024-12-31", "--schedule", "Monthly", "--repaid", "ETH:10"
+        ])).unwrap();
+        match cmd {
+            Command::SetProposalLoanTerms { proposal_name, interest_rate, due_date, repayment_schedule, repaid_amounts } => {
+                assert_eq!(proposal_name, "test-proposal");
+                assert_eq!(interest_rate, 0.05);
+                assert_eq!(due_date, NaiveDate::from_ymd_opt(2024, 12, 31).unwrap());
+                assert_eq!(repayment_schedule, "Monthly");
+                assert_eq!(repaid_amounts.unwrap().get("ETH"), Some(&10.0));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["proposal", "loan-terms", "test-proposal", "--interest-rate", "0", "--due", "2024-12-31"])).unwrap();
+        assert!(matches!(cmd, Command::SetProposalLoanTerms { ref repayment_schedule, repaid_amounts: None, .. } if repayment_schedule == "AtMaturity"));
+
+        assert!(parse_cli_args(&args(&["proposal", "loan-terms", "test-proposal", "--interest-rate", "0", "--due", "not-a-date"])).is_err());
+        assert!(matches!(parse_cli_args(&args(&["report", "loans"])).unwrap(), Command::PrintLoansOutstanding));
+        assert!(matches!(
+            parse_cli_args(&args(&["report", "outstanding-loans", "--epoch", "Q1"])).unwrap(),
+            Command::PrintOutstandingLoans { epoch_name: Some(ref name) } if name == "Q1"
+        ));
+        assert!(matches!(
+            parse_cli_args(&args(&["report", "loan-tracking", "--output", "loans.md"])).unwrap(),
+            Command::GenerateLoanReport { output_path: Some(ref path) } if path == "loans.md"
+        ));
+        match parse_cli_args(&args(&["proposal", "allocations", "Shared", "--team", "Team A=ETH:10,USD:5", "--team", "Team B=ETH:2.5"])).unwrap() {
+            Command::SetProposalTeamAllocations { proposal_name, allocations } => {
+                assert_eq!(proposal_name, "Shared");
+                assert_eq!(allocations["Team A"], HashMap::from([("ETH".to_string(), 10.0), ("USD".to_string(), 5.0)]));
+                assert_eq!(allocations["Team B"], HashMap::from([("ETH".to_string(), 2.5)]));
+            },
+            _ => panic!("Wrong command type"),
+        }
+        match parse_cli_args(&args(&[
+            "proposal", "pay-allocation", "Shared", "--teams", "Team A, Team B",
+            "--tx", "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e", "--date", "2024-05-01",
+        ])).unwrap() {
+            Command::LogAllocationPayment { proposal_name, team_names, payment_date, skip_verification, .. } => {
+                assert_eq!(proposal_name, "Shared");
+                assert_eq!(team_names, vec!["Team A", "Team B"]);
+                assert_eq!(payment_date, NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+                assert!(!skip_verification);
+            },
+            _ => panic!("Wrong command type"),
+        }
+        match parse_cli_args(&args(&["proposal", "repay", "Loan", "--date", "2024-05-01"])).unwrap() {
+            Command::RecordLoanRepayment { proposal_name, repaid_at } => {
+                assert_eq!(proposal_name, "Loan");
+                assert_eq!(repaid_at, NaiveDate::from_ymd_opt(2024, 5, 1));
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_proposal_reviewer_commands() {
+        let cmd = parse_cli_args(&args(&["proposal", "reviewers", "test-proposal", "--reviewers", "Alice, Bob"])).unwrap();
+        match cmd {
+            Command::SetProposalReviewers { proposal_name, reviewers } => {
+                assert_eq!(proposal_name, "test-proposal");
+                assert_eq!(reviewers, vec!["Alice".to_string(), "Bob".to_string()]);
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["proposal", "sign-off", "test-proposal", "Alice", "--date", "2024-01-15"])).unwrap();
+        match cmd {
+            Command::RecordReviewerSignOff { proposal_name, reviewer, signed_off_at } => {
+                assert_eq!(proposal_name, "test-proposal");
+                assert_eq!(reviewer, "Alice");
+                assert_eq!(signed_off_at, Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["proposal", "reviewers", "test-proposal"])).unwrap();
+        assert!(matches!(cmd, Command::SetProposalReviewers { ref reviewers, .. } if reviewers.is_empty()));
+    }
+
+    #[test]
+    fn test_proposal_template_commands() {
+        let cmd = parse_cli_args(&args(&[
+            "proposal", "template-create", "ops-grant", "--title", "Ops {epoch}",
+            "--amounts", "ETH:10", "--tags", "ops, infra", "--every", "1", "--max", "4"
+        ])).unwrap();
+        match cmd {
+            Command::CreateProposalTemplate { name, title_pattern, amounts, is_loan, tags, recurrence_interval_epochs, max_occurrences, .. } => {
+                assert_eq!(name, "ops-grant");
+                assert_eq!(title_pattern, "Ops {epoch}");
+                assert_eq!(amounts.get("ETH"), Some(&10.0));
+                assert!(!is_loan);
+                assert_eq!(tags, vec!["ops".to_string(), "infra".to_string()]);
+                assert_eq!(recurrence_interval_epochs, Some(1));
+                assert_eq!(max_occurrences, Some(4));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        assert!(matches!(parse_cli_args(&args(&["proposal", "templates"])).unwrap(), Command::ListProposalTemplates));
+
+        let cmd = parse_cli_args(&args(&[
+            "proposal", "from-template", "ops-grant", "--epoch", "Q1", "--start", "2024-01-01"
+        ])).unwrap();
+        match cmd {
+            Command::CreateProposalFromTemplate { template_name, epoch_name, start_date, title, .. } => {
+                assert_eq!(template_name, "ops-grant");
+                assert_eq!(epoch_name.as_deref(), Some("Q1"));
+                assert_eq!(start_date, Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()));
+                assert!(title.is_none());
             },
             _ => panic!("Wrong command type"),
         }
@@ -1226,6 +2760,18 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_proposal_reopen_command() {
+        let cmd = parse_cli_args(&args(&["proposal", "reopen", "test-proposal"])).unwrap();
+        match cmd {
+            Command::SetProposalStatus { proposal_name, status } => {
+                assert_eq!(proposal_name, "test-proposal");
+                assert_eq!(status, ProposalStatus::Reopened);
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
     #[test]
     fn test_proposal_close_invalid_resolution() {
         let args = args(&[
@@ -1314,8 +2860,10 @@ mod tests {
             "test-proposal",
             "--counted", "Team1:Yes,Team2:No",
             "--uncounted", "Team3:Yes",
+            "--delegate", "Team2=Team3",
             "--opened", "2024-01-01",
-            "--closed", "2024-01-07"
+            "--closed", "2024-01-07",
+            "--deadline", "2024-01-08"
         ]);
 
         let cmd = parse_cli_args(&args).unwrap();
@@ -1325,8 +2873,10 @@ mod tests {
                 proposal_name,
                 counted_votes,
                 uncounted_votes,
+                delegations,
                 vote_opened,
                 vote_closed,
+                deadline,
             } => {
                 assert_eq!(proposal_name, "test-proposal");
                 
@@ -1336,9 +2886,13 @@ mod tests {
                 
                 assert_eq!(uncounted_votes.len(), 1);
                 assert_eq!(uncounted_votes.get("Team3").unwrap(), &VoteChoice::Yes);
+
+                assert_eq!(delegations.len(), 1);
+                assert_eq!(delegations.get("Team2").unwrap(), "Team3");
                 
                 assert_eq!(vote_opened.unwrap(), NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
                 assert_eq!(vote_closed.unwrap(), NaiveDate::from_ymd_opt(2024, 1, 7).unwrap());
+                assert_eq!(deadline.unwrap().to_rfc3339(), "2024-01-08T23:59:59+00:00");
             },
             _ => panic!("Wrong command type"),
         }
@@ -1361,19 +2915,29 @@ mod tests {
                 proposal_name,
                 counted_votes,
                 uncounted_votes,
+                delegations,
                 vote_opened,
                 vote_closed,
+                deadline,
             } => {
                 assert_eq!(proposal_name, "test-proposal");
                 assert_eq!(counted_votes.len(), 1);
                 assert_eq!(uncounted_votes.len(), 1);
+                assert!(delegations.is_empty());
                 assert!(vote_opened.is_none());
                 assert!(vote_closed.is_none());
+                assert!(deadline.is_none());
             },
             _ => panic!("Wrong command type"),
         }
     }
 
+    #[test]
+    fn test_vote_close_expired_command() {
+        let cmd = parse_cli_args(&args(&["vote", "close-expired"])).unwrap();
+        assert!(matches!(cmd, Command::CloseExpiredVotes));
+    }
+
     #[test]
     fn test_vote_process_invalid_format() {
         let args = args(&[
@@ -1423,6 +2987,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_vote_change_commands() {
+        let vote_id = "00000000-0000-0000-0000-000000000001";
+        let cmd = parse_cli_args(&args(&["vote", "request-change", vote_id, "Team1", "no", "--reason", "Misread"])).unwrap();
+        match cmd {
+            Command::RequestVoteChange { vote_id: id, team_name, new_choice, reason } => {
+                assert_eq!(id.to_string(), vote_id);
+                assert_eq!(team_name, "Team1");
+                assert_eq!(new_choice, VoteChoice::No);
+                assert_eq!(reason, "Misread");
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["vote", "approve-change", vote_id, "Team1"])).unwrap();
+        assert!(matches!(cmd, Command::ApproveVoteChange { ref team_name, .. } if team_name == "Team1"));
+
+        let cmd = parse_cli_args(&args(&["vote", "change-requests", vote_id])).unwrap();
+        assert!(matches!(cmd, Command::ListVoteChangeRequests { .. }));
+    }
+
+    #[test]
+    fn test_vote_reopen_command() {
+        let cmd = parse_cli_args(&args(&["vote", "reopen", "Test Proposal"])).unwrap();
+        assert!(matches!(cmd, Command::ReopenVote { ref proposal_name } if proposal_name == "Test Proposal"));
+    }
+
+    #[test]
+    fn test_vote_ping_absent_command() {
+        let cmd = parse_cli_args(&args(&["vote", "ping-absent", "Test Proposal"])).unwrap();
+        assert!(matches!(cmd, Command::PingAbsentVoters { ref proposal_name } if proposal_name == "Test Proposal"));
+    }
+
     #[test]
     fn test_raffle_create_command_full() {
         let args = args(&[
@@ -1494,6 +3091,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_raffle_excluded_command() {
+        let args = args(&["raffle", "excluded", "test-proposal"]);
+
+        let cmd = parse_cli_args(&args).unwrap();
+        match cmd {
+            Command::ListExcludedTeams { proposal_name } => {
+                assert_eq!(proposal_name, "test-proposal");
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_raffle_deciders_command() {
+        let cmd = parse_cli_args(&args(&["raffle", "deciders", "test-proposal"])).unwrap();
+        match cmd {
+            Command::ShowDecidingTeams { proposal_name } => {
+                assert_eq!(proposal_name, "test-proposal");
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_raffle_max_earner_seats_command() {
+        let cmd = parse_cli_args(&args(&["raffle", "max-earner-seats", "3"])).unwrap();
+        assert!(matches!(cmd, Command::SetMaxEarnerSeatsGlobally { max_earner_seats: 3 }));
+    }
+
+    #[test]
+    fn test_raffle_eligibility_command() {
+        let cmd = parse_cli_args(&args(&["raffle", "eligibility", "--excluded", "Team A,Team B"])).unwrap();
+        match cmd {
+            Command::PrintRaffleEligibility { excluded_teams } => {
+                assert_eq!(excluded_teams, Some(vec!["Team A".to_string(), "Team B".to_string()]));
+            },
+            _ => panic!("Expected PrintRaffleEligibility command"),
+        }
+
+        let cmd = parse_cli_args(&args(&["raffle", "eligibility"])).unwrap();
+        assert!(matches!(cmd, Command::PrintRaffleEligibility { excluded_teams: None }));
+    }
+
+    #[test]
+    fn test_raffle_reassign_command() {
+        let cmd = parse_cli_args(&args(&["raffle", "reassign", "Proposal A", "Team X", "excluded", "--reason", "Conflict of interest"])).unwrap();
+        match cmd {
+            Command::ReassignRaffleSeats { proposal_name, team_name, new_outcome, reason } => {
+                assert_eq!(proposal_name, "Proposal A");
+                assert_eq!(team_name, "Team X");
+                assert_eq!(new_outcome, SeatOutcome::Excluded);
+                assert_eq!(reason, "Conflict of interest");
+            },
+            _ => panic!("Expected ReassignRaffleSeats command"),
+        }
+    }
+
+    #[test]
+    fn test_proposal_check_duplicate_command() {
+        let cmd = parse_cli_args(&args(&["proposal", "check-duplicate", "Marketing Budget Q3"])).unwrap();
+        assert!(matches!(cmd, Command::CheckDuplicateProposal { title } if title == "Marketing Budget Q3"));
+    }
+
+    #[test]
+    fn test_raffle_verify_command() {
+        let cmd = parse_cli_args(&args(&["raffle", "verify", "Proposal A"])).unwrap();
+        assert!(matches!(cmd, Command::VerifyRaffle { proposal_name, on_chain: false } if proposal_name == "Proposal A"));
+
+        let cmd = parse_cli_args(&args(&["raffle", "verify", "Proposal A", "--on-chain"])).unwrap();
+        assert!(matches!(cmd, Command::VerifyRaffle { on_chain: true, .. }));
+
+        let cmd = parse_cli_args(&args(&["raffle", "audit", "Proposal A"])).unwrap();
+        assert!(matches!(cmd, Command::GenerateRaffleAuditReport { proposal_name } if proposal_name == "Proposal A"));
+
+        let cmd = parse_cli_args(&args(&["raffle", "rerun", "Proposal A", "--block-offset", "5"])).unwrap();
+        assert!(matches!(cmd, Command::RerunRaffle { proposal_name, block_offset: Some(5) } if proposal_name == "Proposal A"));
+
+        let cmd = parse_cli_args(&args(&["raffle", "show", "Proposal A"])).unwrap();
+        assert!(matches!(cmd, Command::ShowRaffle { proposal_name } if proposal_name == "Proposal A"));
+    }
+
     // Report Command Tests
     #[test]
     fn test_report_team_command() {
@@ -1506,7 +3185,13 @@ mod tests {
     fn test_report_epoch_state_command() {
         let args = args(&["report", "epoch-state"]);
         let cmd = parse_cli_args(&args).unwrap();
-        assert!(matches!(cmd, Command::PrintEpochState));
+        assert!(matches!(cmd, Command::PrintEpochState { format: ReportFormat::Text }));
+
+        let cmd = parse_cli_args(&[
+            "robokitty".to_string(), "report".to_string(), "epoch-state".to_string(),
+            "--format".to_string(), "json".to_string()
+        ]).unwrap();
+        assert!(matches!(cmd, Command::PrintEpochState { format: ReportFormat::Json }));
     }
 
     #[test]
@@ -1572,7 +3257,31 @@ mod tests {
 
         let cmd = parse_cli_args(&args).unwrap();
         match cmd {
-            Command::GenerateEndOfEpochReport { epoch_name } => {
+            Command::GenerateEndOfEpochReport { epoch_name, format } => {
+                assert_eq!(epoch_name, "Q1-2024");
+                assert_eq!(format, ReportFormat::Markdown);
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_report_end_of_epoch_csv_format() {
+        let cmd = parse_cli_args(&args(&["report", "end-of-epoch", "Q1-2024", "--format", "csv"])).unwrap();
+        match cmd {
+            Command::GenerateEndOfEpochReport { epoch_name, format } => {
+                assert_eq!(epoch_name, "Q1-2024");
+                assert_eq!(format, ReportFormat::Csv);
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_report_preview_end_of_epoch_command() {
+        let cmd = parse_cli_args(&args(&["report", "preview-end-of-epoch", "Q1-2024"])).unwrap();
+        match cmd {
+            Command::PreviewEndOfEpochReport { epoch_name } => {
                 assert_eq!(epoch_name, "Q1-2024");
             },
             _ => panic!("Wrong command type"),
@@ -1608,11 +3317,19 @@ mod tests {
 
         let cmd = parse_cli_args(&args).unwrap();
         match cmd {
-            Command::GenerateReportForProposal { proposal_name } => {
+            Command::GenerateReportForProposal { proposal_name, epoch_name, sections } => {
                 assert_eq!(proposal_name, "test-proposal");
+                assert_eq!(epoch_name, None);
+                assert_eq!(sections, None);
             },
             _ => panic!("Wrong command type"),
         }
+
+        let mut args = args;
+        args.extend(["--epoch-name".to_string(), "Epoch 1".to_string(), "--sections".to_string(), "raffle,voting".to_string()]);
+        let cmd = parse_cli_args(&args).unwrap();
+        assert!(matches!(cmd, Command::GenerateReportForProposal { epoch_name: Some(ref e), .. } if e == "Epoch 1"));
+        assert!(matches!(cmd, Command::GenerateReportForProposal { sections: Some(ref s), .. } if s == "raffle,voting"));
     }
 
     #[test]
@@ -1717,15 +3434,197 @@ mod tests {
 
     let cmd = parse_cli_args(&args).unwrap();
     match cmd {
-        Command::LogPayment { payment_tx, payment_date, proposal_names } => {
+        Command::LogPayment { payment_tx, payment_date, proposal_names, skip_verification } => {
             assert_eq!(payment_tx, "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e");
             assert_eq!(payment_date, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
             assert_eq!(proposal_names, vec!["proposal1", "proposal2"]);
+            assert!(!skip_verification);
         },
         _ => panic!("Wrong command type"),
     }
     }
 
+    #[test]
+    fn test_proposal_pay_skip_verification() {
+        let cmd = parse_cli_args(&args(&[
+            "proposal", "pay", "proposal1",
+            "--tx", "0xabc",
+            "--date", "2024-01-01",
+            "--skip-verification",
+        ])).unwrap();
+        match cmd {
+            Command::LogPayment { skip_verification, .. } => assert!(skip_verification),
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_import_proposals_command() {
+        let cmd = parse_cli_args(&args(&["import-proposals", "--file", "proposals.json", "--dry-run"])).unwrap();
+        match cmd {
+            Command::ImportProposals { file_path, dry_run } => {
+                assert_eq!(file_path, "proposals.json");
+                assert!(dry_run);
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_global_dry_run_flag() {
+        let (cmd, dry_run) = parse_cli(&args(&["-n", "epoch", "activate", "Epoch 1"])).unwrap();
+        assert!(dry_run);
+        assert!(matches!(cmd, Command::ActivateEpoch { name } if name == "Epoch 1"));
+
+        let (_, dry_run) = parse_cli(&args(&["run-script", "--dry-run"])).unwrap();
+        assert!(dry_run);
+
+        let (_, dry_run) = parse_cli(&args(&["epoch", "activate", "Epoch 1"])).unwrap();
+        assert!(!dry_run);
+    }
+
+    #[tokio::test]
+    async fn test_script_dry_run_leaves_state_unchanged() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = AppConfig {
+            state_file: temp_dir.path().join("state.json").to_str().unwrap().to_string(),
+            ..AppConfig::default()
+        };
+        let ethereum_service = std::sync::Arc::new(crate::services::ethereum::MockEthereumService::new());
+        let mut budget_system = BudgetSystem::new(config.clone(), ethereum_service, None).await.unwrap();
+
+        let start = Utc::now();
+        let epoch_id = budget_system.create_epoch("Epoch 1", start, start + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.create_team("Team A".to_string(), "Alice".to_string(), Some(vec![1000]), None).unwrap();
+
+        let script = vec![
+            Command::AddTeam { name: "Team B".to_string(), representative: "Bob".to_string(), trailing_monthly_revenue: None, address: None },
+            Command::UpdateTeam {
+                team_name: "Team B".to_string(),
+                updates: UpdateTeamDetails {
+                    name: None,
+                    representative: None,
+                    status: None,
+                    trailing_monthly_revenue: None,
+                    address: Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string()),
+                },
+            },
+            Command::UpdateTeam {
+                team_name: "Team A".to_string(),
+                updates: UpdateTeamDetails {
+                    name: None,
+                    representative: Some("Alicia".to_string()),
+                    status: None,
+                    trailing_monthly_revenue: None,
+                    address: None,
+                },
+            },
+            Command::AddProposal {
+                title: "Infrastructure grant".to_string(),
+                url: None,
+                budget_request_details: Some(BudgetRequestDetailsCommand {
+                    team: Some("Team A".to_string()),
+                    request_amounts: Some(HashMap::from([("ETH".to_string(), 10.0)])),
+                    start_date: None,
+                    end_date: None,
+                    is_loan: None,
+                    payment_address: None,
+                    category: None,
+                    ipfs_hash: None,
+                }),
+                announced_at: None,
+                published_at: None,
+                is_historical: None,
+            },
+            Command::SetEpochReward { token: "ETH".to_string(), amount: 50.0 },
+        ];
+        let script_path = temp_dir.path().join("script.json");
+        fs::write(&script_path, serde_json::to_string(&script).unwrap()).unwrap();
+        let run_script = Command::RunScript { script_file_path: Some(script_path.to_str().unwrap().to_string()) };
+
+        let before = budget_system.state().clone();
+        let saved_before = fs::read_to_string(&config.state_file).unwrap();
+        let mut output = Vec::new();
+        execute_command(&mut budget_system, run_script.clone(), &config, &mut output, true).await.unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let planned: Vec<&str> = output.lines()
+            .filter(|line| line.starts_with("[dry-run] Would execute: "))
+            .collect();
+        assert_eq!(planned.len(), script.len() + 1);
+        assert!(planned[0].starts_with("[dry-run] Would execute: RunScript"));
+        assert!(planned[1].starts_with("[dry-run] Would execute: AddTeam"));
+        assert!(planned[5].starts_with("[dry-run] Would execute: SetEpochReward"));
+        let preview: Vec<&str> = output.lines()
+            .skip_while(|line| !line.starts_with("[dry-run] Nothing was saved"))
+            .skip(1)
+            .collect();
+        assert!(preview.contains(&"  - create team Team B"));
+        assert!(preview.contains(&"  - create proposal Infrastructure grant"));
+        assert!(preview.iter().any(|line| line.starts_with("  - update team Team A (changed:")));
+        assert!(preview.iter().any(|line| line.starts_with("  - update epoch Epoch 1 (changed:")));
+        assert_eq!(serde_json::to_string(budget_system.state()).unwrap(), serde_json::to_string(&before).unwrap());
+        assert_eq!(fs::read_to_string(&config.state_file).unwrap(), saved_before);
+        assert!(!budget_system.is_dry_run());
+
+        let mut output = Vec::new();
+        execute_command(&mut budget_system, run_script, &config, &mut output, false).await.unwrap();
+        let applied: Vec<String> = before.diff(budget_system.state()).change_lines().into_iter()
+            .map(|change| format!("  - {}", change))
+            .collect();
+        assert_eq!(applied, preview);
+        assert_ne!(fs::read_to_string(&config.state_file).unwrap(), saved_before);
+
+        let before = serde_json::to_string(budget_system.state()).unwrap();
+        let mut output = Vec::new();
+        let result = execute_command(&mut budget_system, Command::ActivateEpoch { name: "Epoch 2".to_string() }, &config, &mut output, true).await;
+        assert!(result.unwrap_err().to_string().contains("Epoch not found"));
+        assert!(output.is_empty());
+        assert!(!budget_system.is_dry_run());
+        assert_eq!(serde_json::to_string(budget_system.state()).unwrap(), before);
+    }
+
+    #[test]
+    fn test_proposal_list_command() {
+        let cmd = parse_cli_args(&args(&["proposal", "list"])).unwrap();
+        assert!(matches!(cmd, Command::ListProposals { epoch_name: None, status: Some(ProposalListFilter::Open), team: None, unpaid_only: false, page: Some(1) }));
+
+        let cmd = parse_cli_args(&args(&["proposal", "list", "--status", "all", "--epoch", "Q1", "--page", "3"])).unwrap();
+        assert!(matches!(cmd, Command::ListProposals { epoch_name: Some(ref e), status: None, page: Some(3), .. } if e == "Q1"));
+
+        let cmd = parse_cli_args(&args(&["proposal", "list", "--status", "paid", "--team", "Alpha", "--unpaid-only"])).unwrap();
+        assert!(matches!(cmd, Command::ListProposals { status: Some(ProposalListFilter::Paid), team: Some(ref t), unpaid_only: true, .. } if t == "Alpha"));
+
+        assert!(parse_cli_args(&args(&["proposal", "list", "--status", "pending"])).is_err());
+    }
+
+    #[test]
+    fn test_team_payment_history_command() {
+        let cmd = parse_cli_args(&args(&["team", "payment-history", "Team A"])).unwrap();
+        assert!(matches!(cmd, Command::PrintTeamPaymentHistory { team_name } if team_name == "Team A"));
+    }
+
+    #[test]
+    fn test_report_team_performance() {
+        let cmd = parse_cli_args(&args(&["report", "team-performance", "Team A"])).unwrap();
+        assert!(matches!(cmd, Command::GenerateTeamPerformanceReport { team_name, output_path: None } if team_name == "Team A"));
+
+        let cmd = parse_cli_args(&args(&["report", "team-performance", "Team A", "--output", "out.md"])).unwrap();
+        assert!(matches!(cmd, Command::GenerateTeamPerformanceReport { output_path: Some(path), .. } if path == "out.md"));
+    }
+
+    #[test]
+    fn test_restore_backup() {
+        let cmd = parse_cli_args(&args(&["restore-backup", "2024-01-01T00:00:00.000000Z"])).unwrap();
+        assert!(matches!(cmd, Command::RestoreBackup { timestamp: Some(timestamp), confirm: false } if timestamp == "2024-01-01T00:00:00.000000Z"));
+
+        let cmd = parse_cli_args(&args(&["restore-backup", "2024-01-01T00:00:00.000000Z", "--confirm"])).unwrap();
+        assert!(matches!(cmd, Command::RestoreBackup { confirm: true, .. }));
+
+        let cmd = parse_cli_args(&args(&["restore-backup", "--confirm"])).unwrap();
+        assert!(matches!(cmd, Command::RestoreBackup { timestamp: None, confirm: true }));
+    }
+
     #[test]
     fn test_proposal_pay_invalid_date() {
     let args = args(&[
@@ -1750,9 +3649,223 @@ mod tests {
 
         let cmd = parse_cli_args(&args).unwrap();
         match cmd {
-            Command::GenerateEpochPaymentsReport { epoch_name, output_path } => {
+            Command::GenerateEpochPaymentsReport { epoch_name, output_path, format } => {
                 assert_eq!(epoch_name, "Q1-2024");
                 assert_eq!(output_path, Some("payments.json".to_string()));
+                assert_eq!(format, ReportFormat::Json);
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_absence_streaks_command() {
+        let cmd = parse_cli_args(&args(&["report", "absence-streaks", "--min-streak", "2"])).unwrap();
+        assert!(matches!(cmd, Command::PrintAbsenceStreaks { min_streak: 2 }));
+
+        let cmd = parse_cli_args(&args(&["report", "absence-streaks"])).unwrap();
+        assert!(matches!(cmd, Command::PrintAbsenceStreaks { min_streak: 1 }));
+    }
+
+    #[test]
+    fn test_overdue_payments_command() {
+        let cmd = parse_cli_args(&args(&["report", "overdue-payments", "--days", "14"])).unwrap();
+        assert!(matches!(cmd, Command::PrintOverduePayments { days: 14 }));
+
+        let cmd = parse_cli_args(&args(&["report", "overdue-payments"])).unwrap();
+        assert!(matches!(cmd, Command::PrintOverduePayments { days: 30 }));
+    }
+
+    #[test]
+    fn test_pending_payments_command() {
+        let cmd = parse_cli_args(&args(&["report", "pending-payments", "--epoch", "Q1", "--min-days", "7"])).unwrap();
+        match cmd {
+            Command::ListPendingPayments { epoch_name, min_days_pending } => {
+                assert_eq!(epoch_name.as_deref(), Some("Q1"));
+                assert_eq!(min_days_pending, Some(7));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["report", "pending-payments"])).unwrap();
+        assert!(matches!(cmd, Command::ListPendingPayments { epoch_name: None, min_days_pending: None }));
+    }
+
+    #[test]
+    fn test_infographic_command() {
+        let cmd = parse_cli_args(&args(&["report", "infographic", "Q1-2024"])).unwrap();
+        match cmd {
+            Command::ExportEpochInfographicData { epoch_name } => assert_eq!(epoch_name, "Q1-2024"),
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_export_command() {
+        let cmd = parse_cli_args(&args(&[
+            "report", "export", "unpaid",
+            "--epoch-name", "Q1-2024",
+            "--format", "tsv",
+            "--output", "unpaid.tsv"
+        ])).unwrap();
+        match cmd {
+            Command::Export { kind, epoch_name, format, output_path } => {
+                assert_eq!(kind, ExportKind::Unpaid);
+                assert_eq!(epoch_name, Some("Q1-2024".to_string()));
+                assert_eq!(format, ExportFormat::Tsv);
+                assert_eq!(output_path, Some("unpaid.tsv".to_string()));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["report", "export", "proposals"])).unwrap();
+        assert!(matches!(cmd, Command::Export { kind: ExportKind::Proposals, format: ExportFormat::Csv, .. }));
+    }
+
+    #[test]
+    fn test_state_diff_command() {
+        let cmd = parse_cli_args(&args(&["report", "state-diff", "old.json", "new.json"])).unwrap();
+        match cmd {
+            Command::DiffStateFiles { path_a, path_b } => {
+                assert_eq!(path_a, "old.json");
+                assert_eq!(path_b, "new.json");
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_report_headroom_command() {
+        let cmd = parse_cli_args(&args(&["report", "headroom", "Epoch 1", "--caps", "USDC:5000,ETH:10"])).unwrap();
+        match cmd {
+            Command::PrintBudgetHeadroom { epoch_name, caps } => {
+                assert_eq!(epoch_name, "Epoch 1");
+                assert_eq!(caps, vec![("ETH".to_string(), 10.0), ("USDC".to_string(), 5000.0)]);
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["report", "headroom", "Epoch 1"])).unwrap();
+        assert!(matches!(cmd, Command::PrintBudgetHeadroom { caps, .. } if caps.is_empty()));
+    }
+
+    #[test]
+    fn test_report_treasury_command() {
+        let cmd = parse_cli_args(&args(&["report", "treasury", "Q1"])).unwrap();
+        assert!(matches!(cmd, Command::PrintTreasuryReport { ref epoch_name, format: ReportFormat::Text } if epoch_name == "Q1"));
+
+        let cmd = parse_cli_args(&args(&["report", "treasury", "Q1", "--format", "json"])).unwrap();
+        assert!(matches!(cmd, Command::PrintTreasuryReport { format: ReportFormat::Json, .. }));
+    }
+
+    #[test]
+    fn test_report_all_epochs_command() {
+        let cmd = parse_cli_args(&args(&["report", "all-epochs", "--only-closed", "--output", "all.md"])).unwrap();
+        match cmd {
+            Command::GenerateAllEpochsReport { only_closed, output_path } => {
+                assert!(only_closed);
+                assert_eq!(output_path, Some("all.md".to_string()));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["report", "all-epochs"])).unwrap();
+        assert!(matches!(cmd, Command::GenerateAllEpochsReport { only_closed: false, output_path: None }));
+    }
+
+    #[test]
+    fn test_report_multisig_payload_command() {
+        let cmd = parse_cli_args(&args(&["report", "multisig-payload", "Q1-2024", "--output", "safe.json"])).unwrap();
+        match cmd {
+            Command::GenerateMultisigPayload { epoch_name, output_path } => {
+                assert_eq!(epoch_name, "Q1-2024");
+                assert_eq!(output_path, Some("safe.json".to_string()));
+            },
+            _ => panic!("Wrong command type"),
+        }
+    }
+
+    #[test]
+    fn test_report_vote_trends_command() {
+        let cmd = parse_cli_args(&args(&["report", "vote-trends", "--only-closed", "--output", "trends.md"])).unwrap();
+        match cmd {
+            Command::GenerateVoteTrendsReport { only_closed, output_path } => {
+                assert!(only_closed);
+                assert_eq!(output_path, Some("trends.md".to_string()));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["report", "vote-trends"])).unwrap();
+        assert!(matches!(cmd, Command::GenerateVoteTrendsReport { only_closed: false, output_path: None }));
+    }
+
+    #[test]
+    fn test_report_burndown_command() {
+        let cmd = parse_cli_args(&args(&["report", "burndown", "Epoch 1", "--caps", "USDC:5000", "--output", "burndown.json"])).unwrap();
+        match cmd {
+            Command::ShowBudgetBurndown { epoch_name, caps, output_path } => {
+                assert_eq!(epoch_name, "Epoch 1");
+                assert_eq!(caps, vec![("USDC".to_string(), 5000.0)]);
+                assert_eq!(output_path, Some("burndown.json".to_string()));
+            },
+            _ => panic!("Wrong command type"),
+        }
+
+        let cmd = parse_cli_args(&args(&["report", "burndown", "Epoch 1"])).unwrap();
+        assert!(matches!(cmd, Command::ShowBudgetBurndown { caps, output_path: None, .. } if caps.is_empty()));
+    }
+
+    #[test]
+    fn test_report_approval_rates_command() {
+        let cmd = parse_cli_args(&args(&["report", "approval-rates"])).unwrap();
+        assert!(matches!(cmd, Command::PrintApprovalRatesByStatus { recent_epochs: None }));
+
+        let cmd = parse_cli_args(&args(&["report", "approval-rates", "--recent-epochs", "3"])).unwrap();
+        assert!(matches!(cmd, Command::PrintApprovalRatesByStatus { recent_epochs: Some(3) }));
+    }
+
+    #[test]
+    fn test_report_waiting_times_command() {
+        let cmd = parse_cli_args(&args(&["report", "waiting-times"])).unwrap();
+        assert!(matches!(cmd, Command::PrintProposalWaitingPercentiles { epoch_name: None }));
+
+        let cmd = parse_cli_args(&args(&["report", "waiting-times", "--epoch-name", "Epoch 1"])).unwrap();
+        assert!(matches!(cmd, Command::PrintProposalWaitingPercentiles { epoch_name: Some(ref e) } if e == "Epoch 1"));
+    }
+
+    #[test]
+    fn test_geography_command() {
+        let cmd = parse_cli_args(&args(&["report", "geography"])).unwrap();
+        assert!(matches!(cmd, Command::PrintGeographicDistribution));
+    }
+
+    #[test]
+    fn test_state_size_command() {
+        let cmd = parse_cli_args(&args(&["report", "state-size"])).unwrap();
+        assert!(matches!(cmd, Command::PrintStateSize));
+    }
+
+    #[test]
+    fn test_verify_points_command() {
+        let cmd = parse_cli_args(&args(&["report", "verify-points"])).unwrap();
+        assert!(matches!(cmd, Command::VerifyPoints));
+    }
+
+    #[test]
+    fn test_budget_flow_command() {
+        let args = args(&[
+            "report",
+            "budget-flow",
+            "Q1-2024",
+            "--output", "flow.json"
+        ]);
+
+        let cmd = parse_cli_args(&args).unwrap();
+        match cmd {
+            Command::ExportBudgetFlowData { epoch_name, output_path } => {
+                assert_eq!(epoch_name, "Q1-2024");
+                assert_eq!(output_path, Some("flow.json".to_string()));
             },
             _ => panic!("Wrong command type"),
         }
@@ -1768,14 +3881,24 @@ mod tests {
 
         let cmd = parse_cli_args(&args).unwrap();
         match cmd {
-            Command::GenerateEpochPaymentsReport { epoch_name, output_path } => {
+            Command::GenerateEpochPaymentsReport { epoch_name, output_path, format } => {
                 assert_eq!(epoch_name, "Q1-2024");
                 assert_eq!(output_path, None);
+                assert_eq!(format, ReportFormat::Json);
             },
             _ => panic!("Wrong command type"),
         }
     }
 
+    #[test]
+    fn test_epoch_payments_command_format() {
+        let cmd = parse_cli_args(&args(&["report", "epoch-payments", "Q1-2024", "--format", "csv"])).unwrap();
+        assert!(matches!(cmd, Command::GenerateEpochPaymentsReport { format: ReportFormat::Csv, .. }));
+
+        let cmd = parse_cli_args(&args(&["report", "epoch-payments", "Q1-2024", "--format", "markdown"])).unwrap();
+        assert!(matches!(cmd, Command::GenerateEpochPaymentsReport { format: ReportFormat::Markdown, .. }));
+    }
+
 }
 
 // TODO: Missing unit tests for CLI
@@ -2,8 +2,10 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, io::Write};
 use async_trait::async_trait;
+use uuid::Uuid;
 
-use crate::core::models::VoteChoice;
+use crate::core::models::{EpochStatus, ProposalCategory, ProposalListFilter, ProposalStatus, SeatOutcome, VoteChoice};
+use crate::services::export::ExportFormat;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "params")]
@@ -14,6 +16,12 @@ pub enum Command {
         DateTime<Utc>,
         end_date: DateTime<Utc>,
     },
+    CloneEpoch {
+        source_epoch_name: String,
+        new_name: String,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    },
     ActivateEpoch {
         name: String
     },
@@ -31,6 +39,20 @@ pub enum Command {
         team_name: String,
         updates: UpdateTeamDetails,
     },
+    MergeTeams {
+        source_name: String,
+        target_name: String,
+    },
+    AddTeamAlias {
+        team_name: String,
+        alias: String,
+    },
+    ArchiveTeam {
+        team_name: String,
+    },
+    UnarchiveTeam {
+        team_name: String,
+    },
     AddProposal {
         title: String,
         url: Option<String>,
@@ -68,7 +90,10 @@ pub enum Command {
         max_earner_seats: Option<usize>,
     },
     PrintTeamReport,
-    PrintEpochState,
+    PrintEpochState {
+        #[serde(default)]
+        format: ReportFormat,
+    },
     PrintTeamVoteParticipation {
         team_name: String,
         epoch_name: Option<String> 
@@ -86,23 +111,45 @@ pub enum Command {
         proposal_name: String,
         counted_votes: HashMap<String, VoteChoice>,
         uncounted_votes: HashMap<String, VoteChoice>,
+        /// Teams whose vote was cast by another team, keyed by the delegating team
+        #[serde(default)]
+        delegations: HashMap<String, String>,
         vote_opened: Option<NaiveDate>,
         vote_closed: Option<NaiveDate>,
+        /// Recorded on the vote; the closing date may not be later
+        #[serde(default)]
+        deadline: Option<DateTime<Utc>>,
+    },
+    CloseExpiredVotes,
+    GenerateMultisigPayload {
+        epoch_name: String,
+        #[serde(default)]
+        output_path: Option<String>,
     },
     GenerateReportsForClosedProposals {
         epoch_name: String
     },
     GenerateReportForProposal {
-        proposal_name: String
+        proposal_name: String,
+        #[serde(default)]
+        epoch_name: Option<String>,
+        /// Comma separated report sections (summary, details, budget, raffle, voting); all when omitted
+        #[serde(default)]
+        sections: Option<String>,
     },
     PrintPointReport {
         epoch_name: Option<String>
      },
+    PrintCategoryBreakdown {
+        epoch_name: Option<String>,
+    },
     CloseEpoch {
         epoch_name: Option<String>
     },
     GenerateEndOfEpochReport {
-        epoch_name: String
+        epoch_name: String,
+        #[serde(default = "default_end_of_epoch_format")]
+        format: ReportFormat,
     },
     RunScript {
         script_file_path: Option<String>,
@@ -115,13 +162,360 @@ pub enum Command {
         payment_tx: String,
         payment_date: NaiveDate,
         proposal_names: Vec<String>,
+        #[serde(default)]
+        skip_verification: bool,
     },
     GenerateEpochPaymentsReport {
         epoch_name: String,
         output_path: Option<String>,
+        #[serde(default = "default_payments_format")]
+        format: ReportFormat,
+    },
+    ListExcludedTeams {
+        proposal_name: String,
+    },
+    ExportBudgetFlowData {
+        epoch_name: String,
+        output_path: Option<String>,
+    },
+    SetEpochVotingWindows {
+        epoch_name: Option<String>,
+        min_days_before_vote: Option<u64>,
+        max_days_to_vote: Option<u64>,
+    },
+    RecomputeEpochFromVotes {
+        epoch_name: String,
+        confirm: bool,
+    },
+    PrintAbsenceStreaks {
+        min_streak: usize,
+    },
+    BulkCloseProposals {
+        epoch_name: String,
+        resolution: String,
+        reason: Option<String>,
+        confirm: bool,
+    },
+    BulkAddTeams {
+        teams: Vec<AddTeamDetails>,
+    },
+    ShowDecidingTeams {
+        proposal_name: String,
+    },
+    ShowRaffle {
+        proposal_name: String,
+    },
+    RerunRaffle {
+        proposal_name: String,
+        block_offset: Option<u64>,
+    },
+    PrintOverduePayments {
+        days: u64,
+    },
+    ListPendingPayments {
+        epoch_name: Option<String>,
+        min_days_pending: Option<u64>,
+    },
+    PrintStateSize,
+    /// Recalculates every team's points from the votes and lists any that differ
+    VerifyPoints,
+    ExportEpochInfographicData {
+        epoch_name: String,
+    },
+    SetProposalLoanTerms {
+        proposal_name: String,
+        interest_rate: f64,
+        due_date: NaiveDate,
+        repayment_schedule: String,
+        repaid_amounts: Option<HashMap<String, f64>>,
+    },
+    PrintLoansOutstanding,
+    SetProposalTeamAllocations {
+        proposal_name: String,
+        /// Requested amounts per token, keyed by team name
+        allocations: HashMap<String, HashMap<String, f64>>,
+    },
+    LogAllocationPayment {
+        payment_tx: String,
+        payment_date: NaiveDate,
+        proposal_name: String,
+        team_names: Vec<String>,
+        #[serde(default)]
+        skip_verification: bool,
+    },
+    RecordLoanRepayment {
+        proposal_name: String,
+        repaid_at: Option<NaiveDate>,
+    },
+    PrintOutstandingLoans {
+        epoch_name: Option<String>,
+    },
+    CreateProposalTemplate {
+        name: String,
+        title_pattern: String,
+        team: Option<String>,
+        amounts: HashMap<String, f64>,
+        is_loan: bool,
+        tags: Vec<String>,
+        recurrence_interval_epochs: Option<u32>,
+        max_occurrences: Option<u32>,
+    },
+    ListProposalTemplates,
+    CreateProposalFromTemplate {
+        template_name: String,
+        epoch_name: Option<String>,
+        title: Option<String>,
+        url: Option<String>,
+        team: Option<String>,
+        amounts: Option<HashMap<String, f64>>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        announced_at: Option<NaiveDate>,
+        published_at: Option<NaiveDate>,
+    },
+    UpdateTeamRegion {
+        team_name: String,
+        region: Option<String>,
+    },
+    PrintGeographicDistribution,
+    SetMaxEarnerSeatsGlobally {
+        max_earner_seats: usize,
+    },
+    PreviewEndOfEpochReport {
+        epoch_name: String,
+    },
+    Export {
+        kind: ExportKind,
+        epoch_name: Option<String>,
+        #[serde(default)]
+        format: ExportFormat,
+        output_path: Option<String>,
+    },
+    SetProposalReviewers {
+        proposal_name: String,
+        reviewers: Vec<String>,
+    },
+    RecordReviewerSignOff {
+        proposal_name: String,
+        reviewer: String,
+        signed_off_at: Option<NaiveDate>,
+    },
+    RequestVoteChange {
+        vote_id: Uuid,
+        team_name: String,
+        new_choice: VoteChoice,
+        reason: String,
+    },
+    ApproveVoteChange {
+        vote_id: Uuid,
+        team_name: String,
+    },
+    ListVoteChangeRequests {
+        vote_id: Uuid,
+    },
+    ReopenVote {
+        proposal_name: String,
+    },
+    DiffStateFiles {
+        path_a: String,
+        path_b: String,
+    },
+    ReassignRaffleSeats {
+        proposal_name: String,
+        team_name: String,
+        new_outcome: SeatOutcome,
+        reason: String,
+    },
+    PrintBudgetHeadroom {
+        epoch_name: String,
+        #[serde(default)]
+        caps: Vec<(String, f64)>,
+    },
+    PrintProposalWaitingPercentiles {
+        epoch_name: Option<String>,
+    },
+    PrintRaffleEligibility {
+        excluded_teams: Option<Vec<String>>,
+    },
+    VerifyRaffle {
+        proposal_name: String,
+        /// Also re-fetch the block randomness from the node
+        #[serde(default)]
+        on_chain: bool,
+    },
+    GenerateRaffleAuditReport {
+        proposal_name: String,
+    },
+    SetEpochStatus {
+        epoch_name: String,
+        status: EpochStatus,
+        #[serde(default)]
+        confirm: bool,
+        #[serde(default)]
+        force: bool,
+    },
+    PrintApprovalRatesByStatus {
+        #[serde(default)]
+        recent_epochs: Option<usize>,
+    },
+    SetTeamContacts {
+        team_name: String,
+        #[serde(default)]
+        contacts: Vec<String>,
+    },
+    PingAbsentVoters {
+        proposal_name: String,
+    },
+    SetProposalStatus {
+        proposal_name: String,
+        status: ProposalStatus,
+    },
+    ShowBudgetBurndown {
+        epoch_name: String,
+        #[serde(default)]
+        caps: Vec<(String, f64)>,
+        #[serde(default)]
+        output_path: Option<String>,
+    },
+    GenerateAllEpochsReport {
+        #[serde(default)]
+        only_closed: bool,
+        #[serde(default)]
+        output_path: Option<String>,
+    },
+    /// Committed, paid and reward pool totals per token for an epoch
+    PrintTreasuryReport {
+        epoch_name: String,
+        #[serde(default)]
+        format: ReportFormat,
+    },
+    /// Markdown report of unpaid, outstanding and repaid loans, saved under the reports
+    /// directory when `output_path` is given
+    GenerateLoanReport {
+        #[serde(default)]
+        output_path: Option<String>,
+    },
+    GenerateVoteTrendsReport {
+        #[serde(default)]
+        only_closed: bool,
+        #[serde(default)]
+        output_path: Option<String>,
+    },
+    ListTeamProposals {
+        team_name: String,
+        #[serde(default)]
+        epoch_name: Option<String>,
+    },
+    /// Lists proposals a page at a time; defaults to the active epoch and page 1.
+    /// A status of None lists every proposal. `team` and `unpaid_only` narrow the
+    /// listing further and combine with the status filter.
+    ListProposals {
+        #[serde(default)]
+        epoch_name: Option<String>,
+        #[serde(default)]
+        status: Option<ProposalListFilter>,
+        #[serde(default)]
+        team: Option<String>,
+        #[serde(default)]
+        unpaid_only: bool,
+        #[serde(default)]
+        page: Option<usize>,
+    },
+    ExtendEpoch {
+        #[serde(default)]
+        epoch_name: Option<String>,
+        new_end_date: DateTime<Utc>,
+    },
+    ListTeams {
+        #[serde(default)]
+        status_filter: Option<String>,
+    },
+    ListEpochs {
+        #[serde(default)]
+        include_closed: bool,
+    },
+    ForceCloseEpoch {
+        #[serde(default)]
+        epoch_name: Option<String>,
+    },
+    ImportProposals {
+        file_path: String,
+        #[serde(default)]
+        dry_run: bool,
+    },
+    PinProposalDocument {
+        proposal_name: String,
+        ipfs_hash: String,
+    },
+    CheckDuplicateProposal {
+        title: String,
+    },
+    PrintTeamPaymentHistory {
+        team_name: String,
+    },
+    GenerateTeamPerformanceReport {
+        team_name: String,
+        output_path: Option<String>,
+    },
+    RestoreBackup {
+        #[serde(default)]
+        timestamp: Option<String>,
+        #[serde(default)]
+        confirm: bool,
     },
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ReportFormat {
+    #[default]
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+fn default_payments_format() -> ReportFormat {
+    ReportFormat::Json
+}
+
+fn default_end_of_epoch_format() -> ReportFormat {
+    ReportFormat::Markdown
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ReportFormat::Text),
+            "json" => Ok(ReportFormat::Json),
+            "csv" => Ok(ReportFormat::Csv),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            _ => Err(format!("Invalid format: {}. Use text, json, csv or markdown", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExportKind {
+    Payments,
+    Proposals,
+    Unpaid,
+}
+
+impl std::str::FromStr for ExportKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "payments" => Ok(ExportKind::Payments),
+            "proposals" => Ok(ExportKind::Proposals),
+            "unpaid" => Ok(ExportKind::Unpaid),
+            _ => Err(format!("Invalid export kind: {}. Use payments, proposals or unpaid", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateTeamDetails {
     pub name: Option<String>,
@@ -131,6 +525,14 @@ pub struct UpdateTeamDetails {
     pub address: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddTeamDetails {
+    pub name: String,
+    pub representative: String,
+    pub trailing_monthly_revenue: Option<Vec<u64>>,
+    pub address: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BudgetRequestDetailsCommand {
     pub team: Option<String>,
@@ -139,6 +541,9 @@ pub struct BudgetRequestDetailsCommand {
     pub end_date: Option<NaiveDate>,
     pub is_loan: Option<bool>,
     pub payment_address: Option<String>,
+    pub category: Option<ProposalCategory>,
+    /// IPFS CID of the proposal document
+    pub ipfs_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,6 +554,7 @@ pub struct UpdateProposalDetails {
     pub announced_at: Option<NaiveDate>,
     pub published_at: Option<NaiveDate>,
     pub resolved_at: Option<NaiveDate>,
+    pub category: Option<ProposalCategory>,
 }
 
 #[async_trait]
@@ -1,9 +1,11 @@
 use teloxide::utils::command::BotCommands;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
 use crate::escape_markdown;
-use crate::core::budget_system::BudgetSystem;
-use crate::core::models::VoteChoice;
-use crate::commands::common::{Command, CommandExecutor, BudgetRequestDetailsCommand, UpdateProposalDetails, UpdateTeamDetails};
+use crate::core::budget_system::{BudgetSystem, BudgetSystemError, PROPOSAL_PAGE_SIZE};
+use crate::core::models::{ProposalCategory, ProposalListFilter, VoteChoice};
+use crate::commands::common::{Command, CommandExecutor, AddTeamDetails, ReportFormat, BudgetRequestDetailsCommand, UpdateProposalDetails, UpdateTeamDetails};
 use chrono::{NaiveDate, DateTime, Utc, TimeZone};
+use chrono_tz::Tz;
 use std::collections::HashMap;
 
 /// These commands are supported:
@@ -31,6 +33,20 @@ pub enum TelegramCommand {
         name: String
     },
 
+    /// Close an epoch. Fails while proposals are still open. Usage: /close_epoch <name>
+    /// 
+    #[command(parse_with = "split")]
+    CloseEpoch {
+        epoch_name: String
+    },
+
+    /// Retract an epoch's open proposals, then close it. Usage: /force_close_epoch <name>
+    /// 
+    #[command(parse_with = "split")]
+    ForceCloseEpoch {
+        epoch_name: String
+    },
+
     /// Set epoch reward. Usage: /set_epoch_reward <token> <amount>
     /// 
     #[command(parse_with = "split")]
@@ -56,6 +72,16 @@ pub enum TelegramCommand {
         end_date: String
     },
 
+    /// Create a planned epoch with another epoch's rewards.
+    /// Usage: /clone_epoch <source_epoch> <new_name> <start_date YYYY-MM-DD> <end_date YYYY-MM-DD>
+    #[command(parse_with = "split")]
+    CloneEpoch {
+        source_epoch: String,
+        name: String,
+        start_date: String,
+        end_date: String
+    },
+
     /// Add a new team. 
     /// Usage: /add_team name:TeamName rep:Representative [rev:1000,2000,3000]
     /// For supporter teams, omit the rev parameter
@@ -64,6 +90,14 @@ pub enum TelegramCommand {
         args: String,
     },
 
+    /// Add several teams at once, separated by ';'.
+    /// Usage: /bulk_add_teams name:TeamA rep:RepA; name:TeamB rep:RepB rev:1000,2000,3000
+    /// Each team is added independently; failures are reported per team
+    /// 
+    BulkAddTeams {
+        args: String,
+    },
+
     /// Update a team's details. 
     /// Usage: /update_team team:TeamName [name:NewName] [rep:NewRep] [status:Earner|Supporter|Inactive] [rev:1000,2000,3000]
     /// Note: Earner status requires revenue data
@@ -72,15 +106,28 @@ pub enum TelegramCommand {
         args: String,
     },
 
+    /// Archive a team: it stays in reports but gets no raffle tickets and cannot vote.
+    /// Usage: /archive_team <team_name>
+    ArchiveTeam {
+        team_name: String,
+    },
+
+    /// Restore an archived team to its previous status.
+    /// Usage: /unarchive_team <team_name>
+    UnarchiveTeam {
+        team_name: String,
+    },
+
     /// Add a new proposal. 
-    /// Usage: /add_proposal title:ProposalTitle url:https://example.com [team:TeamName] [amounts:ETH:100.5,USD:1000] [start:2024-01-01] [end:2024-12-31] [announced:2024-01-01] [published:2024-01-01] [loan:true/false] [address:0x...]
+    /// Usage: /add_proposal title:ProposalTitle url:https://example.com [team:TeamName] [amounts:ETH:100.5,USD:1000] [start:2024-01-01] [end:2024-12-31] [announced:2024-01-01|today] [published:2024-01-01|today] [loan:true/false] [address:0x...] [category:Research] [tz:Europe/Berlin]
+    /// "today" is resolved in the tz timezone, falling back to the chat's timezone from chat_timezones, then timezone (UTC if unset)
     /// 
     AddProposal {
         args: String,
     },
 
     /// Update a proposal's details. 
-    /// Usage: /update_proposal proposal:ExistingTitle [title:NewTitle] [url:NewURL] [team:TeamName] [amounts:ETH:200.5,USD:2000] [start:2024-02-01] [end:2024-12-31] [announced:2024-01-01] [published:2024-01-01] [resolved:2024-12-31]
+    /// Usage: /update_proposal proposal:ExistingTitle [title:NewTitle] [url:NewURL] [team:TeamName] [amounts:ETH:200.5,USD:2000] [start:2024-02-01] [end:2024-12-31] [announced:2024-01-01] [published:2024-01-01] [resolved:2024-12-31] [category:Research]
     /// 
     UpdateProposal {
         args: String,
@@ -94,12 +141,19 @@ pub enum TelegramCommand {
     },
 
     /// Process a vote for a proposal.
-    /// Usage: /process_vote name:ProposalName counted:Team1:Yes,Team2:No uncounted:Team3:Yes,Team4:No opened:2024-01-01 closed:2024-01-01
+    /// Usage: /process_vote name:ProposalName counted:Team1:Yes,Team2:No uncounted:Team3:Yes,Team4:No opened:2024-01-01 closed:2024-01-01 [deadline:2024-01-07]
     /// 
     ProcessVote {
         args: String,
     },
 
+    /// Vote on a proposal with buttons for each team seated by its raffle.
+    /// Usage: /start_vote name:ProposalName
+    /// 
+    StartVote {
+        args: String,
+    },
+
     /// Create a raffle for a proposal. 
     /// Usage: /create_raffle name:ProposalName [block_offset:10] [excluded:Team1,Team2]
     /// 
@@ -107,6 +161,67 @@ pub enum TelegramCommand {
         args: String,
     },
 
+    /// Show the deciding teams selected by a proposal's raffle.
+    /// Usage: /deciders name:ProposalName
+    /// 
+    Deciders {
+        args: String,
+    },
+
+    /// Attach the IPFS hash (CID) of a proposal's document.
+    /// Usage: /pin_document proposal:ProposalName hash:Qm...
+    PinDocument {
+        args: String,
+    },
+
+    /// Show a proposal's raffle configuration, team snapshots and seats.
+    /// Usage: /show_raffle name:ProposalName
+    ShowRaffle {
+        args: String,
+    },
+
+    /// Show raffle eligibility and seat chances without creating a raffle.
+    /// Usage: /raffle_eligibility [excluded:Team1,Team2]
+    RaffleEligibility {
+        args: String,
+    },
+
+    /// List a team's proposals, optionally limited to one epoch.
+    /// Usage: /print_team_proposals team:TeamName [epoch:EpochName]
+    PrintTeamProposals {
+        args: String,
+    },
+
+    /// List proposals ten at a time, open ones in the active epoch by default.
+    /// Usage: /list_proposals [status:open|approved|rejected|paid|unpaid|all] [team:TeamName] [unpaid:true] [page:N] [epoch:EpochName]
+    ListProposals {
+        args: String,
+    },
+
+    /// Show a team's approved budget requests and payments across epochs.
+    /// Usage: /team_payment_history name:TeamName
+    TeamPaymentHistory {
+        args: String,
+    },
+
+    /// List epochs by start date. Closed epochs are only shown with "all".
+    /// Usage: /list_epochs [all]
+    ListEpochs {
+        args: String,
+    },
+
+    /// List teams, optionally filtered by status.
+    /// Usage: /list_teams [status:active|earner|supporter|inactive|archived]
+    ListTeams {
+        filter: String,
+    },
+
+    /// Mention teams that have not voted yet on a proposal's open vote.
+    /// Usage: /ping_absent_voters name:ProposalName
+    PingAbsentVoters {
+        args: String,
+    },
+
     /// Generate unpaid requests report. 
     /// Usage: /generate_unpaid_report [epoch_name]
     GenerateUnpaidReport {
@@ -119,8 +234,34 @@ pub enum TelegramCommand {
         epoch_name: String,
     },
 
+    /// Preview the end of epoch report without saving it.
+    /// Usage: /preview_epoch_report <epoch_name>
+    PreviewEpochReport {
+        epoch_name: String,
+    },
+
+    /// List approved requests still unpaid after a number of days.
+    /// Usage: /overdue_payments [days]
+    OverduePayments {
+        args: String,
+    },
+
+    /// List approved requests that are still unpaid, longest pending first.
+    /// Usage: /pending_payments [epoch:<name>] [min_days:<days>]
+    PendingPayments {
+        args: String,
+    },
+
+    /// Show unpaid, outstanding and repaid loans with per-token totals.
+    /// Usage: /loan_report
+    LoanReport,
+
+    /// Recalculate team points from the votes and report any inconsistencies.
+    /// Usage: /verify_points
+    VerifyPoints,
+
     /// Log payment for proposals.
-    /// Usage: /log_payment tx:<HASH> date:<YYYY-MM-DD> proposals:<PROP1,PROP2,...>
+    /// Usage: /log_payment tx:<HASH> date:<YYYY-MM-DD> proposals:<PROP1,PROP2,...> [skip_verification:true]
     LogPayment {
         args: String, 
     }
@@ -157,6 +298,8 @@ struct AddProposalArgs {
     published_date: Option<String>,
     is_loan: Option<bool>,
     payment_address: Option<String>,
+    category: Option<ProposalCategory>,
+    timezone: Option<String>,
 }
 
 #[derive(Debug)]
@@ -173,6 +316,7 @@ struct UpdateProposalArgs {
     resolved_date: Option<String>,
     is_loan: Option<bool>,
     payment_address: Option<String>,
+    category: Option<ProposalCategory>,
 }
 
 #[derive(Debug)]
@@ -181,13 +325,14 @@ struct CloseProposalArgs {
     resolution: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct ProcessVoteArgs {
     name: String,
     counted_votes: HashMap<String, VoteChoice>,
     uncounted_votes: HashMap<String, VoteChoice>,
     vote_opened: Option<NaiveDate>,
     vote_closed: Option<NaiveDate>,
+    deadline: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug)]
@@ -198,6 +343,32 @@ struct CreateRaffleArgs {
 }
 
 impl TelegramCommand {
+    /// Commands that only report on the state. Anything not listed here is treated
+    /// as state-mutating, so new commands are restricted until added.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self,
+            TelegramCommand::Help
+            | TelegramCommand::PrintTeamReport
+            | TelegramCommand::PrintEpochState
+            | TelegramCommand::PrintTeamParticipation { .. }
+            | TelegramCommand::Deciders { .. }
+            | TelegramCommand::ShowRaffle { .. }
+            | TelegramCommand::RaffleEligibility { .. }
+            | TelegramCommand::PrintTeamProposals { .. }
+            | TelegramCommand::ListProposals { .. }
+            | TelegramCommand::TeamPaymentHistory { .. }
+            | TelegramCommand::ListEpochs { .. }
+            | TelegramCommand::ListTeams { .. }
+            | TelegramCommand::GenerateUnpaidReport { .. }
+            | TelegramCommand::EpochPayments { .. }
+            | TelegramCommand::PreviewEpochReport { .. }
+            | TelegramCommand::OverduePayments { .. }
+            | TelegramCommand::PendingPayments { .. }
+            | TelegramCommand::LoanReport
+            | TelegramCommand::VerifyPoints
+        )
+    }
+
     fn parse_date(date_str: &str) -> Result<NaiveDate, String> {
         NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
             .map_err(|e| format!("Invalid date format (use YYYY-MM-DD): {}", e))
@@ -213,6 +384,20 @@ impl TelegramCommand {
         Ok(Utc.from_utc_datetime(&date.and_hms_opt(23, 59, 59).unwrap()))
     }
     
+    fn parse_timezone(tz_str: &str) -> Result<Tz, String> {
+        tz_str.parse::<Tz>()
+            .map_err(|_| format!("Invalid timezone: {}. Use an IANA name such as Europe/Berlin", tz_str))
+    }
+
+    /// Resolves a date-only input to a calendar day. "today" means today in the given timezone.
+    fn resolve_date_in_timezone(date_str: &str, tz: Tz) -> Result<NaiveDate, String> {
+        if date_str.eq_ignore_ascii_case("today") {
+            Ok(Utc::now().with_timezone(&tz).date_naive())
+        } else {
+            Self::parse_date(date_str)
+        }
+    }
+
     fn parse_command(input: &str) -> Result<Vec<String>, String> {
         let mut args = Vec::new();
         let mut current_arg = String::new();
@@ -321,7 +506,7 @@ impl TelegramCommand {
 
     fn parse_add_proposal(args: &[String]) -> Result<AddProposalArgs, String> {
         if args.len() < 2 {
-            return Err("Usage: /add_proposal title:\"<title>\" url:\"<url>\" [team:<name>] [amounts:<token>:<amount>,...] [start:<YYYY-MM-DD>] [end:<YYYY-MM-DD>] [ann:<YYYY-MM-DD>] [pub:<YYYY-MM-DD>] [loan:true/false] [address:<eth_address>]".to_string());
+            return Err("Usage: /add_proposal title:\"<title>\" url:\"<url>\" [team:<name>] [amounts:<token>:<amount>,...] [start:<YYYY-MM-DD>] [end:<YYYY-MM-DD>] [ann:<YYYY-MM-DD>] [pub:<YYYY-MM-DD>] [loan:true/false] [address:<eth_address>] [category:<category>]".to_string());
         }
 
         let mut title = None;
@@ -334,6 +519,8 @@ impl TelegramCommand {
         let mut published_date = None;
         let mut is_loan = None;
         let mut payment_address = None;
+        let mut category = None;
+        let mut timezone = None;
 
         for arg in args {
             if let Some((key, value)) = arg.split_once(':') {
@@ -351,6 +538,11 @@ impl TelegramCommand {
                             .map_err(|_| format!("Invalid loan value: {}", value))?);
                     },
                     "address" => payment_address = Some(value.to_string()),
+                    "category" => category = Some(value.parse::<ProposalCategory>()?),
+                    "tz" => {
+                        Self::parse_timezone(value)?;
+                        timezone = Some(value.to_string());
+                    },
                     _ => return Err(format!("Unknown parameter: {}", key))
                 }
             }
@@ -367,6 +559,8 @@ impl TelegramCommand {
             published_date,
             is_loan,
             payment_address,
+            category,
+            timezone,
         })
     }
 
@@ -390,7 +584,7 @@ impl TelegramCommand {
             return Err("Usage: /update_proposal proposal:\"Name\" [title:\"New Title\"] [url:\"new-url\"] \
                         [team:\"name\"] [amounts:\"token:amount\"] [start:\"YYYY-MM-DD\"] [end:\"YYYY-MM-DD\"] \
                         [announced:\"YYYY-MM-DD\"] [published:\"YYYY-MM-DD\"] [resolved:\"YYYY-MM-DD\"] \
-                        [loan:true/false] [address:eth_address]".to_string());
+                        [loan:true/false] [address:eth_address] [category:category]".to_string());
         }
 
         let mut proposal_name = None;
@@ -405,6 +599,7 @@ impl TelegramCommand {
         let mut resolved_date = None;
         let mut is_loan = None;
         let mut payment_address = None;
+        let mut category = None;
 
         for arg in args {
             if let Some((key, value)) = arg.split_once(':') {
@@ -424,6 +619,7 @@ impl TelegramCommand {
                             .map_err(|_| format!("Invalid loan value: {}", value))?);
                     },
                     "address" => payment_address = Some(value.to_string()),
+                    "category" => category = Some(value.parse::<ProposalCategory>()?),
                     _ => return Err(format!("Unknown parameter: {}", key))
                 }
             }
@@ -442,6 +638,7 @@ impl TelegramCommand {
             resolved_date,
             is_loan,
             payment_address,
+            category,
         })
     }
 
@@ -482,6 +679,7 @@ impl TelegramCommand {
         let mut uncounted_votes = HashMap::new();
         let mut vote_opened = None;
         let mut vote_closed = None;
+        let mut deadline = None;
 
         fn parse_votes(votes_str: &str) -> Result<HashMap<String, VoteChoice>, String> {
             votes_str
@@ -494,7 +692,8 @@ impl TelegramCommand {
                     let choice = match parts[1].to_lowercase().as_str() {
                         "yes" => VoteChoice::Yes,
                         "no" => VoteChoice::No,
-                        _ => return Err(format!("Invalid vote choice: {}. Must be Yes or No", parts[1])),
+                        "abstain" => VoteChoice::Abstain,
+                        _ => return Err(format!("Invalid vote choice: {}. Must be Yes, No or Abstain", parts[1])),
                     };
                     Ok((parts[0].to_string(), choice))
                 })
@@ -509,6 +708,7 @@ impl TelegramCommand {
                     "uncounted" => uncounted_votes = parse_votes(value)?,
                     "opened" => vote_opened = Some(Self::parse_date(value)?),
                     "closed" => vote_closed = Some(Self::parse_date(value)?),
+                    "deadline" => deadline = Some(Self::parse_end_date(value)?),
                     _ => return Err(format!("Unknown parameter: {}", key)),
                 }
             }
@@ -520,9 +720,26 @@ impl TelegramCommand {
             uncounted_votes,
             vote_opened,
             vote_closed,
+            deadline,
         })
     }
 
+    pub fn parse_start_vote(args: &str) -> Result<String, String> {
+        let args = Self::parse_command(args)
+            .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+        let mut proposal_name = None;
+        for arg in args {
+            if let Some((key, value)) = arg.split_once(':') {
+                match key {
+                    "name" => proposal_name = Some(value.to_string()),
+                    _ => return Err(format!("Unknown parameter: {}", key)),
+                }
+            }
+        }
+        proposal_name.ok_or_else(|| "Usage: /start_vote name:<proposal_name>".to_string())
+    }
+
     fn parse_create_raffle(args: &[String]) -> Result<CreateRaffleArgs, String> {
         let mut proposal_name = None;
         let mut block_offset = None;
@@ -557,9 +774,184 @@ impl TelegramCommand {
     
 }
 
+/// A `/start_vote` ballot being filled in through inline keyboard buttons.
+/// Teams are addressed by index in callback data, which Telegram limits to 64 bytes.
+#[derive(Debug, Clone)]
+pub struct PendingVote {
+    counted_teams: Vec<String>,
+    uncounted_teams: Vec<String>,
+    votes: ProcessVoteArgs,
+}
+
+/// A button press on a `/start_vote` keyboard.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VoteCallback {
+    Choice(usize, VoteChoice),
+    Submit,
+    Cancel,
+}
+
+impl VoteCallback {
+    pub fn parse(data: &str) -> Option<Self> {
+        let rest = data.strip_prefix("vote:")?;
+        match rest {
+            "submit" => return Some(VoteCallback::Submit),
+            "cancel" => return Some(VoteCallback::Cancel),
+            _ => {},
+        }
+        let (index, choice) = rest.split_once(':')?;
+        let choice = match choice {
+            "yes" => VoteChoice::Yes,
+            "no" => VoteChoice::No,
+            "abstain" => VoteChoice::Abstain,
+            _ => return None,
+        };
+        Some(VoteCallback::Choice(index.parse().ok()?, choice))
+    }
+
+    fn data(&self) -> String {
+        match self {
+            VoteCallback::Choice(index, choice) => {
+                let choice = match choice {
+                    VoteChoice::Yes => "yes",
+                    VoteChoice::No => "no",
+                    VoteChoice::Abstain => "abstain",
+                };
+                format!("vote:{}:{}", index, choice)
+            },
+            VoteCallback::Submit => "vote:submit".to_string(),
+            VoteCallback::Cancel => "vote:cancel".to_string(),
+        }
+    }
+}
+
+impl PendingVote {
+    /// Starts a ballot for the teams seated by the proposal's raffle.
+    pub fn for_proposal(budget_system: &BudgetSystem, proposal_name: &str) -> Result<Self, String> {
+        let (counted_teams, uncounted_teams) = budget_system.raffle_seats_for_proposal(proposal_name)
+            .map_err(|e| format!("{}. Run /create_raffle name:{} first, then try again once it is drawn", e, proposal_name))?;
+
+        Ok(Self {
+            counted_teams,
+            uncounted_teams,
+            votes: ProcessVoteArgs {
+                name: proposal_name.to_string(),
+                counted_votes: HashMap::new(),
+                uncounted_votes: HashMap::new(),
+                vote_opened: None,
+                vote_closed: None,
+                deadline: None,
+            },
+        })
+    }
+
+    pub fn proposal_name(&self) -> &str {
+        &self.votes.name
+    }
+
+    /// Records a team's choice, replacing any earlier one.
+    pub fn record_choice(&mut self, team_index: usize, choice: VoteChoice) -> Result<(), String> {
+        if let Some(team) = self.counted_teams.get(team_index) {
+            self.votes.counted_votes.insert(team.clone(), choice);
+        } else if let Some(team) = self.uncounted_teams.get(team_index - self.counted_teams.len()) {
+            self.votes.uncounted_votes.insert(team.clone(), choice);
+        } else {
+            return Err(format!("No team at position {}", team_index));
+        }
+        Ok(())
+    }
+
+    fn choice_for(&self, team: &str) -> Option<&VoteChoice> {
+        self.votes.counted_votes.get(team).or_else(|| self.votes.uncounted_votes.get(team))
+    }
+
+    /// Plain-text ballot shown above the keyboard.
+    pub fn summary(&self) -> String {
+        let mut text = format!("Vote on {}\n", self.votes.name);
+        for (label, teams) in [("Counted", &self.counted_teams), ("Uncounted", &self.uncounted_teams)] {
+            if teams.is_empty() {
+                continue;
+            }
+            text.push_str(&format!("\n{}:\n", label));
+            for team in teams {
+                let choice = self.choice_for(team)
+                    .map(|c| format!("{:?}", c))
+                    .unwrap_or_else(|| "-".to_string());
+                text.push_str(&format!("  {}: {}\n", team, choice));
+            }
+        }
+        text.push_str("\nPick a choice for each team, then Submit. Teams without a choice are left out.");
+        text
+    }
+
+    /// One row of Yes/No/Abstain buttons per team, the current choice marked, then Submit and Cancel.
+    pub fn keyboard(&self) -> InlineKeyboardMarkup {
+        let mut rows: Vec<Vec<InlineKeyboardButton>> = self.counted_teams.iter()
+            .chain(self.uncounted_teams.iter())
+            .enumerate()
+            .map(|(index, team)| {
+                let current = self.choice_for(team);
+                [VoteChoice::Yes, VoteChoice::No, VoteChoice::Abstain].into_iter()
+                    .map(|choice| {
+                        let marker = if current == Some(&choice) { "✓ " } else { "" };
+                        let label = format!("{}{}: {:?}", marker, team, choice);
+                        InlineKeyboardButton::callback(label, VoteCallback::Choice(index, choice).data())
+                    })
+                    .collect()
+            })
+            .collect();
+        rows.push(vec![
+            InlineKeyboardButton::callback("Submit", VoteCallback::Submit.data()),
+            InlineKeyboardButton::callback("Cancel", VoteCallback::Cancel.data()),
+        ]);
+        InlineKeyboardMarkup::new(rows)
+    }
+
+    pub fn into_command(self) -> Command {
+        Command::CreateAndProcessVote {
+            proposal_name: self.votes.name,
+            counted_votes: self.votes.counted_votes,
+            uncounted_votes: self.votes.uncounted_votes,
+            delegations: HashMap::new(),
+            vote_opened: self.votes.vote_opened,
+            vote_closed: self.votes.vote_closed,
+            deadline: self.votes.deadline,
+        }
+    }
+}
+
+/// Formats a failed command for the chat, adding a hint for budget system
+/// errors the user can act on.
+pub(crate) fn command_failed(e: Box<dyn std::error::Error>) -> String {
+    let hint = match e.downcast_ref::<BudgetSystemError>() {
+        Some(BudgetSystemError::NoActiveEpoch) => Some("Activate an epoch first with /activate_epoch <name>"),
+        Some(BudgetSystemError::EpochAlreadyActive) => Some("Close the active epoch before activating another"),
+        Some(BudgetSystemError::EpochOverlap) => Some("Pick dates outside existing epochs"),
+        Some(BudgetSystemError::FutureDateNotAllowed) => Some("Use today's date or earlier"),
+        Some(BudgetSystemError::ActionableProposalsRemaining(_)) => Some("Close them first or use /force_close_epoch <name>"),
+        Some(BudgetSystemError::PaymentVerificationFailed(_)) => Some("Check the transaction hash, or add skip_verification:true for historical payments"),
+        _ => None,
+    };
+    match hint {
+        Some(hint) => format!("Command failed: {}. {}", e, hint),
+        None => format!("Command failed: {}", e),
+    }
+}
+
+/// Runs a command as if it was sent from the configured admin chat.
 pub async fn execute_command(
     telegram_cmd: TelegramCommand,
     budget_system: &mut BudgetSystem,
+) -> Result<String, String> {
+    let chat_id = budget_system.config().telegram.chat_id.clone();
+    execute_chat_command(telegram_cmd, budget_system, &chat_id).await
+}
+
+/// Runs a command sent from `chat_id`; dates without a `tz:` are resolved in that chat's timezone.
+pub async fn execute_chat_command(
+    telegram_cmd: TelegramCommand,
+    budget_system: &mut BudgetSystem,
+    chat_id: &str,
 ) -> Result<String, String> {
     match telegram_cmd {
         TelegramCommand::Help => {
@@ -573,7 +965,7 @@ pub async fn execute_command(
         },
 
         TelegramCommand::PrintEpochState => {
-            budget_system.execute_command(Command::PrintEpochState).await
+            budget_system.execute_command(Command::PrintEpochState { format: ReportFormat::Text }).await
             .map(|s| escape_markdown(&s))
             .map_err(|e| format!("Command failed: {}", e))
         },
@@ -599,13 +991,41 @@ pub async fn execute_command(
                 end_date
             }).await
             .map(|s| escape_markdown(&s))
-            .map_err(|e| format!("Command failed: {}", e))
+            .map_err(command_failed)
+        },
+
+        TelegramCommand::CloneEpoch { source_epoch, name, start_date, end_date } => {
+            let start_date = TelegramCommand::parse_start_date(&start_date)
+                .map_err(|e| format!("Invalid start date: {}", e))?;
+            let end_date = TelegramCommand::parse_end_date(&end_date)
+                .map_err(|e| format!("Invalid end date: {}", e))?;
+
+            budget_system.execute_command(Command::CloneEpoch {
+                source_epoch_name: source_epoch,
+                new_name: name,
+                start_date,
+                end_date
+            }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(command_failed)
         },
 
         TelegramCommand::ActivateEpoch { name } => {
             budget_system.execute_command(Command::ActivateEpoch { name }).await
             .map(|s| escape_markdown(&s))
-            .map_err(|e| format!("Command failed: {}", e))
+            .map_err(command_failed)
+        },
+
+        TelegramCommand::CloseEpoch { epoch_name } => {
+            budget_system.execute_command(Command::CloseEpoch { epoch_name: Some(epoch_name) }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(command_failed)
+        },
+
+        TelegramCommand::ForceCloseEpoch { epoch_name } => {
+            budget_system.execute_command(Command::ForceCloseEpoch { epoch_name: Some(epoch_name) }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(command_failed)
         },
 
         TelegramCommand::SetEpochReward { token, amount } => {
@@ -633,6 +1053,36 @@ pub async fn execute_command(
             .map_err(|e| format!("Command failed: {}", e))
         },
         
+        TelegramCommand::BulkAddTeams { args } => {
+            let mut teams = Vec::new();
+            let mut parse_failures = Vec::new();
+
+            for spec in args.split(';').map(str::trim).filter(|spec| !spec.is_empty()) {
+                let parsed = TelegramCommand::parse_command(spec)
+                    .and_then(|spec_args| TelegramCommand::parse_add_team(&spec_args));
+                match parsed {
+                    Ok(team_args) => teams.push(AddTeamDetails {
+                        name: team_args.name,
+                        representative: team_args.representative,
+                        trailing_monthly_revenue: team_args.revenue,
+                        address: team_args.address,
+                    }),
+                    Err(e) => parse_failures.push(format!("  Failed to parse '{}': {}", spec, e)),
+                }
+            }
+
+            if teams.is_empty() && parse_failures.is_empty() {
+                return Err("Usage: /bulk_add_teams name:<name> rep:<representative> [rev:<revenue>]; name:<name> rep:<representative> ...".to_string());
+            }
+
+            let mut output = budget_system.execute_command(Command::BulkAddTeams { teams }).await
+                .map_err(|e| format!("Command failed: {}", e))?;
+            if !parse_failures.is_empty() {
+                output.push_str(&format!("\n{} team spec(s) could not be parsed:\n{}", parse_failures.len(), parse_failures.join("\n")));
+            }
+            Ok(escape_markdown(&output))
+        },
+
         TelegramCommand::UpdateTeam { args } => {
             let args = TelegramCommand::parse_command(&args)
                 .map_err(|e| format!("Failed to parse team arguments: {}", e))?;
@@ -664,12 +1114,33 @@ pub async fn execute_command(
             .map_err(|e| format!("Command failed: {}", e))
         }
 
+        TelegramCommand::ArchiveTeam { team_name } => {
+            budget_system.execute_command(Command::ArchiveTeam { team_name }).await
+                .map(|s| escape_markdown(&s))
+                .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::UnarchiveTeam { team_name } => {
+            budget_system.execute_command(Command::UnarchiveTeam { team_name }).await
+                .map(|s| escape_markdown(&s))
+                .map_err(|e| format!("Command failed: {}", e))
+        },
+
         TelegramCommand::AddProposal { args } => {
             let args = TelegramCommand::parse_command(&args)
                 .map_err(|e| format!("Failed to parse proposal arguments: {}", e))?;
             
             let proposal_args = TelegramCommand::parse_add_proposal(&args)
                 .map_err(|e| format!("Failed to parse proposal details: {}", e))?;
+
+            let tz = TelegramCommand::parse_timezone(proposal_args.timezone.as_deref()
+                .unwrap_or(budget_system.config().telegram.timezone_for_chat(chat_id)))?;
+            let announced_at = proposal_args.announced_date
+                .map(|d| TelegramCommand::resolve_date_in_timezone(&d, tz))
+                .transpose()?;
+            let published_at = proposal_args.published_date
+                .map(|d| TelegramCommand::resolve_date_in_timezone(&d, tz))
+                .transpose()?;
             
             let budget_request_details = if proposal_args.team.is_some() || proposal_args.amounts.is_some() {
                 Some(BudgetRequestDetailsCommand {
@@ -680,8 +1151,12 @@ pub async fn execute_command(
                     end_date: proposal_args.end_date
                         .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
                     is_loan: proposal_args.is_loan,
-                    payment_address: proposal_args.payment_address
+                    payment_address: proposal_args.payment_address,
+                    category: proposal_args.category,
+                    ipfs_hash: None,
                 })
+            } else if proposal_args.category.is_some() {
+                return Err("category needs a budget request (team or amounts)".to_string());
             } else {
                 None
             };
@@ -690,14 +1165,12 @@ pub async fn execute_command(
                 title: proposal_args.title,
                 url: Some(proposal_args.url),
                 budget_request_details,
-                announced_at: proposal_args.announced_date
-                    .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
-                published_at: proposal_args.published_date
-                    .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+                announced_at,
+                published_at,
                 is_historical: None,
             }).await
             .map(|s| escape_markdown(&s))
-            .map_err(|e| format!("Command failed: {}", e))
+            .map_err(command_failed)
         },
         
         TelegramCommand::UpdateProposal { args } => {
@@ -717,6 +1190,8 @@ pub async fn execute_command(
                         .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
                     is_loan: update_args.is_loan,
                     payment_address: update_args.payment_address,
+                    category: None,
+                    ipfs_hash: None,
                 })
             } else {
                 None
@@ -734,6 +1209,7 @@ pub async fn execute_command(
                         .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
                     resolved_at: update_args.resolved_date
                         .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+                    category: update_args.category,
                 }
             }).await
             .map(|s| escape_markdown(&s))
@@ -752,6 +1228,70 @@ pub async fn execute_command(
                 resolution: parsed_args.resolution 
             }).await
             .map(|s| escape_markdown(&s))
+            .map_err(command_failed)
+        },
+
+        TelegramCommand::Deciders { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut proposal_name = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "name" => proposal_name = Some(value.to_string()),
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+            let proposal_name = proposal_name.ok_or("Usage: /deciders name:<proposal_name>")?;
+
+            budget_system.execute_command(Command::ShowDecidingTeams { proposal_name }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::PinDocument { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut proposal_name = None;
+            let mut ipfs_hash = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "proposal" => proposal_name = Some(value.to_string()),
+                        "hash" => ipfs_hash = Some(value.to_string()),
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+            let usage = "Usage: /pin_document proposal:<proposal_name> hash:<ipfs_hash>";
+            let proposal_name = proposal_name.ok_or(usage)?;
+            let ipfs_hash = ipfs_hash.ok_or(usage)?;
+
+            budget_system.execute_command(Command::PinProposalDocument { proposal_name, ipfs_hash }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::ShowRaffle { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut proposal_name = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "name" => proposal_name = Some(value.to_string()),
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+            let proposal_name = proposal_name.ok_or("Usage: /show_raffle name:<proposal_name>")?;
+
+            budget_system.execute_command(Command::ShowRaffle { proposal_name }).await
+            .map(|s| escape_markdown(&s))
             .map_err(|e| format!("Command failed: {}", e))
         },
 
@@ -766,13 +1306,21 @@ pub async fn execute_command(
                 proposal_name: parsed_args.name,
                 counted_votes: parsed_args.counted_votes,
                 uncounted_votes: parsed_args.uncounted_votes,
+                delegations: HashMap::new(),
                 vote_opened: parsed_args.vote_opened,
                 vote_closed: parsed_args.vote_closed,
+                deadline: parsed_args.deadline,
             }).await
             .map(|s| escape_markdown(&s))
             .map_err(|e| format!("Command failed: {}", e))
         }
 
+        TelegramCommand::StartVote { args } => {
+            let proposal_name = TelegramCommand::parse_start_vote(&args)?;
+            PendingVote::for_proposal(budget_system, &proposal_name)
+                .map(|vote| escape_markdown(&vote.summary()))
+        },
+
         TelegramCommand::CreateRaffle { args } => {
             let args = TelegramCommand::parse_command(&args)
                 .map_err(|e| format!("Failed to parse arguments: {}", e))?;
@@ -789,45 +1337,276 @@ pub async fn execute_command(
             .map_err(|e| format!("Command failed: {}", e))
         },
 
-        TelegramCommand::GenerateUnpaidReport { args } => {
-            let epoch_name = if args.is_empty() {
-                None
-            } else {
-                Some(args)
-            };
-        
-            let temp_dir = std::env::temp_dir();
-            let output_path = temp_dir.join(format!("unpaid_report_{}.json", Utc::now().timestamp()));
-            
-            // First generate the report file
-            budget_system.generate_unpaid_requests_report(
-                Some(output_path.to_str().unwrap()),
-                epoch_name.as_deref()
-            ).map_err(|e| e.to_string())?;
-        
-            // Read and format the JSON
-            let json_content = std::fs::read_to_string(&output_path)
-                .map_err(|e| format!("Failed to read report: {}", e))?;
-            
-            // Parse and re-serialize to ensure proper formatting
-            let json_value: serde_json::Value = serde_json::from_str(&json_content)
-                .map_err(|e| format!("Failed to parse JSON: {}", e))?;
-            let formatted_json = serde_json::to_string_pretty(&json_value)
-                .map_err(|e| format!("Failed to format JSON: {}", e))?;
-            
-            // Return the formatted message
-            Ok(format!("{}\n\n```json\n\n{}\n\n```", 
-                escape_markdown("Unpaid Requests Report:"),
-                formatted_json
-            ))
-            
+        TelegramCommand::GenerateUnpaidReport { args } => {
+            let epoch_name = if args.is_empty() {
+                None
+            } else {
+                Some(args)
+            };
+        
+            let temp_dir = std::env::temp_dir();
+            let output_path = temp_dir.join(format!("unpaid_report_{}.json", Utc::now().timestamp()));
+            
+            // First generate the report file
+            budget_system.generate_unpaid_requests_report(
+                Some(output_path.to_str().unwrap()),
+                epoch_name.as_deref()
+            ).map_err(|e| e.to_string())?;
+        
+            // Read and format the JSON
+            let json_content = std::fs::read_to_string(&output_path)
+                .map_err(|e| format!("Failed to read report: {}", e))?;
+            
+            // Parse and re-serialize to ensure proper formatting
+            let json_value: serde_json::Value = serde_json::from_str(&json_content)
+                .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+            let formatted_json = serde_json::to_string_pretty(&json_value)
+                .map_err(|e| format!("Failed to format JSON: {}", e))?;
+            
+            // Return the formatted message
+            Ok(format!("{}\n\n```json\n\n{}\n\n```", 
+                escape_markdown("Unpaid Requests Report:"),
+                formatted_json
+            ))
+            
+        },
+
+        TelegramCommand::EpochPayments { epoch_name } => {
+            budget_system.execute_command(Command::GenerateEpochPaymentsReport { 
+                epoch_name, 
+                output_path: None,
+                format: ReportFormat::Markdown,
+            }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::RaffleEligibility { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut excluded_teams = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key.to_lowercase().as_str() {
+                        "excluded" => {
+                            excluded_teams = Some(value.split(',')
+                                .map(|s| s.trim().to_string())
+                                .collect());
+                        },
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+
+            budget_system.execute_command(Command::PrintRaffleEligibility { excluded_teams }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::PrintTeamProposals { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut team_name = None;
+            let mut epoch_name = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "team" => team_name = Some(value.to_string()),
+                        "epoch" => epoch_name = Some(value.to_string()),
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+            let team_name = team_name.ok_or("Usage: /print_team_proposals team:<team_name> [epoch:<epoch_name>]")?;
+
+            budget_system.execute_command(Command::ListTeamProposals { team_name, epoch_name }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::ListProposals { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut status = Some(ProposalListFilter::Open);
+            let mut page = 1;
+            let mut epoch_name = None;
+            let mut team_name = None;
+            let mut unpaid_only = false;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "status" if value.eq_ignore_ascii_case("all") => status = None,
+                        "status" => status = Some(value.parse()?),
+                        "page" => page = value.parse().map_err(|_| format!("Invalid page: {}", value))?,
+                        "epoch" => epoch_name = Some(value.to_string()),
+                        "team" => team_name = Some(value.to_string()),
+                        "unpaid" => unpaid_only = value.parse().map_err(|_| format!("Invalid value for unpaid: {}", value))?,
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+
+            let mut output = budget_system.execute_command(Command::ListProposals {
+                epoch_name: epoch_name.clone(),
+                status,
+                team: team_name.clone(),
+                unpaid_only,
+                page: Some(page),
+            }).await
+                .map(|s| escape_markdown(&s))
+                .map_err(|e| format!("Command failed: {}", e))?;
+
+            let epoch_id = match &epoch_name {
+                Some(name) => budget_system.get_epoch_id_by_name(name),
+                None => budget_system.state().current_epoch(),
+            };
+            let team_id = team_name.as_deref().and_then(|name| budget_system.get_team_id_by_name(name));
+            let (_, total) = budget_system.list_proposals_paginated(epoch_id, status, team_id, unpaid_only, page, PROPOSAL_PAGE_SIZE);
+            if page < total.div_ceil(PROPOSAL_PAGE_SIZE) {
+                let mut next = String::from("/list_proposals");
+                match status {
+                    Some(ProposalListFilter::Open) => {},
+                    Some(filter) => next.push_str(&format!(" status:{}", format!("{:?}", filter).to_lowercase())),
+                    None => next.push_str(" status:all"),
+                }
+                if let Some(name) = &team_name {
+                    next.push_str(&format!(" team:{}", name));
+                }
+                if unpaid_only {
+                    next.push_str(" unpaid:true");
+                }
+                if let Some(name) = &epoch_name {
+                    next.push_str(&format!(" epoch:{}", name));
+                }
+                next.push_str(&format!(" page:{}", page + 1));
+                output.push_str(&escape_markdown(&format!(" — use {}", next)));
+            }
+            Ok(output)
+        },
+
+        TelegramCommand::TeamPaymentHistory { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut team_name = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "name" => team_name = Some(value.to_string()),
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+            let team_name = team_name.ok_or("Usage: /team_payment_history name:<team_name>")?;
+
+            budget_system.execute_command(Command::PrintTeamPaymentHistory { team_name }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(command_failed)
+        },
+
+        TelegramCommand::ListEpochs { args } => {
+            let include_closed = match args.trim() {
+                "" => false,
+                "all" => true,
+                other => return Err(format!("Unknown argument: {}. Usage: /list_epochs [all]", other)),
+            };
+
+            budget_system.execute_command(Command::ListEpochs { include_closed }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(command_failed)
+        },
+
+        TelegramCommand::ListTeams { filter } => {
+            let args = TelegramCommand::parse_command(&filter)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut status_filter = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "status" => status_filter = Some(value.to_string()),
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+
+            budget_system.execute_command(Command::ListTeams { status_filter }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(command_failed)
+        },
+
+        TelegramCommand::PingAbsentVoters { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut proposal_name = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "name" => proposal_name = Some(value.to_string()),
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+            let proposal_name = proposal_name.ok_or("Usage: /ping_absent_voters name:<proposal_name>")?;
+
+            budget_system.execute_command(Command::PingAbsentVoters { proposal_name }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::PreviewEpochReport { epoch_name } => {
+            budget_system.execute_command(Command::PreviewEndOfEpochReport { epoch_name }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::OverduePayments { args } => {
+            let days = if args.trim().is_empty() {
+                budget_system.config().telegram.overdue_payment_days
+            } else {
+                args.trim().parse::<u64>()
+                    .map_err(|e| format!("Invalid number of days: {}", e))?
+            };
+
+            budget_system.execute_command(Command::PrintOverduePayments { days }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::PendingPayments { args } => {
+            let args = TelegramCommand::parse_command(&args)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+            let mut epoch_name = None;
+            let mut min_days_pending = None;
+            for arg in args {
+                if let Some((key, value)) = arg.split_once(':') {
+                    match key {
+                        "epoch" => epoch_name = Some(value.to_string()),
+                        "min_days" => min_days_pending = Some(value.parse::<u64>()
+                            .map_err(|e| format!("Invalid number of days: {}", e))?),
+                        _ => return Err(format!("Unknown parameter: {}", key)),
+                    }
+                }
+            }
+
+            budget_system.execute_command(Command::ListPendingPayments { epoch_name, min_days_pending }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
+        },
+
+        TelegramCommand::LoanReport => {
+            budget_system.execute_command(Command::GenerateLoanReport { output_path: None }).await
+            .map(|s| escape_markdown(&s))
+            .map_err(|e| format!("Command failed: {}", e))
         },
 
-        TelegramCommand::EpochPayments { epoch_name } => {
-            budget_system.execute_command(Command::GenerateEpochPaymentsReport { 
-                epoch_name, 
-                output_path: None 
-            }).await
+        TelegramCommand::VerifyPoints => {
+            budget_system.execute_command(Command::VerifyPoints).await
             .map(|s| escape_markdown(&s))
             .map_err(|e| format!("Command failed: {}", e))
         },
@@ -839,6 +1618,7 @@ pub async fn execute_command(
             let mut tx = None;
             let mut date = None;
             let mut proposals = None;
+            let mut skip_verification = false;
         
             for arg in args {
                 if let Some((key, value)) = arg.split_once(':') {
@@ -849,6 +1629,8 @@ pub async fn execute_command(
                         "proposals" => proposals = Some(value.split(',')
                             .map(String::from)
                             .collect::<Vec<String>>()),
+                        "skip_verification" => skip_verification = value.parse::<bool>()
+                            .map_err(|_| format!("Invalid skip_verification value: {}", value))?,
                         _ => return Err(format!("Unknown parameter: {}", key)),
                     }
                 }
@@ -861,10 +1643,11 @@ pub async fn execute_command(
             budget_system.execute_command(Command::LogPayment {
                 payment_tx: tx,
                 payment_date: date,
-                proposal_names: proposals 
+                proposal_names: proposals,
+                skip_verification,
             }).await
             .map(|s| escape_markdown(&s))
-            .map_err(|e| format!("Command failed: {}", e))
+            .map_err(command_failed)
         }
     }
 }
@@ -887,16 +1670,38 @@ mod tests {
         let config = crate::app_config::AppConfig {
             state_file: temp_dir.path().join("test_state.json").to_str().unwrap().to_string(),
             ipc_path: "/tmp/test_reth.ipc".to_string(),
+            rpc_url: None,
             future_block_offset: 10,
+            eth_retry: crate::app_config::RetryConfig { max_attempts: 1, base_delay_ms: 0, max_delay_ms: 0, jitter_ms: 0 },
             script_file: "test_script.json".to_string(),
             default_total_counted_seats: 7,
             default_max_earner_seats: 5,
+            revenue_window_months: 3,
             default_qualified_majority_threshold: 0.7,
+            default_quorum: 0.0,
             counted_vote_points: 5,
             uncounted_vote_points: 2,
+            abstain_points: 5,
+            state_file_warn_bytes: 10 * 1024 * 1024,
+            state_backup_count: 20,
+            require_reviewer_sign_off: false,
+            allow_vote_changes: false,
+            vote_change_window_hours: 24,
+            allow_fuzzy_match: true,
+            allow_similar_proposals: true,
+            discord_webhook_url: None,
+            epoch_budget_caps: HashMap::new(),
+            token_addresses: HashMap::new(),
+            safe_chain_id: 1,
             telegram: crate::app_config::TelegramConfig {
                 chat_id: "test_chat_id".to_string(),
                 token: "test_token".to_string(),
+                timezone: "UTC".to_string(),
+                chat_timezones: HashMap::from([("kiritimati_chat".to_string(), "Pacific/Kiritimati".to_string())]),
+                overdue_payment_days: 30,
+                allow_absent_voter_pings: false,
+                allowed_chat_ids: Vec::new(),
+                public_read_only_commands: false,
             },
         };
         let ethereum_service = Arc::new(MockEthereumService::new());
@@ -1005,6 +1810,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_clone_epoch_command() {
+        let cmd = TelegramCommand::parse("/clone_epoch Q1 Q2 2024-04-01 2024-06-30", "bot_name").unwrap();
+        match cmd {
+            TelegramCommand::CloneEpoch { source_epoch, name, start_date, end_date } => {
+                assert_eq!(source_epoch, "Q1");
+                assert_eq!(name, "Q2");
+                assert_eq!(start_date, "2024-04-01");
+                assert_eq!(end_date, "2024-06-30");
+            },
+            _ => panic!("Wrong command parsed")
+        }
+        assert!(TelegramCommand::parse("/clone_epoch Q1 Q2 2024-04-01", "bot_name").is_err());
+    }
+
     #[test]
     fn test_parse_activate_epoch_command() {
         let cmd = TelegramCommand::parse("/activate_epoch TestEpoch", "bot_name").unwrap();
@@ -1209,6 +2029,427 @@ mod tests {
         assert!(response.contains("Updated team"));
     }
 
+    #[tokio::test]
+    async fn test_bulk_add_teams_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let command = TelegramCommand::BulkAddTeams {
+            args: "name:Team A rep:Alice; name:Team B rep:Bob rev:1000,2000,3000; name:Team C rep:Carol addy:0xnotanaddress; rep:NoName".to_string()
+        };
+
+        let response = execute_command(command, &mut budget_system).await.unwrap();
+        assert!(response.contains("2 added, 1 failed"));
+        assert!(response.contains("Added team: Team A"));
+        assert!(response.contains("Added team: Team B"));
+        assert!(response.contains("Failed to add team Team C"));
+        assert!(response.contains("1 team spec\\(s\\) could not be parsed"));
+        assert!(budget_system.get_team_id_by_name("Team B").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deciders_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let result = execute_command(TelegramCommand::Deciders {
+            args: "name:Missing Proposal".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Proposal not found"));
+
+        let result = execute_command(TelegramCommand::Deciders {
+            args: "".to_string()
+        }, &mut budget_system).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pin_document_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        let start_date = Utc::now();
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, start_date + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.add_proposal("Proposal A".to_string(), None, None, None, None, None, None).unwrap();
+
+        let command = TelegramCommand::parse(
+            "/pin_document proposal:Proposal A hash:QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "bot_name"
+        ).unwrap();
+        let response = execute_command(command, &mut budget_system).await.unwrap();
+        assert!(response.contains("Pinned document QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"));
+
+        let result = execute_command(TelegramCommand::PinDocument {
+            args: "proposal:Proposal A hash:not-a-cid".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("IPFS hash must be"));
+
+        let result = execute_command(TelegramCommand::PinDocument {
+            args: "proposal:Proposal A".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Usage: /pin_document"));
+    }
+
+    #[tokio::test]
+    async fn test_show_raffle_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        let start_date = Utc::now();
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, start_date + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.add_proposal("Proposal A".to_string(), None, None, None, None, None, None).unwrap();
+
+        let response = execute_command(TelegramCommand::ShowRaffle {
+            args: "name:Proposal A".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert_eq!(response, "No raffle was conducted for proposal 'Proposal A'\\.\n");
+
+        let result = execute_command(TelegramCommand::ShowRaffle {
+            args: "".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Usage: /show_raffle"));
+    }
+
+    #[test]
+    fn test_vote_callback_round_trip() {
+        for callback in [
+            VoteCallback::Choice(3, VoteChoice::Abstain),
+            VoteCallback::Choice(0, VoteChoice::Yes),
+            VoteCallback::Submit,
+            VoteCallback::Cancel,
+        ] {
+            assert_eq!(VoteCallback::parse(&callback.data()), Some(callback));
+        }
+        assert_eq!(VoteCallback::parse("vote:1:maybe"), None);
+        assert_eq!(VoteCallback::parse("other:submit"), None);
+    }
+
+    #[tokio::test]
+    async fn test_start_vote_ballot() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let start_date = Utc::now();
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, start_date + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![4000]), None).unwrap();
+        budget_system.create_team("Team B".to_string(), "Rep".to_string(), None, None).unwrap();
+        budget_system.add_proposal(
+            "Test Proposal".to_string(), None, None,
+            Some(Utc::now().date_naive()), Some(Utc::now().date_naive()), None,
+            None,
+        ).unwrap();
+
+        let result = execute_command(TelegramCommand::StartVote {
+            args: "name:Test Proposal".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("/create_raffle name:Test Proposal"));
+
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
+        budget_system.finalize_raffle(raffle_id, 12345, 12355, "mock_randomness".to_string()).await.unwrap();
+
+        let mut vote = PendingVote::for_proposal(&budget_system, "Test Proposal").unwrap();
+        let keyboard = vote.keyboard();
+        assert_eq!(keyboard.inline_keyboard.len(), 3);
+        assert_eq!(keyboard.inline_keyboard[2].len(), 2);
+
+        vote.record_choice(0, VoteChoice::No).unwrap();
+        vote.record_choice(0, VoteChoice::Yes).unwrap();
+        vote.record_choice(1, VoteChoice::Abstain).unwrap();
+        assert!(vote.record_choice(2, VoteChoice::Yes).is_err());
+        assert!(vote.keyboard().inline_keyboard[0][0].text.starts_with("✓ "));
+
+        let summary = vote.summary();
+        assert!(summary.contains("Team A: Yes"));
+        assert!(summary.contains("Team B: Abstain"));
+
+        match vote.into_command() {
+            Command::CreateAndProcessVote { proposal_name, counted_votes, uncounted_votes, .. } => {
+                assert_eq!(proposal_name, "Test Proposal");
+                assert_eq!(counted_votes.len() + uncounted_votes.len(), 2);
+                assert!(counted_votes.values().chain(uncounted_votes.values()).any(|c| *c == VoteChoice::Yes));
+            },
+            other => panic!("Unexpected command: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_proposals_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let start_date = Utc::now();
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, start_date + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        for i in 1..=11 {
+            budget_system.add_proposal(format!("Proposal {:02}", i), None, None, None, None, None, None).unwrap();
+        }
+
+        let output = execute_command(TelegramCommand::ListProposals { args: String::new() }, &mut budget_system).await.unwrap();
+        assert!(output.contains("Open proposals in epoch Test Epoch \\(11 total\\)"));
+        assert!(output.ends_with("Page 1 of 2 — use /list\\_proposals page:2"));
+
+        let output = execute_command(TelegramCommand::ListProposals {
+            args: "status:all epoch:Test Epoch page:2".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(output.contains("Proposal 11"));
+        assert!(output.ends_with("Page 2 of 2"));
+
+        let result = execute_command(TelegramCommand::ListProposals { args: "status:pending".to_string() }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Invalid status filter"));
+
+        let output = execute_command(TelegramCommand::ListProposals { args: "status:unpaid".to_string() }, &mut budget_system).await.unwrap();
+        assert!(output.contains("No unpaid proposals in epoch Test Epoch"));
+        let result = execute_command(TelegramCommand::ListProposals { args: "team:Nobody".to_string() }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Team not found: Nobody"));
+    }
+
+    #[tokio::test]
+    async fn test_raffle_eligibility_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let start_date = Utc::now();
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, start_date + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![4000]), None).unwrap();
+        budget_system.create_team("Team B".to_string(), "Rep".to_string(), None, None).unwrap();
+
+        let response = execute_command(TelegramCommand::RaffleEligibility {
+            args: "excluded:Team B".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("Team A: 2 ticket\\(s\\)"));
+        assert!(response.contains("Excluded: Team B"));
+
+        let result = execute_command(TelegramCommand::RaffleEligibility {
+            args: "team:Team B".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Unknown parameter"));
+    }
+
+    #[tokio::test]
+    async fn test_budget_system_errors_include_hints() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let result = execute_command(TelegramCommand::AddProposal {
+            args: "title:Test url:https://example.com".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("No active epoch. Activate an epoch first"));
+
+        let start_date = Utc::now();
+        let epoch_id = budget_system.create_epoch("First", start_date, start_date + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        let second = start_date + chrono::Duration::days(60);
+        budget_system.create_epoch("Second", second, second + chrono::Duration::days(30)).unwrap();
+
+        let result = execute_command(TelegramCommand::ActivateEpoch {
+            name: "Second".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Another epoch is currently active. Close the active epoch"));
+    }
+
+    #[tokio::test]
+    async fn test_print_team_proposals_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), None, None).unwrap();
+
+        let response = execute_command(TelegramCommand::PrintTeamProposals {
+            args: "team:Team A".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert_eq!(response, "No proposals found for team Team A");
+
+        let result = execute_command(TelegramCommand::PrintTeamProposals {
+            args: "epoch:Epoch 1".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Usage"));
+    }
+
+    #[tokio::test]
+    async fn test_close_epoch_commands() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        let now = Utc::now();
+        let epoch_id = budget_system.create_epoch("Q1", now, now + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.add_proposal("Proposal B".to_string(), None, None, None, None, None, None).unwrap();
+        budget_system.add_proposal("Proposal A".to_string(), None, None, None, None, None, None).unwrap();
+
+        assert!(matches!(
+            TelegramCommand::parse("/close_epoch Q1", "bot_name").unwrap(),
+            TelegramCommand::CloseEpoch { epoch_name } if epoch_name == "Q1"
+        ));
+
+        let result = execute_command(TelegramCommand::CloseEpoch {
+            epoch_name: "Q1".to_string()
+        }, &mut budget_system).await;
+        let error = result.unwrap_err();
+        assert!(error.contains("2 actionable proposals remaining (Proposal A, Proposal B)"));
+        assert!(error.contains("/force_close_epoch"));
+        assert!(!budget_system.get_epoch(&epoch_id).unwrap().is_closed());
+
+        let response = execute_command(TelegramCommand::ForceCloseEpoch {
+            epoch_name: "Q1".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("Retracted proposals: Proposal A, Proposal B"));
+        assert!(response.contains("Rewards: N/A"));
+        assert!(budget_system.get_epoch(&epoch_id).unwrap().is_closed());
+        assert!(budget_system.state().proposals().values()
+            .all(|p| p.resolution() == Some(Resolution::Retracted)));
+    }
+
+    #[tokio::test]
+    async fn test_list_epochs_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        let now = Utc::now();
+        let epoch_id = budget_system.create_epoch("Q1_2024", now, now + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+
+        assert!(matches!(
+            TelegramCommand::parse("/list_epochs", "bot_name").unwrap(),
+            TelegramCommand::ListEpochs { args } if args.is_empty()
+        ));
+
+        let response = execute_command(TelegramCommand::ListEpochs {
+            args: "all".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("Q1\\_2024"));
+        assert!(response.contains("\\| ▶ \\|"));
+
+        let result = execute_command(TelegramCommand::ListEpochs {
+            args: "closed".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Usage"));
+    }
+
+    #[tokio::test]
+    async fn test_list_teams_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team B".to_string(), "Rep".to_string(), None, None).unwrap();
+
+        let response = execute_command(TelegramCommand::ListTeams {
+            filter: "status:earner".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("Team A"));
+        assert!(!response.contains("Team B"));
+
+        let response = execute_command(TelegramCommand::ListTeams {
+            filter: String::new()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("Team B"));
+
+        let result = execute_command(TelegramCommand::ListTeams {
+            filter: "status:retired".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Invalid team status"));
+    }
+
+    #[tokio::test]
+    async fn test_archive_team_commands() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), None, None).unwrap();
+
+        let command = TelegramCommand::parse("/archive_team Team A", "bot_name").unwrap();
+        let response = execute_command(command, &mut budget_system).await.unwrap();
+        assert_eq!(response, "Archived team: Team A");
+
+        let response = execute_command(TelegramCommand::ListTeams {
+            filter: "status:archived".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("Team A"));
+
+        let response = execute_command(TelegramCommand::UnarchiveTeam {
+            team_name: "Team A".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert_eq!(response, "Unarchived team: Team A");
+
+        let result = execute_command(TelegramCommand::UnarchiveTeam {
+            team_name: "Team A".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Team is not archived"));
+    }
+
+    #[tokio::test]
+    async fn test_ping_absent_voters_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let result = execute_command(TelegramCommand::PingAbsentVoters {
+            args: "name:Some Proposal".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("disabled"));
+
+        let result = execute_command(TelegramCommand::PingAbsentVoters {
+            args: String::new()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Usage"));
+    }
+
+    #[tokio::test]
+    async fn test_overdue_payments_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let response = execute_command(TelegramCommand::OverduePayments {
+            args: "".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("No overdue payments"));
+        assert!(response.contains("30 days"));
+
+        let result = execute_command(TelegramCommand::OverduePayments {
+            args: "soon".to_string()
+        }, &mut budget_system).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pending_payments_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let response = execute_command(TelegramCommand::PendingPayments {
+            args: "min_days:7".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("No pending payments"));
+
+        let result = execute_command(TelegramCommand::PendingPayments {
+            args: "min_days:soon".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Invalid number of days"));
+
+        let result = execute_command(TelegramCommand::PendingPayments {
+            args: "epoch:Missing".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Epoch not found"));
+    }
+
+    #[tokio::test]
+    async fn test_loan_report_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let response = execute_command(TelegramCommand::LoanReport, &mut budget_system).await.unwrap();
+        assert!(response.contains("Loan Tracking Report"));
+        assert!(response.contains("No outstanding loans"));
+        assert!(TelegramCommand::LoanReport.is_read_only());
+    }
+
+    #[tokio::test]
+    async fn test_verify_points_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let response = execute_command(TelegramCommand::VerifyPoints, &mut budget_system).await.unwrap();
+        assert_eq!(response, "All points consistent");
+        assert!(TelegramCommand::VerifyPoints.is_read_only());
+    }
+
+    #[tokio::test]
+    async fn test_preview_epoch_report_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let start_date = Utc::now();
+        let end_date = start_date + chrono::Duration::days(30);
+        budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+
+        let response = execute_command(TelegramCommand::PreviewEpochReport {
+            epoch_name: "Test Epoch".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert!(response.contains("PREVIEW — NOT FINAL"));
+
+        let result = execute_command(TelegramCommand::PreviewEpochReport {
+            epoch_name: "Missing".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Epoch not found"));
+    }
+
     #[tokio::test]
     async fn test_add_team_command_invalid_args() {
         let (mut budget_system, _temp_dir) = create_test_budget_system().await;
@@ -1261,6 +2502,95 @@ mod tests {
         assert!(response.contains("Added proposal: Test Proposal"));
     }
 
+    #[tokio::test]
+    async fn test_proposal_category_args() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        let start_date = Utc::now();
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, start_date + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+
+        let result = execute_command(TelegramCommand::AddProposal {
+            args: "title:Proposal A url:https://test.com category:Research".to_string()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("category needs a budget request"));
+
+        execute_command(TelegramCommand::AddProposal {
+            args: "title:Proposal A url:https://test.com amounts:ETH:10 category:Research".to_string()
+        }, &mut budget_system).await.unwrap();
+        let proposal_id = budget_system.get_proposal_id_by_name("Proposal A").unwrap();
+        assert_eq!(budget_system.get_proposal(&proposal_id).unwrap().category(), Some(&ProposalCategory::Research));
+
+        execute_command(TelegramCommand::UpdateProposal {
+            args: "proposal:Proposal A category:grant".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert_eq!(budget_system.get_proposal(&proposal_id).unwrap().category(), Some(&ProposalCategory::Grant));
+    }
+
+    #[tokio::test]
+    async fn test_add_proposal_command_with_timezone() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let start_date = Utc::now();
+        let end_date = start_date + chrono::Duration::days(30);
+        budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        budget_system.activate_epoch(budget_system.get_epoch_id_by_name("Test Epoch").unwrap()).unwrap();
+
+        let command = TelegramCommand::AddProposal {
+            args: "title:Tz Proposal url:https://test.com announced:today published:2024-01-02 tz:Pacific/Kiritimati".to_string()
+        };
+        execute_command(command, &mut budget_system).await.unwrap();
+
+        let proposal_id = budget_system.get_proposal_id_by_name("Tz Proposal").unwrap();
+        let proposal = budget_system.get_proposal(&proposal_id).unwrap();
+        let kiritimati_today = Utc::now().with_timezone(&chrono_tz::Pacific::Kiritimati).date_naive();
+        assert_eq!(proposal.announced_at(), Some(kiritimati_today));
+        assert_eq!(proposal.published_at(), NaiveDate::from_ymd_opt(2024, 1, 2));
+
+        let command = TelegramCommand::AddProposal {
+            args: "title:Bad Tz url:https://test.com announced:today tz:Mars/Olympus".to_string()
+        };
+        let result = execute_command(command, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Invalid timezone"));
+    }
+
+    #[tokio::test]
+    async fn test_add_proposal_command_uses_chat_timezone() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+
+        let start_date = Utc::now();
+        let end_date = start_date + chrono::Duration::days(30);
+        budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        budget_system.activate_epoch(budget_system.get_epoch_id_by_name("Test Epoch").unwrap()).unwrap();
+
+        let command = TelegramCommand::AddProposal {
+            args: "title:Chat Tz Proposal url:https://test.com announced:today".to_string()
+        };
+        execute_chat_command(command, &mut budget_system, "kiritimati_chat").await.unwrap();
+
+        let command = TelegramCommand::AddProposal {
+            args: "title:Default Tz Proposal url:https://test.com announced:today".to_string()
+        };
+        execute_chat_command(command, &mut budget_system, "other_chat").await.unwrap();
+
+        let kiritimati_today = Utc::now().with_timezone(&chrono_tz::Pacific::Kiritimati).date_naive();
+        let proposal_id = budget_system.get_proposal_id_by_name("Chat Tz Proposal").unwrap();
+        assert_eq!(budget_system.get_proposal(&proposal_id).unwrap().announced_at(), Some(kiritimati_today));
+
+        let proposal_id = budget_system.get_proposal_id_by_name("Default Tz Proposal").unwrap();
+        assert_eq!(budget_system.get_proposal(&proposal_id).unwrap().announced_at(), Some(Utc::now().date_naive()));
+    }
+
+    #[test]
+    fn test_resolve_date_in_timezone() {
+        let utc_today = TelegramCommand::resolve_date_in_timezone("today", Tz::UTC).unwrap();
+        assert_eq!(utc_today, Utc::now().date_naive());
+        assert_eq!(
+            TelegramCommand::resolve_date_in_timezone("2024-03-01", Tz::Asia__Tokyo).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+        );
+        assert!(TelegramCommand::resolve_date_in_timezone("03/01/2024", Tz::UTC).is_err());
+    }
+
     #[tokio::test]
     async fn test_update_proposal_command() {
         let (mut budget_system, _temp_dir) = create_test_budget_system().await;
@@ -1385,6 +2715,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_process_vote_deadline() {
+        let input = "name:Test Proposal counted:TeamA:Yes deadline:2024-10-20";
+        let args = TelegramCommand::parse_command(input).unwrap();
+        let result = TelegramCommand::parse_process_vote(&args).unwrap();
+        assert_eq!(result.deadline.unwrap().to_rfc3339(), "2024-10-20T23:59:59+00:00");
+
+        let args = TelegramCommand::parse_command("name:Test Proposal deadline:soon").unwrap();
+        assert!(TelegramCommand::parse_process_vote(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_process_vote_with_abstain() {
+        let input = "name:Test Proposal counted:TeamA:Yes,TeamB:Abstain uncounted:TeamC:abstain";
+        let args = TelegramCommand::parse_command(input).unwrap();
+        let result = TelegramCommand::parse_process_vote(&args).unwrap();
+        assert_eq!(result.counted_votes.get("TeamB"), Some(&VoteChoice::Abstain));
+        assert_eq!(result.uncounted_votes.get("TeamC"), Some(&VoteChoice::Abstain));
+    }
+
     #[test]
     fn test_invalid_vote_format() {
         let input = "name:Test Proposal counted:TeamA:maybe";
@@ -1673,6 +3023,7 @@ mod tests {
             Some(Utc::now().date_naive()),
             Some(Utc::now().date_naive()),
             None,
+            None,
         ).unwrap();
 
         budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
@@ -1722,6 +3073,7 @@ mod tests {
             Some(Utc::now().date_naive()),
             Some(Utc::now().date_naive()),
             None,
+            None,
         ).unwrap();
 
         // Approve the proposal
@@ -1777,5 +3129,24 @@ mod tests {
         assert!(result.unwrap_err().contains("Invalid date"));
     }
 
-}
+    #[tokio::test]
+    async fn test_team_payment_history_command() {
+        let (mut budget_system, _temp_dir) = create_test_budget_system().await;
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), None, None).unwrap();
+
+        assert!(matches!(
+            TelegramCommand::parse("/team_payment_history name:Team A", "bot").unwrap(),
+            TelegramCommand::TeamPaymentHistory { args } if args == "name:Team A"
+        ));
+
+        let response = execute_command(TelegramCommand::TeamPaymentHistory {
+            args: "name:Team A".to_string()
+        }, &mut budget_system).await.unwrap();
+        assert_eq!(response, escape_markdown("No approved budget requests found for team Team A"));
 
+        let result = execute_command(TelegramCommand::TeamPaymentHistory {
+            args: String::new()
+        }, &mut budget_system).await;
+        assert!(result.unwrap_err().contains("Usage"));
+    }
+}
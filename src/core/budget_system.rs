@@ -2,25 +2,33 @@
 
 use crate::core::state::BudgetSystemState;
 use crate::core::models::{
-    Team, TeamStatus, Epoch, EpochStatus, TeamReward,
-    Proposal, ProposalStatus, Resolution, BudgetRequestDetails,
-    Raffle, RaffleConfig, RaffleResult, RaffleTicket,
-    Vote, VoteType, VoteChoice, VoteCount, VoteParticipation, VoteResult, get_id_by_name
+    Team, TeamStatus, Epoch, EpochStatus, EpochReward, TeamReward, EpochGovernanceConfig,
+    Proposal, ProposalStatus, ProposalCategory, ProposalListFilter, ProposalRevision, Resolution, BudgetRequestDetails, LoanTerms,
+    Raffle, RaffleConfig, RaffleResult, RaffleTicket, SeatOutcome, ProposalTemplate, ProposalOverrides, RecurrenceConfig,
+    Vote, VoteType, VoteChoice, VoteCount, VoteParticipation, VoteResult, VoteChangeRequest, get_id_by_name, find_closest_name, suggest_names, suggestion_max_distance
 };
 use crate::core::progress::raffle::{RaffleProgress, RaffleCreationError};
-use crate::core::models::common::{NameMatches, UnpaidRequest, UnpaidRequestsReport, TeamPayment, EpochPaymentsReport};
-use crate::services::ethereum::EthereumServiceTrait;
+use crate::core::models::common::{NameMatches, UnpaidRequest, UnpaidRequestsReport, TeamPayment, EpochPaymentsReport, BudgetFlowData, TeamAbsenceInfo, OverduePayment, StateSizeEstimate, BurndownPoint,
+    EpochInfographicData, TeamRanking, ResolutionCounts, VoteParticipationPoint, TeamBudgetAllocation,
+    EpochStateReport, OpenProposalSummary, PointMismatch, TreasuryReport, TreasuryTokenFlow, StateDiff, WaitingTimeStats, ReportSections, ImportSummary, ImportError,
+    TeamPaymentRecord, PendingPayment, RaffleVerification};
+use crate::services::ethereum::{EthereumServiceTrait, RetryEvent};
+use crate::services::ipfs::{ipfs_gateway_url, validate_ipfs_hash};
+use crate::services::discord::{DiscordServiceTrait, DiscordWebhook, DiscordPayload, DiscordEmbed, COLOR_GREEN, COLOR_RED, COLOR_BLUE};
+use crate::services::export::{self, ExportFormat};
+use crate::services::safe::{SafeBatch, SafeTransaction};
 use crate::commands::common::{ 
-    UpdateProposalDetails, UpdateTeamDetails, Command, CommandExecutor
+    UpdateProposalDetails, UpdateTeamDetails, Command, CommandExecutor, ReportFormat, ExportKind
 };
 use crate::app_config::AppConfig;
 use crate::core::file_system::FileSystem;
 use crate::escape_markdown;
 
 use chrono::{DateTime, NaiveDate, Utc, TimeZone};
+use ethers::types::Address;
 use uuid::Uuid;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     error::Error, fmt,
     fs,
     io::Write,
@@ -28,27 +36,141 @@ use std::{
     str,
     sync::Arc,
 };
-use log::debug;
+use log::{debug, info};
 use async_trait::async_trait;
 use tokio::time::Duration;
 use futures::{pin_mut, Stream, StreamExt};
-use async_stream::try_stream;
+use async_stream::{stream, try_stream};
+use tokio::sync::broadcast;
 
 
+/// Payments pending longer than this many days are flagged in the pending payments list.
+const PENDING_PAYMENT_WARNING_DAYS: i64 = 30;
+
+/// Most names a failed name lookup suggests.
+const MAX_NAME_SUGGESTIONS: usize = 3;
+
+/// Jaro-Winkler similarity at which a new proposal title counts as a near-duplicate.
+const SIMILAR_PROPOSAL_THRESHOLD: f64 = 0.85;
+
+/// Proposals per page in `Command::ListProposals`.
+pub const PROPOSAL_PAGE_SIZE: usize = 10;
+
+const TEAM_SUMMARY_HEADERS: [&str; 7] = [
+    "Team Name", "Status", "Counted Votes", "Uncounted Votes", "Total Points", "% of Total Points", "Reward Amount",
+];
+
+/// Cells of an end-of-epoch proposal table row. Amounts stay per token so they
+/// can be joined for Markdown or split into columns for CSV; `None` when the
+/// proposal has no budget request.
+struct ProposalTableRow {
+    leading: Vec<String>,
+    amounts: Option<Vec<(String, f64)>>,
+    trailing: Vec<String>,
+    report_link: String,
+}
+
+struct TeamSummaryRow {
+    name: String,
+    status: String,
+    counted_votes: u32,
+    uncounted_votes: u32,
+    points: u32,
+    percentage: f64,
+    rewards: Option<Vec<(String, f64)>>,
+}
+
+/// One step of an Ethereum call observed by `BudgetSystem::observe_eth_call`.
+enum EthCallStep<T> {
+    Retrying(RetryEvent),
+    Done(Result<T, String>),
+}
+
 pub struct BudgetSystem {
     state: BudgetSystemState,
     ethereum_service: Arc<dyn EthereumServiceTrait>,
+    discord_service: Option<Arc<dyn DiscordServiceTrait>>,
     config: AppConfig,
+    /// State as it was when a dry run started; set while one is in progress.
+    dry_run_snapshot: Option<BudgetSystemState>,
 }
 
 
-// TODO: fix this when we tackle errors - it's a hack for the sake of Command::PrintPointReport
-#[derive(Debug)]
-struct BudgetSystemError(String);
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetSystemError {
+    InsufficientAnnouncementPeriod {
+        proposal_id: Uuid,
+        announced_at: NaiveDate,
+        earliest_vote_date: NaiveDate,
+    },
+    EpochNotFound(Uuid),
+    TeamNotFound(Uuid),
+    ProposalNotFound(Uuid),
+    RaffleNotFound(Uuid),
+    VoteNotFound(Uuid),
+    VoteAlreadyClosed,
+    ProposalAlreadyClosed,
+    EpochAlreadyClosed,
+    EpochOverlap,
+    NoActiveEpoch,
+    EpochAlreadyActive,
+    /// Titles of the open or reopened proposals blocking the close
+    ActionableProposalsRemaining(Vec<String>),
+    /// Holds the title of the proposal that was already paid
+    PaymentAlreadyRecorded(String),
+    FutureDateNotAllowed,
+    /// Describes why the payment transaction could not be confirmed on-chain
+    PaymentVerificationFailed(String),
+    EthereumError(String),
+    Other(String),
+}
 
 impl fmt::Display for BudgetSystemError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            BudgetSystemError::InsufficientAnnouncementPeriod { proposal_id, announced_at, earliest_vote_date } => write!(
+                f,
+                "Proposal {} was announced on {} and cannot be voted on before {}",
+                proposal_id, announced_at, earliest_vote_date
+            ),
+            BudgetSystemError::EpochNotFound(id) => write!(f, "Epoch not found: {}", id),
+            BudgetSystemError::TeamNotFound(id) => write!(f, "Team not found: {}", id),
+            BudgetSystemError::ProposalNotFound(id) => write!(f, "Proposal not found: {}", id),
+            BudgetSystemError::RaffleNotFound(id) => write!(f, "Raffle not found: {}", id),
+            BudgetSystemError::VoteNotFound(id) => write!(f, "Vote not found: {}", id),
+            BudgetSystemError::VoteAlreadyClosed => write!(f, "Vote is already closed"),
+            BudgetSystemError::ProposalAlreadyClosed => write!(f, "Proposal is already closed"),
+            BudgetSystemError::EpochAlreadyClosed => write!(f, "Epoch is already closed"),
+            BudgetSystemError::EpochOverlap => write!(f, "New epoch overlaps with an existing epoch"),
+            BudgetSystemError::NoActiveEpoch => write!(f, "No active epoch"),
+            BudgetSystemError::EpochAlreadyActive => write!(f, "Another epoch is currently active"),
+            BudgetSystemError::ActionableProposalsRemaining(titles) => write!(
+                f, "Cannot close epoch: {} actionable proposals remaining ({})", titles.len(), titles.join(", ")
+            ),
+            BudgetSystemError::PaymentAlreadyRecorded(title) => write!(f, "Proposal '{}' is already paid", title),
+            BudgetSystemError::FutureDateNotAllowed => write!(f, "Payment date cannot be in the future"),
+            BudgetSystemError::PaymentVerificationFailed(msg) => write!(f, "Payment verification failed: {}", msg),
+            BudgetSystemError::EthereumError(msg) => write!(f, "Ethereum error: {}", msg),
+            BudgetSystemError::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<&'static str> for BudgetSystemError {
+    fn from(msg: &'static str) -> Self {
+        BudgetSystemError::Other(msg.to_string())
+    }
+}
+
+impl From<String> for BudgetSystemError {
+    fn from(msg: String) -> Self {
+        BudgetSystemError::Other(msg)
+    }
+}
+
+impl From<Box<dyn Error>> for BudgetSystemError {
+    fn from(err: Box<dyn Error>) -> Self {
+        BudgetSystemError::Other(err.to_string())
     }
 }
 
@@ -60,21 +182,37 @@ impl Error for BudgetSystemError {}
         TeamStatus::Earner { .. } => "Earner",
         TeamStatus::Supporter => "Supporter",
         TeamStatus::Inactive => "Inactive",
+        TeamStatus::Archived => "Archived",
     }
 }
 
 impl BudgetSystem {
+    /// Votes in a loaded state whose deadline has passed are closed right away. Loading
+    /// has no side effects, so the change is only written with the next save and no
+    /// Discord notification is sent for these votes.
     pub async fn new(
         config: AppConfig, 
         ethereum_service: Arc<dyn EthereumServiceTrait>,
         state: Option<BudgetSystemState>
     ) -> Result<Self, Box<dyn Error>> {
         let state = state.unwrap_or_else(BudgetSystemState::new);
-        Ok(Self {
+        let discord_service = config.discord_webhook_url.as_deref()
+            .map(|url| Arc::new(DiscordWebhook::new(url)) as Arc<dyn DiscordServiceTrait>);
+        // Runs as a dry run that keeps its changes, so nothing is saved or notified
+        let mut budget_system = Self {
             state,
             ethereum_service,
+            discord_service,
             config,
-        })
+            dry_run_snapshot: Some(BudgetSystemState::new()),
+        };
+        match budget_system.close_expired_votes() {
+            Ok(closed) if !closed.is_empty() => info!("Closed {} vote(s) past their deadline on load", closed.len()),
+            Ok(_) => {},
+            Err(e) => log::warn!("Failed to close expired votes on load: {}", e),
+        }
+        budget_system.dry_run_snapshot = None;
+        Ok(budget_system)
     }
 
     pub fn state(&self) -> &BudgetSystemState {
@@ -89,6 +227,58 @@ impl BudgetSystem {
         self.config = config;
     }
 
+    /// Replaces the service notified of epoch, vote, raffle and payment events;
+    /// `None` turns notifications off.
+    pub fn set_discord_service(&mut self, discord_service: Option<Arc<dyn DiscordServiceTrait>>) {
+        self.discord_service = discord_service;
+    }
+
+    /// Starts a dry run: commands keep running in full, but nothing is saved or
+    /// notified until `finish_dry_run` puts the state back.
+    pub fn start_dry_run(&mut self) {
+        if self.dry_run_snapshot.is_none() {
+            self.dry_run_snapshot = Some(self.state.clone());
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run_snapshot.is_some()
+    }
+
+    /// Ends a dry run, discarding every change made since `start_dry_run`, and
+    /// returns what those changes were.
+    pub fn finish_dry_run(&mut self) -> StateDiff {
+        match self.dry_run_snapshot.take() {
+            Some(snapshot) => {
+                let diff = snapshot.diff(&self.state);
+                self.state = snapshot;
+                diff
+            },
+            None => StateDiff::default(),
+        }
+    }
+
+    /// Sends `payload` in the background. Notifications never block or fail the
+    /// operation that triggered them; delivery errors are only logged.
+    fn notify_discord(&self, payload: DiscordPayload) {
+        let Some(service) = self.discord_service.clone() else {
+            return;
+        };
+        if self.is_dry_run() {
+            return;
+        }
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = service.send_notification(payload).await {
+                        log::error!("Failed to send Discord notification: {}", e);
+                    }
+                });
+            }
+            Err(_) => log::warn!("No async runtime available, Discord notification dropped: {}", payload.content),
+        }
+    }
+
     pub fn get_team(&self, id: &Uuid) -> Option<&Team> {
         self.state.current_state().teams().get(id)
     }
@@ -109,6 +299,33 @@ impl BudgetSystem {
         self.state.votes().get(id)
     }
 
+    /// The vote on a proposal. A proposal with both an informal and a formal vote may
+    /// return either; use `get_latest_vote_for_proposal` when that matters.
+    pub fn get_vote_for_proposal(&self, proposal_id: Uuid) -> Option<&Vote> {
+        self.state.votes().values().find(|v| v.proposal_id() == proposal_id)
+    }
+
+    pub fn get_vote_for_proposal_mut(&mut self, proposal_id: Uuid) -> Option<&mut Vote> {
+        let vote_id = self.get_vote_for_proposal(proposal_id)?.id();
+        self.state.get_vote_mut(&vote_id)
+    }
+
+    /// The most recently opened vote on a proposal.
+    pub fn get_latest_vote_for_proposal(&self, proposal_id: Uuid) -> Option<&Vote> {
+        self.state.votes().values()
+            .filter(|v| v.proposal_id() == proposal_id)
+            .max_by_key(|v| v.opened_at())
+    }
+
+    pub fn get_raffle_for_proposal(&self, proposal_id: Uuid) -> Option<&Raffle> {
+        self.state.raffles().values().find(|r| r.config().proposal_id() == proposal_id)
+    }
+
+    pub fn get_raffle_for_proposal_mut(&mut self, proposal_id: Uuid) -> Option<&mut Raffle> {
+        let raffle_id = self.get_raffle_for_proposal(proposal_id)?.id();
+        self.state.get_raffle_mut(&raffle_id)
+    }
+
     pub fn create_team(&mut self, name: String, representative: String, trailing_monthly_revenue: Option<Vec<u64>>, address: Option<String>) -> Result<Uuid, Box<dyn Error>> {
         let team = Team::new(name, representative, trailing_monthly_revenue, address)?;
         let id = self.state.add_team(team);
@@ -122,11 +339,138 @@ impl BudgetSystem {
         Ok(())
     }
 
+    /// Folds the source team into the target: the source's unresolved proposals move to
+    /// the target, the source name becomes an alias of the target and the source is
+    /// marked inactive. Raffle snapshots and closed votes keep referring to the source
+    /// team, so past reports are unchanged. Returns the number of proposals moved.
+    pub fn merge_teams(&mut self, source_name: &str, target_name: &str) -> Result<usize, Box<dyn Error>> {
+        let source_id = get_id_by_name(self.state.current_state().teams(), source_name)
+            .ok_or_else(|| self.team_not_found(source_name))?;
+        let target_id = self.get_team_id_by_name(target_name)
+            .ok_or_else(|| self.team_not_found(target_name))?;
+        if source_id == target_id {
+            return Err("Cannot merge a team into itself".into());
+        }
+        for team_id in [source_id, target_id] {
+            let team = self.state.get_team(&team_id).ok_or("Team not found")?;
+            if !team.is_active() {
+                return Err(format!("Team {} is {}", team.name(), format_team_status(team.status()).to_lowercase()).into());
+            }
+            if self.is_team_in_open_vote(team_id) {
+                return Err(format!("Team {} is part of an open vote", team.name()).into());
+            }
+        }
+
+        let unresolved: Vec<Uuid> = self.state.proposals().values()
+            .filter(|p| p.resolution().is_none())
+            .filter(|p| p.budget_request_details().and_then(|d| d.team()) == Some(source_id))
+            .map(|p| p.id())
+            .collect();
+        for proposal_id in &unresolved {
+            if let Some(details) = self.state.get_proposal_mut(proposal_id).and_then(|p| p.budget_request_details_mut()) {
+                details.set_team(Some(target_id));
+            }
+        }
+
+        let source = self.state.get_team_mut(&source_id).ok_or("Team not found")?;
+        source.set_status(TeamStatus::Inactive)?;
+        let mut aliases = vec![source.name().to_string()];
+        aliases.extend(source.take_aliases());
+        let target = self.state.get_team_mut(&target_id).ok_or("Team not found")?;
+        for alias in aliases {
+            target.add_alias(alias);
+        }
+
+        self.save_state()?;
+        Ok(unresolved.len())
+    }
+
+    /// Archives a team: it stays in the state, so historical reports still resolve it,
+    /// but it gets no raffle tickets and cannot vote. Refused while the team is part of
+    /// an open vote.
+    pub fn archive_team(&mut self, team_id: Uuid) -> Result<(), BudgetSystemError> {
+        let team = self.state.get_team(&team_id).ok_or(BudgetSystemError::TeamNotFound(team_id))?;
+        if !team.is_archived() && self.is_team_in_open_vote(team_id) {
+            return Err(BudgetSystemError::Other(format!("Team {} is part of an open vote", team.name())));
+        }
+        self.state.get_team_mut(&team_id)
+            .ok_or(BudgetSystemError::TeamNotFound(team_id))?
+            .archive()?;
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Restores an archived team to the status it had before archiving.
+    pub fn unarchive_team(&mut self, team_id: Uuid) -> Result<(), BudgetSystemError> {
+        self.state.get_team_mut(&team_id)
+            .ok_or(BudgetSystemError::TeamNotFound(team_id))?
+            .unarchive()?;
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Adds an alias the team can be looked up by. Refused when another team already
+    /// goes by that name or alias, ignoring case.
+    pub fn add_team_alias(&mut self, team_id: Uuid, alias: &str) -> Result<(), BudgetSystemError> {
+        let alias = alias.trim();
+        if alias.is_empty() {
+            return Err(BudgetSystemError::Other("Alias cannot be empty".to_string()));
+        }
+        if let Some(other) = self.team_claiming_name(alias, team_id) {
+            return Err(BudgetSystemError::Other(format!("Alias {} is already used by team {}", alias, other.name())));
+        }
+        self.state.get_team_mut(&team_id)
+            .ok_or(BudgetSystemError::TeamNotFound(team_id))?
+            .add_alias(alias.to_string());
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
+        Ok(())
+    }
+
+    /// A team other than `except` whose name or alias is `name`, ignoring case.
+    fn team_claiming_name(&self, name: &str, except: Uuid) -> Option<&Team> {
+        self.state.current_state().teams().values()
+            .find(|team| team.id() != except && team.answers_to(name))
+    }
+
+    /// A team other than `except` with `name` as an alias, ignoring case.
+    fn team_with_alias(&self, name: &str, except: Uuid) -> Option<&Team> {
+        self.state.current_state().teams().values()
+            .find(|team| team.id() != except && team.alias_matches(name))
+    }
+
+    /// Whether the team was drawn into, or has voted in, a vote that is still open.
+    fn is_team_in_open_vote(&self, team_id: Uuid) -> bool {
+        self.state.votes().values()
+            .filter(|vote| !vote.is_closed())
+            .any(|vote| {
+                let participated = match vote.participation() {
+                    VoteParticipation::Formal { counted, uncounted } => counted.contains(&team_id) || uncounted.contains(&team_id),
+                    VoteParticipation::Informal(teams) => teams.contains(&team_id),
+                };
+                let seated = match vote.vote_type() {
+                    VoteType::Formal { raffle_id, .. } => self.state.get_raffle(raffle_id)
+                        .and_then(|raffle| raffle.result())
+                        .is_some_and(|result| result.counted().contains(&team_id) || result.uncounted().contains(&team_id)),
+                    VoteType::Informal => false,
+                };
+                participated || seated
+            })
+    }
+
+    /// Updates a team's details. A renamed team keeps its old name as an alias unless
+    /// another team already goes by it.
     pub fn update_team(&mut self, team_id: Uuid, updates: UpdateTeamDetails) -> Result<(), Box<dyn Error>> {
+        let old_name = self.state.get_team(&team_id).ok_or("Team not found")?.name().to_string();
+        if let Some(name) = updates.name.as_deref() {
+            if let Some(other) = self.team_with_alias(name, team_id) {
+                return Err(format!("Name {} is already an alias of team {}", name, other.name()).into());
+            }
+        }
+        let keep_old_name = self.team_claiming_name(&old_name, team_id).is_none();
         let team = self.state.get_team_mut(&team_id).ok_or("Team not found")?;
         
         if let Some(name) = updates.name {
-            team.set_name(name);
+            team.set_name(name, keep_old_name);
         }
         
         if let Some(representative) = updates.representative {
@@ -161,6 +505,34 @@ impl BudgetSystem {
         Ok(())
     }
 
+    /// The payment address a team had at the given time, for reconciling past payments.
+    pub fn team_address_at(&self, team_id: Uuid, at: DateTime<Utc>) -> Result<Option<Address>, BudgetSystemError> {
+        let team = self.state.get_team(&team_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Team not found: {}", team_id)))?;
+        Ok(team.address_at(at))
+    }
+
+    pub fn update_team_region(&mut self, team_id: Uuid, region: Option<String>) -> Result<(), Box<dyn Error>> {
+        let team = self.state.get_team_mut(&team_id).ok_or("Team not found")?;
+        team.set_region(region);
+        self.save_state()?;
+        Ok(())
+    }
+
+    /// Maps each region to the sorted names of its teams. Teams without a region
+    /// are grouped under "Unspecified".
+    pub fn get_team_geographic_distribution(&self) -> HashMap<String, Vec<String>> {
+        let mut distribution: HashMap<String, Vec<String>> = HashMap::new();
+        for team in self.state.current_state().teams().values() {
+            let region = team.region().unwrap_or("Unspecified").to_string();
+            distribution.entry(region).or_default().push(team.name().to_string());
+        }
+        for names in distribution.values_mut() {
+            names.sort();
+        }
+        distribution
+    }
+
     pub fn ethereum_service(&self) -> &Arc<dyn EthereumServiceTrait> {
         &self.ethereum_service
     }
@@ -173,14 +545,65 @@ impl BudgetSystem {
         self.ethereum_service.get_randomness(block_number).await
     }
 
+    /// Restores the timestamped backup, or the `.bak` copy without a timestamp; without `confirm` only previews it.
+    pub fn restore_backup(&mut self, timestamp: Option<&str>, confirm: bool) -> Result<String, Box<dyn Error>> {
+        let (label, backup) = match timestamp {
+            Some(timestamp) => {
+                let path = FileSystem::find_backup(&self.config.state_file, timestamp)?;
+                (timestamp.to_string(), FileSystem::load_state(path.to_str().ok_or("Invalid backup path")?)?)
+            },
+            None => (format!("{}.bak", self.config.state_file), FileSystem::restore_from_backup(&self.config.state_file)?),
+        };
+
+        let summary = format!(
+            "Backup {}: {} teams, {} epochs, {} proposals, {} votes (current: {} teams, {} epochs, {} proposals, {} votes)",
+            label,
+            backup.current_state().teams().len(), backup.epochs().len(), backup.proposals().len(), backup.votes().len(),
+            self.state.current_state().teams().len(), self.state.epochs().len(), self.state.proposals().len(), self.state.votes().len(),
+        );
+        if !confirm {
+            return Ok(format!("{}\nRun again with --confirm to replace the current state.", summary));
+        }
+
+        self.state = backup;
+        self.save_state()?;
+        Ok(format!("Restored {}", summary))
+    }
+
     pub async fn get_raffle_randomness(&self) -> Result<(u64, u64, String), Box<dyn Error>> {
         self.ethereum_service.get_raffle_randomness().await
     }
 
     pub fn save_state(&self) -> Result<(), Box<dyn std::error::Error>> {
-        FileSystem::save_state(&self.state, &self.config.state_file)
+        if self.is_dry_run() {
+            return Ok(());
+        }
+        FileSystem::save_state_with_backup(&self.state, &self.config.state_file, self.config.state_backup_count)?;
+
+        if let Ok(Some(_)) = FileSystem::check_state_file_size(&self.config.state_file, self.config.state_file_warn_bytes) {
+            let estimate = self.state_size_estimate();
+            log::warn!(
+                "State contains {} epochs, {} proposals, {} raffles, {} votes and {} history snapshots",
+                estimate.epochs, estimate.proposals, estimate.raffles, estimate.votes, estimate.history_snapshots
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn state_size_estimate(&self) -> StateSizeEstimate {
+        StateSizeEstimate {
+            serialized_bytes: serde_json::to_string_pretty(&self.state).map(|json| json.len()).unwrap_or(0),
+            teams: self.state.current_state().teams().len(),
+            history_snapshots: self.state.history().len(),
+            epochs: self.state.epochs().len(),
+            proposals: self.state.proposals().len(),
+            raffles: self.state.raffles().len(),
+            votes: self.state.votes().len(),
+        }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn add_proposal(
         &mut self,
         title: String,
@@ -188,12 +611,12 @@ impl BudgetSystem {
         budget_request_details: Option<BudgetRequestDetails>,
         announced_at: Option<NaiveDate>,
         published_at: Option<NaiveDate>,
-        is_historical: Option<bool>
-    ) -> Result<Uuid, &'static str> {
-        let current_epoch_id = self.state.current_epoch()
-            .ok_or("No active epoch")?;
+        is_historical: Option<bool>,
+        category: Option<ProposalCategory>
+    ) -> Result<Uuid, BudgetSystemError> {
+        let current_epoch_id = self.check_new_proposal(&title, None)?;
 
-        let proposal = Proposal::new(
+        let mut proposal = Proposal::new(
             current_epoch_id,
             title,
             url,
@@ -202,43 +625,231 @@ impl BudgetSystem {
             published_at,
             is_historical
         );
+        proposal.set_category(category);
 
         let proposal_id = self.state.add_proposal(&proposal);
         
         if let Some(epoch) = self.state.get_epoch_mut(&current_epoch_id) {
             epoch.add_proposal(proposal_id);
         } else {
-            return Err("Current epoch not found");
+            return Err(BudgetSystemError::EpochNotFound(current_epoch_id));
         }
 
         let _ = self.save_state();
         Ok(proposal_id)
     }
 
-    pub fn close_with_reason(&mut self, id: Uuid, resolution: &Resolution) -> Result<(), &'static str> {
-        if let Some(proposal) = self.state.get_proposal_mut(&id) {
-            if proposal.is_closed() {
-                return Err("Proposal is already closed");
+    /// Records the IPFS hash of a proposal's document, replacing any earlier one.
+    pub fn pin_proposal_document(&mut self, proposal_name: &str, ipfs_hash: &str) -> Result<(), BudgetSystemError> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| BudgetSystemError::Other(self.proposal_not_found(proposal_name)))?;
+        self.set_proposal_ipfs_hash(proposal_id, ipfs_hash.trim().to_string())
+    }
+
+    fn set_proposal_ipfs_hash(&mut self, proposal_id: Uuid, ipfs_hash: String) -> Result<(), BudgetSystemError> {
+        self.state.get_proposal_mut(&proposal_id)
+            .ok_or(BudgetSystemError::ProposalNotFound(proposal_id))?
+            .set_ipfs_hash(Some(ipfs_hash))?;
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Returns the epoch a proposal with this title would be added to: `epoch_id`, or the active epoch.
+    fn check_new_proposal(&self, title: &str, epoch_id: Option<Uuid>) -> Result<Uuid, BudgetSystemError> {
+        let current_epoch_id = match epoch_id {
+            Some(id) => id,
+            None => self.state.current_epoch().ok_or(BudgetSystemError::NoActiveEpoch)?,
+        };
+
+        if let Some(existing) = self.find_similar_proposals(title, current_epoch_id, SIMILAR_PROPOSAL_THRESHOLD).first() {
+            if !self.config.allow_similar_proposals {
+                return Err(BudgetSystemError::Other(format!("Similar proposal already exists: {}", existing.title())));
             }
-            if let Some(details) = &proposal.budget_request_details() {
-                if details.is_paid() {
-                    return Err("Cannot close: Proposal is already paid");
-                }
+            log::warn!("Adding proposal '{}' although a similar proposal exists: {}", title, existing.title());
+        }
+        Ok(current_epoch_id)
+    }
+
+    /// Proposals in the epoch whose title has a case-insensitive Jaro-Winkler
+    /// similarity of at least `threshold` to `title`, most similar first.
+    pub fn find_similar_proposals(&self, title: &str, epoch_id: Uuid, threshold: f64) -> Vec<&Proposal> {
+        let title = title.trim().to_lowercase();
+        let mut similar: Vec<(&Proposal, f64)> = self.get_proposals_for_epoch(epoch_id)
+            .into_iter()
+            .map(|p| (p, strsim::jaro_winkler(&title, &p.title().trim().to_lowercase())))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .collect();
+        similar.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.title().cmp(b.0.title())));
+        similar.into_iter().map(|(p, _)| p).collect()
+    }
+
+    pub fn close_with_reason(&mut self, id: Uuid, resolution: &Resolution) -> Result<(), BudgetSystemError> {
+        self.check_proposal_closable(id)?;
+        let proposal = self.state.get_proposal_mut(&id).ok_or(BudgetSystemError::ProposalNotFound(id))?;
+        proposal.set_resolution(Some(resolution.clone()));
+        proposal.set_status(ProposalStatus::Closed);
+        let _ = self.save_state();
+        Ok(())
+    }
+
+    fn check_proposal_closable(&self, id: Uuid) -> Result<(), BudgetSystemError> {
+        let proposal = self.state.get_proposal(&id).ok_or(BudgetSystemError::ProposalNotFound(id))?;
+        if proposal.is_closed() {
+            return Err(BudgetSystemError::ProposalAlreadyClosed);
+        }
+        if let Some(details) = &proposal.budget_request_details() {
+            if details.is_paid() {
+                return Err(BudgetSystemError::PaymentAlreadyRecorded(proposal.title().to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the epoch `close_epoch` would close: the named one, or the active one.
+    fn check_epoch_closable(&self, epoch_name: Option<&str>) -> Result<Uuid, BudgetSystemError> {
+        let epoch_id = match epoch_name {
+            Some(name) => self.get_epoch_id_by_name(name)
+                .ok_or_else(|| format!("Epoch not found: {}", name))?,
+            None => self.state.current_epoch()
+                .ok_or(BudgetSystemError::NoActiveEpoch)?
+        };
+    
+        // Check for actionable proposals
+        let mut actionable_proposals: Vec<String> = self.get_proposals_for_epoch(epoch_id)
+            .iter()
+            .filter(|p| p.is_actionable())
+            .map(|p| p.title().to_string())
+            .collect();
+    
+        if !actionable_proposals.is_empty() {
+            actionable_proposals.sort();
+            return Err(BudgetSystemError::ActionableProposalsRemaining(actionable_proposals));
+        }
+
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or(BudgetSystemError::EpochNotFound(epoch_id))?;
+        if epoch.is_closed() {
+            return Err(BudgetSystemError::EpochAlreadyClosed);
+        }
+        Ok(epoch_id)
+    }
+
+    /// Sends a closed, unpaid proposal back to an actionable state, clearing its resolution.
+    /// Refused while the proposal's epoch is closed or when a closed vote exists for it,
+    /// since the vote has to be reopened first.
+    pub fn reopen_proposal(&mut self, proposal_name: &str) -> Result<(), BudgetSystemError> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| BudgetSystemError::Other(self.proposal_not_found(proposal_name)))?;
+        let proposal = self.state.get_proposal(&proposal_id).ok_or("Proposal not found")?;
+
+        if !proposal.is_closed() {
+            return Err(BudgetSystemError::Other(format!("Proposal '{}' is not closed", proposal_name)));
+        }
+        if proposal.budget_request_details().is_some_and(|details| details.is_paid()) {
+            return Err(BudgetSystemError::Other(format!("Cannot reopen: proposal '{}' is already paid", proposal_name)));
+        }
+        let epoch = self.state.get_epoch(&proposal.epoch_id()).ok_or("Epoch not found")?;
+        if epoch.is_closed() {
+            return Err(BudgetSystemError::Other(format!("Cannot reopen: epoch '{}' is closed", epoch.name())));
+        }
+        if self.state.votes().values().any(|vote| vote.proposal_id() == proposal_id && vote.is_closed()) {
+            return Err(BudgetSystemError::Other(format!(
+                "Cannot reopen: proposal '{}' has a closed vote; reopen the vote first (vote reopen)",
+                proposal_name
+            )));
+        }
+
+        let proposal = self.state.get_proposal_mut(&proposal_id).ok_or("Proposal not found")?;
+        proposal.set_status(ProposalStatus::Reopened);
+        proposal.set_resolution(None);
+        proposal.set_resolved_at(None);
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Reopens the closed vote on an unpaid proposal in an open epoch. The vote's result
+    /// is dropped and every seat has to vote again; a closed proposal is reopened with it
+    /// so closing the vote resolves it again.
+    pub fn reopen_vote(&mut self, proposal_name: &str) -> Result<Uuid, BudgetSystemError> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| BudgetSystemError::Other(self.proposal_not_found(proposal_name)))?;
+        let proposal = self.state.get_proposal(&proposal_id).ok_or("Proposal not found")?;
+
+        if proposal.budget_request_details().is_some_and(|details| details.is_paid()) {
+            return Err(BudgetSystemError::Other(format!("Cannot reopen vote: proposal '{}' is already paid", proposal_name)));
+        }
+        let epoch = self.state.get_epoch(&proposal.epoch_id()).ok_or("Epoch not found")?;
+        if epoch.is_closed() {
+            return Err(BudgetSystemError::Other(format!("Cannot reopen vote: epoch '{}' is closed", epoch.name())));
+        }
+        let vote_id = self.state.votes().values()
+            .find(|vote| vote.proposal_id() == proposal_id && vote.is_closed())
+            .map(|vote| vote.id())
+            .ok_or_else(|| BudgetSystemError::Other(format!("Proposal '{}' has no closed vote", proposal_name)))?;
+
+        let vote = self.state.get_vote_mut(&vote_id).ok_or(BudgetSystemError::VoteNotFound(vote_id))?;
+        vote.reopen()?;
+        let proposal = self.state.get_proposal_mut(&proposal_id).ok_or("Proposal not found")?;
+        if proposal.is_closed() {
+            proposal.set_status(ProposalStatus::Reopened);
+            proposal.set_resolution(None);
+            proposal.set_resolved_at(None);
+        }
+        self.save_state()?;
+        Ok(vote_id)
+    }
+
+    /// Finds a proposal for report generation, returning its ID and epoch name.
+    /// Without an epoch name the current epoch is searched first, then all epochs;
+    /// a title found in several epochs is an error asking for the epoch.
+    pub fn find_proposal_for_report(&self, proposal_name: &str, epoch_name: Option<&str>) -> Result<(Uuid, String), BudgetSystemError> {
+        if let Some(name) = epoch_name {
+            let epoch_id = self.get_epoch_id_by_name(name)
+                .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", name)))?;
+            let epoch = self.state.get_epoch(&epoch_id)
+                .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", name)))?;
+            return self.get_proposals_for_epoch(epoch_id).into_iter()
+                .find(|p| p.name_matches(proposal_name))
+                .map(|p| (p.id(), epoch.name().to_string()))
+                .ok_or_else(|| BudgetSystemError::Other(format!("Proposal '{}' not found in epoch '{}'", proposal_name, name)));
+        }
+
+        if let Some(current_epoch) = self.get_current_epoch() {
+            if let Some(proposal) = self.get_proposals_for_epoch(current_epoch.id()).into_iter()
+                .find(|p| p.name_matches(proposal_name))
+            {
+                return Ok((proposal.id(), current_epoch.name().to_string()));
+            }
+        }
+
+        let mut matches: Vec<(Uuid, String)> = self.state.epochs().values()
+            .flat_map(|epoch| {
+                self.get_proposals_for_epoch(epoch.id()).into_iter()
+                    .filter(|p| p.name_matches(proposal_name))
+                    .map(|p| (p.id(), epoch.name().to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        match matches.len() {
+            0 => Err(BudgetSystemError::Other(format!("Proposal not found: {}", proposal_name))),
+            1 => Ok(matches.remove(0)),
+            _ => {
+                let mut epochs: Vec<String> = matches.into_iter().map(|(_, name)| name).collect();
+                epochs.sort();
+                Err(BudgetSystemError::Other(format!(
+                    "Proposal '{}' exists in several epochs ({}); specify the epoch name",
+                    proposal_name, epochs.join(", ")
+                )))
             }
-            proposal.set_resolution(Some(resolution.clone()));
-            proposal.set_status(ProposalStatus::Closed);
-            let _ = self.save_state();
-            Ok(())
-        } else {
-            Err("Proposal not found")
         }
     }
 
-    pub fn generate_and_save_proposal_report(&self, proposal_id: Uuid, epoch_name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    pub fn generate_and_save_proposal_report(&self, proposal_id: Uuid, epoch_name: &str, sections: ReportSections) -> Result<PathBuf, Box<dyn Error>> {
         let proposal = self.get_proposal(&proposal_id)
             .ok_or_else(|| format!("Proposal not found: {:?}", proposal_id))?;
 
-        let report_content = self.generate_proposal_report(proposal_id)?;
+        let report_content = self.generate_proposal_report(proposal_id, sections)?;
         
         FileSystem::generate_and_save_proposal_report(
             proposal,
@@ -248,21 +859,48 @@ impl BudgetSystem {
         )
     }
 
-    pub fn create_formal_vote(&mut self, proposal_id: Uuid, raffle_id: Uuid, _threshold: Option<f64>) -> Result<Uuid, &'static str> {
-        let proposal = self.state.get_proposal_mut(&proposal_id)
-            .ok_or("Proposal not found")?;
+    /// Opens a formal vote on the proposal. With a `deadline`, `close_expired_votes`
+    /// closes the vote once it passes.
+    pub fn create_formal_vote(&mut self, proposal_id: Uuid, raffle_id: Uuid, _threshold: Option<f64>, deadline: Option<DateTime<Utc>>) -> Result<Uuid, BudgetSystemError> {
+        let epoch_id = self.state.get_proposal(&proposal_id)
+            .ok_or(BudgetSystemError::ProposalNotFound(proposal_id))?
+            .epoch_id();
+        if self.enforce_proposal_announcement_window(epoch_id)?.contains(&proposal_id) {
+            return Err("Proposal went past the epoch's max days to vote and was closed as invalid".into());
+        }
+
+        let proposal = self.state.get_proposal(&proposal_id)
+            .ok_or(BudgetSystemError::ProposalNotFound(proposal_id))?;
 
         if !proposal.is_actionable() {
-            return Err("Proposal is not in a votable state");
+            return Err("Proposal is not in a votable state".into());
         }
 
-        let epoch_id = proposal.epoch_id();
+        let min_days_before_vote = self.state.get_epoch(&epoch_id)
+            .and_then(|epoch| epoch.governance_config().min_days_before_vote());
+        if let (Some(min_days), Some(announced_at)) = (min_days_before_vote, proposal.announced_at()) {
+            let earliest_vote_date = announced_at + chrono::Duration::days(min_days as i64);
+            if Utc::now().date_naive() < earliest_vote_date {
+                return Err(BudgetSystemError::InsufficientAnnouncementPeriod {
+                    proposal_id,
+                    announced_at,
+                    earliest_vote_date,
+                });
+            }
+        }
+
+        if self.config.require_reviewer_sign_off && !proposal.all_reviewers_signed_off() {
+            return Err(BudgetSystemError::Other(format!(
+                "Proposal is missing reviewer sign-off from: {}",
+                proposal.pending_reviewers().join(", ")
+            )));
+        }
 
         let raffle = self.state.get_raffle(&raffle_id)
-            .ok_or("Raffle not found")?;
+            .ok_or(BudgetSystemError::RaffleNotFound(raffle_id))?;
 
         if raffle.result().is_none() {
-            return Err("Raffle results have not been generated");
+            return Err("Raffle results have not been generated".into());
         }
 
         let config = raffle.config();
@@ -272,11 +910,13 @@ impl BudgetSystem {
             total_eligible_seats: config.total_counted_seats() as u32,
             threshold: self.config.default_qualified_majority_threshold,
             counted_points: self.config.counted_vote_points,
-            uncounted_points: self.config.uncounted_vote_points
+            uncounted_points: self.config.uncounted_vote_points,
+            abstain_points: self.config.abstain_points,
+            quorum: self.config.default_quorum,
         };
 
-        let vote = Vote::new(proposal_id, epoch_id, vote_type, false);
-
+        let mut vote = Vote::new(proposal_id, epoch_id, vote_type, false);
+        vote.set_deadline(deadline);
 
         let vote_id = self.state.add_vote(&vote);
         let _ = self.save_state();
@@ -300,20 +940,37 @@ impl BudgetSystem {
         Ok(vote_id)
     }
 
-    pub fn cast_votes(&mut self, vote_id: Uuid, votes: Vec<(Uuid, VoteChoice)>) -> Result<(), &'static str> {
-        let raffle_result = {
-            let vote = self.state.get_vote(&vote_id).ok_or("Vote not found")?;
-            match vote.vote_type() {
-                VoteType::Formal { raffle_id, .. } => {
-                    self.state.get_raffle(&raffle_id)
-                        .and_then(|raffle| raffle.result().cloned())
-                },
-                VoteType::Informal => None,
-            }
-        };
-    
+    /// Raffle result that decides the seats of an open vote; `None` for informal votes.
+    fn open_vote_raffle_result(&self, vote_id: Uuid) -> Result<Option<RaffleResult>, BudgetSystemError> {
+        let vote = self.state.get_vote(&vote_id).ok_or(BudgetSystemError::VoteNotFound(vote_id))?;
+        if vote.is_closed() {
+            return Err(BudgetSystemError::VoteAlreadyClosed);
+        }
+        Ok(match vote.vote_type() {
+            VoteType::Formal { raffle_id, .. } => {
+                self.state.get_raffle(raffle_id)
+                    .and_then(|raffle| raffle.result().cloned())
+            },
+            VoteType::Informal => None,
+        })
+    }
+
+    fn check_teams_not_archived(&self, team_ids: impl IntoIterator<Item = Uuid>) -> Result<(), BudgetSystemError> {
+        if let Some(team) = team_ids.into_iter()
+            .filter_map(|team_id| self.state.get_team(&team_id))
+            .find(|team| team.is_archived())
+        {
+            return Err(BudgetSystemError::Other(format!("Team {} is archived and cannot vote", team.name())));
+        }
+        Ok(())
+    }
+
+    pub fn cast_votes(&mut self, vote_id: Uuid, votes: Vec<(Uuid, VoteChoice)>) -> Result<(), BudgetSystemError> {
+        let raffle_result = self.open_vote_raffle_result(vote_id)?;
+        self.check_teams_not_archived(votes.iter().map(|(team_id, _)| *team_id))?;
+
         {
-            let vote = self.state.get_vote_mut(&vote_id).ok_or("Vote not found")?;
+            let vote = self.state.get_vote_mut(&vote_id).ok_or(BudgetSystemError::VoteNotFound(vote_id))?;
             for (team_id, choice) in votes {
                 vote.cast_vote(team_id, choice, raffle_result.as_ref())?;
             }
@@ -323,63 +980,324 @@ impl BudgetSystem {
         Ok(())
     }
 
-    pub fn close_vote(&mut self, vote_id: Uuid) -> Result<bool, &'static str> {
-        let vote = self.state.get_vote_mut(&vote_id).ok_or("Vote not found")?;
-        
-        if vote.is_closed() {
-            return Err("Vote is already closed");
-        }
+    /// Casts the delegator's vote on behalf of the delegate. Points for the seat go to the delegator.
+    pub fn cast_delegated_vote(&mut self, vote_id: Uuid, delegator_id: Uuid, delegate_id: Uuid, choice: VoteChoice) -> Result<(), BudgetSystemError> {
+        let raffle_result = self.open_vote_raffle_result(vote_id)?;
+        self.state.get_team(&delegate_id).ok_or(BudgetSystemError::TeamNotFound(delegate_id))?;
+        self.check_teams_not_archived([delegator_id, delegate_id])?;
 
-        vote.close()?;
-
-        let result = match vote.result() {
-            Some(VoteResult::Formal { passed, .. }) => *passed,
-            Some(VoteResult::Informal { .. }) => false,
-            None => return Err("Vote result not available"),
-        };
+        let vote = self.state.get_vote_mut(&vote_id).ok_or(BudgetSystemError::VoteNotFound(vote_id))?;
+        vote.cast_delegated_vote(delegator_id, delegate_id, choice, raffle_result.as_ref())?;
 
-        let _ = self.save_state();
-        Ok(result)
+        self.save_state()?;
+        Ok(())
     }
 
-    pub fn create_epoch(&mut self, name: &str, start_date:DateTime<Utc>, end_date: DateTime<Utc>) -> Result<Uuid, &'static str> {
-        let new_epoch = Epoch::new(name.to_string(), start_date, end_date)?;
-
-        // Check for overlapping epochs
-        for epoch in self.state.epochs().values() {
-            if (start_date < epoch.end_date() && end_date > epoch.start_date()) ||
-            (epoch.start_date() < end_date && epoch.end_date() > start_date) {
-                return Err("New epoch overlaps with an existing epoch");
-            }
+    pub fn request_vote_change(&mut self, vote_id: Uuid, team_id: Uuid, new_choice: VoteChoice, reason: &str) -> Result<(), BudgetSystemError> {
+        if !self.config.allow_vote_changes {
+            return Err("Vote changes are not enabled".into());
         }
+        self.state.get_team(&team_id).ok_or("Team not found")?;
+        let vote = self.state.get_vote_mut(&vote_id).ok_or("Vote not found")?;
+        let window = chrono::Duration::hours(self.config.vote_change_window_hours as i64);
+        vote.request_vote_change(team_id, new_choice, reason.to_string(), window)?;
 
-        let epoch_id = self.state.add_epoch(&new_epoch);
-        let _ = self.save_state();
-        Ok(epoch_id)
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
+        Ok(())
     }
 
-    pub fn activate_epoch(&mut self, epoch_id: Uuid) -> Result<(), &'static str> {
-        if self.state.current_epoch().is_some() {
-            return Err("Another epoch is currently active");
+    pub fn approve_vote_change(&mut self, vote_id: Uuid, team_id: Uuid) -> Result<(), BudgetSystemError> {
+        if !self.config.allow_vote_changes {
+            return Err("Vote changes are not enabled".into());
         }
+        let vote = self.state.get_vote_mut(&vote_id).ok_or("Vote not found")?;
+        let new_choice = vote.approve_vote_change(team_id)?;
+        info!("Approved vote change for team {} on vote {}: now {:?}", team_id, vote_id, new_choice);
 
-        let epoch = self.state.get_epoch_mut(&epoch_id).ok_or("Epoch not found")?;
-
-        let _ = epoch.activate();
-        self.state.set_current_epoch(Some(epoch_id));
-        let _ = self.save_state();
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
         Ok(())
     }
 
-    pub fn set_epoch_reward(&mut self, token: &str, amount: f64) -> Result<(), &'static str> {
-        let epoch_id = self.state.current_epoch().ok_or("No active epoch")?;
-        let epoch = self.state.get_epoch_mut(&epoch_id).ok_or("Epoch not found")?;
+    pub fn get_vote_change_requests(&self, vote_id: Uuid) -> Result<&[VoteChangeRequest], BudgetSystemError> {
+        let vote = self.state.get_vote(&vote_id).ok_or("Vote not found")?;
+        Ok(vote.pending_vote_changes())
+    }
+
+    /// Teams eligible to vote that have not cast a vote yet, sorted by name. Formal votes
+    /// take eligibility from the raffle result, informal votes from the active teams.
+    pub fn pending_voters(&self, vote_id: Uuid) -> Result<Vec<Uuid>, BudgetSystemError> {
+        let vote = self.state.get_vote(&vote_id).ok_or("Vote not found")?;
+        let (eligible, voted): (Vec<Uuid>, Vec<Uuid>) = match (vote.vote_type(), vote.participation()) {
+            (VoteType::Formal { raffle_id, .. }, VoteParticipation::Formal { counted, uncounted }) => {
+                let result = self.state.get_raffle(raffle_id)
+                    .and_then(|raffle| raffle.result())
+                    .ok_or("Raffle result not found")?;
+                (
+                    result.counted().iter().chain(result.uncounted()).copied().collect(),
+                    counted.iter().chain(uncounted).copied().collect(),
+                )
+            },
+            (_, VoteParticipation::Informal(participants)) => (
+                self.state.current_state().teams().values()
+                    .filter(|team| team.is_active())
+                    .map(|team| team.id())
+                    .collect(),
+                participants.clone(),
+            ),
+            _ => return Err(BudgetSystemError::Other("Vote participation does not match vote type".to_string())),
+        };
+
+        let mut pending: Vec<&Team> = eligible.iter()
+            .filter(|team_id| !voted.contains(team_id))
+            .filter_map(|team_id| self.state.get_team(team_id))
+            .collect();
+        pending.sort_by(|a, b| a.name().cmp(b.name()));
+        Ok(pending.into_iter().map(|team| team.id()).collect())
+    }
+
+    /// Builds a reminder @-mentioning the teams that still have to vote on the proposal's
+    /// open vote. Only teams with a recorded Telegram contact are mentioned; `None` means
+    /// there is nobody to ping. Requires `telegram.allow_absent_voter_pings`.
+    pub fn absent_voter_ping_message(&self, proposal_name: &str) -> Result<Option<String>, BudgetSystemError> {
+        if !self.config.telegram.allow_absent_voter_pings {
+            return Err("Absent voter pings are disabled (set telegram.allow_absent_voter_pings to enable)".into());
+        }
+
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| BudgetSystemError::Other(self.proposal_not_found(proposal_name)))?;
+        let vote = self.state.votes().values()
+            .find(|vote| vote.proposal_id() == proposal_id && !vote.is_closed())
+            .ok_or_else(|| BudgetSystemError::Other(format!("No open vote for proposal: {}", proposal_name)))?;
+
+        let mut mentions = Vec::new();
+        let mut without_contact = Vec::new();
+        for team_id in self.pending_voters(vote.id())? {
+            let Some(team) = self.state.get_team(&team_id) else { continue };
+            if team.contacts().is_empty() {
+                without_contact.push(team.name().to_string());
+            } else {
+                let handles: Vec<String> = team.contacts().iter().map(|handle| format!("@{}", handle)).collect();
+                mentions.push(format!("{} ({})", handles.join(" "), team.name()));
+            }
+        }
+
+        if mentions.is_empty() {
+            return Ok(None);
+        }
+
+        let mut message = format!("Reminder: the vote on '{}' is still open. Waiting for:\n", proposal_name);
+        for mention in &mentions {
+            message.push_str(&format!("- {}\n", mention));
+        }
+        if !without_contact.is_empty() {
+            message.push_str(&format!("No Telegram contact on record for: {}\n", without_contact.join(", ")));
+        }
+        Ok(Some(message))
+    }
+
+    pub fn update_team_contacts(&mut self, team_id: Uuid, contacts: Vec<String>) -> Result<(), Box<dyn Error>> {
+        let team = self.state.get_team_mut(&team_id).ok_or("Team not found")?;
+        team.set_contacts(contacts);
+        self.save_state()?;
+        Ok(())
+    }
+
+    pub fn close_vote(&mut self, vote_id: Uuid) -> Result<bool, BudgetSystemError> {
+        let vote = self.state.get_vote_mut(&vote_id).ok_or(BudgetSystemError::VoteNotFound(vote_id))?;
+        
+        if vote.is_closed() {
+            return Err(BudgetSystemError::VoteAlreadyClosed);
+        }
+
+        vote.close()?;
+
+        let (result, is_formal) = match vote.result() {
+            Some(VoteResult::Formal { passed, .. }) => (*passed, true),
+            Some(VoteResult::Informal { .. }) => (false, false),
+            None => return Err("Vote result not available".into()),
+        };
+        let proposal_id = vote.proposal_id();
+
+        let _ = self.save_state();
+
+        let title = self.state.get_proposal(&proposal_id)
+            .map(|p| p.title().to_string())
+            .unwrap_or_else(|| proposal_id.to_string());
+        let embed = match (is_formal, result) {
+            (true, true) => DiscordEmbed::new(title, COLOR_GREEN).field("Result", "Approved", true),
+            (true, false) => DiscordEmbed::new(title, COLOR_RED).field("Result", "Rejected", true),
+            (false, _) => DiscordEmbed::new(title, COLOR_BLUE).field("Result", "Informal vote", true),
+        };
+        self.notify_discord(DiscordPayload::new("Vote closed").with_embed(embed));
+        Ok(result)
+    }
+
+    pub fn create_epoch(&mut self, name: &str, start_date:DateTime<Utc>, end_date: DateTime<Utc>) -> Result<Uuid, BudgetSystemError> {
+        let new_epoch = self.check_new_epoch(name, start_date, end_date)?;
+        let epoch_id = self.state.add_epoch(&new_epoch);
+        let _ = self.save_state();
+        Ok(epoch_id)
+    }
+
+    /// Creates a planned epoch with the given name and dates that takes over the source
+    /// epoch's rewards. Proposals, votes and team rewards are not copied.
+    pub fn clone_epoch(&mut self, source_epoch_id: Uuid, new_name: &str, new_start: DateTime<Utc>, new_end: DateTime<Utc>) -> Result<Uuid, BudgetSystemError> {
+        let mut new_epoch = self.check_epoch_clone(source_epoch_id, new_name, new_start, new_end)?;
+        let source = self.state.get_epoch(&source_epoch_id)
+            .ok_or(BudgetSystemError::EpochNotFound(source_epoch_id))?;
+        for reward in source.rewards() {
+            new_epoch.set_reward(reward.token().to_string(), reward.amount())?;
+        }
+
+        let epoch_id = self.state.add_epoch(&new_epoch);
+        let _ = self.save_state();
+        Ok(epoch_id)
+    }
+
+    fn check_epoch_clone(&self, source_epoch_id: Uuid, new_name: &str, new_start: DateTime<Utc>, new_end: DateTime<Utc>) -> Result<Epoch, BudgetSystemError> {
+        if self.state.get_epoch(&source_epoch_id).is_none() {
+            return Err(BudgetSystemError::EpochNotFound(source_epoch_id));
+        }
+        if self.get_epoch_id_by_name(new_name).is_some() {
+            return Err(BudgetSystemError::Other(format!("Epoch already exists: {}", new_name)));
+        }
+        self.check_new_epoch(new_name, new_start, new_end)
+    }
+
+    fn check_new_epoch(&self, name: &str, start_date: DateTime<Utc>, end_date: DateTime<Utc>) -> Result<Epoch, BudgetSystemError> {
+        let new_epoch = Epoch::new(name.to_string(), start_date, end_date)?;
+
+        // Check for overlapping epochs
+        for epoch in self.state.epochs().values() {
+            if (start_date < epoch.end_date() && end_date > epoch.start_date()) ||
+            (epoch.start_date() < end_date && epoch.end_date() > start_date) {
+                return Err(BudgetSystemError::EpochOverlap);
+            }
+        }
+        Ok(new_epoch)
+    }
+
+    pub fn activate_epoch(&mut self, epoch_id: Uuid) -> Result<(), BudgetSystemError> {
+        self.check_epoch_activation(epoch_id)?;
+        let epoch = self.state.get_epoch_mut(&epoch_id).ok_or(BudgetSystemError::EpochNotFound(epoch_id))?;
+
+        let _ = epoch.activate();
+        let embed = DiscordEmbed::new(epoch.name(), COLOR_BLUE)
+            .field("Start", epoch.start_date().format("%Y-%m-%d").to_string(), true)
+            .field("End", epoch.end_date().format("%Y-%m-%d").to_string(), true);
+        self.state.set_current_epoch(Some(epoch_id));
+        let _ = self.save_state();
+        self.notify_discord(DiscordPayload::new("Epoch activated").with_embed(embed));
+        Ok(())
+    }
+
+    fn check_epoch_activation(&self, epoch_id: Uuid) -> Result<(), BudgetSystemError> {
+        if self.state.current_epoch().is_some() {
+            return Err(BudgetSystemError::EpochAlreadyActive);
+        }
+        self.state.get_epoch(&epoch_id).ok_or(BudgetSystemError::EpochNotFound(epoch_id))?;
+        Ok(())
+    }
+
+    pub fn set_epoch_reward(&mut self, token: &str, amount: f64) -> Result<(), &'static str> {
+        let epoch_id = self.state.current_epoch().ok_or("No active epoch")?;
+        let epoch = self.state.get_epoch_mut(&epoch_id).ok_or("Epoch not found")?;
         
         let _ = epoch.set_reward(token.to_string(), amount);
         let _ = self.save_state();
         Ok(())
     }
 
+    pub fn set_epoch_voting_windows(
+        &mut self,
+        epoch_name: Option<&str>,
+        min_days_before_vote: Option<u64>,
+        max_days_to_vote: Option<u64>
+    ) -> Result<Uuid, Box<dyn Error>> {
+        let epoch_id = match epoch_name {
+            Some(name) => self.get_epoch_id_by_name(name)
+                .ok_or_else(|| format!("Epoch not found: {}", name))?,
+            None => self.state.current_epoch().ok_or("No active epoch")?,
+        };
+
+        let governance_config = EpochGovernanceConfig::new(min_days_before_vote, max_days_to_vote)?;
+        let epoch = self.state.get_epoch_mut(&epoch_id).ok_or("Epoch not found")?;
+        if epoch.is_closed() {
+            return Err("Cannot change voting windows of a closed epoch".into());
+        }
+        epoch.set_governance_config(governance_config);
+
+        let _ = self.save_state();
+        Ok(epoch_id)
+    }
+
+    /// Closes proposals in the epoch that have been waiting for a decision for
+    /// longer than the epoch's `max_days_to_vote`. Proposals with an open vote are
+    /// left for the vote to resolve. Returns the closed proposal ids.
+    pub fn enforce_proposal_announcement_window(&mut self, epoch_id: Uuid) -> Result<Vec<Uuid>, BudgetSystemError> {
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_id)))?;
+
+        let max_days = match epoch.governance_config().max_days_to_vote() {
+            Some(days) => days,
+            None => return Ok(Vec::new()),
+        };
+
+        let today = Utc::now().date_naive();
+        let expired: Vec<Uuid> = epoch.associated_proposals().iter()
+            .filter_map(|id| self.state.get_proposal(id))
+            .filter(|p| p.is_actionable() && p.resolution().is_none())
+            .filter(|p| p.announced_at()
+                .is_some_and(|announced| today > announced + chrono::Duration::days(max_days as i64)))
+            .filter(|p| !self.state.votes().values().any(|vote| vote.proposal_id() == p.id() && !vote.is_closed()))
+            .map(|p| p.id())
+            .collect();
+
+        for proposal_id in &expired {
+            self.close_with_reason(*proposal_id, &Resolution::Invalid)?;
+        }
+
+        Ok(expired)
+    }
+
+    /// Applies `enforce_proposal_announcement_window` to every epoch that isn't closed.
+    pub fn enforce_announcement_windows(&mut self) -> Result<Vec<Uuid>, BudgetSystemError> {
+        let epoch_ids: Vec<Uuid> = self.state.epochs().values()
+            .filter(|epoch| !epoch.is_closed())
+            .map(|epoch| epoch.id())
+            .collect();
+
+        let mut closed = Vec::new();
+        for epoch_id in epoch_ids {
+            closed.extend(self.enforce_proposal_announcement_window(epoch_id)?);
+        }
+        Ok(closed)
+    }
+
+    pub fn bulk_close_proposals(&mut self, epoch_id: Uuid, resolution: Resolution, reason: &str) -> Result<Vec<Uuid>, BudgetSystemError> {
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_id)))?;
+
+        let open_proposals: Vec<Uuid> = epoch.associated_proposals().iter()
+            .filter_map(|id| self.state.get_proposal(id))
+            .filter(|p| p.is_actionable())
+            .map(|p| p.id())
+            .collect();
+
+        for proposal_id in &open_proposals {
+            self.close_with_reason(*proposal_id, &resolution)?;
+            info!("Closed proposal {} with resolution {:?}: {}", proposal_id, resolution, reason);
+        }
+
+        Ok(open_proposals)
+    }
+
+    pub fn get_proposal_revision_history(&self, proposal_id: Uuid) -> &[ProposalRevision] {
+        self.state.get_proposal(&proposal_id)
+            .map(|p| p.revision_history())
+            .unwrap_or(&[])
+    }
+
     pub fn get_current_epoch(&self) -> Option<&Epoch> {
         self.state.current_epoch().and_then(|id| self.state.epochs().get(&id))
     }
@@ -394,6 +1312,188 @@ impl BudgetSystem {
         }
     }
 
+    /// All proposals whose budget request belongs to the team, across every epoch.
+    pub fn get_proposals_for_team(&self, team_id: Uuid) -> Vec<&Proposal> {
+        self.state.proposals().values()
+            .filter(|p| p.budget_request_details().is_some_and(|d| d.includes_team(team_id)))
+            .collect()
+    }
+
+    pub fn get_proposals_for_team_in_epoch(&self, team_id: Uuid, epoch_id: Uuid) -> Vec<&Proposal> {
+        self.get_proposals_for_team(team_id).into_iter()
+            .filter(|p| p.epoch_id() == epoch_id)
+            .collect()
+    }
+
+    /// Approved budget requests for a team across all epochs, paid or unpaid, ordered by
+    /// epoch start date, then payment date (unpaid last), then title.
+    pub fn get_team_payment_history(&self, team_id: Uuid) -> Vec<TeamPaymentRecord> {
+        let mut epochs: Vec<&Epoch> = self.state.epochs().values().collect();
+        epochs.sort_by_key(|e| e.start_date());
+
+        let mut history = Vec::new();
+        for epoch in epochs {
+            let mut records: Vec<TeamPaymentRecord> = epoch.associated_proposals().iter()
+                .filter_map(|id| self.state.get_proposal(id))
+                .filter(|p| p.is_approved())
+                .filter_map(|proposal| {
+                    let details = proposal.budget_request_details()?;
+                    let amounts = details.team_allocations().remove(&team_id)?;
+                    let payment = details.team_payment(team_id);
+                    Some(TeamPaymentRecord {
+                        proposal_id: proposal.id(),
+                        proposal_title: proposal.title().to_string(),
+                        epoch_name: epoch.name().to_string(),
+                        amounts,
+                        payment_date: payment.map(|(_, date)| date),
+                        payment_tx: payment.map(|(tx, _)| format!("{:?}", tx)),
+                        is_loan: details.is_loan(),
+                    })
+                })
+                .collect();
+            records.sort_by(|a, b| {
+                (a.payment_date.is_none(), a.payment_date, &a.proposal_title)
+                    .cmp(&(b.payment_date.is_none(), b.payment_date, &b.proposal_title))
+            });
+            history.extend(records);
+        }
+        history
+    }
+
+    /// Markdown report of a team's voting and payments across all started
+    /// epochs. Participation is the share of formal votes the team took part in.
+    pub fn generate_team_performance_report(&self, team_name: &str) -> Result<String, Box<dyn Error>> {
+        let team_id = self.get_team_id_by_name(team_name)
+            .ok_or_else(|| self.team_not_found(team_name))?;
+
+        let mut epochs: Vec<&Epoch> = self.state.epochs().values()
+            .filter(|e| !e.is_planned())
+            .collect();
+        epochs.sort_by_key(|e| e.start_date());
+
+        let mut report = format!("# Team Performance Report: {}\n\n", team_name);
+        if epochs.is_empty() {
+            report.push_str("No epochs have started yet.\n");
+            return Ok(report);
+        }
+
+        let payments = self.get_team_payment_history(team_id);
+        let mut rates: Vec<Option<f64>> = Vec::new();
+        let mut points_per_epoch = Vec::new();
+        let mut total_payments: HashMap<String, f64> = HashMap::new();
+
+        report.push_str("## Epochs\n\n");
+        report.push_str("| Epoch | Points | Proposals Voted | Counted Seats | Uncounted Seats | Participation | Rolling Avg (3) | Payments Received |\n");
+        report.push_str("|-------|--------|-----------------|---------------|-----------------|---------------|-----------------|-------------------|\n");
+
+        for epoch in &epochs {
+            let (mut voted, mut counted_seats, mut uncounted_seats, mut formal_votes) = (0, 0, 0, 0);
+            for vote in self.state.votes().values().filter(|v| v.epoch_id() == epoch.id()) {
+                match vote.participation() {
+                    VoteParticipation::Formal { counted, uncounted } => {
+                        formal_votes += 1;
+                        if counted.contains(&team_id) {
+                            counted_seats += 1;
+                        } else if uncounted.contains(&team_id) {
+                            uncounted_seats += 1;
+                        }
+                    },
+                    VoteParticipation::Informal(participants) => {
+                        if participants.contains(&team_id) {
+                            voted += 1;
+                        }
+                    },
+                }
+            }
+            voted += counted_seats + uncounted_seats;
+
+            let rate = (formal_votes > 0)
+                .then(|| (counted_seats + uncounted_seats) as f64 / formal_votes as f64 * 100.0);
+            rates.push(rate);
+            let window: Vec<f64> = rates.iter().rev().take(3).flatten().copied().collect();
+            let rolling = (!window.is_empty()).then(|| window.iter().sum::<f64>() / window.len() as f64);
+
+            let points = self.calculate_team_points_for_epoch(team_id, epoch.id());
+            points_per_epoch.push(points);
+
+            let mut received: HashMap<String, f64> = HashMap::new();
+            for record in payments.iter().filter(|r| r.epoch_name == epoch.name() && r.payment_date.is_some()) {
+                for (token, amount) in &record.amounts {
+                    *received.entry(token.clone()).or_insert(0.0) += amount;
+                    *total_payments.entry(token.clone()).or_insert(0.0) += amount;
+                }
+            }
+
+            report.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                epoch.name(),
+                points,
+                voted,
+                counted_seats,
+                uncounted_seats,
+                rate.map_or("N/A".to_string(), |r| format!("{:.1}%", r)),
+                rolling.map_or("N/A".to_string(), |r| format!("{:.1}%", r)),
+                if received.is_empty() { "-".to_string() } else { Self::format_export_amounts(&received) },
+            ));
+        }
+
+        report.push_str("\n## Totals\n\n");
+        report.push_str(&format!("- **Points**: {}\n", points_per_epoch.iter().sum::<u32>()));
+        report.push_str(&format!("- **Payments Received**: {}\n",
+            if total_payments.is_empty() { "None".to_string() } else { Self::format_export_amounts(&total_payments) }));
+        report.push_str(&format!("- **Trend**: {}\n", Self::points_trend(&points_per_epoch)));
+
+        Ok(report)
+    }
+
+    /// Compares points of the last epoch with those three epochs back; changes
+    /// within 10% count as stable.
+    fn points_trend(points_per_epoch: &[u32]) -> &'static str {
+        if points_per_epoch.len() < 3 {
+            return "Not enough data (needs 3 epochs)";
+        }
+        let recent = &points_per_epoch[points_per_epoch.len() - 3..];
+        let (first, last) = (recent[0] as f64, recent[2] as f64);
+        let tolerance = first * 0.1;
+        if last > first + tolerance {
+            "Improving"
+        } else if last < first - tolerance {
+            "Declining"
+        } else {
+            "Stable"
+        }
+    }
+
+    /// Saves the team performance report to `output_path`, or by default to
+    /// `reports/<epoch>/<team>_performance.md` for the current or latest epoch.
+    pub fn save_team_performance_report(&self, team_name: &str, output_path: Option<&str>) -> Result<PathBuf, Box<dyn Error>> {
+        let report = self.generate_team_performance_report(team_name)?;
+
+        let output_path = match output_path {
+            Some(path) => PathBuf::from(path),
+            None => {
+                let epoch = self.get_current_epoch()
+                    .or_else(|| self.state.epochs().values()
+                        .filter(|e| !e.is_planned())
+                        .max_by_key(|e| e.start_date()))
+                    .ok_or("No started epoch to file the report under")?;
+                Path::new(&self.config.state_file)
+                    .parent()
+                    .unwrap_or_else(|| Path::new("."))
+                    .join("reports")
+                    .join(FileSystem::sanitize_filename(epoch.name()))
+                    .join(format!("{}_performance.md", FileSystem::sanitize_filename(team_name)))
+            }
+        };
+
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&output_path, report)?;
+
+        Ok(output_path)
+    }
+
     pub fn update_epoch_dates(&mut self, epoch_id: Uuid, new_start: DateTime<Utc>, new_end: DateTime<Utc>) -> Result<(), &'static str> {
         // Check for overlaps with other epochs
         for other_epoch in self.state.epochs().values() {
@@ -415,8 +1515,122 @@ impl BudgetSystem {
         Ok(())
     }
 
+    /// Moves the end date of an active epoch forward. The start date stays fixed, the new
+    /// window must not overlap other epochs, and it has to cover every resolution date
+    /// already recorded for the epoch's proposals.
+    pub fn extend_epoch(&mut self, epoch_name: Option<&str>, new_end: DateTime<Utc>) -> Result<(), BudgetSystemError> {
+        let epoch_id = match epoch_name {
+            Some(name) => self.get_epoch_id_by_name(name)
+                .ok_or_else(|| format!("Epoch not found: {}", name))?,
+            None => self.state.current_epoch().ok_or(BudgetSystemError::NoActiveEpoch)?,
+        };
+        let epoch = self.state.get_epoch(&epoch_id).ok_or(BudgetSystemError::EpochNotFound(epoch_id))?;
+
+        if !epoch.is_active() {
+            return Err(BudgetSystemError::Other(format!("Can only extend an active epoch; '{}' is {:?}", epoch.name(), epoch.status())));
+        }
+        if new_end <= epoch.end_date() {
+            return Err(BudgetSystemError::Other(format!(
+                "New end date {} must be later than the current end date {}",
+                new_end.format("%Y-%m-%d"),
+                epoch.end_date().format("%Y-%m-%d")
+            )));
+        }
+
+        let latest_resolution = epoch.associated_proposals().iter()
+            .filter_map(|id| self.state.get_proposal(id))
+            .filter_map(|p| p.resolved_at())
+            .max();
+        if let Some(latest) = latest_resolution {
+            if new_end.date_naive() < latest {
+                return Err(BudgetSystemError::Other(format!(
+                    "New end date {} is earlier than the latest proposal resolution on {}",
+                    new_end.format("%Y-%m-%d"),
+                    latest
+                )));
+            }
+        }
+
+        let start = epoch.start_date();
+        let overlaps = self.state.epochs().values()
+            .any(|other| other.id() != epoch_id && start < other.end_date() && new_end > other.start_date());
+        if overlaps {
+            return Err(BudgetSystemError::EpochOverlap);
+        }
+
+        let epoch = self.state.get_epoch_mut(&epoch_id).ok_or(BudgetSystemError::EpochNotFound(epoch_id))?;
+        epoch.set_dates(start, new_end)?;
+        self.save_state()?;
+        Ok(())
+    }
+
+    /// Lists epochs by start date with their status, dates, proposal count and
+    /// whether a reward is set. The active epoch is marked with an arrow.
+    pub fn list_epochs(&self, include_closed: bool) -> String {
+        let mut epochs: Vec<&Epoch> = self.state.epochs().values()
+            .filter(|e| include_closed || !e.is_closed())
+            .collect();
+        if epochs.is_empty() {
+            return "No epochs found".to_string();
+        }
+        epochs.sort_by_key(|e| e.start_date());
+
+        let mut output = String::from("| | Name | ID | Status | Start | End | Proposals | Reward |\n");
+        output.push_str("|---|------|----|--------|-------|-----|-----------|--------|\n");
+        for epoch in epochs {
+            let marker = if self.state.current_epoch() == Some(epoch.id()) { "▶" } else { "" };
+            output.push_str(&format!(
+                "| {} | {} | {} | {:?} | {} | {} | {} | {} |\n",
+                marker,
+                epoch.name(),
+                epoch.id(),
+                epoch.status(),
+                epoch.start_date().format("%Y-%m-%d"),
+                epoch.end_date().format("%Y-%m-%d"),
+                epoch.associated_proposals().len(),
+                if epoch.rewards().is_empty() { "No" } else { "Yes" },
+            ));
+        }
+        output
+    }
+
+    /// Teams whose status matches `status_kind` (earner, supporter, inactive, archived,
+    /// or active for earners and supporters; case-insensitive), sorted by name.
+    pub fn get_teams_by_status<'a>(&'a self, status_kind: &str) -> Result<Vec<&'a Team>, BudgetSystemError> {
+        let matches: fn(&Team) -> bool = match status_kind.to_lowercase().as_str() {
+            "earner" => Team::is_earner,
+            "supporter" => Team::is_supporter,
+            "inactive" => Team::is_inactive,
+            "archived" => Team::is_archived,
+            "active" => Team::is_active,
+            _ => return Err(BudgetSystemError::Other(format!(
+                "Invalid team status: {}. Use active, earner, supporter, inactive or archived", status_kind
+            ))),
+        };
+        let mut teams: Vec<&Team> = self.state.current_state().teams().values()
+            .filter(|team| matches(team))
+            .collect();
+        teams.sort_by(|a, b| a.name().cmp(b.name()));
+        Ok(teams)
+    }
+
+    /// Earner and supporter teams, sorted by name.
+    pub fn get_active_teams(&self) -> Vec<&Team> {
+        let mut teams: Vec<&Team> = self.state.current_state().teams().values()
+            .filter(|team| team.is_active())
+            .collect();
+        teams.sort_by(|a, b| a.name().cmp(b.name()));
+        teams
+    }
+
+    /// Looks a team up by name, then by alias (case-insensitive). A merged team keeps
+    /// its name, but it resolves to the team it was merged into.
     pub fn get_team_id_by_name(&self, name: &str) -> Option<Uuid> {
-        get_id_by_name(&self.state.current_state().teams(), name)
+        let teams = self.state.current_state().teams();
+        let team_id = get_id_by_name(teams, name)?;
+        self.team_with_alias(teams.get(&team_id)?.name(), team_id)
+            .map(|target| target.id())
+            .or(Some(team_id))
     }
 
     pub fn get_epoch_id_by_name(&self, name: &str) -> Option<Uuid> {
@@ -427,6 +1641,135 @@ impl BudgetSystem {
         get_id_by_name(&self.state.proposals(), name)
     } 
 
+    /// Team whose name is closest to `input`, with its edit distance; `None` if the closest is ambiguous.
+    pub fn find_closest_team_name(&self, input: &str) -> Option<(Uuid, &str, usize)> {
+        find_closest_name(self.state.current_state().teams().iter().map(|(id, team)| (*id, team.name())), input)
+    }
+
+    /// Proposal whose title is closest to `input`, with its edit distance; `None` if the closest is ambiguous.
+    pub fn find_closest_proposal_name(&self, input: &str) -> Option<(Uuid, &str, usize)> {
+        find_closest_name(self.state.proposals().iter().map(|(id, proposal)| (*id, proposal.title())), input)
+    }
+
+    fn team_not_found(&self, name: &str) -> String {
+        let closest = self.find_closest_team_name(name);
+        let teams = self.state.current_state().teams().values().map(|team| team.name());
+        self.name_not_found("Team", name, closest, teams)
+    }
+
+    fn proposal_not_found(&self, name: &str) -> String {
+        let closest = self.find_closest_proposal_name(name);
+        let titles = self.state.proposals().values().map(|proposal| proposal.title());
+        self.name_not_found("Proposal", name, closest, titles)
+    }
+
+    /// Not-found message for a name lookup when fuzzy matching is on: the closest name if it
+    /// is unambiguous, otherwise every name that ties for closest.
+    fn name_not_found<'a>(
+        &self,
+        kind: &str,
+        name: &str,
+        closest: Option<(Uuid, &'a str, usize)>,
+        candidates: impl IntoIterator<Item = &'a str>,
+    ) -> String {
+        let suggestions = if !self.config.allow_fuzzy_match {
+            Vec::new()
+        } else if let Some((_, closest, distance)) = closest {
+            if distance <= suggestion_max_distance(name) { vec![closest] } else { Vec::new() }
+        } else {
+            let mut candidates: Vec<&str> = candidates.into_iter().collect();
+            candidates.sort();
+            suggest_names(name, candidates, MAX_NAME_SUGGESTIONS)
+        };
+        if suggestions.is_empty() {
+            format!("{} not found: {}", kind, name)
+        } else {
+            format!("{} '{}' not found. Did you mean: {}?", kind, name, suggestions.join(", "))
+        }
+    }
+
+    /// Adds the proposals in a JSON array file, where each element has the fields of
+    /// `Command::AddProposal`. Entries are processed in order; a failing entry is
+    /// recorded in the summary and skipped without aborting the rest of the batch.
+    pub fn import_proposals(&mut self, file_path: &str, dry_run: bool) -> Result<ImportSummary, Box<dyn Error>> {
+        let contents = fs::read_to_string(file_path)?;
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&contents)
+            .map_err(|e| format!("Expected a JSON array of proposals: {}", e))?;
+
+        let mut summary = ImportSummary {
+            total: entries.len(),
+            imported: 0,
+            skipped: 0,
+            dry_run,
+            errors: Vec::new(),
+        };
+        let mut seen_titles: Vec<String> = Vec::new();
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            let title = entry.get("title").and_then(|t| t.as_str()).map(String::from);
+            match self.import_proposal_entry(entry, &seen_titles, dry_run) {
+                Ok(title) => {
+                    seen_titles.push(title);
+                    summary.imported += 1;
+                },
+                Err(e) => {
+                    summary.skipped += 1;
+                    summary.errors.push(ImportError { index, title, error: e.to_string() });
+                },
+            }
+        }
+
+        Ok(summary)
+    }
+
+    fn import_proposal_entry(
+        &mut self,
+        entry: serde_json::Value,
+        seen_titles: &[String],
+        dry_run: bool,
+    ) -> Result<String, BudgetSystemError> {
+        let command: Command = serde_json::from_value(serde_json::json!({ "type": "AddProposal", "params": entry }))
+            .map_err(|e| format!("Invalid proposal: {}", e))?;
+        let Command::AddProposal { title, url, budget_request_details, announced_at, published_at, is_historical } = command else {
+            unreachable!("AddProposal tag always deserializes to Command::AddProposal");
+        };
+
+        if self.get_proposal_id_by_name(&title).is_some()
+            || seen_titles.contains(&title) {
+            return Err(format!("Duplicate proposal title: {}", title).into());
+        }
+        if self.state.current_epoch().is_none() {
+            return Err(BudgetSystemError::NoActiveEpoch);
+        }
+
+        let category = budget_request_details.as_ref().and_then(|d| d.category.clone());
+        let ipfs_hash = budget_request_details.as_ref().and_then(|d| d.ipfs_hash.clone());
+        if let Some(hash) = &ipfs_hash {
+            validate_ipfs_hash(hash)?;
+        }
+        let budget_request_details = budget_request_details.map(|details| {
+            let team_id = details.team
+                .map(|name| self.get_team_id_by_name(&name).ok_or_else(|| self.team_not_found(&name)))
+                .transpose()?;
+            BudgetRequestDetails::new(
+                team_id,
+                details.request_amounts.unwrap_or_default(),
+                details.start_date,
+                details.end_date,
+                details.is_loan,
+                details.payment_address,
+            ).map_err(BudgetSystemError::from)
+        }).transpose()?;
+
+        if !dry_run {
+            let proposal_id = self.add_proposal(title.clone(), url, budget_request_details, announced_at, published_at, is_historical, category)?;
+            if let Some(hash) = ipfs_hash {
+                self.set_proposal_ipfs_hash(proposal_id, hash)?;
+            }
+        }
+        Ok(title)
+    }
+
     pub fn import_predefined_raffle(
         &mut self,
         proposal_name: &str,
@@ -436,7 +1779,7 @@ impl BudgetSystem {
         max_earner_seats: usize
     ) -> Result<Uuid, Box<dyn Error>> {
         let proposal_id = self.get_proposal_id_by_name(proposal_name)
-            .ok_or_else(|| format!("Proposal not found: {}", proposal_name))?;
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
         
         let epoch_id = self.state.current_epoch()
             .ok_or("No active epoch")?;
@@ -498,11 +1841,10 @@ impl BudgetSystem {
         uncounted_points: Option<u32>
     ) -> Result<Uuid, Box<dyn Error>> {
         let proposal_id = self.get_proposal_id_by_name(proposal_name)
-            .ok_or_else(|| format!("Proposal not found: {}", proposal_name))?;
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
     
-        let raffle_id = self.state.raffles().iter()
-            .find(|(_, raffle)| raffle.config().proposal_id() == proposal_id)
-            .map(|(id, _)| *id)
+        let raffle_id = self.get_raffle_for_proposal(proposal_id)
+            .map(|r| r.id())
             .ok_or_else(|| format!("No raffle found for proposal: {}", proposal_name))?;
 
         let raffle = self.state.get_raffle(&raffle_id)
@@ -515,7 +1857,9 @@ impl BudgetSystem {
             total_eligible_seats: raffle.config().total_counted_seats() as u32,
             threshold: self.config.default_qualified_majority_threshold,
             counted_points: counted_points.unwrap_or(self.config.counted_vote_points),
-            uncounted_points: uncounted_points.unwrap_or(self.config.uncounted_vote_points)
+            uncounted_points: uncounted_points.unwrap_or(self.config.uncounted_vote_points),
+            abstain_points: self.config.abstain_points,
+            quorum: self.config.default_quorum,
         };
     
         let mut vote = Vote::new(proposal_id, epoch_id, vote_type, true);
@@ -607,58 +1951,61 @@ impl BudgetSystem {
         }
     }
 
+    /// Lists every team, with archived teams in a separate section at the end.
     pub fn print_team_report(&self) -> String {
         let mut teams: Vec<&Team> = self.state.current_state().teams().values().collect();
         teams.sort_by(|a, b| a.name().cmp(&b.name()));
+        let (archived, current): (Vec<&Team>, Vec<&Team>) = teams.into_iter().partition(|team| team.is_archived());
 
         let mut report = String::from("Team Report:\n\n");
+        for team in current {
+            report.push_str(&self.format_team_report_entry(team));
+        }
+        if !archived.is_empty() {
+            report.push_str("Archived Teams:\n\n");
+            for team in archived {
+                report.push_str(&self.format_team_report_entry(team));
+            }
+        }
 
-        for team in teams {
-            report.push_str(&format!("Name: {}\n", team.name()));
-            report.push_str(&format!("ID: {}\n", team.id()));
-            report.push_str(&format!("Representative: {}\n", team.representative()));
-            report.push_str(&format!("Status: {:?}\n", team.status()));
+        report
+    }
 
-            if let TeamStatus::Earner { trailing_monthly_revenue } = &team.status() {
-                report.push_str(&format!("Trailing Monthly Revenue: {:?}\n", trailing_monthly_revenue));
-            }
+    fn format_team_report_entry(&self, team: &Team) -> String {
+        let mut report = String::new();
+        report.push_str(&format!("Name: {}\n", team.name()));
+        report.push_str(&format!("ID: {}\n", team.id()));
+        report.push_str(&format!("Representative: {}\n", team.representative()));
+        report.push_str(&format!("Status: {:?}\n", team.status()));
+
+        if let TeamStatus::Earner { trailing_monthly_revenue } = &team.status() {
+            report.push_str(&format!("Trailing Monthly Revenue: {:?}\n", trailing_monthly_revenue));
+        }
 
-            // Add a breakdown of points per epoch
-            report.push_str("Points per Epoch:\n");
-            for epoch in self.state.epochs().values() {
-                let epoch_points = self.get_team_points_for_epoch(team.id(), epoch.id()).unwrap_or(0);
-                report.push_str(&format!("  {}: {} points\n", epoch.name(), epoch_points));
+        if team.address_history().len() > 1 {
+            report.push_str("Payment Address History:\n");
+            for change in team.address_history() {
+                let address = change.address().map_or("(cleared)".to_string(), |a| format!("{:?}", a));
+                report.push_str(&format!("  {}: {}\n", change.changed_at().format("%Y-%m-%d %H:%M:%S UTC"), address));
             }
+        }
 
-            report.push_str("\n");
+        // Add a breakdown of points per epoch
+        report.push_str("Points per Epoch:\n");
+        for epoch in self.state.epochs().values() {
+            let epoch_points = self.get_team_points_for_epoch(team.id(), epoch.id()).unwrap_or(0);
+            report.push_str(&format!("  {}: {} points\n", epoch.name(), epoch_points));
         }
 
+        report.push('\n');
+
         report
     }
 
-    pub fn print_epoch_state(&self) -> Result<String, Box<dyn Error>> {
+    pub fn get_epoch_state_report(&self) -> Result<EpochStateReport, Box<dyn Error>> {
         let epoch = self.get_current_epoch().ok_or("No active epoch")?;
         let proposals = self.get_proposals_for_epoch(epoch.id());
 
-        let mut report = String::new();
-
-        // Epoch overview
-        report.push_str(&format!("*State of Epoch {}*\n\n", escape_markdown(&epoch.name())));
-        report.push_str("🌍 *Overview*\n");
-        report.push_str(&format!("ID: `{}`\n", epoch.id()));
-        report.push_str(&format!("Start Date: `{}`\n", epoch.start_date().format("%Y-%m-%d %H:%M:%S UTC")));
-        report.push_str(&format!("End Date: `{}`\n", epoch.end_date().format("%Y-%m-%d %H:%M:%S UTC")));
-        report.push_str(&format!("Status: `{:?}`\n", epoch.status()));
-
-        if let Some(reward) = epoch.reward() {
-            report.push_str(&format!("Epoch Reward: `{} {}`\n", reward.amount(), escape_markdown(reward.token())));
-        } else {
-            report.push_str("Epoch Reward: `Not set`\n");
-        }
-
-        report.push_str("\n");
-
-        // Proposal counts
         let mut open_proposals = Vec::new();
         let mut approved_count = 0;
         let mut rejected_count = 0;
@@ -671,49 +2018,226 @@ impl BudgetSystem {
                 Some(Resolution::Retracted) => retracted_count += 1,
                 _ => {
                     if proposal.is_actionable() {
-                        open_proposals.push(proposal);
+                        let details = proposal.budget_request_details();
+                        open_proposals.push(OpenProposalSummary {
+                            title: proposal.title().to_string(),
+                            url: proposal.url().map(|u| u.to_string()),
+                            start_date: details.and_then(|d| d.start_date()),
+                            end_date: details.and_then(|d| d.end_date()),
+                            amounts: details.map(|d| d.request_amounts().clone()).unwrap_or_default(),
+                            days_open: self.days_open(proposal),
+                            reviewers: proposal.reviewers().to_vec(),
+                        });
                     }
                 }
             }
         }
 
+        let mut budget_headroom: Vec<(String, f64)> = if self.config.epoch_budget_caps.is_empty() {
+            Vec::new()
+        } else {
+            self.get_epoch_budget_headroom(epoch.id(), self.config.epoch_budget_caps.clone())?
+                .into_iter()
+                .collect()
+        };
+        budget_headroom.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(EpochStateReport {
+            epoch_id: epoch.id(),
+            epoch_name: epoch.name().to_string(),
+            start_date: epoch.start_date(),
+            end_date: epoch.end_date(),
+            status: format!("{:?}", epoch.status()),
+            rewards: epoch.rewards().iter().map(|r| (r.token().to_string(), r.amount())).collect(),
+            total_proposals: proposals.len(),
+            approved_count,
+            rejected_count,
+            retracted_count,
+            open_proposals,
+            budget_headroom,
+        })
+    }
+
+    /// Committed, paid and reward pool totals for an epoch. Proposals count towards the
+    /// epoch they belong to, whatever their start and end dates.
+    pub fn get_treasury_report(&self, epoch_name: &str) -> Result<TreasuryReport, BudgetSystemError> {
+        let epoch_id = self.get_epoch_id_by_name(epoch_name)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_name)))?;
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_name)))?;
+
+        let mut flows: HashMap<String, TreasuryTokenFlow> = HashMap::new();
+        let new_flow = |token: &str| TreasuryTokenFlow { token: token.to_string(), ..Default::default() };
+
+        let approved = self.get_proposals_for_epoch(epoch_id).into_iter()
+            .filter(|p| p.is_approved())
+            .filter_map(|p| p.budget_request_details());
+        for details in approved {
+            for (token, &amount) in details.request_amounts() {
+                let entry = flows.entry(token.clone()).or_insert_with(|| new_flow(token));
+                entry.approved += amount;
+                if details.is_paid() {
+                    entry.paid += amount;
+                } else {
+                    entry.committed += amount;
+                }
+            }
+        }
+        for reward in epoch.rewards() {
+            flows.entry(reward.token().to_string())
+                .or_insert_with(|| new_flow(reward.token()))
+                .reward_pool += reward.amount();
+        }
+
+        let mut tokens: Vec<TreasuryTokenFlow> = flows.into_values().collect();
+        tokens.sort_by(|a, b| a.token.cmp(&b.token));
+
+        let mut grouped: Vec<TreasuryTokenFlow> = Vec::new();
+        for token in &tokens {
+            let column = token_column(&token.token);
+            let index = match grouped.iter().position(|g| g.token == column) {
+                Some(index) => index,
+                None => {
+                    grouped.push(new_flow(&column));
+                    grouped.len() - 1
+                }
+            };
+            let entry = &mut grouped[index];
+            entry.approved += token.approved;
+            entry.committed += token.committed;
+            entry.paid += token.paid;
+            entry.reward_pool += token.reward_pool;
+        }
+        grouped.sort_by(|a, b| a.token.cmp(&b.token));
+
+        Ok(TreasuryReport {
+            generated_at: Utc::now(),
+            epoch_name: epoch.name().to_string(),
+            tokens,
+            grouped,
+        })
+    }
+
+    /// Markdown summary of `get_treasury_report`.
+    pub fn generate_treasury_report(&self, epoch_name: &str) -> Result<String, BudgetSystemError> {
+        let report = self.get_treasury_report(epoch_name)?;
+
+        let mut output = format!("# Treasury Report: {}\n\n", report.epoch_name);
+        if report.tokens.is_empty() {
+            output.push_str("No approved requests or rewards in this epoch\n");
+            return Ok(output);
+        }
+
+        let table = |flows: &[TreasuryTokenFlow]| {
+            let mut table = String::from("| Token | Approved | Committed | Paid | Reward Pool | Total Outflow |\n");
+            table.push_str("|-------|----------|-----------|------|-------------|---------------|\n");
+            for flow in flows {
+                table.push_str(&format!("| {} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} |\n",
+                    flow.token, flow.approved, flow.committed, flow.paid, flow.reward_pool, flow.total_outflow()));
+            }
+            table
+        };
+
+        output.push_str("## By Token\n\n");
+        output.push_str(&table(&report.tokens));
+        if report.grouped.len() < report.tokens.len() {
+            output.push_str("\n## Stablecoins Grouped\n\n");
+            output.push_str(&table(&report.grouped));
+        }
+
+        let unreconciled: Vec<&str> = report.tokens.iter()
+            .filter(|flow| !flow.reconciles())
+            .map(|flow| flow.token.as_str())
+            .collect();
+        if unreconciled.is_empty() {
+            output.push_str("\nCommitted and paid amounts reconcile with approved requests.\n");
+        } else {
+            output.push_str(&format!("\nWarning: committed and paid amounts do not add up to approved requests for {}\n",
+                unreconciled.join(", ")));
+        }
+        Ok(output)
+    }
+
+    pub fn print_epoch_state(&self) -> Result<String, Box<dyn Error>> {
+        let state = self.get_epoch_state_report()?;
+
+        let mut report = String::new();
+
+        // Epoch overview
+        report.push_str(&format!("*State of Epoch {}*\n\n", escape_markdown(&state.epoch_name)));
+        report.push_str("🌍 *Overview*\n");
+        report.push_str(&format!("ID: `{}`\n", state.epoch_id));
+        report.push_str(&format!("Start Date: `{}`\n", state.start_date.format("%Y-%m-%d %H:%M:%S UTC")));
+        report.push_str(&format!("End Date: `{}`\n", state.end_date.format("%Y-%m-%d %H:%M:%S UTC")));
+        report.push_str(&format!("Status: `{}`\n", state.status));
+
+        if state.rewards.is_empty() {
+            report.push_str("Epoch Reward: `Not set`\n");
+        } else {
+            for (token, amount) in &state.rewards {
+                report.push_str(&format!("Epoch Reward: `{} {}`\n", amount, escape_markdown(token)));
+            }
+        }
+
+        report.push('\n');
+
+        // Proposal counts
         report.push_str("📊 *Proposals*\n");
-        report.push_str(&format!("Total: `{}`\n", proposals.len()));
-        report.push_str(&format!("Open: `{}`\n", open_proposals.len()));
-        report.push_str(&format!("Approved: `{}`\n", approved_count));
-        report.push_str(&format!("Rejected: `{}`\n", rejected_count));
-        report.push_str(&format!("Retracted: `{}`\n", retracted_count));
+        report.push_str(&format!("Total: `{}`\n", state.total_proposals));
+        report.push_str(&format!("Open: `{}`\n", state.open_proposals.len()));
+        report.push_str(&format!("Approved: `{}`\n", state.approved_count));
+        report.push_str(&format!("Rejected: `{}`\n", state.rejected_count));
+        report.push_str(&format!("Retracted: `{}`\n", state.retracted_count));
 
         report.push_str("\n");
 
+        // Budget headroom, only when caps are configured
+        if !state.budget_headroom.is_empty() {
+            report.push_str("🏦 *Budget headroom*\n");
+            for (token, headroom) in &state.budget_headroom {
+                let note = if *headroom < 0.0 { " \\(over budget\\)" } else { "" };
+                report.push_str(&format!("{}: `{}`{}\n", escape_markdown(token), headroom, note));
+            }
+            report.push('\n');
+        }
+
         // Open proposals
-        if !open_proposals.is_empty() {
+        if !state.open_proposals.is_empty() {
             report.push_str("📬 *Open proposals*\n\n");
         
-            for proposal in open_proposals {
-                report.push_str(&format!("*{}*\n", escape_markdown(proposal.title())));
-                if let Some(url) = proposal.url() {
+            for proposal in &state.open_proposals {
+                report.push_str(&format!("*{}*\n", escape_markdown(&proposal.title)));
+                if let Some(url) = &proposal.url {
                     report.push_str(&format!("🔗 {}\n", escape_markdown(url)));
                 }
-                if let Some(details) = proposal.budget_request_details() {
-                    if let (Some(start), Some(end)) = (details.start_date(), details.end_date()) {
-                        report.push_str(&format!("📆 {} \\- {}\n", 
-                            escape_markdown(&start.format("%b %d").to_string()),
-                            escape_markdown(&end.format("%b %d").to_string())
-                        ));
-                    }
-                    if !details.request_amounts().is_empty() {
-                        let amounts: Vec<String> = details.request_amounts().iter()
-                            .map(|(token, amount)| format!("{} {}", 
-                                escape_markdown(&amount.to_string()), 
-                                escape_markdown(token)
-                            ))
-                            .collect();
-                        report.push_str(&format!("💰 {}\n", amounts.join(", ")));
+                if let (Some(start), Some(end)) = (proposal.start_date, proposal.end_date) {
+                    report.push_str(&format!("📆 {} \\- {}\n", 
+                        escape_markdown(&start.format("%b %d").to_string()),
+                        escape_markdown(&end.format("%b %d").to_string())
+                    ));
+                }
+                if !proposal.amounts.is_empty() {
+                    let amounts: Vec<String> = proposal.amounts.iter()
+                        .map(|(token, amount)| format!("{} {}", 
+                            escape_markdown(&amount.to_string()), 
+                            escape_markdown(token)
+                        ))
+                        .collect();
+                    report.push_str(&format!("💰 {}\n", amounts.join(", ")));
+                }
+                if !proposal.reviewers.is_empty() {
+                    let signed = proposal.reviewers.iter().filter(|r| r.is_signed_off()).count();
+                    let pending: Vec<&str> = proposal.reviewers.iter()
+                        .filter(|r| !r.is_signed_off())
+                        .map(|r| r.name())
+                        .collect();
+                    let mut line = format!("{}/{} signed off", signed, proposal.reviewers.len());
+                    if !pending.is_empty() {
+                        line.push_str(&format!(" (pending: {})", pending.join(", ")));
                     }
+                    report.push_str(&format!("✍️ {}\n", escape_markdown(&line)));
                 }
-                let days_open = self.days_open(proposal);
-                report.push_str(&format!("⏳ _{} days open_\n\n", escape_markdown(&days_open.to_string())));
+                report.push_str(&format!("⏳ _{} days open_\n\n", escape_markdown(&proposal.days_open.to_string())));
             }
         }
 
@@ -722,7 +2246,7 @@ impl BudgetSystem {
 
     pub fn print_team_vote_participation(&self, team_name: &str, epoch_name: Option<&str>) -> Result<String, Box<dyn Error>> {
         let team_id = self.get_team_id_by_name(team_name)
-            .ok_or_else(|| format!("Team not found: {}", team_name))?;
+            .ok_or_else(|| self.team_not_found(team_name))?;
     
         let epoch = if let Some(name) = epoch_name {
             self.state.epochs().values()
@@ -738,18 +2262,15 @@ impl BudgetSystem {
         let mut vote_reports = Vec::new();
         let mut total_points = 0;
     
-        for vote_id in epoch.associated_proposals().iter()
-            .filter_map(|proposal_id| self.state.votes().values()
-                .find(|v| v.proposal_id() == *proposal_id)
-                .map(|v| v.id())) 
+        for vote in epoch.associated_proposals().iter()
+            .filter_map(|proposal_id| self.get_vote_for_proposal(*proposal_id))
         {
-            let vote = self.state.get_vote(&vote_id).expect("Could not get Vote");
             let (participation_status, points) = match (vote.vote_type(), vote.participation()) {
-                (VoteType::Formal { counted_points, uncounted_points, .. }, VoteParticipation::Formal { counted, uncounted }) => {
+                (VoteType::Formal { .. }, VoteParticipation::Formal { counted, uncounted }) => {
                     if counted.contains(&team_id) {
-                        (Some("Counted"), *counted_points)
+                        (Some("Counted"), vote.points_for_team(team_id))
                     } else if uncounted.contains(&team_id) {
-                        (Some("Uncounted"), *uncounted_points)
+                        (Some("Uncounted"), vote.points_for_team(team_id))
                     } else {
                         (None, 0)
                     }
@@ -766,7 +2287,7 @@ impl BudgetSystem {
     
             if let Some(status) = participation_status {
                 let proposal = self.state.proposals().get(&vote.proposal_id())
-                    .ok_or_else(|| format!("Proposal not found for vote: {}", vote_id))?;
+                    .ok_or_else(|| format!("Proposal not found for vote: {}", vote.id()))?;
     
                 let vote_type = match vote.vote_type() {
                     VoteType::Formal { .. } => "Formal",
@@ -790,7 +2311,7 @@ impl BudgetSystem {
                         Participation: {}\n\
                         Result: {}\n\
                         Points Earned: {}\n\n",
-                        vote_id, proposal.title(), vote_type, status, result, points
+                        vote.id(), proposal.title(), vote_type, status, result, points
                     )
                 ));
             }
@@ -820,9 +2341,140 @@ impl BudgetSystem {
         Utc::now().date_naive().signed_duration_since(announced_date).num_days()
     }
 
+    /// One 1-based page of proposals, oldest first, and the number of proposals matching
+    /// the filters. Without an epoch, proposals from every epoch are listed.
+    pub fn list_proposals_paginated(
+        &self,
+        epoch_id: Option<Uuid>,
+        status_filter: Option<ProposalListFilter>,
+        team_id: Option<Uuid>,
+        unpaid_only: bool,
+        page: usize,
+        page_size: usize,
+    ) -> (Vec<&Proposal>, usize) {
+        let mut proposals: Vec<&Proposal> = match epoch_id {
+            Some(id) => self.get_proposals_for_epoch(id),
+            None => self.state.proposals().values().collect(),
+        };
+        proposals.retain(|p| status_filter.is_none_or(|filter| filter.matches(p)));
+        proposals.retain(|p| !unpaid_only || ProposalListFilter::Unpaid.matches(p));
+        if let Some(team_id) = team_id {
+            proposals.retain(|p| p.budget_request_details().is_some_and(|d| d.includes_team(team_id)));
+        }
+        proposals.sort_by_key(|p| (p.published_at().or(p.announced_at()), p.title().to_string()));
+
+        let total = proposals.len();
+        let proposals = proposals.into_iter()
+            .skip(page.saturating_sub(1) * page_size)
+            .take(page_size)
+            .collect();
+        (proposals, total)
+    }
+
+    /// Percentiles of announcement-to-resolution time for resolved proposals,
+    /// either in one epoch or across all epochs.
+    pub fn get_proposal_waiting_time_percentiles(&self, epoch_id: Option<Uuid>) -> Result<WaitingTimeStats, BudgetSystemError> {
+        let proposals: Vec<&Proposal> = match epoch_id {
+            Some(id) => {
+                if self.state.get_epoch(&id).is_none() {
+                    return Err(BudgetSystemError::Other(format!("Epoch not found: {}", id)));
+                }
+                self.get_proposals_for_epoch(id)
+            },
+            None => self.state.proposals().values().collect(),
+        };
+
+        let mut waiting_days: Vec<f64> = proposals.iter()
+            .filter(|p| p.resolution().is_some())
+            .filter_map(|p| match (p.announced_at(), p.resolved_at()) {
+                (Some(announced), Some(resolved)) => Some(resolved.signed_duration_since(announced).num_days() as f64),
+                _ => None,
+            })
+            .collect();
+
+        if waiting_days.is_empty() {
+            return Err(BudgetSystemError::Other("No resolved proposals with announcement and resolution dates".to_string()));
+        }
+        waiting_days.sort_by(|a, b| a.total_cmp(b));
+
+        Ok(WaitingTimeStats {
+            p50_days: Self::percentile(&waiting_days, 50.0),
+            p75_days: Self::percentile(&waiting_days, 75.0),
+            p90_days: Self::percentile(&waiting_days, 90.0),
+            p95_days: Self::percentile(&waiting_days, 95.0),
+            p99_days: Self::percentile(&waiting_days, 99.0),
+            sample_size: waiting_days.len(),
+        })
+    }
+
+    /// Linear interpolation between the closest ranks of a sorted, non-empty sample.
+    fn percentile(sorted: &[f64], pct: f64) -> f64 {
+        let rank = pct / 100.0 * (sorted.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+    }
+
+    /// Shows who would take part in a raffle created now, with ticket counts and the
+    /// chance of a counted seat under the configured seat parameters. Nothing is stored.
+    pub fn print_upcoming_raffle_eligibility(&self, excluded_teams: Option<Vec<String>>) -> Result<String, BudgetSystemError> {
+        let epoch = self.get_current_epoch()
+            .ok_or_else(|| BudgetSystemError::Other("No active epoch".to_string()))?;
+
+        let mut excluded_ids = HashSet::new();
+        for name in excluded_teams.unwrap_or_default() {
+            let team_id = self.get_team_id_by_name(&name)
+                .ok_or_else(|| BudgetSystemError::Other(self.team_not_found(&name)))?;
+            excluded_ids.insert(team_id);
+        }
+
+        let mut teams: Vec<&Team> = self.state.current_state().teams().values()
+            .filter(|t| t.is_active())
+            .collect();
+        teams.sort_by(|a, b| a.name().cmp(b.name()));
+
+        let (excluded, eligible): (Vec<&Team>, Vec<&Team>) = teams.into_iter()
+            .partition(|t| excluded_ids.contains(&t.id()));
+        let (earners, supporters): (Vec<&Team>, Vec<&Team>) = eligible.into_iter()
+            .partition(|t| matches!(t.status(), TeamStatus::Earner { .. }));
+
+        let total_seats = self.config.default_total_counted_seats;
+        let earner_seats = self.config.default_max_earner_seats.min(earners.len());
+        let supporter_seats = total_seats.saturating_sub(earner_seats);
+
+        let mut report = format!(
+            "Raffle eligibility for epoch '{}' ({} counted seats, up to {} for earners)\n",
+            epoch.name(), total_seats, self.config.default_max_earner_seats
+        );
+
+        for (label, group, seats) in [("Earners", &earners, earner_seats), ("Supporters", &supporters, supporter_seats)] {
+            let tickets: Vec<u64> = group.iter()
+                .map(|t| Raffle::ticket_count_in_window(t.status(), Some(self.config.revenue_window_months)))
+                .collect();
+            let probabilities = Raffle::counted_seat_probabilities(&tickets, seats);
+
+            report.push_str(&format!("\n{} ({} teams, {} tickets, {} counted seats):\n",
+                label, group.len(), tickets.iter().sum::<u64>(), seats.min(group.len())));
+            if group.is_empty() {
+                report.push_str("  None\n");
+            }
+            for ((team, ticket_count), probability) in group.iter().zip(&tickets).zip(&probabilities) {
+                report.push_str(&format!("  {}: {} ticket(s), {:.1}% chance of a counted seat\n",
+                    team.name(), ticket_count, probability * 100.0));
+            }
+        }
+
+        if !excluded.is_empty() {
+            let names: Vec<&str> = excluded.iter().map(|t| t.name()).collect();
+            report.push_str(&format!("\nExcluded: {}\n", names.join(", ")));
+        }
+
+        Ok(report)
+    }
+
     pub fn prepare_raffle(&mut self, proposal_name: &str, excluded_teams: Option<Vec<String>>, app_config: &AppConfig) -> Result<(Uuid, Vec<RaffleTicket>), Box<dyn Error>> {
         let proposal_id = self.get_proposal_id_by_name(proposal_name)
-            .ok_or_else(|| format!("Proposal not found: {}", proposal_name))?;
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
         let epoch_id = self.state.current_epoch()
             .ok_or("No active epoch")?;
 
@@ -832,7 +2484,7 @@ impl BudgetSystem {
                 .collect::<Vec<Uuid>>()
         }).unwrap_or_else(Vec::new);
 
-        let raffle_config = RaffleConfig::new(
+        let mut raffle_config = RaffleConfig::new(
             proposal_id,
             epoch_id,
             app_config.default_total_counted_seats,
@@ -845,6 +2497,7 @@ impl BudgetSystem {
             None,
             false
         );
+        raffle_config.set_revenue_window_months(Some(app_config.revenue_window_months));
 
         let raffle = Raffle::new(raffle_config, &self.state.current_state().teams())?;
         let tickets = raffle.tickets().to_vec();
@@ -865,7 +2518,7 @@ impl BudgetSystem {
         max_earner_seats: Option<usize>
     ) -> Result<(Uuid, Raffle), Box<dyn Error>> {
         let proposal_id = self.get_proposal_id_by_name(proposal_name)
-            .ok_or_else(|| format!("Proposal not found: {}", proposal_name))?;
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
     
         let epoch_id = self.state.current_epoch()
             .ok_or("No active epoch")?;
@@ -915,9 +2568,39 @@ impl BudgetSystem {
         Ok((raffle_id, raffle))
     }
 
-    pub async fn finalize_raffle(&mut self, raffle_id: Uuid, initiation_block: u64, randomness_block: u64, randomness: String) -> Result<Raffle, Box<dyn Error>> {
+    /// Changes the default max earner seats for new raffles without a restart.
+    /// Returns the IDs of unfinalized raffles whose max earner seats exceed the new value;
+    /// those raffles keep their own setting and are only reported.
+    pub fn set_default_max_earner_seats(&mut self, max_earner_seats: usize) -> Result<Vec<Uuid>, BudgetSystemError> {
+        if max_earner_seats > self.config.default_total_counted_seats {
+            return Err(BudgetSystemError::Other(format!(
+                "max_earner_seats ({}) cannot be greater than default_total_counted_seats ({})",
+                max_earner_seats, self.config.default_total_counted_seats
+            )));
+        }
+
+        self.config.default_max_earner_seats = max_earner_seats;
+        info!("Default max earner seats set to {}", max_earner_seats);
+
+        let mut exceeding: Vec<(Uuid, usize)> = self.state.raffles().iter()
+            .filter(|(_, raffle)| !raffle.is_completed() && raffle.config().max_earner_seats() > max_earner_seats)
+            .map(|(id, raffle)| (*id, raffle.config().max_earner_seats()))
+            .collect();
+        exceeding.sort_by_key(|(id, _)| *id);
+
+        for (raffle_id, seats) in &exceeding {
+            log::warn!(
+                "Unfinalized raffle {} has max_earner_seats {} which exceeds the new default of {}",
+                raffle_id, seats, max_earner_seats
+            );
+        }
+
+        Ok(exceeding.into_iter().map(|(id, _)| id).collect())
+    }
+
+    pub async fn finalize_raffle(&mut self, raffle_id: Uuid, initiation_block: u64, randomness_block: u64, randomness: String) -> Result<Raffle, BudgetSystemError> {
         let raffle = self.state.get_raffle_mut(&raffle_id)
-            .ok_or_else(|| format!("Raffle not found: {}", raffle_id))?;
+            .ok_or(BudgetSystemError::RaffleNotFound(raffle_id))?;
     
         raffle.config_mut().set_initiation_block(initiation_block);
         raffle.config_mut().set_randomness_block(randomness_block);
@@ -928,10 +2611,280 @@ impl BudgetSystem {
     
         let raffle_clone = raffle.clone();
         let _ = self.save_state()?;
-    
+
+        let proposal_title = self.state.get_proposal(&raffle_clone.config().proposal_id())
+            .map(|p| p.title().to_string())
+            .unwrap_or_else(|| raffle_clone.config().proposal_id().to_string());
+        let team_names = |ids: &[Uuid]| -> String {
+            let names: Vec<String> = ids.iter()
+                .map(|id| self.get_team(id).map(|t| t.name().to_string()).unwrap_or_else(|| id.to_string()))
+                .collect();
+            if names.is_empty() { "None".to_string() } else { names.join(", ") }
+        };
+        let mut embed = DiscordEmbed::new(proposal_title, COLOR_BLUE);
+        if let Some(result) = raffle_clone.result() {
+            embed = embed
+                .field("Counted", team_names(result.counted()), false)
+                .field("Uncounted", team_names(result.uncounted()), false);
+        }
+        self.notify_discord(DiscordPayload::new("Raffle completed").with_embed(embed));
+
         Ok(raffle_clone)
     }
 
+    /// Replays a completed raffle from its stored configuration and team snapshots and
+    /// compares the counted and uncounted teams with the recorded result.
+    pub fn verify_raffle(&self, raffle_id: Uuid) -> Result<RaffleVerification, BudgetSystemError> {
+        let raffle = self.state.get_raffle(&raffle_id)
+            .ok_or(BudgetSystemError::RaffleNotFound(raffle_id))?;
+        let recorded = raffle.result()
+            .ok_or_else(|| format!("Raffle {} has no result to verify", raffle_id))?;
+        if raffle.is_historical() && raffle.config().block_randomness() == "N/A" {
+            return Err(format!("Raffle {} was imported with a predefined result and has no randomness to replay", raffle_id).into());
+        }
+        let recomputed = raffle.recompute_result()?;
+
+        let team_name = |id: &Uuid| raffle.team_snapshots().iter()
+            .find(|s| s.id() == *id)
+            .map(|s| s.name().to_string())
+            .unwrap_or_else(|| id.to_string());
+        let mut discrepancies = Vec::new();
+        for snapshot in raffle.team_snapshots() {
+            let id = snapshot.id();
+            let seat = |result: &RaffleResult| {
+                if result.counted().contains(&id) {
+                    "counted"
+                } else if result.uncounted().contains(&id) {
+                    "uncounted"
+                } else {
+                    "not seated"
+                }
+            };
+            let (recorded_seat, recomputed_seat) = (seat(recorded), seat(&recomputed));
+            if recorded_seat != recomputed_seat {
+                discrepancies.push(format!(
+                    "{}: recorded as {}, replay gives {}", team_name(&id), recorded_seat, recomputed_seat
+                ));
+            }
+        }
+        for id in recorded.counted().iter().chain(recorded.uncounted()) {
+            if !raffle.team_snapshots().iter().any(|s| s.id() == *id) {
+                discrepancies.push(format!("{}: seated in the recorded result but has no team snapshot", team_name(id)));
+            }
+        }
+
+        Ok(RaffleVerification { matches: discrepancies.is_empty(), discrepancies })
+    }
+
+    /// Re-fetches the randomness of the raffle's randomness block from the Ethereum
+    /// service and replays the draw with it. `Ok(true)` when the fetched randomness
+    /// equals the stored value and reproduces the stored counted and uncounted teams.
+    pub async fn verify_raffle_randomness_on_chain(&self, raffle_id: Uuid) -> Result<bool, BudgetSystemError> {
+        let raffle = self.state.get_raffle(&raffle_id)
+            .ok_or(BudgetSystemError::RaffleNotFound(raffle_id))?;
+        let recorded = raffle.result()
+            .ok_or_else(|| format!("Raffle {} has no result to verify", raffle_id))?;
+        if raffle.is_historical() && raffle.config().block_randomness() == "N/A" {
+            return Err(format!("Raffle {} was imported with a predefined result and has no randomness to replay", raffle_id).into());
+        }
+
+        let randomness = self.ethereum_service.get_randomness(raffle.config().randomness_block()).await
+            .map_err(|e| BudgetSystemError::EthereumError(e.to_string()))?;
+        if randomness != raffle.config().block_randomness() {
+            return Ok(false);
+        }
+
+        let mut replay = raffle.clone();
+        replay.config_mut().set_block_randomness(randomness);
+        let recomputed = replay.recompute_result()?;
+        let sorted = |ids: &[Uuid]| {
+            let mut ids = ids.to_vec();
+            ids.sort();
+            ids
+        };
+        Ok(sorted(recomputed.counted()) == sorted(recorded.counted())
+            && sorted(recomputed.uncounted()) == sorted(recorded.uncounted()))
+    }
+
+    /// Markdown walk-through of a raffle draw: the blocks and randomness used, every
+    /// ticket's seed and score in draw order, and the resulting seats.
+    pub fn generate_raffle_audit_report(&self, raffle_id: Uuid) -> Result<String, Box<dyn Error>> {
+        let raffle = self.state.get_raffle(&raffle_id)
+            .ok_or_else(|| format!("Raffle not found: {}", raffle_id))?;
+        let config = raffle.config();
+        let proposal_title = self.state.get_proposal(&config.proposal_id())
+            .map(|p| p.title().to_string())
+            .unwrap_or_else(|| config.proposal_id().to_string());
+        let team_name = |id: Uuid| raffle.team_snapshots().iter()
+            .find(|s| s.id() == id)
+            .map(|s| s.name().to_string())
+            .unwrap_or_else(|| id.to_string());
+
+        let mut report = format!("# Raffle Audit: {}\n\n", proposal_title);
+        report.push_str(&format!("- **Raffle ID**: {}\n", raffle.id()));
+        report.push_str(&format!("- **Initiation Block**: {}\n", config.initiation_block()));
+        report.push_str(&format!("- **Randomness Block**: [{}]({})\n", config.randomness_block(), raffle.etherscan_url()));
+        report.push_str(&format!("- **Block Randomness**: `{}`\n", config.block_randomness()));
+        report.push_str(&format!("- **Counted Seats**: {} (at most {} earners)\n", config.total_counted_seats(), config.max_earner_seats()));
+        if let Some(months) = config.revenue_window_months() {
+            report.push_str(&format!("- **Revenue Window**: {} month(s)\n", months));
+        }
+        report.push('\n');
+
+        report.push_str("## Score Computation\n\n");
+        report.push_str("Each ticket's seed is the block randomness and the ticket index joined by `_`. \
+            The score is the first 8 bytes of the seed's SHA-256 hash, read as a big-endian integer and divided by 2^64 - 1. \
+            Tickets of excluded teams keep a score of 0. Tickets are ranked by score, ties broken by team ID and then ticket index.\n\n");
+
+        report.push_str("## Ticket Ranges\n\n");
+        report.push_str("| Team | Status | Tickets | Ballot Range | Best Score |\n");
+        report.push_str("|------|--------|---------|--------------|------------|\n");
+        for (name, start, end) in self.group_tickets_by_team(raffle.tickets()) {
+            let snapshot = raffle.team_snapshots().iter().find(|s| s.name() == name);
+            let status = snapshot.map_or("Unknown", |s| format_team_status(s.status()));
+            let best = snapshot.map_or(0.0, |s| raffle.best_score(s.id()));
+            report.push_str(&format!("| {} | {} | {} | {} - {} | {:.6} |\n", name, status, end - start + 1, start, end, best));
+        }
+
+        report.push_str("\n## Ticket Scores\n\n");
+        report.push_str("| Rank | Ticket | Team | Seed | Score |\n");
+        report.push_str("|------|--------|------|------|-------|\n");
+        for (rank, ticket) in raffle.ranked_tickets().into_iter().enumerate() {
+            let seed = if config.excluded_teams().contains(&ticket.team_id()) {
+                "excluded".to_string()
+            } else {
+                format!("`{}`", Raffle::ticket_seed(config.block_randomness(), ticket.index()))
+            };
+            report.push_str(&format!("| {} | {} | {} | {} | {:.6} |\n",
+                rank + 1, ticket.index(), team_name(ticket.team_id()), seed, ticket.score()));
+        }
+
+        report.push_str("\n## Result\n\n");
+        match raffle.result() {
+            Some(result) => {
+                for (label, ids) in [("Counted", result.counted()), ("Uncounted", result.uncounted())] {
+                    report.push_str(&format!("### {} ({})\n", label, ids.len()));
+                    for id in ids {
+                        report.push_str(&format!("- {}\n", team_name(*id)));
+                    }
+                    report.push('\n');
+                }
+                if raffle.is_manually_adjusted() {
+                    report.push_str(&format!("The result includes {} manual seat override(s).\n", raffle.manual_overrides().len()));
+                }
+            },
+            None => report.push_str("The raffle has not been drawn yet.\n"),
+        }
+
+        Ok(report)
+    }
+
+    fn raffle_for_proposal(&self, proposal_name: &str) -> Result<&Raffle, String> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        self.get_raffle_for_proposal(proposal_id)
+            .ok_or_else(|| format!("No raffle found for proposal: {}", proposal_name))
+    }
+
+    /// Manually moves a team between counted, uncounted and excluded in a finalized raffle.
+    /// Every override is kept in the raffle's audit log; overrides are refused once
+    /// a formal vote has been created from the raffle.
+    pub fn override_raffle_seat(&mut self, raffle_id: Uuid, team_name: &str, new_outcome: SeatOutcome, reason: &str) -> Result<(), BudgetSystemError> {
+        let team_id = self.get_team_id_by_name(team_name)
+            .ok_or_else(|| BudgetSystemError::Other(self.team_not_found(team_name)))?;
+
+        let vote_exists = self.state.votes().values().any(|v| {
+            matches!(v.vote_type(), VoteType::Formal { raffle_id: id, .. } if *id == raffle_id)
+        });
+        if vote_exists {
+            return Err(BudgetSystemError::Other(format!(
+                "Raffle {} already has a vote; seats can no longer be reassigned", raffle_id
+            )));
+        }
+
+        let raffle = self.state.get_raffle_mut(&raffle_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Raffle not found: {}", raffle_id)))?;
+        raffle.override_seat(team_id, new_outcome, reason.trim().to_string())?;
+
+        info!("Raffle {}: {} reassigned to {:?} ({})", raffle_id, team_name, new_outcome, reason.trim());
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Replaces a proposal's raffle with a new draw on fresh randomness, keeping its seat
+    /// counts, revenue window and excluded teams. Refused once a formal vote has been
+    /// created from the raffle. The replaced raffle is recorded on the new one, and is
+    /// put back if the new draw fails.
+    pub async fn rerun_raffle(&mut self, proposal_name: &str, new_block_offset: Option<u64>) -> Result<Raffle, Box<dyn Error>> {
+        let old_raffle = self.raffle_for_proposal(proposal_name)?.clone();
+        let old_id = old_raffle.id();
+        let vote_exists = self.state.votes().values().any(|v| {
+            matches!(v.vote_type(), VoteType::Formal { raffle_id: id, .. } if *id == old_id)
+        });
+        if vote_exists {
+            return Err(format!("Raffle {} already has a vote and can no longer be re-run", old_id).into());
+        }
+
+        let mut config = self.config.clone();
+        config.default_total_counted_seats = old_raffle.config().total_counted_seats();
+        config.default_max_earner_seats = old_raffle.config().max_earner_seats();
+        if let Some(months) = old_raffle.config().revenue_window_months() {
+            config.revenue_window_months = months;
+        }
+        let excluded_teams: Vec<String> = old_raffle.config().excluded_teams().iter()
+            .filter_map(|id| self.state.get_team(id).map(|t| t.name().to_string()))
+            .collect();
+
+        // The old raffle stays in state until the new draw is finalized, so an
+        // interrupted re-run never leaves the proposal without a raffle.
+        let outcome = {
+            let progress_stream = self.raffle_progress_stream(
+                proposal_name.to_string(), new_block_offset, Some(excluded_teams), config
+            );
+            pin_mut!(progress_stream);
+            let mut outcome = Err("Raffle re-run ended without a result".to_string());
+            while let Some(progress) = progress_stream.next().await {
+                match progress {
+                    Ok(RaffleProgress::Completed { raffle_id, .. }) => {
+                        outcome = Ok(raffle_id);
+                        break;
+                    },
+                    Ok(progress) => info!("{}", progress.format_message()),
+                    Err(e) => {
+                        outcome = Err(e.to_string());
+                        break;
+                    },
+                }
+            }
+            outcome
+        };
+
+        let new_id = match outcome {
+            Ok(id) => id,
+            Err(e) => {
+                let proposal_id = old_raffle.config().proposal_id();
+                let unfinished: Vec<Uuid> = self.state.raffles().values()
+                    .filter(|r| r.config().proposal_id() == proposal_id && r.id() != old_id)
+                    .map(|r| r.id())
+                    .collect();
+                for id in unfinished {
+                    self.state.remove_raffle(id);
+                }
+                self.save_state()?;
+                return Err(format!("Re-running the raffle failed, kept raffle {}: {}", old_id, e).into());
+            },
+        };
+
+        let raffle = self.state.get_raffle_mut(&new_id)
+            .ok_or_else(|| format!("Raffle not found: {}", new_id))?;
+        raffle.supersede(&old_raffle);
+        let raffle = raffle.clone();
+        self.state.remove_raffle(old_id);
+        info!("Raffle {} for '{}' replaced by {}", old_id, proposal_name, new_id);
+        self.save_state()?;
+        Ok(raffle)
+    }
+
     pub fn group_tickets_by_team(&self, tickets: &[RaffleTicket]) -> Vec<(String, u64, u64)> {
         let mut grouped_tickets: Vec<(String, u64, u64)> = Vec::new();
         let mut current_team: Option<(String, u64, u64)> = None;
@@ -961,42 +2914,139 @@ impl BudgetSystem {
         grouped_tickets
     }
 
+    pub fn raffle_excluded_teams(&self, proposal_name: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+
+        let raffle = self.get_raffle_for_proposal(proposal_id)
+            .ok_or_else(|| format!("No raffle found for proposal: {}", proposal_name))?;
+
+        Ok(self.get_raffle_exclusions(raffle.id()))
+    }
+
+    /// Names of the teams excluded from a raffle. Names come from the raffle's team
+    /// snapshots, falling back to the current team list; teams found in neither render
+    /// as "Unknown Team (id)". Empty when the raffle does not exist.
+    pub fn get_raffle_exclusions(&self, raffle_id: Uuid) -> Vec<String> {
+        let raffle = match self.state.get_raffle(&raffle_id) {
+            Some(raffle) => raffle,
+            None => return Vec::new(),
+        };
+
+        raffle.config().excluded_teams().iter()
+            .map(|team_id| {
+                raffle.team_snapshots().iter()
+                    .find(|s| s.id() == *team_id)
+                    .map(|s| s.name().to_string())
+                    .or_else(|| self.state.get_team(team_id).map(|t| t.name().to_string()))
+                    .unwrap_or_else(|| format!("Unknown Team ({})", team_id))
+            })
+            .collect()
+    }
+
+    pub fn deciding_teams_for_proposal(&self, proposal_name: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+
+        let raffle = self.get_raffle_for_proposal(proposal_id)
+            .ok_or_else(|| format!("No raffle found for proposal: {}", proposal_name))?;
+
+        if raffle.result().is_none() {
+            return Err(format!("Raffle for proposal '{}' has not been finalized", proposal_name).into());
+        }
+
+        let deciding = raffle.deciding_teams().iter()
+            .map(|team_id| {
+                raffle.team_snapshots().iter()
+                    .find(|s| s.id() == *team_id)
+                    .map(|s| s.name().to_string())
+                    .or_else(|| self.state.get_team(team_id).map(|t| t.name().to_string()))
+                    .unwrap_or_else(|| format!("Unknown Team ({})", team_id))
+            })
+            .collect();
+
+        Ok(deciding)
+    }
+
+    /// Names of the counted and uncounted teams seated by a proposal's finalized raffle.
+    /// Excluded teams are left out, as they cannot vote.
+    pub fn raffle_seats_for_proposal(&self, proposal_name: &str) -> Result<(Vec<String>, Vec<String>), Box<dyn Error>> {
+        let raffle = self.raffle_for_proposal(proposal_name)?;
+        let result = raffle.result()
+            .ok_or_else(|| format!("Raffle for proposal '{}' has not been finalized", proposal_name))?;
+
+        let names = |team_ids: &[Uuid]| -> Vec<String> {
+            team_ids.iter()
+                .filter(|team_id| !raffle.config().excluded_teams().contains(team_id))
+                .map(|team_id| {
+                    raffle.team_snapshots().iter()
+                        .find(|s| s.id() == *team_id)
+                        .map(|s| s.name().to_string())
+                        .or_else(|| self.state.get_team(team_id).map(|t| t.name().to_string()))
+                        .unwrap_or_else(|| format!("Unknown Team ({})", team_id))
+                })
+                .collect()
+        };
+
+        Ok((names(result.counted()), names(result.uncounted())))
+    }
+
+    /// `delegations` maps a voting team's name to the team that cast its vote.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_and_process_vote(
         &mut self,
         proposal_name: &str,
         counted_votes: HashMap<String, VoteChoice>,
         uncounted_votes: HashMap<String, VoteChoice>,
+        delegations: HashMap<String, String>,
         vote_opened: Option<NaiveDate>,
         vote_closed: Option<NaiveDate>,
+        deadline: Option<DateTime<Utc>>,
     ) -> Result<String, Box<dyn Error>> {
-        // Find proposal and raffle
-        let (proposal_id, raffle_id) = self.find_proposal_and_raffle(proposal_name)
-            .map_err(|e| format!("Failed to find proposal or raffle: {}", e))?;
-        
-        // Check if the proposal already has a resolution
-        let proposal = self.state.get_proposal_mut(&proposal_id)
-            .ok_or_else(|| "Proposal not found after ID lookup".to_string())?;
-        if proposal.resolution().is_some() {
-            return Err("Cannot create vote: Proposal already has a resolution".into());
+        let (proposal_id, raffle_id) = self.check_vote_creatable(proposal_name, &counted_votes, &uncounted_votes)?;
+        let mut delegates = HashMap::new();
+        for (delegator, delegate) in &delegations {
+            if !counted_votes.contains_key(delegator) && !uncounted_votes.contains_key(delegator) {
+                return Err(format!("No vote from {} to delegate", delegator).into());
+            }
+            let delegator_id = self.get_team_id_by_name(delegator)
+                .ok_or_else(|| self.team_not_found(delegator))?;
+            let delegate_id = self.get_team_id_by_name(delegate)
+                .ok_or_else(|| self.team_not_found(delegate))?;
+            delegates.insert(delegator_id, delegate_id);
+        }
+        // Checked up front so a bad delegation doesn't leave a half-cast vote behind
+        for (delegator_id, delegate_id) in &delegates {
+            if delegator_id == delegate_id {
+                return Err("A team cannot delegate its seat to itself".into());
+            }
+            if delegates.contains_key(delegate_id) {
+                return Err("A delegate cannot delegate its own seat".into());
+            }
+        }
+        if let (Some(closed), Some(deadline)) = (vote_closed, deadline) {
+            if closed > deadline.date_naive() {
+                return Err(format!("Vote closed on {} is past its deadline of {}", closed, deadline.date_naive()).into());
+            }
         }
-
-        // Validate votes
-        self.validate_votes(raffle_id, &counted_votes, &uncounted_votes)
-            .map_err(|e| format!("Vote validation failed: {}", e))?;
     
         // Create vote
-        let vote_id = self.create_formal_vote(proposal_id, raffle_id, None)
+        let vote_id = self.create_formal_vote(proposal_id, raffle_id, None, deadline)
             .map_err(|e| format!("Failed to create formal vote: {}", e))?;
     
         // Cast votes
-        let all_votes: Vec<(Uuid, VoteChoice)> = counted_votes.into_iter()
+        let (delegated_votes, direct_votes): (Vec<_>, Vec<_>) = counted_votes.into_iter()
             .chain(uncounted_votes)
             .filter_map(|(team_name, choice)| {
                 self.get_team_id_by_name(&team_name).map(|id| (id, choice))
             })
-            .collect();
-        self.cast_votes(vote_id, all_votes)
+            .partition(|(team_id, _)| delegates.contains_key(team_id));
+        self.cast_votes(vote_id, direct_votes)
             .map_err(|e| format!("Failed to cast votes: {}", e))?;
+        for (delegator_id, choice) in delegated_votes {
+            self.cast_delegated_vote(vote_id, delegator_id, delegates[&delegator_id], choice)
+                .map_err(|e| format!("Failed to cast delegated vote: {}", e))?;
+        }
     
         // Update vote dates
         self.update_vote_dates(vote_id, vote_opened, vote_closed)
@@ -1010,14 +3060,37 @@ impl BudgetSystem {
         self.generate_vote_report(vote_id)
     }
     
-    pub fn find_proposal_and_raffle(&self, proposal_name: &str) -> Result<(Uuid, Uuid), Box<dyn Error>> {
-        let proposal_id = self.get_proposal_id_by_name(proposal_name)
-            .ok_or_else(|| format!("Proposal not found: {}", proposal_name))?;
-        
-        let raffle_id = self.state.raffles().iter()
-            .find(|(_, raffle)| raffle.config().proposal_id() == proposal_id)
-            .map(|(id, _)| *id)
-            .ok_or_else(|| format!("No raffle found for proposal: {}", proposal_name))?;
+    /// Returns the proposal and raffle `create_and_process_vote` would record the vote against.
+    fn check_vote_creatable(
+        &self,
+        proposal_name: &str,
+        counted_votes: &HashMap<String, VoteChoice>,
+        uncounted_votes: &HashMap<String, VoteChoice>,
+    ) -> Result<(Uuid, Uuid), Box<dyn Error>> {
+        // Find proposal and raffle
+        let (proposal_id, raffle_id) = self.find_proposal_and_raffle(proposal_name)
+            .map_err(|e| format!("Failed to find proposal or raffle: {}", e))?;
+        
+        // Check if the proposal already has a resolution
+        let proposal = self.state.get_proposal(&proposal_id)
+            .ok_or_else(|| "Proposal not found after ID lookup".to_string())?;
+        if proposal.resolution().is_some() {
+            return Err("Cannot create vote: Proposal already has a resolution".into());
+        }
+
+        // Validate votes
+        self.validate_votes(raffle_id, counted_votes, uncounted_votes)
+            .map_err(|e| format!("Vote validation failed: {}", e))?;
+        Ok((proposal_id, raffle_id))
+    }
+
+    pub fn find_proposal_and_raffle(&self, proposal_name: &str) -> Result<(Uuid, Uuid), Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        
+        let raffle_id = self.get_raffle_for_proposal(proposal_id)
+            .map(|r| r.id())
+            .ok_or_else(|| format!("No raffle found for proposal: {}", proposal_name))?;
         Ok((proposal_id, raffle_id))
     }
     
@@ -1068,6 +3141,13 @@ impl BudgetSystem {
         proposal_id: Uuid,
         vote_closed: Option<NaiveDate>,
     ) -> Result<bool, Box<dyn Error>> {
+        // Checked before closing the vote so a failure leaves both untouched
+        let proposal = self.state.get_proposal(&proposal_id)
+            .ok_or_else(|| format!("Proposal not found: {}", proposal_id))?;
+        if !proposal.is_actionable() {
+            return Err(format!("Failed to update proposal: proposal '{}' is not open", proposal.title()).into());
+        }
+
         let passed = self.close_vote(vote_id)?;
         
         let proposal = self.state.get_proposal_mut(&proposal_id)
@@ -1100,17 +3180,42 @@ impl BudgetSystem {
         }
     }
 
+    /// Closes every open vote whose deadline has passed, tallying the votes cast so far,
+    /// and resolves its proposal as of the deadline. Returns the closed votes with
+    /// whether each passed. A vote that closes below its quorum fails.
+    pub fn close_expired_votes(&mut self) -> Result<Vec<(Uuid, bool)>, Box<dyn Error>> {
+        let now = Utc::now();
+        let mut expired: Vec<(Uuid, Uuid, DateTime<Utc>)> = self.state.votes().values()
+            .filter(|vote| vote.is_expired(now))
+            .filter_map(|vote| vote.deadline().map(|deadline| (vote.id(), vote.proposal_id(), deadline)))
+            .collect();
+        expired.sort_by_key(|(_, _, deadline)| *deadline);
+
+        let mut closed = Vec::new();
+        for (vote_id, proposal_id, deadline) in expired {
+            let passed = self.close_vote_and_update_proposal(vote_id, proposal_id, Some(deadline.date_naive()))?;
+            if let Some(vote) = self.state.get_vote_mut(&vote_id) {
+                vote.set_closed_at(Some(deadline));
+            }
+            closed.push((vote_id, passed));
+        }
+        if !closed.is_empty() {
+            self.save_state()?;
+        }
+        Ok(closed)
+    }
+
     pub fn generate_vote_report(&self, vote_id: Uuid) -> Result<String, Box<dyn Error>> {
         let vote = self.state.get_vote(&vote_id).ok_or("Vote not found")?;
         let proposal = self.state.proposals().get(&vote.proposal_id()).ok_or("Proposal not found")?;
-        let raffle = self.state.raffles().values()
-            .find(|r| r.config().proposal_id() == vote.proposal_id())
+        let raffle = self.get_raffle_for_proposal(vote.proposal_id())
             .ok_or("Associated raffle not found")?;
     
         let (counted, uncounted) = vote.vote_counts().ok_or("Vote counts not available")?;
         let counted_yes = counted.yes();
         let counted_no = counted.no();
-        let total_counted_votes = counted_yes + counted_no;
+        let counted_abstain = counted.abstain();
+        let total_counted_votes = counted.total();
         
         let total_eligible_seats = match vote.vote_type() {
             VoteType::Formal { total_eligible_seats, .. } => total_eligible_seats,
@@ -1120,10 +3225,12 @@ impl BudgetSystem {
         // Calculate absent votes for counted seats only
         let absent = total_eligible_seats.saturating_sub(total_counted_votes as u32);
 
-        let status = match vote.result() {
-            Some(VoteResult::Formal { passed, .. }) => if *passed { "Approved" } else { "Not Approved" },
-            Some(VoteResult::Informal { .. }) => "N/A (Informal)",
-            None => "Pending",
+        let status = match (vote.result(), vote.quorum_status()) {
+            (Some(VoteResult::Formal { passed: false, .. }), Some((required, cast))) if cast < required =>
+                format!("Failed: quorum not met ({}/{} required, {} cast)", required, total_eligible_seats, cast),
+            (Some(VoteResult::Formal { passed, .. }), _) => if *passed { "Approved" } else { "Not Approved" }.to_string(),
+            (Some(VoteResult::Informal { .. }), _) => "N/A (Informal)".to_string(),
+            (None, _) => "Pending".to_string(),
         };
     
         let deciding_teams: Vec<String> = raffle.deciding_teams().iter()
@@ -1133,7 +3240,7 @@ impl BudgetSystem {
             .collect();
     
         // Calculate uncounted votes
-        let total_uncounted_votes = uncounted.yes() + uncounted.no();
+        let total_uncounted_votes = uncounted.total();
         let total_uncounted_seats = raffle.result()
             .map(|result| result.uncounted().len())
             .unwrap_or(0) as u32;
@@ -1170,22 +3277,40 @@ impl BudgetSystem {
         };
     
     
-        let report = format!(
-            "**{}**\n{}\n\n**Status: {}**\n__{} in favor, {} against, {} absent__\n\n**Deciding teams**\n`{:?}`\n\n{}\n{}",
+        let mut report = format!(
+            "**{}**\n{}\n\n**Status: {}**\n__{} in favor, {} against, {} abstained, {} absent__\n\n**Deciding teams**\n`{:?}`\n\n{}\n{}",
             proposal.title(),
             proposal.url().as_deref().unwrap_or(""),
             status,
             counted_yes,
             counted_no,
+            counted_abstain,
             absent,
             deciding_teams,
             counted_votes_info,
             uncounted_votes_info
         );
+
+        let mut delegated: Vec<String> = vote.delegations().keys()
+            .filter_map(|&delegator_id| self.team_name_with_delegate(vote, delegator_id))
+            .collect();
+        delegated.sort();
+        if !delegated.is_empty() {
+            report.push_str(&format!("\nDelegated votes: {}", delegated.join(", ")));
+        }
     
         Ok(report)
     }
 
+    /// "Team A (voted by Team B)" when the team's vote was cast by a delegate, otherwise the team name.
+    fn team_name_with_delegate(&self, vote: &Vote, team_id: Uuid) -> Option<String> {
+        let team = self.state.get_team(&team_id)?;
+        match vote.delegations().get(&team_id).and_then(|delegate_id| self.state.get_team(delegate_id)) {
+            Some(delegate) => Some(format!("{} (voted by {})", team.name(), delegate.name())),
+            None => Some(team.name().to_string()),
+        }
+    }
+
     pub fn validate_votes_against_raffle(
         &self,
         raffle: &Raffle,
@@ -1199,7 +3324,7 @@ impl BudgetSystem {
     
         for team_name in counted_votes.keys() {
             let team_id = self.get_team_id_by_name(team_name)
-                .ok_or_else(|| format!("Team not found: {}", team_name))?;
+                .ok_or_else(|| self.team_not_found(team_name))?;
             if !counted_team_ids.contains(&team_id) {
                 return Err(format!("Team {} is not eligible for counted vote", team_name).into());
             }
@@ -1207,7 +3332,7 @@ impl BudgetSystem {
     
         for team_name in uncounted_votes.keys() {
             let team_id = self.get_team_id_by_name(team_name)
-                .ok_or_else(|| format!("Team not found: {}", team_name))?;
+                .ok_or_else(|| self.team_not_found(team_name))?;
             if !uncounted_team_ids.contains(&team_id) {
                 return Err(format!("Team {} is not eligible for uncounted vote", team_name).into());
             }
@@ -1238,6 +3363,444 @@ impl BudgetSystem {
         Ok(())
     }
 
+    pub fn create_proposal_template(&mut self, template: ProposalTemplate) -> Result<(), BudgetSystemError> {
+        if self.state.proposal_templates().contains_key(template.name()) {
+            return Err(BudgetSystemError::Other(format!("Proposal template already exists: {}", template.name())));
+        }
+        if let Some(team_id) = template.team_id() {
+            self.state.get_team(&team_id).ok_or("Team not found")?;
+        }
+
+        self.state.add_proposal_template(template);
+        let _ = self.save_state();
+        Ok(())
+    }
+
+    pub fn list_proposal_templates(&self) -> Vec<&ProposalTemplate> {
+        let mut templates: Vec<&ProposalTemplate> = self.state.proposal_templates().values().collect();
+        templates.sort_by(|a, b| a.name().cmp(b.name()));
+        templates
+    }
+
+    pub fn create_proposal_from_template(
+        &mut self,
+        template_name: &str,
+        epoch_id: Uuid,
+        overrides: ProposalOverrides
+    ) -> Result<Uuid, BudgetSystemError> {
+        let template = self.state.proposal_templates().get(template_name)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Proposal template not found: {}", template_name)))?;
+        if template.is_exhausted() {
+            return Err(BudgetSystemError::Other(format!("Proposal template '{}' has reached its maximum occurrences", template_name)));
+        }
+
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_id)))?;
+        if epoch.is_closed() {
+            return Err("Cannot add proposals to a closed epoch".into());
+        }
+        if let (Some(recurrence), Some(last_epoch_id)) = (template.recurrence(), template.last_epoch_id()) {
+            let mut epochs: Vec<&Epoch> = self.state.epochs().values().collect();
+            epochs.sort_by_key(|e| e.start_date());
+            let position = |id: Uuid| epochs.iter().position(|e| e.id() == id);
+            if let (Some(last), Some(target)) = (position(last_epoch_id), position(epoch_id)) {
+                if target < last + recurrence.interval_epochs() as usize {
+                    return Err(BudgetSystemError::Other(format!(
+                        "Proposal template '{}' recurs every {} epoch(s) and was last used in epoch '{}'",
+                        template_name, recurrence.interval_epochs(), epochs[last].name()
+                    )));
+                }
+            }
+        }
+
+        let title = overrides.title.unwrap_or_else(|| template.render_title(epoch.name()));
+        if self.get_proposal_id_by_name(&title).is_some() {
+            return Err(BudgetSystemError::Other(format!("Proposal already exists: {}", title)));
+        }
+        self.check_new_proposal(&title, Some(epoch_id))?;
+
+        let amounts = overrides.amounts.unwrap_or_else(|| template.amount_pattern().clone());
+        let budget_request_details = if amounts.is_empty() {
+            None
+        } else {
+            Some(BudgetRequestDetails::new(
+                overrides.team_id.or(template.team_id()),
+                amounts,
+                overrides.start_date,
+                overrides.end_date,
+                Some(template.is_loan()),
+                None,
+            )?)
+        };
+
+        let mut proposal = Proposal::new(
+            epoch_id,
+            title,
+            overrides.url,
+            budget_request_details,
+            overrides.announced_at,
+            overrides.published_at,
+            None,
+        );
+        proposal.set_dates(overrides.announced_at, overrides.published_at, None)?;
+        proposal.set_tags(template.tags().to_vec());
+
+        let proposal_id = self.state.add_proposal(&proposal);
+        if let Some(epoch) = self.state.get_epoch_mut(&epoch_id) {
+            epoch.add_proposal(proposal_id);
+        }
+        if let Some(template) = self.state.get_proposal_template_mut(template_name) {
+            template.record_use(epoch_id);
+        }
+
+        self.save_state()?;
+        Ok(proposal_id)
+    }
+
+    pub fn set_proposal_loan_terms(&mut self, proposal_name: &str, loan_terms: LoanTerms) -> Result<(), Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        let proposal = self.state.get_proposal_mut(&proposal_id)
+            .ok_or("Proposal not found")?;
+        let details = proposal.budget_request_details_mut()
+            .ok_or_else(|| format!("Proposal '{}' has no budget request", proposal_name))?;
+
+        details.set_loan_terms(Some(loan_terms))?;
+        self.save_state()?;
+        Ok(())
+    }
+
+    /// Splits a proposal's budget request between teams, keyed by team name.
+    pub fn set_proposal_team_allocations(&mut self, proposal_name: &str, allocations: HashMap<String, HashMap<String, f64>>) -> Result<(), Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        let mut by_team = HashMap::new();
+        for (team_name, amounts) in allocations {
+            let team_id = self.get_team_id_by_name(&team_name)
+                .ok_or_else(|| self.team_not_found(&team_name))?;
+            by_team.insert(team_id, amounts);
+        }
+
+        let proposal = self.state.get_proposal_mut(&proposal_id)
+            .ok_or("Proposal not found")?;
+        let details = proposal.budget_request_details_mut()
+            .ok_or_else(|| format!("Proposal '{}' has no budget request", proposal_name))?;
+
+        details.set_team_allocations(by_team)?;
+        self.save_state()?;
+        Ok(())
+    }
+
+    pub fn record_loan_repayment(&mut self, proposal_name: &str, repaid_at: NaiveDate) -> Result<(), Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        let proposal = self.state.get_proposal_mut(&proposal_id)
+            .ok_or("Proposal not found")?;
+        let details = proposal.budget_request_details_mut()
+            .ok_or_else(|| format!("Proposal '{}' has no budget request", proposal_name))?;
+
+        details.record_repayment(repaid_at)?;
+        self.save_state()?;
+        Ok(())
+    }
+
+    pub fn set_proposal_reviewers(&mut self, proposal_name: &str, reviewers: Vec<String>) -> Result<(), Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        let proposal = self.state.get_proposal_mut(&proposal_id)
+            .ok_or("Proposal not found")?;
+
+        proposal.set_reviewers(reviewers)?;
+        self.save_state()?;
+        Ok(())
+    }
+
+    pub fn record_reviewer_sign_off(&mut self, proposal_name: &str, reviewer: &str, date: NaiveDate) -> Result<(), Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        let proposal = self.state.get_proposal_mut(&proposal_id)
+            .ok_or("Proposal not found")?;
+
+        proposal.record_reviewer_sign_off(reviewer, date)?;
+        self.save_state()?;
+        Ok(())
+    }
+
+    pub fn generate_loans_outstanding_report(&self) -> String {
+        let today = Utc::now().date_naive();
+        let mut loans: Vec<&Proposal> = self.state.proposals().values()
+            .filter(|p| p.is_approved())
+            .filter(|p| p.budget_request_details().is_some_and(|d| d.is_loan()))
+            .collect();
+        loans.sort_by(|a, b| a.title().cmp(b.title()));
+
+        let mut report = String::from("Loans Outstanding\n");
+        if loans.is_empty() {
+            report.push_str("  No approved loans\n");
+            return report;
+        }
+
+        for proposal in loans {
+            let details = proposal.budget_request_details().unwrap();
+            let team_name = details.team()
+                .and_then(|id| self.state.get_team(&id))
+                .map(|t| t.name().to_string())
+                .unwrap_or_else(|| "No Team".to_string());
+            report.push_str(&format!("\n{} ({})\n", proposal.title(), team_name));
+
+            let terms = match details.loan_terms() {
+                Some(terms) => terms,
+                None => {
+                    report.push_str("  No loan terms set\n");
+                    continue;
+                }
+            };
+            report.push_str(&format!("  Interest rate: {:.2}%, due: {}, schedule: {:?}\n",
+                terms.interest_rate() * 100.0, terms.due_date(), terms.repayment_schedule()));
+
+            let expected_fraction = match details.payment_date().or(details.start_date()) {
+                Some(start) => terms.expected_repaid_fraction(start, today),
+                None => 0.0,
+            };
+
+            let mut amounts: Vec<_> = details.request_amounts().iter().collect();
+            amounts.sort_by_key(|(token, _)| *token);
+            for (token, &principal) in amounts {
+                let total_due = terms.total_due(principal);
+                let expected = total_due * expected_fraction;
+                let repaid = terms.repaid_amounts().get(token).copied().unwrap_or(0.0);
+                report.push_str(&format!(
+                    "  {}: principal {:.2}, total due {:.2}, expected to date {:.2}, repaid {:.2}, outstanding {:.2}{}\n",
+                    token, principal, total_due, expected, repaid,
+                    (total_due - repaid).max(0.0),
+                    if repaid + 1e-9 < expected { " (behind schedule)" } else { "" }
+                ));
+            }
+        }
+
+        report
+    }
+
+    /// Loans that have been paid out but not repaid, optionally limited to one epoch, oldest payment first.
+    pub fn generate_outstanding_loans_report(&self, epoch_name: Option<&str>) -> Result<String, BudgetSystemError> {
+        let epoch_id = match epoch_name {
+            Some(name) => Some(self.get_epoch_id_by_name(name)
+                .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", name)))?),
+            None => None,
+        };
+        let today = Utc::now().date_naive();
+
+        let mut loans: Vec<(&Proposal, NaiveDate)> = self.state.proposals().values()
+            .filter(|p| p.is_approved())
+            .filter(|p| epoch_id.is_none_or(|id| p.epoch_id() == id))
+            .filter_map(|p| {
+                let details = p.budget_request_details()?;
+                if !details.is_loan() || !details.is_paid() || details.is_repaid() {
+                    return None;
+                }
+                Some((p, details.payment_date()?))
+            })
+            .collect();
+        loans.sort_by(|(a, a_paid), (b, b_paid)| a_paid.cmp(b_paid).then_with(|| a.title().cmp(b.title())));
+
+        let mut report = match epoch_name {
+            Some(name) => format!("Outstanding Loans ({})\n", name),
+            None => String::from("Outstanding Loans\n"),
+        };
+        if loans.is_empty() {
+            report.push_str("  No outstanding loans\n");
+            return Ok(report);
+        }
+
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        for (proposal, payment_date) in loans {
+            let details = proposal.budget_request_details().unwrap();
+            let team_name = details.team()
+                .and_then(|id| self.state.get_team(&id))
+                .map(|t| t.name().to_string())
+                .unwrap_or_else(|| "No Team".to_string());
+            let mut amounts: Vec<_> = details.request_amounts().iter().collect();
+            amounts.sort_by_key(|(token, _)| *token);
+            for (token, amount) in &amounts {
+                *totals.entry(token.to_string()).or_insert(0.0) += **amount;
+            }
+            let amounts = amounts.iter()
+                .map(|(token, amount)| format!("{:.2} {}", amount, token))
+                .collect::<Vec<_>>()
+                .join(", ");
+            report.push_str(&format!(
+                "  {} ({}): {}, paid {}, {} days outstanding\n",
+                proposal.title(), team_name, amounts, payment_date, (today - payment_date).num_days()
+            ));
+        }
+
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort_by(|a, b| a.0.cmp(&b.0));
+        let totals = totals.iter()
+            .map(|(token, amount)| format!("{:.2} {}", amount, token))
+            .collect::<Vec<_>>()
+            .join(", ");
+        report.push_str(&format!("Total outstanding: {}\n", totals));
+        Ok(report)
+    }
+
+    /// Markdown report of every approved loan, split into loans not yet paid out, loans
+    /// paid out and awaiting repayment, and repaid loans, with per-token totals. Amounts
+    /// include interest when loan terms are set, and partial repayments recorded in the
+    /// terms reduce the outstanding amount. Loans are due on the request's end date.
+    pub fn generate_loan_tracking_report(&self) -> Result<String, Box<dyn Error>> {
+        let today = Utc::now().date_naive();
+        let mut loans: Vec<&Proposal> = self.state.proposals().values()
+            .filter(|p| p.is_approved())
+            .filter(|p| p.budget_request_details().is_some_and(|d| d.is_loan()))
+            .collect();
+        loans.sort_by_key(|p| (p.resolved_at(), p.title().to_string()));
+
+        let mut unpaid_rows = Vec::new();
+        let mut outstanding_rows = Vec::new();
+        let mut repaid_rows = Vec::new();
+        let mut unpaid_totals: HashMap<String, f64> = HashMap::new();
+        let mut outstanding_totals: HashMap<String, f64> = HashMap::new();
+        let mut repaid_totals: HashMap<String, f64> = HashMap::new();
+
+        for proposal in loans {
+            let details = proposal.budget_request_details().unwrap();
+            let team_name = details.team()
+                .and_then(|id| self.state.get_team(&id))
+                .map_or("No Team", |t| t.name());
+            let epoch_name = self.state.get_epoch(&proposal.epoch_id()).map_or("Unknown", |e| e.name());
+            let approved = proposal.resolved_at().map_or("N/A".to_string(), |d| d.to_string());
+            let due_date = details.end_date();
+            let due = due_date.map_or("N/A".to_string(), |d| d.to_string());
+
+            let mut amounts: Vec<(&String, f64, f64)> = details.request_amounts().iter()
+                .map(|(token, &principal)| {
+                    let owed = details.loan_terms().map_or(principal, |t| t.total_due(principal));
+                    let repaid = if details.is_repaid() {
+                        owed
+                    } else {
+                        details.loan_terms()
+                            .and_then(|t| t.repaid_amounts().get(token).copied())
+                            .unwrap_or(0.0)
+                            .min(owed)
+                    };
+                    (token, owed, repaid)
+                })
+                .collect();
+            amounts.sort_by_key(|(token, _, _)| *token);
+
+            // Days past the due date, as of repayment for repaid loans
+            let overdue_on = details.repaid_at().unwrap_or(today);
+            let days_overdue = due_date.map_or("N/A".to_string(), |due| (overdue_on - due).num_days().max(0).to_string());
+
+            if !details.is_paid() {
+                for (token, owed, _) in &amounts {
+                    *unpaid_totals.entry(token.to_string()).or_insert(0.0) += owed;
+                }
+                let amounts = amounts.iter()
+                    .map(|(token, owed, _)| format!("{:.2} {}", owed, token))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                unpaid_rows.push(format!(
+                    "| {} | {} | {} | {} | {} | {} | {} |",
+                    proposal.title(), team_name, amounts, epoch_name, approved, due, days_overdue
+                ));
+                continue;
+            }
+
+            for (token, owed, repaid) in &amounts {
+                *outstanding_totals.entry(token.to_string()).or_insert(0.0) += owed - repaid;
+                *repaid_totals.entry(token.to_string()).or_insert(0.0) += repaid;
+            }
+            let payment_tx = details.payment_tx().map_or("N/A".to_string(), |tx| format!("{:?}", tx));
+
+            if details.is_repaid() {
+                let amounts = amounts.iter()
+                    .map(|(token, owed, _)| format!("{:.2} {}", owed, token))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                repaid_rows.push(format!(
+                    "| {} | {} | {} | {} | {} | {} | {} | {} | {} |",
+                    proposal.title(), team_name, amounts, epoch_name, approved, due,
+                    details.repaid_at().unwrap(), days_overdue, payment_tx
+                ));
+            } else {
+                let amounts = amounts.iter()
+                    .map(|(token, owed, repaid)| if *repaid > 0.0 {
+                        format!("{:.2} of {:.2} {}", owed - repaid, owed, token)
+                    } else {
+                        format!("{:.2} {}", owed, token)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                outstanding_rows.push(format!(
+                    "| {} | {} | {} | {} | {} | {} | {} | {} |",
+                    proposal.title(), team_name, amounts, epoch_name, approved, due, days_overdue, payment_tx
+                ));
+            }
+        }
+
+        let mut report = String::from("# Loan Tracking Report\n\n");
+        report.push_str(&format!("Generated on {}\n\n", today));
+
+        report.push_str("## Unpaid Loans\n\n");
+        if unpaid_rows.is_empty() {
+            report.push_str("No approved loans waiting to be paid out\n\n");
+        } else {
+            report.push_str("| Proposal | Team | Amount | Epoch | Approved | Due | Days Overdue |\n");
+            report.push_str("|----------|------|--------|-------|----------|-----|--------------|\n");
+            for row in &unpaid_rows {
+                report.push_str(row);
+                report.push('\n');
+            }
+            report.push('\n');
+        }
+
+        report.push_str("## Outstanding Loans\n\n");
+        if outstanding_rows.is_empty() {
+            report.push_str("No outstanding loans\n\n");
+        } else {
+            report.push_str("| Proposal | Team | Amount | Epoch | Approved | Due | Days Overdue | Payment Tx |\n");
+            report.push_str("|----------|------|--------|-------|----------|-----|--------------|------------|\n");
+            for row in &outstanding_rows {
+                report.push_str(row);
+                report.push('\n');
+            }
+            report.push('\n');
+        }
+
+        report.push_str("## Repaid Loans\n\n");
+        if repaid_rows.is_empty() {
+            report.push_str("No repaid loans\n\n");
+        } else {
+            report.push_str("| Proposal | Team | Amount | Epoch | Approved | Due | Repaid | Days Overdue | Payment Tx |\n");
+            report.push_str("|----------|------|--------|-------|----------|-----|--------|--------------|------------|\n");
+            for row in &repaid_rows {
+                report.push_str(row);
+                report.push('\n');
+            }
+            report.push('\n');
+        }
+
+        report.push_str("## Totals\n\n");
+        let mut tokens: Vec<&String> = unpaid_totals.keys().chain(outstanding_totals.keys()).collect();
+        tokens.sort();
+        tokens.dedup();
+        if tokens.is_empty() {
+            report.push_str("No approved loans\n");
+        } else {
+            report.push_str("| Token | Unpaid | Outstanding | Repaid |\n");
+            report.push_str("|-------|--------|-------------|--------|\n");
+            let total = |totals: &HashMap<String, f64>, token: &String| totals.get(token).copied().unwrap_or(0.0);
+            for token in tokens {
+                report.push_str(&format!("| {} | {:.2} | {:.2} | {:.2} |\n",
+                    token, total(&unpaid_totals, token), total(&outstanding_totals, token), total(&repaid_totals, token)));
+            }
+        }
+
+        Ok(report)
+    }
+
     pub fn generate_markdown_test(&self) -> String {
         let test_message = r#"
 *Bold text*
@@ -1256,7 +3819,7 @@ def hello_world():
         test_message.to_string()
     }
 
-    pub fn generate_proposal_report(&self, proposal_id: Uuid) -> Result<String, Box<dyn Error>> {
+    pub fn generate_proposal_report(&self, proposal_id: Uuid, sections: ReportSections) -> Result<String, Box<dyn Error>> {
         debug!("Generating proposal report for ID: {:?}", proposal_id);
     
         let proposal = self.state.get_proposal(&proposal_id)
@@ -1268,15 +3831,40 @@ def hello_world():
     
         // Main title (moved outside of Summary)
         report.push_str(&format!("# Proposal Report: {}\n\n", proposal.title()));
+
+        if sections.contains(ReportSections::SUMMARY) {
+            report.push_str(&self.proposal_summary_section(proposal));
+        }
+        if sections.contains(ReportSections::DETAILS) {
+            report.push_str(&self.proposal_details_section(proposal));
+        }
+        if sections.contains(ReportSections::BUDGET) {
+            report.push_str(&self.proposal_budget_section(proposal));
+        }
+        if sections.contains(ReportSections::RAFFLE) {
+            report.push_str(&self.proposal_raffle_section(proposal));
+        }
+        if sections.contains(ReportSections::VOTING) {
+            report.push_str(&self.proposal_voting_section(proposal));
+        }
+        if sections.contains(ReportSections::DETAILS) {
+            report.push_str(&self.proposal_revisions_section(proposal));
+        }
     
-        // Summary
+        Ok(report)
+    }
+
+    fn proposal_summary_section(&self, proposal: &Proposal) -> String {
+        let proposal_id = proposal.id();
+        let mut report = String::new();
+
         report.push_str("## Summary\n\n");
         if let (Some(announced), Some(resolved)) = (proposal.announced_at(), proposal.resolved_at()) {
             let resolution_days = self.calculate_days_between(announced, resolved);
             report.push_str(&format!("This proposal was resolved in {} days from its announcement date. ", resolution_days));
         }
     
-        if let Some(vote) = self.state.votes().values().find(|v| v.proposal_id() == proposal_id) {
+        if let Some(vote) = self.get_vote_for_proposal(proposal_id) {
             if let Some(result) = vote.result() {
                 match result {
                     VoteResult::Formal { counted, uncounted, passed } => {
@@ -1304,20 +3892,33 @@ def hello_world():
         }
     
         report.push_str("\n\n");
-    
-        // Proposal Details
+
+        report
+    }
+
+    fn proposal_details_section(&self, proposal: &Proposal) -> String {
+        let mut report = String::new();
+
         report.push_str("## Proposal Details\n\n");
         report.push_str(&format!("- **ID**: {}\n", proposal.id()));
         report.push_str(&format!("- **Title**: {}\n", proposal.title()));
         report.push_str(&format!("- **URL**: {}\n", proposal.url().as_deref().unwrap_or("N/A")));
+        report.push_str(&format!("- **Category**: {}\n", proposal.category().map_or("N/A".to_string(), |c| c.to_string())));
+        report.push_str(&format!("- **Document**: {}\n", proposal.ipfs_hash().map_or("N/A".to_string(), ipfs_gateway_url)));
+        report.push_str(&format!("- **Tags**: {}\n", if proposal.tags().is_empty() { "N/A".to_string() } else { proposal.tags().join(", ") }));
         report.push_str(&format!("- **Status**: {:?}\n", proposal.status()));
         report.push_str(&format!("- **Resolution**: {}\n", proposal.resolution().as_ref().map_or("N/A".to_string(), |r| format!("{:?}", r))));
         report.push_str(&format!("- **Announced**: {}\n", proposal.announced_at().map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string())));
         report.push_str(&format!("- **Published**: {}\n", proposal.published_at().map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string())));
         report.push_str(&format!("- **Resolved**: {}\n", proposal.resolved_at().map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string())));
         report.push_str(&format!("- **Is Historical**: {}\n\n", proposal.is_historical()));
-    
-        // Budget Request Details
+
+        report
+    }
+
+    fn proposal_budget_section(&self, proposal: &Proposal) -> String {
+        let mut report = String::new();
+
         if let Some(budget_details) = proposal.budget_request_details() {
             report.push_str("## Budget Request Details\n\n");
             
@@ -1335,6 +3936,32 @@ def hello_world():
             for (token, amount) in amounts {
                 report.push_str(&format!("  - {}: {}\n", token, amount));
             }
+
+            if budget_details.has_team_allocations() {
+                let mut allocations: Vec<(String, Uuid, HashMap<String, f64>)> = budget_details.team_allocations().into_iter()
+                    .map(|(team_id, amounts)| {
+                        let name = self.state.get_team(&team_id)
+                            .map_or_else(|| team_id.to_string(), |team| team.name().to_string());
+                        (name, team_id, amounts)
+                    })
+                    .collect();
+                allocations.sort_by(|a, b| a.0.cmp(&b.0));
+
+                report.push_str("- **Team Allocations**:\n");
+                for (name, team_id, amounts) in allocations {
+                    let mut amounts: Vec<_> = amounts.into_iter().collect();
+                    amounts.sort_by(|(a, _), (b, _)| a.cmp(b));
+                    let amounts = amounts.iter()
+                        .map(|(token, amount)| format!("{} {}", amount, token))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let status = match budget_details.team_payment(team_id) {
+                        Some((tx, date)) => format!("paid {} ({:?})", date.format("%Y-%m-%d"), tx),
+                        None => "unpaid".to_string(),
+                    };
+                    report.push_str(&format!("  - {}: {}, {}\n", name, amounts, status));
+                }
+            }
  
             report.push_str(&format!("- **Start Date**: {}\n", 
                 budget_details.start_date()
@@ -1344,6 +3971,11 @@ def hello_world():
                     .map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string())));
             report.push_str(&format!("- **Is Loan**: {}\n", 
                 budget_details.is_loan()));
+            if let Some(terms) = budget_details.loan_terms() {
+                report.push_str(&format!("- **Interest Rate**: {:.2}%\n", terms.interest_rate() * 100.0));
+                report.push_str(&format!("- **Loan Due Date**: {}\n", terms.due_date().format("%Y-%m-%d")));
+                report.push_str(&format!("- **Repayment Schedule**: {:?}\n", terms.repayment_schedule()));
+            }
             report.push_str(&format!("- **Payment Address**: {}\n", 
                 budget_details.payment_address()
                     .map_or("N/A".to_string(), |addr| format!("{:?}", addr))));
@@ -1355,68 +3987,145 @@ def hello_world():
             }
             report.push_str("\n");
         }
-    
-        // Raffle Information
-        if let Some(raffle) = self.state.raffles().values().find(|r| r.config().proposal_id() == proposal_id) {
+
+        report
+    }
+
+    fn proposal_raffle_section(&self, proposal: &Proposal) -> String {
+        let proposal_id = proposal.id();
+        let mut report = String::new();
+
+        if let Some(raffle) = self.get_raffle_for_proposal(proposal_id) {
             report.push_str("## Raffle Information\n\n");
-            report.push_str(&format!("- **Raffle ID**: {}\n", raffle.id()));
-            report.push_str(&format!("- **Initiation Block**: {}\n", raffle.config().initiation_block()));
-            report.push_str(&format!("- **Randomness Block**: [{}]({})\n", 
-                raffle.config().randomness_block(), raffle.etherscan_url()));
-            report.push_str(&format!("- **Block Randomness**: {}\n", raffle.config().block_randomness()));
-            report.push_str(&format!("- **Total Counted Seats**: {}\n", raffle.config().total_counted_seats()));
-            report.push_str(&format!("- **Max Earner Seats**: {}\n", raffle.config().max_earner_seats()));
-            report.push_str(&format!("- **Is Historical**: {}\n\n", raffle.config().is_historical()));
-    
-            // Team Snapshots
-            report.push_str(&self.generate_team_snapshots_table(raffle));
-    
-            // Raffle Outcome
-            if let Some(result) = raffle.result() {
-                report.push_str("### Raffle Outcome\n\n");
-                self.generate_raffle_outcome(&mut report, raffle, result);
-            }
+            report.push_str(&self.raffle_details(raffle));
         } else {
             report.push_str("## Raffle Information\n\nNo raffle was conducted for this proposal.\n\n");
         }
-    
-        // Voting Information
-        if let Some(vote) = self.state.votes().values().find(|v| v.proposal_id() == proposal_id) {
-            report.push_str("## Voting Information\n\n");
-            report.push_str("### Vote Details\n\n");
-            report.push_str(&format!("- **Vote ID**: {}\n", vote.id()));
-            report.push_str(&format!("- **Type**: {:?}\n", vote.vote_type()));
-            report.push_str(&format!("- **Status**: {:?}\n", vote.status()));
-            report.push_str(&format!("- **Opened**: {}\n", vote.opened_at().format("%Y-%m-%d %H:%M:%S")));
-            if let Some(closed_at) = vote.closed_at() {
-                report.push_str(&format!("- **Closed**: {}\n", closed_at.format("%Y-%m-%d %H:%M:%S")));
-            }
-            if let Some(result) = vote.result() {
-                match result {
-                    VoteResult::Formal { passed, .. } => {
-                        report.push_str(&format!("- **Result**: {}\n\n", if *passed { "Passed" } else { "Not Passed" }));
-                    },
-                    VoteResult::Informal { .. } => {
-                        report.push_str("- **Result**: Informal (No Pass/Fail)\n\n");
-                    }
-                }
-            }
-    
-            // Participation
-            report.push_str("### Participation\n\n");
-            report.push_str(&self.generate_vote_participation_tables(vote));
-    
-            // Vote Counts
+
+        report
+    }
+
+    /// A proposal's raffle on its own: the Raffle Information section of the proposal report.
+    pub fn show_raffle(&self, proposal_name: &str) -> Result<String, Box<dyn Error>> {
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        let proposal = self.state.get_proposal(&proposal_id).ok_or("Proposal not found")?;
+
+        let Some(raffle) = self.get_raffle_for_proposal(proposal_id) else {
+            return Ok(format!("No raffle was conducted for proposal '{}'.\n", proposal.title()));
+        };
+        let mut output = format!("# Raffle: {}\n\n", proposal.title());
+        output.push_str(&self.raffle_details(raffle));
+        if raffle.result().is_none() {
+            output.push_str("The raffle has not been drawn yet.\n");
+        }
+        Ok(output)
+    }
+
+    fn raffle_details(&self, raffle: &Raffle) -> String {
+        let mut report = String::new();
+        report.push_str(&format!("- **Raffle ID**: {}\n", raffle.id()));
+        report.push_str(&format!("- **Initiation Block**: {}\n", raffle.config().initiation_block()));
+        report.push_str(&format!("- **Randomness Block**: [{}]({})\n", 
+            raffle.config().randomness_block(), raffle.etherscan_url()));
+        report.push_str(&format!("- **Block Randomness**: {}\n", raffle.config().block_randomness()));
+        report.push_str(&format!("- **Total Counted Seats**: {}\n", raffle.config().total_counted_seats()));
+        report.push_str(&format!("- **Max Earner Seats**: {}\n", raffle.config().max_earner_seats()));
+        report.push_str(&format!("- **Is Historical**: {}\n", raffle.config().is_historical()));
+        let excluded = self.get_raffle_exclusions(raffle.id());
+        if excluded.is_empty() {
+            report.push_str("- **Excluded Teams**: None\n");
+        } else {
+            report.push_str(&format!("- **Excluded Teams**: {}\n", excluded.join(", ")));
+        }
+        if raffle.is_manually_adjusted() {
+            report.push_str("- **Manually Adjusted**: Yes\n");
+        }
+        report.push('\n');
+
+        // Team Snapshots
+        report.push_str(&self.generate_team_snapshots_table(raffle));
+
+        // Raffle Outcome
+        if let Some(result) = raffle.result() {
+            report.push_str("### Raffle Outcome\n\n");
+            self.generate_raffle_outcome(&mut report, raffle, result);
+        }
+
+        if raffle.is_manually_adjusted() {
+            report.push_str("### Manual Overrides\n\n");
+            report.push_str("| Team | From | To | Reason | Timestamp |\n");
+            report.push_str("|------|------|----|--------|-----------|\n");
+            for entry in raffle.manual_overrides() {
+                let team_name = raffle.team_snapshots().iter()
+                    .find(|s| s.id() == entry.team_id())
+                    .map(|s| s.name().to_string())
+                    .unwrap_or_else(|| format!("Unknown Team ({})", entry.team_id()));
+                report.push_str(&format!("| {} | {:?} | {:?} | {} | {} |\n",
+                    team_name, entry.previous_outcome(), entry.new_outcome(), entry.reason(),
+                    entry.timestamp().format("%Y-%m-%d %H:%M:%S UTC")));
+            }
+            report.push('\n');
+        }
+
+        if !raffle.superseded_raffles().is_empty() {
+            report.push_str("### Superseded Raffles\n\n");
+            report.push_str("| Raffle ID | Randomness Block | Block Randomness | Replaced At |\n");
+            report.push_str("|-----------|------------------|------------------|-------------|\n");
+            for entry in raffle.superseded_raffles() {
+                report.push_str(&format!("| {} | {} | {} | {} |\n",
+                    entry.raffle_id(), entry.randomness_block(), entry.block_randomness(),
+                    entry.replaced_at().format("%Y-%m-%d %H:%M:%S UTC")));
+            }
+            report.push('\n');
+        }
+
+        report
+    }
+
+    fn proposal_voting_section(&self, proposal: &Proposal) -> String {
+        let proposal_id = proposal.id();
+        let mut report = String::new();
+
+        if let Some(vote) = self.get_vote_for_proposal(proposal_id) {
+            report.push_str("## Voting Information\n\n");
+            report.push_str("### Vote Details\n\n");
+            report.push_str(&format!("- **Vote ID**: {}\n", vote.id()));
+            report.push_str(&format!("- **Type**: {:?}\n", vote.vote_type()));
+            report.push_str(&format!("- **Status**: {:?}\n", vote.status()));
+            report.push_str(&format!("- **Opened**: {}\n", vote.opened_at().format("%Y-%m-%d %H:%M:%S")));
+            if let Some(closed_at) = vote.closed_at() {
+                report.push_str(&format!("- **Closed**: {}\n", closed_at.format("%Y-%m-%d %H:%M:%S")));
+            }
+            if let Some(result) = vote.result() {
+                match result {
+                    VoteResult::Formal { passed, .. } => {
+                        report.push_str(&format!("- **Result**: {}\n\n", if *passed { "Passed" } else { "Not Passed" }));
+                    },
+                    VoteResult::Informal { .. } => {
+                        report.push_str("- **Result**: Informal (No Pass/Fail)\n\n");
+                    }
+                }
+            }
+    
+            // Participation
+            report.push_str("### Participation\n\n");
+            report.push_str(&self.generate_vote_participation_tables(vote));
+    
+            // Vote Counts
             if !vote.is_historical() {
                 report.push_str("### Vote Counts\n");
                 match vote.vote_type() {
                     VoteType::Formal { total_eligible_seats, .. } => {
                         if let Some(VoteResult::Formal { counted, uncounted, .. }) = vote.result() {
-                            let absent = *total_eligible_seats as i32 - (counted.yes() + counted.no()) as i32;
+                            let absent = *total_eligible_seats as i32 - counted.total() as i32;
                             
                             report.push_str("#### Counted Votes\n");
                             report.push_str(&format!("- **Yes**: {}\n", counted.yes()));
                             report.push_str(&format!("- **No**: {}\n", counted.no()));
+                            if counted.abstain() > 0 {
+                                report.push_str(&format!("- **Abstain**: {}\n", counted.abstain()));
+                            }
                             if absent > 0 {
                                 report.push_str(&format!("- **Absent**: {}\n", absent));
                             }
@@ -1424,12 +4133,18 @@ def hello_world():
                             report.push_str("\n#### Uncounted Votes\n");
                             report.push_str(&format!("- **Yes**: {}\n", uncounted.yes()));
                             report.push_str(&format!("- **No**: {}\n", uncounted.no()));
+                            if uncounted.abstain() > 0 {
+                                report.push_str(&format!("- **Abstain**: {}\n", uncounted.abstain()));
+                            }
                         }
                     },
                     VoteType::Informal => {
                         if let Some(VoteResult::Informal { count }) = vote.result() {
                             report.push_str(&format!("- **Yes**: {}\n", count.yes()));
                             report.push_str(&format!("- **No**: {}\n", count.no()));
+                            if count.abstain() > 0 {
+                                report.push_str(&format!("- **Abstain**: {}\n", count.abstain()));
+                            }
                         }
                     }
                 }
@@ -1439,8 +4154,30 @@ def hello_world():
         } else {
             report.push_str("## Voting Information\n\nNo vote was conducted for this proposal.\n\n");
         }
-    
-        Ok(report)
+
+        report
+    }
+
+    fn proposal_revisions_section(&self, proposal: &Proposal) -> String {
+        let mut report = String::new();
+
+        report.push_str("## Revision History\n\n");
+        if proposal.revision_history().is_empty() {
+            report.push_str("No revisions recorded for this proposal.\n\n");
+        } else {
+            report.push_str("| Timestamp | Field | Old Value | New Value |\n");
+            report.push_str("|-----------|-------|-----------|-----------|\n");
+            for revision in proposal.revision_history() {
+                report.push_str(&format!("| {} | {} | {} | {} |\n",
+                    revision.timestamp().format("%Y-%m-%d %H:%M:%S"),
+                    revision.field_changed(),
+                    Self::markdown_table_cell(&revision.old_value().to_string()),
+                    Self::markdown_table_cell(&revision.new_value().to_string())));
+            }
+            report.push('\n');
+        }
+
+        report
     }
 
     pub fn generate_team_snapshots_table(&self, raffle: &Raffle) -> String {
@@ -1455,6 +4192,7 @@ def hello_world():
                 TeamStatus::Earner { .. } => "Earner",
                 TeamStatus::Supporter => "Supporter",
                 TeamStatus::Inactive => "Inactive",
+                TeamStatus::Archived => "Archived",
             };
 
             let revenue = match &snapshot.status() {
@@ -1501,11 +4239,7 @@ def hello_world():
         report.push_str(&format!("##### Earner Seats ({})\n", counted_earners.len()));
         for team_id in counted_earners {
             if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == *team_id) {
-                let best_score = raffle.tickets().iter()
-                    .filter(|t| t.team_id() == *team_id)
-                    .map(|t| t.score())
-                    .max_by(|a, b| a.partial_cmp(b).unwrap())
-                    .unwrap_or(0.0);
+                let best_score = raffle.best_score(*team_id);
                 report.push_str(&format!("- {} (Best Score: {:.4})\n", snapshot.name(), best_score));
             }
         }
@@ -1513,11 +4247,7 @@ def hello_world():
         report.push_str(&format!("\n##### Supporter Seats ({})\n", counted_supporters.len()));
         for team_id in counted_supporters {
             if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == *team_id) {
-                let best_score = raffle.tickets().iter()
-                    .filter(|t| t.team_id() == *team_id)
-                    .map(|t| t.score())
-                    .max_by(|a, b| a.partial_cmp(b).unwrap())
-                    .unwrap_or(0.0);
+                let best_score = raffle.best_score(*team_id);
                 report.push_str(&format!("- {} (Best Score: {:.4})\n", snapshot.name(), best_score));
             }
         }
@@ -1525,11 +4255,7 @@ def hello_world():
         report.push_str("\n#### Uncounted Seats\n");
         for team_id in result.uncounted() {
             if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == *team_id) {
-                let best_score = raffle.tickets().iter()
-                    .filter(|t| t.team_id() == *team_id)
-                    .map(|t| t.score())
-                    .max_by(|a, b| a.partial_cmp(b).unwrap())
-                    .unwrap_or(0.0);
+                let best_score = raffle.best_score(*team_id);
                 report.push_str(&format!("- {} (Best Score: {:.4})\n", snapshot.name(), best_score));
             }
         }
@@ -1543,18 +4269,30 @@ def hello_world():
                 tables.push_str("#### Counted Votes\n");
                 tables.push_str("| Team | Points Credited |\n");
                 tables.push_str("|------|------------------|\n");
-                for &team_id in counted {
+                for &team_id in counted.iter().filter(|&&id| !vote.has_abstained(id)) {
                     if let Some(team) = self.state.current_state().teams().get(&team_id) {
-                        tables.push_str(&format!("| {} | {} |\n", team.name(), self.config.counted_vote_points));
+                        tables.push_str(&format!("| {} | {} |\n", team.name(), vote.points_for_team(team_id)));
                     }
                 }
 
                 tables.push_str("\n#### Uncounted Votes\n");
                 tables.push_str("| Team | Points Credited |\n");
                 tables.push_str("|------|------------------|\n");
-                for &team_id in uncounted {
+                for &team_id in uncounted.iter().filter(|&&id| !vote.has_abstained(id)) {
                     if let Some(team) = self.state.current_state().teams().get(&team_id) {
-                        tables.push_str(&format!("| {} | {} |\n", team.name(), self.config.uncounted_vote_points));
+                        tables.push_str(&format!("| {} | {} |\n", team.name(), vote.points_for_team(team_id)));
+                    }
+                }
+
+                if !vote.abstained().is_empty() {
+                    tables.push_str("\n#### Abstentions\n");
+                    tables.push_str("| Team | Seat | Points Credited |\n");
+                    tables.push_str("|------|------|------------------|\n");
+                    for &team_id in counted.iter().chain(uncounted).filter(|&&id| vote.has_abstained(id)) {
+                        if let Some(team) = self.state.current_state().teams().get(&team_id) {
+                            let seat = if counted.contains(&team_id) { "Counted" } else { "Uncounted" };
+                            tables.push_str(&format!("| {} | {} | {} |\n", team.name(), seat, vote.points_for_team(team_id)));
+                        }
                     }
                 }
             },
@@ -1609,13 +4347,13 @@ def hello_world():
 
             for proposal_id in epoch.associated_proposals() {
                 if let Some(proposal) = self.state.get_proposal(&proposal_id) {
-                    if let Some(vote) = self.state.votes().values().find(|v| v.proposal_id() == *proposal_id) {
+                    if let Some(vote) = self.get_vote_for_proposal(*proposal_id) {
                         let (participation_type, points) = match (vote.vote_type(), vote.participation()) {
-                            (VoteType::Formal { counted_points, uncounted_points, .. }, VoteParticipation::Formal { counted, uncounted }) => {
+                            (VoteType::Formal { .. }, VoteParticipation::Formal { counted, uncounted }) => {
                                 if counted.contains(team_id) {
-                                    ("Counted", *counted_points)
+                                    ("Counted", vote.points_for_team(*team_id))
                                 } else if uncounted.contains(team_id) {
-                                    ("Uncounted", *uncounted_points)
+                                    ("Uncounted", vote.points_for_team(*team_id))
                                 } else {
                                     continue;
                                 }
@@ -1649,6 +4387,71 @@ def hello_world():
         Ok(report)
     }
 
+    /// Markdown table of an epoch's proposals grouped by category, with approved,
+    /// rejected and total counts and the requested and approved amounts per token.
+    pub fn generate_category_breakdown_report(&self, epoch_id: Uuid) -> Result<String, &'static str> {
+        #[derive(Default)]
+        struct CategoryTotals {
+            approved: usize,
+            rejected: usize,
+            total: usize,
+            requested: HashMap<String, f64>,
+            approved_amounts: HashMap<String, f64>,
+        }
+
+        let epoch = self.state.epochs().get(&epoch_id).ok_or("Epoch not found")?;
+        let mut by_category: BTreeMap<Option<ProposalCategory>, CategoryTotals> = BTreeMap::new();
+
+        for proposal_id in epoch.associated_proposals() {
+            let Some(proposal) = self.state.get_proposal(proposal_id) else { continue };
+            let totals = by_category.entry(proposal.category().cloned()).or_default();
+            totals.total += 1;
+            let amounts = proposal.budget_request_details().map(|d| d.request_amounts().clone()).unwrap_or_default();
+            for (token, amount) in &amounts {
+                *totals.requested.entry(token.clone()).or_insert(0.0) += amount;
+            }
+            if proposal.is_approved() {
+                totals.approved += 1;
+                for (token, amount) in &amounts {
+                    *totals.approved_amounts.entry(token.clone()).or_insert(0.0) += amount;
+                }
+            } else if proposal.is_rejected() {
+                totals.rejected += 1;
+            }
+        }
+
+        let mut report = format!("# Category Breakdown: {}\n\n", epoch.name());
+        if by_category.is_empty() {
+            report.push_str("No proposals in this epoch.\n");
+            return Ok(report);
+        }
+        report.push_str("| Category | Approved | Rejected | Total | Requested | Approved Amount |\n");
+        report.push_str("|----------|----------|----------|-------|-----------|-----------------|\n");
+        // Categories in declaration order, uncategorized proposals last
+        let (uncategorized, categorized): (Vec<_>, Vec<_>) = by_category.iter().partition(|(category, _)| category.is_none());
+        for (category, totals) in categorized.into_iter().chain(uncategorized) {
+            let label = category.as_ref().map_or("Uncategorized".to_string(), |c| c.to_string());
+            report.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+                label, totals.approved, totals.rejected, totals.total,
+                Self::format_category_amounts(&totals.requested),
+                Self::format_category_amounts(&totals.approved_amounts)));
+        }
+
+        Ok(report)
+    }
+
+    fn format_category_amounts(amounts: &HashMap<String, f64>) -> String {
+        if amounts.is_empty() {
+            return "-".to_string();
+        }
+        let mut amounts: Vec<_> = amounts.iter().collect();
+        amounts.sort_by(|a, b| a.0.cmp(b.0));
+        amounts.iter()
+            .map(|(token, amount)| format!("{:.2} {}", amount, token))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     pub fn get_team_points_history(&self, team_id: Uuid) -> Result<Vec<(Uuid, u32)>, &'static str> {
         self.state.epochs().iter()
             .map(|(&epoch_id, _)| {
@@ -1663,78 +4466,45 @@ def hello_world():
         let mut total_points = 0;
 
         for proposal_id in epoch.associated_proposals() {
-            if let Some(vote) = self.state.votes().values().find(|v| v.proposal_id() == *proposal_id) {
-                if let (VoteType::Formal { counted_points, uncounted_points, .. }, VoteParticipation::Formal { counted, uncounted }) = (vote.vote_type(), vote.participation()) {
-                    if counted.contains(&team_id) {
-                        total_points += counted_points;
-                    } else if uncounted.contains(&team_id) {
-                        total_points += uncounted_points;
-                    }
-                }
+            if let Some(vote) = self.get_vote_for_proposal(*proposal_id) {
+                total_points += vote.points_for_team(team_id);
             }
         }
 
         Ok(total_points)
     }
 
-    pub fn close_epoch(&mut self, epoch_name: Option<&str>) -> Result<(), Box<dyn Error>> {
+    pub fn close_epoch(&mut self, epoch_name: Option<&str>) -> Result<(), BudgetSystemError> {
+        // Proposals past the voting window are closed first so they don't block the epoch
         let epoch_id = match epoch_name {
-            Some(name) => self.get_epoch_id_by_name(name)
-                .ok_or_else(|| format!("Epoch not found: {}", name))?,
-            None => self.state.current_epoch()
-                .ok_or("No active epoch")?
+            Some(name) => self.get_epoch_id_by_name(name),
+            None => self.state.current_epoch(),
         };
-    
-        // Check for actionable proposals
-        let actionable_proposals = self.get_proposals_for_epoch(epoch_id)
-            .iter()
-            .filter(|p| p.is_actionable())
-            .count();
-    
-        if actionable_proposals > 0 {
-            return Err(format!("Cannot close epoch: {} actionable proposals remaining", actionable_proposals).into());
+        if let Some(epoch_id) = epoch_id {
+            self.enforce_proposal_announcement_window(epoch_id)?;
         }
-    
-        let total_points = self.get_total_points_for_epoch(epoch_id);
-        let mut team_rewards = HashMap::new();
+        let epoch_id = self.check_epoch_closable(epoch_name)?;
     
         // Calculate rewards
-        {
+        let team_rewards = {
             let epoch = self.state.get_epoch(&epoch_id)
-                .ok_or("Epoch not found")?;
-
-            if epoch.is_closed() {
-                return Err("Epoch is already closed".into());
-            }
-
-            if let Some(reward) = epoch.reward() {
-                if total_points == 0 {
-                    return Err("No points earned in this epoch".into());
-                }
-
-                for team_id in self.state.current_state().teams().keys() {
-                    let team_points = self.calculate_team_points_for_epoch(*team_id, epoch_id);
-                    let percentage = team_points as f64 / total_points as f64 * 100.0;
-                    let amount = reward.amount() * (percentage / 100.0);
+                .ok_or(BudgetSystemError::EpochNotFound(epoch_id))?;
 
-                    match TeamReward::new(percentage, amount) {
-                        Ok(team_reward) => {
-                            team_rewards.insert(*team_id, team_reward);
-                        },
-                        Err(e) => return Err(format!("Failed to create team reward: {}", e).into()),
-                    }
-                }
+            if !epoch.rewards().is_empty() {
+                self.calculate_team_rewards_for_epoch(epoch_id)?
+            } else {
+                HashMap::new()
             }
-        }
+        };
     
          // Update epoch
         {
             let epoch = self.state.get_epoch_mut(&epoch_id)
-                .ok_or("Epoch not found")?;
+                .ok_or(BudgetSystemError::EpochNotFound(epoch_id))?;
 
             epoch.set_status(EpochStatus::Closed);
             for (team_id, team_reward) in team_rewards {
-                epoch.set_team_reward(team_id, team_reward.percentage(), team_reward.amount())?;
+                epoch.set_team_reward(team_id, team_reward.percentage(), team_reward.amounts().clone())?;
             }
         }
 
@@ -1745,2252 +4515,9507 @@ def hello_world():
 
         let _ = self.save_state()?;
 
-        Ok(())
-    }
+        let proposal_count = self.get_proposals_for_epoch(epoch_id).len();
+        if let Some(epoch) = self.state.get_epoch(&epoch_id) {
+            let embed = DiscordEmbed::new(epoch.name(), COLOR_BLUE)
+                .field("Proposals", proposal_count.to_string(), true)
+                .field("Teams Rewarded", epoch.team_rewards().len().to_string(), true);
+            self.notify_discord(DiscordPayload::new("Epoch closed").with_embed(embed));
+        }
 
-    pub fn get_total_points_for_epoch(&self, epoch_id: Uuid) -> u32 {
-        self.state.current_state().teams().keys()
-            .map(|team_id| self.calculate_team_points_for_epoch(*team_id, epoch_id))
-            .sum()
+        Ok(())
     }
 
-    pub fn calculate_team_points_for_epoch(&self, team_id: Uuid, epoch_id: Uuid) -> u32 {
-        let epoch = match self.state.epochs().get(&epoch_id) {
-            Some(e) => e,
-            None => return 0,
+    /// Retracts every actionable proposal in the epoch, then closes it.
+    /// Returns the titles of the retracted proposals.
+    pub fn force_close_epoch(&mut self, epoch_name: Option<&str>) -> Result<Vec<String>, BudgetSystemError> {
+        let epoch_id = match epoch_name {
+            Some(name) => self.get_epoch_id_by_name(name)
+                .ok_or_else(|| format!("Epoch not found: {}", name))?,
+            None => self.state.current_epoch()
+                .ok_or(BudgetSystemError::NoActiveEpoch)?
         };
+        let epoch = self.state.get_epoch(&epoch_id).ok_or(BudgetSystemError::EpochNotFound(epoch_id))?;
+        if epoch.is_closed() {
+            return Err(BudgetSystemError::EpochAlreadyClosed);
+        }
+        let epoch_name = epoch.name().to_string();
 
-        epoch.associated_proposals().iter()
-            .filter_map(|proposal_id| self.state.votes().values().find(|v| v.proposal_id() == *proposal_id))
-            .map(|vote| match (vote.vote_type(), vote.participation()) {
-                (VoteType::Formal { counted_points, uncounted_points, .. }, VoteParticipation::Formal { counted, uncounted }) => {
-                    if counted.contains(&team_id) {
-                        *counted_points
-                    } else if uncounted.contains(&team_id) {
-                        *uncounted_points
-                    } else {
-                        0
-                    }
-                },
-                _ => 0,
-            })
-            .sum()
+        let mut actionable: Vec<(Uuid, String)> = self.get_proposals_for_epoch(epoch_id)
+            .iter()
+            .filter(|p| p.is_actionable())
+            .map(|p| (p.id(), p.title().to_string()))
+            .collect();
+        actionable.sort_by(|a, b| a.1.cmp(&b.1));
+
+        for (proposal_id, _) in &actionable {
+            self.close_with_reason(*proposal_id, &Resolution::Retracted)?;
+        }
+        self.close_epoch(Some(&epoch_name))?;
+
+        Ok(actionable.into_iter().map(|(_, title)| title).collect())
     }
 
-    pub fn generate_end_of_epoch_report(&self, epoch_name: &str) -> Result<(), Box<dyn Error>> {
+    /// Summary of a closed epoch: its reward totals and the points and
+    /// rewards earned by each participating team.
+    pub fn epoch_close_summary(&self, epoch_name: &str) -> Result<String, BudgetSystemError> {
         let epoch = self.state.epochs().values()
             .find(|e| e.name() == epoch_name)
             .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
 
-        if !epoch.is_closed() {
-            return Err("Cannot generate report: Epoch is not closed".into());
-        }
-
-        let mut report = String::new();
-
-        // Generate epoch summary
-        report.push_str(&self.generate_epoch_summary(epoch)?);
+        let mut summary = format!("Closed epoch: {}\n", epoch.name());
+        summary.push_str(&format!("Rewards: {}\n", Self::format_epoch_rewards(epoch)));
 
-        // Generate proposal tables and individual reports
-        report.push_str(&self.generate_proposal_tables(epoch)?);
+        let mut team_points: Vec<(&Team, u32)> = self.state.current_state().teams().values()
+            .map(|team| (team, self.calculate_team_points_for_epoch(team.id(), epoch.id())))
+            .filter(|(_, points)| *points > 0)
+            .collect();
+        team_points.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name().cmp(b.0.name())));
 
-        // Generate team summary
-        report.push_str(&self.generate_team_summary(epoch)?);
+        if team_points.is_empty() {
+            summary.push_str("No team earned points in this epoch\n");
+            return Ok(summary);
+        }
 
-        // Save the report
-        let file_name = format!("end_of_epoch_report-{}.md", FileSystem::sanitize_filename(epoch_name));
-        let state_file_path = Path::new(&self.config.state_file);
-        let report_path = state_file_path
-            .parent()
-            .unwrap_or_else(|| Path::new("."))
-            .join("reports")
-            .join(FileSystem::sanitize_filename(epoch_name))
-            .join(file_name);
+        summary.push_str("Team points:\n");
+        for (team, points) in team_points {
+            let reward = epoch.team_rewards().get(&team.id())
+                .map(|reward| {
+                    let amounts = epoch.rewards().iter()
+                        .map(|r| format!("{:.2} {}", reward.amount(r.token()), r.token()))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!(" ({:.2}%: {})", reward.percentage(), amounts)
+                })
+                .unwrap_or_default();
+            summary.push_str(&format!("  - {}: {} points{}\n", team.name(), points, reward));
+        }
+        Ok(summary)
+    }
 
-        fs::create_dir_all(report_path.parent().unwrap())?;
-        fs::write(&report_path, report)?;
+    fn calculate_team_rewards_for_epoch(&self, epoch_id: Uuid) -> Result<HashMap<Uuid, TeamReward>, Box<dyn Error>> {
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or("Epoch not found")?;
+        if epoch.rewards().is_empty() {
+            return Err("Epoch has no reward configured".into());
+        }
 
-        Ok(())
-    }
+        let total_points = self.get_total_points_for_epoch(epoch_id);
+        if total_points == 0 {
+            return Err("No points earned in this epoch".into());
+        }
 
-    pub fn generate_epoch_summary(&self, epoch: &Epoch) -> Result<String, Box<dyn Error>> {
-        let proposals = self.get_proposals_for_epoch(epoch.id());
-        let approved = proposals.iter().filter(|p| matches!(p.resolution(), Some(Resolution::Approved))).count();
-        let rejected = proposals.iter().filter(|p| matches!(p.resolution(), Some(Resolution::Rejected))).count();
-        let retracted = proposals.iter().filter(|p| matches!(p.resolution(), Some(Resolution::Retracted))).count();
+        let mut team_rewards = HashMap::new();
+        for team_id in self.state.current_state().teams().keys() {
+            let team_points = self.calculate_team_points_for_epoch(*team_id, epoch_id);
+            let percentage = team_points as f64 / total_points as f64 * 100.0;
+            let amounts = epoch.rewards().iter()
+                .map(|reward| (reward.token().to_string(), reward.amount() * (percentage / 100.0)))
+                .collect();
 
-        let summary = format!(
-            "# End of Epoch Report: {}\n\n\
-            ## Epoch Summary\n\
-            - **Period**: {} to {}\n\
-            - **Total Proposals**: {}\n\
-            - **Approved Proposals**: {}\n\
-            - **Rejected Proposals**: {}\n\
-            - **Retracted Proposals**: {}\n\
-            - **Total Reward**: {}\n\n",
-            epoch.name(),
-            epoch.start_date().format("%Y-%m-%d"),
-            epoch.end_date().format("%Y-%m-%d"),
-            proposals.len(),
-            approved,
-            rejected,
-            retracted,
-            epoch.reward().map_or("N/A".to_string(), |r| format!("{} {}", r.amount(), r.token())),
-        );
+            match TeamReward::new(percentage, amounts) {
+                Ok(team_reward) => {
+                    team_rewards.insert(*team_id, team_reward);
+                },
+                Err(e) => return Err(format!("Failed to create team reward: {}", e).into()),
+            }
+        }
 
-        Ok(summary)
+        Ok(team_rewards)
     }
 
-    pub fn generate_proposal_tables(&self, epoch: &Epoch) -> Result<String, Box<dyn Error>> {
-        let mut tables = String::new();
-        let proposals = self.get_proposals_for_epoch(epoch.id());
-    
-        let statuses = vec![
-            ("Approved", Resolution::Approved),
-            ("Rejected", Resolution::Rejected),
-            ("Retracted", Resolution::Retracted),
-        ];
-    
-        for (status, resolution) in statuses {
-            let filtered_proposals: Vec<&Proposal> = proposals.iter()
-                .filter(|p| matches!(p.resolution(), Some(r) if r == resolution))
-                .map(|p| *p)  // Dereference once to go from &&Proposal to &Proposal
-                .collect();
-    
-            if !filtered_proposals.is_empty() {
-                tables.push_str(&format!("### {} Proposals\n", status));
+    /// Admin escape hatch: sets an epoch's status directly, bypassing `activate_epoch`
+    /// and `close_epoch`. No rewards are calculated. Without `confirm` only the planned
+    /// change is described. Forcing Active fails while another epoch is active, and
+    /// forcing Closed with actionable proposals also requires `force`.
+    pub fn force_epoch_status(&mut self, epoch_name: &str, status: EpochStatus, confirm: bool, force: bool) -> Result<String, BudgetSystemError> {
+        let epoch_id = self.get_epoch_id_by_name(epoch_name)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_name)))?;
+        let current_status = self.state.get_epoch(&epoch_id)
+            .map(|e| e.status())
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_name)))?;
+
+        if current_status == status {
+            return Err(BudgetSystemError::Other(format!("Epoch '{}' is already {:?}", epoch_name, status)));
+        }
 
-                 // Different headers based on resolution
-                if resolution == Resolution::Approved {
-                    tables.push_str("| Name | URL | Team | Amounts | Start Date | End Date | Announced | Resolved | Paid | Report |\n");
-                    tables.push_str("|------|-----|------|---------|------------|----------|-----------|----------|------|--------|\n");
-                } else {
-                    tables.push_str("| Name | URL | Team | Amounts | Start Date | End Date | Announced | Resolved | Report |\n");
-                    tables.push_str("|------|-----|------|---------|------------|----------|-----------|----------|--------|\n");
+        let mut warnings = Vec::new();
+        match status {
+            EpochStatus::Active => {
+                if let Some(active_id) = self.state.current_epoch().filter(|id| *id != epoch_id) {
+                    let active_name = self.state.get_epoch(&active_id).map(|e| e.name().to_string()).unwrap_or_default();
+                    return Err(BudgetSystemError::Other(format!(
+                        "Cannot force '{}' to Active: epoch '{}' is already active", epoch_name, active_name
+                    )));
                 }
-    
-                for proposal in &filtered_proposals {
-                    // Generate individual proposal report
-                    let report_path = self.generate_and_save_proposal_report(proposal.id(), epoch.name())?;
-                    let report_link = report_path.file_name().unwrap().to_str().unwrap();
-    
-                    let team_name = proposal.budget_request_details()
-                        .and_then(|d| d.team())
-                        .and_then(|id| self.state.current_state().teams().get(&id))
-                        .map_or("N/A".to_string(), |t| t.name().to_string());
-
-                    let _payment_date = proposal.budget_request_details()
-                    .and_then(|d| d.payment_date())
-                    .map_or_else(
-                        || {
-                            if proposal.budget_request_details().is_some() {
-                                "Unpaid".to_string()
-                            } else {
-                                "N/A".to_string()
-                            }
-                        },
-                        |d| d.format("%Y-%m-%d").to_string()
-                    );
-    
-                    let amounts = proposal.budget_request_details()
-                        .map(|d| d.request_amounts().iter()
-                            .map(|(token, amount)| format!("{} {}", amount, token))
-                            .collect::<Vec<_>>()
-                            .join(", "))
-                        .unwrap_or_else(|| "N/A".to_string());
-
-                    if resolution == Resolution::Approved {
-                        let payment_date = proposal.budget_request_details()
-                            .and_then(|d| d.payment_date())
-                            .map_or_else(
-                                || {
-                                    if proposal.budget_request_details().is_some() {
-                                        "Unpaid".to_string()
-                                    } else {
-                                        "N/A".to_string()
-                                    }
-                                },
-                                |d| d.format("%Y-%m-%d").to_string()
-                            );
-
-                        tables.push_str(&format!(
-                            "| {} | {} | {} | {} | {} | {} | {} | {} | {} | [Report]({}) |\n",
-                            proposal.title(),
-                            proposal.url().as_deref().unwrap_or("N/A"),
-                            team_name,
-                            amounts,
-                            proposal.budget_request_details().and_then(|d| d.start_date()).map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-                            proposal.budget_request_details().and_then(|d| d.end_date()).map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-                            proposal.announced_at().map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-                            proposal.resolved_at().map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-                            payment_date,
-                            report_link,
-                        ));
-                    } else {
-                        tables.push_str(&format!(
-                            "| {} | {} | {} | {} | {} | {} | {} | {} | [Report]({}) |\n",
-                            proposal.title(),
-                            proposal.url().as_deref().unwrap_or("N/A"),
-                            team_name,
-                            amounts,
-                            proposal.budget_request_details().and_then(|d| d.start_date()).map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-                            proposal.budget_request_details().and_then(|d| d.end_date()).map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-                            proposal.announced_at().map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-                            proposal.resolved_at().map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-                            report_link,
-                        ));
+                if current_status == EpochStatus::Closed {
+                    warnings.push("Rewards already allocated for this epoch are kept and may be recalculated on the next close.".to_string());
+                }
+            },
+            EpochStatus::Closed => {
+                let actionable = self.get_proposals_for_epoch(epoch_id).iter()
+                    .filter(|p| p.is_actionable())
+                    .count();
+                if actionable > 0 {
+                    if !force {
+                        return Err(BudgetSystemError::Other(format!(
+                            "Cannot force '{}' to Closed: {} actionable proposal(s) remaining (use force to override)",
+                            epoch_name, actionable
+                        )));
                     }
+                    warnings.push(format!("{} actionable proposal(s) will be left open in a closed epoch.", actionable));
                 }
-                tables.push_str("\n");
-            }
+                warnings.push("Team rewards are not calculated; use the normal close or recompute them afterwards.".to_string());
+            },
+            EpochStatus::Planned => {
+                if current_status == EpochStatus::Closed {
+                    warnings.push("Rewards already allocated for this epoch are kept.".to_string());
+                }
+            },
         }
-    
-        Ok(tables)
-    }
-    
 
-    pub fn generate_team_summary(&self, epoch: &Epoch) -> Result<String, Box<dyn Error>> {
-        let mut summary = String::from("## Team Summary\n");
-        summary.push_str("| Team Name | Status | Counted Votes | Uncounted Votes | Total Points | % of Total Points | Reward Amount |\n");
-        summary.push_str("|-----------|--------|---------------|-----------------|--------------|-------------------|---------------|\n");
+        let mut output = format!(
+            "WARNING: forcing epoch '{}' from {:?} to {:?} bypasses the normal epoch lifecycle.\n",
+            epoch_name, current_status, status
+        );
+        for warning in &warnings {
+            output.push_str(&format!("  - {}\n", warning));
+        }
 
-        let total_points: u32 = self.state.current_state().teams().keys()
-            .map(|team_id| self.get_team_points_for_epoch(*team_id, epoch.id()).unwrap_or(0))
-            .sum();
+        if !confirm {
+            output.push_str("Run again with --confirm to apply.");
+            return Ok(output);
+        }
 
-        for (team_id, team) in self.state.current_state().teams() {
-            let status = format_team_status(team.status());
-            let team_points = self.get_team_points_for_epoch(*team_id, epoch.id()).unwrap_or(0);
-            let percentage = if total_points > 0 {
-                (team_points as f64 / total_points as f64) * 100.0
-            } else {
-                0.0
-            };
+        if let Some(epoch) = self.state.get_epoch_mut(&epoch_id) {
+            epoch.set_status(status);
+        }
+        match status {
+            EpochStatus::Active => self.state.set_current_epoch(Some(epoch_id)),
+            _ if self.state.current_epoch() == Some(epoch_id) => self.state.set_current_epoch(None),
+            _ => {},
+        }
 
-            let (counted_votes, uncounted_votes) = self.get_team_vote_counts(*team_id, epoch.id());
+        log::warn!("Epoch '{}' status forced from {:?} to {:?}", epoch_name, current_status, status);
+        for warning in &warnings {
+            log::warn!("{}", warning);
+        }
+        self.save_state().map_err(|e| BudgetSystemError::Other(e.to_string()))?;
 
-            let reward_amount = epoch.team_rewards().get(team_id)
-                .map(|reward| format!("{} {}", reward.amount(), epoch.reward().as_ref().map_or("".to_string(), |r| r.token().to_string())))
-                .unwrap_or_else(|| "N/A".to_string());
+        output.push_str(&format!("Epoch '{}' is now {:?}.", epoch_name, status));
+        Ok(output)
+    }
 
-            summary.push_str(&format!(
-                "| {} | {} | {} | {} | {} | {:.2}% | {} |\n",
-                team.name(),
-                status,
-                counted_votes,
-                uncounted_votes,
-                team_points,
-                percentage,
-                reward_amount
-            ));
+    /// Recalculates a closed epoch's team rewards from its current votes.
+    /// Without `confirm` only the differences are reported; with it the stored
+    /// rewards are overwritten.
+    pub fn recompute_epoch_rewards(&mut self, epoch_name: &str, confirm: bool) -> Result<String, Box<dyn Error>> {
+        let epoch_id = self.get_epoch_id_by_name(epoch_name)
+            .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
+
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or("Epoch not found")?;
+        if !epoch.is_closed() {
+            return Err("Cannot recompute rewards: Epoch is not closed".into());
         }
 
-        Ok(summary)
-    }
+        let stored_rewards = epoch.team_rewards().clone();
+        let recomputed_rewards = self.calculate_team_rewards_for_epoch(epoch_id)?;
 
-    pub fn get_team_vote_counts(&self, team_id: Uuid, epoch_id: Uuid) -> (u32, u32) {
-        let mut counted = 0;
-        let mut uncounted = 0;
+        let mut team_ids: Vec<Uuid> = stored_rewards.keys()
+            .chain(recomputed_rewards.keys())
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        team_ids.sort_by_key(|id| self.state.get_team(id)
+            .map(|t| t.name().to_string())
+            .unwrap_or_else(|| id.to_string()));
+
+        let tokens: Vec<&str> = epoch.rewards().iter().map(|r| r.token()).collect();
+        let format_amounts = |reward: Option<&TeamReward>| tokens.iter()
+            .map(|token| format!("{:.2} {}", reward.map_or(0.0, |r| r.amount(token)), token))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut differences = Vec::new();
+        for team_id in team_ids {
+            let old = stored_rewards.get(&team_id);
+            let new = recomputed_rewards.get(&team_id);
+            let old_percentage = old.map_or(0.0, |r| r.percentage());
+            let new_percentage = new.map_or(0.0, |r| r.percentage());
+            let amounts_differ = tokens.iter().any(|token| {
+                (old.map_or(0.0, |r| r.amount(token)) - new.map_or(0.0, |r| r.amount(token))).abs() > 1e-9
+            });
+            if (old_percentage - new_percentage).abs() > 1e-9 || amounts_differ {
+                let team_name = self.state.get_team(&team_id)
+                    .map(|t| t.name().to_string())
+                    .unwrap_or_else(|| format!("Unknown Team ({})", team_id));
+                differences.push(format!(
+                    "  {}: {:.2}% ({}) -> {:.2}% ({})\n",
+                    team_name, old_percentage, format_amounts(old), new_percentage, format_amounts(new)
+                ));
+            }
+        }
 
-        for vote in self.state.votes().values() {
-            if vote.epoch_id() == epoch_id {
-                match vote.participation() {
-                    VoteParticipation::Formal { counted: c, uncounted: u } => {
-                        if c.contains(&team_id) {
-                            counted += 1;
-                        } else if u.contains(&team_id) {
-                            uncounted += 1;
-                        }
-                    },
-                    VoteParticipation::Informal(_) => {}  // Informal votes are not counted here
-                }
+        let mut report = format!("Recomputed rewards for epoch '{}'\n", epoch_name);
+        if differences.is_empty() {
+            report.push_str("  Stored rewards match the current votes\n");
+            return Ok(report);
+        }
+        for line in &differences {
+            report.push_str(line);
+        }
+
+        if confirm {
+            let epoch = self.state.get_epoch_mut(&epoch_id)
+                .ok_or("Epoch not found")?;
+            for team_id in stored_rewards.keys() {
+                epoch.remove_team_reward(team_id);
             }
+            for (team_id, team_reward) in recomputed_rewards {
+                epoch.set_team_reward(team_id, team_reward.percentage(), team_reward.amounts().clone())?;
+            }
+            let _ = self.save_state();
+            report.push_str(&format!("Updated {} team reward(s)\n", differences.len()));
+        } else {
+            report.push_str("No changes written. Re-run with confirmation to apply.\n");
         }
 
-        (counted, uncounted)
+        Ok(report)
     }
 
-    /// Creates a new raffle with progress updates streamed as an async stream
-    ///
-    /// # Arguments
-    /// * `proposal_name` - Name of the proposal to create raffle for
-    /// * `block_offset` - Optional override for the default block offset
-    /// * `excluded_teams` - Optional list of team names to exclude
-    ///
-    /// # Returns
-    /// A stream of RaffleProgress updates that can be consumed asynchronously
-    pub async fn create_raffle_with_progress<'a>(
-        &'a mut self,
-        proposal_name: String,
-        block_offset: Option<u64>,
-        excluded_teams: Option<Vec<String>>,
-    ) -> impl Stream<Item = Result<RaffleProgress, RaffleCreationError>> + Send + 'a {
-        let config = self.config.clone();
-        let eth_service = Arc::clone(&self.ethereum_service);
-        
-        try_stream! {
-            // Do setup inside the stream
-            let (raffle_id, tickets) = self.prepare_raffle(&proposal_name, excluded_teams.clone(), &config)
-                .map_err(|e| RaffleCreationError(format!("Failed to prepare raffle: {}", e)))?;
-    
-            let ticket_ranges = self.group_tickets_by_team(&tickets);
-    
-            yield RaffleProgress::Preparing {
-                proposal_name: proposal_name.clone(),
-                raffle_id,
-                ticket_ranges,
+    pub fn get_total_points_for_epoch(&self, epoch_id: Uuid) -> u32 {
+        self.state.current_state().teams().keys()
+            .map(|team_id| self.calculate_team_points_for_epoch(*team_id, epoch_id))
+            .sum()
+    }
+
+    pub fn calculate_team_points_for_epoch(&self, team_id: Uuid, epoch_id: Uuid) -> u32 {
+        let epoch = match self.state.epochs().get(&epoch_id) {
+            Some(e) => e,
+            None => return 0,
+        };
+
+        epoch.associated_proposals().iter()
+            .filter_map(|proposal_id| self.get_vote_for_proposal(*proposal_id))
+            .map(|vote| vote.points_for_team(team_id))
+            .sum()
+    }
+
+    /// Points per epoch and team, recomputed from every formal vote in the state.
+    /// Votes count towards their own epoch, and seats are taken from the vote's raffle
+    /// result rather than the counted/uncounted split recorded on the vote.
+    pub fn recalculate_all_points(&self) -> HashMap<Uuid, HashMap<Uuid, u32>> {
+        let mut points: HashMap<Uuid, HashMap<Uuid, u32>> = HashMap::new();
+
+        for vote in self.state.votes().values() {
+            let (VoteType::Formal { raffle_id, counted_points, uncounted_points, abstain_points, .. },
+                VoteParticipation::Formal { counted, uncounted }) = (vote.vote_type(), vote.participation()) else {
+                continue;
             };
-    
-            let current_block = eth_service.get_current_block()
-                .await
-                .map_err(|e| RaffleCreationError(format!("Failed to get current block: {}", e)))?;
-                
-            let target_block = current_block + block_offset.unwrap_or(config.future_block_offset);
-    
-            while eth_service.get_current_block()
-                .await
-                .map_err(|e| RaffleCreationError(format!("Failed to get current block: {}", e)))? < target_block 
-            {
-                yield RaffleProgress::WaitingForBlock {
-                    proposal_name: proposal_name.clone(),
-                    raffle_id,
-                    current_block,
-                    target_block,
+            let raffle_result = self.state.get_raffle(raffle_id).and_then(|r| r.result());
+            let participants: HashSet<Uuid> = counted.iter().chain(uncounted).copied().collect();
+
+            let epoch_points = points.entry(vote.epoch_id()).or_default();
+            for team_id in participants {
+                let is_counted = match raffle_result {
+                    Some(result) if result.counted().contains(&team_id) => Some(true),
+                    Some(result) if result.uncounted().contains(&team_id) => Some(false),
+                    Some(_) => None,
+                    // Without a raffle result, trust the vote's own split
+                    None => Some(counted.contains(&team_id)),
                 };
-                
-                tokio::time::sleep(Duration::from_secs(1)).await;
-            }
-    
-            let randomness = eth_service.get_randomness(target_block)
-                .await
-                .map_err(|e| RaffleCreationError(format!("Failed to get randomness: {}", e)))?;
-    
-            yield RaffleProgress::RandomnessAcquired {
-                proposal_name: proposal_name.clone(),
-                raffle_id,
-                current_block,
-                target_block,
-                randomness: randomness.clone(),
-            };
-    
-            let raffle = self.finalize_raffle(raffle_id, current_block, target_block, randomness)
-                .await
-                .map_err(|e| RaffleCreationError(format!("Failed to finalize raffle: {}", e)))?;
-    
-            let (counted, uncounted) = if let Some(result) = raffle.result() {
-                let format_team_with_score = |team_id: &Uuid| {
-                    let snapshot = raffle.team_snapshots().iter()
-                        .find(|s| s.id() == *team_id)
-                        .unwrap();
-                    let best_score = raffle.tickets().iter()
-                        .filter(|t| t.team_id() == *team_id)
-                        .map(|t| t.score())
-                        .max_by(|a, b| a.partial_cmp(b).unwrap())
-                        .unwrap_or(0.0);
-                    (snapshot.status().clone(), format!("{} (score: {})", snapshot.name(), best_score))
+                let seat_points = match is_counted {
+                    Some(true) => *counted_points,
+                    Some(false) => *uncounted_points,
+                    None => 0,
                 };
-        
-                let counted: Vec<(TeamStatus, String)> = result.counted().iter()
-                    .map(|team_id| format_team_with_score(team_id))
-                    .collect();
-                let uncounted: Vec<(TeamStatus, String)> = result.uncounted().iter()
-                    .map(|team_id| format_team_with_score(team_id))
-                    .collect();
-                (counted, uncounted)
-            } else {
-                (Vec::new(), Vec::new())
-            };
-        
-            yield RaffleProgress::Completed {
-                proposal_name: proposal_name.clone(),
-                raffle_id,
-                counted,
-                uncounted,
-            };
+                let team_points = if vote.has_abstained(team_id) {
+                    (*abstain_points).min(seat_points)
+                } else {
+                    seat_points
+                };
+                *epoch_points.entry(team_id).or_insert(0) += team_points;
+            }
         }
+
+        points
     }
 
-    pub fn generate_unpaid_requests_report(
-        &self,
-        output_path: Option<&str>,
-        epoch_name: Option<&str>,
-    ) -> Result<String, Box<dyn Error>> {
-        // Collect unpaid requests
-        let unpaid_requests: Vec<UnpaidRequest> = self
-            .state
-            .proposals()
-            .iter()
-            .filter_map(|(proposal_id, proposal)| {
-                // Check if proposal is approved
-                if !proposal.is_approved() {
-                    return None;
+    /// Compares the points reported for each epoch and team with `recalculate_all_points`.
+    pub fn verify_point_consistency(&self) -> Result<(), Vec<PointMismatch>> {
+        let recalculated = self.recalculate_all_points();
+
+        let mut mismatches = Vec::new();
+        for &epoch_id in self.state.epochs().keys() {
+            let epoch_points = recalculated.get(&epoch_id);
+            let team_ids: HashSet<Uuid> = self.state.current_state().teams().keys()
+                .chain(epoch_points.into_iter().flat_map(|points| points.keys()))
+                .copied()
+                .collect();
+            for team_id in team_ids {
+                let stored = self.calculate_team_points_for_epoch(team_id, epoch_id);
+                let recalculated = epoch_points.and_then(|points| points.get(&team_id)).copied().unwrap_or(0);
+                if stored != recalculated {
+                    mismatches.push(PointMismatch { epoch_id, team_id, stored, recalculated });
                 }
+            }
+        }
+        // Votes whose epoch no longer exists
+        for (&epoch_id, epoch_points) in &recalculated {
+            if self.state.get_epoch(&epoch_id).is_none() {
+                for (&team_id, &recalculated) in epoch_points.iter().filter(|(_, &points)| points > 0) {
+                    mismatches.push(PointMismatch { epoch_id, team_id, stored: 0, recalculated });
+                }
+            }
+        }
 
-                // Check if it has budget details
-                let budget_details = match proposal.budget_request_details() {
-                    Some(details) => details,
-                    None => return None,
-                };
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            mismatches.sort_by_key(|m| (m.epoch_id, m.team_id));
+            Err(mismatches)
+        }
+    }
 
-                // Skip if already paid
-                if budget_details.is_paid() {
+    /// Counts, per active team, the epochs in which it took part in none of the
+    /// formal votes. Epochs without any formal vote are skipped.
+    pub fn get_team_absence_streaks(&self) -> Result<Vec<TeamAbsenceInfo>, BudgetSystemError> {
+        Ok(self.team_absence_streaks(None))
+    }
+
+    fn team_absence_streaks(&self, until: Option<DateTime<Utc>>) -> Vec<TeamAbsenceInfo> {
+        let mut epochs: Vec<&Epoch> = self.state.epochs().values()
+            .filter(|e| !e.is_planned())
+            .filter(|e| until.is_none_or(|until| e.start_date() <= until))
+            .collect();
+        epochs.sort_by_key(|e| e.start_date());
+
+        // Teams that participated in each epoch with at least one formal vote
+        let epoch_participants: Vec<(&Epoch, HashSet<Uuid>)> = epochs.into_iter()
+            .filter_map(|epoch| {
+                let formal_votes: Vec<&Vote> = self.state.votes().values()
+                    .filter(|v| v.epoch_id() == epoch.id())
+                    .filter(|v| matches!(v.vote_type(), VoteType::Formal { .. }))
+                    .collect();
+                if formal_votes.is_empty() {
                     return None;
                 }
+                let participants = formal_votes.iter()
+                    .flat_map(|v| match v.participation() {
+                        VoteParticipation::Formal { counted, uncounted } => {
+                            counted.iter().chain(uncounted.iter()).cloned().collect::<Vec<_>>()
+                        },
+                        VoteParticipation::Informal(_) => Vec::new(),
+                    })
+                    .collect();
+                Some((epoch, participants))
+            })
+            .collect();
 
-                // Get team name
-                let team_name = budget_details
-                    .team()
-                    .and_then(|team_id| self.state.current_state().teams().get(&team_id))
-                    .map(|team| team.name().to_string())
-                    .unwrap_or_else(|| "No Team".to_string());
-
-                // Get epoch name
-                let epoch = self.state.epochs().get(&proposal.epoch_id());
-                
-                // Filter by epoch if specified
-                if let Some(target_epoch) = epoch_name {
-                    if let Some(epoch) = epoch {
-                        if epoch.name() != target_epoch {
-                            return None;
-                        }
-                    }
+        let mut absences: Vec<TeamAbsenceInfo> = self.state.current_state().teams().values()
+            .filter(|team| team.is_active())
+            .map(|team| {
+                let total_absences = epoch_participants.iter()
+                    .filter(|(_, participants)| !participants.contains(&team.id()))
+                    .count();
+                let current_streak_epochs = epoch_participants.iter().rev()
+                    .take_while(|(_, participants)| !participants.contains(&team.id()))
+                    .count();
+                let last_participated_epoch = epoch_participants.iter().rev()
+                    .find(|(_, participants)| participants.contains(&team.id()))
+                    .map(|(epoch, _)| epoch.name().to_string());
+
+                TeamAbsenceInfo {
+                    team_name: team.name().to_string(),
+                    current_streak_epochs,
+                    last_participated_epoch,
+                    total_absences,
                 }
+            })
+            .collect();
 
-                let epoch_name = epoch
-                    .map(|e| e.name().to_string())
-                    .unwrap_or_else(|| "Unknown Epoch".to_string());
+        absences.sort_by(|a, b| b.current_streak_epochs.cmp(&a.current_streak_epochs)
+            .then_with(|| a.team_name.cmp(&b.team_name)));
 
-                // Get approval date
-                let approved_date = proposal.resolved_at()
-                    .unwrap_or_else(|| Utc::now().date_naive());
+        absences
+    }
 
-                Some(UnpaidRequest::new(
-                    *proposal_id,
-                    proposal.title().to_string(),
-                    team_name,
-                    budget_details.request_amounts().clone(),
-                    budget_details.payment_address().map(|addr| format!("{:?}", addr)),
-                    approved_date,
-                    budget_details.is_loan(),
-                    epoch_name,
-                    proposal.url().map(|u| u.to_string()),
-                    budget_details.start_date(),
-                ))
-            })
+    /// Aggregates every epoch (or only closed ones) into a single markdown report: an overall
+    /// summary, per-token totals, an epoch-by-epoch comparison with stablecoins grouped
+    /// under "Stables", team points and team funding split by stablecoins.
+    pub fn generate_all_epochs_report(&self, only_closed: bool) -> Result<String, BudgetSystemError> {
+        let mut epochs: Vec<&Epoch> = self.state.epochs().values()
+            .filter(|epoch| !only_closed || epoch.status() == EpochStatus::Closed)
             .collect();
+        epochs.sort_by_key(|epoch| epoch.start_date());
+
+        let mut requested: HashMap<String, f64> = HashMap::new();
+        let mut allocated: HashMap<String, f64> = HashMap::new();
+        let mut paid: HashMap<String, f64> = HashMap::new();
+        let mut resolutions: HashMap<String, usize> = HashMap::new();
+        let mut team_funding: HashMap<(String, String), (f64, f64)> = HashMap::new();
+        let mut team_points: HashMap<String, Vec<(String, u32)>> = HashMap::new();
+        let mut resolution_days = Vec::new();
+        let mut payment_days = Vec::new();
+        let mut total_proposals = 0;
+        let mut comparison = String::new();
+        // Per epoch: requested, allocated and paid amounts keyed by report column
+        let mut epoch_amounts: Vec<(&str, [HashMap<String, f64>; 3])> = Vec::new();
+
+        for epoch in &epochs {
+            let proposals = self.get_proposals_for_epoch(epoch.id());
+            let approved = proposals.iter().filter(|p| p.is_approved()).count();
+            let rejected = proposals.iter().filter(|p| p.is_rejected()).count();
+            let votes = self.state.votes().values().filter(|v| v.epoch_id() == epoch.id()).count();
+            total_proposals += proposals.len();
+
+            let mut amounts: [HashMap<String, f64>; 3] = Default::default();
+            for proposal in &proposals {
+                let resolution = proposal.resolution().map_or("Unresolved".to_string(), |r| format!("{:?}", r));
+                *resolutions.entry(resolution).or_insert(0) += 1;
+                if let (Some(announced), Some(resolved)) = (proposal.announced_at(), proposal.resolved_at()) {
+                    resolution_days.push((resolved - announced).num_days() as f64);
+                }
 
-        let report = UnpaidRequestsReport::new(unpaid_requests);
+                let Some(details) = proposal.budget_request_details() else { continue };
+                for (token, amount) in details.request_amounts() {
+                    *amounts[0].entry(token_column(token)).or_insert(0.0) += amount;
+                    *requested.entry(token.clone()).or_insert(0.0) += amount;
+                }
+                if !proposal.is_approved() {
+                    continue;
+                }
+                if let (Some(resolved), Some(payment_date)) = (proposal.resolved_at(), details.payment_date()) {
+                    payment_days.push((payment_date - resolved).num_days() as f64);
+                }
+                let team_name = details.team()
+                    .and_then(|id| self.state.get_team(&id))
+                    .map_or("Unassigned".to_string(), |team| team.name().to_string());
+                for (token, amount) in details.request_amounts() {
+                    *amounts[1].entry(token_column(token)).or_insert(0.0) += amount;
+                    *allocated.entry(token.clone()).or_insert(0.0) += amount;
+                    let funding = team_funding.entry((team_name.clone(), token.clone())).or_insert((0.0, 0.0));
+                    funding.0 += amount;
+                    if details.is_paid() {
+                        *amounts[2].entry(token_column(token)).or_insert(0.0) += amount;
+                        *paid.entry(token.clone()).or_insert(0.0) += amount;
+                        funding.1 += amount;
+                    }
+                }
+            }
 
-        // Generate output path if not provided
-        let output_path = output_path.map(PathBuf::from).unwrap_or_else(|| {
-            let date = Utc::now().format("%Y%m%d");
-            PathBuf::from(&self.config.state_file)
-                .parent()
-                .unwrap()
-                .join("reports")
-                .join(format!("unpaid_requests_{}.json", date))
-        });
+            for team in self.state.current_state().teams().values() {
+                let points = self.calculate_team_points_for_epoch(team.id(), epoch.id());
+                if points > 0 {
+                    team_points.entry(team.name().to_string()).or_default().push((epoch.name().to_string(), points));
+                }
+            }
 
-        // Create directory if it doesn't exist
-        if let Some(parent) = output_path.parent() {
-            fs::create_dir_all(parent)?;
+            comparison.push_str(&format!(
+                "| {} | {:?} | {} to {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                epoch.name(),
+                epoch.status(),
+                epoch.start_date().format("%Y-%m-%d"),
+                epoch.end_date().format("%Y-%m-%d"),
+                Self::format_epoch_rewards(epoch),
+                proposals.len(),
+                approved,
+                rejected,
+                proposals.len() - approved - rejected,
+                Self::format_approval_rate(approved, rejected),
+                votes,
+            ));
+            epoch_amounts.push((epoch.name(), amounts));
         }
 
-        // Write report to file
-        let json = serde_json::to_string_pretty(&report)?;
-        fs::write(&output_path, json)?;
+        let average = |values: &[f64]| if values.is_empty() {
+            "N/A".to_string()
+        } else {
+            format!("{:.1} days", values.iter().sum::<f64>() / values.len() as f64)
+        };
+        let count = |resolution: &str| resolutions.get(resolution).copied().unwrap_or(0);
+
+        let mut report = String::from("# All Epochs Report\n\n");
+        report.push_str(&format!(
+            "Generated on {} covering {} {}.\n\n",
+            Utc::now().format("%Y-%m-%d %H:%M UTC"),
+            epochs.len(),
+            if only_closed { "closed epoch(s)" } else { "epoch(s)" }
+        ));
+
+        report.push_str("## Summary\n");
+        report.push_str(&format!("- **Total Proposals**: {}\n", total_proposals));
+        for resolution in ["Approved", "Rejected", "Invalid", "Duplicate", "Retracted", "Unresolved"] {
+            report.push_str(&format!("- **{}**: {}\n", resolution, count(resolution)));
+        }
+        report.push_str(&format!("- **Approval Rate**: {}\n", Self::format_approval_rate(count("Approved"), count("Rejected"))));
+        report.push_str(&format!("- **Average Resolution Time**: {}\n", average(&resolution_days)));
+        report.push_str(&format!("- **Average Payment Time**: {}\n\n", average(&payment_days)));
 
-        Ok(format!("Generated unpaid requests report at: {:?}", output_path))
+        report.push_str("## Totals by Token\n\n");
+        if requested.is_empty() {
+            report.push_str("No budget requests.\n\n");
+        } else {
+            report.push_str("| Token | Requested | Allocated | Paid | Unpaid |\n");
+            report.push_str("|-------|----------:|----------:|-----:|-------:|\n");
+            let mut tokens: Vec<&String> = requested.keys().collect();
+            tokens.sort();
+            for token in tokens {
+                let allocated_amount = allocated.get(token).copied().unwrap_or(0.0);
+                let paid_amount = paid.get(token).copied().unwrap_or(0.0);
+                report.push_str(&format!(
+                    "| {} | {:.2} | {:.2} | {:.2} | {:.2} |\n",
+                    token, requested[token], allocated_amount, paid_amount, allocated_amount - paid_amount
+                ));
+            }
+            report.push('\n');
+        }
+
+        report.push_str("## Epoch Comparison\n\n");
+        if epochs.is_empty() {
+            report.push_str("No epochs to compare.\n\n");
+        } else {
+            report.push_str("| Epoch | Status | Period | Reward | Proposals | Approved | Rejected | Other | Approval Rate | Votes |\n");
+            report.push_str("|-------|--------|--------|--------|----------:|---------:|---------:|------:|--------------:|------:|\n");
+            report.push_str(&comparison);
+            report.push('\n');
+
+            // Stables first, then the remaining tokens alphabetically
+            let mut columns: Vec<String> = epoch_amounts.iter()
+                .flat_map(|(_, amounts)| amounts.iter().flat_map(|m| m.keys().cloned()))
+                .collect();
+            columns.sort_by_key(|column| (column != "Stables", column.clone()));
+            columns.dedup();
+
+            if !columns.is_empty() {
+                report.push_str("### Amounts by Epoch\n\n");
+                report.push_str(&format!("| Epoch | Measure | {} |\n", columns.join(" | ")));
+                report.push_str(&format!("|-------|---------|{}\n", "------:|".repeat(columns.len())));
+                for (epoch_name, amounts) in &epoch_amounts {
+                    for (measure, values) in ["Requested", "Allocated", "Paid"].iter().zip(amounts) {
+                        let cells: Vec<String> = columns.iter()
+                            .map(|column| format!("{:.2}", values.get(column).copied().unwrap_or(0.0)))
+                            .collect();
+                        report.push_str(&format!("| {} | {} | {} |\n", epoch_name, measure, cells.join(" | ")));
+                    }
+                }
+                report.push('\n');
+            }
+        }
+
+        report.push_str("## Team Points\n\n");
+        if team_points.is_empty() {
+            report.push_str("No points earned.\n\n");
+        } else {
+            report.push_str("| Team | Total Points | By Epoch |\n");
+            report.push_str("|------|-------------:|----------|\n");
+            let mut teams: Vec<(&String, &Vec<(String, u32)>)> = team_points.iter().collect();
+            teams.sort_by(|a, b| a.0.cmp(b.0));
+            for (team, points) in teams {
+                let total: u32 = points.iter().map(|(_, p)| p).sum();
+                let by_epoch: Vec<String> = points.iter().map(|(epoch, p)| format!("{}: {}", epoch, p)).collect();
+                report.push_str(&format!("| {} | {} | {} |\n", team, total, by_epoch.join(", ")));
+            }
+            report.push('\n');
+        }
+
+        report.push_str("## Team Funding\n\n");
+        let mut funding: Vec<_> = team_funding.iter().collect();
+        funding.sort_by(|a, b| a.0.cmp(b.0));
+        for (title, stable) in [("Stablecoins", true), ("Non-Stablecoins", false)] {
+            report.push_str(&format!("### {}\n\n", title));
+            let rows: Vec<_> = funding.iter().filter(|((_, token), _)| is_stablecoin(token) == stable).collect();
+            if rows.is_empty() {
+                report.push_str("None.\n\n");
+                continue;
+            }
+            report.push_str("| Team | Token | Allocated | Paid |\n");
+            report.push_str("|------|-------|----------:|-----:|\n");
+            for ((team, token), (team_allocated, team_paid)) in rows {
+                report.push_str(&format!("| {} | {} | {:.2} | {:.2} |\n", team, token, team_allocated, team_paid));
+            }
+            report.push('\n');
+        }
+
+        Ok(report)
     }
 
-    pub fn record_payments(
-        &mut self,
-        payment_tx: &str,
-        payment_date: NaiveDate,
-        proposal_names: &[String]
-    ) -> Result<String, Box<dyn Error>> {
-        if payment_date > Utc::now().date_naive() {
-            return Err("Payment date cannot be in the future".into());
+    /// Compares formal vote participation across epochs (or only closed ones), oldest
+    /// first. The trend arrow compares an epoch's average counted participation with
+    /// the previous epoch that held formal votes. Teams drawn into a raffle that did
+    /// not vote count as absent.
+    pub fn generate_vote_participation_trends(&self, only_closed: bool) -> Result<String, Box<dyn Error>> {
+        let mut epochs: Vec<&Epoch> = self.state.epochs().values()
+            .filter(|epoch| !only_closed || epoch.status() == EpochStatus::Closed)
+            .collect();
+        epochs.sort_by_key(|epoch| epoch.start_date());
+
+        let mut report = String::from("# Vote Participation Trends\n\n");
+        if epochs.is_empty() {
+            report.push_str(if only_closed { "No closed epochs.\n" } else { "No epochs.\n" });
+            return Ok(report);
         }
 
-        let mut updated_proposals = Vec::new();
+        report.push_str("| Epoch | Status | Formal Votes | Counted Participation | Uncounted Participation | Points Awarded | Trend |\n");
+        report.push_str("|-------|--------|-------------:|----------------------:|------------------------:|---------------:|:-----:|\n");
 
-        // Validate all proposals first
-        for name in proposal_names {
-            let proposal_id = self.get_proposal_id_by_name(name)
-                .ok_or_else(|| format!("Proposal not found: {}", name))?;
+        // Team name -> (absences, times drawn)
+        let mut absences: HashMap<String, (u32, u32)> = HashMap::new();
+        let mut previous_rate: Option<f64> = None;
+        let average = |rates: &[f64]| (!rates.is_empty()).then(|| rates.iter().sum::<f64>() / rates.len() as f64);
 
-            let proposal = self.get_proposal(&proposal_id)
-                .ok_or_else(|| format!("Proposal not found: {}", name))?;
+        for epoch in &epochs {
+            let votes: Vec<&Vote> = self.state.votes().values()
+                .filter(|v| v.epoch_id() == epoch.id())
+                .filter(|v| matches!(v.vote_type(), VoteType::Formal { .. }))
+                .collect();
 
-            if !proposal.is_approved() {
-                return Err(format!("Proposal '{}' is not approved", name).into());
-            }
+            let mut counted_rates = Vec::new();
+            let mut uncounted_rates = Vec::new();
+            let mut points = 0;
+            for vote in &votes {
+                let (VoteType::Formal { raffle_id, total_eligible_seats, .. }, VoteParticipation::Formal { counted, uncounted }) = (vote.vote_type(), vote.participation()) else {
+                    continue;
+                };
+                if *total_eligible_seats > 0 {
+                    counted_rates.push(counted.len() as f64 / *total_eligible_seats as f64);
+                }
+                points += counted.iter().chain(uncounted).map(|&team_id| vote.points_for_team(team_id)).sum::<u32>();
 
-            if let Some(details) = proposal.budget_request_details() {
-                if details.is_paid() {
-                    return Err(format!("Proposal '{}' is already paid", name).into());
+                let Some(result) = self.state.get_raffle(raffle_id).and_then(|r| r.result()) else { continue };
+                if !result.uncounted().is_empty() {
+                    uncounted_rates.push(uncounted.len() as f64 / result.uncounted().len() as f64);
                 }
-            } else {
-                return Err(format!("Proposal '{}' has no budget request", name).into());
+                for team_id in result.counted().iter().chain(result.uncounted()) {
+                    let Some(team) = self.state.get_team(team_id) else { continue };
+                    let entry = absences.entry(team.name().to_string()).or_insert((0, 0));
+                    entry.1 += 1;
+                    if !counted.contains(team_id) && !uncounted.contains(team_id) {
+                        entry.0 += 1;
+                    }
+                }
+            }
+
+            let counted_rate = average(&counted_rates);
+            let trend = match (previous_rate, counted_rate) {
+                (Some(previous), Some(current)) if (current - previous).abs() < 1e-9 => "→",
+                (Some(previous), Some(current)) if current > previous => "↑",
+                (Some(_), Some(_)) => "↓",
+                _ => "-",
+            };
+            if counted_rate.is_some() {
+                previous_rate = counted_rate;
             }
+
+            let format_rate = |rate: Option<f64>| rate.map_or("N/A".to_string(), |r| format!("{:.1}%", r * 100.0));
+            report.push_str(&format!(
+                "| {} | {:?} | {} | {} | {} | {} | {} |\n",
+                epoch.name(),
+                epoch.status(),
+                votes.len(),
+                format_rate(counted_rate),
+                format_rate(average(&uncounted_rates)),
+                points,
+                trend,
+            ));
         }
 
-        // Update proposals
-        for name in proposal_names {
-            let proposal_id = self.get_proposal_id_by_name(name).unwrap();
-            
-            if let Some(mut details) = self.get_proposal(&proposal_id).unwrap().budget_request_details().cloned() {
-                details.record_payment(payment_tx.to_string(), payment_date)?;
-                
-                let proposal = self.state.get_proposal_mut(&proposal_id)
-                    .ok_or_else(|| format!("Failed to get mutable reference to proposal: {}", name))?;
-                proposal.set_budget_request_details(Some(details));
-                updated_proposals.push(name.clone());
+        report.push_str("\n## Most Absent Teams\n\n");
+        let mut most_absent: Vec<(String, (u32, u32))> = absences.into_iter()
+            .filter(|(_, (absent, _))| *absent > 0)
+            .collect();
+        most_absent.sort_by(|a, b| b.1.0.cmp(&a.1.0).then_with(|| a.0.cmp(&b.0)));
+        if most_absent.is_empty() {
+            report.push_str("No absences recorded.\n");
+        } else {
+            report.push_str("| Team | Absences | Times Drawn |\n");
+            report.push_str("|------|---------:|------------:|\n");
+            for (team, (absent, drawn)) in most_absent.into_iter().take(5) {
+                report.push_str(&format!("| {} | {} | {} |\n", team, absent, drawn));
             }
         }
 
-        let _ = self.save_state()?;
-        Ok(format!("Payment recorded for proposals: {}", updated_proposals.join(", ")))
+        Ok(report)
     }
 
-    pub fn generate_epoch_payments_report(
-        &self,
-        epoch_name: &str,
-        output_path: Option<&str>
-    ) -> Result<String, Box<dyn Error>> {
-        // Find epoch and validate it's closed
-        let epoch = self.state.epochs()
-            .values()
+    fn format_approval_rate(approved: usize, rejected: usize) -> String {
+        let resolved = approved + rejected;
+        if resolved == 0 {
+            "N/A".to_string()
+        } else {
+            format!("{:.1}%", approved as f64 / resolved as f64 * 100.0)
+        }
+    }
+
+    fn format_epoch_rewards(epoch: &Epoch) -> String {
+        if epoch.rewards().is_empty() {
+            return "N/A".to_string();
+        }
+        epoch.rewards().iter()
+            .map(|r| format!("{} {}", r.amount(), r.token()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Saves the end-of-epoch report as Markdown. With `ReportFormat::Csv` the
+    /// proposal tables and team summary are also saved as one CSV file per table,
+    /// with `ReportFormat::Json` as a single JSON file of the same rows.
+    pub fn generate_end_of_epoch_report(&self, epoch_name: &str, format: ReportFormat) -> Result<(), Box<dyn Error>> {
+        let epoch = self.state.epochs().values()
             .find(|e| e.name() == epoch_name)
             .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
 
         if !epoch.is_closed() {
-            return Err("Cannot generate payments report: Epoch is not closed".into());
+            return Err("Cannot generate report: Epoch is not closed".into());
         }
 
-        let reward = epoch.reward()
-            .ok_or("Epoch has no reward configured")?;
+        let report = self.build_end_of_epoch_report(epoch, true)?;
 
-        // Build payments list
-        let payments = epoch.team_rewards()
-            .iter()
-            .filter_map(|(&team_id, team_reward)| {
-                let team = self.state.current_state().teams().get(&team_id)?;
-                Some(TeamPayment::new(
-                    team.name().to_string(),
-                    team.payment_address().cloned(),
-                    team_reward.amount(),
-                    team_reward.percentage(),
-                ))
+        // Save the report
+        let sanitized_epoch = FileSystem::sanitize_filename(epoch_name);
+        let file_name = format!("end_of_epoch_report-{}.md", sanitized_epoch);
+        let state_file_path = Path::new(&self.config.state_file);
+        let report_dir = state_file_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("reports")
+            .join(&sanitized_epoch);
+        let report_path = report_dir.join(file_name);
+
+        fs::create_dir_all(report_path.parent().unwrap())?;
+        fs::write(&report_path, report)?;
+
+        match format {
+            ReportFormat::Csv => {
+                for (table, rows) in self.end_of_epoch_tables(epoch) {
+                    let mut buffer = Vec::new();
+                    export::write_delimited(&rows, ',', &mut buffer)?;
+                    fs::write(report_dir.join(format!("{}-{}.csv", table, sanitized_epoch)), buffer)?;
+                }
+            },
+            ReportFormat::Json => {
+                let tables: serde_json::Map<String, serde_json::Value> = self.end_of_epoch_tables(epoch).into_iter()
+                    .map(|(table, rows)| (table, Self::rows_to_json(&rows)))
+                    .collect();
+                fs::write(
+                    report_dir.join(format!("end_of_epoch_report-{}.json", sanitized_epoch)),
+                    serde_json::to_string_pretty(&tables)?,
+                )?;
+            },
+            ReportFormat::Text | ReportFormat::Markdown => {},
+        }
+
+        Ok(())
+    }
+
+    /// Proposal tables (only those with proposals) and the team summary as
+    /// header plus data rows, with amounts split into one column per token.
+    fn end_of_epoch_tables(&self, epoch: &Epoch) -> Vec<(String, Vec<Vec<String>>)> {
+        let proposals = self.get_proposals_for_epoch(epoch.id());
+        let mut tables = Vec::new();
+
+        for (status, resolution) in Self::proposal_table_statuses() {
+            let filtered: Vec<&Proposal> = proposals.iter()
+                .filter(|p| matches!(p.resolution(), Some(r) if r == resolution))
+                .copied()
+                .collect();
+            if filtered.is_empty() {
+                continue;
+            }
+
+            let include_paid = resolution == Resolution::Approved;
+            let rows: Vec<ProposalTableRow> = filtered.iter()
+                .map(|proposal| {
+                    let report_path = FileSystem::generate_report_file_path(proposal, epoch.name(), Path::new(&self.config.state_file));
+                    let report_link = report_path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+                    self.proposal_table_row(proposal, include_paid, report_link)
+                })
+                .collect();
+
+            let mut tokens: Vec<&str> = rows.iter()
+                .flat_map(|row| row.amounts.iter().flatten().map(|(token, _)| token.as_str()))
+                .collect();
+            tokens.sort();
+            tokens.dedup();
+
+            let mut table = vec![Self::split_amount_headers(Self::proposal_table_headers(include_paid), "Amounts", &tokens)];
+            for row in &rows {
+                let mut cells = row.leading.clone();
+                cells.extend(Self::amount_cells(row.amounts.as_deref().unwrap_or_default(), &tokens));
+                cells.extend(row.trailing.iter().cloned());
+                cells.push(row.report_link.clone());
+                table.push(cells);
+            }
+            tables.push((format!("proposals_{}", status.to_lowercase()), table));
+        }
+
+        let tokens: Vec<&str> = epoch.rewards().iter().map(|r| r.token()).collect();
+        let mut table = vec![Self::split_amount_headers(&TEAM_SUMMARY_HEADERS, "Reward Amount", &tokens)];
+        for row in self.team_summary_rows(epoch) {
+            let mut cells = vec![
+                row.name,
+                row.status,
+                row.counted_votes.to_string(),
+                row.uncounted_votes.to_string(),
+                row.points.to_string(),
+                format!("{:.2}%", row.percentage),
+            ];
+            cells.extend(Self::amount_cells(row.rewards.as_deref().unwrap_or_default(), &tokens));
+            table.push(cells);
+        }
+        tables.push(("team_summary".to_string(), table));
+
+        tables
+    }
+
+    /// Replaces the `amount_header` column with one `<header> (<TOKEN>)` column per token.
+    fn split_amount_headers(headers: &[&str], amount_header: &str, tokens: &[&str]) -> Vec<String> {
+        headers.iter()
+            .flat_map(|header| {
+                if *header == amount_header {
+                    tokens.iter().map(|token| format!("{} ({})", header, token)).collect()
+                } else {
+                    vec![header.to_string()]
+                }
             })
-            .collect();
+            .collect()
+    }
 
-        let report = EpochPaymentsReport::new(
-            epoch.name().to_string(),
-            reward.token().to_string(),
-            reward.amount(),
-            payments,
+    fn amount_cells(amounts: &[(String, f64)], tokens: &[&str]) -> Vec<String> {
+        tokens.iter()
+            .map(|token| amounts.iter()
+                .find(|(t, _)| t == token)
+                .map(|(_, amount)| amount.to_string())
+                .unwrap_or_default())
+            .collect()
+    }
+
+    fn rows_to_json(rows: &[Vec<String>]) -> serde_json::Value {
+        let Some((headers, data)) = rows.split_first() else {
+            return serde_json::Value::Array(Vec::new());
+        };
+        data.iter()
+            .map(|row| headers.iter().cloned()
+                .zip(row.iter().cloned().map(serde_json::Value::String))
+                .collect::<serde_json::Map<_, _>>())
+            .map(serde_json::Value::Object)
+            .collect()
+    }
+
+    /// Builds the end-of-epoch report from current state without saving anything
+    /// and without requiring the epoch to be closed.
+    pub fn preview_close_epoch_report(&self, epoch_name: &str) -> Result<String, BudgetSystemError> {
+        let epoch = self.state.epochs().values()
+            .find(|e| e.name() == epoch_name)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_name)))?;
+
+        let mut report = String::from("# PREVIEW — NOT FINAL\n\n");
+        report.push_str(&format!(
+            "Generated from current state on {}. Nothing has been saved.\n",
+            Utc::now().format("%Y-%m-%d %H:%M UTC")
+        ));
+        if !epoch.is_closed() {
+            let open_proposals = self.get_proposals_for_epoch(epoch.id()).iter()
+                .filter(|p| p.is_actionable())
+                .count();
+            report.push_str(&format!(
+                "The epoch is not closed yet: {} proposal(s) are still open and rewards have not been allocated.\n",
+                open_proposals
+            ));
+        }
+        report.push('\n');
+
+        let body = self.build_end_of_epoch_report(epoch, false)
+            .map_err(|e| BudgetSystemError::Other(e.to_string()))?;
+        report.push_str(&body);
+
+        Ok(report)
+    }
+
+    fn build_end_of_epoch_report(&self, epoch: &Epoch, save_proposal_reports: bool) -> Result<String, Box<dyn Error>> {
+        let mut report = String::new();
+
+        // Generate epoch summary
+        report.push_str(&self.generate_epoch_summary(epoch)?);
+
+        // Generate proposal tables and individual reports
+        report.push_str(&self.build_proposal_tables(epoch, save_proposal_reports)?);
+
+        // Generate team summary
+        report.push_str(&self.generate_team_summary(epoch)?);
+
+        // Generate geographic diversity
+        report.push_str(&self.generate_geographic_diversity());
+
+        Ok(report)
+    }
+
+    pub fn generate_epoch_summary(&self, epoch: &Epoch) -> Result<String, Box<dyn Error>> {
+        let proposals = self.get_proposals_for_epoch(epoch.id());
+        let approved = proposals.iter().filter(|p| matches!(p.resolution(), Some(Resolution::Approved))).count();
+        let rejected = proposals.iter().filter(|p| matches!(p.resolution(), Some(Resolution::Rejected))).count();
+        let retracted = proposals.iter().filter(|p| matches!(p.resolution(), Some(Resolution::Retracted))).count();
+
+        let summary = format!(
+            "# End of Epoch Report: {}\n\n\
+            ## Epoch Summary\n\
+            - **Period**: {} to {}\n\
+            - **Total Proposals**: {}\n\
+            - **Approved Proposals**: {}\n\
+            - **Rejected Proposals**: {}\n\
+            - **Retracted Proposals**: {}\n\
+            - **Total Reward**: {}\n\n",
+            epoch.name(),
+            epoch.start_date().format("%Y-%m-%d"),
+            epoch.end_date().format("%Y-%m-%d"),
+            proposals.len(),
+            approved,
+            rejected,
+            retracted,
+            Self::format_epoch_rewards(epoch),
         );
 
-        // Generate output path and save report
-        if let Some(path) = output_path {
-            let json = serde_json::to_string_pretty(&report)?;
-            let output_path = PathBuf::from(path);
-            if let Some(parent) = output_path.parent() {
-                fs::create_dir_all(parent)?;
-            }
-            fs::write(&output_path, json)?;
-            Ok(format!("Generated epoch payments report at: {:?}", output_path))
+        Ok(summary)
+    }
+
+    pub fn generate_proposal_tables(&self, epoch: &Epoch) -> Result<String, Box<dyn Error>> {
+        self.build_proposal_tables(epoch, true)
+    }
+
+    fn proposal_table_statuses() -> [(&'static str, Resolution); 3] {
+        [
+            ("Approved", Resolution::Approved),
+            ("Rejected", Resolution::Rejected),
+            ("Retracted", Resolution::Retracted),
+        ]
+    }
+
+    fn proposal_table_headers(include_paid: bool) -> &'static [&'static str] {
+        if include_paid {
+            &["Name", "URL", "Team", "Amounts", "Start Date", "End Date", "Announced", "Resolved", "Paid", "Report"]
         } else {
-            let json = serde_json::to_string_pretty(&report)?;
-            Ok(json)
+            &["Name", "URL", "Team", "Amounts", "Start Date", "End Date", "Announced", "Resolved", "Report"]
         }
     }
 
-}
+    fn markdown_table_header(headers: &[&str]) -> String {
+        format!(
+            "| {} |\n|{}|\n",
+            headers.join(" | "),
+            headers.iter().map(|h| "-".repeat(h.len() + 2)).collect::<Vec<_>>().join("|")
+        )
+    }
 
-#[async_trait]
-impl CommandExecutor for BudgetSystem {
-    async fn execute_command(&mut self, command: Command) -> Result<String, Box<dyn std::error::Error>> {
-        match command {
-            Command::CreateEpoch { name, start_date, end_date } => {
-                let epoch_id = self.create_epoch(&name, start_date, end_date)?;
-                Ok(format!("Created epoch: {} ({})", name, epoch_id))
-            },
-            Command::ActivateEpoch { name } => {
-                let epoch_id = self.get_epoch_id_by_name(&name)
-                    .ok_or_else(|| format!("Epoch not found: {}", name))?;
-                self.activate_epoch(epoch_id)?;
-                Ok(format!("Activated epoch: {} ({})", name, epoch_id))
-            },
-            Command::SetEpochReward { token, amount } => {
-                self.set_epoch_reward(&token, amount)?;
-                Ok(format!("Set epoch reward: {} {}", amount, token))
-            },
-            Command::AddTeam { name, representative, trailing_monthly_revenue, address} => {
-                let team_id = self.create_team(name.clone(), representative, trailing_monthly_revenue, address)?;
-                Ok(format!("Added team: {} ({})", name, team_id))
-            },
-            Command::UpdateTeam { team_name, updates } => {
-                let team_id = self.get_team_id_by_name(&team_name)
-                    .ok_or_else(|| format!("Team not found: {}", team_name))?;
-                self.update_team(team_id, updates)?;
-                Ok(format!("Updated team: {}", team_name))
-            },
-            Command::AddProposal { title, url, budget_request_details, announced_at, published_at, is_historical } => {
-                let budget_request_details = budget_request_details.map(|details| {
-                    BudgetRequestDetails::new(
-                        details.team.and_then(|name| self.get_team_id_by_name(&name)),
-                        details.request_amounts.unwrap_or_default(),
-                        details.start_date,
-                        details.end_date,
-                        details.is_loan,
-                        details.payment_address,
-                    )
-                }).transpose()?;
-             
-                let proposal_id = self.add_proposal(title.clone(), url, budget_request_details, announced_at, published_at, is_historical)?;
-                Ok(format!("Added proposal: {} ({})", title, proposal_id))
-             },
-            Command::UpdateProposal { proposal_name, updates } => {
-                self.update_proposal(&proposal_name, updates)?;
-                Ok(format!("Updated proposal: {}", proposal_name))
-            },
-            Command::ImportPredefinedRaffle { 
-                proposal_name, 
-                counted_teams, 
-                uncounted_teams, 
-                total_counted_seats, 
-                max_earner_seats 
-            } => {
-                let raffle_id = self.import_predefined_raffle(
-                    &proposal_name, 
-                    counted_teams.clone(), 
-                    uncounted_teams.clone(), 
-                    total_counted_seats, 
-                    max_earner_seats
-                )?;
-                
-                let raffle = self.state().raffles().get(&raffle_id).unwrap();
-            
-                let mut output = format!("Imported predefined raffle for proposal '{}' (Raffle ID: {})\n", proposal_name, raffle_id);
-                output += &format!("  Counted teams: {:?}\n", counted_teams);
-                output += &format!("  Uncounted teams: {:?}\n", uncounted_teams);
-                output += &format!("  Total counted seats: {}\n", total_counted_seats);
-                output += &format!("  Max earner seats: {}\n", max_earner_seats);
-            
-                output += "\nTeam Snapshots:\n";
-                for snapshot in raffle.team_snapshots() {
-                    output += &format!("  {} ({}): {:?}\n", snapshot.name(), snapshot.id(), snapshot.status());
+    /// Escapes pipes and turns line breaks into `<br>` so a value stays inside its table cell.
+    fn markdown_table_cell(value: &str) -> String {
+        value.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>")
+    }
+
+    fn proposal_table_row(&self, proposal: &Proposal, include_paid: bool, report_link: String) -> ProposalTableRow {
+        let details = proposal.budget_request_details();
+        let format_date = |date: Option<NaiveDate>| date.map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string());
+
+        let team_name = details
+            .and_then(|d| d.team())
+            .and_then(|id| self.state.current_state().teams().get(&id))
+            .map_or("N/A".to_string(), |t| t.name().to_string());
+
+        let mut trailing = vec![
+            format_date(details.and_then(|d| d.start_date())),
+            format_date(details.and_then(|d| d.end_date())),
+            format_date(proposal.announced_at()),
+            format_date(proposal.resolved_at()),
+        ];
+        if include_paid {
+            trailing.push(match details {
+                Some(d) => d.payment_date().map_or("Unpaid".to_string(), |date| date.format("%Y-%m-%d").to_string()),
+                None => "N/A".to_string(),
+            });
+        }
+
+        ProposalTableRow {
+            leading: vec![
+                proposal.title().to_string(),
+                proposal.url().unwrap_or("N/A").to_string(),
+                team_name,
+            ],
+            amounts: details.map(|d| d.request_amounts().iter()
+                .map(|(token, amount)| (token.clone(), *amount))
+                .collect()),
+            trailing,
+            report_link,
+        }
+    }
+
+    fn build_proposal_tables(&self, epoch: &Epoch, save_proposal_reports: bool) -> Result<String, Box<dyn Error>> {
+        let mut tables = String::new();
+        let proposals = self.get_proposals_for_epoch(epoch.id());
+    
+        for (status, resolution) in Self::proposal_table_statuses() {
+            let filtered_proposals: Vec<&Proposal> = proposals.iter()
+                .filter(|p| matches!(p.resolution(), Some(r) if r == resolution))
+                .map(|p| *p)  // Dereference once to go from &&Proposal to &Proposal
+                .collect();
+    
+            if !filtered_proposals.is_empty() {
+                tables.push_str(&format!("### {} Proposals\n", status));
+
+                // Only approved proposals have a payment column
+                let include_paid = resolution == Resolution::Approved;
+                tables.push_str(&Self::markdown_table_header(Self::proposal_table_headers(include_paid)));
+    
+                for proposal in &filtered_proposals {
+                    // Generate individual proposal report
+                    let report_path = if save_proposal_reports {
+                        self.generate_and_save_proposal_report(proposal.id(), epoch.name(), ReportSections::all())?
+                    } else {
+                        FileSystem::generate_report_file_path(proposal, epoch.name(), Path::new(&self.config.state_file))
+                    };
+                    let report_link = report_path.file_name().unwrap().to_str().unwrap().to_string();
+
+                    let row = self.proposal_table_row(proposal, include_paid, report_link);
+                    let amounts = row.amounts.as_ref()
+                        .map(|amounts| amounts.iter()
+                            .map(|(token, amount)| format!("{} {}", amount, token))
+                            .collect::<Vec<_>>()
+                            .join(", "))
+                        .unwrap_or_else(|| "N/A".to_string());
+
+                    let mut cells = row.leading;
+                    cells.push(amounts);
+                    cells.extend(row.trailing);
+                    cells.push(format!("[Report]({})", row.report_link));
+                    tables.push_str(&format!("| {} |\n", cells.join(" | ")));
                 }
-            
-                if let Some(result) = raffle.result() {
-                    output += "\nRaffle Result:\n";
-                    output += &format!("  Counted teams: {:?}\n", result.counted());
-                    output += &format!("  Uncounted teams: {:?}\n", result.uncounted());
+                tables.push_str("\n");
+            }
+        }
+    
+        Ok(tables)
+    }
+    
+
+    fn team_summary_rows(&self, epoch: &Epoch) -> Vec<TeamSummaryRow> {
+        let total_points: u32 = self.state.current_state().teams().keys()
+            .map(|team_id| self.get_team_points_for_epoch(*team_id, epoch.id()).unwrap_or(0))
+            .sum();
+
+        self.state.current_state().teams().iter()
+            .map(|(team_id, team)| {
+                let points = self.get_team_points_for_epoch(*team_id, epoch.id()).unwrap_or(0);
+                let percentage = if total_points > 0 {
+                    (points as f64 / total_points as f64) * 100.0
                 } else {
-                    output += "\nRaffle result not available\n";
-                }
-            
-                Ok(output)
-            },
-            Command::ImportHistoricalVote { 
-                proposal_name, 
-                passed, 
-                participating_teams,
-                non_participating_teams,
-                counted_points,
-                uncounted_points,
-            } => {
-                let vote_id = self.import_historical_vote(
-                    &proposal_name,
-                    passed,
-                    participating_teams.clone(),
-                    non_participating_teams.clone(),
-                    counted_points,
-                    uncounted_points
-                )?;
-            
-                let vote = self.state().votes().get(&vote_id).unwrap();
-                let _proposal = self.state().proposals().get(&vote.proposal_id()).unwrap();
-            
-                let mut output = format!("Imported historical vote for proposal '{}' (Vote ID: {})\n", proposal_name, vote_id);
-                output += &format!("Vote passed: {}\n", passed);
-            
-                output += "\nNon-participating teams:\n";
-                for team_name in &non_participating_teams {
-                    output += &format!("  {}\n", team_name);
+                    0.0
+                };
+                let (counted_votes, uncounted_votes) = self.get_team_vote_counts(*team_id, epoch.id());
+                let rewards = epoch.team_rewards().get(team_id)
+                    .map(|reward| epoch.rewards().iter()
+                        .map(|r| (r.token().to_string(), reward.amount(r.token())))
+                        .collect());
+
+                TeamSummaryRow {
+                    name: team.name().to_string(),
+                    status: format_team_status(team.status()).to_string(),
+                    counted_votes,
+                    uncounted_votes,
+                    points,
+                    percentage,
+                    rewards,
                 }
-            
-                if let VoteType::Formal { raffle_id, .. } = vote.vote_type() {
-                    if let Some(raffle) = self.state().raffles().get(&raffle_id) {
-                        if let VoteParticipation::Formal { counted, uncounted } = vote.participation() {
-                            output += "\nCounted seats:\n";
-                            for &team_id in counted {
-                                if let Some(team) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
-                                    output += &format!("  {} (+{} points)\n", team.name(), self.config.counted_vote_points);
-                                }
-                            }
-            
-                            output += "\nUncounted seats:\n";
-                            for &team_id in uncounted {
-                                if let Some(team) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
-                                    output += &format!("  {} (+{} points)\n", team.name(), self.config.uncounted_vote_points);
-                                }
-                            }
+            })
+            .collect()
+    }
+
+    pub fn generate_team_summary(&self, epoch: &Epoch) -> Result<String, Box<dyn Error>> {
+        let mut summary = String::from("## Team Summary\n");
+        summary.push_str(&Self::markdown_table_header(&TEAM_SUMMARY_HEADERS));
+
+        for row in self.team_summary_rows(epoch) {
+            let reward_amount = row.rewards
+                .map(|rewards| rewards.iter()
+                    .map(|(token, amount)| format!("{} {}", amount, token))
+                    .collect::<Vec<_>>()
+                    .join(", "))
+                .unwrap_or_else(|| "N/A".to_string());
+
+            summary.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {:.2}% | {} |\n",
+                row.name,
+                row.status,
+                row.counted_votes,
+                row.uncounted_votes,
+                row.points,
+                row.percentage,
+                reward_amount
+            ));
+        }
+
+        Ok(summary)
+    }
+
+    pub fn generate_geographic_diversity(&self) -> String {
+        let distribution = self.get_team_geographic_distribution();
+        let mut section = String::from("\n## Geographic Diversity\n");
+
+        if distribution.is_empty() {
+            section.push_str("No teams registered.\n");
+            return section;
+        }
+
+        let total_teams: usize = distribution.values().map(|names| names.len()).sum();
+        let mut regions: Vec<(&String, &Vec<String>)> = distribution.iter().collect();
+        regions.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+
+        section.push_str("| Region | Team Count | % of Teams | Teams |\n");
+        section.push_str("|--------|------------|------------|-------|\n");
+        for (region, names) in regions {
+            section.push_str(&format!(
+                "| {} | {} | {:.2}% | {} |\n",
+                region,
+                names.len(),
+                (names.len() as f64 / total_teams as f64) * 100.0,
+                names.join(", ")
+            ));
+        }
+
+        section
+    }
+
+    pub fn get_team_vote_counts(&self, team_id: Uuid, epoch_id: Uuid) -> (u32, u32) {
+        let mut counted = 0;
+        let mut uncounted = 0;
+
+        for vote in self.state.votes().values() {
+            if vote.epoch_id() == epoch_id {
+                match vote.participation() {
+                    VoteParticipation::Formal { counted: c, uncounted: u } => {
+                        if c.contains(&team_id) {
+                            counted += 1;
+                        } else if u.contains(&team_id) {
+                            uncounted += 1;
                         }
-                    } else {
-                        output += "\nAssociated raffle not found. Cannot display seat breakdowns.\n";
-                    }
-                } else {
-                    output += "\nThis is an informal vote, no counted/uncounted breakdown available.\n";
-                }
-            
-                output += "\nNote: Detailed vote counts are not available for historical votes.\n";
-            
-                Ok(output)
-            },
-            Command::ImportHistoricalRaffle { 
-                proposal_name, 
-                initiation_block, 
-                randomness_block, 
-                team_order, 
-                excluded_teams,
-                total_counted_seats, 
-                max_earner_seats 
-            } => {
-                let (raffle_id, raffle) = self.import_historical_raffle(
-                    &proposal_name,
-                    initiation_block,
-                    randomness_block,
-                    team_order.clone(),
-                    excluded_teams.clone(),
-                    total_counted_seats.or(Some(self.config.default_total_counted_seats)),
-                    max_earner_seats.or(Some(self.config.default_max_earner_seats)),
-                ).await?;
-            
-                let mut output = format!("Imported historical raffle for proposal '{}' (Raffle ID: {})\n", proposal_name, raffle_id);
-                output += &format!("Randomness: {}\n", raffle.config().block_randomness());
-            
-                if let Some(excluded) = excluded_teams {
-                    output += &format!("Excluded teams: {:?}\n", excluded);
+                    },
+                    VoteParticipation::Informal(_) => {}  // Informal votes are not counted here
                 }
-            
-                for snapshot in raffle.team_snapshots() {
-                    let tickets: Vec<_> = raffle.tickets().iter()
-                        .filter(|t| t.team_id() == snapshot.id())
-                        .collect();
-                    
-                    if !tickets.is_empty() {
-                        let start = tickets.first().unwrap().index();
-                        let end = tickets.last().unwrap().index();
-                        output += &format!("Team '{}' ballot range: {} - {}\n", snapshot.name(), start, end);
-                    }
+            }
+        }
+
+        (counted, uncounted)
+    }
+
+    /// Drives an Ethereum call to completion, passing on the service's retry events as
+    /// they happen so progress streams can report them instead of pausing silently.
+    fn observe_eth_call<'a, T: Send + 'a>(
+        call: impl std::future::Future<Output = Result<T, Box<dyn Error>>> + Send + 'a,
+        mut retries: Option<broadcast::Receiver<RetryEvent>>,
+    ) -> impl Stream<Item = EthCallStep<T>> + Send + 'a {
+        stream! {
+            pin_mut!(call);
+            loop {
+                let step = match retries.as_mut() {
+                    // Biased towards events so none are lost once the call completes
+                    Some(events) => tokio::select! {
+                        biased;
+                        Ok(event) = events.recv() => EthCallStep::Retrying(event),
+                        result = &mut call => EthCallStep::Done(result.map_err(|e| e.to_string())),
+                    },
+                    None => EthCallStep::Done(call.as_mut().await.map_err(|e| e.to_string())),
+                };
+                let done = matches!(step, EthCallStep::Done(_));
+                yield step;
+                if done {
+                    break;
                 }
-            
-                if let Some(result) = raffle.result() {
-                    output += "Counted seats:\n";
-                    output += "Earner seats:\n";
-                    let mut earner_count = 0;
-                    for &team_id in result.counted() {
-                        if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
-                            if let TeamStatus::Earner { .. } = snapshot.status() {
-                                earner_count += 1;
-                                let best_score = raffle.tickets().iter()
-                                    .filter(|t| t.team_id() == team_id)
-                                    .map(|t| t.score())
-                                    .max_by(|a, b| a.partial_cmp(b).unwrap())
-                                    .unwrap_or(0.0);
-                                output += &format!("  {} (score: {})\n", snapshot.name(), best_score);
-                            }
-                        }
-                    }
-                    output += "Supporter seats:\n";
-                    for &team_id in result.counted() {
-                        if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
-                            if let TeamStatus::Supporter = snapshot.status() {
-                                let best_score = raffle.tickets().iter()
-                                    .filter(|t| t.team_id() == team_id)
-                                    .map(|t| t.score())
-                                    .max_by(|a, b| a.partial_cmp(b).unwrap())
-                                    .unwrap_or(0.0);
-                                output += &format!("  {} (score: {})\n", snapshot.name(), best_score);
-                            }
-                        }
-                    }
-                    output += &format!("Total counted seats: {} (Earners: {}, Supporters: {})\n", 
-                                result.counted().len(), earner_count, result.counted().len() - earner_count);
-            
-                    output += "Uncounted seats:\n";
-                    output += "Earner seats:\n";
-                    for &team_id in result.uncounted() {
-                        if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
-                            if let TeamStatus::Earner { .. } = snapshot.status() {
-                                let best_score = raffle.tickets().iter()
-                                    .filter(|t| t.team_id() == team_id)
-                                    .map(|t| t.score())
-                                    .max_by(|a, b| a.partial_cmp(b).unwrap())
-                                    .unwrap_or(0.0);
-                                output += &format!("  {} (score: {})\n", snapshot.name(), best_score);
-                            }
-                        }
+            }
+        }
+    }
+
+    /// Creates a new raffle with progress updates streamed as an async stream
+    ///
+    /// # Arguments
+    /// * `proposal_name` - Name of the proposal to create raffle for
+    /// * `block_offset` - Optional override for the default block offset
+    /// * `excluded_teams` - Optional list of team names to exclude
+    ///
+    /// # Returns
+    /// A stream of RaffleProgress updates that can be consumed asynchronously
+    pub async fn create_raffle_with_progress<'a>(
+        &'a mut self,
+        proposal_name: String,
+        block_offset: Option<u64>,
+        excluded_teams: Option<Vec<String>>,
+    ) -> impl Stream<Item = Result<RaffleProgress, RaffleCreationError>> + Send + 'a {
+        let config = self.config.clone();
+        self.raffle_progress_stream(proposal_name, block_offset, excluded_teams, config)
+    }
+
+    /// The draw behind `create_raffle_with_progress`, with seat counts and the block
+    /// offset taken from `config` instead of the system configuration.
+    fn raffle_progress_stream<'a>(
+        &'a mut self,
+        proposal_name: String,
+        block_offset: Option<u64>,
+        excluded_teams: Option<Vec<String>>,
+        config: AppConfig,
+    ) -> impl Stream<Item = Result<RaffleProgress, RaffleCreationError>> + Send + 'a {
+        let eth_service = Arc::clone(&self.ethereum_service);
+        
+        try_stream! {
+            // Do setup inside the stream
+            if self.get_proposal_id_by_name(&proposal_name).is_none() {
+                Err(RaffleCreationError::ProposalNotFound(proposal_name.clone()))?;
+            }
+            if self.state.current_epoch().is_none() {
+                Err(RaffleCreationError::NoActiveEpoch)?;
+            }
+
+            let (raffle_id, tickets) = self.prepare_raffle(&proposal_name, excluded_teams.clone(), &config)
+                .map_err(|e| RaffleCreationError::Finalization(format!("Failed to prepare raffle: {}", e)))?;
+
+            if tickets.is_empty() {
+                self.state.remove_raffle(raffle_id);
+                self.save_state()
+                    .map_err(|e| RaffleCreationError::Finalization(format!("Failed to save state: {}", e)))?;
+                Err(RaffleCreationError::InsufficientTeams)?;
+            }
+    
+            let ticket_ranges = self.group_tickets_by_team(&tickets);
+    
+            yield RaffleProgress::Preparing {
+                proposal_name: proposal_name.clone(),
+                raffle_id,
+                ticket_ranges,
+            };
+    
+            let mut current_block = 0;
+            for await step in Self::observe_eth_call(eth_service.get_current_block(), eth_service.subscribe_retries()) {
+                match step {
+                    EthCallStep::Retrying(event) => yield RaffleProgress::Retrying { attempt: event.attempt, error: event.error },
+                    EthCallStep::Done(result) => current_block = result
+                        .map_err(|e| RaffleCreationError::EthereumUnavailable(format!("Failed to get current block: {}", e)))?,
+                }
+            }
+                
+            let target_block = current_block + block_offset.unwrap_or(config.future_block_offset);
+    
+            loop {
+                let mut latest_block = 0;
+                for await step in Self::observe_eth_call(eth_service.get_current_block(), eth_service.subscribe_retries()) {
+                    match step {
+                        EthCallStep::Retrying(event) => yield RaffleProgress::Retrying { attempt: event.attempt, error: event.error },
+                        EthCallStep::Done(result) => latest_block = result
+                            .map_err(|e| RaffleCreationError::EthereumUnavailable(format!("Failed to get current block: {}", e)))?,
                     }
-                    output += "Supporter seats:\n";
-                    for &team_id in result.uncounted() {
-                        if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
-                            if let TeamStatus::Supporter = snapshot.status() {
-                                let best_score = raffle.tickets().iter()
-                                    .filter(|t| t.team_id() == team_id)
-                                    .map(|t| t.score())
-                                    .max_by(|a, b| a.partial_cmp(b).unwrap())
-                                    .unwrap_or(0.0);
-                                output += &format!("  {} (score: {})\n", snapshot.name(), best_score);
-                            }
+                }
+                if latest_block >= target_block {
+                    break;
+                }
+
+                yield RaffleProgress::WaitingForBlock {
+                    proposal_name: proposal_name.clone(),
+                    raffle_id,
+                    current_block,
+                    target_block,
+                };
+                
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+    
+            let mut randomness = String::new();
+            for await step in Self::observe_eth_call(eth_service.get_randomness(target_block), eth_service.subscribe_retries()) {
+                match step {
+                    EthCallStep::Retrying(event) => yield RaffleProgress::Retrying { attempt: event.attempt, error: event.error },
+                    EthCallStep::Done(result) => randomness = result
+                        .map_err(|e| RaffleCreationError::EthereumUnavailable(format!("Failed to get randomness: {}", e)))?,
+                }
+            }
+    
+            yield RaffleProgress::RandomnessAcquired {
+                proposal_name: proposal_name.clone(),
+                raffle_id,
+                current_block,
+                target_block,
+                randomness: randomness.clone(),
+            };
+    
+            let raffle = self.finalize_raffle(raffle_id, current_block, target_block, randomness)
+                .await
+                .map_err(|e| RaffleCreationError::Finalization(format!("Failed to finalize raffle: {}", e)))?;
+    
+            let (counted, uncounted) = if let Some(result) = raffle.result() {
+                let format_team_with_score = |team_id: &Uuid| {
+                    let snapshot = raffle.team_snapshots().iter()
+                        .find(|s| s.id() == *team_id)
+                        .unwrap();
+                    let best_score = raffle.best_score(*team_id);
+                    (snapshot.status().clone(), format!("{} (score: {})", snapshot.name(), best_score))
+                };
+        
+                let counted: Vec<(TeamStatus, String)> = result.counted().iter()
+                    .map(|team_id| format_team_with_score(team_id))
+                    .collect();
+                let uncounted: Vec<(TeamStatus, String)> = result.uncounted().iter()
+                    .map(|team_id| format_team_with_score(team_id))
+                    .collect();
+                (counted, uncounted)
+            } else {
+                (Vec::new(), Vec::new())
+            };
+        
+            yield RaffleProgress::Completed {
+                proposal_name: proposal_name.clone(),
+                raffle_id,
+                counted,
+                uncounted,
+            };
+        }
+    }
+
+    pub fn generate_unpaid_requests_report(
+        &self,
+        output_path: Option<&str>,
+        epoch_name: Option<&str>,
+    ) -> Result<String, Box<dyn Error>> {
+        // Collect unpaid requests
+        let unpaid_requests: Vec<UnpaidRequest> = self
+            .state
+            .proposals()
+            .iter()
+            .filter_map(|(proposal_id, proposal)| {
+                // Check if proposal is approved
+                if !proposal.is_approved() {
+                    return None;
+                }
+
+                // Check if it has budget details
+                let budget_details = match proposal.budget_request_details() {
+                    Some(details) => details,
+                    None => return None,
+                };
+
+                // Skip if already paid
+                if budget_details.is_paid() {
+                    return None;
+                }
+
+                // Get team name
+                let team_name = budget_details
+                    .team()
+                    .and_then(|team_id| self.state.current_state().teams().get(&team_id))
+                    .map(|team| team.name().to_string())
+                    .unwrap_or_else(|| "No Team".to_string());
+
+                // Get epoch name
+                let epoch = self.state.epochs().get(&proposal.epoch_id());
+                
+                // Filter by epoch if specified
+                if let Some(target_epoch) = epoch_name {
+                    if let Some(epoch) = epoch {
+                        if epoch.name() != target_epoch {
+                            return None;
                         }
                     }
-                } else {
-                    output += "Raffle result not available\n";
                 }
-            
-                Ok(output)
-            },
-            Command::PrintTeamReport => {
-                Ok(self.print_team_report())
-            },
-            Command::PrintEpochState => {
-                self.print_epoch_state()
-            },
-            Command::PrintTeamVoteParticipation { team_name, epoch_name } => {
-                self.print_team_vote_participation(&team_name, epoch_name.as_deref())
-            },
-            Command::CloseProposal { proposal_name, resolution } => {
-                let proposal_id = self.get_proposal_id_by_name(&proposal_name)
-                    .ok_or_else(|| format!("Proposal not found: {}", proposal_name))?;
-                let resolution = match resolution.to_lowercase().as_str() {
-                    "approved" => Resolution::Approved,
-                    "rejected" => Resolution::Rejected,
-                    "invalid" => Resolution::Invalid,
-                    "duplicate" => Resolution::Duplicate,
-                    "retracted" => Resolution::Retracted,
-                    _ => return Err(format!("Invalid resolution type: {}", resolution).into()),
+
+                let epoch_name = epoch
+                    .map(|e| e.name().to_string())
+                    .unwrap_or_else(|| "Unknown Epoch".to_string());
+
+                // Get approval date
+                let approved_date = proposal.resolved_at()
+                    .unwrap_or_else(|| Utc::now().date_naive());
+
+                Some(UnpaidRequest::new(
+                    *proposal_id,
+                    proposal.title().to_string(),
+                    team_name,
+                    budget_details.request_amounts().clone(),
+                    budget_details.payment_address().map(|addr| format!("{:?}", addr)),
+                    approved_date,
+                    budget_details.is_loan(),
+                    epoch_name,
+                    proposal.url().map(|u| u.to_string()),
+                    budget_details.start_date(),
+                ))
+            })
+            .collect();
+
+        let report = UnpaidRequestsReport::new(unpaid_requests);
+
+        // Generate output path if not provided
+        let output_path = output_path.map(PathBuf::from).unwrap_or_else(|| {
+            let date = Utc::now().format("%Y%m%d");
+            PathBuf::from(&self.config.state_file)
+                .parent()
+                .unwrap()
+                .join("reports")
+                .join(format!("unpaid_requests_{}.json", date))
+        });
+
+        // Create directory if it doesn't exist
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write report to file
+        let json = serde_json::to_string_pretty(&report)?;
+        fs::write(&output_path, json)?;
+
+        Ok(format!("Generated unpaid requests report at: {:?}", output_path))
+    }
+
+    /// Builds a Safe transaction builder batch paying every approved, unpaid proposal in
+    /// the epoch. ETH is sent natively; other tokens are ERC-20 transfers from the
+    /// contracts in `token_addresses`, assumed to have 18 decimals. Fails without
+    /// generating anything if a proposal lacks a payment address or a token has no
+    /// configured contract.
+    pub fn generate_gnosis_safe_payload(&self, epoch_name: &str) -> Result<String, Box<dyn Error>> {
+        let epoch_id = self.get_epoch_id_by_name(epoch_name)
+            .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
+
+        let mut proposals: Vec<&Proposal> = self.get_proposals_for_epoch(epoch_id).into_iter()
+            .filter(|p| p.is_approved())
+            .filter(|p| p.budget_request_details().is_some_and(|d| !d.is_paid() && !d.request_amounts().is_empty()))
+            .collect();
+        proposals.sort_by(|a, b| a.title().cmp(b.title()));
+
+        // One payout per unpaid team allocation, sent to that team's address.
+        // Proposals without a team fall back to their own payment address.
+        let mut payouts: Vec<(String, Option<Address>, HashMap<String, f64>)> = Vec::new();
+        for proposal in proposals {
+            let Some(details) = proposal.budget_request_details() else { continue };
+            let allocations = details.team_allocations();
+            if allocations.is_empty() {
+                payouts.push((proposal.title().to_string(), details.payment_address().copied(), details.request_amounts().clone()));
+                continue;
+            }
+            let mut team_payouts: Vec<_> = allocations.into_iter()
+                .filter(|(team_id, _)| details.team_payment(*team_id).is_none())
+                .map(|(team_id, amounts)| {
+                    let team = self.state.get_team(&team_id);
+                    let team_name = team.map(|t| t.name().to_string()).unwrap_or_else(|| team_id.to_string());
+                    (format!("{} ({})", proposal.title(), team_name), team.and_then(|t| t.payment_address().copied()), amounts)
+                })
+                .collect();
+            team_payouts.sort_by(|a, b| a.0.cmp(&b.0));
+            payouts.extend(team_payouts);
+        }
+
+        let missing_addresses: Vec<&str> = payouts.iter()
+            .filter(|(_, recipient, _)| recipient.is_none())
+            .map(|(label, _, _)| label.as_str())
+            .collect();
+        if !missing_addresses.is_empty() {
+            return Err(format!("Payouts without a payment address: {}", missing_addresses.join(", ")).into());
+        }
+
+        let mut transactions = Vec::new();
+        let mut unknown_tokens = BTreeSet::new();
+        for (label, recipient, amounts) in &payouts {
+            let Some(&recipient) = recipient.as_ref() else { continue };
+            let mut amounts: Vec<(&String, &f64)> = amounts.iter().collect();
+            amounts.sort_by(|a, b| a.0.cmp(b.0));
+            for (token, &amount) in amounts {
+                let transaction = if token.eq_ignore_ascii_case("ETH") {
+                    SafeTransaction::native_transfer(recipient, amount)
+                } else if let Some(contract) = self.config.token_addresses.get(token) {
+                    let contract: Address = contract.parse()
+                        .map_err(|_| format!("Invalid contract address for {}: {}", token, contract))?;
+                    SafeTransaction::erc20_transfer(contract, recipient, amount)
+                } else {
+                    unknown_tokens.insert(token.clone());
+                    continue;
                 };
-                self.close_with_reason(proposal_id, &resolution)?;
-                Ok(format!("Closed proposal '{}' with resolution: {:?}", proposal_name, resolution))
-            },
-            Command::CreateRaffle { proposal_name, block_offset, excluded_teams } => {
-                let progress_stream = self.create_raffle_with_progress(
-                    proposal_name,
-                    block_offset,
-                    excluded_teams,
-                ).await;
+                transactions.push(transaction.map_err(|e| format!("{} ({}): {}", label, token, e))?);
+            }
+        }
+        if !unknown_tokens.is_empty() {
+            return Err(format!(
+                "No contract address configured for: {}",
+                unknown_tokens.into_iter().collect::<Vec<_>>().join(", ")
+            ).into());
+        }
+
+        Ok(serde_json::to_string_pretty(&SafeBatch::new(self.config.safe_chain_id, transactions))?)
+    }
+
+    pub fn get_overdue_payments(&self, days_overdue: u64) -> Result<Vec<OverduePayment>, BudgetSystemError> {
+        let today = Utc::now().date_naive();
+
+        let mut overdue: Vec<OverduePayment> = self.state.proposals().values()
+            .filter(|p| p.is_approved())
+            .filter_map(|proposal| {
+                let details = proposal.budget_request_details()?;
+                if details.is_paid() {
+                    return None;
+                }
+
+                let resolved_at = proposal.resolved_at()?;
+                let days_since_approval = (today - resolved_at).num_days();
+                if days_since_approval <= days_overdue as i64 {
+                    return None;
+                }
+
+                let team_name = details.team()
+                    .and_then(|team_id| self.state.get_team(&team_id))
+                    .map(|team| team.name().to_string())
+                    .unwrap_or_else(|| "No Team".to_string());
+
+                Some(OverduePayment {
+                    proposal_id: proposal.id(),
+                    title: proposal.title().to_string(),
+                    team_name,
+                    days_overdue: days_since_approval,
+                    amounts: details.request_amounts().clone(),
+                    payment_address: details.payment_address().map(|addr| format!("{:?}", addr)),
+                })
+            })
+            .collect();
+
+        overdue.sort_by(|a, b| b.days_overdue.cmp(&a.days_overdue).then_with(|| a.title.cmp(&b.title)));
+        Ok(overdue)
+    }
+
+    /// Every approved budget request that is still unpaid, longest pending first.
+    pub fn get_pending_payments(&self) -> Vec<PendingPayment> {
+        let today = Utc::now().date_naive();
+
+        let mut pending: Vec<PendingPayment> = self.state.proposals().values()
+            .filter(|p| p.is_approved())
+            .filter_map(|proposal| {
+                let details = proposal.budget_request_details()?;
+                if details.is_paid() {
+                    return None;
+                }
+
+                let approved_date = proposal.resolved_at().unwrap_or(today);
+                let team_name = details.team()
+                    .and_then(|team_id| self.state.get_team(&team_id))
+                    .map(|team| team.name().to_string())
+                    .unwrap_or_else(|| "No Team".to_string());
+                let epoch_name = self.state.get_epoch(&proposal.epoch_id())
+                    .map(|epoch| epoch.name().to_string())
+                    .unwrap_or_else(|| "Unknown Epoch".to_string());
+
+                Some(PendingPayment {
+                    proposal_id: proposal.id(),
+                    title: proposal.title().to_string(),
+                    team_name,
+                    epoch_name,
+                    amounts: details.request_amounts().clone(),
+                    payment_address: details.payment_address().map(|addr| format!("{:?}", addr)),
+                    approved_date,
+                    days_pending: (today - approved_date).num_days(),
+                    is_loan: details.is_loan(),
+                })
+            })
+            .collect();
+
+        pending.sort_by(|a, b| b.days_pending.cmp(&a.days_pending).then_with(|| a.title.cmp(&b.title)));
+        pending
+    }
+
+    pub async fn record_payments(
+        &mut self,
+        payment_tx: &str,
+        payment_date: NaiveDate,
+        proposal_names: &[String],
+        skip_verification: bool,
+    ) -> Result<String, BudgetSystemError> {
+        if payment_date > Utc::now().date_naive() {
+            return Err(BudgetSystemError::FutureDateNotAllowed);
+        }
+
+        let mut updated_proposals = Vec::new();
+
+        // Validate all proposals first
+        for name in proposal_names {
+            let proposal_id = self.get_proposal_id_by_name(name)
+                .ok_or_else(|| self.proposal_not_found(name))?;
+
+            let proposal = self.get_proposal(&proposal_id)
+                .ok_or_else(|| format!("Proposal not found: {}", name))?;
+
+            if !proposal.is_approved() {
+                return Err(format!("Proposal '{}' is not approved", name).into());
+            }
+
+            if let Some(details) = proposal.budget_request_details() {
+                if details.is_paid() {
+                    return Err(BudgetSystemError::PaymentAlreadyRecorded(name.clone()));
+                }
+            } else {
+                return Err(format!("Proposal '{}' has no budget request", name).into());
+            }
+        }
+
+        if !skip_verification {
+            let recipients: Vec<(String, Option<Address>)> = proposal_names.iter()
+                .map(|name| {
+                    let payment_address = self.get_proposal_id_by_name(name)
+                        .and_then(|id| self.get_proposal(&id))
+                        .and_then(|proposal| proposal.budget_request_details())
+                        .and_then(|details| details.payment_address().copied());
+                    (name.clone(), payment_address)
+                })
+                .collect();
+            self.verify_payment_transaction(payment_tx, &recipients).await?;
+        }
+
+        // Update proposals
+        for name in proposal_names {
+            let proposal_id = self.get_proposal_id_by_name(name).unwrap();
+            
+            if let Some(mut details) = self.get_proposal(&proposal_id).unwrap().budget_request_details().cloned() {
+                details.record_payment(payment_tx.to_string(), payment_date)?;
+                
+                let proposal = self.state.get_proposal_mut(&proposal_id)
+                    .ok_or_else(|| format!("Failed to get mutable reference to proposal: {}", name))?;
+                proposal.set_budget_request_details(Some(details));
+                updated_proposals.push(name.clone());
+            }
+        }
+
+        let _ = self.save_state()?;
+        self.notify_payment(payment_tx, payment_date, &updated_proposals.join(", "));
+        Ok(format!("Payment recorded for proposals: {}", updated_proposals.join(", ")))
+    }
+
+    /// Records a payment covering the named teams' allocations of a multi-team
+    /// proposal. When verified, the transaction must go to each team's payment address.
+    pub async fn record_allocation_payments(
+        &mut self,
+        payment_tx: &str,
+        payment_date: NaiveDate,
+        proposal_name: &str,
+        team_names: &[String],
+        skip_verification: bool,
+    ) -> Result<String, BudgetSystemError> {
+        if payment_date > Utc::now().date_naive() {
+            return Err(BudgetSystemError::FutureDateNotAllowed);
+        }
+
+        let proposal_id = self.get_proposal_id_by_name(proposal_name)
+            .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+        let proposal = self.get_proposal(&proposal_id)
+            .ok_or_else(|| format!("Proposal not found: {}", proposal_name))?;
+        if !proposal.is_approved() {
+            return Err(format!("Proposal '{}' is not approved", proposal_name).into());
+        }
+        let details = proposal.budget_request_details()
+            .ok_or_else(|| format!("Proposal '{}' has no budget request", proposal_name))?;
+        if !details.has_team_allocations() {
+            return Err(format!("Proposal '{}' has no team allocations", proposal_name).into());
+        }
+
+        let mut recipients = Vec::new();
+        for team_name in team_names {
+            let team_id = self.get_team_id_by_name(team_name)
+                .ok_or_else(|| self.team_not_found(team_name))?;
+            if !details.team_allocations().contains_key(&team_id) {
+                return Err(format!("Team '{}' has no allocation in proposal '{}'", team_name, proposal_name).into());
+            }
+            if details.team_payment(team_id).is_some() {
+                return Err(BudgetSystemError::PaymentAlreadyRecorded(format!("{} ({})", proposal_name, team_name)));
+            }
+            let payment_address = self.state.get_team(&team_id).and_then(|team| team.payment_address().copied());
+            recipients.push((team_id, team_name.clone(), payment_address));
+        }
+
+        if !skip_verification {
+            let expected: Vec<(String, Option<Address>)> = recipients.iter()
+                .map(|(_, name, address)| (name.clone(), *address))
+                .collect();
+            self.verify_payment_transaction(payment_tx, &expected).await?;
+        }
+
+        let mut details = details.clone();
+        for (team_id, _, _) in &recipients {
+            details.record_allocation_payment(*team_id, payment_tx.to_string(), payment_date)?;
+        }
+        let fully_paid = details.is_paid();
+        self.state.get_proposal_mut(&proposal_id)
+            .ok_or_else(|| format!("Failed to get mutable reference to proposal: {}", proposal_name))?
+            .set_budget_request_details(Some(details));
+
+        self.save_state()?;
+        let teams = recipients.iter().map(|(_, name, _)| name.as_str()).collect::<Vec<_>>().join(", ");
+        self.notify_payment(payment_tx, payment_date, &format!("{} ({})", proposal_name, teams));
+        let mut output = format!("Payment recorded for proposal '{}' allocations: {}", proposal_name, teams);
+        if fully_paid {
+            output.push_str(" (all allocations paid)");
+        }
+        Ok(output)
+    }
+
+    fn notify_payment(&self, payment_tx: &str, payment_date: NaiveDate, paid: &str) {
+        let embed = DiscordEmbed::new(paid, COLOR_GREEN)
+            .field("Transaction", payment_tx, false)
+            .field("Date", payment_date.format("%Y-%m-%d").to_string(), true);
+        self.notify_discord(DiscordPayload::new("Payment recorded").with_embed(embed));
+    }
+
+    /// Checks that `payment_tx` exists on-chain and pays the payment address of each named
+    /// recipient, either as the recipient of ETH sent directly or of an ERC-20 transfer.
+    /// Recipients without a payment address only need the transaction to exist. ETH paid
+    /// out through a contract call, such as a Safe batch, can't be checked this way.
+    async fn verify_payment_transaction(&self, payment_tx: &str, recipients: &[(String, Option<Address>)]) -> Result<(), BudgetSystemError> {
+        let tx_info = self.ethereum_service.get_transaction(payment_tx).await
+            .map_err(|e| BudgetSystemError::EthereumError(e.to_string()))?
+            .ok_or_else(|| BudgetSystemError::PaymentVerificationFailed(
+                format!("transaction {} not found on-chain", payment_tx)
+            ))?;
+
+        let paid = tx_info.paid_addresses();
+        for (name, payment_address) in recipients {
+            let Some(expected) = *payment_address else {
+                continue;
+            };
+            if paid.contains(&expected) {
+                continue;
+            }
+            if paid.is_empty() {
+                return Err(BudgetSystemError::PaymentVerificationFailed(format!(
+                    "cannot verify token/batch payment: transaction {} sends no ETH directly and emits no ERC-20 transfers; \
+                     record it with verification skipped",
+                    payment_tx
+                )));
+            }
+            let paid = paid.iter().map(|address| format!("{:?}", address)).collect::<Vec<_>>().join(", ");
+            return Err(BudgetSystemError::PaymentVerificationFailed(format!(
+                "payment address {:?} of '{}' does not match any recipient of the transaction ({})",
+                expected, name, paid
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn generate_epoch_payments_report(
+        &self,
+        epoch_name: &str,
+        output_path: Option<&str>,
+        output_format: ReportFormat,
+    ) -> Result<String, Box<dyn Error>> {
+        // Find epoch and validate it's closed
+        let epoch = self.state.epochs()
+            .values()
+            .find(|e| e.name() == epoch_name)
+            .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
+
+        if !epoch.is_closed() {
+            return Err("Cannot generate payments report: Epoch is not closed".into());
+        }
+
+        if epoch.rewards().is_empty() {
+            return Err("Epoch has no reward configured".into());
+        }
+
+        // Build payments list, one entry per team and reward token
+        let mut payments: Vec<TeamPayment> = epoch.team_rewards()
+            .iter()
+            .filter_map(|(&team_id, team_reward)| {
+                let team = self.state.current_state().teams().get(&team_id)?;
+                let points = self.calculate_team_points_for_epoch(team_id, epoch.id());
+                Some(epoch.rewards().iter().map(move |reward| TeamPayment::new(
+                    team.name().to_string(),
+                    team.payment_address().cloned(),
+                    points,
+                    reward.token().to_string(),
+                    team_reward.amount(reward.token()),
+                    team_reward.percentage(),
+                )))
+            })
+            .flatten()
+            .collect();
+        let token_order = |token: &str| epoch.rewards().iter().position(|r| r.token() == token);
+        payments.sort_by(|a, b| a.team_name.cmp(&b.team_name)
+            .then_with(|| token_order(&a.reward_token).cmp(&token_order(&b.reward_token))));
+
+        let report = EpochPaymentsReport::new(
+            epoch.name().to_string(),
+            epoch.rewards().iter().map(|r| (r.token().to_string(), r.amount())).collect(),
+            payments,
+        );
+
+        let content = match output_format {
+            ReportFormat::Json => serde_json::to_string_pretty(&report)?,
+            ReportFormat::Csv => Self::epoch_payments_csv(&report)?,
+            ReportFormat::Markdown | ReportFormat::Text => Self::epoch_payments_markdown(&report),
+        };
+
+        // Generate output path and save report
+        if let Some(path) = output_path {
+            let output_path = PathBuf::from(path);
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&output_path, content)?;
+            Ok(format!("Generated epoch payments report at: {:?}", output_path))
+        } else {
+            Ok(content)
+        }
+    }
+
+    fn epoch_payments_csv(report: &EpochPaymentsReport) -> Result<String, Box<dyn Error>> {
+        let mut rows = vec![
+            ["epoch_name", "team_name", "payment_address", "points", "percentage", "reward_token", "reward_amount"]
+                .iter().map(|h| h.to_string()).collect()
+        ];
+        for payment in &report.payments {
+            rows.push(vec![
+                report.epoch_name.clone(),
+                payment.team_name.clone(),
+                payment.default_payment_address.map(|addr| format!("{:?}", addr)).unwrap_or_default(),
+                payment.points.to_string(),
+                payment.percentage.to_string(),
+                payment.reward_token.clone(),
+                payment.amount.to_string(),
+            ]);
+        }
+
+        let mut buffer = Vec::new();
+        export::write_delimited(&rows, ',', &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn epoch_payments_markdown(report: &EpochPaymentsReport) -> String {
+        let mut output = format!("## Epoch Payments: {}\n\n", report.epoch_name);
+        let totals = report.total_rewards.iter()
+            .map(|(token, amount)| format!("{:.2} {}", amount, token))
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!("Total reward: {}\n\n", totals));
+
+        if report.payments.is_empty() {
+            output.push_str("No team payments.\n");
+            return output;
+        }
+
+        output.push_str("| Team | Payment Address | Points | Percentage | Amount |\n");
+        output.push_str("|------|-----------------|--------|------------|--------|\n");
+        for payment in &report.payments {
+            output.push_str(&format!(
+                "| {} | {} | {} | {:.2}% | {:.2} {} |\n",
+                payment.team_name,
+                payment.default_payment_address.map(|addr| format!("{:?}", addr)).unwrap_or_else(|| "N/A".to_string()),
+                payment.points,
+                payment.percentage,
+                payment.amount,
+                payment.reward_token
+            ));
+        }
+        output
+    }
+
+    /// Exports payments, proposals or unpaid requests as CSV or TSV. Writes to
+    /// `output_path` when given, otherwise returns the delimited text.
+    pub fn export_delimited(
+        &self,
+        kind: ExportKind,
+        epoch_name: Option<&str>,
+        format: ExportFormat,
+        output_path: Option<&str>,
+    ) -> Result<String, Box<dyn Error>> {
+        let epoch_id = match epoch_name {
+            Some(name) => Some(self.get_epoch_id_by_name(name)
+                .ok_or_else(|| format!("Epoch not found: {}", name))?),
+            None => None,
+        };
+
+        let rows = match kind {
+            ExportKind::Payments => {
+                let epoch_id = epoch_id.ok_or("An epoch name is required to export payments")?;
+                self.payment_export_rows(epoch_id)?
+            },
+            ExportKind::Proposals => self.proposal_export_rows(epoch_id),
+            ExportKind::Unpaid => self.unpaid_export_rows(epoch_id),
+        };
+
+        let mut buffer = Vec::new();
+        export::write_delimited(&rows, format.delimiter(), &mut buffer)?;
+
+        match output_path {
+            Some(path) => {
+                let output_path = PathBuf::from(path);
+                if let Some(parent) = output_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&output_path, buffer)?;
+                Ok(format!("Exported {} row(s) to {:?}", rows.len().saturating_sub(1), output_path))
+            },
+            None => Ok(String::from_utf8(buffer)?),
+        }
+    }
+
+    fn payment_export_rows(&self, epoch_id: Uuid) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+        let epoch = self.state.get_epoch(&epoch_id).ok_or("Epoch not found")?;
+        if !epoch.is_closed() {
+            return Err("Cannot export payments: Epoch is not closed".into());
+        }
+        if epoch.rewards().is_empty() {
+            return Err("Epoch has no reward configured".into());
+        }
+
+        let mut payments: Vec<Vec<String>> = epoch.team_rewards().iter()
+            .filter_map(|(team_id, team_reward)| {
+                let team = self.state.get_team(team_id)?;
+                Some(epoch.rewards().iter().map(move |reward| vec![
+                    team.name().to_string(),
+                    team.payment_address().map(|addr| format!("{:?}", addr)).unwrap_or_default(),
+                    team_reward.amount(reward.token()).to_string(),
+                    reward.token().to_string(),
+                    format!("{:.2}", team_reward.percentage()),
+                ]))
+            })
+            .flatten()
+            .collect();
+        payments.sort();
+
+        let mut rows = vec![
+            ["Team", "Payment Address", "Amount", "Token", "Percentage"].iter().map(|h| h.to_string()).collect()
+        ];
+        rows.extend(payments);
+        Ok(rows)
+    }
+
+    fn proposal_export_rows(&self, epoch_id: Option<Uuid>) -> Vec<Vec<String>> {
+        let mut proposals: Vec<&Proposal> = self.state.proposals().values()
+            .filter(|p| epoch_id.is_none_or(|id| p.epoch_id() == id))
+            .collect();
+        proposals.sort_by_key(|p| (p.published_at(), p.title().to_string()));
+
+        let mut rows = vec![
+            ["Title", "URL", "Epoch", "Team", "Status", "Resolution", "Amounts", "Start Date", "End Date",
+             "Announced", "Resolved", "Paid", "Payment Tx"].iter().map(|h| h.to_string()).collect()
+        ];
+        for proposal in proposals {
+            let details = proposal.budget_request_details();
+            rows.push(vec![
+                proposal.title().to_string(),
+                proposal.url().unwrap_or_default().to_string(),
+                self.state.get_epoch(&proposal.epoch_id()).map(|e| e.name().to_string()).unwrap_or_default(),
+                self.export_team_name(details),
+                format!("{:?}", proposal.status()),
+                proposal.resolution().map(|r| format!("{:?}", r)).unwrap_or_default(),
+                details.map(|d| Self::format_export_amounts(d.request_amounts())).unwrap_or_default(),
+                Self::format_export_date(details.and_then(|d| d.start_date())),
+                Self::format_export_date(details.and_then(|d| d.end_date())),
+                Self::format_export_date(proposal.announced_at()),
+                Self::format_export_date(proposal.resolved_at()),
+                Self::format_export_date(details.and_then(|d| d.payment_date())),
+                details.and_then(|d| d.payment_tx()).map(|tx| format!("{:?}", tx)).unwrap_or_default(),
+            ]);
+        }
+        rows
+    }
+
+    fn unpaid_export_rows(&self, epoch_id: Option<Uuid>) -> Vec<Vec<String>> {
+        let mut proposals: Vec<&Proposal> = self.state.proposals().values()
+            .filter(|p| p.is_approved())
+            .filter(|p| epoch_id.is_none_or(|id| p.epoch_id() == id))
+            .filter(|p| p.budget_request_details().is_some_and(|d| !d.is_paid()))
+            .collect();
+        proposals.sort_by_key(|p| (p.resolved_at(), p.title().to_string()));
+
+        let mut rows = vec![
+            ["Title", "Team", "Epoch", "Amounts", "Payment Address", "Approved", "Is Loan", "URL"]
+                .iter().map(|h| h.to_string()).collect()
+        ];
+        for proposal in proposals {
+            let details = proposal.budget_request_details();
+            rows.push(vec![
+                proposal.title().to_string(),
+                self.export_team_name(details),
+                self.state.get_epoch(&proposal.epoch_id()).map(|e| e.name().to_string()).unwrap_or_default(),
+                details.map(|d| Self::format_export_amounts(d.request_amounts())).unwrap_or_default(),
+                details.and_then(|d| d.payment_address()).map(|addr| format!("{:?}", addr)).unwrap_or_default(),
+                Self::format_export_date(proposal.resolved_at()),
+                details.is_some_and(|d| d.is_loan()).to_string(),
+                proposal.url().unwrap_or_default().to_string(),
+            ]);
+        }
+        rows
+    }
+
+    fn export_team_name(&self, details: Option<&BudgetRequestDetails>) -> String {
+        details
+            .and_then(|d| d.team())
+            .and_then(|id| self.state.get_team(&id))
+            .map(|t| t.name().to_string())
+            .unwrap_or_default()
+    }
+
+    fn format_export_amounts(amounts: &HashMap<String, f64>) -> String {
+        let mut amounts: Vec<(&String, &f64)> = amounts.iter().collect();
+        amounts.sort_by(|a, b| a.0.cmp(b.0));
+        amounts.iter()
+            .map(|(token, amount)| format!("{} {}", amount, token))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn format_export_date(date: Option<NaiveDate>) -> String {
+        date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()
+    }
+
+    /// Loads two state files and lists the epochs, teams, proposals and votes
+    /// added, removed or modified between them.
+    pub fn generate_state_diff(snapshot_a_path: &Path, snapshot_b_path: &Path) -> Result<StateDiff, BudgetSystemError> {
+        let load = |path: &Path| {
+            FileSystem::load_state(&path.to_string_lossy())
+                .map_err(|e| BudgetSystemError::Other(format!("Failed to load state from {}: {}", path.display(), e)))
+        };
+        let before = load(snapshot_a_path)?;
+        let after = load(snapshot_b_path)?;
+        Ok(before.diff(&after))
+    }
+
+    /// Remaining budget per capped token: the cap minus everything approved in the epoch.
+    /// Negative values mean the epoch is over budget for that token.
+    pub fn get_epoch_budget_headroom(&self, epoch_id: Uuid, budget_caps: HashMap<String, f64>) -> Result<HashMap<String, f64>, BudgetSystemError> {
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_id)))?;
+
+        let mut approved: HashMap<&str, f64> = HashMap::new();
+        for proposal in epoch.associated_proposals().iter().filter_map(|id| self.state.get_proposal(id)) {
+            if !proposal.is_approved() {
+                continue;
+            }
+            if let Some(details) = proposal.budget_request_details() {
+                for (token, amount) in details.request_amounts() {
+                    *approved.entry(token.as_str()).or_insert(0.0) += amount;
+                }
+            }
+        }
+
+        Ok(budget_caps.into_iter()
+            .map(|(token, cap)| {
+                let used = approved.get(token.as_str()).copied().unwrap_or(0.0);
+                (token, cap - used)
+            })
+            .collect())
+    }
+
+    /// Spend pacing for an epoch against its budget caps. Approvals count on the proposal's
+    /// resolution date and payments on their payment date; for every date with activity
+    /// (plus the epoch start) each capped token gets its cumulative approved and paid
+    /// amounts and the cap remaining after approvals.
+    pub fn epoch_burndown(&self, epoch_name: &str, budget_caps: &HashMap<String, f64>) -> Result<Vec<BurndownPoint>, BudgetSystemError> {
+        let epoch_id = self.get_epoch_id_by_name(epoch_name)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_name)))?;
+        let epoch = self.state.get_epoch(&epoch_id).ok_or("Epoch not found")?;
+
+        let mut approvals: Vec<(NaiveDate, &str, f64)> = Vec::new();
+        let mut payments: Vec<(NaiveDate, &str, f64)> = Vec::new();
+        for proposal in epoch.associated_proposals().iter().filter_map(|id| self.state.get_proposal(id)) {
+            if !proposal.is_approved() {
+                continue;
+            }
+            let Some(details) = proposal.budget_request_details() else { continue };
+            let approved_on = proposal.resolved_at()
+                .or(proposal.published_at())
+                .or(proposal.announced_at());
+            for (token, amount) in details.request_amounts() {
+                if !budget_caps.contains_key(token) {
+                    continue;
+                }
+                if let Some(date) = approved_on {
+                    approvals.push((date, token.as_str(), *amount));
+                }
+                if let Some(date) = details.payment_date() {
+                    payments.push((date, token.as_str(), *amount));
+                }
+            }
+        }
+
+        let mut dates: Vec<NaiveDate> = approvals.iter().chain(&payments).map(|(date, _, _)| *date).collect();
+        dates.push(epoch.start_date().date_naive());
+        dates.sort();
+        dates.dedup();
+
+        let mut tokens: Vec<&String> = budget_caps.keys().collect();
+        tokens.sort();
+
+        let cumulative = |events: &[(NaiveDate, &str, f64)], date: NaiveDate, token: &str| -> f64 {
+            events.iter()
+                .filter(|(d, t, _)| *d <= date && *t == token)
+                .map(|(_, _, amount)| amount)
+                .sum()
+        };
+
+        let mut points = Vec::new();
+        for date in dates {
+            for token in &tokens {
+                let approved_cumulative = cumulative(&approvals, date, token);
+                points.push(BurndownPoint {
+                    date,
+                    token: token.to_string(),
+                    approved_cumulative,
+                    paid_cumulative: cumulative(&payments, date, token),
+                    remaining: budget_caps[*token] - approved_cumulative,
+                });
+            }
+        }
+        Ok(points)
+    }
+
+    /// Builds Sankey-style flow data for an epoch's approved budget requests:
+    /// token -> team -> Paid/Unpaid. Links reference nodes by index, as expected
+    /// by the D3.js Sankey plugin.
+    pub fn generate_budget_flow_data(&self, epoch_id: Uuid) -> Result<BudgetFlowData, BudgetSystemError> {
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_id)))?;
+
+        let mut flow = BudgetFlowData::new();
+
+        let mut proposals: Vec<&Proposal> = epoch.associated_proposals().iter()
+            .filter_map(|id| self.state.get_proposal(id))
+            .filter(|p| p.is_approved())
+            .collect();
+        proposals.sort_by_key(|p| (p.published_at(), p.title().to_string()));
+
+        for proposal in proposals {
+            let details = match proposal.budget_request_details() {
+                Some(details) => details,
+                None => continue,
+            };
+
+            let team_name = details.team()
+                .and_then(|id| self.state.get_team(&id))
+                .map(|t| t.name().to_string())
+                .unwrap_or_else(|| "Unassigned".to_string());
+            let team_node = flow.node_index(&team_name, "team");
+            let sink_node = flow.node_index(if details.is_paid() { "Paid" } else { "Unpaid" }, "status");
+
+            let mut amounts: Vec<(&String, &f64)> = details.request_amounts().iter().collect();
+            amounts.sort_by(|a, b| a.0.cmp(b.0));
+
+            for (token, &amount) in amounts {
+                let token_node = flow.node_index(token, "token");
+                flow.add_flow(token_node, team_node, amount);
+                flow.add_flow(team_node, sink_node, amount);
+            }
+        }
+
+        Ok(flow)
+    }
+
+    pub fn generate_epoch_infographic_data(&self, epoch_id: Uuid) -> Result<EpochInfographicData, BudgetSystemError> {
+        let epoch = self.state.get_epoch(&epoch_id)
+            .ok_or_else(|| BudgetSystemError::Other(format!("Epoch not found: {}", epoch_id)))?;
+
+        // Team performance rankings
+        let mut team_points: Vec<(String, u32)> = self.state.current_state().teams().values()
+            .map(|team| (team.name().to_string(), self.calculate_team_points_for_epoch(team.id(), epoch_id)))
+            .collect();
+        team_points.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let team_rankings = team_points.into_iter()
+            .enumerate()
+            .map(|(i, (team_name, points))| TeamRanking { rank: i + 1, team_name, points })
+            .collect();
+
+        // Proposal resolutions
+        let proposals = self.get_proposals_for_epoch(epoch_id);
+        let mut resolution_counts = ResolutionCounts::default();
+        for proposal in &proposals {
+            match proposal.resolution() {
+                Some(Resolution::Approved) => resolution_counts.approved += 1,
+                Some(Resolution::Rejected) => resolution_counts.rejected += 1,
+                Some(Resolution::Retracted) => resolution_counts.retracted += 1,
+                Some(Resolution::Invalid) => resolution_counts.invalid += 1,
+                Some(Resolution::Duplicate) => resolution_counts.duplicate += 1,
+                None => resolution_counts.unresolved += 1,
+            }
+        }
+
+        // Participation trend across formal votes
+        let mut vote_participation: Vec<VoteParticipationPoint> = self.state.votes().values()
+            .filter(|v| v.epoch_id() == epoch_id)
+            .filter_map(|vote| {
+                let total_eligible_seats = match vote.vote_type() {
+                    VoteType::Formal { total_eligible_seats, .. } => *total_eligible_seats,
+                    VoteType::Informal => return None,
+                };
+                let (counted, uncounted) = match vote.participation() {
+                    VoteParticipation::Formal { counted, uncounted } => (counted.len(), uncounted.len()),
+                    VoteParticipation::Informal(_) => return None,
+                };
+                let participation_rate = if total_eligible_seats > 0 {
+                    counted as f64 / total_eligible_seats as f64
+                } else {
+                    0.0
+                };
+                Some(VoteParticipationPoint {
+                    proposal_title: self.state.get_proposal(&vote.proposal_id())
+                        .map(|p| p.title().to_string())
+                        .unwrap_or_else(|| "Unknown Proposal".to_string()),
+                    opened_at: vote.opened_at(),
+                    counted_participants: counted,
+                    uncounted_participants: uncounted,
+                    total_eligible_seats,
+                    participation_rate,
+                })
+            })
+            .collect();
+        vote_participation.sort_by_key(|point| point.opened_at);
+
+        // Approved budget by team and token
+        let mut allocations: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        for proposal in proposals.iter().filter(|p| p.is_approved()) {
+            if let Some(details) = proposal.budget_request_details() {
+                let team_name = details.team()
+                    .and_then(|id| self.state.get_team(&id))
+                    .map(|t| t.name().to_string())
+                    .unwrap_or_else(|| "Unassigned".to_string());
+                let team_amounts = allocations.entry(team_name).or_default();
+                for (token, amount) in details.request_amounts() {
+                    *team_amounts.entry(token.clone()).or_insert(0.0) += amount;
+                }
+            }
+        }
+        let mut budget_allocation: Vec<TeamBudgetAllocation> = allocations.into_iter()
+            .map(|(team_name, amounts)| TeamBudgetAllocation { team_name, amounts })
+            .collect();
+        budget_allocation.sort_by(|a, b| a.team_name.cmp(&b.team_name));
+
+        let approval_rate_by_status = self.get_approval_rate_by_team_status(Some(epoch_id))?;
+        let approval_rate_trend = self.approval_rate_trend(Some(epoch.start_date()), APPROVAL_TREND_EPOCHS);
+        let absence_streaks = self.team_absence_streaks(Some(epoch.start_date()));
+        let governance_health_score = Self::governance_health_score(
+            &vote_participation, &approval_rate_by_status, &approval_rate_trend, &absence_streaks
+        );
+
+        Ok(EpochInfographicData {
+            epoch_name: epoch.name().to_string(),
+            team_rankings,
+            resolution_counts,
+            vote_participation,
+            budget_allocation,
+            governance_health_score,
+            approval_rate_by_status,
+            approval_rate_trend,
+        })
+    }
+
+    /// Governance health from 0 to 100: average counted-seat participation in the formal votes
+    /// (60%), how evenly proposals from each team status are approved (20%) and whether the
+    /// approval rate held up across the trend window (20%), less a deduction for every epoch
+    /// of the teams' current absence streaks. `None` without formal votes.
+    fn governance_health_score(
+        vote_participation: &[VoteParticipationPoint],
+        approval_rate_by_status: &HashMap<String, f64>,
+        approval_rate_trend: &[(String, f64)],
+        absence_streaks: &[TeamAbsenceInfo],
+    ) -> Option<f64> {
+        if vote_participation.is_empty() {
+            return None;
+        }
+        let participation = vote_participation.iter().map(|p| p.participation_rate).sum::<f64>()
+            / vote_participation.len() as f64;
+
+        // A gap between the best and worst approved status means status predicts success
+        let rates = approval_rate_by_status.values().copied();
+        let status_parity = 1.0 - (rates.clone().fold(0.0, f64::max) - rates.fold(1.0, f64::min)).max(0.0);
+
+        // A falling approval rate across the window lowers the score
+        let trend = match (approval_rate_trend.first(), approval_rate_trend.last()) {
+            (Some((_, first)), Some((_, last))) => 1.0 - (first - last).max(0.0),
+            _ => 1.0,
+        };
+
+        let absence_epochs: usize = absence_streaks.iter().map(|a| a.current_streak_epochs).sum();
+        let deduction = (absence_epochs as f64 * ABSENCE_STREAK_DEDUCTION).min(MAX_ABSENCE_STREAK_DEDUCTION);
+
+        Some(((0.6 * participation + 0.2 * status_parity + 0.2 * trend) * 100.0 - deduction).max(0.0))
+    }
+
+    /// Approval rate (approved / resolved) of proposals grouped by the submitting team's
+    /// status at raffle time, optionally limited to one epoch. Proposals without a raffle
+    /// use the team's current status; proposals without a team are left out.
+    pub fn get_approval_rate_by_team_status(&self, epoch_id: Option<Uuid>) -> Result<HashMap<String, f64>, BudgetSystemError> {
+        let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+
+        for proposal in self.state.proposals().values()
+            .filter(|p| p.resolution().is_some())
+            .filter(|p| epoch_id.is_none_or(|id| p.epoch_id() == id))
+        {
+            let team_id = match proposal.budget_request_details().and_then(|d| d.team()) {
+                Some(id) => id,
+                None => continue,
+            };
+            let status = self.get_raffle_for_proposal(proposal.id())
+                .and_then(|r| r.team_snapshots().iter().find(|s| s.id() == team_id))
+                .map(|s| s.status().clone())
+                .or_else(|| self.state.get_team(&team_id).map(|t| t.status().clone()));
+            let label = match status {
+                Some(TeamStatus::Earner { .. }) => "Earner",
+                Some(TeamStatus::Supporter) => "Supporter",
+                Some(TeamStatus::Inactive) => "Inactive",
+                Some(TeamStatus::Archived) => "Archived",
+                None => continue,
+            };
+
+            let entry = counts.entry(label.to_string()).or_insert((0, 0));
+            entry.1 += 1;
+            if proposal.is_approved() {
+                entry.0 += 1;
+            }
+        }
+
+        Ok(counts.into_iter()
+            .map(|(label, (approved, resolved))| (label, approved as f64 / resolved as f64))
+            .collect())
+    }
+
+    /// Approval rate per epoch over the last `window` started epochs, oldest first.
+    /// Epochs without resolved proposals are skipped.
+    pub fn get_approval_rate_by_epoch_recency(&self, window: usize) -> Result<Vec<(String, f64)>, BudgetSystemError> {
+        if window == 0 {
+            return Err(BudgetSystemError::Other("Window must be at least one epoch".to_string()));
+        }
+        Ok(self.approval_rate_trend(None, window))
+    }
+
+    fn approval_rate_trend(&self, until: Option<DateTime<Utc>>, window: usize) -> Vec<(String, f64)> {
+        let mut epochs: Vec<&Epoch> = self.state.epochs().values()
+            .filter(|e| !e.is_planned())
+            .filter(|e| until.is_none_or(|until| e.start_date() <= until))
+            .collect();
+        epochs.sort_by_key(|e| e.start_date());
+        let skip = epochs.len().saturating_sub(window);
+
+        epochs.into_iter()
+            .skip(skip)
+            .filter_map(|epoch| {
+                let resolved: Vec<&Proposal> = self.get_proposals_for_epoch(epoch.id()).into_iter()
+                    .filter(|p| p.resolution().is_some())
+                    .collect();
+                if resolved.is_empty() {
+                    return None;
+                }
+                let approved = resolved.iter().filter(|p| p.is_approved()).count();
+                Some((epoch.name().to_string(), approved as f64 / resolved.len() as f64))
+            })
+            .collect()
+    }
+
+}
+
+/// Number of epochs in the approval trend included with infographic data
+const APPROVAL_TREND_EPOCHS: usize = 5;
+
+/// Health score points deducted per epoch of a team's current absence streak, and their cap
+const ABSENCE_STREAK_DEDUCTION: f64 = 2.0;
+const MAX_ABSENCE_STREAK_DEDUCTION: f64 = 20.0;
+
+/// Tokens grouped as stablecoins in cross-epoch reports
+const STABLECOIN_TOKENS: &[&str] = &["DAI", "USDC", "USD", "yv-mkUSD", "USDT", "crvUSD", "PYUSD"];
+
+fn is_stablecoin(token: &str) -> bool {
+    STABLECOIN_TOKENS.iter().any(|stable| stable.eq_ignore_ascii_case(token))
+}
+
+/// Report column for a token: stablecoins share a single "Stables" column.
+fn token_column(token: &str) -> String {
+    if is_stablecoin(token) {
+        "Stables".to_string()
+    } else {
+        token.to_string()
+    }
+}
+
+fn parse_resolution(resolution: &str) -> Result<Resolution, String> {
+    match resolution.to_lowercase().as_str() {
+        "approved" => Ok(Resolution::Approved),
+        "rejected" => Ok(Resolution::Rejected),
+        "invalid" => Ok(Resolution::Invalid),
+        "duplicate" => Ok(Resolution::Duplicate),
+        "retracted" => Ok(Resolution::Retracted),
+        _ => Err(format!("Invalid resolution type: {}", resolution)),
+    }
+}
+
+impl BudgetSystem {
+    /// Checks a command's preconditions without running it or changing any state.
+    /// Commands that only read state, and those without checks of their own here,
+    /// are reported as valid.
+    pub fn validate_command(&self, command: &Command) -> Result<String, Box<dyn Error>> {
+        match command {
+            Command::CreateEpoch { name, start_date, end_date } => {
+                self.check_new_epoch(name, *start_date, *end_date)?;
+            },
+            Command::CloneEpoch { source_epoch_name, new_name, start_date, end_date } => {
+                let source_id = self.get_epoch_id_by_name(source_epoch_name)
+                    .ok_or_else(|| format!("Epoch not found: {}", source_epoch_name))?;
+                self.check_epoch_clone(source_id, new_name, *start_date, *end_date)?;
+            },
+            Command::ActivateEpoch { name } => {
+                let epoch_id = self.get_epoch_id_by_name(name)
+                    .ok_or_else(|| format!("Epoch not found: {}", name))?;
+                self.check_epoch_activation(epoch_id)?;
+            },
+            Command::SetEpochReward { token, amount } => {
+                self.state.current_epoch().ok_or(BudgetSystemError::NoActiveEpoch)?;
+                EpochReward::new(token.clone(), *amount)?;
+            },
+            Command::AddTeam { name, representative, trailing_monthly_revenue, address } => {
+                Team::new(name.clone(), representative.clone(), trailing_monthly_revenue.clone(), address.clone())?;
+            },
+            Command::UpdateTeam { team_name, .. }
+            | Command::AddTeamAlias { team_name, .. }
+            | Command::ArchiveTeam { team_name }
+            | Command::UnarchiveTeam { team_name } => {
+                self.get_team_id_by_name(team_name)
+                    .ok_or_else(|| self.team_not_found(team_name))?;
+            },
+            Command::AddProposal { title, budget_request_details, .. } => {
+                self.check_new_proposal(title, None)?;
+                if let Some(hash) = budget_request_details.as_ref().and_then(|d| d.ipfs_hash.as_ref()) {
+                    validate_ipfs_hash(hash)?;
+                }
+                if let Some(team) = budget_request_details.as_ref().and_then(|d| d.team.as_ref()) {
+                    self.get_team_id_by_name(team)
+                        .ok_or_else(|| self.team_not_found(team))?;
+                }
+            },
+            Command::PinProposalDocument { proposal_name, ipfs_hash } => {
+                self.get_proposal_id_by_name(proposal_name)
+                    .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+                validate_ipfs_hash(ipfs_hash.trim())?;
+            },
+            Command::UpdateProposal { proposal_name, .. }
+            | Command::CreateRaffle { proposal_name, .. }
+            | Command::RerunRaffle { proposal_name, .. } => {
+                self.get_proposal_id_by_name(proposal_name)
+                    .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+            },
+            Command::CloseProposal { proposal_name, resolution } => {
+                let proposal_id = self.get_proposal_id_by_name(proposal_name)
+                    .ok_or_else(|| self.proposal_not_found(proposal_name))?;
+                parse_resolution(resolution)?;
+                self.check_proposal_closable(proposal_id)?;
+            },
+            Command::CreateAndProcessVote { proposal_name, counted_votes, uncounted_votes, .. } => {
+                self.check_vote_creatable(proposal_name, counted_votes, uncounted_votes)?;
+            },
+            Command::CloseEpoch { epoch_name } => {
+                self.check_epoch_closable(epoch_name.as_deref())?;
+            },
+            _ => {},
+        }
+        Ok(format!("[dry-run] Would execute: {:?}", command))
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for BudgetSystem {
+    async fn execute_command(&mut self, command: Command) -> Result<String, Box<dyn std::error::Error>> {
+        match command {
+            Command::CreateEpoch { name, start_date, end_date } => {
+                let epoch_id = self.create_epoch(&name, start_date, end_date)?;
+                Ok(format!("Created epoch: {} ({})", name, epoch_id))
+            },
+            Command::CloneEpoch { source_epoch_name, new_name, start_date, end_date } => {
+                let source_id = self.get_epoch_id_by_name(&source_epoch_name)
+                    .ok_or_else(|| format!("Epoch not found: {}", source_epoch_name))?;
+                let epoch_id = self.clone_epoch(source_id, &new_name, start_date, end_date)?;
+                let rewards: Vec<String> = self.get_epoch(&epoch_id)
+                    .map(|e| e.rewards().iter().map(|r| format!("{} {}", r.amount(), r.token())).collect())
+                    .unwrap_or_default();
+                let rewards = if rewards.is_empty() { "none".to_string() } else { rewards.join(", ") };
+                Ok(format!("Created epoch: {} ({}) from {}, rewards: {}", new_name, epoch_id, source_epoch_name, rewards))
+            },
+            Command::ActivateEpoch { name } => {
+                let epoch_id = self.get_epoch_id_by_name(&name)
+                    .ok_or_else(|| format!("Epoch not found: {}", name))?;
+                self.activate_epoch(epoch_id)?;
+                Ok(format!("Activated epoch: {} ({})", name, epoch_id))
+            },
+            Command::SetEpochReward { token, amount } => {
+                self.set_epoch_reward(&token, amount)?;
+                Ok(format!("Set epoch reward: {} {}", amount, token))
+            },
+            Command::AddTeam { name, representative, trailing_monthly_revenue, address} => {
+                let team_id = self.create_team(name.clone(), representative, trailing_monthly_revenue, address)?;
+                Ok(format!("Added team: {} ({})", name, team_id))
+            },
+            Command::UpdateTeam { team_name, updates } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                self.update_team(team_id, updates)?;
+                Ok(format!("Updated team: {}", team_name))
+            },
+            Command::MergeTeams { source_name, target_name } => {
+                let moved = self.merge_teams(&source_name, &target_name)?;
+                Ok(format!("Merged team {} into {} ({} proposal(s) reassigned)", source_name, target_name, moved))
+            },
+            Command::AddTeamAlias { team_name, alias } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                self.add_team_alias(team_id, &alias)?;
+                Ok(format!("Added alias {} to team {}", alias.trim(), team_name))
+            },
+            Command::ArchiveTeam { team_name } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                self.archive_team(team_id)?;
+                Ok(format!("Archived team: {}", team_name))
+            },
+            Command::UnarchiveTeam { team_name } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                self.unarchive_team(team_id)?;
+                Ok(format!("Unarchived team: {}", team_name))
+            },
+            Command::AddProposal { title, url, budget_request_details, announced_at, published_at, is_historical } => {
+                let category = budget_request_details.as_ref().and_then(|d| d.category.clone());
+                let ipfs_hash = budget_request_details.as_ref().and_then(|d| d.ipfs_hash.clone());
+                if let Some(hash) = &ipfs_hash {
+                    validate_ipfs_hash(hash)?;
+                }
+                let budget_request_details = budget_request_details.map(|details| {
+                    BudgetRequestDetails::new(
+                        details.team.and_then(|name| self.get_team_id_by_name(&name)),
+                        details.request_amounts.unwrap_or_default(),
+                        details.start_date,
+                        details.end_date,
+                        details.is_loan,
+                        details.payment_address,
+                    )
+                }).transpose()?;
+             
+                let proposal_id = self.add_proposal(title.clone(), url, budget_request_details, announced_at, published_at, is_historical, category)?;
+                if let Some(hash) = ipfs_hash {
+                    self.set_proposal_ipfs_hash(proposal_id, hash)?;
+                }
+                Ok(format!("Added proposal: {} ({})", title, proposal_id))
+             },
+            Command::PinProposalDocument { proposal_name, ipfs_hash } => {
+                self.pin_proposal_document(&proposal_name, &ipfs_hash)?;
+                Ok(format!("Pinned document {} to proposal {}", ipfs_hash.trim(), proposal_name))
+            },
+            Command::ImportProposals { file_path, dry_run } => {
+                let summary = self.import_proposals(&file_path, dry_run)?;
+                Ok(serde_json::to_string_pretty(&summary)?)
+            },
+            Command::CheckDuplicateProposal { title } => {
+                let epoch_id = self.state.current_epoch().ok_or(BudgetSystemError::NoActiveEpoch)?;
+                let similar = self.find_similar_proposals(&title, epoch_id, SIMILAR_PROPOSAL_THRESHOLD);
+                if similar.is_empty() {
+                    return Ok(format!("No proposals similar to '{}' in the active epoch", title));
+                }
+                let mut output = format!("Proposals similar to '{}':\n", title);
+                for proposal in similar {
+                    let similarity = strsim::jaro_winkler(&title.trim().to_lowercase(), &proposal.title().trim().to_lowercase());
+                    output.push_str(&format!("  {} ({:.0}% similar)\n", proposal.title(), similarity * 100.0));
+                }
+                Ok(output)
+            },
+            Command::UpdateProposal { proposal_name, updates } => {
+                self.get_proposal_id_by_name(&proposal_name)
+                    .ok_or_else(|| self.proposal_not_found(&proposal_name))?;
+                self.update_proposal(&proposal_name, updates)?;
+                Ok(format!("Updated proposal: {}", proposal_name))
+            },
+            Command::ImportPredefinedRaffle { 
+                proposal_name, 
+                counted_teams, 
+                uncounted_teams, 
+                total_counted_seats, 
+                max_earner_seats 
+            } => {
+                let raffle_id = self.import_predefined_raffle(
+                    &proposal_name, 
+                    counted_teams.clone(), 
+                    uncounted_teams.clone(), 
+                    total_counted_seats, 
+                    max_earner_seats
+                )?;
+                
+                let raffle = self.state().raffles().get(&raffle_id).unwrap();
+            
+                let mut output = format!("Imported predefined raffle for proposal '{}' (Raffle ID: {})\n", proposal_name, raffle_id);
+                output += &format!("  Counted teams: {:?}\n", counted_teams);
+                output += &format!("  Uncounted teams: {:?}\n", uncounted_teams);
+                output += &format!("  Total counted seats: {}\n", total_counted_seats);
+                output += &format!("  Max earner seats: {}\n", max_earner_seats);
+            
+                output += "\nTeam Snapshots:\n";
+                for snapshot in raffle.team_snapshots() {
+                    output += &format!("  {} ({}): {:?}\n", snapshot.name(), snapshot.id(), snapshot.status());
+                }
+            
+                if let Some(result) = raffle.result() {
+                    output += "\nRaffle Result:\n";
+                    output += &format!("  Counted teams: {:?}\n", result.counted());
+                    output += &format!("  Uncounted teams: {:?}\n", result.uncounted());
+                } else {
+                    output += "\nRaffle result not available\n";
+                }
+            
+                Ok(output)
+            },
+            Command::ImportHistoricalVote { 
+                proposal_name, 
+                passed, 
+                participating_teams,
+                non_participating_teams,
+                counted_points,
+                uncounted_points,
+            } => {
+                let vote_id = self.import_historical_vote(
+                    &proposal_name,
+                    passed,
+                    participating_teams.clone(),
+                    non_participating_teams.clone(),
+                    counted_points,
+                    uncounted_points
+                )?;
+            
+                let vote = self.state().votes().get(&vote_id).unwrap();
+                let _proposal = self.state().proposals().get(&vote.proposal_id()).unwrap();
+            
+                let mut output = format!("Imported historical vote for proposal '{}' (Vote ID: {})\n", proposal_name, vote_id);
+                output += &format!("Vote passed: {}\n", passed);
+            
+                output += "\nNon-participating teams:\n";
+                for team_name in &non_participating_teams {
+                    output += &format!("  {}\n", team_name);
+                }
+            
+                if let VoteType::Formal { raffle_id, .. } = vote.vote_type() {
+                    if let Some(raffle) = self.state().raffles().get(&raffle_id) {
+                        if let VoteParticipation::Formal { counted, uncounted } = vote.participation() {
+                            output += "\nCounted seats:\n";
+                            for &team_id in counted {
+                                if let Some(team) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
+                                    output += &format!("  {} (+{} points)\n", team.name(), self.config.counted_vote_points);
+                                }
+                            }
+            
+                            output += "\nUncounted seats:\n";
+                            for &team_id in uncounted {
+                                if let Some(team) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
+                                    output += &format!("  {} (+{} points)\n", team.name(), self.config.uncounted_vote_points);
+                                }
+                            }
+                        }
+                    } else {
+                        output += "\nAssociated raffle not found. Cannot display seat breakdowns.\n";
+                    }
+                } else {
+                    output += "\nThis is an informal vote, no counted/uncounted breakdown available.\n";
+                }
+            
+                output += "\nNote: Detailed vote counts are not available for historical votes.\n";
+            
+                Ok(output)
+            },
+            Command::ImportHistoricalRaffle { 
+                proposal_name, 
+                initiation_block, 
+                randomness_block, 
+                team_order, 
+                excluded_teams,
+                total_counted_seats, 
+                max_earner_seats 
+            } => {
+                let (raffle_id, raffle) = self.import_historical_raffle(
+                    &proposal_name,
+                    initiation_block,
+                    randomness_block,
+                    team_order.clone(),
+                    excluded_teams.clone(),
+                    total_counted_seats.or(Some(self.config.default_total_counted_seats)),
+                    max_earner_seats.or(Some(self.config.default_max_earner_seats)),
+                ).await?;
+            
+                let mut output = format!("Imported historical raffle for proposal '{}' (Raffle ID: {})\n", proposal_name, raffle_id);
+                output += &format!("Randomness: {}\n", raffle.config().block_randomness());
+            
+                if let Some(excluded) = excluded_teams {
+                    output += &format!("Excluded teams: {:?}\n", excluded);
+                }
+            
+                for snapshot in raffle.team_snapshots() {
+                    let tickets: Vec<_> = raffle.tickets().iter()
+                        .filter(|t| t.team_id() == snapshot.id())
+                        .collect();
+                    
+                    if !tickets.is_empty() {
+                        let start = tickets.first().unwrap().index();
+                        let end = tickets.last().unwrap().index();
+                        output += &format!("Team '{}' ballot range: {} - {}\n", snapshot.name(), start, end);
+                    }
+                }
+            
+                if let Some(result) = raffle.result() {
+                    output += "Counted seats:\n";
+                    output += "Earner seats:\n";
+                    let mut earner_count = 0;
+                    for &team_id in result.counted() {
+                        if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
+                            if let TeamStatus::Earner { .. } = snapshot.status() {
+                                earner_count += 1;
+                                let best_score = raffle.best_score(team_id);
+                                output += &format!("  {} (score: {})\n", snapshot.name(), best_score);
+                            }
+                        }
+                    }
+                    output += "Supporter seats:\n";
+                    for &team_id in result.counted() {
+                        if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
+                            if let TeamStatus::Supporter = snapshot.status() {
+                                let best_score = raffle.best_score(team_id);
+                                output += &format!("  {} (score: {})\n", snapshot.name(), best_score);
+                            }
+                        }
+                    }
+                    output += &format!("Total counted seats: {} (Earners: {}, Supporters: {})\n", 
+                                result.counted().len(), earner_count, result.counted().len() - earner_count);
+            
+                    output += "Uncounted seats:\n";
+                    output += "Earner seats:\n";
+                    for &team_id in result.uncounted() {
+                        if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
+                            if let TeamStatus::Earner { .. } = snapshot.status() {
+                                let best_score = raffle.best_score(team_id);
+                                output += &format!("  {} (score: {})\n", snapshot.name(), best_score);
+                            }
+                        }
+                    }
+                    output += "Supporter seats:\n";
+                    for &team_id in result.uncounted() {
+                        if let Some(snapshot) = raffle.team_snapshots().iter().find(|s| s.id() == team_id) {
+                            if let TeamStatus::Supporter = snapshot.status() {
+                                let best_score = raffle.best_score(team_id);
+                                output += &format!("  {} (score: {})\n", snapshot.name(), best_score);
+                            }
+                        }
+                    }
+                } else {
+                    output += "Raffle result not available\n";
+                }
+            
+                Ok(output)
+            },
+            Command::PrintTeamReport => {
+                Ok(self.print_team_report())
+            },
+            Command::PrintEpochState { format } => {
+                match format {
+                    ReportFormat::Text | ReportFormat::Markdown => self.print_epoch_state(),
+                    ReportFormat::Json => Ok(serde_json::to_string_pretty(&self.get_epoch_state_report()?)?),
+                    ReportFormat::Csv => Err("CSV output is not supported for the epoch state report".into()),
+                }
+            },
+            Command::PrintTeamVoteParticipation { team_name, epoch_name } => {
+                self.print_team_vote_participation(&team_name, epoch_name.as_deref())
+            },
+            Command::CloseProposal { proposal_name, resolution } => {
+                let proposal_id = self.get_proposal_id_by_name(&proposal_name)
+                    .ok_or_else(|| self.proposal_not_found(&proposal_name))?;
+                let resolution = parse_resolution(&resolution)?;
+                self.close_with_reason(proposal_id, &resolution)?;
+                Ok(format!("Closed proposal '{}' with resolution: {:?}", proposal_name, resolution))
+            },
+            Command::CreateRaffle { proposal_name, block_offset, excluded_teams } => {
+                let progress_stream = self.create_raffle_with_progress(
+                    proposal_name,
+                    block_offset,
+                    excluded_teams,
+                ).await;
+
+                let mut output = String::new();
+                pin_mut!(progress_stream);
+                
+                while let Some(progress) = progress_stream.next().await {
+                    match progress {
+                        Ok(progress) => {
+                            output.push_str(&format!("{}\n", progress.format_message()));
+                            if progress.is_complete() {
+                                break;
+                            }
+                        },
+                        Err(e) => return Err(Box::new(e)),
+                    }
+                }
+                
+                Ok(output)
+            },
+            Command::RerunRaffle { proposal_name, block_offset } => {
+                let raffle = self.rerun_raffle(&proposal_name, block_offset).await?;
+                let previous = raffle.superseded_raffles().last().map(|s| s.raffle_id().to_string()).unwrap_or_default();
+                let team_names = |ids: &[Uuid]| -> String {
+                    let names: Vec<&str> = ids.iter()
+                        .filter_map(|id| raffle.team_snapshots().iter().find(|s| s.id() == *id).map(|s| s.name()))
+                        .collect();
+                    if names.is_empty() { "None".to_string() } else { names.join(", ") }
+                };
+
+                let mut output = format!("Re-ran raffle for '{}': {} replaces {}\n", proposal_name, raffle.id(), previous);
+                output.push_str(&format!("Randomness block {}: {}\n",
+                    raffle.config().randomness_block(), raffle.config().block_randomness()));
+                if let Some(result) = raffle.result() {
+                    output.push_str(&format!("Counted: {}\n", team_names(result.counted())));
+                    output.push_str(&format!("Uncounted: {}\n", team_names(result.uncounted())));
+                }
+                Ok(output)
+            },
+            Command::CreateAndProcessVote { proposal_name, counted_votes, uncounted_votes, delegations, vote_opened, vote_closed, deadline } => {
+                let mut output = format!("Executing CreateAndProcessVote command for proposal: {}\n", proposal_name);
+                
+                match self.create_and_process_vote(
+                    &proposal_name,
+                    counted_votes,
+                    uncounted_votes,
+                    delegations,
+                    vote_opened,
+                    vote_closed,
+                    deadline
+                ) {
+                    Ok(report) => {
+                        output += &format!("Vote processed successfully for proposal: {}\n", proposal_name);
+                        output += &format!("Vote report:\n{}\n", report);
+                    
+                        // Print point credits
+                        if let Some(vote) = self.get_proposal_id_by_name(&proposal_name)
+                            .and_then(|proposal_id| self.get_vote_for_proposal(proposal_id))
+                        {
+                            output += "\nPoints credited:\n";
+                            if let VoteParticipation::Formal { counted, uncounted } = &vote.participation() {
+                                for &team_id in counted {
+                                    if let Some(team) = self.team_name_with_delegate(vote, team_id) {
+                                        output += &format!("  {} (+{} points)\n", team, self.config.counted_vote_points);
+                                    }
+                                }
+                                for &team_id in uncounted {
+                                    if let Some(team) = self.team_name_with_delegate(vote, team_id) {
+                                        output += &format!("  {} (+{} points)\n", team, self.config.uncounted_vote_points);
+                                    }
+                                }
+                            }
+                        } else {
+                            output += "Warning: Vote not found after processing\n";
+                        }
+                    },
+                    Err(e) => {
+                        output += &format!("Error: Failed to process vote for proposal '{}'. Reason: {}\n", proposal_name, e);
+                    }
+                }
+
+                Ok(output)
+            },
+            Command::CloseExpiredVotes => {
+                let closed = self.close_expired_votes()?;
+                let mut output = format!("Closed {} expired vote(s)\n", closed.len());
+                for (vote_id, passed) in closed {
+                    let title = self.state.get_vote(&vote_id)
+                        .and_then(|vote| self.state.get_proposal(&vote.proposal_id()))
+                        .map_or_else(|| vote_id.to_string(), |p| p.title().to_string());
+                    output.push_str(&format!("- {}: {}\n", title, if passed { "Approved" } else { "Rejected" }));
+                }
+                Ok(output)
+            },
+            Command::GenerateMultisigPayload { epoch_name, output_path } => {
+                let payload = self.generate_gnosis_safe_payload(&epoch_name)?;
+                match output_path {
+                    Some(path) => {
+                        let output_path = PathBuf::from(path);
+                        if let Some(parent) = output_path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::write(&output_path, payload)?;
+                        Ok(format!("Generated multisig payload at: {:?}", output_path))
+                    },
+                    None => Ok(payload),
+                }
+            },
+            Command::GenerateReportsForClosedProposals { epoch_name } => {
+                let epoch_id = self.get_epoch_id_by_name(&epoch_name)
+                    .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
+                
+                let closed_proposals: Vec<_> = self.get_proposals_for_epoch(epoch_id)
+                    .into_iter()
+                    .filter(|p| p.is_closed())
+                    .collect();
+
+                let mut report = String::new();
+                for proposal in closed_proposals {
+                    match self.generate_and_save_proposal_report(proposal.id(), &epoch_name, ReportSections::all()) {
+                        Ok(file_path) => report.push_str(&format!("Report generated for proposal '{}' at {:?}\n", proposal.title(), file_path)),
+                        Err(e) => report.push_str(&format!("Failed to generate report for proposal '{}': {}\n", proposal.title(), e)),
+                    }
+                }
+                Ok(report)
+            },
+            Command::GenerateReportForProposal { proposal_name, epoch_name, sections } => {
+                let sections = sections.as_deref().map(str::parse::<ReportSections>).transpose()?.unwrap_or_default();
+                let (proposal_id, epoch_name) = self.find_proposal_for_report(&proposal_name, epoch_name.as_deref())?;
+                let title = self.get_proposal(&proposal_id)
+                    .map(|p| p.title().to_string())
+                    .unwrap_or_else(|| proposal_name.clone());
+
+                match self.generate_and_save_proposal_report(proposal_id, &epoch_name, sections) {
+                    Ok(file_path) => Ok(format!("Report generated for proposal '{}' at {:?}", title, file_path)),
+                    Err(e) => Err(format!("Failed to generate report for proposal '{}': {}", title, e).into()),
+                }
+            },
+            Command::PrintPointReport { epoch_name } => {
+                self.generate_point_report(epoch_name.as_deref())
+                    .map_err(|e| Box::new(BudgetSystemError::Other(e.to_string())) as Box<dyn Error>)
+            },
+            Command::PrintCategoryBreakdown { epoch_name } => {
+                let (_, epoch_id) = self.get_current_or_specified_epoch(epoch_name.as_deref())?;
+                Ok(self.generate_category_breakdown_report(epoch_id)?)
+            },
+            Command::CloseEpoch { epoch_name } => {
+                let closed_name = match &epoch_name {
+                    Some(name) => name.clone(),
+                    None => self.get_current_epoch().map(|e| e.name().to_string()).ok_or(BudgetSystemError::NoActiveEpoch)?,
+                };
+                self.close_epoch(epoch_name.as_deref())?;
+                Ok(format!(
+                    "Successfully closed epoch: {}\n\n{}",
+                    epoch_name.unwrap_or_else(|| "Active epoch".to_string()),
+                    self.epoch_close_summary(&closed_name)?
+                ))
+            },
+            Command::ForceCloseEpoch { epoch_name } => {
+                let closed_name = match &epoch_name {
+                    Some(name) => name.clone(),
+                    None => self.get_current_epoch().map(|e| e.name().to_string()).ok_or(BudgetSystemError::NoActiveEpoch)?,
+                };
+                let retracted = self.force_close_epoch(epoch_name.as_deref())?;
+                let mut output = format!("Successfully closed epoch: {}\n", closed_name);
+                if !retracted.is_empty() {
+                    output.push_str(&format!("Retracted proposals: {}\n", retracted.join(", ")));
+                }
+                output.push('\n');
+                output.push_str(&self.epoch_close_summary(&closed_name)?);
+                Ok(output)
+            },
+            Command::GenerateEndOfEpochReport { epoch_name, format } => {
+                self.generate_end_of_epoch_report(&epoch_name, format)?;
+                Ok(format!("Generated End of Epoch Report for epoch: {}", epoch_name))
+            },
+            Command::RunScript { .. } => {
+                Err("RunScript command should be handled by the CLI, not the BudgetSystem".into())
+            },
+            Command::GenerateUnpaidRequestsReport { output_path, epoch_name } => {
+                self.generate_unpaid_requests_report(
+                    output_path.as_deref(),
+                    epoch_name.as_deref()
+                ).map(|s| format!("{}\n", s))
+            },
+            Command::LogPayment { payment_tx, payment_date, proposal_names, skip_verification } => {
+                Ok(self.record_payments(&payment_tx, payment_date, &proposal_names, skip_verification).await?)
+            },
+            Command::GenerateEpochPaymentsReport { epoch_name, output_path, format } => {
+                self.generate_epoch_payments_report(&epoch_name, output_path.as_deref(), format)
+            },
+            Command::ExportBudgetFlowData { epoch_name, output_path } => {
+                let epoch_id = self.get_epoch_id_by_name(&epoch_name)
+                    .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
+                let flow = self.generate_budget_flow_data(epoch_id)?;
+                let json = serde_json::to_string_pretty(&flow)?;
+
+                if let Some(path) = output_path {
+                    let output_path = PathBuf::from(path);
+                    if let Some(parent) = output_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&output_path, json)?;
+                    Ok(format!("Exported budget flow data to: {:?}", output_path))
+                } else {
+                    Ok(json)
+                }
+            },
+            Command::SetEpochVotingWindows { epoch_name, min_days_before_vote, max_days_to_vote } => {
+                let epoch_id = self.set_epoch_voting_windows(epoch_name.as_deref(), min_days_before_vote, max_days_to_vote)?;
+                let closed = self.enforce_proposal_announcement_window(epoch_id)?;
+
+                let mut output = format!(
+                    "Set voting windows: min days before vote: {}, max days to vote: {}\n",
+                    min_days_before_vote.map_or("none".to_string(), |d| d.to_string()),
+                    max_days_to_vote.map_or("none".to_string(), |d| d.to_string())
+                );
+                for proposal_id in closed {
+                    if let Some(proposal) = self.get_proposal(&proposal_id) {
+                        output.push_str(&format!("  Closed overdue proposal: {}\n", proposal.title()));
+                    }
+                }
+                Ok(output)
+            },
+            Command::RecomputeEpochFromVotes { epoch_name, confirm } => {
+                self.recompute_epoch_rewards(&epoch_name, confirm)
+            },
+            Command::SetEpochStatus { epoch_name, status, confirm, force } => {
+                Ok(self.force_epoch_status(&epoch_name, status, confirm, force)?)
+            },
+            Command::BulkCloseProposals { epoch_name, resolution, reason, confirm } => {
+                let epoch_id = self.get_epoch_id_by_name(&epoch_name)
+                    .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
+                let resolution = parse_resolution(&resolution)?;
+
+                if !confirm {
+                    let mut names: Vec<String> = self.get_proposals_for_epoch(epoch_id).iter()
+                        .filter(|p| p.is_actionable())
+                        .map(|p| p.title().to_string())
+                        .collect();
+                    names.sort();
+                    if names.is_empty() {
+                        return Ok(format!("No open proposals in epoch '{}'", epoch_name));
+                    }
+                    let mut output = format!("{} proposal(s) in epoch '{}' would be closed as {:?}:\n", names.len(), epoch_name, resolution);
+                    for name in names {
+                        output.push_str(&format!("  {}\n", name));
+                    }
+                    output.push_str("Run again with --confirm to close them.");
+                    return Ok(output);
+                }
+
+                let reason = reason.unwrap_or_else(|| "Bulk close".to_string());
+                let closed = self.bulk_close_proposals(epoch_id, resolution.clone(), &reason)?;
+                Ok(format!("Closed {} proposal(s) in epoch '{}' with resolution: {:?}", closed.len(), epoch_name, resolution))
+            },
+            Command::BulkAddTeams { teams } => {
+                let mut added = Vec::new();
+                let mut failed = Vec::new();
+                for team in teams {
+                    match self.create_team(team.name.clone(), team.representative, team.trailing_monthly_revenue, team.address) {
+                        Ok(team_id) => added.push(format!("  Added team: {} ({})", team.name, team_id)),
+                        Err(e) => failed.push(format!("  Failed to add team {}: {}", team.name, e)),
+                    }
+                }
+
+                let mut output = format!("Bulk add teams: {} added, {} failed\n", added.len(), failed.len());
+                for line in added.iter().chain(failed.iter()) {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+                Ok(output.trim_end().to_string())
+            },
+            Command::PrintAbsenceStreaks { min_streak } => {
+                let streaks = self.get_team_absence_streaks()?;
+                let mut output = format!("Team Absence Streaks (minimum streak: {})\n", min_streak);
+                let mut any = false;
+                for info in streaks.iter().filter(|i| i.current_streak_epochs >= min_streak) {
+                    any = true;
+                    output.push_str(&format!(
+                        "  {}: current streak {} epoch(s), total absences {}, last participated: {}\n",
+                        info.team_name,
+                        info.current_streak_epochs,
+                        info.total_absences,
+                        info.last_participated_epoch.as_deref().unwrap_or("never")
+                    ));
+                }
+                if !any {
+                    output.push_str("  No teams found\n");
+                }
+                Ok(output)
+            },
+            Command::ListExcludedTeams { proposal_name } => {
+                let excluded = self.raffle_excluded_teams(&proposal_name)?;
+                let mut output = format!("Excluded teams for raffle of proposal '{}':\n", proposal_name);
+                if excluded.is_empty() {
+                    output.push_str("  None\n");
+                } else {
+                    for team_name in excluded {
+                        output.push_str(&format!("  {} (reason: excluded in raffle configuration)\n", team_name));
+                    }
+                }
+                Ok(output)
+            },
+            Command::PrintOverduePayments { days } => {
+                let overdue = self.get_overdue_payments(days)?;
+                Ok(OverduePayment::format_list(&overdue, days))
+            },
+            Command::ListPendingPayments { epoch_name, min_days_pending } => {
+                if let Some(name) = &epoch_name {
+                    self.get_epoch_id_by_name(name)
+                        .ok_or_else(|| format!("Epoch not found: {}", name))?;
+                }
+                let pending: Vec<PendingPayment> = self.get_pending_payments().into_iter()
+                    .filter(|p| epoch_name.as_ref().is_none_or(|name| &p.epoch_name == name))
+                    .filter(|p| min_days_pending.is_none_or(|days| p.days_pending >= days as i64))
+                    .collect();
+                if pending.is_empty() {
+                    return Ok("No pending payments".to_string());
+                }
+
+                let mut output = String::from("| Proposal | Team | Epoch | Amounts | Approved | Days Pending | Loan |\n");
+                output.push_str("|----------|------|-------|---------|----------|--------------|------|\n");
+                for payment in &pending {
+                    let mut amounts: Vec<_> = payment.amounts.iter().collect();
+                    amounts.sort_by_key(|(token, _)| *token);
+                    let amounts = amounts.iter()
+                        .map(|(token, amount)| format!("{} {}", amount, token))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {} | {} | {} | {} |\n",
+                        payment.title,
+                        payment.team_name,
+                        payment.epoch_name,
+                        amounts,
+                        payment.approved_date,
+                        payment.days_pending,
+                        if payment.is_loan { "Yes" } else { "No" }
+                    ));
+                }
+
+                let stale: Vec<&str> = pending.iter()
+                    .filter(|p| p.days_pending > PENDING_PAYMENT_WARNING_DAYS)
+                    .map(|p| p.title.as_str())
+                    .collect();
+                if !stale.is_empty() {
+                    output.push_str(&format!(
+                        "\nWarning: {} payment(s) pending for more than {} days: {}\n",
+                        stale.len(), PENDING_PAYMENT_WARNING_DAYS, stale.join(", ")
+                    ));
+                }
+                Ok(output)
+            },
+            Command::VerifyPoints => {
+                match self.verify_point_consistency() {
+                    Ok(()) => Ok("All points consistent".to_string()),
+                    Err(mismatches) => {
+                        let mut output = format!("Found {} point mismatch(es):\n", mismatches.len());
+                        for mismatch in mismatches {
+                            let epoch = self.state.get_epoch(&mismatch.epoch_id)
+                                .map_or(mismatch.epoch_id.to_string(), |e| e.name().to_string());
+                            let team = self.state.get_team(&mismatch.team_id)
+                                .map_or(mismatch.team_id.to_string(), |t| t.name().to_string());
+                            output.push_str(&format!("  {} / {}: stored {}, recalculated {}\n",
+                                epoch, team, mismatch.stored, mismatch.recalculated));
+                        }
+                        Ok(output)
+                    },
+                }
+            },
+            Command::PrintStateSize => {
+                let estimate = self.state_size_estimate();
+                let mut output = format!("State size: {} bytes (warning threshold: {} bytes)\n",
+                    estimate.serialized_bytes, self.config.state_file_warn_bytes);
+                output.push_str(&format!("  Epochs: {}\n", estimate.epochs));
+                output.push_str(&format!("  Proposals: {}\n", estimate.proposals));
+                output.push_str(&format!("  Raffles: {}\n", estimate.raffles));
+                output.push_str(&format!("  Votes: {}\n", estimate.votes));
+                output.push_str(&format!("  Teams: {}\n", estimate.teams));
+                output.push_str(&format!("  History snapshots: {}\n", estimate.history_snapshots));
+                Ok(output)
+            },
+            Command::ExportEpochInfographicData { epoch_name } => {
+                let epoch_id = self.get_epoch_id_by_name(&epoch_name)
+                    .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
+                let data = self.generate_epoch_infographic_data(epoch_id)?;
+
+                let output_path = Path::new(&self.config.state_file)
+                    .parent()
+                    .unwrap_or_else(|| Path::new("."))
+                    .join("reports")
+                    .join(FileSystem::sanitize_filename(&epoch_name))
+                    .join("infographic_data.json");
+                fs::create_dir_all(output_path.parent().unwrap())?;
+                fs::write(&output_path, serde_json::to_string_pretty(&data)?)?;
+
+                Ok(format!("Exported epoch infographic data to: {:?}", output_path))
+            },
+            Command::SetProposalLoanTerms { proposal_name, interest_rate, due_date, repayment_schedule, repaid_amounts } => {
+                let repayment_schedule = repayment_schedule.parse()?;
+                // Keep previously recorded repayments unless new amounts are given
+                let repaid_amounts = match repaid_amounts {
+                    Some(amounts) => amounts,
+                    None => self.get_proposal_id_by_name(&proposal_name)
+                        .and_then(|id| self.state.get_proposal(&id))
+                        .and_then(|p| p.budget_request_details())
+                        .and_then(|d| d.loan_terms())
+                        .map(|t| t.repaid_amounts().clone())
+                        .unwrap_or_default(),
+                };
+                let terms = LoanTerms::new(interest_rate, due_date, repayment_schedule, repaid_amounts)?;
+                self.set_proposal_loan_terms(&proposal_name, terms)?;
+                Ok(format!("Set loan terms for proposal '{}'", proposal_name))
+            },
+            Command::PrintLoansOutstanding => {
+                Ok(self.generate_loans_outstanding_report())
+            },
+            Command::SetProposalTeamAllocations { proposal_name, allocations } => {
+                self.set_proposal_team_allocations(&proposal_name, allocations)?;
+                Ok(format!("Set team allocations for proposal '{}'", proposal_name))
+            },
+            Command::LogAllocationPayment { payment_tx, payment_date, proposal_name, team_names, skip_verification } => {
+                Ok(self.record_allocation_payments(&payment_tx, payment_date, &proposal_name, &team_names, skip_verification).await?)
+            },
+            Command::RecordLoanRepayment { proposal_name, repaid_at } => {
+                let repaid_at = repaid_at.unwrap_or_else(|| Utc::now().date_naive());
+                self.record_loan_repayment(&proposal_name, repaid_at)?;
+                Ok(format!("Recorded repayment of loan '{}' on {}", proposal_name, repaid_at))
+            },
+            Command::PrintOutstandingLoans { epoch_name } => {
+                Ok(self.generate_outstanding_loans_report(epoch_name.as_deref())?)
+            },
+            Command::CreateProposalTemplate { name, title_pattern, team, amounts, is_loan, tags, recurrence_interval_epochs, max_occurrences } => {
+                let team_id = match team {
+                    Some(team_name) => Some(self.get_team_id_by_name(&team_name)
+                        .ok_or_else(|| self.team_not_found(&team_name))?),
+                    None => None,
+                };
+                let recurrence = recurrence_interval_epochs
+                    .map(|interval| RecurrenceConfig::new(interval, max_occurrences))
+                    .transpose()?;
+                let template = ProposalTemplate::new(name.clone(), title_pattern, team_id, amounts, is_loan, tags, recurrence)?;
+                self.create_proposal_template(template)?;
+                Ok(format!("Created proposal template: {}", name))
+            },
+            Command::ListProposalTemplates => {
+                let templates = self.list_proposal_templates();
+                if templates.is_empty() {
+                    return Ok("No proposal templates".to_string());
+                }
+
+                let mut output = String::from("Proposal Templates:\n");
+                for template in templates {
+                    let team_name = template.team_id()
+                        .and_then(|id| self.state.get_team(&id))
+                        .map(|t| t.name().to_string())
+                        .unwrap_or_else(|| "No Team".to_string());
+                    let mut amounts: Vec<_> = template.amount_pattern().iter().collect();
+                    amounts.sort_by_key(|(token, _)| *token);
+                    let amounts = amounts.iter()
+                        .map(|(token, amount)| format!("{} {}", amount, token))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let recurrence = match template.recurrence() {
+                        Some(r) => format!("every {} epoch(s), max {}", r.interval_epochs(),
+                            r.max_occurrences().map_or("unlimited".to_string(), |m| m.to_string())),
+                        None => "none".to_string(),
+                    };
+                    output.push_str(&format!("  {}: \"{}\" ({}), amounts: {}, loan: {}, tags: [{}], recurrence: {}, used {} time(s)\n",
+                        template.name(), template.title_pattern(), team_name, amounts, template.is_loan(),
+                        template.tags().join(", "), recurrence, template.created_count()));
+                }
+                Ok(output)
+            },
+            Command::CreateProposalFromTemplate { template_name, epoch_name, title, url, team, amounts, start_date, end_date, announced_at, published_at } => {
+                let epoch_id = match epoch_name {
+                    Some(name) => self.get_epoch_id_by_name(&name)
+                        .ok_or_else(|| format!("Epoch not found: {}", name))?,
+                    None => self.state.current_epoch().ok_or("No active epoch")?,
+                };
+                let team_id = match team {
+                    Some(team_name) => Some(self.get_team_id_by_name(&team_name)
+                        .ok_or_else(|| self.team_not_found(&team_name))?),
+                    None => None,
+                };
+                let overrides = ProposalOverrides {
+                    title,
+                    url,
+                    team_id,
+                    amounts,
+                    start_date,
+                    end_date,
+                    announced_at,
+                    published_at,
+                };
+                let proposal_id = self.create_proposal_from_template(&template_name, epoch_id, overrides)?;
+                let title = self.get_proposal(&proposal_id).map(|p| p.title().to_string()).unwrap_or_default();
+                Ok(format!("Created proposal '{}' ({}) from template '{}'", title, proposal_id, template_name))
+            },
+            Command::UpdateTeamRegion { team_name, region } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                self.update_team_region(team_id, region)?;
+                let region = self.get_team(&team_id).and_then(|t| t.region()).unwrap_or("none");
+                Ok(format!("Updated region for team {}: {}", team_name, region))
+            },
+            Command::PrintGeographicDistribution => {
+                let distribution = self.get_team_geographic_distribution();
+                if distribution.is_empty() {
+                    return Ok("No teams registered.".to_string());
+                }
+                let mut regions: Vec<(String, Vec<String>)> = distribution.into_iter().collect();
+                regions.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+                let mut output = String::from("Geographic distribution of teams:\n");
+                for (region, names) in regions {
+                    output.push_str(&format!("  {} ({}): {}\n", region, names.len(), names.join(", ")));
+                }
+                Ok(output)
+            },
+            Command::SetMaxEarnerSeatsGlobally { max_earner_seats } => {
+                let exceeding = self.set_default_max_earner_seats(max_earner_seats)?;
+                let mut output = format!("Default max earner seats set to {}\n", max_earner_seats);
+                if !exceeding.is_empty() {
+                    output.push_str("Warning: the following unfinalized raffles exceed the new value:\n");
+                    for raffle_id in exceeding {
+                        let raffle = self.state.get_raffle(&raffle_id);
+                        let proposal = raffle
+                            .and_then(|r| self.get_proposal(&r.config().proposal_id()))
+                            .map_or("Unknown proposal".to_string(), |p| p.title().to_string());
+                        let seats = raffle.map_or(0, |r| r.config().max_earner_seats());
+                        output.push_str(&format!("  {} ({}): max earner seats {}\n", proposal, raffle_id, seats));
+                    }
+                }
+                Ok(output)
+            },
+            Command::PreviewEndOfEpochReport { epoch_name } => {
+                Ok(self.preview_close_epoch_report(&epoch_name)?)
+            },
+            Command::Export { kind, epoch_name, format, output_path } => {
+                self.export_delimited(kind, epoch_name.as_deref(), format, output_path.as_deref())
+            },
+            Command::SetProposalReviewers { proposal_name, reviewers } => {
+                self.set_proposal_reviewers(&proposal_name, reviewers)?;
+                let names: Vec<String> = self.get_proposal_id_by_name(&proposal_name)
+                    .and_then(|id| self.get_proposal(&id))
+                    .map(|p| p.reviewers().iter().map(|r| r.name().to_string()).collect())
+                    .unwrap_or_default();
+                if names.is_empty() {
+                    Ok(format!("Cleared reviewers for proposal '{}'", proposal_name))
+                } else {
+                    Ok(format!("Set reviewers for proposal '{}': {}", proposal_name, names.join(", ")))
+                }
+            },
+            Command::RecordReviewerSignOff { proposal_name, reviewer, signed_off_at } => {
+                let date = signed_off_at.unwrap_or_else(|| Utc::now().date_naive());
+                self.record_reviewer_sign_off(&proposal_name, &reviewer, date)?;
+                let pending: Vec<String> = self.get_proposal_id_by_name(&proposal_name)
+                    .and_then(|id| self.get_proposal(&id))
+                    .map(|p| p.pending_reviewers().iter().map(|name| name.to_string()).collect())
+                    .unwrap_or_default();
+                let mut output = format!("Recorded sign-off from {} on proposal '{}' ({})", reviewer, proposal_name, date);
+                if pending.is_empty() {
+                    output.push_str("\nAll reviewers have signed off");
+                } else {
+                    output.push_str(&format!("\nPending reviewers: {}", pending.join(", ")));
+                }
+                Ok(output)
+            },
+            Command::RequestVoteChange { vote_id, team_name, new_choice, reason } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                self.request_vote_change(vote_id, team_id, new_choice, &reason)?;
+                Ok(format!("Requested vote change for team {} on vote {}: {:?}", team_name, vote_id, new_choice))
+            },
+            Command::ApproveVoteChange { vote_id, team_name } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                self.approve_vote_change(vote_id, team_id)?;
+                Ok(format!("Approved vote change for team {} on vote {}", team_name, vote_id))
+            },
+            Command::ReopenVote { proposal_name } => {
+                let vote_id = self.reopen_vote(&proposal_name)?;
+                Ok(format!("Reopened vote {} on proposal '{}'; every seat has to vote again", vote_id, proposal_name))
+            },
+            Command::ListVoteChangeRequests { vote_id } => {
+                let requests = self.get_vote_change_requests(vote_id)?;
+                if requests.is_empty() {
+                    return Ok(format!("No pending vote change requests for vote {}", vote_id));
+                }
+                let mut output = format!("Pending vote change requests for vote {}:\n", vote_id);
+                for request in requests {
+                    let team_name = self.get_team(&request.team_id())
+                        .map_or("Unknown team".to_string(), |t| t.name().to_string());
+                    output.push_str(&format!("  {} -> {:?} (requested {}): {}\n",
+                        team_name,
+                        request.new_choice(),
+                        request.requested_at().format("%Y-%m-%d %H:%M UTC"),
+                        request.reason()));
+                }
+                Ok(output)
+            },
+            Command::DiffStateFiles { path_a, path_b } => {
+                let diff = Self::generate_state_diff(Path::new(&path_a), Path::new(&path_b))?;
+                if diff.is_empty() {
+                    return Ok(format!("No differences between {} and {}", path_a, path_b));
+                }
+
+                let mut output = format!("Changes from {} to {}:\n", path_a, path_b);
+                let sections = [
+                    ("Epochs", &diff.added_epochs, &diff.removed_epochs, &diff.modified_epochs),
+                    ("Teams", &diff.added_teams, &diff.removed_teams, &diff.modified_teams),
+                    ("Proposals", &diff.added_proposals, &diff.removed_proposals, &diff.modified_proposals),
+                    ("Votes", &diff.added_votes, &diff.removed_votes, &diff.modified_votes),
+                ];
+                for (label, added, removed, modified) in sections {
+                    if added.is_empty() && removed.is_empty() && modified.is_empty() {
+                        continue;
+                    }
+                    output.push_str(&format!("\n{}:\n", label));
+                    for name in added {
+                        output.push_str(&format!("  + {}\n", name));
+                    }
+                    for name in removed {
+                        output.push_str(&format!("  - {}\n", name));
+                    }
+                    for (name, summary) in modified {
+                        output.push_str(&format!("  ~ {} ({})\n", name, summary));
+                    }
+                }
+                Ok(output)
+            },
+            Command::ReassignRaffleSeats { proposal_name, team_name, new_outcome, reason } => {
+                let proposal_id = self.get_proposal_id_by_name(&proposal_name)
+                    .ok_or_else(|| self.proposal_not_found(&proposal_name))?;
+                let raffle_id = self.get_raffle_for_proposal(proposal_id)
+                    .map(|r| r.id())
+                    .ok_or_else(|| format!("No raffle found for proposal: {}", proposal_name))?;
+
+                self.override_raffle_seat(raffle_id, &team_name, new_outcome, &reason)?;
+                Ok(format!(
+                    "Reassigned {} to {:?} in the raffle for '{}'. The raffle is now marked as manually adjusted.",
+                    team_name, new_outcome, proposal_name
+                ))
+            },
+            Command::PrintBudgetHeadroom { epoch_name, caps } => {
+                let epoch_id = self.get_epoch_id_by_name(&epoch_name)
+                    .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
+                let caps: HashMap<String, f64> = if caps.is_empty() {
+                    self.config.epoch_budget_caps.clone()
+                } else {
+                    caps.into_iter().collect()
+                };
+                if caps.is_empty() {
+                    return Err("No budget caps given and none configured".into());
+                }
+
+                let mut headroom: Vec<(String, f64)> = self.get_epoch_budget_headroom(epoch_id, caps.clone())?
+                    .into_iter()
+                    .collect();
+                headroom.sort_by(|a, b| a.0.cmp(&b.0));
+
+                let mut output = format!("Budget headroom for epoch '{}':\n", epoch_name);
+                for (token, remaining) in headroom {
+                    let cap = caps[&token];
+                    output.push_str(&format!("  {}: {:.2} of {:.2} remaining", token, remaining, cap));
+                    if remaining < 0.0 {
+                        output.push_str(" (over budget)");
+                    }
+                    output.push('\n');
+                }
+                Ok(output)
+            },
+            Command::PrintProposalWaitingPercentiles { epoch_name } => {
+                let epoch_id = match &epoch_name {
+                    Some(name) => Some(self.get_epoch_id_by_name(name)
+                        .ok_or_else(|| format!("Epoch not found: {}", name))?),
+                    None => None,
+                };
+                let stats = self.get_proposal_waiting_time_percentiles(epoch_id)?;
+
+                let scope = epoch_name.map_or("all epochs".to_string(), |name| format!("epoch '{}'", name));
+                let mut output = format!("Proposal waiting times for {} ({} resolved proposals):\n", scope, stats.sample_size);
+                for (label, days) in [
+                    ("p50", stats.p50_days),
+                    ("p75", stats.p75_days),
+                    ("p90", stats.p90_days),
+                    ("p95", stats.p95_days),
+                    ("p99", stats.p99_days),
+                ] {
+                    output.push_str(&format!("  {}: {:.1} days\n", label, days));
+                }
+                Ok(output)
+            },
+            Command::PrintRaffleEligibility { excluded_teams } => {
+                Ok(self.print_upcoming_raffle_eligibility(excluded_teams)?)
+            },
+            Command::VerifyRaffle { proposal_name, on_chain } => {
+                let raffle_id = self.raffle_for_proposal(&proposal_name)?.id();
+                let on_chain_match = if on_chain {
+                    Some(self.verify_raffle_randomness_on_chain(raffle_id).await?)
+                } else {
+                    None
+                };
+                let raffle = self.raffle_for_proposal(&proposal_name)?;
+                let verification = self.verify_raffle(raffle_id)?;
+
+                let mut output = format!(
+                    "Raffle for '{}' (randomness block {}, {}):\n",
+                    proposal_name, raffle.config().randomness_block(), raffle.config().block_randomness()
+                );
+                if verification.matches {
+                    output.push_str("  Verified: replaying the draw reproduces the recorded counted and uncounted teams\n");
+                } else {
+                    output.push_str("  Mismatch: the recorded result differs from the replayed draw\n");
+                    for discrepancy in &verification.discrepancies {
+                        output.push_str(&format!("  - {}\n", discrepancy));
+                    }
+                }
+                match on_chain_match {
+                    Some(true) => output.push_str("  On-chain: the block's randomness matches and reproduces the recorded result\n"),
+                    Some(false) => output.push_str("  On-chain: Mismatch, the randomness fetched from the node does not reproduce the recorded result\n"),
+                    None => {},
+                }
+                if raffle.is_manually_adjusted() {
+                    output.push_str(&format!(
+                        "  Note: the raffle has {} manual seat override(s)\n", raffle.manual_overrides().len()
+                    ));
+                }
+                Ok(output)
+            },
+            Command::GenerateRaffleAuditReport { proposal_name } => {
+                let raffle_id = self.raffle_for_proposal(&proposal_name)?.id();
+                Ok(self.generate_raffle_audit_report(raffle_id)?)
+            },
+            Command::PrintApprovalRatesByStatus { recent_epochs } => {
+                let mut by_status: Vec<(String, f64)> = self.get_approval_rate_by_team_status(None)?.into_iter().collect();
+                by_status.sort_by(|a, b| a.0.cmp(&b.0));
+                let window = recent_epochs.unwrap_or(APPROVAL_TREND_EPOCHS);
+                let trend = self.get_approval_rate_by_epoch_recency(window)?;
+
+                let mut output = String::from("Approval rate by team status:\n");
+                if by_status.is_empty() {
+                    output.push_str("  No resolved proposals with a team\n");
+                }
+                for (status, rate) in by_status {
+                    output.push_str(&format!("  {}: {:.1}%\n", status, rate * 100.0));
+                }
+                output.push_str(&format!("\nApproval rate over the last {} epoch(s):\n", window));
+                if trend.is_empty() {
+                    output.push_str("  No resolved proposals\n");
+                }
+                for (epoch_name, rate) in trend {
+                    output.push_str(&format!("  {}: {:.1}%\n", epoch_name, rate * 100.0));
+                }
+                Ok(output)
+            },
+            Command::SetTeamContacts { team_name, contacts } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                self.update_team_contacts(team_id, contacts)?;
+                let contacts = self.get_team(&team_id).map(|t| t.contacts().join(", ")).unwrap_or_default();
+                if contacts.is_empty() {
+                    Ok(format!("Cleared contacts for team {}", team_name))
+                } else {
+                    Ok(format!("Updated contacts for team {}: {}", team_name, contacts))
+                }
+            },
+            Command::PingAbsentVoters { proposal_name } => {
+                match self.absent_voter_ping_message(&proposal_name)? {
+                    Some(message) => Ok(message),
+                    None => Ok(format!("No teams with a Telegram contact are waiting to vote on '{}'", proposal_name)),
+                }
+            },
+            Command::SetProposalStatus { proposal_name, status } => {
+                match status {
+                    ProposalStatus::Reopened | ProposalStatus::Open => {
+                        self.reopen_proposal(&proposal_name)?;
+                        Ok(format!("Reopened proposal '{}'", proposal_name))
+                    },
+                    ProposalStatus::Closed => Err("Use CloseProposal to close a proposal with a resolution".into()),
+                }
+            },
+            Command::ShowBudgetBurndown { epoch_name, caps, output_path } => {
+                let caps: HashMap<String, f64> = if caps.is_empty() {
+                    self.config.epoch_budget_caps.clone()
+                } else {
+                    caps.into_iter().collect()
+                };
+                if caps.is_empty() {
+                    return Err("No budget caps given and none configured".into());
+                }
+
+                let points = self.epoch_burndown(&epoch_name, &caps)?;
+                let mut output = format!("## Budget Burndown: {}\n\n", epoch_name);
+                output.push_str("| Date | Token | Approved (cumulative) | Paid (cumulative) | Remaining |\n");
+                output.push_str("|------|-------|----------------------:|------------------:|----------:|\n");
+                for point in &points {
+                    output.push_str(&format!(
+                        "| {} | {} | {:.2} | {:.2} | {:.2} |\n",
+                        point.date, point.token, point.approved_cumulative, point.paid_cumulative, point.remaining
+                    ));
+                }
+
+                if let Some(path) = output_path {
+                    let output_path = PathBuf::from(path);
+                    if let Some(parent) = output_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&output_path, serde_json::to_string_pretty(&points)?)?;
+                    output.push_str(&format!("\nExported burndown data to: {:?}\n", output_path));
+                }
+                Ok(output)
+            },
+            Command::PrintTreasuryReport { epoch_name, format } => {
+                match format {
+                    ReportFormat::Text | ReportFormat::Markdown => Ok(self.generate_treasury_report(&epoch_name)?),
+                    ReportFormat::Json => Ok(serde_json::to_string_pretty(&self.get_treasury_report(&epoch_name)?)?),
+                    ReportFormat::Csv => Err("CSV output is not supported for the treasury report".into()),
+                }
+            },
+            Command::GenerateLoanReport { output_path } => {
+                let report = self.generate_loan_tracking_report()?;
+                match output_path {
+                    Some(path) => {
+                        let report_path = Path::new(&self.config.state_file)
+                            .parent()
+                            .unwrap_or_else(|| Path::new("."))
+                            .join("reports")
+                            .join(path);
+                        if let Some(parent) = report_path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::write(&report_path, &report)?;
+                        Ok(format!("Loan report saved to: {:?}", report_path))
+                    },
+                    None => Ok(report),
+                }
+            },
+            Command::GenerateAllEpochsReport { only_closed, output_path } => {
+                let report = self.generate_all_epochs_report(only_closed)?;
+                match output_path {
+                    Some(path) => {
+                        let report_path = Path::new(&self.config.state_file)
+                            .parent()
+                            .unwrap_or_else(|| Path::new("."))
+                            .join("reports")
+                            .join(path);
+                        if let Some(parent) = report_path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::write(&report_path, &report)?;
+                        Ok(format!("All epochs report saved to: {:?}", report_path))
+                    },
+                    None => Ok(report),
+                }
+            },
+            Command::GenerateVoteTrendsReport { only_closed, output_path } => {
+                let report = self.generate_vote_participation_trends(only_closed)?;
+                match output_path {
+                    Some(path) => {
+                        let report_path = Path::new(&self.config.state_file)
+                            .parent()
+                            .unwrap_or_else(|| Path::new("."))
+                            .join("reports")
+                            .join(path);
+                        if let Some(parent) = report_path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::write(&report_path, &report)?;
+                        Ok(format!("Vote trends report saved to: {:?}", report_path))
+                    },
+                    None => Ok(report),
+                }
+            },
+            Command::ListTeamProposals { team_name, epoch_name } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                let mut proposals = match &epoch_name {
+                    Some(name) => {
+                        let epoch_id = self.get_epoch_id_by_name(name)
+                            .ok_or_else(|| format!("Epoch not found: {}", name))?;
+                        self.get_proposals_for_team_in_epoch(team_id, epoch_id)
+                    },
+                    None => self.get_proposals_for_team(team_id),
+                };
+                if proposals.is_empty() {
+                    return Ok(match epoch_name {
+                        Some(name) => format!("No proposals found for team {} in epoch {}", team_name, name),
+                        None => format!("No proposals found for team {}", team_name),
+                    });
+                }
+                proposals.sort_by_key(|p| (p.published_at().or(p.announced_at()), p.title().to_string()));
+
+                let mut output = format!("Proposals for team {}:\n", team_name);
+                for proposal in proposals {
+                    let epoch = self.state.get_epoch(&proposal.epoch_id()).map_or("Unknown", |e| e.name());
+                    let status = match proposal.resolution() {
+                        Some(resolution) => format!("{:?}", resolution),
+                        None => format!("{:?}", proposal.status()),
+                    };
+                    let amounts = proposal.budget_request_details()
+                        .map(|d| {
+                            let mut amounts: Vec<_> = d.request_amounts().iter().collect();
+                            amounts.sort_by_key(|(token, _)| *token);
+                            amounts.iter()
+                                .map(|(token, amount)| format!("{} {}", amount, token))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        })
+                        .unwrap_or_else(|| "N/A".to_string());
+                    output.push_str(&format!("  - {} [{}]: {} ({})\n", proposal.title(), epoch, status, amounts));
+                }
+                Ok(output)
+            },
+            Command::ListProposals { epoch_name, status, team, unpaid_only, page } => {
+                let epoch_id = match &epoch_name {
+                    Some(name) => self.get_epoch_id_by_name(name)
+                        .ok_or_else(|| format!("Epoch not found: {}", name))?,
+                    None => self.state.current_epoch().ok_or(BudgetSystemError::NoActiveEpoch)?,
+                };
+                let team_id = match &team {
+                    Some(name) => Some(self.get_team_id_by_name(name).ok_or_else(|| self.team_not_found(name))?),
+                    None => None,
+                };
+                let epoch = self.state.get_epoch(&epoch_id).map_or("Unknown", |e| e.name());
+                let mut label = match status {
+                    Some(status) => format!("{:?}", status),
+                    None if unpaid_only => "Unpaid".to_string(),
+                    None => "All".to_string(),
+                };
+                if unpaid_only && status.is_some_and(|s| s != ProposalListFilter::Unpaid) {
+                    label.push_str(" unpaid");
+                }
+                let scope = match team_id.and_then(|id| self.state.get_team(&id)) {
+                    Some(team) => format!("epoch {} for {}", epoch, team.name()),
+                    None => format!("epoch {}", epoch),
+                };
+                let page = page.unwrap_or(1);
+
+                let (proposals, total) = self.list_proposals_paginated(Some(epoch_id), status, team_id, unpaid_only, page, PROPOSAL_PAGE_SIZE);
+                if total == 0 {
+                    return Ok(format!("No {} proposals in {}", label.to_lowercase(), scope));
+                }
+                let pages = total.div_ceil(PROPOSAL_PAGE_SIZE);
+                if page == 0 || page > pages {
+                    return Err(format!("Page {} is out of range (1-{})", page, pages).into());
+                }
+
+                let mut output = format!("{} proposals in {} ({} total):\n\n", label, scope, total);
+                for (index, proposal) in proposals.iter().enumerate() {
+                    let status = match proposal.resolution() {
+                        Some(resolution) => format!("{:?}", resolution),
+                        None => format!("{:?}", proposal.status()),
+                    };
+                    output.push_str(&format!("{}. {} ({})\n", (page - 1) * PROPOSAL_PAGE_SIZE + index + 1, proposal.title(), status));
+                    if let Some(url) = proposal.url() {
+                        output.push_str(&format!("   URL: {}\n", url));
+                    }
+                    if let Some(details) = proposal.budget_request_details() {
+                        if let Some(team) = details.team().and_then(|id| self.state.get_team(&id)) {
+                            output.push_str(&format!("   Team: {}\n", team.name()));
+                        }
+                        let mut amounts: Vec<_> = details.request_amounts().iter().collect();
+                        amounts.sort_by_key(|(token, _)| *token);
+                        if !amounts.is_empty() {
+                            let amounts = amounts.iter()
+                                .map(|(token, amount)| format!("{} {}", amount, token))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            output.push_str(&format!("   Requested: {}\n", amounts));
+                        }
+                        if proposal.is_approved() {
+                            output.push_str(&format!("   Paid: {}\n", if details.is_paid() { "yes" } else { "no" }));
+                        }
+                    }
+                    if proposal.is_actionable() {
+                        output.push_str(&format!("   Open for {} days\n", self.days_open(proposal)));
+                    } else if let Some(duration) = proposal.duration() {
+                        output.push_str(&format!("   Resolved after {} days\n", duration.num_days()));
+                    }
+                }
+                output.push_str(&format!("\nPage {} of {}", page, pages));
+                Ok(output)
+            },
+            Command::PrintTeamPaymentHistory { team_name } => {
+                let team_id = self.get_team_id_by_name(&team_name)
+                    .ok_or_else(|| self.team_not_found(&team_name))?;
+                let history = self.get_team_payment_history(team_id);
+                if history.is_empty() {
+                    return Ok(format!("No approved budget requests found for team {}", team_name));
+                }
+
+                let mut output = format!("Payment history for team {}:\n\n", team_name);
+                output.push_str("| Epoch | Proposal | Amounts | Type | Paid | Transaction |\n");
+                output.push_str("|-------|----------|---------|------|------|-------------|\n");
+                for record in history {
+                    let mut amounts: Vec<_> = record.amounts.iter().collect();
+                    amounts.sort_by_key(|(token, _)| *token);
+                    let amounts = amounts.iter()
+                        .map(|(token, amount)| format!("{} {}", amount, token))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {} | {} | {} |\n",
+                        record.epoch_name,
+                        record.proposal_title,
+                        amounts,
+                        if record.is_loan { "Loan" } else { "Grant" },
+                        record.payment_date.map_or("Unpaid".to_string(), |d| d.format("%Y-%m-%d").to_string()),
+                        record.payment_tx.as_deref().unwrap_or("-"),
+                    ));
+                }
+                Ok(output)
+            },
+            Command::GenerateTeamPerformanceReport { team_name, output_path } => {
+                let path = self.save_team_performance_report(&team_name, output_path.as_deref())?;
+                Ok(format!("Generated team performance report at: {:?}", path))
+            },
+            Command::RestoreBackup { timestamp, confirm } => {
+                self.restore_backup(timestamp.as_deref(), confirm)
+            },
+            Command::ListEpochs { include_closed } => {
+                Ok(self.list_epochs(include_closed))
+            },
+            Command::ListTeams { status_filter } => {
+                let teams = match &status_filter {
+                    Some(status) => self.get_teams_by_status(status)?,
+                    None => {
+                        let mut teams: Vec<&Team> = self.state.current_state().teams().values().collect();
+                        teams.sort_by(|a, b| a.name().cmp(b.name()));
+                        teams
+                    },
+                };
+                if teams.is_empty() {
+                    return Ok(match status_filter {
+                        Some(status) => format!("No {} teams found", status.to_lowercase()),
+                        None => "No teams found".to_string(),
+                    });
+                }
+
+                let mut output = match &status_filter {
+                    Some(status) => format!("Teams ({}):\n", status.to_lowercase()),
+                    None => "Teams:\n".to_string(),
+                };
+                for team in teams {
+                    output.push_str(&format!(
+                        "  - {} ({}): {}\n",
+                        team.name(),
+                        format_team_status(team.status()),
+                        team.representative()
+                    ));
+                }
+                Ok(output)
+            },
+            Command::ExtendEpoch { epoch_name, new_end_date } => {
+                self.extend_epoch(epoch_name.as_deref(), new_end_date)?;
+                Ok(format!(
+                    "Extended epoch {} to {}",
+                    epoch_name.unwrap_or_else(|| "Active epoch".to_string()),
+                    new_end_date.format("%Y-%m-%d %H:%M UTC")
+                ))
+            },
+            Command::ShowRaffle { proposal_name } => {
+                Ok(self.show_raffle(&proposal_name)?)
+            },
+            Command::ShowDecidingTeams { proposal_name } => {
+                let deciding = self.deciding_teams_for_proposal(&proposal_name)?;
+                let mut output = format!("Deciding teams for proposal '{}':\n", proposal_name);
+                if deciding.is_empty() {
+                    output.push_str("  None\n");
+                } else {
+                    for team_name in deciding {
+                        output.push_str(&format!("  {}\n", team_name));
+                    }
+                }
+                Ok(output)
+            },
+        }
+    }
+
+    async fn execute_command_with_streaming<W: Write + Send + 'static>(
+        &mut self, 
+        command: Command, 
+        output: &mut W
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match command {
+            Command::CreateRaffle { proposal_name, block_offset, excluded_teams } => {
+                let progress_stream = self.create_raffle_with_progress(
+                    proposal_name,
+                    block_offset,
+                    excluded_teams,
+                ).await;
+                
+                pin_mut!(progress_stream);
+                
+                while let Some(progress) = progress_stream.next().await {
+                    match progress {
+                        Ok(progress) => {
+                            writeln!(output, "{}", progress.format_message())?;
+                            output.flush()?;
+                            if progress.is_complete() {
+                                break;
+                            }
+                        },
+                        Err(e) => return Err(Box::new(e)),
+                    }
+                }
+                Ok(())
+            },
+            // For commands that don't support streaming, fall back to the original implementation
+            _ => {
+                let result = self.execute_command(command).await?;
+                write!(output, "{}", result)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Utc, Duration};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+    use futures::pin_mut;
+    use crate::app_config::{RetryConfig, TelegramConfig};
+    use crate::commands::common::BudgetRequestDetailsCommand;
+    use crate::services::ethereum::MockEthereumService;
+    use tokio::time::Duration as Dur;
+
+    // Helpers
+
+    async fn create_test_budget_system(state_file: &str, initial_state: Option<BudgetSystemState>) -> BudgetSystem {
+        let config = AppConfig {
+            state_file: state_file.to_string(),
+            ipc_path: "/tmp/test_reth.ipc".to_string(),
+            rpc_url: None,
+            future_block_offset: 10,
+            eth_retry: RetryConfig { max_attempts: 1, base_delay_ms: 0, max_delay_ms: 0, jitter_ms: 0 },
+            script_file: "test_script.json".to_string(),
+            default_total_counted_seats: 7,
+            default_max_earner_seats: 5,
+            revenue_window_months: 3,
+            default_qualified_majority_threshold: 0.7,
+            default_quorum: 0.0,
+            counted_vote_points: 5,
+            uncounted_vote_points: 2,
+            abstain_points: 5,
+            state_file_warn_bytes: 10 * 1024 * 1024,
+            state_backup_count: 20,
+            require_reviewer_sign_off: false,
+            allow_vote_changes: false,
+            vote_change_window_hours: 24,
+            allow_fuzzy_match: true,
+            allow_similar_proposals: true,
+            discord_webhook_url: None,
+            epoch_budget_caps: HashMap::new(),
+            token_addresses: HashMap::new(),
+            safe_chain_id: 1,
+            telegram: TelegramConfig {
+                chat_id: "test_chat_id".to_string(),
+                token: "test_token".to_string(),
+                timezone: "UTC".to_string(),
+                chat_timezones: HashMap::new(),
+                overdue_payment_days: 30,
+                allow_absent_voter_pings: false,
+                allowed_chat_ids: Vec::new(),
+                public_read_only_commands: false,
+            },
+        };
+        let ethereum_service = Arc::new(MockEthereumService::new());
+        BudgetSystem::new(config, ethereum_service, initial_state).await.unwrap()
+    }
+
+    async fn create_active_epoch(budget_system: &mut BudgetSystem) -> Uuid {
+        let start_date = Utc::now();
+        let end_date = start_date + Duration::days(30);
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        epoch_id
+    }
+
+    async fn create_proposal_with_raffle(budget_system: &mut BudgetSystem, proposal_name: &str) -> (Uuid, Uuid) {
+        let proposal_id = budget_system.add_proposal(
+            proposal_name.to_string(),
+            None,
+            None,
+            Some(Utc::now().date_naive()),
+            Some(Utc::now().date_naive()),
+            None,
+            None
+        ).unwrap();
+    
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle(proposal_name, None, &config).unwrap();
+        budget_system.finalize_raffle(
+            raffle_id,
+            12345,
+            12355,
+            "mock_randomness".to_string()
+        ).await.unwrap();
+    
+        (proposal_id, raffle_id)
+    }
+
+    fn get_mock_service(budget_system: &BudgetSystem) -> Option<Arc<MockEthereumService>> {
+        budget_system.ethereum_service()
+            .clone() // Clone the Arc before downcasting
+            .downcast_arc::<MockEthereumService>()
+            .ok()
+    }
+
+    async fn setup_block_progression(mock_service: Arc<MockEthereumService>) {
+        let service = mock_service.clone();
+        tokio::spawn(async move {
+            for _ in 0..5 {
+                service.increment_block();
+                tokio::time::sleep(Dur::from_millis(100)).await;
+            }
+        });
+    }
+    
+    // Tests
+
+    #[tokio::test]
+    async fn test_state_management() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+
+        // Test creating a new BudgetSystem
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        
+        // Modify state
+        let epoch_id = budget_system.create_epoch("Test Epoch", Utc::now(), Utc::now() + Duration::days(30)).unwrap();
+        let team_id = budget_system.create_team("Test Team".to_string(), "Representative".to_string(), Some(vec![1000, 2000, 3000]), None).unwrap();
+
+        // Save state
+        budget_system.save_state().unwrap();
+
+        // Test loading existing state
+        let loaded_state = FileSystem::try_load_state(&state_file).unwrap();
+        let loaded_system = create_test_budget_system(&state_file, Some(loaded_state)).await;
+
+        // Verify loaded state
+        assert_eq!(loaded_system.state().epochs().len(), 1);
+        assert!(loaded_system.state().epochs().contains_key(&epoch_id));
+        assert_eq!(loaded_system.state().current_state().teams().len(), 1);
+        assert!(loaded_system.state().current_state().teams().contains_key(&team_id));
+
+        // Test loading from non-existent file (should create new system)
+        let non_existent_file = temp_dir.path().join("non_existent.json").to_str().unwrap().to_string();
+        let new_system = create_test_budget_system(&non_existent_file, None).await;
+        assert!(new_system.state().epochs().is_empty());
+        assert!(new_system.state().current_state().teams().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_epoch_management() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Test creating a new epoch
+        let start_date = Utc::now();
+        let end_date = start_date + Duration::days(30);
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        let epoch = budget_system.get_epoch(&epoch_id).unwrap();
+        assert_eq!(epoch.name(), "Test Epoch");
+        assert_eq!(epoch.start_date(), start_date);
+        assert_eq!(epoch.end_date(), end_date);
+
+        // Test activating an epoch
+        budget_system.activate_epoch(epoch_id).unwrap();
+        assert_eq!(budget_system.state().current_epoch(), Some(epoch_id));
+
+        // Test setting epoch reward
+        budget_system.set_epoch_reward("ETH", 100.0).unwrap();
+        let updated_epoch = budget_system.get_epoch(&epoch_id).unwrap();
+        assert_eq!(updated_epoch.reward("ETH").unwrap().token(), "ETH");
+        assert_eq!(updated_epoch.reward("ETH").unwrap().amount(), 100.0);
+
+        // Test creating overlapping epoch (should fail)
+        let overlapping_start = start_date + Duration::days(15);
+        let overlapping_end = end_date + Duration::days(15);
+        assert!(budget_system.create_epoch("Overlapping Epoch", overlapping_start, overlapping_end).is_err());
+
+        // Test activating an epoch when another is already active (should fail)
+        let another_epoch_id = budget_system.create_epoch("Another Epoch", end_date + Duration::days(1), end_date + Duration::days(31)).unwrap();
+        assert!(budget_system.activate_epoch(another_epoch_id).is_err());
+
+        // Ensure points are earned before closing an epoch
+        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+
+        // Close the proposal before closing the epoch
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+
+        budget_system.close_epoch(Some("Test Epoch")).unwrap();
+        let closed_epoch = budget_system.get_epoch(&epoch_id).unwrap();
+        assert!(closed_epoch.is_closed());
+        assert_eq!(budget_system.state().current_epoch(), None);
+    }
+
+    #[tokio::test]
+    async fn test_team_management() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Test creating a new team
+        let team_id = budget_system.create_team(
+            "Test Team".to_string(),
+            "Representative".to_string(),
+            Some(vec![1000, 2000, 3000]),
+            None
+        ).unwrap();
+        let team = budget_system.get_team(&team_id).unwrap();
+        assert_eq!(team.name(), "Test Team");
+        assert_eq!(team.representative(), "Representative");
+        assert!(matches!(team.status(), TeamStatus::Earner { .. }));
+
+        // Test getting team by name
+        let team_id_by_name = budget_system.get_team_id_by_name("Test Team").unwrap();
+        assert_eq!(team_id_by_name, team_id);
+
+        // Test removing a team
+        budget_system.remove_team(team_id).unwrap();
+        assert!(budget_system.get_team(&team_id).is_none());
+
+        // Test creating a team with invalid data (should fail)
+        assert!(budget_system.create_team("".to_string(), "Representative".to_string(), None, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_team() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let team_id = budget_system.create_team("Test Team".to_string(), "John Doe".to_string(), Some(vec![1000]), None).unwrap();
+
+        let updates = UpdateTeamDetails {
+            name: Some("Updated Team".to_string()),
+            representative: Some("Jane Doe".to_string()),
+            status: Some("Supporter".to_string()),
+            trailing_monthly_revenue: None,
+            address: None
+        };
+
+        budget_system.update_team(team_id, updates).unwrap();
+
+        let updated_team = budget_system.get_team(&team_id).unwrap();
+        assert_eq!(updated_team.name(), "Updated Team");
+        assert_eq!(updated_team.representative(), "Jane Doe");
+        assert!(matches!(updated_team.status(), TeamStatus::Supporter));
+    }
+
+    #[tokio::test]
+    async fn test_update_team_earner_status() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let team_id = budget_system.create_team("Test Team".to_string(), "John Doe".to_string(), Some(vec![1000]), None).unwrap();
+
+        let updates = UpdateTeamDetails {
+            name: None,
+            representative: None,
+            status: Some("Earner".to_string()),
+            trailing_monthly_revenue: Some(vec![2000, 3000, 4000]),
+            address: None,
+        };
+
+        budget_system.update_team(team_id, updates).unwrap();
+
+        let updated_team = budget_system.get_team(&team_id).unwrap();
+        if let TeamStatus::Earner { trailing_monthly_revenue } = updated_team.status() {
+            assert_eq!(trailing_monthly_revenue, &[2000, 3000, 4000]);
+        } else {
+            panic!("Expected Earner status");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_team_invalid_status() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let team_id = budget_system.create_team("Test Team".to_string(), "John Doe".to_string(), Some(vec![1000]), None).unwrap();
+
+        let updates = UpdateTeamDetails {
+            name: None,
+            representative: None,
+            status: Some("InvalidStatus".to_string()),
+            trailing_monthly_revenue: None,
+            address: None,
+        };
+
+        assert!(budget_system.update_team(team_id, updates).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_proposal_management() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Create an active epoch
+        let epoch_id = create_active_epoch(&mut budget_system).await;
+
+        // Test adding a new proposal
+        let proposal_id = budget_system.add_proposal(
+            "Test Proposal".to_string(),
+            Some("http://example.com".to_string()),
+            Some(BudgetRequestDetails::new(
+                None,
+                [("ETH".to_string(), 100.0)].iter().cloned().collect(),
+                Some(Utc::now().date_naive()),
+                Some((Utc::now() + Duration::days(30)).date_naive()),
+                Some(false),
+                None
+            ).unwrap()),
+            Some(Utc::now().date_naive()),
+            Some(Utc::now().date_naive()),
+            None,
+            None
+        ).unwrap();
+
+        let proposal = budget_system.get_proposal(&proposal_id).unwrap();
+        assert_eq!(proposal.title(), "Test Proposal");
+
+        // Test updating a proposal
+        let updates = UpdateProposalDetails {
+            title: Some("Updated Proposal".to_string()),
+            url: None,
+            budget_request_details: None,
+            announced_at: None,
+            published_at: None,
+            resolved_at: None,
+            category: None,
+        };
+        budget_system.update_proposal("Test Proposal", updates).unwrap();
+        let updated_proposal = budget_system.get_proposal(&proposal_id).unwrap();
+        assert_eq!(updated_proposal.title(), "Updated Proposal");
+
+        // Test closing a proposal
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        let closed_proposal = budget_system.get_proposal(&proposal_id).unwrap();
+        assert!(closed_proposal.is_closed());
+        assert_eq!(closed_proposal.resolution(), Some(Resolution::Approved));
+
+        // Test getting proposals for an epoch
+        let epoch_proposals = budget_system.get_proposals_for_epoch(epoch_id);
+        assert_eq!(epoch_proposals.len(), 1);
+        assert_eq!(epoch_proposals[0].id(), proposal_id);
+
+        // Test adding a proposal without an active epoch (should fail)
+        budget_system.close_epoch(None).unwrap();
+        assert!(budget_system.add_proposal(
+            "Failed Proposal".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None
+        ).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_raffle_management() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Create an active epoch and a proposal
+        let _epoch_id = create_active_epoch(&mut budget_system).await;
+        let _proposal_id = budget_system.add_proposal(
+            "Test Proposal".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None
+        ).unwrap();
+
+        // Create some teams
+        let team_id1 = budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        let team_id2 = budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), None, None).unwrap();
+
+        // Test preparing a raffle
+        let config = budget_system.config().clone();
+        let (raffle_id, tickets) = budget_system.prepare_raffle(
+            "Test Proposal",
+            None,
+            &config
+        ).unwrap();
+        assert!(!tickets.is_empty());
+
+        // Test finalizing a raffle
+        let raffle = budget_system.finalize_raffle(
+            raffle_id,
+            12345,
+            12355,
+            "mock_randomness".to_string()
+        ).await.unwrap();
+        assert!(raffle.result().is_some());
+
+        // Test importing a predefined raffle
+        let imported_raffle_id = budget_system.import_predefined_raffle(
+            "Test Proposal",
+            vec!["Team 1".to_string()],
+            vec!["Team 2".to_string()],
+            1,
+            1
+        ).unwrap();
+        let imported_raffle = budget_system.get_raffle(&imported_raffle_id).unwrap();
+        assert_eq!(imported_raffle.result().unwrap().counted(), &[team_id1]);
+        assert_eq!(imported_raffle.result().unwrap().uncounted(), &[team_id2]);
+
+        // Test importing a historical raffle
+        let (_historical_raffle_id, historical_raffle) = budget_system.import_historical_raffle(
+            "Test Proposal",
+            12345,
+            12355,
+            Some(vec!["Team 1".to_string(), "Team 2".to_string()]),
+            None,
+            Some(2),
+            Some(1)
+        ).await.unwrap();
+        assert_eq!(historical_raffle.config().initiation_block(), 12345);
+        assert_eq!(historical_raffle.config().randomness_block(), 12355);
+        assert!(historical_raffle.result().is_some());
+
+        // Test raffle exclusions
+        let excluded_raffle_id = budget_system.import_predefined_raffle(
+            "Test Proposal",
+            vec!["Team 1".to_string()],
+            vec![],
+            1,
+            1
+        ).unwrap();
+        let excluded_raffle = budget_system.get_raffle(&excluded_raffle_id).unwrap();
+        assert_eq!(excluded_raffle.result().unwrap().counted(), &[team_id1]);
+        assert!(excluded_raffle.result().unwrap().uncounted().is_empty());
+
+        // Test invalid raffle creation (non-existent proposal)
+        assert!(budget_system.prepare_raffle(
+            "Non-existent Proposal",
+            None,
+            &config
+        ).is_err());
+
+        // Test invalid raffle finalization (non-existent raffle)
+        assert!(budget_system.finalize_raffle(
+            Uuid::new_v4(),
+            12345,
+            12355,
+            "mock_randomness".to_string()
+        ).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_vote_management() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        let proposal_id = budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+
+        // Create teams
+        let team_id1 = budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        let team_id2 = budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), Some(vec![2000]), None).unwrap();
+
+        // Prepare and finalize raffle
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
+        let mock_randomness = "mock_randomness".to_string();
+        budget_system.finalize_raffle(raffle_id, 12345, 12355, mock_randomness).await.unwrap();
+
+        // Create and process a formal vote
+        let formal_vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(formal_vote_id, vec![(team_id1, VoteChoice::Yes), (team_id2, VoteChoice::No)]).unwrap();
+
+        // Test closing a vote
+        let vote_result = budget_system.close_vote(formal_vote_id).unwrap();
+        let closed_vote = budget_system.get_vote(&formal_vote_id).unwrap();
+        assert!(closed_vote.is_closed());
+        assert!(matches!(closed_vote.result(), Some(VoteResult::Formal { .. })));
+
+        // Verify vote result
+        if let Some(VoteResult::Formal { counted, uncounted, passed }) = closed_vote.result() {
+            assert_eq!(counted.yes() + counted.no(), 2);
+            assert_eq!(uncounted.yes() + uncounted.no(), 0);
+            assert_eq!(*passed, vote_result);
+        } else {
+            panic!("Expected Formal vote result");
+        }
+
+        // Test error case: closing an already closed vote
+        assert!(budget_system.close_vote(formal_vote_id).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reporting() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+    
+        let epoch_id = create_active_epoch(&mut budget_system).await;
+        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        
+        // Create proposal and raffle
+        let proposal_id = budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
+        
+        // Finalize raffle with the team included
+        let mock_randomness = "mock_randomness".to_string();
+        budget_system.finalize_raffle(raffle_id, 12345, 12355, mock_randomness).await.unwrap();
+    
+        // Create and process a vote
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+    
+        // Generate reports
+        let team_report = budget_system.print_team_report();
+        assert!(team_report.contains("Test Team"));
+    
+        let epoch_state = budget_system.print_epoch_state().unwrap();
+        assert!(epoch_state.contains("Test Proposal"));
+    
+        let proposal_report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(proposal_report.contains("Test Proposal"));
+    
+        let point_report = budget_system.generate_point_report(None).unwrap();
+        assert!(point_report.contains("Test Team"));
+    
+        // Close proposal before closing epoch
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+    
+        budget_system.close_epoch(None).unwrap();
+        budget_system.generate_end_of_epoch_report(&budget_system.get_epoch(&epoch_id).unwrap().name(), ReportFormat::Markdown).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_integration() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Create and activate an epoch
+        let epoch_id = create_active_epoch(&mut budget_system).await;
+        budget_system.set_epoch_reward("ETH", 1000.0).unwrap();
+
+        // Create teams
+        let team_id1 = budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        let team_id2 = budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), Some(vec![2000]), None).unwrap();
+        let team_id3 = budget_system.create_team("Team 3".to_string(), "Rep 3".to_string(), None, None).unwrap();
+
+        // Create a proposal
+        let proposal_id = budget_system.add_proposal(
+            "Test Proposal".to_string(),
+            Some("http://example.com".to_string()),
+            Some(BudgetRequestDetails::new(
+                Some(team_id1),
+                [("ETH".to_string(), 100.0)].iter().cloned().collect(),
+                Some(Utc::now().date_naive()),
+                Some((Utc::now() + Duration::days(30)).date_naive()),
+                Some(false),
+                None,
+            ).unwrap()),
+            Some(Utc::now().date_naive()),
+            Some(Utc::now().date_naive()),
+            None,
+            None
+        ).unwrap();
+
+        // Conduct a raffle
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
+        budget_system.finalize_raffle(raffle_id, 12345, 12355, "mock_randomness".to_string()).await.unwrap();
+        
+        // Generate epoch report
+        let epoch_state = budget_system.print_epoch_state().unwrap();
+        assert!(epoch_state.contains("Test Proposal"));
+
+        // Create and process a vote
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![
+            (team_id1, VoteChoice::Yes),
+            (team_id2, VoteChoice::Yes),
+            (team_id3, VoteChoice::No)
+        ]).unwrap();
+        let vote_result = budget_system.close_vote(vote_id).unwrap();
+        
+        // Verify the actual vote result
+        let vote = budget_system.get_vote(&vote_id).unwrap();
+        if let Some(VoteResult::Formal { passed, .. }) = vote.result() {
+            assert_eq!(*passed, vote_result);
+        } else {
+            panic!("Expected Formal vote result");
+        }
+
+        // Close the proposal
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        
+
+        // Close the epoch
+        budget_system.close_epoch(None).unwrap();
+
+        // Generate other report
+        let team_report = budget_system.print_team_report();
+        let proposal_report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        let point_report = budget_system.generate_point_report(Some("Test Epoch")).unwrap();
+        budget_system.generate_end_of_epoch_report(&budget_system.get_epoch(&epoch_id).unwrap().name(), ReportFormat::Markdown).unwrap();
+
+        // Verify the integrations
+        assert!(team_report.contains("Team 1") && team_report.contains("Team 2") && team_report.contains("Team 3"));
+        assert!(proposal_report.contains("Approved"));
+        assert!(point_report.contains("Team 1") && point_report.contains("Team 2") && point_report.contains("Team 3"));
+
+        // Verify the final state
+        let closed_epoch = budget_system.get_epoch(&epoch_id).unwrap();
+        assert!(closed_epoch.is_closed());
+        let closed_proposal = budget_system.get_proposal(&proposal_id).unwrap();
+        assert!(closed_proposal.is_closed());
+        assert_eq!(closed_proposal.resolution(), Some(Resolution::Approved));
+    }
+
+    #[tokio::test]
+    async fn test_error_handling_and_edge_cases() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Test handling of non-existent entities
+        assert!(budget_system.get_team(&Uuid::new_v4()).is_none());
+        assert!(budget_system.get_proposal(&Uuid::new_v4()).is_none());
+        assert!(budget_system.get_epoch(&Uuid::new_v4()).is_none());
+        assert!(budget_system.get_raffle(&Uuid::new_v4()).is_none());
+        assert!(budget_system.get_vote(&Uuid::new_v4()).is_none());
+
+        // Test behavior with empty state
+        assert!(budget_system.print_epoch_state().is_err());
+        assert!(budget_system.generate_point_report(None).is_err());
+
+        // Test invalid inputs
+        assert!(budget_system.create_epoch("", Utc::now(), Utc::now()).is_err());
+        assert!(budget_system.create_team("".to_string(), "Rep".to_string(), None, None).is_err());
+        assert!(budget_system.set_epoch_reward("ETH", -100.0).is_err());
+
+        // Test overlapping epochs
+        let epoch1_id = budget_system.create_epoch("Epoch 1", Utc::now(), Utc::now() + Duration::days(30)).unwrap();
+        assert!(budget_system.create_epoch("Epoch 2", Utc::now() + Duration::days(15), Utc::now() + Duration::days(45)).is_err());
+
+        // Test activating multiple epochs
+        budget_system.activate_epoch(epoch1_id).unwrap();
+        let epoch2_id = budget_system.create_epoch("Epoch 2", Utc::now() + Duration::days(31), Utc::now() + Duration::days(61)).unwrap();
+        assert!(budget_system.activate_epoch(epoch2_id).is_err());
+
+        // Test closing an epoch with open proposals
+        let _proposal_id = budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        assert!(budget_system.close_epoch(None).is_err());
+
+        // Test updating a non-existent proposal
+        let updates = UpdateProposalDetails {
+            title: Some("Updated Title".to_string()),
+            url: None,
+            budget_request_details: None,
+            announced_at: None,
+            published_at: None,
+            resolved_at: None,
+            category: None,
+        };
+        assert!(budget_system.update_proposal("Non-existent Proposal", updates).is_err());
+
+        // Test creating a raffle for a non-existent proposal
+        let config = budget_system.config().clone();
+        assert!(budget_system.prepare_raffle("Non-existent Proposal", None, &config).is_err());
+
+        // Test casting votes for a non-existent vote
+        assert!(budget_system.cast_votes(Uuid::new_v4(), vec![(Uuid::new_v4(), VoteChoice::Yes)]).is_err());
+
+        // Test closing a non-existent vote
+        assert!(budget_system.close_vote(Uuid::new_v4()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ethereum_service_interaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Test successful interactions
+        assert_eq!(budget_system.get_current_block().await.unwrap(), 12345);
+        assert_eq!(budget_system.get_randomness(12355).await.unwrap(), "mock_randomness_for_block_12355");
+        
+        let (init_block, rand_block, randomness) = budget_system.get_raffle_randomness().await.unwrap();
+        assert_eq!(init_block, 12345);
+        assert_eq!(rand_block, 12355);
+        assert_eq!(randomness, "mock_randomness_for_block_12355");
+
+        // Test raffle creation with Ethereum service interaction
+        create_active_epoch(&mut budget_system).await;
+        budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
+        
+        let raffle = budget_system.finalize_raffle(raffle_id, 12345, 12355, "mock_randomness".to_string()).await.unwrap();
+        
+        assert_eq!(raffle.config().initiation_block(), 12345);
+        assert_eq!(raffle.config().randomness_block(), 12355);
+        assert_eq!(raffle.config().block_randomness(), "mock_randomness");
+    }
+
+    #[tokio::test]
+    async fn test_raffle_creation_stream() {
+        use futures::pin_mut;
+        use std::time::Duration;
+        use std::sync::Arc;
+
+        // Create mock service
+        let mock_service = Arc::new(MockEthereumService::new());        
+
+        let temp_dir = TempDir::new().unwrap();
+        
+        // Create budget system with our mock service
+        let mut budget_system = {
+            let config = AppConfig {
+                state_file: temp_dir.path().join("test_state.json").to_str().unwrap().to_string(),
+                ipc_path: "/tmp/test_reth.ipc".to_string(),
+                rpc_url: None,
+                future_block_offset: 2, // Small offset for testing
+                eth_retry: RetryConfig { max_attempts: 1, base_delay_ms: 0, max_delay_ms: 0, jitter_ms: 0 },
+                script_file: "test_script.json".to_string(),
+                default_total_counted_seats: 7,
+                default_max_earner_seats: 5,
+                revenue_window_months: 3,
+                default_qualified_majority_threshold: 0.7,
+                default_quorum: 0.0,
+                counted_vote_points: 5,
+                uncounted_vote_points: 2,
+                abstain_points: 5,
+                state_file_warn_bytes: 10 * 1024 * 1024,
+                state_backup_count: 20,
+                require_reviewer_sign_off: false,
+                allow_vote_changes: false,
+                vote_change_window_hours: 24,
+                allow_fuzzy_match: true,
+                allow_similar_proposals: true,
+                discord_webhook_url: None,
+                epoch_budget_caps: HashMap::new(),
+                token_addresses: HashMap::new(),
+                safe_chain_id: 1,
+                telegram: TelegramConfig {
+                    chat_id: "test_chat_id".to_string(),
+                    token: "test_token".to_string(),
+                    timezone: "UTC".to_string(),
+                    chat_timezones: HashMap::new(),
+                    overdue_payment_days: 30,
+                    allow_absent_voter_pings: false,
+                    allowed_chat_ids: Vec::new(),
+                    public_read_only_commands: false,
+                },
+            };
+            BudgetSystem::new(config, mock_service, None).await.unwrap()
+        };
+        
+        // Setup block progression before executing command
+        if let Some(mock_service) = get_mock_service(&budget_system) {
+            setup_block_progression(mock_service).await;
+        }
+
+        // Setup test data
+        create_active_epoch(&mut budget_system).await;
+        
+        // Add test teams
+        budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), Some(vec![2000]), None).unwrap();
+        
+        budget_system.add_proposal(
+            "Test Proposal".to_string(),
+            None,
+            None,
+            Some(Utc::now().date_naive()),
+            Some(Utc::now().date_naive()),
+            None,
+            None
+        ).unwrap();
+
+        // Create and pin the stream
+        let progress_stream = budget_system.create_raffle_with_progress(
+            "Test Proposal".to_string(),
+            Some(2), // Small offset for testing
+            None
+        ).await;
+        pin_mut!(progress_stream);
+
+        // Collect updates with longer timeout
+        let mut updates = Vec::new();
+        while let Some(progress) = tokio::time::timeout(
+            Duration::from_secs(10), // Increased timeout
+            progress_stream.next()
+        ).await.unwrap() {
+            let progress = progress.unwrap();
+            println!("Received progress update: {:?}", progress);
+            updates.push(progress);
+            
+            if matches!(updates.last().unwrap(), RaffleProgress::Completed { .. }) {
+                break;
+            }
+        }
+
+        // Verify states
+        assert!(!updates.is_empty(), "Should have received updates");
+        assert!(matches!(updates[0], RaffleProgress::Preparing { .. }), "First update should be Preparing");
+        
+        let has_waiting = updates.iter().any(|p| matches!(p, RaffleProgress::WaitingForBlock { .. }));
+        assert!(has_waiting, "Should have WaitingForBlock state");
+        
+        let has_randomness = updates.iter().any(|p| matches!(p, RaffleProgress::RandomnessAcquired { .. }));
+        assert!(has_randomness, "Should have RandomnessAcquired state");
+        
+        assert!(matches!(updates.last().unwrap(), RaffleProgress::Completed { .. }), "Should end with Completed state");
+
+        if let RaffleProgress::Completed { counted, uncounted, .. } = updates.last().unwrap() {
+            assert!(!counted.is_empty() || !uncounted.is_empty(), "Raffle should contain teams");
+            println!("Final raffle result - Counted teams: {:?}, Uncounted teams: {:?}", counted, uncounted);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_raffle_with_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Setup required state
+        create_active_epoch(&mut budget_system).await;
+        budget_system.add_proposal(
+            "Test Proposal".to_string(),
+            None,
+            None,
+            Some(Utc::now().date_naive()),
+            Some(Utc::now().date_naive()),
+            None,
+            None
+        ).unwrap();
+
+        // Add some teams
+        budget_system.create_team("Team1".to_string(), "Rep1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team2".to_string(), "Rep2".to_string(), Some(vec![2000]), None).unwrap();
+
+        // Setup block progression before executing command
+        if let Some(mock_service) = get_mock_service(&budget_system) {
+            setup_block_progression(mock_service).await;
+        }
+
+        // Create the progress stream and collect updates in their own scope
+        let updates = {
+            let progress_stream = budget_system.create_raffle_with_progress(
+                "Test Proposal".to_string(),
+                Some(1), // Small offset for testing
+                None,
+            ).await;
+
+            let mut updates = Vec::new();
+            pin_mut!(progress_stream);
+            
+            while let Some(progress) = progress_stream.next().await {
+                match progress {
+                    Ok(update) => {
+                        updates.push(update.clone());
+                        if matches!(update, RaffleProgress::Completed { .. }) {
+                            break;
+                        }
+                    },
+                    Err(e) => panic!("Unexpected error: {}", e),
+                }
+            }
+            updates
+        }; // progress_stream is dropped here, releasing the mutable borrow
+
+        // Now we can borrow budget_system again
+        
+        // Verify progress sequence
+        assert!(matches!(updates[0], RaffleProgress::Preparing { .. }));
+        assert!(matches!(updates[1], RaffleProgress::WaitingForBlock { .. }));
+        assert!(matches!(updates[2], RaffleProgress::RandomnessAcquired { .. }));
+        assert!(matches!(updates[3], RaffleProgress::Completed { .. }));
+
+        // Verify final state
+        if let RaffleProgress::Completed { ref counted, ref uncounted, .. } = updates[3] {
+            assert_eq!(counted.len() + uncounted.len(), 2); // All teams should be assigned
+        } else {
+            panic!("Final update should be Completed");
+        }
+
+        // Verify raffle was created in system
+        assert_eq!(budget_system.state().raffles().len(), 1);
+    }
+
+    // Test error cases
+    #[tokio::test]
+    async fn test_create_raffle_with_progress_invalid_proposal() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Setup block progression before executing command
+        if let Some(mock_service) = get_mock_service(&budget_system) {
+            setup_block_progression(mock_service).await;
+        }
+
+        let progress_stream = budget_system.create_raffle_with_progress(
+            "NonExistent".to_string(),
+            None,
+            None,
+        ).await;
+
+        pin_mut!(progress_stream);
+        
+        // Should fail on first update
+        let first_update = progress_stream.next().await.unwrap();
+        assert!(first_update.is_err());
+        assert_eq!(
+            first_update.unwrap_err(),
+            RaffleCreationError::ProposalNotFound("NonExistent".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_raffle_with_progress_error_variants() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+
+        // Active epoch but no teams to draw from
+        {
+            let progress_stream = budget_system.create_raffle_with_progress(
+                "Test Proposal".to_string(), None, None
+            ).await;
+            pin_mut!(progress_stream);
+            let err = progress_stream.next().await.unwrap().unwrap_err();
+            assert_eq!(err, RaffleCreationError::InsufficientTeams);
+        }
+        assert!(budget_system.state().raffles().is_empty());
+
+        // No active epoch
+        budget_system.state.set_current_epoch(None);
+        {
+            let progress_stream = budget_system.create_raffle_with_progress(
+                "Test Proposal".to_string(), None, None
+            ).await;
+            pin_mut!(progress_stream);
+            let err = progress_stream.next().await.unwrap().unwrap_err();
+            assert_eq!(err, RaffleCreationError::NoActiveEpoch);
+            assert!(!err.is_transient());
+        }
+
+        assert!(RaffleCreationError::EthereumUnavailable("timeout".to_string()).is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_raffle_excluded_teams() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), None, None).unwrap();
+
+        // No raffle yet
+        assert!(budget_system.raffle_excluded_teams("Test Proposal").is_err());
+
+        let config = budget_system.config().clone();
+        budget_system.prepare_raffle("Test Proposal", Some(vec!["Team 2".to_string()]), &config).unwrap();
+
+        let excluded = budget_system.raffle_excluded_teams("Test Proposal").unwrap();
+        assert_eq!(excluded, vec!["Team 2".to_string()]);
+
+        let output = budget_system.execute_command(Command::ListExcludedTeams {
+            proposal_name: "Test Proposal".to_string(),
+        }).await.unwrap();
+        assert!(output.contains("Team 2"));
+        assert!(!output.contains("Team 1"));
+
+        assert!(budget_system.raffle_excluded_teams("Non-existent Proposal").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_unpaid_requests_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Create an epoch
+        let _epoch_id = create_active_epoch(&mut budget_system).await;
+
+        // Create a team
+        let team_id = budget_system.create_team(
+            "Test Team".to_string(),
+            "Representative".to_string(),
+            Some(vec![1000]),
+            None
+        ).unwrap();
+
+        // Create a proposal with budget request
+        let mut amounts = HashMap::new();
+        amounts.insert("ETH".to_string(), 100.0);
+        
+        let proposal_id = budget_system.add_proposal(
+            "Test Proposal".to_string(),
+            None,
+            Some(BudgetRequestDetails::new(
+                Some(team_id),
+                amounts,
+                None,
+                None,
+                Some(false),
+                Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string()),
+            ).unwrap()),
+            Some(Utc::now().date_naive()),
+            Some(Utc::now().date_naive()),
+            None,
+            None,
+        ).unwrap();
+
+        // Approve the proposal
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+
+        // Generate report
+        let output_path = temp_dir.path().join("test_report.json");
+        let result = budget_system.generate_unpaid_requests_report(
+            Some(output_path.to_str().unwrap()),
+            None,
+        );
+
+        assert!(result.is_ok());
+
+        // Verify report contents
+        let report_content = fs::read_to_string(output_path).unwrap();
+        let report: UnpaidRequestsReport = serde_json::from_str(&report_content).unwrap();
+        
+        assert_eq!(report.unpaid_requests.len(), 1);
+        assert_eq!(report.unpaid_requests[0].title, "Test Proposal");
+        assert_eq!(report.unpaid_requests[0].team_name, "Test Team");
+    }
+
+    #[tokio::test]
+    async fn test_discord_notifications_for_epoch_lifecycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let discord = Arc::new(crate::services::discord::MockDiscordService::new());
+        budget_system.set_discord_service(Some(discord.clone()));
+
+        create_active_epoch(&mut budget_system).await;
+        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Voted Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        let passed = budget_system.close_vote(vote_id).unwrap();
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+
+        let payment_tx = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e";
+        let paid_id = create_test_proposal(&mut budget_system, "Paid Proposal", vec![100.0]);
+        budget_system.close_with_reason(paid_id, &Resolution::Approved).unwrap();
+        budget_system.record_payments(payment_tx, Utc::now().date_naive(), &["Paid Proposal".to_string()], true).await.unwrap();
+        budget_system.close_epoch(None).unwrap();
+
+        // Notifications are sent from spawned tasks
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        let sent = discord.sent();
+        let events: Vec<&str> = sent.iter().map(|p| p.content.as_str()).collect();
+        assert_eq!(events, vec!["Epoch activated", "Raffle completed", "Vote closed", "Payment recorded", "Epoch closed"]);
+
+        let embed = |i: usize| &sent[i].embeds.as_ref().unwrap()[0];
+        assert_eq!(embed(0).title, "Test Epoch");
+        assert_eq!(embed(1).title, "Voted Proposal");
+        assert_eq!(embed(2).color, if passed { COLOR_GREEN } else { COLOR_RED });
+        assert_eq!(embed(3).title, "Paid Proposal");
+        assert_eq!(embed(3).fields[0].value, payment_tx);
+        assert_eq!(embed(4).title, "Test Epoch");
+    }
+
+    #[tokio::test]
+    async fn test_discord_rejected_vote_is_red() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let discord = Arc::new(crate::services::discord::MockDiscordService::new());
+        budget_system.set_discord_service(Some(discord.clone()));
+
+        create_active_epoch(&mut budget_system).await;
+        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Rejected Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::No)]).unwrap();
+        assert!(!budget_system.close_vote(vote_id).unwrap());
+
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        let sent = discord.sent();
+        let vote_closed = sent.iter().find(|p| p.content == "Vote closed").unwrap();
+        let embed = &vote_closed.embeds.as_ref().unwrap()[0];
+        assert_eq!(embed.color, COLOR_RED);
+        assert_eq!(embed.fields[0].value, "Rejected");
+    }
+
+    #[tokio::test]
+   async fn test_record_payments_success() {
+       let temp_dir = TempDir::new().unwrap();
+       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+ 
+       let mut budget_system = create_test_budget_system(&state_file, None).await;
+    
+       // Create test epoch and activate it
+       let start_date = Utc::now();
+       let end_date = start_date + Duration::days(30);
+       let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+       budget_system.activate_epoch(epoch_id).unwrap();
+       
+       // Create test proposals with budget requests
+       let proposal1_id = create_test_proposal(&mut budget_system, "Proposal1", vec![1000.0]);
+       let proposal2_id = create_test_proposal(&mut budget_system, "Proposal2", vec![2000.0]);
+       
+       // Approve the proposals
+       budget_system.close_with_reason(proposal1_id, &Resolution::Approved).unwrap();
+       budget_system.close_with_reason(proposal2_id, &Resolution::Approved).unwrap();
+
+       // Record payments
+       let result = budget_system.record_payments(
+           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
+           Utc::now().date_naive(),
+           &vec!["Proposal1".to_string(), "Proposal2".to_string()],
+           false,
+       ).await;
+
+       assert!(result.is_ok());
+       
+       // Verify payments recorded
+       let proposal1 = budget_system.get_proposal(&proposal1_id).unwrap();
+       let proposal2 = budget_system.get_proposal(&proposal2_id).unwrap();
+       
+       assert!(proposal1.budget_request_details().unwrap().is_paid());
+       assert!(proposal2.budget_request_details().unwrap().is_paid());
+   }
+
+   #[tokio::test]
+   async fn test_record_payments_future_date() {
+       let temp_dir = TempDir::new().unwrap();
+       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+ 
+       let mut budget_system = create_test_budget_system(&state_file, None).await;
+    
+       
+       let future_date = Utc::now().date_naive() + Duration::days(1);
+       
+       let result = budget_system.record_payments(
+           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
+           future_date,
+           &vec!["Proposal1".to_string()],
+           false,
+       ).await;
+
+       assert!(result.is_err());
+       assert!(result.unwrap_err().to_string().contains("future"));
+   }
+
+   #[tokio::test]
+   async fn test_record_payments_non_existent_proposal() {
+       let temp_dir = TempDir::new().unwrap();
+       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+ 
+       let mut budget_system = create_test_budget_system(&state_file, None).await;
+    
+       let result = budget_system.record_payments(
+           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
+           Utc::now().date_naive(),
+           &vec!["NonExistentProposal".to_string()],
+           false,
+       ).await;
+
+       assert!(result.is_err());
+       assert!(result.unwrap_err().to_string().contains("not found"));
+   }
+
+   #[tokio::test]
+   async fn test_record_payments_not_approved() {
+       let temp_dir = TempDir::new().unwrap();
+       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+    
+       let mut budget_system = create_test_budget_system(&state_file, None).await;
+       // Create test epoch and proposal but don't approve it
+       let _epoch_id = create_test_epoch(&mut budget_system);
+       let _proposal_id = create_test_proposal(&mut budget_system, "Proposal1", vec![1000.0]);
+
+       let result = budget_system.record_payments(
+           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
+           Utc::now().date_naive(),
+           &vec!["Proposal1".to_string()],
+           false,
+       ).await;
+
+       assert!(result.is_err());
+       assert!(result.unwrap_err().to_string().contains("not approved"));
+   }
+
+   #[tokio::test]
+   async fn test_record_payments_already_paid() {
+       let temp_dir = TempDir::new().unwrap();
+       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+    
+       let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+       // Create and approve proposal
+       let _epoch_id = create_test_epoch(&mut budget_system);
+       let proposal_id = create_test_proposal(&mut budget_system, "Proposal1", vec![1000.0]);
+       budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+
+       // Record payment first time
+       budget_system.record_payments(
+           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
+           Utc::now().date_naive(),
+           &vec!["Proposal1".to_string()],
+           false,
+       ).await.unwrap();
+
+       // Try to record payment second time
+       let result = budget_system.record_payments(
+           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
+           Utc::now().date_naive(),
+           &vec!["Proposal1".to_string()],
+           false,
+       ).await;
+
+       assert!(result.is_err());
+       assert!(result.unwrap_err().to_string().contains("already paid"));
+   }
+
+   // Helper functions
+
+   fn create_test_epoch(budget_system: &mut BudgetSystem) -> Uuid {
+       let start_date = Utc::now();
+       let end_date = start_date + Duration::days(30);
+       let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+       budget_system.activate_epoch(epoch_id).unwrap();
+       epoch_id
+   }
+
+   fn create_test_proposal(budget_system: &mut BudgetSystem, name: &str, amounts: Vec<f64>) -> Uuid {
+       let mut request_amounts = HashMap::new();
+       for (i, amount) in amounts.iter().enumerate() {
+           request_amounts.insert(format!("ETH{}", i), *amount);
+       }
+       
+       let budget_details = BudgetRequestDetails::new(
+           None,
+           request_amounts,
+           Some(Utc::now().date_naive()),
+           Some((Utc::now() + Duration::days(30)).date_naive()),
+           Some(false),
+           Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string())
+       ).unwrap();
+
+       budget_system.add_proposal(
+           name.to_string(),
+           Some("http://example.com".to_string()),
+           Some(budget_details),
+           Some(Utc::now().date_naive()),
+           Some(Utc::now().date_naive()),
+           None,
+           None
+       ).unwrap()
+   }
+
+   #[tokio::test]
+    async fn test_generate_epoch_payments_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Create and setup epoch
+        let start_date = Utc::now();
+        let end_date = start_date + Duration::days(30);
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.set_epoch_reward("ETH", 1000.0).unwrap();
+
+        // Add team with payment address
+        let team_id = budget_system.create_team(
+            "Test Team".to_string(),
+            "Representative".to_string(),
+            Some(vec![1000]),
+            Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string())
+        ).unwrap();
+
+        // Create a proposal and setup voting to generate some team rewards
+        let proposal_id = budget_system.add_proposal(
+            "Test Proposal".to_string(),
+            None,
+            None,
+            Some(Utc::now().date_naive()),
+            Some(Utc::now().date_naive()),
+            None,
+            None
+        ).unwrap();
+
+        // Create and complete raffle
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
+        budget_system.finalize_raffle(
+            raffle_id,
+            12345,
+            12355,
+            "mock_randomness".to_string()
+        ).await.unwrap();
+
+        // Create and process vote
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+
+        // Close proposal and epoch
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        budget_system.close_epoch(None).unwrap();
+
+        // Generate report
+        let report = budget_system.generate_epoch_payments_report("Test Epoch", None, ReportFormat::Json).unwrap();
+        let parsed: EpochPaymentsReport = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(parsed.epoch_name, "Test Epoch");
+        assert_eq!(parsed.total_rewards, vec![("ETH".to_string(), 1000.0)]);
+        assert_eq!(parsed.payments.len(), 1);
+        assert_eq!(parsed.payments[0].team_name, "Test Team");
+        assert!(parsed.payments[0].default_payment_address.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_recompute_epoch_rewards() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        budget_system.set_epoch_reward("ETH", 1000.0).unwrap();
+        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+
+        // Recomputing an open epoch is not allowed
+        assert!(budget_system.recompute_epoch_rewards("Test Epoch", true).is_err());
+
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        budget_system.close_epoch(None).unwrap();
+
+        let original = budget_system.get_epoch(&epoch_id).unwrap().team_rewards().clone();
+        let report = budget_system.recompute_epoch_rewards("Test Epoch", false).unwrap();
+        assert!(report.contains("match"));
+
+        // Simulate a manual edit of the stored rewards
+        budget_system.state.get_epoch_mut(&epoch_id).unwrap()
+            .set_team_reward(team_id, 50.0, HashMap::from([("ETH".to_string(), 500.0)])).unwrap();
+
+        let preview = budget_system.recompute_epoch_rewards("Test Epoch", false).unwrap();
+        assert!(preview.contains("Test Team: 50.00% (500.00 ETH) -> 100.00% (1000.00 ETH)"));
+        assert_eq!(budget_system.get_epoch(&epoch_id).unwrap().team_rewards().get(&team_id).unwrap().amount("ETH"), 500.0);
+
+        let applied = budget_system.execute_command(Command::RecomputeEpochFromVotes {
+            epoch_name: "Test Epoch".to_string(),
+            confirm: true,
+        }).await.unwrap();
+        assert!(applied.contains("Updated 1 team reward(s)"));
+        assert_eq!(budget_system.get_epoch(&epoch_id).unwrap().team_rewards(), &original);
+    }
+
+    #[tokio::test]
+    async fn test_get_team_absence_streaks() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let team1 = budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        let team2 = budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), None, None).unwrap();
+        budget_system.create_team("Team 3".to_string(), "Rep 3".to_string(), None, None).unwrap();
+
+        let now = Utc::now();
+        let epoch1 = budget_system.create_epoch("Epoch 1", now - Duration::days(60), now - Duration::days(31)).unwrap();
+        let epoch2 = budget_system.create_epoch("Epoch 2", now - Duration::days(30), now + Duration::days(30)).unwrap();
+
+        for (epoch_id, proposal_name, voters) in [
+            (epoch1, "Proposal 1", vec![team1, team2]),
+            (epoch2, "Proposal 2", vec![team1]),
+        ] {
+            budget_system.activate_epoch(epoch_id).unwrap();
+            let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, proposal_name).await;
+            let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+            budget_system.cast_votes(vote_id, voters.into_iter().map(|t| (t, VoteChoice::Yes)).collect()).unwrap();
+            budget_system.close_vote(vote_id).unwrap();
+            budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+            if epoch_id == epoch1 {
+                budget_system.close_epoch(None).unwrap();
+            }
+        }
+
+        let streaks = budget_system.get_team_absence_streaks().unwrap();
+        assert_eq!(streaks.len(), 3);
+        assert_eq!(streaks[0], TeamAbsenceInfo {
+            team_name: "Team 3".to_string(),
+            current_streak_epochs: 2,
+            last_participated_epoch: None,
+            total_absences: 2,
+        });
+        assert_eq!(streaks[1].team_name, "Team 2");
+        assert_eq!(streaks[1].current_streak_epochs, 1);
+        assert_eq!(streaks[1].last_participated_epoch, Some("Epoch 1".to_string()));
+        assert_eq!(streaks[2].team_name, "Team 1");
+        assert_eq!(streaks[2].current_streak_epochs, 0);
+
+        let output = budget_system.execute_command(Command::PrintAbsenceStreaks { min_streak: 2 }).await.unwrap();
+        assert!(output.contains("Team 3"));
+        assert!(!output.contains("Team 2"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_epoch_payments_report_not_closed() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Create active epoch but don't close it
+        let start_date = Utc::now();
+        let end_date = start_date + Duration::days(30);
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+
+        let result = budget_system.generate_epoch_payments_report("Test Epoch", None, ReportFormat::Json);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("not closed"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_epoch_payments_report_no_reward() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        // Create epoch and close it but don't set reward
+        let start_date = Utc::now();
+        let end_date = start_date + Duration::days(30);
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.close_epoch(None).unwrap();
+
+        let result = budget_system.generate_epoch_payments_report("Test Epoch", None, ReportFormat::Json);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("no reward"));
+    }
+
+    #[tokio::test]
+    async fn test_create_formal_vote_respects_announcement_window() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team1".to_string(), "Rep1".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+
+        budget_system.execute_command(Command::SetEpochVotingWindows {
+            epoch_name: None,
+            min_days_before_vote: Some(3),
+            max_days_to_vote: None,
+        }).await.unwrap();
+
+        let today = Utc::now().date_naive();
+        let err = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap_err();
+        assert_eq!(err, BudgetSystemError::InsufficientAnnouncementPeriod {
+            proposal_id,
+            announced_at: today,
+            earliest_vote_date: today + Duration::days(3),
+        });
+
+        budget_system.state.get_proposal_mut(&proposal_id).unwrap()
+            .set_announced_at(Some(today - Duration::days(3)));
+        assert!(budget_system.create_formal_vote(proposal_id, raffle_id, None, None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_enforce_proposal_announcement_window() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_active_epoch(&mut budget_system).await;
+        let today = Utc::now().date_naive();
+        let stale_id = budget_system.add_proposal(
+            "Stale Proposal".to_string(), None, None, Some(today - Duration::days(20)), None, None, None
+        ).unwrap();
+        let fresh_id = budget_system.add_proposal(
+            "Fresh Proposal".to_string(), None, None, Some(today - Duration::days(2)), None, None, None
+        ).unwrap();
+
+        // No max window configured: nothing is closed
+        assert!(budget_system.enforce_proposal_announcement_window(epoch_id).unwrap().is_empty());
+
+        let output = budget_system.execute_command(Command::SetEpochVotingWindows {
+            epoch_name: Some("Test Epoch".to_string()),
+            min_days_before_vote: None,
+            max_days_to_vote: Some(14),
+        }).await.unwrap();
+        assert!(output.contains("Stale Proposal"));
+
+        let stale = budget_system.get_proposal(&stale_id).unwrap();
+        assert!(stale.is_closed());
+        assert_eq!(stale.resolution(), Some(Resolution::Invalid));
+        assert!(budget_system.get_proposal(&fresh_id).unwrap().is_open());
+
+        // Proposals that go past the window later are closed by the ongoing check,
+        // except those with an open vote
+        let voting_id = budget_system.add_proposal(
+            "Voting Proposal".to_string(), None, None, Some(today - Duration::days(20)), None, None, None
+        ).unwrap();
+        let vote_id = budget_system.create_informal_vote(voting_id).unwrap();
+        budget_system.state.get_proposal_mut(&fresh_id).unwrap()
+            .set_announced_at(Some(today - Duration::days(15)));
+        assert_eq!(budget_system.enforce_announcement_windows().unwrap(), vec![fresh_id]);
+        assert_eq!(budget_system.get_proposal(&fresh_id).unwrap().resolution(), Some(Resolution::Invalid));
+        assert!(budget_system.get_proposal(&voting_id).unwrap().is_open());
+        assert!(!budget_system.state.get_vote(&vote_id).unwrap().is_closed());
+
+        // Opening a formal vote on an overdue proposal closes it instead
+        let (late_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Late Proposal").await;
+        budget_system.state.get_proposal_mut(&late_id).unwrap()
+            .set_announced_at(Some(today - Duration::days(15)));
+        let err = budget_system.create_formal_vote(late_id, raffle_id, None, None).unwrap_err();
+        assert!(err.to_string().contains("max days to vote"));
+        assert!(budget_system.get_proposal(&late_id).unwrap().is_closed());
+
+        // Invalid window combination is rejected
+        assert!(budget_system.set_epoch_voting_windows(None, Some(10), Some(5)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_budget_flow_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let proposal1_id = create_test_proposal(&mut budget_system, "Proposal1", vec![1000.0]);
+        let proposal2_id = create_test_proposal(&mut budget_system, "Proposal2", vec![500.0]);
+        let proposal3_id = create_test_proposal(&mut budget_system, "Proposal3", vec![700.0]);
+
+        budget_system.close_with_reason(proposal1_id, &Resolution::Approved).unwrap();
+        budget_system.close_with_reason(proposal2_id, &Resolution::Approved).unwrap();
+        budget_system.close_with_reason(proposal3_id, &Resolution::Rejected).unwrap();
+        budget_system.record_payments(
+            "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
+            Utc::now().date_naive(),
+            &["Proposal1".to_string()],
+            false,
+        ).await.unwrap();
+
+        let flow = budget_system.generate_budget_flow_data(epoch_id).unwrap();
+
+        let index_of = |name: &str| flow.nodes.iter().position(|n| n.name == name).unwrap();
+        let value_of = |source: usize, target: usize| flow.links.iter()
+            .find(|l| l.source == source && l.target == target)
+            .map(|l| l.value);
+
+        assert_eq!(value_of(index_of("ETH0"), index_of("Unassigned")), Some(1500.0));
+        assert_eq!(value_of(index_of("Unassigned"), index_of("Paid")), Some(1000.0));
+        assert_eq!(value_of(index_of("Unassigned"), index_of("Unpaid")), Some(500.0));
+        assert_eq!(flow.links.len(), 3);
+
+        assert!(budget_system.generate_budget_flow_data(Uuid::new_v4()).is_err());
+
+        let output = budget_system.execute_command(Command::ExportBudgetFlowData {
+            epoch_name: "Test Epoch".to_string(),
+            output_path: None,
+        }).await.unwrap();
+        let parsed: BudgetFlowData = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed.nodes.len(), flow.nodes.len());
+    }
+
+    #[test]
+    fn test_format_team_status() {
+        let earner_status = TeamStatus::Earner { 
+            trailing_monthly_revenue: vec![1000, 2000, 3000] 
+        };
+        assert_eq!(format_team_status(&earner_status), "Earner");
+        assert_eq!(format_team_status(&TeamStatus::Supporter), "Supporter");
+        assert_eq!(format_team_status(&TeamStatus::Inactive), "Inactive");
+        assert_eq!(format_team_status(&TeamStatus::Archived), "Archived");
+    }
+
+    #[tokio::test]
+    async fn test_end_of_epoch_report_filename() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        
+        // Create and close an epoch
+        let _epoch_id = create_test_epoch(&mut budget_system);
+        budget_system.close_epoch(None).unwrap();
+        
+        budget_system.generate_end_of_epoch_report("Test Epoch", ReportFormat::Markdown).unwrap();
+        
+        let expected_path = temp_dir.path()
+            .join("reports")
+            .join("Test_Epoch")
+            .join("end_of_epoch_report-Test_Epoch.md");
+        
+        assert!(expected_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_generate_proposal_tables() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        
+        let start_date = Utc::now();
+        let end_date = start_date + Duration::days(30);
+        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+
+        // Create an approved proposal with payment
+         let proposal1 = create_test_proposal(&mut budget_system, "Approved Proposal", vec![1000.0]);
+         budget_system.close_with_reason(proposal1, &Resolution::Approved).unwrap();
+         
+         // Create a rejected proposal
+         let proposal2 = create_test_proposal(&mut budget_system, "Rejected Proposal", vec![500.0]);
+         budget_system.close_with_reason(proposal2, &Resolution::Rejected).unwrap();
+         
+         let epoch = budget_system.get_current_epoch().unwrap();
+         let tables = budget_system.generate_proposal_tables(epoch).unwrap();
+         
+        // Check approved proposals table has Paid column
+        assert!(tables.contains("| Name | URL | Team | Amounts | Start Date | End Date | Announced | Resolved | Paid | Report |"));
+        
+        // Check rejected proposals table doesn't have Paid column
+        assert!(tables.contains("| Name | URL | Team | Amounts | Start Date | End Date | Announced | Resolved | Report |"));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_close_proposals() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let open1 = create_test_proposal(&mut budget_system, "Open 1", vec![100.0]);
+        let open2 = create_test_proposal(&mut budget_system, "Open 2", vec![200.0]);
+        let closed = create_test_proposal(&mut budget_system, "Closed", vec![300.0]);
+        budget_system.close_with_reason(closed, &Resolution::Approved).unwrap();
+
+        let preview = budget_system.execute_command(Command::BulkCloseProposals {
+            epoch_name: "Test Epoch".to_string(),
+            resolution: "Rejected".to_string(),
+            reason: None,
+            confirm: false,
+        }).await.unwrap();
+        assert!(preview.contains("2 proposal(s)"));
+        assert!(budget_system.get_proposal(&open1).unwrap().is_actionable());
+
+        let mut closed_ids = budget_system.bulk_close_proposals(epoch_id, Resolution::Rejected, "Epoch ended").unwrap();
+        closed_ids.sort();
+        let mut expected = vec![open1, open2];
+        expected.sort();
+        assert_eq!(closed_ids, expected);
+
+        assert!(budget_system.get_proposal(&open1).unwrap().is_rejected());
+        assert!(budget_system.get_proposal(&open2).unwrap().is_rejected());
+        assert!(budget_system.get_proposal(&closed).unwrap().is_approved());
+
+        let output = budget_system.execute_command(Command::BulkCloseProposals {
+            epoch_name: "Test Epoch".to_string(),
+            resolution: "Rejected".to_string(),
+            reason: None,
+            confirm: true,
+        }).await.unwrap();
+        assert!(output.contains("Closed 0 proposal(s)"));
+
+        assert!(budget_system.execute_command(Command::BulkCloseProposals {
+            epoch_name: "Test Epoch".to_string(),
+            resolution: "Bogus".to_string(),
+            reason: None,
+            confirm: true,
+        }).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_proposal_revision_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_test_epoch(&mut budget_system);
+        let proposal_id = create_test_proposal(&mut budget_system, "Original", vec![100.0]);
+        assert!(budget_system.get_proposal_revision_history(proposal_id).is_empty());
+
+        budget_system.update_proposal("Original", UpdateProposalDetails {
+            title: Some("Renamed".to_string()),
+            url: None,
+            budget_request_details: None,
+            announced_at: None,
+            published_at: None,
+            resolved_at: None,
+            category: None,
+        }).unwrap();
+
+        let history = budget_system.get_proposal_revision_history(proposal_id);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].field_changed(), "title");
+
+        let report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(report.contains("## Revision History"));
+        assert!(report.contains("| title | \"Original\" | \"Renamed\" |"));
+
+        budget_system.update_proposal("Renamed", UpdateProposalDetails {
+            title: None,
+            url: Some("https://example.com/?q=a|b".to_string()),
+            budget_request_details: None,
+            announced_at: None,
+            published_at: None,
+            resolved_at: None,
+            category: None,
+        }).unwrap();
+        let report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(report.contains("| \"https://example.com/?q=a\\|b\" |"));
+        assert_eq!(BudgetSystem::markdown_table_cell("line 1\nline 2"), "line 1<br>line 2");
+
+        assert!(budget_system.get_proposal_revision_history(Uuid::new_v4()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deciding_teams_for_proposal() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), Some(vec![2000]), None).unwrap();
+        budget_system.create_team("Team 3".to_string(), "Rep 3".to_string(), None, None).unwrap();
+
+        budget_system.add_proposal("Pending Raffle".to_string(), None, None, None, None, None, None).unwrap();
+        assert!(budget_system.deciding_teams_for_proposal("Pending Raffle").is_err());
+        let config = budget_system.config().clone();
+        budget_system.prepare_raffle("Pending Raffle", None, &config).unwrap();
+        assert!(budget_system.deciding_teams_for_proposal("Pending Raffle").is_err());
+
+        create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let mut deciding = budget_system.deciding_teams_for_proposal("Test Proposal").unwrap();
+        deciding.sort();
+        assert_eq!(deciding, vec!["Team 1".to_string(), "Team 2".to_string(), "Team 3".to_string()]);
+
+        let output = budget_system.execute_command(Command::ShowDecidingTeams {
+            proposal_name: "Test Proposal".to_string(),
+        }).await.unwrap();
+        assert!(output.contains("Deciding teams for proposal 'Test Proposal'"));
+        assert!(output.contains("Team 3"));
+
+        assert!(budget_system.deciding_teams_for_proposal("Non-existent Proposal").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pin_proposal_document() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+        let cid = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+        let proposal_id = budget_system.add_proposal("Audit".to_string(), None, None, None, None, None, None).unwrap();
+
+        let report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(report.contains("- **Document**: N/A"));
+
+        let output = budget_system.execute_command(Command::PinProposalDocument {
+            proposal_name: "Audit".to_string(),
+            ipfs_hash: cid.to_string(),
+        }).await.unwrap();
+        assert_eq!(output, format!("Pinned document {} to proposal Audit", cid));
+        assert_eq!(budget_system.get_proposal(&proposal_id).unwrap().ipfs_hash(), Some(cid));
+        let report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(report.contains(&format!("- **Document**: https://ipfs.io/ipfs/{}", cid)));
+
+        let err = budget_system.pin_proposal_document("Audit", "QmTooShort").unwrap_err();
+        assert_eq!(err.to_string(), "CIDv0 IPFS hash must be 46 characters long");
+        assert!(budget_system.pin_proposal_document("Missing", cid).is_err());
+
+        // A hash given with the budget request is validated before the proposal is added
+        let add = |ipfs_hash: &str| Command::AddProposal {
+            title: format!("Grant {}", ipfs_hash.len()),
+            url: None,
+            budget_request_details: Some(BudgetRequestDetailsCommand {
+                team: None,
+                request_amounts: Some(HashMap::from([("ETH".to_string(), 10.0)])),
+                start_date: None,
+                end_date: None,
+                is_loan: None,
+                payment_address: None,
+                category: None,
+                ipfs_hash: Some(ipfs_hash.to_string()),
+            }),
+            announced_at: None,
+            published_at: None,
+            is_historical: None,
+        };
+        assert!(budget_system.execute_command(add("bafynot")).await.is_err());
+        assert!(budget_system.get_proposal_id_by_name("Grant 7").is_none());
+        let new_cid = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+        budget_system.execute_command(add(new_cid)).await.unwrap();
+        let grant_id = budget_system.get_proposal_id_by_name("Grant 59").unwrap();
+        assert_eq!(budget_system.get_proposal(&grant_id).unwrap().ipfs_hash(), Some(new_cid));
+    }
+
+    #[tokio::test]
+    async fn test_category_breakdown_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let epoch_id = create_active_epoch(&mut budget_system).await;
+        assert!(budget_system.generate_category_breakdown_report(epoch_id).unwrap().contains("No proposals in this epoch."));
+
+        let request = |amount: f64| BudgetRequestDetails::new(
+            None, HashMap::from([("ETH".to_string(), amount)]), None, None, None, None
+        ).unwrap();
+        let approved = budget_system.add_proposal("Node".to_string(), None, Some(request(10.0)), None, None, None, Some(ProposalCategory::Infrastructure)).unwrap();
+        let rejected = budget_system.add_proposal("Servers".to_string(), None, Some(request(5.0)), None, None, None, Some(ProposalCategory::Infrastructure)).unwrap();
+        budget_system.add_proposal("Ads".to_string(), None, Some(request(2.5)), None, None, None, Some(ProposalCategory::Marketing)).unwrap();
+        budget_system.add_proposal("Misc".to_string(), None, None, None, None, None, None).unwrap();
+        budget_system.close_with_reason(approved, &Resolution::Approved).unwrap();
+        budget_system.close_with_reason(rejected, &Resolution::Rejected).unwrap();
+
+        let report = budget_system.generate_category_breakdown_report(epoch_id).unwrap();
+        let rows: Vec<&str> = report.lines().filter(|l| l.starts_with("| ") && !l.starts_with("| Category")).collect();
+        assert_eq!(rows, vec![
+            "| Infrastructure | 1 | 1 | 2 | 15.00 ETH | 10.00 ETH |",
+            "| Marketing | 0 | 0 | 1 | 2.50 ETH | - |",
+            "| Uncategorized | 0 | 0 | 1 | - | - |",
+        ]);
+
+        let proposal_report = budget_system.generate_proposal_report(approved, ReportSections::all()).unwrap();
+        assert!(proposal_report.contains("- **Category**: Infrastructure"));
+
+        let output = budget_system.execute_command(Command::PrintCategoryBreakdown { epoch_name: None }).await.unwrap();
+        assert_eq!(output, report);
+        assert!(budget_system.generate_category_breakdown_report(Uuid::new_v4()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rerun_raffle() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), None, None).unwrap();
+        budget_system.create_team("Team 3".to_string(), "Rep 3".to_string(), None, None).unwrap();
+        let proposal_id = budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        let mut config = budget_system.config().clone();
+        config.default_total_counted_seats = 2;
+        config.default_max_earner_seats = 1;
+        let (old_id, _) = budget_system.prepare_raffle("Test Proposal", Some(vec!["Team 3".to_string()]), &config).unwrap();
+        budget_system.finalize_raffle(old_id, 12345, 12355, "mock_randomness".to_string()).await.unwrap();
+
+        let raffle = budget_system.rerun_raffle("Test Proposal", Some(0)).await.unwrap();
+        assert_ne!(raffle.id(), old_id);
+        assert!(budget_system.get_raffle(&old_id).is_none());
+        assert_eq!(raffle.config().block_randomness(), "mock_randomness_for_block_12345");
+        assert_eq!(raffle.config().total_counted_seats(), 2);
+        assert_eq!(raffle.config().max_earner_seats(), 1);
+        assert_eq!(budget_system.get_raffle_exclusions(raffle.id()), vec!["Team 3".to_string()]);
+        assert_eq!(raffle.superseded_raffles().len(), 1);
+        assert_eq!(raffle.superseded_raffles()[0].raffle_id(), old_id);
+        assert_eq!(raffle.superseded_raffles()[0].block_randomness(), "mock_randomness");
+        assert!(budget_system.show_raffle("Test Proposal").unwrap().contains(&format!("| {} | 12355 | mock_randomness |", old_id)));
+
+        // A failed draw keeps the current raffle
+        let current_id = raffle.id();
+        get_mock_service(&budget_system).unwrap().set_failure_count(3);
+        assert!(budget_system.rerun_raffle("Test Proposal", Some(0)).await.is_err());
+        let raffles: Vec<Uuid> = budget_system.state().raffles().values()
+            .filter(|r| r.config().proposal_id() == proposal_id)
+            .map(|r| r.id())
+            .collect();
+        assert_eq!(raffles, vec![current_id]);
+        assert!(fs::read_to_string(&state_file).unwrap().contains(&current_id.to_string()));
+
+        let output = budget_system.execute_command(Command::RerunRaffle {
+            proposal_name: "Test Proposal".to_string(),
+            block_offset: Some(0),
+        }).await.unwrap();
+        assert!(output.contains(&format!("replaces {}", current_id)));
+        let (_, rerun_id) = budget_system.find_proposal_and_raffle("Test Proposal").unwrap();
+        assert_eq!(budget_system.get_raffle(&rerun_id).unwrap().superseded_raffles().len(), 2);
+
+        budget_system.create_formal_vote(proposal_id, rerun_id, None, None).unwrap();
+        let err = budget_system.rerun_raffle("Test Proposal", Some(0)).await.unwrap_err();
+        assert!(err.to_string().contains("already has a vote"));
+        assert!(budget_system.rerun_raffle("No Proposal", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_show_raffle() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), None, None).unwrap();
+
+        budget_system.add_proposal("No Raffle".to_string(), None, None, None, None, None, None).unwrap();
+        let output = budget_system.execute_command(Command::ShowRaffle {
+            proposal_name: "No Raffle".to_string(),
+        }).await.unwrap();
+        assert_eq!(output, "No raffle was conducted for proposal 'No Raffle'.\n");
+
+        budget_system.add_proposal("Pending Raffle".to_string(), None, None, None, None, None, None).unwrap();
+        let config = budget_system.config().clone();
+        budget_system.prepare_raffle("Pending Raffle", None, &config).unwrap();
+        let output = budget_system.show_raffle("Pending Raffle").unwrap();
+        assert!(output.contains("### Team Snapshots"));
+        assert!(output.contains("The raffle has not been drawn yet."));
+
+        create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let output = budget_system.show_raffle("Test Proposal").unwrap();
+        assert!(output.starts_with("# Raffle: Test Proposal"));
+        assert!(output.contains("- **Is Historical**: false"));
+        assert!(output.contains("| Team 1 | Earner |"));
+        assert!(output.contains("#### Counted Seats"));
+        assert!(output.contains("Team 2 (Best Score:"));
+        assert!(!output.contains("not been drawn"));
+
+        assert!(budget_system.show_raffle("Non-existent Proposal").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_pending_payments() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let today = Utc::now().date_naive();
+        let approve_days_ago = |budget_system: &mut BudgetSystem, name: &str, days: i64| {
+            let id = create_test_proposal(budget_system, name, vec![100.0]);
+            let proposal = budget_system.state.get_proposal_mut(&id).unwrap();
+            let date = today - Duration::days(days);
+            proposal.set_dates(Some(date), Some(date), Some(date)).unwrap();
+            proposal.approve().unwrap();
+            id
+        };
+
+        create_test_epoch(&mut budget_system);
+        let old = approve_days_ago(&mut budget_system, "Old", 45);
+        approve_days_ago(&mut budget_system, "Paid", 50);
+        budget_system.record_payments(
+            "0x1234567890123456789012345678901234567890123456789012345678901234",
+            today,
+            &["Paid".to_string()],
+            true,
+        ).await.unwrap();
+        budget_system.close_epoch(None).unwrap();
+
+        let start_date = Utc::now() + Duration::days(31);
+        let second_epoch = budget_system.create_epoch("Second Epoch", start_date, start_date + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(second_epoch).unwrap();
+        let recent = approve_days_ago(&mut budget_system, "Recent", 5);
+        let loan = approve_days_ago(&mut budget_system, "Loan", 10);
+        budget_system.state.get_proposal_mut(&loan).unwrap()
+            .budget_request_details_mut().unwrap()
+            .set_is_loan(true);
+        create_test_proposal(&mut budget_system, "Open", vec![100.0]);
+
+        let pending = budget_system.get_pending_payments();
+        let ids: Vec<Uuid> = pending.iter().map(|p| p.proposal_id).collect();
+        assert_eq!(ids, vec![old, loan, recent]);
+        assert_eq!(pending[0].epoch_name, "Test Epoch");
+        assert_eq!(pending[0].approved_date, today - Duration::days(45));
+        assert_eq!(pending[0].days_pending, 45);
+        assert!(pending[1].is_loan);
+        assert!(!pending[2].is_loan);
+
+        let output = budget_system.execute_command(Command::ListPendingPayments { epoch_name: None, min_days_pending: None }).await.unwrap();
+        assert!(output.starts_with("| Proposal | Team | Epoch | Amounts | Approved | Days Pending | Loan |\n"));
+        assert!(output.find("\n| Old |").unwrap() < output.find("\n| Loan |").unwrap());
+        assert!(output.contains("| Loan | No Team | Second Epoch | 100 ETH0 | "));
+        assert!(output.contains("Warning: 1 payment(s) pending for more than 30 days: Old"));
+        assert!(!output.contains("| Paid |"));
+        assert!(!output.contains("| Open |"));
+
+        // Filtering by epoch
+        let output = budget_system.execute_command(Command::ListPendingPayments {
+            epoch_name: Some("Second Epoch".to_string()),
+            min_days_pending: None,
+        }).await.unwrap();
+        assert!(!output.contains("| Old |"));
+        assert!(output.contains("| Recent |"));
+        assert!(!output.contains("Warning"));
+        assert!(budget_system.execute_command(Command::ListPendingPayments {
+            epoch_name: Some("Missing".to_string()),
+            min_days_pending: None,
+        }).await.is_err());
+
+        // Filtering by age; the threshold is inclusive
+        let output = budget_system.execute_command(Command::ListPendingPayments { epoch_name: None, min_days_pending: Some(10) }).await.unwrap();
+        assert!(output.contains("| Old |"));
+        assert!(output.contains("\n| Loan |"));
+        assert!(!output.contains("| Recent |"));
+
+        let output = budget_system.execute_command(Command::ListPendingPayments {
+            epoch_name: Some("Second Epoch".to_string()),
+            min_days_pending: Some(30),
+        }).await.unwrap();
+        assert_eq!(output, "No pending payments");
+    }
+
+    #[tokio::test]
+    async fn test_get_overdue_payments() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_test_epoch(&mut budget_system);
+        let today = Utc::now().date_naive();
+        let mut approve_days_ago = |name: &str, days: i64| {
+            let id = create_test_proposal(&mut budget_system, name, vec![100.0]);
+            let proposal = budget_system.state.get_proposal_mut(&id).unwrap();
+            let date = today - Duration::days(days);
+            proposal.set_dates(Some(date), Some(date), Some(date)).unwrap();
+            proposal.approve().unwrap();
+            id
+        };
+
+        let very_late = approve_days_ago("Very Late", 60);
+        let late = approve_days_ago("Late", 40);
+        let recent = approve_days_ago("Recent", 5);
+        approve_days_ago("Paid", 50);
+        budget_system.record_payments(
+            "0x1234567890123456789012345678901234567890123456789012345678901234",
+            today,
+            &["Paid".to_string()],
+            false,
+        ).await.unwrap();
+        create_test_proposal(&mut budget_system, "Open", vec![100.0]);
+
+        let overdue = budget_system.get_overdue_payments(30).unwrap();
+        let ids: Vec<Uuid> = overdue.iter().map(|p| p.proposal_id).collect();
+        assert_eq!(ids, vec![very_late, late]);
+        assert!(!ids.contains(&recent));
+        assert_eq!(overdue[0].days_overdue, 60);
+        assert_eq!(overdue[0].team_name, "No Team");
+        assert!(overdue[0].payment_address.is_some());
+
+        let output = budget_system.execute_command(Command::PrintOverduePayments { days: 30 }).await.unwrap();
+        assert!(output.contains("Very Late"));
+        assert!(!output.contains("Recent"));
+
+        let output = budget_system.execute_command(Command::PrintOverduePayments { days: 365 }).await.unwrap();
+        assert!(output.starts_with("No overdue payments"));
+    }
+
+    #[tokio::test]
+    async fn test_state_size_estimate() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let empty = budget_system.state_size_estimate();
+        assert_eq!(empty.proposals, 0);
+        assert_eq!(empty.epochs, 0);
+
+        create_test_epoch(&mut budget_system);
+        create_test_proposal(&mut budget_system, "Proposal 1", vec![100.0]);
+        create_test_proposal(&mut budget_system, "Proposal 2", vec![200.0]);
+
+        let estimate = budget_system.state_size_estimate();
+        assert_eq!(estimate.epochs, 1);
+        assert_eq!(estimate.proposals, 2);
+        assert_eq!(estimate.raffles, 0);
+        assert!(estimate.serialized_bytes > empty.serialized_bytes);
+
+        // Saving past the warning threshold still succeeds
+        budget_system.config.state_file_warn_bytes = 1;
+        budget_system.save_state().unwrap();
+
+        let output = budget_system.execute_command(Command::PrintStateSize).await.unwrap();
+        assert!(output.contains("Proposals: 2"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_epoch_infographic_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let team1 = budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), Some(vec![2000]), None).unwrap();
+        let epoch_id = create_active_epoch(&mut budget_system).await;
+
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Voted Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team1, VoteChoice::Yes)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+
+        let funded = create_test_proposal(&mut budget_system, "Funded", vec![250.0]);
+        budget_system.close_with_reason(funded, &Resolution::Approved).unwrap();
+        let rejected = create_test_proposal(&mut budget_system, "Rejected", vec![100.0]);
+        budget_system.close_with_reason(rejected, &Resolution::Rejected).unwrap();
+        create_test_proposal(&mut budget_system, "Open", vec![100.0]);
+
+        let data = budget_system.generate_epoch_infographic_data(epoch_id).unwrap();
+        assert_eq!(data.epoch_name, "Test Epoch");
+
+        assert_eq!(data.team_rankings[0].team_name, "Team 1");
+        assert_eq!(data.team_rankings[0].rank, 1);
+        assert!(data.team_rankings[0].points > data.team_rankings[1].points);
+
+        assert_eq!(data.resolution_counts, ResolutionCounts {
+            approved: 2,
+            rejected: 1,
+            unresolved: 1,
+            ..Default::default()
+        });
+
+        assert_eq!(data.vote_participation.len(), 1);
+        assert_eq!(data.vote_participation[0].proposal_title, "Voted Proposal");
+        assert_eq!(data.vote_participation[0].counted_participants, 1);
+        // No team statuses to compare and a single epoch in the trend leave participation
+        // and Team 2's one-epoch absence streak to vary
+        assert!(data.approval_rate_by_status.is_empty());
+        let expected = 60.0 * data.vote_participation[0].participation_rate + 40.0 - ABSENCE_STREAK_DEDUCTION;
+        assert!((data.governance_health_score.unwrap() - expected).abs() < 1e-9);
+
+        assert_eq!(data.budget_allocation.len(), 1);
+        assert_eq!(data.budget_allocation[0].team_name, "Unassigned");
+        assert_eq!(data.budget_allocation[0].amounts.get("ETH0"), Some(&250.0));
+
+        assert!(budget_system.generate_epoch_infographic_data(Uuid::new_v4()).is_err());
+
+        budget_system.execute_command(Command::ExportEpochInfographicData {
+            epoch_name: "Test Epoch".to_string(),
+        }).await.unwrap();
+        let written = fs::read_to_string(temp_dir.path().join("reports").join("Test_Epoch").join("infographic_data.json")).unwrap();
+        let parsed: EpochInfographicData = serde_json::from_str(&written).unwrap();
+        assert_eq!(parsed, data);
+    }
+
+    #[tokio::test]
+    async fn test_set_proposal_loan_terms_and_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_test_epoch(&mut budget_system);
+        let loan_id = create_test_proposal(&mut budget_system, "Loan", vec![1000.0]);
+        budget_system.state.get_proposal_mut(&loan_id).unwrap()
+            .budget_request_details_mut().unwrap()
+            .set_is_loan(true);
+        let grant_id = create_test_proposal(&mut budget_system, "Grant", vec![500.0]);
+
+        let start = budget_system.get_proposal(&loan_id).unwrap().budget_request_details().unwrap().start_date().unwrap();
+        let command = |name: &str, due_date: NaiveDate, repaid_amounts: Option<HashMap<String, f64>>| Command::SetProposalLoanTerms {
+            proposal_name: name.to_string(),
+            interest_rate: 0.1,
+            due_date,
+            repayment_schedule: "AtMaturity".to_string(),
+            repaid_amounts,
+        };
+
+        // Due date must follow the start date, and grants cannot carry loan terms
+        assert!(budget_system.execute_command(command("Loan", start, None)).await.is_err());
+        assert!(budget_system.execute_command(command("Grant", start + Duration::days(90), None)).await.is_err());
+
+        let repaid: HashMap<String, f64> = [("ETH0".to_string(), 200.0)].into_iter().collect();
+        budget_system.execute_command(command("Loan", start + Duration::days(90), Some(repaid))).await.unwrap();
+        // Updating terms without repaid amounts keeps the recorded repayments
+        budget_system.execute_command(command("Loan", start + Duration::days(120), None)).await.unwrap();
+
+        let terms = budget_system.get_proposal(&loan_id).unwrap().budget_request_details().unwrap().loan_terms().unwrap().clone();
+        assert_eq!(terms.due_date(), start + Duration::days(120));
+        assert_eq!(terms.repaid_amounts().get("ETH0"), Some(&200.0));
+
+        let proposal_report = budget_system.generate_proposal_report(loan_id, ReportSections::all()).unwrap();
+        assert!(proposal_report.contains("- **Interest Rate**: 10.00%"));
+
+        // Only approved loans show up in the outstanding report
+        assert!(budget_system.generate_loans_outstanding_report().contains("No approved loans"));
+        budget_system.close_with_reason(loan_id, &Resolution::Approved).unwrap();
+        budget_system.close_with_reason(grant_id, &Resolution::Approved).unwrap();
+
+        let report = budget_system.execute_command(Command::PrintLoansOutstanding).await.unwrap();
+        assert!(report.contains("Loan (No Team)"));
+        assert!(!report.contains("Grant"));
+        assert!(report.contains("ETH0: principal 1000.00, total due 1100.00, expected to date 0.00, repaid 200.00, outstanding 900.00"));
+    }
+
+    #[tokio::test]
+    async fn test_outstanding_loans_report_and_repayment() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_test_epoch(&mut budget_system);
+        for name in ["Loan A", "Loan B", "Unpaid Loan"] {
+            let id = create_test_proposal(&mut budget_system, name, vec![100.0]);
+            budget_system.state.get_proposal_mut(&id).unwrap()
+                .budget_request_details_mut().unwrap()
+                .set_is_loan(true);
+            budget_system.close_with_reason(id, &Resolution::Approved).unwrap();
+        }
+        let grant_id = create_test_proposal(&mut budget_system, "Grant", vec![50.0]);
+        budget_system.close_with_reason(grant_id, &Resolution::Approved).unwrap();
+
+        let today = Utc::now().date_naive();
+        let tx = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e";
+        budget_system.record_payments(tx, today - Duration::days(30), &["Loan A".to_string(), "Grant".to_string()], true).await.unwrap();
+        budget_system.record_payments(tx, today - Duration::days(10), &["Loan B".to_string()], true).await.unwrap();
+
+        let report = budget_system.execute_command(Command::PrintOutstandingLoans { epoch_name: None }).await.unwrap();
+        let loan_a = report.find("Loan A (No Team): 100.00 ETH0, paid").unwrap();
+        let loan_b = report.find("Loan B (No Team)").unwrap();
+        assert!(loan_a < loan_b);
+        assert!(report.contains("30 days outstanding"));
+        assert!(!report.contains("Unpaid Loan"));
+        assert!(!report.contains("Grant"));
+        assert!(report.contains("Total outstanding: 200.00 ETH0"));
+
+        // Repaying before payment or repaying grants is rejected
+        assert!(budget_system.record_loan_repayment("Loan A", today - Duration::days(31)).is_err());
+        assert!(budget_system.record_loan_repayment("Grant", today).is_err());
+        assert!(budget_system.record_loan_repayment("Unpaid Loan", today).is_err());
+
+        let output = budget_system.execute_command(Command::RecordLoanRepayment {
+            proposal_name: "Loan A".to_string(),
+            repaid_at: None,
+        }).await.unwrap();
+        assert_eq!(output, format!("Recorded repayment of loan 'Loan A' on {}", today));
+
+        let report = budget_system.generate_outstanding_loans_report(Some("Test Epoch")).unwrap();
+        assert!(report.starts_with("Outstanding Loans (Test Epoch)"));
+        assert!(!report.contains("Loan A"));
+        assert!(report.contains("Loan B"));
+
+        budget_system.record_loan_repayment("Loan B", today).unwrap();
+        assert!(budget_system.generate_outstanding_loans_report(None).unwrap().contains("No outstanding loans"));
+        assert!(budget_system.generate_outstanding_loans_report(Some("Missing")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_point_consistency() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_active_epoch(&mut budget_system).await;
+        let team_ids: Vec<Uuid> = ["Team A", "Team B"].iter()
+            .map(|name| budget_system.create_team(name.to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap())
+            .collect();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Voted Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, team_ids.iter().map(|&id| (id, VoteChoice::Yes)).collect()).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+
+        let counted_points = budget_system.calculate_team_points_for_epoch(team_ids[0], epoch_id);
+        assert!(counted_points > 0);
+        let points = budget_system.recalculate_all_points();
+        assert_eq!(points[&epoch_id][&team_ids[0]], counted_points);
+        assert_eq!(points[&epoch_id].len(), 2);
+        assert!(budget_system.verify_point_consistency().is_ok());
+        assert_eq!(budget_system.execute_command(Command::VerifyPoints).await.unwrap(), "All points consistent");
+
+        // A team that was not in the raffle shows up as a counted participant
+        let late_team = budget_system.create_team("Late Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.state.get_vote_mut(&vote_id).unwrap().add_participant(late_team, true).unwrap();
+
+        let mismatches = budget_system.verify_point_consistency().unwrap_err();
+        assert_eq!(mismatches, vec![PointMismatch { epoch_id, team_id: late_team, stored: counted_points, recalculated: 0 }]);
+
+        let output = budget_system.execute_command(Command::VerifyPoints).await.unwrap();
+        assert!(output.starts_with("Found 1 point mismatch(es):"));
+        assert!(output.contains(&format!("Test Epoch / Late Team: stored {}, recalculated 0", counted_points)));
+    }
+
+    #[tokio::test]
+    async fn test_treasury_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+
+        let report = budget_system.generate_treasury_report("Test Epoch").unwrap();
+        assert!(report.contains("No approved requests or rewards in this epoch"));
+        assert!(budget_system.get_treasury_report("Missing").is_err());
+
+        let today = Utc::now().date_naive();
+        let mut request = |name: &str, amounts: &[(&str, f64)], start: NaiveDate, resolution: Resolution| {
+            let id = create_test_proposal(&mut budget_system, name, vec![1.0]);
+            let amounts = amounts.iter().map(|(token, amount)| (token.to_string(), *amount)).collect();
+            let details = BudgetRequestDetails::new(None, amounts, Some(start), Some(start + Duration::days(90)), Some(false), None).unwrap();
+            budget_system.state.get_proposal_mut(&id).unwrap().set_budget_request_details(Some(details));
+            budget_system.close_with_reason(id, &resolution).unwrap();
+        };
+        // Dates outside the epoch don't matter, only the epoch the proposal belongs to
+        request("Stable Grant", &[("USDC", 1000.0), ("DAI", 500.0)], today - Duration::days(120), Resolution::Approved);
+        request("Paid Grant", &[("ETH", 2.0)], today, Resolution::Approved);
+        request("Rejected Grant", &[("USDC", 999.0)], today, Resolution::Rejected);
+        budget_system.set_epoch_reward("ETH", 10.0).unwrap();
+        let tx = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e";
+        budget_system.record_payments(tx, today, &["Paid Grant".to_string()], true).await.unwrap();
+
+        let report = budget_system.get_treasury_report("Test Epoch").unwrap();
+        let flow = |token: &str, approved: f64, committed: f64, paid: f64, reward_pool: f64| TreasuryTokenFlow {
+            token: token.to_string(), approved, committed, paid, reward_pool,
+        };
+        assert_eq!(report.tokens, vec![
+            flow("DAI", 500.0, 500.0, 0.0, 0.0),
+            flow("ETH", 2.0, 0.0, 2.0, 10.0),
+            flow("USDC", 1000.0, 1000.0, 0.0, 0.0),
+        ]);
+        assert_eq!(report.grouped, vec![
+            flow("ETH", 2.0, 0.0, 2.0, 10.0),
+            flow("Stables", 1500.0, 1500.0, 0.0, 0.0),
+        ]);
+        assert!(report.tokens.iter().all(|flow| flow.reconciles()));
+        assert_eq!(report.grouped[0].total_outflow(), 12.0);
+
+        let markdown = budget_system.execute_command(Command::PrintTreasuryReport {
+            epoch_name: "Test Epoch".to_string(),
+            format: ReportFormat::Markdown,
+        }).await.unwrap();
+        assert!(markdown.starts_with("# Treasury Report: Test Epoch"));
+        assert!(markdown.contains("| ETH | 2.00 | 0.00 | 2.00 | 10.00 | 12.00 |"));
+        assert!(markdown.contains("## Stablecoins Grouped"));
+        assert!(markdown.contains("| Stables | 1500.00 | 1500.00 | 0.00 | 0.00 | 1500.00 |"));
+        assert!(markdown.contains("Committed and paid amounts reconcile with approved requests."));
+
+        let json = budget_system.execute_command(Command::PrintTreasuryReport {
+            epoch_name: "Test Epoch".to_string(),
+            format: ReportFormat::Json,
+        }).await.unwrap();
+        let parsed: TreasuryReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.tokens, report.tokens);
+    }
+
+    #[tokio::test]
+    async fn test_loan_tracking_report() {
+        use crate::core::models::RepaymentSchedule;
+
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_test_epoch(&mut budget_system);
+        let grant_id = create_test_proposal(&mut budget_system, "Grant", vec![50.0]);
+        budget_system.close_with_reason(grant_id, &Resolution::Approved).unwrap();
+
+        let report = budget_system.generate_loan_tracking_report().unwrap();
+        assert!(report.contains("## Unpaid Loans\n\nNo approved loans waiting to be paid out"));
+        assert!(report.contains("## Outstanding Loans\n\nNo outstanding loans"));
+        assert!(report.contains("## Repaid Loans\n\nNo repaid loans"));
+        assert!(report.contains("## Totals\n\nNo approved loans"));
+        assert!(!report.contains("Grant"));
+
+        let today = Utc::now().date_naive();
+        let team_id = budget_system.create_team("Lender".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        for name in ["Unpaid Loan", "Overdue Loan", "Partial Loan", "Repaid Loan"] {
+            let id = create_test_proposal(&mut budget_system, name, vec![100.0]);
+            let details = budget_system.state.get_proposal_mut(&id).unwrap()
+                .budget_request_details_mut().unwrap();
+            details.set_is_loan(true);
+            details.set_team(Some(team_id));
+            budget_system.close_with_reason(id, &Resolution::Approved).unwrap();
+        }
+        for name in ["Unpaid Loan", "Overdue Loan"] {
+            budget_system.state.get_proposal_mut(&budget_system.get_proposal_id_by_name(name).unwrap()).unwrap()
+                .budget_request_details_mut().unwrap()
+                .set_dates(Some(today - Duration::days(60)), Some(today - Duration::days(5)))
+                .unwrap();
+        }
+        let repaid: HashMap<String, f64> = [("ETH0".to_string(), 40.0)].into_iter().collect();
+        budget_system.set_proposal_loan_terms("Partial Loan", LoanTerms::new(0.0, today + Duration::days(20), RepaymentSchedule::AtMaturity, repaid).unwrap()).unwrap();
+
+        let txs = [
+            ("Overdue Loan", "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44a"),
+            ("Partial Loan", "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44b"),
+            ("Repaid Loan", "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e"),
+        ];
+        for (name, tx) in txs {
+            budget_system.record_payments(tx, today - Duration::days(3), &[name.to_string()], true).await.unwrap();
+        }
+        budget_system.record_loan_repayment("Repaid Loan", today).unwrap();
+
+        let report = budget_system.execute_command(Command::GenerateLoanReport { output_path: None }).await.unwrap();
+        let (unpaid, rest) = report.split_once("## Outstanding Loans").unwrap();
+        let (outstanding, repaid) = rest.split_once("## Repaid Loans").unwrap();
+        assert!(unpaid.contains(&format!("| Unpaid Loan | Lender | 100.00 ETH0 | Test Epoch | N/A | {} | 5 |", today - Duration::days(5))));
+        assert!(!unpaid.contains("Overdue Loan"));
+        assert!(outstanding.contains(&format!("| Overdue Loan | Lender | 100.00 ETH0 | Test Epoch | N/A | {} | 5 | {} |",
+            today - Duration::days(5), txs[0].1.to_lowercase())));
+        assert!(outstanding.contains(&format!("| Partial Loan | Lender | 60.00 of 100.00 ETH0 | Test Epoch | N/A | {} | 0 | {} |",
+            today + Duration::days(30), txs[1].1.to_lowercase())));
+        assert!(!outstanding.contains("Unpaid Loan"));
+        assert!(!outstanding.contains("Repaid Loan"));
+        assert!(repaid.contains(&format!("| Repaid Loan | Lender | 100.00 ETH0 | Test Epoch | N/A | {} | {} | 0 | {} |",
+            today + Duration::days(30), today, txs[2].1.to_lowercase())));
+        assert!(report.contains("| ETH0 | 100.00 | 160.00 | 140.00 |"));
+
+        let output = budget_system.execute_command(Command::GenerateLoanReport { output_path: Some("loans.md".to_string()) }).await.unwrap();
+        assert!(output.starts_with("Loan report saved to"));
+        assert_eq!(fs::read_to_string(temp_dir.path().join("reports").join("loans.md")).unwrap(), report);
+    }
+
+    #[tokio::test]
+    async fn test_proposal_templates() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        budget_system.create_team("Ops".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let epoch_id = create_test_epoch(&mut budget_system);
+
+        let output = budget_system.execute_command(Command::CreateProposalTemplate {
+            name: "ops-grant".to_string(),
+            title_pattern: "Ops Grant {epoch} #{n}".to_string(),
+            team: Some("Ops".to_string()),
+            amounts: [("ETH".to_string(), 10.0)].into_iter().collect(),
+            is_loan: false,
+            tags: vec!["ops".to_string()],
+            recurrence_interval_epochs: Some(1),
+            max_occurrences: Some(2),
+        }).await.unwrap();
+        assert!(output.contains("Created proposal template: ops-grant"));
+
+        let duplicate = ProposalTemplate::new("ops-grant".to_string(), "Other".to_string(), None, HashMap::new(), false, vec![], None).unwrap();
+        assert!(budget_system.create_proposal_template(duplicate).is_err());
+
+        let first = budget_system.create_proposal_from_template("ops-grant", epoch_id, ProposalOverrides::default()).unwrap();
+        let proposal = budget_system.get_proposal(&first).unwrap();
+        assert_eq!(proposal.title(), "Ops Grant Test Epoch #1");
+        assert_eq!(proposal.epoch_id(), epoch_id);
+        let details = proposal.budget_request_details().unwrap();
+        assert_eq!(details.team(), budget_system.get_team_id_by_name("Ops"));
+        assert_eq!(details.request_amounts().get("ETH"), Some(&10.0));
+        assert_eq!(proposal.tags(), ["ops".to_string()]);
+        assert!(budget_system.get_epoch(&epoch_id).unwrap().associated_proposals().contains(&first));
+
+        // The template recurs once per epoch
+        let err = budget_system.create_proposal_from_template("ops-grant", epoch_id, ProposalOverrides {
+            title: Some("Ops Grant Extra".to_string()),
+            ..ProposalOverrides::default()
+        }).unwrap_err();
+        assert!(err.to_string().contains("recurs every 1 epoch(s) and was last used in epoch 'Test Epoch'"));
+
+        let next_start = Utc::now() + Duration::days(31);
+        budget_system.create_epoch("Next Epoch", next_start, next_start + Duration::days(30)).unwrap();
+        let output = budget_system.execute_command(Command::CreateProposalFromTemplate {
+            template_name: "ops-grant".to_string(),
+            epoch_name: Some("Next Epoch".to_string()),
+            title: None,
+            url: Some("https://example.com".to_string()),
+            team: None,
+            amounts: Some([("ETH".to_string(), 25.0)].into_iter().collect()),
+            start_date: None,
+            end_date: None,
+            announced_at: None,
+            published_at: None,
+        }).await.unwrap();
+        assert!(output.contains("Ops Grant Next Epoch #2"));
+        let second = budget_system.get_proposal_id_by_name("Ops Grant Next Epoch #2").unwrap();
+        assert_eq!(budget_system.get_proposal(&second).unwrap().budget_request_details().unwrap().request_amounts().get("ETH"), Some(&25.0));
+
+        // Max occurrences reached
+        assert!(budget_system.create_proposal_from_template("ops-grant", epoch_id, ProposalOverrides::default()).is_err());
+        assert!(budget_system.create_proposal_from_template("missing", epoch_id, ProposalOverrides::default()).is_err());
+
+        let listing = budget_system.execute_command(Command::ListProposalTemplates).await.unwrap();
+        assert!(listing.contains("ops-grant"));
+        assert!(listing.contains("used 2 time(s)"));
+    }
+
+    #[tokio::test]
+    async fn test_epoch_state_json_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        create_test_proposal(&mut budget_system, "Open Proposal", vec![100.0]);
+        let approved = create_test_proposal(&mut budget_system, "Approved Proposal", vec![50.0]);
+        budget_system.close_with_reason(approved, &Resolution::Approved).unwrap();
+
+        let report = budget_system.get_epoch_state_report().unwrap();
+        assert_eq!(report.epoch_id, epoch_id);
+        assert_eq!(report.total_proposals, 2);
+        assert_eq!(report.approved_count, 1);
+        assert_eq!(report.open_proposals.len(), 1);
+        assert_eq!(report.open_proposals[0].title, "Open Proposal");
+        assert_eq!(report.open_proposals[0].amounts.get("ETH0"), Some(&100.0));
+
+        let json = budget_system.execute_command(Command::PrintEpochState { format: ReportFormat::Json }).await.unwrap();
+        let parsed: EpochStateReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, report);
+
+        let text = budget_system.execute_command(Command::PrintEpochState { format: ReportFormat::Text }).await.unwrap();
+        assert!(text.contains("*State of Epoch Test Epoch*"));
+
+        // The format defaults to text when omitted from script params
+        let command: Command = serde_json::from_str(r#"{"type": "PrintEpochState", "params": {}}"#).unwrap();
+        assert!(matches!(command, Command::PrintEpochState { format: ReportFormat::Text }));
+    }
+
+    #[tokio::test]
+    async fn test_team_geographic_distribution() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        budget_system.create_team("Beta".to_string(), "Rep".to_string(), None, None).unwrap();
+        budget_system.create_team("Alpha".to_string(), "Rep".to_string(), None, None).unwrap();
+        budget_system.create_team("Gamma".to_string(), "Rep".to_string(), None, None).unwrap();
+
+        for team_name in ["Alpha", "Beta"] {
+            budget_system.execute_command(Command::UpdateTeamRegion {
+                team_name: team_name.to_string(),
+                region: Some("EMEA".to_string()),
+            }).await.unwrap();
+        }
+
+        let distribution = budget_system.get_team_geographic_distribution();
+        assert_eq!(distribution.len(), 2);
+        assert_eq!(distribution.get("EMEA"), Some(&vec!["Alpha".to_string(), "Beta".to_string()]));
+        assert_eq!(distribution.get("Unspecified"), Some(&vec!["Gamma".to_string()]));
+
+        let output = budget_system.execute_command(Command::PrintGeographicDistribution).await.unwrap();
+        assert!(output.contains("EMEA (2): Alpha, Beta"));
+        assert!(output.contains("Unspecified (1): Gamma"));
+
+        let result = budget_system.execute_command(Command::UpdateTeamRegion {
+            team_name: "Missing".to_string(),
+            region: None,
+        }).await;
+        assert!(result.is_err());
+
+        create_test_epoch(&mut budget_system);
+        budget_system.close_epoch(None).unwrap();
+        budget_system.generate_end_of_epoch_report("Test Epoch", ReportFormat::Markdown).unwrap();
+        let report = fs::read_to_string(temp_dir.path()
+            .join("reports")
+            .join("Test_Epoch")
+            .join("end_of_epoch_report-Test_Epoch.md")).unwrap();
+        assert!(report.contains("## Geographic Diversity"));
+        assert!(report.contains("| EMEA | 2 | 66.67% | Alpha, Beta |"));
+    }
+
+    #[tokio::test]
+    async fn test_set_default_max_earner_seats() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+
+        let (_, finalized_raffle) = create_proposal_with_raffle(&mut budget_system, "Finalized").await;
+        create_test_proposal(&mut budget_system, "Pending", vec![100.0]);
+        let config = budget_system.config().clone();
+        let (pending_raffle, _) = budget_system.prepare_raffle("Pending", None, &config).unwrap();
+
+        // Cannot break the max_earner <= total invariant
+        assert!(budget_system.set_default_max_earner_seats(8).is_err());
+        assert_eq!(budget_system.config().default_max_earner_seats, 5);
+
+        let exceeding = budget_system.set_default_max_earner_seats(3).unwrap();
+        assert_eq!(exceeding, vec![pending_raffle]);
+        assert!(!exceeding.contains(&finalized_raffle));
+        assert_eq!(budget_system.config().default_max_earner_seats, 3);
+
+        let output = budget_system.execute_command(Command::SetMaxEarnerSeatsGlobally { max_earner_seats: 4 }).await.unwrap();
+        assert!(output.contains("Default max earner seats set to 4"));
+        assert!(output.contains("Pending"));
+
+        let output = budget_system.execute_command(Command::SetMaxEarnerSeatsGlobally { max_earner_seats: 5 }).await.unwrap();
+        assert!(!output.contains("Warning"));
+    }
+
+    #[tokio::test]
+    async fn test_preview_close_epoch_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_test_epoch(&mut budget_system);
+        create_test_proposal(&mut budget_system, "Open Proposal", vec![100.0]);
+        let approved = create_test_proposal(&mut budget_system, "Approved Proposal", vec![50.0]);
+        budget_system.close_with_reason(approved, &Resolution::Approved).unwrap();
+
+        // Works on an open epoch and writes nothing to disk
+        let preview = budget_system.preview_close_epoch_report("Test Epoch").unwrap();
+        assert!(preview.starts_with("# PREVIEW — NOT FINAL"));
+        assert!(preview.contains("1 proposal(s) are still open"));
+        assert!(preview.contains("# End of Epoch Report: Test Epoch"));
+        assert!(preview.contains("Approved Proposal"));
+        assert!(preview.contains("## Team Summary"));
+        assert!(!temp_dir.path().join("reports").exists());
+
+        assert!(budget_system.preview_close_epoch_report("Missing").is_err());
+
+        let output = budget_system.execute_command(Command::PreviewEndOfEpochReport {
+            epoch_name: "Test Epoch".to_string()
+        }).await.unwrap();
+        assert!(output.contains("PREVIEW — NOT FINAL"));
+    }
+
+    #[tokio::test]
+    async fn test_export_delimited() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        let approved = create_test_proposal(&mut budget_system, "Approved, Unpaid", vec![100.0, 1000.0]);
+        budget_system.close_with_reason(approved, &Resolution::Approved).unwrap();
+        create_test_proposal(&mut budget_system, "Still Open", vec![5.0]);
+
+        let csv = budget_system.export_delimited(ExportKind::Unpaid, None, ExportFormat::Csv, None).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Title,Team,Epoch,Amounts"));
+        assert!(lines[1].starts_with("\"Approved, Unpaid\",,Test Epoch,\"100 ETH0, 1000 ETH1\","));
+
+        let tsv = budget_system.export_delimited(ExportKind::Unpaid, Some("Test Epoch"), ExportFormat::Tsv, None).unwrap();
+        assert!(tsv.lines().nth(1).unwrap().starts_with("Approved, Unpaid\t\tTest Epoch\t100 ETH0, 1000 ETH1\t"));
+
+        let proposals = budget_system.export_delimited(ExportKind::Proposals, None, ExportFormat::Tsv, None).unwrap();
+        assert_eq!(proposals.lines().count(), 3);
+        assert!(proposals.contains("Still Open"));
+
+        // Payments need a closed epoch
+        assert!(budget_system.export_delimited(ExportKind::Payments, None, ExportFormat::Csv, None).is_err());
+        assert!(budget_system.export_delimited(ExportKind::Payments, Some("Test Epoch"), ExportFormat::Csv, None).is_err());
+        assert!(budget_system.export_delimited(ExportKind::Proposals, Some("Missing"), ExportFormat::Csv, None).is_err());
+
+        let output_path = temp_dir.path().join("exports").join("unpaid.tsv");
+        let output = budget_system.execute_command(Command::Export {
+            kind: ExportKind::Unpaid,
+            epoch_name: None,
+            format: ExportFormat::Tsv,
+            output_path: Some(output_path.to_str().unwrap().to_string()),
+        }).await.unwrap();
+        assert!(output.contains("Exported 1 row(s)"));
+        assert_eq!(fs::read_to_string(&output_path).unwrap(), tsv);
+    }
+
+    #[tokio::test]
+    async fn test_reviewer_sign_off_gate() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team1".to_string(), "Rep1".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+
+        budget_system.execute_command(Command::SetProposalReviewers {
+            proposal_name: "Test Proposal".to_string(),
+            reviewers: vec!["Alice".to_string(), "Bob".to_string()],
+        }).await.unwrap();
+
+        let state = budget_system.print_epoch_state().unwrap();
+        assert!(state.contains("0/2 signed off \\(pending: Alice, Bob\\)"));
+
+        budget_system.config.require_reviewer_sign_off = true;
+        let err = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap_err();
+        assert!(err.to_string().contains("Alice, Bob"));
+
+        let output = budget_system.execute_command(Command::RecordReviewerSignOff {
+            proposal_name: "Test Proposal".to_string(),
+            reviewer: "Alice".to_string(),
+            signed_off_at: None,
+        }).await.unwrap();
+        assert!(output.contains("Pending reviewers: Bob"));
+        assert!(budget_system.create_formal_vote(proposal_id, raffle_id, None, None).is_err());
+
+        let result = budget_system.execute_command(Command::RecordReviewerSignOff {
+            proposal_name: "Test Proposal".to_string(),
+            reviewer: "Mallory".to_string(),
+            signed_off_at: None,
+        }).await;
+        assert!(result.is_err());
+
+        budget_system.record_reviewer_sign_off("Test Proposal", "Bob", Utc::now().date_naive()).unwrap();
+        assert!(budget_system.create_formal_vote(proposal_id, raffle_id, None, None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reviewers_not_required_by_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-                let mut output = String::new();
-                pin_mut!(progress_stream);
-                
-                while let Some(progress) = progress_stream.next().await {
-                    match progress {
-                        Ok(progress) => {
-                            output.push_str(&format!("{}\n", progress.format_message()));
-                            if progress.is_complete() {
-                                break;
-                            }
-                        },
-                        Err(e) => return Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, e.0))),
-                    }
-                }
-                
-                Ok(output)
-            },
-            Command::CreateAndProcessVote { proposal_name, counted_votes, uncounted_votes, vote_opened, vote_closed } => {
-                let mut output = format!("Executing CreateAndProcessVote command for proposal: {}\n", proposal_name);
-                
-                match self.create_and_process_vote(
-                    &proposal_name,
-                    counted_votes,
-                    uncounted_votes,
-                    vote_opened,
-                    vote_closed
-                ) {
-                    Ok(report) => {
-                        output += &format!("Vote processed successfully for proposal: {}\n", proposal_name);
-                        output += &format!("Vote report:\n{}\n", report);
-                    
-                        // Print point credits
-                        if let Some(vote_id) = self.state().votes().values()
-                            .find(|v| v.proposal_id() == self.get_proposal_id_by_name(&proposal_name).unwrap())
-                            .map(|v| v.id())
-                        {
-                            let vote = self.state().votes().get(&vote_id).unwrap();
-                            
-                            output += "\nPoints credited:\n";
-                            if let VoteParticipation::Formal { counted, uncounted } = &vote.participation() {
-                                for &team_id in counted {
-                                    if let Some(team) = self.state().current_state().teams().get(&team_id) {
-                                        output += &format!("  {} (+{} points)\n", team.name(), self.config.counted_vote_points);
-                                    }
-                                }
-                                for &team_id in uncounted {
-                                    if let Some(team) = self.state().current_state().teams().get(&team_id) {
-                                        output += &format!("  {} (+{} points)\n", team.name(), self.config.uncounted_vote_points);
-                                    }
-                                }
-                            }
-                        } else {
-                            output += "Warning: Vote not found after processing\n";
-                        }
-                    },
-                    Err(e) => {
-                        output += &format!("Error: Failed to process vote for proposal '{}'. Reason: {}\n", proposal_name, e);
-                    }
-                }
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team1".to_string(), "Rep1".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        budget_system.set_proposal_reviewers("Test Proposal", vec!["Alice".to_string()]).unwrap();
 
-                Ok(output)
-            },
-            Command::GenerateReportsForClosedProposals { epoch_name } => {
-                let epoch_id = self.get_epoch_id_by_name(&epoch_name)
-                    .ok_or_else(|| format!("Epoch not found: {}", epoch_name))?;
-                
-                let closed_proposals: Vec<_> = self.get_proposals_for_epoch(epoch_id)
-                    .into_iter()
-                    .filter(|p| p.is_closed())
-                    .collect();
+        assert!(budget_system.create_formal_vote(proposal_id, raffle_id, None, None).is_ok());
+    }
 
-                let mut report = String::new();
-                for proposal in closed_proposals {
-                    match self.generate_and_save_proposal_report(proposal.id(), &epoch_name) {
-                        Ok(file_path) => report.push_str(&format!("Report generated for proposal '{}' at {:?}\n", proposal.title(), file_path)),
-                        Err(e) => report.push_str(&format!("Failed to generate report for proposal '{}': {}\n", proposal.title(), e)),
-                    }
-                }
-                Ok(report)
-            },
-            Command::GenerateReportForProposal { proposal_name } => {
-                let current_epoch = self.get_current_epoch()
-                    .ok_or("No active epoch")?;
-                
-                let proposal = self.get_proposals_for_epoch(current_epoch.id())
-                    .into_iter()
-                    .find(|p| p.name_matches(&proposal_name))
-                    .ok_or_else(|| format!("Proposal not found in current epoch: {}", proposal_name))?;
+    #[tokio::test]
+    async fn test_vote_change_requests() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-                match self.generate_and_save_proposal_report(proposal.id(), &current_epoch.name()) {
-                    Ok(file_path) => Ok(format!("Report generated for proposal '{}' at {:?}", proposal.title(), file_path)),
-                    Err(e) => Err(format!("Failed to generate report for proposal '{}': {}", proposal.title(), e).into()),
-                }
-            },
-            Command::PrintPointReport { epoch_name } => {
-                self.generate_point_report(epoch_name.as_deref())
-                    .map_err(|e| Box::new(BudgetSystemError(e.to_string())) as Box<dyn Error>)
-            },
-            Command::CloseEpoch { epoch_name } => {
-                self.close_epoch(epoch_name.as_deref())?;
-                Ok(format!("Successfully closed epoch: {}", epoch_name.unwrap_or_else(|| "Active epoch".to_string())))
-            },
-            Command::GenerateEndOfEpochReport { epoch_name } => {
-                self.generate_end_of_epoch_report(&epoch_name)?;
-                Ok(format!("Generated End of Epoch Report for epoch: {}", epoch_name))
-            },
-            Command::RunScript { .. } => {
-                Err("RunScript command should be handled by the CLI, not the BudgetSystem".into())
-            },
-            Command::GenerateUnpaidRequestsReport { output_path, epoch_name } => {
-                self.generate_unpaid_requests_report(
-                    output_path.as_deref(),
-                    epoch_name.as_deref()
-                ).map(|s| format!("{}\n", s))
-            },
-            Command::LogPayment { payment_tx, payment_date, proposal_names } => {
-                self.record_payments(&payment_tx, payment_date, &proposal_names)
-            },
-            Command::GenerateEpochPaymentsReport { epoch_name, output_path } => {
-                self.generate_epoch_payments_report(&epoch_name, output_path.as_deref())
-            },
+        create_active_epoch(&mut budget_system).await;
+        let team_id = budget_system.create_team("Team1".to_string(), "Rep1".to_string(), Some(vec![1000]), None).unwrap();
+        let (_, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let proposal_id = budget_system.get_proposal_id_by_name("Test Proposal").unwrap();
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::No)]).unwrap();
+
+        // Disabled by default
+        assert!(budget_system.request_vote_change(vote_id, team_id, VoteChoice::Yes, "Misread").is_err());
+
+        budget_system.config.allow_vote_changes = true;
+        budget_system.execute_command(Command::RequestVoteChange {
+            vote_id,
+            team_name: "Team1".to_string(),
+            new_choice: VoteChoice::Yes,
+            reason: "Misread the proposal".to_string(),
+        }).await.unwrap();
+        assert_eq!(budget_system.get_vote_change_requests(vote_id).unwrap().len(), 1);
+
+        let output = budget_system.execute_command(Command::ListVoteChangeRequests { vote_id }).await.unwrap();
+        assert!(output.contains("Team1 -> Yes"));
+        assert!(output.contains("Misread the proposal"));
+
+        budget_system.execute_command(Command::ApproveVoteChange {
+            vote_id,
+            team_name: "Team1".to_string(),
+        }).await.unwrap();
+        assert!(budget_system.get_vote_change_requests(vote_id).unwrap().is_empty());
+        assert!(budget_system.approve_vote_change(vote_id, team_id).is_err());
+
+        // Outside the change window
+        budget_system.config.vote_change_window_hours = 0;
+        let err = budget_system.request_vote_change(vote_id, team_id, VoteChoice::No, "Changed my mind").unwrap_err();
+        assert!(err.to_string().contains("window for changing this vote has passed"));
+
+        budget_system.close_vote(vote_id).unwrap();
+        let (counted, _) = budget_system.get_vote(&vote_id).unwrap().vote_counts().unwrap();
+        assert_eq!(counted.yes(), 1);
+        assert_eq!(counted.no(), 0);
+
+        assert!(budget_system.request_vote_change(vote_id, team_id, VoteChoice::No, "Too late").is_err());
+        assert!(budget_system.get_vote_change_requests(Uuid::new_v4()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_state_diff() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_test_epoch(&mut budget_system);
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), None, None).unwrap();
+        budget_system.save_state().unwrap();
+        let snapshot_a = temp_dir.path().join("snapshot_a.json");
+        fs::copy(&state_file, &snapshot_a).unwrap();
+
+        budget_system.create_team("Team B".to_string(), "Rep".to_string(), None, None).unwrap();
+        create_test_proposal(&mut budget_system, "New Proposal", vec![100.0]);
+        budget_system.save_state().unwrap();
+
+        let diff = BudgetSystem::generate_state_diff(&snapshot_a, Path::new(&state_file)).unwrap();
+        assert_eq!(diff.added_teams, vec!["Team B".to_string()]);
+        assert_eq!(diff.added_proposals, vec!["New Proposal".to_string()]);
+        assert_eq!(diff.modified_epochs.len(), 1);
+        assert!(diff.modified_epochs[0].1.contains("associated_proposals"));
+
+        let output = budget_system.execute_command(Command::DiffStateFiles {
+            path_a: snapshot_a.to_str().unwrap().to_string(),
+            path_b: state_file.clone(),
+        }).await.unwrap();
+        assert!(output.contains("+ Team B"));
+        assert!(output.contains("+ New Proposal"));
+
+        let output = budget_system.execute_command(Command::DiffStateFiles {
+            path_a: state_file.clone(),
+            path_b: state_file.clone(),
+        }).await.unwrap();
+        assert!(output.starts_with("No differences"));
+
+        assert!(BudgetSystem::generate_state_diff(Path::new("missing.json"), Path::new(&state_file)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_similar_proposal_detection() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        budget_system.config.allow_similar_proposals = false;
+        let epoch_id = create_active_epoch(&mut budget_system).await;
+
+        let existing_id = budget_system.add_proposal("Marketing Budget Q3".to_string(), None, None, None, None, None, None).unwrap();
+
+        // Exact match, ignoring case
+        let err = budget_system.add_proposal("marketing budget q3".to_string(), None, None, None, None, None, None).unwrap_err();
+        assert_eq!(err.to_string(), "Similar proposal already exists: Marketing Budget Q3");
+
+        // High similarity
+        let similar = budget_system.find_similar_proposals("Marketing Budgte Q3", epoch_id, SIMILAR_PROPOSAL_THRESHOLD);
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].id(), existing_id);
+        assert!(budget_system.add_proposal("Marketing Budgte Q3".to_string(), None, None, None, None, None, None).is_err());
+
+        let output = budget_system.execute_command(Command::CheckDuplicateProposal {
+            title: "Marketing Budget Q3 ".to_string(),
+        }).await.unwrap();
+        assert!(output.contains("Marketing Budget Q3 (100% similar)"));
+
+        // Low similarity
+        assert!(budget_system.find_similar_proposals("Security Audit", epoch_id, SIMILAR_PROPOSAL_THRESHOLD).is_empty());
+        budget_system.add_proposal("Security Audit".to_string(), None, None, None, None, None, None).unwrap();
+        let output = budget_system.execute_command(Command::CheckDuplicateProposal {
+            title: "Infrastructure Grant".to_string(),
+        }).await.unwrap();
+        assert!(output.starts_with("No proposals similar"));
+
+        // Non-strict mode only warns
+        budget_system.config.allow_similar_proposals = true;
+        budget_system.add_proposal("Marketing Budget Q3.".to_string(), None, None, None, None, None, None).unwrap();
+        budget_system.config.allow_similar_proposals = false;
+
+        // The same title in a later epoch is allowed
+        for title in ["Marketing Budget Q3", "Security Audit", "Marketing Budget Q3."] {
+            let id = budget_system.get_proposal_id_by_name(title).unwrap();
+            budget_system.close_with_reason(id, &Resolution::Rejected).unwrap();
         }
+        budget_system.close_epoch(None).unwrap();
+        let start = Utc::now() + Duration::days(31);
+        let next_epoch = budget_system.create_epoch("Next Epoch", start, start + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(next_epoch).unwrap();
+        assert!(budget_system.add_proposal("Marketing Budget Q3".to_string(), None, None, None, None, None, None).is_ok());
     }
 
-    async fn execute_command_with_streaming<W: Write + Send + 'static>(
-        &mut self, 
-        command: Command, 
-        output: &mut W
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        match command {
-            Command::CreateRaffle { proposal_name, block_offset, excluded_teams } => {
-                let progress_stream = self.create_raffle_with_progress(
-                    proposal_name,
-                    block_offset,
-                    excluded_teams,
-                ).await;
-                
-                pin_mut!(progress_stream);
-                
-                while let Some(progress) = progress_stream.next().await {
-                    match progress {
-                        Ok(progress) => {
-                            writeln!(output, "{}", progress.format_message())?;
-                            output.flush()?;
-                            if progress.is_complete() {
-                                break;
-                            }
-                        },
-                        Err(e) => return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other, 
-                            e.0
-                        ))),
-                    }
-                }
-                Ok(())
-            },
-            // For commands that don't support streaming, fall back to the original implementation
-            _ => {
-                let result = self.execute_command(command).await?;
-                write!(output, "{}", result)?;
-                Ok(())
-            }
+    #[tokio::test]
+    async fn test_verify_raffle() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        for name in ["Team A", "Team B"] {
+            budget_system.create_team(name.to_string(), "Rep".to_string(), None, None).unwrap();
+        }
+        let (_, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+
+        let verification = budget_system.verify_raffle(raffle_id).unwrap();
+        assert!(verification.matches);
+        assert!(verification.discrepancies.is_empty());
+        let output = budget_system.execute_command(Command::VerifyRaffle {
+            proposal_name: "Test Proposal".to_string(),
+            on_chain: false,
+        }).await.unwrap();
+        assert!(output.contains("Verified"));
+
+        budget_system.override_raffle_seat(raffle_id, "Team A", SeatOutcome::Uncounted, "Recused").unwrap();
+        let verification = budget_system.verify_raffle(raffle_id).unwrap();
+        assert!(!verification.matches);
+        assert_eq!(verification.discrepancies, vec!["Team A: recorded as uncounted, replay gives counted".to_string()]);
+
+        let output = budget_system.execute_command(Command::VerifyRaffle {
+            proposal_name: "Test Proposal".to_string(),
+            on_chain: false,
+        }).await.unwrap();
+        assert!(output.contains("Mismatch"));
+        assert!(output.contains("1 manual seat override(s)"));
+
+        assert!(matches!(budget_system.verify_raffle(Uuid::new_v4()), Err(BudgetSystemError::RaffleNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_raffle_audit_report_and_on_chain_verification() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![4000]), None).unwrap();
+        budget_system.create_team("Team B".to_string(), "Rep".to_string(), None, None).unwrap();
+        budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
+        // The mock service reports this randomness for block 12355
+        budget_system.finalize_raffle(raffle_id, 12345, 12355, "mock_randomness_for_block_12355".to_string()).await.unwrap();
+
+        let report = budget_system.generate_raffle_audit_report(raffle_id).unwrap();
+        assert!(report.starts_with("# Raffle Audit: Test Proposal"));
+        assert!(report.contains("- **Randomness Block**: [12355](https://etherscan.io/block/12355#consensusinfo)"));
+        assert!(report.contains("- **Block Randomness**: `mock_randomness_for_block_12355`"));
+        assert!(report.contains("| Team A | Earner | 2 | 0 - 1 |"));
+        assert!(report.contains("| Team B | Supporter | 1 | 2 - 2 |"));
+        assert!(report.contains("`mock_randomness_for_block_12355_2`"));
+        assert!(report.contains("### Counted (2)"));
+
+        let output = budget_system.execute_command(Command::GenerateRaffleAuditReport {
+            proposal_name: "Test Proposal".to_string(),
+        }).await.unwrap();
+        assert_eq!(output, report);
+
+        assert!(budget_system.verify_raffle_randomness_on_chain(raffle_id).await.unwrap());
+        let output = budget_system.execute_command(Command::VerifyRaffle {
+            proposal_name: "Test Proposal".to_string(),
+            on_chain: true,
+        }).await.unwrap();
+        assert!(output.contains("On-chain: the block's randomness matches"));
+
+        // A raffle whose stored randomness differs from the chain fails the check
+        let (_, other_raffle) = create_proposal_with_raffle(&mut budget_system, "Other Proposal").await;
+        assert!(!budget_system.verify_raffle_randomness_on_chain(other_raffle).await.unwrap());
+        assert!(matches!(
+            budget_system.verify_raffle_randomness_on_chain(Uuid::new_v4()).await,
+            Err(BudgetSystemError::RaffleNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_override_raffle_seat() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        for name in ["Team A", "Team B"] {
+            budget_system.create_team(name.to_string(), "Rep".to_string(), None, None).unwrap();
+        }
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let team_a = budget_system.get_team_id_by_name("Team A").unwrap();
+
+        assert!(budget_system.override_raffle_seat(raffle_id, "Unknown", SeatOutcome::Excluded, "x").is_err());
+        assert!(budget_system.override_raffle_seat(raffle_id, "Team A", SeatOutcome::Excluded, "").is_err());
+
+        let output = budget_system.execute_command(Command::ReassignRaffleSeats {
+            proposal_name: "Test Proposal".to_string(),
+            team_name: "Team A".to_string(),
+            new_outcome: SeatOutcome::Excluded,
+            reason: "Conflict of interest".to_string(),
+        }).await.unwrap();
+        assert!(output.contains("manually adjusted"));
+
+        let raffle = budget_system.get_raffle(&raffle_id).unwrap();
+        assert_eq!(raffle.seat_outcome(team_a), Some(SeatOutcome::Excluded));
+        assert_eq!(raffle.manual_overrides().len(), 1);
+        assert!(!raffle.deciding_teams().contains(&team_a));
+
+        let report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(report.contains("- **Manually Adjusted**: Yes"));
+        assert!(report.contains("### Manual Overrides"));
+        assert!(report.contains("| Team A | Counted | Excluded | Conflict of interest |"));
+
+        // Seats are locked once voting has started
+        budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        assert!(budget_system.override_raffle_seat(raffle_id, "Team A", SeatOutcome::Counted, "Resolved").is_err());
+        assert_eq!(budget_system.get_raffle(&raffle_id).unwrap().manual_overrides().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_epoch_budget_headroom() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        for (name, amounts) in [("Approved 1", vec![40.0, 500.0]), ("Approved 2", vec![80.0])] {
+            let id = create_test_proposal(&mut budget_system, name, amounts);
+            budget_system.state.get_proposal_mut(&id).unwrap().approve().unwrap();
+        }
+        // Open proposals do not count against the budget
+        create_test_proposal(&mut budget_system, "Open", vec![1000.0]);
+
+        let caps: HashMap<String, f64> = [("ETH0", 100.0), ("ETH1", 1000.0), ("USDC", 5000.0)]
+            .into_iter()
+            .map(|(token, cap)| (token.to_string(), cap))
+            .collect();
+        let headroom = budget_system.get_epoch_budget_headroom(epoch_id, caps).unwrap();
+        assert_eq!(headroom.len(), 3);
+        assert_eq!(headroom["ETH0"], -20.0);
+        assert_eq!(headroom["ETH1"], 500.0);
+        assert_eq!(headroom["USDC"], 5000.0);
+
+        assert!(budget_system.get_epoch_budget_headroom(Uuid::new_v4(), HashMap::new()).is_err());
+
+        let output = budget_system.execute_command(Command::PrintBudgetHeadroom {
+            epoch_name: "Test Epoch".to_string(),
+            caps: vec![("ETH0".to_string(), 100.0)],
+        }).await.unwrap();
+        assert!(output.contains("ETH0: -20.00 of 100.00 remaining (over budget)"));
+
+        // Without caps the command falls back to the configured ones
+        assert!(budget_system.execute_command(Command::PrintBudgetHeadroom {
+            epoch_name: "Test Epoch".to_string(),
+            caps: vec![],
+        }).await.is_err());
+
+        assert!(budget_system.get_epoch_state_report().unwrap().budget_headroom.is_empty());
+        budget_system.config.epoch_budget_caps.insert("ETH1".to_string(), 1000.0);
+        let report = budget_system.get_epoch_state_report().unwrap();
+        assert_eq!(report.budget_headroom, vec![("ETH1".to_string(), 500.0)]);
+        assert!(budget_system.print_epoch_state().unwrap().contains("Budget headroom"));
+    }
+
+    #[tokio::test]
+    async fn test_find_proposal_for_report_across_epochs() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let old_start = Utc::now() - Duration::days(60);
+        let old_epoch = budget_system.create_epoch("Old Epoch", old_start, old_start + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(old_epoch).unwrap();
+        let old_only = create_test_proposal(&mut budget_system, "Old Proposal", vec![10.0]);
+        let old_shared = create_test_proposal(&mut budget_system, "Shared Proposal", vec![10.0]);
+        budget_system.state.get_epoch_mut(&old_epoch).unwrap().set_status(EpochStatus::Closed);
+        budget_system.state.set_current_epoch(None);
+
+        create_test_epoch(&mut budget_system);
+        let current_shared = create_test_proposal(&mut budget_system, "Shared Proposal", vec![20.0]);
+
+        assert_eq!(budget_system.find_proposal_for_report("Old Proposal", None).unwrap(), (old_only, "Old Epoch".to_string()));
+        assert_eq!(budget_system.find_proposal_for_report("Shared Proposal", None).unwrap().0, current_shared);
+        assert_eq!(budget_system.find_proposal_for_report("Shared Proposal", Some("Old Epoch")).unwrap().0, old_shared);
+        assert!(budget_system.find_proposal_for_report("Old Proposal", Some("Test Epoch")).is_err());
+        assert!(budget_system.find_proposal_for_report("Missing", None).is_err());
+
+        // Without a current epoch to prefer, a title in two epochs is ambiguous
+        budget_system.state.set_current_epoch(None);
+        let err = budget_system.find_proposal_for_report("Shared Proposal", None).unwrap_err().to_string();
+        assert!(err.contains("Old Epoch, Test Epoch"));
+
+        let output = budget_system.execute_command(Command::GenerateReportForProposal {
+            proposal_name: "Shared Proposal".to_string(),
+            epoch_name: Some("Old Epoch".to_string()),
+            sections: None,
+        }).await.unwrap();
+        assert!(output.contains("Report generated for proposal 'Shared Proposal'"));
+        assert!(output.contains("Old_Epoch"));
+    }
+
+    #[tokio::test]
+    async fn test_team_address_at() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let old_address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+        let new_address = "0x1234567890123456789012345678901234567890";
+        let team_id = budget_system.create_team("Team A".to_string(), "Rep".to_string(), None, Some(old_address.to_string())).unwrap();
+        let before_rotation = Utc::now();
+
+        budget_system.update_team(team_id, UpdateTeamDetails {
+            name: None,
+            representative: None,
+            status: None,
+            trailing_monthly_revenue: None,
+            address: Some(new_address.to_string()),
+        }).unwrap();
+
+        assert_eq!(budget_system.team_address_at(team_id, before_rotation).unwrap(), Some(old_address.parse().unwrap()));
+        assert_eq!(budget_system.team_address_at(team_id, Utc::now()).unwrap(), Some(new_address.parse().unwrap()));
+        assert!(budget_system.team_address_at(Uuid::new_v4(), Utc::now()).is_err());
+
+        assert!(budget_system.print_team_report().contains("Payment Address History:"));
+    }
+
+    #[tokio::test]
+    async fn test_proposal_waiting_time_percentiles() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        assert!(budget_system.get_proposal_waiting_time_percentiles(Some(epoch_id)).is_err());
+
+        let announced = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        for (i, days) in [1, 2, 3, 4, 10].iter().enumerate() {
+            let id = create_test_proposal(&mut budget_system, &format!("Proposal {}", i), vec![10.0]);
+            let proposal = budget_system.state.get_proposal_mut(&id).unwrap();
+            proposal.set_dates(Some(announced), Some(announced), Some(announced + Duration::days(*days))).unwrap();
+            proposal.approve().unwrap();
+        }
+        // Unresolved proposals are left out
+        create_test_proposal(&mut budget_system, "Still Open", vec![10.0]);
+
+        let stats = budget_system.get_proposal_waiting_time_percentiles(Some(epoch_id)).unwrap();
+        assert_eq!(stats.sample_size, 5);
+        assert_eq!(stats.p50_days, 3.0);
+        assert_eq!(stats.p75_days, 4.0);
+        assert!((stats.p90_days - 7.6).abs() < 1e-9);
+        assert!((stats.p99_days - 9.76).abs() < 1e-9);
+        assert_eq!(budget_system.get_proposal_waiting_time_percentiles(None).unwrap(), stats);
+        assert!(budget_system.get_proposal_waiting_time_percentiles(Some(Uuid::new_v4())).is_err());
+
+        let output = budget_system.execute_command(Command::PrintProposalWaitingPercentiles {
+            epoch_name: Some("Test Epoch".to_string()),
+        }).await.unwrap();
+        assert!(output.contains("epoch 'Test Epoch' (5 resolved proposals)"));
+        assert!(output.contains("p50: 3.0 days"));
+    }
+
+    #[tokio::test]
+    async fn test_print_upcoming_raffle_eligibility() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        assert!(budget_system.print_upcoming_raffle_eligibility(None).is_err());
+        create_test_epoch(&mut budget_system);
+
+        budget_system.create_team("Big Earner".to_string(), "Rep".to_string(), Some(vec![16000, 16000, 16000]), None).unwrap();
+        budget_system.create_team("Small Earner".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Supporter".to_string(), "Rep".to_string(), None, None).unwrap();
+        budget_system.create_team("Skipped".to_string(), "Rep".to_string(), None, None).unwrap();
+        let raffles_before = budget_system.state().raffles().len();
+
+        let output = budget_system.execute_command(Command::PrintRaffleEligibility {
+            excluded_teams: Some(vec!["Skipped".to_string()]),
+        }).await.unwrap();
+        assert!(output.contains("Raffle eligibility for epoch 'Test Epoch' (7 counted seats, up to 5 for earners)"));
+        assert!(output.contains("Earners (2 teams, 5 tickets, 2 counted seats):"));
+        assert!(output.contains("  Big Earner: 4 ticket(s), 100.0% chance of a counted seat"));
+        assert!(output.contains("Supporters (1 teams, 1 tickets, 1 counted seats):"));
+        assert!(output.contains("Excluded: Skipped"));
+        assert_eq!(budget_system.state().raffles().len(), raffles_before);
+
+        // With a single earner seat the bigger earner is four times as likely to get it
+        budget_system.config.default_max_earner_seats = 1;
+        let output = budget_system.print_upcoming_raffle_eligibility(None).unwrap();
+        assert!(output.contains("  Big Earner: 4 ticket(s), 80.0% chance"));
+        assert!(output.contains("  Small Earner: 1 ticket(s), 20.0% chance"));
+
+        assert!(budget_system.print_upcoming_raffle_eligibility(Some(vec!["Nobody".to_string()])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_force_epoch_status() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        create_test_proposal(&mut budget_system, "Open Proposal", vec![10.0]);
+        let next_start = Utc::now() + Duration::days(31);
+        let next_id = budget_system.create_epoch("Next Epoch", next_start, next_start + Duration::days(30)).unwrap();
+
+        // Only one epoch may be active
+        assert!(budget_system.force_epoch_status("Next Epoch", EpochStatus::Active, true, false).is_err());
+        assert!(budget_system.force_epoch_status("Test Epoch", EpochStatus::Active, true, false).is_err());
+        assert!(budget_system.force_epoch_status("Missing", EpochStatus::Closed, true, false).is_err());
+
+        // Actionable proposals block a forced close unless forced
+        let err = budget_system.force_epoch_status("Test Epoch", EpochStatus::Closed, true, false).unwrap_err();
+        assert!(err.to_string().contains("1 actionable proposal(s)"));
+
+        // Without confirmation nothing changes
+        let preview = budget_system.execute_command(Command::SetEpochStatus {
+            epoch_name: "Test Epoch".to_string(),
+            status: EpochStatus::Closed,
+            confirm: false,
+            force: true,
+        }).await.unwrap();
+        assert!(preview.contains("WARNING"));
+        assert!(preview.contains("--confirm"));
+        assert_eq!(budget_system.get_epoch(&epoch_id).unwrap().status(), EpochStatus::Active);
+
+        let output = budget_system.force_epoch_status("Test Epoch", EpochStatus::Closed, true, true).unwrap();
+        assert!(output.contains("now Closed"));
+        assert!(budget_system.get_epoch(&epoch_id).unwrap().is_closed());
+        assert_eq!(budget_system.state().current_epoch(), None);
+
+        budget_system.force_epoch_status("Next Epoch", EpochStatus::Active, true, false).unwrap();
+        assert_eq!(budget_system.state().current_epoch(), Some(next_id));
+        assert!(budget_system.get_epoch(&next_id).unwrap().is_active());
+
+        budget_system.force_epoch_status("Next Epoch", EpochStatus::Planned, true, false).unwrap();
+        assert!(budget_system.get_epoch(&next_id).unwrap().is_planned());
+        assert_eq!(budget_system.state().current_epoch(), None);
+    }
+
+    #[tokio::test]
+    async fn test_approval_rates() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let earner = budget_system.create_team("Earner".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let supporter = budget_system.create_team("Supporter".to_string(), "Rep".to_string(), None, None).unwrap();
+
+        let old_start = Utc::now() - Duration::days(60);
+        let old_epoch = budget_system.create_epoch("Old Epoch", old_start, old_start + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(old_epoch).unwrap();
+        let add = |budget_system: &mut BudgetSystem, name: &str, team: Uuid, approve: bool| {
+            let id = create_test_proposal(budget_system, name, vec![10.0]);
+            let proposal = budget_system.state.get_proposal_mut(&id).unwrap();
+            let mut details = proposal.budget_request_details().unwrap().clone();
+            details.set_team(Some(team));
+            proposal.set_budget_request_details(Some(details));
+            if approve { proposal.approve().unwrap() } else { proposal.reject().unwrap() }
+        };
+        add(&mut budget_system, "E1", earner, true);
+        add(&mut budget_system, "E2", earner, true);
+        add(&mut budget_system, "S1", supporter, false);
+        budget_system.state.get_epoch_mut(&old_epoch).unwrap().set_status(EpochStatus::Closed);
+        budget_system.state.set_current_epoch(None);
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        add(&mut budget_system, "E3", earner, false);
+        add(&mut budget_system, "S2", supporter, true);
+        // Open proposals are not counted
+        create_test_proposal(&mut budget_system, "Open", vec![10.0]);
+
+        let by_status = budget_system.get_approval_rate_by_team_status(None).unwrap();
+        assert!((by_status["Earner"] - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(by_status["Supporter"], 0.5);
+
+        let trend = budget_system.get_approval_rate_by_epoch_recency(5).unwrap();
+        assert_eq!(trend.len(), 2);
+        assert_eq!(trend[0].0, "Old Epoch");
+        assert!((trend[0].1 - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(trend[1], ("Test Epoch".to_string(), 0.5));
+        assert_eq!(budget_system.get_approval_rate_by_epoch_recency(1).unwrap().len(), 1);
+        assert!(budget_system.get_approval_rate_by_epoch_recency(0).is_err());
+
+        let data = budget_system.generate_epoch_infographic_data(epoch_id).unwrap();
+        let epoch_by_status = HashMap::from([("Earner".to_string(), 0.0), ("Supporter".to_string(), 1.0)]);
+        assert_eq!(data.approval_rate_by_status, epoch_by_status);
+        assert_eq!(budget_system.get_approval_rate_by_team_status(Some(epoch_id)).unwrap(), epoch_by_status);
+        assert_eq!(data.approval_rate_trend, trend);
+
+        // Half participation, a full status gap and a falling approval rate
+        let point = VoteParticipationPoint {
+            proposal_title: "Vote".to_string(),
+            opened_at: Utc::now(),
+            counted_participants: 1,
+            uncounted_participants: 0,
+            total_eligible_seats: 2,
+            participation_rate: 0.5,
+        };
+        let score = BudgetSystem::governance_health_score(&[point.clone()], &epoch_by_status, &trend, &[]).unwrap();
+        let expected = 30.0 + 0.0 + 20.0 * (1.0 - (2.0 / 3.0 - 0.5));
+        assert!((score - expected).abs() < 1e-9);
+        assert!(BudgetSystem::governance_health_score(&[], &epoch_by_status, &trend, &[]).is_none());
+
+        // Each epoch of a current absence streak costs points, up to a cap
+        let absent = |team_name: &str, current_streak_epochs| TeamAbsenceInfo {
+            team_name: team_name.to_string(),
+            current_streak_epochs,
+            last_participated_epoch: None,
+            total_absences: current_streak_epochs,
+        };
+        let streaks = [absent("Earner", 2), absent("Supporter", 1)];
+        let score = BudgetSystem::governance_health_score(&[point.clone()], &epoch_by_status, &trend, &streaks).unwrap();
+        assert!((score - (expected - 3.0 * ABSENCE_STREAK_DEDUCTION)).abs() < 1e-9);
+        let streaks = [absent("Earner", 50)];
+        let score = BudgetSystem::governance_health_score(&[point], &epoch_by_status, &trend, &streaks).unwrap();
+        assert!((score - (expected - MAX_ABSENCE_STREAK_DEDUCTION)).abs() < 1e-9);
+        let data = budget_system.generate_epoch_infographic_data(old_epoch).unwrap();
+        assert_eq!(data.approval_rate_trend.len(), 1);
+
+        let output = budget_system.execute_command(Command::PrintApprovalRatesByStatus { recent_epochs: None }).await.unwrap();
+        assert!(output.contains("  Earner: 66.7%"));
+        assert!(output.contains("  Supporter: 50.0%"));
+        assert!(output.contains("  Test Epoch: 50.0%"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_proposal_report_sections() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        create_active_epoch(&mut budget_system).await;
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), None, None).unwrap();
+        let (proposal_id, _) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+
+        let full = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        for heading in ["## Summary", "## Proposal Details", "## Raffle Information", "## Voting Information", "## Revision History"] {
+            assert!(full.contains(heading), "missing {}", heading);
         }
+
+        let partial = budget_system.generate_proposal_report(proposal_id, ReportSections::RAFFLE | ReportSections::VOTING).unwrap();
+        assert!(partial.starts_with("# Proposal Report: Test Proposal"));
+        assert!(partial.contains("## Raffle Information"));
+        assert!(partial.contains("### Team Snapshots"));
+        assert!(partial.contains("## Voting Information"));
+        assert!(!partial.contains("## Summary"));
+        assert!(!partial.contains("## Proposal Details"));
+        assert!(!partial.contains("## Revision History"));
+
+        let output = budget_system.execute_command(Command::GenerateReportForProposal {
+            proposal_name: "Test Proposal".to_string(),
+            epoch_name: None,
+            sections: Some("raffle".to_string()),
+        }).await.unwrap();
+        assert!(output.contains("Report generated"));
+
+        assert!(budget_system.execute_command(Command::GenerateReportForProposal {
+            proposal_name: "Test Proposal".to_string(),
+            epoch_name: None,
+            sections: Some("appendix".to_string()),
+        }).await.is_err());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::{Utc, Duration};
-    use std::sync::Arc;
-    use tempfile::TempDir;
-    use uuid::Uuid;
-    use futures::pin_mut;
-    use crate::app_config::TelegramConfig;
-    use crate::services::ethereum::MockEthereumService;
-    use tokio::time::Duration as Dur;
+    #[tokio::test]
+    async fn test_ping_absent_voters() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-    // Helpers
+        create_active_epoch(&mut budget_system).await;
+        let team1 = budget_system.create_team("Team1".to_string(), "Rep1".to_string(), Some(vec![1000]), None).unwrap();
+        let team2 = budget_system.create_team("Team2".to_string(), "Rep2".to_string(), Some(vec![2000]), None).unwrap();
+        let team3 = budget_system.create_team("Team3".to_string(), "Rep3".to_string(), None, None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team1, VoteChoice::Yes)]).unwrap();
 
-    async fn create_test_budget_system(state_file: &str, initial_state: Option<BudgetSystemState>) -> BudgetSystem {
-        let config = AppConfig {
-            state_file: state_file.to_string(),
-            ipc_path: "/tmp/test_reth.ipc".to_string(),
-            future_block_offset: 10,
-            script_file: "test_script.json".to_string(),
-            default_total_counted_seats: 7,
-            default_max_earner_seats: 5,
-            default_qualified_majority_threshold: 0.7,
-            counted_vote_points: 5,
-            uncounted_vote_points: 2,
-            telegram: TelegramConfig {
-                chat_id: "test_chat_id".to_string(),
-                token: "test_token".to_string(),
-            },
-        };
-        let ethereum_service = Arc::new(MockEthereumService::new());
-        BudgetSystem::new(config, ethereum_service, initial_state).await.unwrap()
+        assert_eq!(budget_system.pending_voters(vote_id).unwrap(), vec![team2, team3]);
+
+        budget_system.execute_command(Command::SetTeamContacts {
+            team_name: "Team2".to_string(),
+            contacts: vec!["@team2_lead".to_string(), "team2_ops".to_string()],
+        }).await.unwrap();
+        assert_eq!(budget_system.get_team(&team2).unwrap().contacts(), &["team2_lead".to_string(), "team2_ops".to_string()]);
+
+        // Disabled unless explicitly allowed
+        let command = Command::PingAbsentVoters { proposal_name: "Test Proposal".to_string() };
+        assert!(budget_system.execute_command(command.clone()).await.is_err());
+
+        budget_system.config.telegram.allow_absent_voter_pings = true;
+        let output = budget_system.execute_command(command.clone()).await.unwrap();
+        assert!(output.contains("@team2_lead @team2_ops (Team2)"));
+        assert!(!output.contains("@Team1"));
+        assert!(output.contains("No Telegram contact on record for: Team3"));
+
+        budget_system.cast_votes(vote_id, vec![(team2, VoteChoice::No)]).unwrap();
+        assert_eq!(budget_system.absent_voter_ping_message("Test Proposal").unwrap(), None);
+
+        budget_system.close_vote(vote_id).unwrap();
+        assert!(budget_system.execute_command(command).await.is_err());
     }
 
-    async fn create_active_epoch(budget_system: &mut BudgetSystem) -> Uuid {
-        let start_date = Utc::now();
-        let end_date = start_date + Duration::days(30);
-        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
-        budget_system.activate_epoch(epoch_id).unwrap();
-        epoch_id
+    #[tokio::test]
+    async fn test_reopen_proposal() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let proposal_id = create_test_proposal(&mut budget_system, "Scope Change", vec![100.0]);
+
+        // Unknown and still-open proposals
+        assert!(budget_system.reopen_proposal("Missing").is_err());
+        let err = budget_system.reopen_proposal("Scope Change").unwrap_err();
+        assert!(err.to_string().contains("is not closed"));
+
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        let output = budget_system.execute_command(Command::SetProposalStatus {
+            proposal_name: "Scope Change".to_string(),
+            status: ProposalStatus::Reopened,
+        }).await.unwrap();
+        assert!(output.contains("Reopened proposal 'Scope Change'"));
+        let proposal = budget_system.get_proposal(&proposal_id).unwrap();
+        assert!(proposal.is_reopened());
+        assert!(proposal.is_actionable());
+        assert_eq!(proposal.resolution(), None);
+
+        // Closing through this command is not supported
+        assert!(budget_system.execute_command(Command::SetProposalStatus {
+            proposal_name: "Scope Change".to_string(),
+            status: ProposalStatus::Closed,
+        }).await.is_err());
+
+        // Paid proposals stay closed
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        budget_system.state.get_proposal_mut(&proposal_id).unwrap()
+            .budget_request_details_mut().unwrap()
+            .record_payment(format!("0x{}", "ab".repeat(32)), Utc::now().date_naive()).unwrap();
+        let err = budget_system.reopen_proposal("Scope Change").unwrap_err();
+        assert!(err.to_string().contains("already paid"));
+
+        // Closed epoch
+        let other_id = create_test_proposal(&mut budget_system, "Other", vec![50.0]);
+        budget_system.close_with_reason(other_id, &Resolution::Rejected).unwrap();
+        budget_system.state.get_epoch_mut(&epoch_id).unwrap().set_status(EpochStatus::Closed);
+        let err = budget_system.reopen_proposal("Other").unwrap_err();
+        assert!(err.to_string().contains("epoch 'Test Epoch' is closed"));
+        budget_system.state.get_epoch_mut(&epoch_id).unwrap().set_status(EpochStatus::Active);
+
+        // Closed vote must be reopened first
+        let voted_id = create_test_proposal(&mut budget_system, "Voted", vec![25.0]);
+        let vote_id = budget_system.create_informal_vote(voted_id).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        budget_system.close_with_reason(voted_id, &Resolution::Rejected).unwrap();
+        let err = budget_system.reopen_proposal("Voted").unwrap_err();
+        assert!(err.to_string().contains("closed vote"));
+        assert!(budget_system.get_proposal(&voted_id).unwrap().is_closed());
+
+        let output = budget_system.execute_command(Command::ReopenVote { proposal_name: "Voted".to_string() }).await.unwrap();
+        assert!(output.contains(&format!("Reopened vote {}", vote_id)));
+        let vote = budget_system.state.get_vote(&vote_id).unwrap();
+        assert!(!vote.is_closed());
+        assert!(vote.result().is_none());
+        assert!(budget_system.reopen_vote("Voted").unwrap_err().to_string().contains("no closed vote"));
+        let proposal = budget_system.get_proposal(&voted_id).unwrap();
+        assert!(proposal.is_reopened());
+        assert_eq!(proposal.resolution(), None);
     }
 
-    async fn create_proposal_with_raffle(budget_system: &mut BudgetSystem, proposal_name: &str) -> (Uuid, Uuid) {
-        let proposal_id = budget_system.add_proposal(
-            proposal_name.to_string(),
-            None,
-            None,
-            Some(Utc::now().date_naive()),
-            Some(Utc::now().date_naive()),
-            None
-        ).unwrap();
-    
-        let config = budget_system.config().clone();
-        let (raffle_id, _) = budget_system.prepare_raffle(proposal_name, None, &config).unwrap();
-        budget_system.finalize_raffle(
-            raffle_id,
-            12345,
-            12355,
-            "mock_randomness".to_string()
-        ).await.unwrap();
-    
-        (proposal_id, raffle_id)
+    #[tokio::test]
+    async fn test_reopen_vote_then_close_again() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        budget_system.config.default_qualified_majority_threshold = 0.1;
+        create_test_epoch(&mut budget_system);
+        let mut teams = Vec::new();
+        for name in ["Team A", "Team B"] {
+            teams.push(budget_system.create_team(name.to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap());
+        }
+
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Revote").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, teams.iter().map(|&id| (id, VoteChoice::Yes)).collect()).unwrap();
+        assert!(budget_system.close_vote_and_update_proposal(vote_id, proposal_id, None).unwrap());
+        assert!(budget_system.get_proposal(&proposal_id).unwrap().is_approved());
+
+        // A closed proposal can't be resolved again, and the vote stays open
+        let other_id = create_test_proposal(&mut budget_system, "Closed Early", vec![10.0]);
+        let informal_id = budget_system.create_informal_vote(other_id).unwrap();
+        budget_system.close_with_reason(other_id, &Resolution::Approved).unwrap();
+        assert!(budget_system.close_vote_and_update_proposal(informal_id, other_id, None).is_err());
+        assert!(!budget_system.state.get_vote(&informal_id).unwrap().is_closed());
+
+        budget_system.reopen_vote("Revote").unwrap();
+        assert!(budget_system.get_proposal(&proposal_id).unwrap().is_actionable());
+        assert!(budget_system.get_pending_payments().iter().all(|p| p.title != "Revote"));
+
+        budget_system.cast_votes(vote_id, teams.iter().map(|&id| (id, VoteChoice::No)).collect()).unwrap();
+        assert!(!budget_system.close_vote_and_update_proposal(vote_id, proposal_id, None).unwrap());
+        assert!(budget_system.state.get_vote(&vote_id).unwrap().is_closed());
+        assert!(budget_system.get_proposal(&proposal_id).unwrap().is_rejected());
     }
 
-    fn get_mock_service(budget_system: &BudgetSystem) -> Option<Arc<MockEthereumService>> {
-        budget_system.ethereum_service()
-            .clone() // Clone the Arc before downcasting
-            .downcast_arc::<MockEthereumService>()
-            .ok()
+    #[tokio::test]
+    async fn test_epoch_burndown() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let start = budget_system.get_epoch(&epoch_id).unwrap().start_date().date_naive();
+        let day1 = start + chrono::Duration::days(1);
+        let day3 = start + chrono::Duration::days(3);
+
+        let first = create_test_proposal(&mut budget_system, "First", vec![40.0]);
+        let second = create_test_proposal(&mut budget_system, "Second", vec![30.0]);
+        let rejected = create_test_proposal(&mut budget_system, "Rejected", vec![500.0]);
+        for (id, resolved) in [(first, day1), (second, day3)] {
+            let proposal = budget_system.state.get_proposal_mut(&id).unwrap();
+            proposal.approve().unwrap();
+            proposal.set_resolved_at(Some(resolved));
+        }
+        budget_system.state.get_proposal_mut(&rejected).unwrap().reject().unwrap();
+        budget_system.state.get_proposal_mut(&first).unwrap()
+            .budget_request_details_mut().unwrap()
+            .record_payment(format!("0x{}", "ab".repeat(32)), day3).unwrap();
+
+        let caps = HashMap::from([("ETH0".to_string(), 100.0)]);
+        let points = budget_system.epoch_burndown("Test Epoch", &caps).unwrap();
+        let rows: Vec<(NaiveDate, f64, f64, f64)> = points.iter()
+            .map(|p| (p.date, p.approved_cumulative, p.paid_cumulative, p.remaining))
+            .collect();
+        assert_eq!(rows, vec![
+            (start, 0.0, 0.0, 100.0),
+            (day1, 40.0, 0.0, 60.0),
+            (day3, 70.0, 40.0, 30.0),
+        ]);
+
+        assert!(budget_system.epoch_burndown("Missing", &caps).is_err());
+
+        let export_path = temp_dir.path().join("charts").join("burndown.json");
+        let output = budget_system.execute_command(Command::ShowBudgetBurndown {
+            epoch_name: "Test Epoch".to_string(),
+            caps: vec![("ETH0".to_string(), 100.0)],
+            output_path: Some(export_path.to_str().unwrap().to_string()),
+        }).await.unwrap();
+        assert!(output.contains(&format!("| {} | ETH0 | 70.00 | 40.00 | 30.00 |", day3)));
+        let exported: Vec<BurndownPoint> = serde_json::from_str(&fs::read_to_string(&export_path).unwrap()).unwrap();
+        assert_eq!(exported, points);
+
+        // Without caps the command falls back to the configured ones
+        assert!(budget_system.execute_command(Command::ShowBudgetBurndown {
+            epoch_name: "Test Epoch".to_string(),
+            caps: Vec::new(),
+            output_path: None,
+        }).await.is_err());
     }
 
-    async fn setup_block_progression(mock_service: Arc<MockEthereumService>) {
-        let service = mock_service.clone();
-        tokio::spawn(async move {
-            for _ in 0..5 {
-                service.increment_block();
-                tokio::time::sleep(Dur::from_millis(100)).await;
-            }
-        });
+    #[tokio::test]
+    async fn test_generate_all_epochs_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let team_id = budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let today = Utc::now().date_naive();
+
+        // Multi-token request, including a stablecoin, approved and paid
+        let funded = create_test_proposal(&mut budget_system, "Funded", vec![100.0, 50.0]);
+        let proposal = budget_system.state.get_proposal_mut(&funded).unwrap();
+        let details = proposal.budget_request_details_mut().unwrap();
+        details.set_team(Some(team_id));
+        details.add_request_amount("USDC".to_string(), 1000.0).unwrap();
+        details.record_payment(format!("0x{}", "ab".repeat(32)), today + chrono::Duration::days(6)).unwrap();
+        proposal.approve().unwrap();
+        proposal.set_resolved_at(Some(today + chrono::Duration::days(4)));
+
+        let rejected = create_test_proposal(&mut budget_system, "Rejected", vec![10.0]);
+        budget_system.state.get_proposal_mut(&rejected).unwrap().reject().unwrap();
+
+        // A formal vote earns Team A points for the epoch
+        let (voted, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Voted").await;
+        let vote_id = budget_system.create_formal_vote(voted, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+
+        budget_system.state.get_epoch_mut(&epoch_id).unwrap().set_status(EpochStatus::Closed);
+        budget_system.state.set_current_epoch(None);
+
+        // An epoch without proposals
+        let start = Utc::now() + chrono::Duration::days(60);
+        budget_system.create_epoch("Empty Epoch", start, start + chrono::Duration::days(30)).unwrap();
+
+        // Reward configured on the closed epoch only
+        budget_system.state.get_epoch_mut(&epoch_id).unwrap().set_reward("ETH".to_string(), 10.0).unwrap();
+
+        let report = budget_system.generate_all_epochs_report(false).unwrap();
+        assert!(report.contains("covering 2 epoch(s)"));
+        assert!(report.contains("- **Total Proposals**: 3"));
+        assert!(report.contains("- **Approved**: 1"));
+        assert!(report.contains("- **Rejected**: 1"));
+        assert!(report.contains("- **Unresolved**: 1"));
+        assert!(report.contains("- **Approval Rate**: 50.0%"));
+        assert!(report.contains("- **Average Resolution Time**: 4.0 days"));
+        assert!(report.contains("- **Average Payment Time**: 2.0 days"));
+        assert!(report.contains("| ETH0 | 110.00 | 100.00 | 100.00 | 0.00 |"));
+        assert!(report.contains("| ETH1 | 50.00 | 50.00 | 50.00 | 0.00 |"));
+        assert!(report.contains("| USDC | 1000.00 | 1000.00 | 1000.00 | 0.00 |"));
+        assert!(report.contains("| 10 ETH | 3 | 1 | 1 | 1 | 50.0% | 1 |"));
+        assert!(report.contains("| Team A | 5 | Test Epoch: 5 |"));
+        assert!(report.contains("| Empty Epoch | Planned |"));
+        assert!(report.contains("| N/A | 0 | 0 | 0 | 0 | N/A | 0 |"));
+
+        // Stablecoins share one column, other tokens keep their own
+        assert!(report.contains("| Epoch | Measure | Stables | ETH0 | ETH1 |"));
+        assert!(report.contains("| Test Epoch | Requested | 1000.00 | 110.00 | 50.00 |"));
+        assert!(report.contains("| Test Epoch | Allocated | 1000.00 | 100.00 | 50.00 |"));
+        assert!(report.contains("| Empty Epoch | Paid | 0.00 | 0.00 | 0.00 |"));
+
+        let stablecoins = report.split("### Stablecoins").nth(1).unwrap().split("### Non-Stablecoins").next().unwrap();
+        assert!(stablecoins.contains("| Team A | USDC | 1000.00 | 1000.00 |"));
+        assert!(!stablecoins.contains("ETH0"));
+        let non_stablecoins = report.split("### Non-Stablecoins").nth(1).unwrap();
+        assert!(non_stablecoins.contains("| Team A | ETH0 | 100.00 | 100.00 |"));
+
+        let closed_only = budget_system.generate_all_epochs_report(true).unwrap();
+        assert!(closed_only.contains("covering 1 closed epoch(s)"));
+        assert!(closed_only.contains("| Test Epoch | Closed |"));
+        assert!(!closed_only.contains("Empty Epoch"));
+
+        let output = budget_system.execute_command(Command::GenerateAllEpochsReport {
+            only_closed: true,
+            output_path: Some("all_epochs.md".to_string()),
+        }).await.unwrap();
+        assert!(output.contains("all_epochs.md"));
+        let saved = fs::read_to_string(temp_dir.path().join("reports").join("all_epochs.md")).unwrap();
+        assert!(saved.starts_with("# All Epochs Report"));
     }
-    
-    // Tests
 
     #[tokio::test]
-    async fn test_state_management() {
+    async fn test_get_proposals_for_team() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Test creating a new BudgetSystem
+        let team_a = budget_system.create_team("Team A".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
+        let team_b = budget_system.create_team("Team B".to_string(), "Rep B".to_string(), None, None).unwrap();
+        let idle = budget_system.create_team("Idle Team".to_string(), "Rep C".to_string(), None, None).unwrap();
+        let assign = |budget_system: &mut BudgetSystem, proposal_id: Uuid, team_id: Uuid| {
+            budget_system.state.get_proposal_mut(&proposal_id).unwrap()
+                .budget_request_details_mut().unwrap()
+                .set_team(Some(team_id));
+        };
+
+        let old_start = Utc::now() - Duration::days(60);
+        let old_epoch = budget_system.create_epoch("Old Epoch", old_start, old_start + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(old_epoch).unwrap();
+        let old_a = create_test_proposal(&mut budget_system, "Old A", vec![10.0]);
+        assign(&mut budget_system, old_a, team_a);
+        budget_system.state.get_epoch_mut(&old_epoch).unwrap().set_status(EpochStatus::Closed);
+        budget_system.state.set_current_epoch(None);
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let new_a = create_test_proposal(&mut budget_system, "New A", vec![20.0]);
+        assign(&mut budget_system, new_a, team_a);
+        let new_b = create_test_proposal(&mut budget_system, "New B", vec![30.0]);
+        assign(&mut budget_system, new_b, team_b);
+        create_test_proposal(&mut budget_system, "Unassigned", vec![40.0]);
+
+        let mut all_a: Vec<Uuid> = budget_system.get_proposals_for_team(team_a).iter().map(|p| p.id()).collect();
+        all_a.sort();
+        let mut expected = vec![old_a, new_a];
+        expected.sort();
+        assert_eq!(all_a, expected);
+
+        let current_a: Vec<Uuid> = budget_system.get_proposals_for_team_in_epoch(team_a, epoch_id).iter().map(|p| p.id()).collect();
+        assert_eq!(current_a, vec![new_a]);
+        assert!(budget_system.get_proposals_for_team_in_epoch(team_b, old_epoch).is_empty());
+
+        // Existing team without proposals, and an unknown team ID
+        assert!(budget_system.get_proposals_for_team(idle).is_empty());
+        assert!(budget_system.get_proposals_for_team(Uuid::new_v4()).is_empty());
+
+        let output = budget_system.execute_command(Command::ListTeamProposals {
+            team_name: "Team A".to_string(),
+            epoch_name: None,
+        }).await.unwrap();
+        assert!(output.contains("Old A [Old Epoch]"));
+        assert!(output.contains("New A [Test Epoch]"));
+        assert!(!output.contains("New B"));
+
+        let output = budget_system.execute_command(Command::ListTeamProposals {
+            team_name: "Team A".to_string(),
+            epoch_name: Some("Old Epoch".to_string()),
+        }).await.unwrap();
+        assert!(output.contains("Old A"));
+        assert!(!output.contains("New A"));
+
+        let output = budget_system.execute_command(Command::ListTeamProposals {
+            team_name: "Idle Team".to_string(),
+            epoch_name: None,
+        }).await.unwrap();
+        assert_eq!(output, "No proposals found for team Idle Team");
+
+        assert!(budget_system.execute_command(Command::ListTeamProposals {
+            team_name: "Missing".to_string(),
+            epoch_name: None,
+        }).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_typed_budget_system_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
-        
-        // Modify state
-        let epoch_id = budget_system.create_epoch("Test Epoch", Utc::now(), Utc::now() + Duration::days(30)).unwrap();
-        let team_id = budget_system.create_team("Test Team".to_string(), "Representative".to_string(), Some(vec![1000, 2000, 3000]), None).unwrap();
 
-        // Save state
-        budget_system.save_state().unwrap();
+        assert_eq!(
+            budget_system.add_proposal("Orphan".to_string(), None, None, None, None, None, None),
+            Err(BudgetSystemError::NoActiveEpoch)
+        );
+        let missing = Uuid::new_v4();
+        assert_eq!(budget_system.activate_epoch(missing), Err(BudgetSystemError::EpochNotFound(missing)));
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let start = budget_system.get_epoch(&epoch_id).unwrap().start_date();
+        assert_eq!(
+            budget_system.create_epoch("Overlap", start, start + Duration::days(5)),
+            Err(BudgetSystemError::EpochOverlap)
+        );
+        let later = start + Duration::days(60);
+        let later_id = budget_system.create_epoch("Later", later, later + Duration::days(30)).unwrap();
+        assert_eq!(budget_system.activate_epoch(later_id), Err(BudgetSystemError::EpochAlreadyActive));
+
+        let proposal_id = create_test_proposal(&mut budget_system, "Open", vec![100.0]);
+        assert_eq!(
+            budget_system.close_epoch(None),
+            Err(BudgetSystemError::ActionableProposalsRemaining(vec!["Open".to_string()]))
+        );
+        assert_eq!(
+            budget_system.create_formal_vote(missing, missing, None, None),
+            Err(BudgetSystemError::ProposalNotFound(missing))
+        );
+        assert_eq!(
+            budget_system.create_formal_vote(proposal_id, missing, None, None),
+            Err(BudgetSystemError::RaffleNotFound(missing))
+        );
+        assert!(matches!(
+            budget_system.finalize_raffle(missing, 1, 2, "randomness".to_string()).await,
+            Err(BudgetSystemError::RaffleNotFound(id)) if id == missing
+        ));
 
-        // Test loading existing state
-        let loaded_state = FileSystem::try_load_state(&state_file).unwrap();
-        let loaded_system = create_test_budget_system(&state_file, Some(loaded_state)).await;
+        let vote_id = budget_system.create_informal_vote(proposal_id).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        assert_eq!(budget_system.close_vote(vote_id), Err(BudgetSystemError::VoteAlreadyClosed));
+        assert_eq!(budget_system.cast_votes(vote_id, Vec::new()), Err(BudgetSystemError::VoteAlreadyClosed));
+        assert_eq!(budget_system.close_vote(missing), Err(BudgetSystemError::VoteNotFound(missing)));
 
-        // Verify loaded state
-        assert_eq!(loaded_system.state().epochs().len(), 1);
-        assert!(loaded_system.state().epochs().contains_key(&epoch_id));
-        assert_eq!(loaded_system.state().current_state().teams().len(), 1);
-        assert!(loaded_system.state().current_state().teams().contains_key(&team_id));
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        assert_eq!(
+            budget_system.close_with_reason(proposal_id, &Resolution::Approved),
+            Err(BudgetSystemError::ProposalAlreadyClosed)
+        );
 
-        // Test loading from non-existent file (should create new system)
-        let non_existent_file = temp_dir.path().join("non_existent.json").to_str().unwrap().to_string();
-        let new_system = create_test_budget_system(&non_existent_file, None).await;
-        assert!(new_system.state().epochs().is_empty());
-        assert!(new_system.state().current_state().teams().is_empty());
+        let tomorrow = Utc::now().date_naive() + Duration::days(1);
+        let names = vec!["Open".to_string()];
+        let tx = format!("0x{}", "ab".repeat(32));
+        assert_eq!(budget_system.record_payments(&tx, tomorrow, &names, false).await, Err(BudgetSystemError::FutureDateNotAllowed));
+        budget_system.record_payments(&tx, Utc::now().date_naive(), &names, false).await.unwrap();
+        assert_eq!(
+            budget_system.record_payments(&tx, Utc::now().date_naive(), &names, false).await,
+            Err(BudgetSystemError::PaymentAlreadyRecorded("Open".to_string()))
+        );
+
+        budget_system.close_epoch(None).unwrap();
+        assert_eq!(budget_system.close_epoch(Some("Test Epoch")), Err(BudgetSystemError::EpochAlreadyClosed));
     }
 
     #[tokio::test]
-    async fn test_epoch_management() {
+    async fn test_extend_epoch() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Test creating a new epoch
-        let start_date = Utc::now();
-        let end_date = start_date + Duration::days(30);
-        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
+        let planned_start = Utc::now() + Duration::days(90);
+        budget_system.create_epoch("Planned", planned_start, planned_start + Duration::days(30)).unwrap();
+        assert_eq!(budget_system.extend_epoch(None, planned_start), Err(BudgetSystemError::NoActiveEpoch));
+        let err = budget_system.extend_epoch(Some("Planned"), planned_start + Duration::days(40)).unwrap_err();
+        assert!(err.to_string().contains("Can only extend an active epoch"));
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let (start, end) = {
+            let epoch = budget_system.get_epoch(&epoch_id).unwrap();
+            (epoch.start_date(), epoch.end_date())
+        };
+
+        // Never backward
+        let err = budget_system.extend_epoch(None, end - Duration::days(1)).unwrap_err();
+        assert!(err.to_string().contains("must be later than the current end date"));
+
+        // Must cover existing resolutions
+        let proposal_id = create_test_proposal(&mut budget_system, "Late", vec![10.0]);
+        budget_system.state.get_proposal_mut(&proposal_id).unwrap()
+            .set_resolved_at(Some((end + Duration::days(10)).date_naive()));
+        let err = budget_system.extend_epoch(None, end + Duration::days(5)).unwrap_err();
+        assert!(err.to_string().contains("earlier than the latest proposal resolution"));
+
+        // Overlap with the planned epoch
+        assert_eq!(
+            budget_system.extend_epoch(Some("Test Epoch"), planned_start + Duration::days(1)),
+            Err(BudgetSystemError::EpochOverlap)
+        );
+
+        let output = budget_system.execute_command(Command::ExtendEpoch {
+            epoch_name: None,
+            new_end_date: end + Duration::days(14),
+        }).await.unwrap();
+        assert!(output.contains("Extended epoch Active epoch"));
         let epoch = budget_system.get_epoch(&epoch_id).unwrap();
-        assert_eq!(epoch.name(), "Test Epoch");
-        assert_eq!(epoch.start_date(), start_date);
-        assert_eq!(epoch.end_date(), end_date);
+        assert_eq!(epoch.start_date(), start);
+        assert_eq!(epoch.end_date(), end + Duration::days(14));
+    }
 
-        // Test activating an epoch
-        budget_system.activate_epoch(epoch_id).unwrap();
-        assert_eq!(budget_system.state().current_epoch(), Some(epoch_id));
+    #[tokio::test]
+    async fn test_clone_epoch() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Test setting epoch reward
+        let source_id = create_active_epoch(&mut budget_system).await;
         budget_system.set_epoch_reward("ETH", 100.0).unwrap();
-        let updated_epoch = budget_system.get_epoch(&epoch_id).unwrap();
-        assert_eq!(updated_epoch.reward().unwrap().token(), "ETH");
-        assert_eq!(updated_epoch.reward().unwrap().amount(), 100.0);
-
-        // Test creating overlapping epoch (should fail)
-        let overlapping_start = start_date + Duration::days(15);
-        let overlapping_end = end_date + Duration::days(15);
-        assert!(budget_system.create_epoch("Overlapping Epoch", overlapping_start, overlapping_end).is_err());
+        budget_system.set_epoch_reward("USDC", 5000.0).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Source Proposal").await;
+        budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+
+        let start = Utc::now() + Duration::days(60);
+        let clone_id = budget_system.clone_epoch(source_id, "Next Epoch", start, start + Duration::days(30)).unwrap();
+        assert_ne!(clone_id, source_id);
+
+        let source = budget_system.get_epoch(&source_id).unwrap().clone();
+        let clone = budget_system.get_epoch(&clone_id).unwrap();
+        assert_eq!(clone.name(), "Next Epoch");
+        assert_eq!(clone.status(), EpochStatus::Planned);
+        assert_eq!(clone.start_date(), start);
+        assert_eq!(clone.rewards(), source.rewards());
+        assert!(clone.associated_proposals().is_empty());
+        assert_eq!(source.associated_proposals().len(), 1);
+        assert!(budget_system.state.votes().values().all(|v| v.epoch_id() != clone_id));
+        assert_eq!(budget_system.state.current_epoch(), Some(source_id));
+
+        // Overlapping dates, duplicate names and unknown sources are rejected
+        assert_eq!(
+            budget_system.clone_epoch(source_id, "Overlap", start + Duration::days(10), start + Duration::days(40)),
+            Err(BudgetSystemError::EpochOverlap)
+        );
+        let later = start + Duration::days(100);
+        assert!(budget_system.clone_epoch(source_id, "Next Epoch", later, later + Duration::days(30)).is_err());
+        let unknown = Uuid::new_v4();
+        assert_eq!(
+            budget_system.clone_epoch(unknown, "Unknown", later, later + Duration::days(30)),
+            Err(BudgetSystemError::EpochNotFound(unknown))
+        );
 
-        // Test activating an epoch when another is already active (should fail)
-        let another_epoch_id = budget_system.create_epoch("Another Epoch", end_date + Duration::days(1), end_date + Duration::days(31)).unwrap();
-        assert!(budget_system.activate_epoch(another_epoch_id).is_err());
+        let output = budget_system.execute_command(Command::CloneEpoch {
+            source_epoch_name: "Test Epoch".to_string(),
+            new_name: "Later Epoch".to_string(),
+            start_date: later,
+            end_date: later + Duration::days(30),
+        }).await.unwrap();
+        assert!(output.contains("Created epoch: Later Epoch"));
+        assert!(output.contains("100 ETH"));
+        assert_eq!(budget_system.state.epochs().len(), 3);
+    }
 
-        // Ensure points are earned before closing an epoch
-        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
-        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
-        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None).unwrap();
-        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
-        budget_system.close_vote(vote_id).unwrap();
+    #[tokio::test]
+    async fn test_generate_gnosis_safe_payload() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let token = "0x6b175474e89094c44da98b954eedeac495271d0f";
+        budget_system.config.token_addresses.insert("ETH0".to_string(), token.to_string());
+        create_test_epoch(&mut budget_system);
+        let recipient = "742d35cc6634c0532925a3b844bc454e4438f44e";
+
+        // Approved and unpaid: one ERC-20 transfer and one native ETH transfer
+        let grant = create_test_proposal(&mut budget_system, "Grant", vec![2.0]);
+        let proposal = budget_system.state.get_proposal_mut(&grant).unwrap();
+        proposal.budget_request_details_mut().unwrap().add_request_amount("ETH".to_string(), 0.5).unwrap();
+        proposal.approve().unwrap();
+
+        // Paid, rejected and pending proposals are left out
+        let paid = create_test_proposal(&mut budget_system, "Paid", vec![1.0]);
+        let proposal = budget_system.state.get_proposal_mut(&paid).unwrap();
+        proposal.budget_request_details_mut().unwrap()
+            .record_payment(format!("0x{}", "ab".repeat(32)), Utc::now().date_naive()).unwrap();
+        proposal.approve().unwrap();
+        let rejected = create_test_proposal(&mut budget_system, "Rejected", vec![1.0]);
+        budget_system.state.get_proposal_mut(&rejected).unwrap().reject().unwrap();
+        create_test_proposal(&mut budget_system, "Pending", vec![1.0]);
+
+        let payload: serde_json::Value = serde_json::from_str(&budget_system.generate_gnosis_safe_payload("Test Epoch").unwrap()).unwrap();
+        assert_eq!(payload["version"], "1.0");
+        assert_eq!(payload["chainId"], "1");
+        let transactions = payload["transactions"].as_array().unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0]["to"], format!("0x{}", recipient));
+        assert_eq!(transactions[0]["value"], "500000000000000000");
+        assert_eq!(transactions[0]["data"], "0x");
+        assert_eq!(transactions[1]["to"], token);
+        assert_eq!(transactions[1]["value"], "0");
+        assert_eq!(transactions[1]["operation"], 0);
+        assert_eq!(
+            transactions[1]["data"],
+            format!("0xa9059cbb{:0>64}{:0>64x}", recipient, 2_000_000_000_000_000_000u128)
+        );
 
-        // Close the proposal before closing the epoch
-        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        // Split proposals pay each unpaid allocation to the team's own address
+        let alpha_address = "1111111111111111111111111111111111111111";
+        let alpha = budget_system.create_team("Alpha".to_string(), "Alice".to_string(), None, Some(format!("0x{}", alpha_address))).unwrap();
+        let beta = budget_system.create_team("Beta".to_string(), "Bob".to_string(), None, None).unwrap();
+        let split = create_test_proposal(&mut budget_system, "Split", vec![3.0]);
+        let details = budget_system.state.get_proposal_mut(&split).unwrap().budget_request_details_mut().unwrap();
+        details.set_team_allocations(HashMap::from([
+            (alpha, HashMap::from([("ETH0".to_string(), 1.0)])),
+            (beta, HashMap::from([("ETH0".to_string(), 2.0)])),
+        ])).unwrap();
+        details.record_allocation_payment(beta, format!("0x{}", "cd".repeat(32)), Utc::now().date_naive()).unwrap();
+        budget_system.state.get_proposal_mut(&split).unwrap().approve().unwrap();
+
+        let payload: serde_json::Value = serde_json::from_str(&budget_system.generate_gnosis_safe_payload("Test Epoch").unwrap()).unwrap();
+        let transactions = payload["transactions"].as_array().unwrap();
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(
+            transactions[2]["data"],
+            format!("0xa9059cbb{:0>64}{:0>64x}", alpha_address, 1_000_000_000_000_000_000u128)
+        );
 
-        budget_system.close_epoch(Some("Test Epoch")).unwrap();
-        let closed_epoch = budget_system.get_epoch(&epoch_id).unwrap();
-        assert!(closed_epoch.is_closed());
-        assert_eq!(budget_system.state().current_epoch(), None);
+        let output_path = temp_dir.path().join("safe").join("batch.json");
+        let output = budget_system.execute_command(Command::GenerateMultisigPayload {
+            epoch_name: "Test Epoch".to_string(),
+            output_path: Some(output_path.to_str().unwrap().to_string()),
+        }).await.unwrap();
+        assert!(output.contains("Generated multisig payload at"));
+        assert!(fs::read_to_string(&output_path).unwrap().contains("\"chainId\": \"1\""));
+
+        // Tokens need a configured contract
+        budget_system.config.token_addresses.clear();
+        let err = budget_system.generate_gnosis_safe_payload("Test Epoch").unwrap_err();
+        assert_eq!(err.to_string(), "No contract address configured for: ETH0");
+
+        // Every proposal to pay needs a payment address
+        let no_address = create_test_proposal(&mut budget_system, "No Address", vec![1.0]);
+        let proposal = budget_system.state.get_proposal_mut(&no_address).unwrap();
+        proposal.budget_request_details_mut().unwrap().set_payment_address(None).unwrap();
+        proposal.approve().unwrap();
+        let err = budget_system.generate_gnosis_safe_payload("Test Epoch").unwrap_err();
+        assert_eq!(err.to_string(), "Payouts without a payment address: No Address");
+
+        // An unpaid allocation needs an address on its team
+        let gamma = budget_system.create_team("Gamma".to_string(), "Carol".to_string(), None, None).unwrap();
+        let gamma_split = create_test_proposal(&mut budget_system, "Gamma Split", vec![1.0]);
+        let proposal = budget_system.state.get_proposal_mut(&gamma_split).unwrap();
+        proposal.budget_request_details_mut().unwrap()
+            .set_team_allocations(HashMap::from([(gamma, HashMap::from([("ETH0".to_string(), 1.0)]))])).unwrap();
+        proposal.approve().unwrap();
+        let err = budget_system.generate_gnosis_safe_payload("Test Epoch").unwrap_err();
+        assert_eq!(err.to_string(), "Payouts without a payment address: Gamma Split (Gamma), No Address");
+
+        assert!(budget_system.generate_gnosis_safe_payload("Unknown").is_err());
     }
 
     #[tokio::test]
-    async fn test_team_management() {
+    async fn test_generate_epoch_payments_report_formats() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Test creating a new team
-        let team_id = budget_system.create_team(
-            "Test Team".to_string(),
-            "Representative".to_string(),
-            Some(vec![1000, 2000, 3000]),
-            None
+        create_test_epoch(&mut budget_system);
+        budget_system.set_epoch_reward("ETH", 1000.0).unwrap();
+        let team1 = budget_system.create_team(
+            "Team A".to_string(),
+            "Rep A".to_string(),
+            Some(vec![1000]),
+            Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string())
         ).unwrap();
-        let team = budget_system.get_team(&team_id).unwrap();
-        assert_eq!(team.name(), "Test Team");
-        assert_eq!(team.representative(), "Representative");
-        assert!(matches!(team.status(), TeamStatus::Earner { .. }));
-
-        // Test getting team by name
-        let team_id_by_name = budget_system.get_team_id_by_name("Test Team").unwrap();
-        assert_eq!(team_id_by_name, team_id);
+        let team2 = budget_system.create_team("Team B".to_string(), "Rep B".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team1, VoteChoice::Yes), (team2, VoteChoice::No)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        budget_system.close_epoch(None).unwrap();
 
-        // Test removing a team
-        budget_system.remove_team(team_id).unwrap();
-        assert!(budget_system.get_team(&team_id).is_none());
+        let json = budget_system.generate_epoch_payments_report("Test Epoch", None, ReportFormat::Json).unwrap();
+        let parsed: EpochPaymentsReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.payments.len(), 2);
+        assert_eq!(parsed.payments[0].team_name, "Team A");
+        assert!(parsed.payments[0].points > 0);
+
+        let csv = budget_system.generate_epoch_payments_report("Test Epoch", None, ReportFormat::Csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next().unwrap(), "epoch_name,team_name,payment_address,points,percentage,reward_token,reward_amount");
+        let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+        assert_eq!(rows.len(), parsed.payments.len());
+        for (row, payment) in rows.iter().zip(&parsed.payments) {
+            assert_eq!(row[0], parsed.epoch_name);
+            assert_eq!(row[1], payment.team_name);
+            let address = (!row[2].is_empty()).then(|| row[2].parse::<Address>().unwrap());
+            assert_eq!(address, payment.default_payment_address);
+            assert_eq!(row[3].parse::<u32>().unwrap(), payment.points);
+            assert_eq!(row[4].parse::<f64>().unwrap(), payment.percentage);
+            assert_eq!(row[5], payment.reward_token);
+            assert_eq!(row[6].parse::<f64>().unwrap(), payment.amount);
+        }
 
-        // Test creating a team with invalid data (should fail)
-        assert!(budget_system.create_team("".to_string(), "Representative".to_string(), None, None).is_err());
+        let markdown = budget_system.execute_command(Command::GenerateEpochPaymentsReport {
+            epoch_name: "Test Epoch".to_string(),
+            output_path: None,
+            format: ReportFormat::Markdown,
+        }).await.unwrap();
+        assert!(markdown.contains("## Epoch Payments: Test Epoch"));
+        assert!(markdown.contains("| Team | Payment Address | Points | Percentage | Amount |"));
+        assert!(markdown.contains("| Team B | N/A |"));
     }
 
     #[tokio::test]
-    async fn test_update_team() {
+    async fn test_close_epoch_with_multiple_reward_tokens() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        let team_id = budget_system.create_team("Test Team".to_string(), "John Doe".to_string(), Some(vec![1000]), None).unwrap();
-
-        let updates = UpdateTeamDetails {
-            name: Some("Updated Team".to_string()),
-            representative: Some("Jane Doe".to_string()),
-            status: Some("Supporter".to_string()),
-            trailing_monthly_revenue: None,
-            address: None
-        };
+        let epoch_id = create_test_epoch(&mut budget_system);
+        budget_system.set_epoch_reward("ETH", 10.0).unwrap();
+        budget_system.set_epoch_reward("USDC", 5000.0).unwrap();
+        budget_system.set_epoch_reward("ETH", 100.0).unwrap();
+        assert_eq!(budget_system.get_epoch(&epoch_id).unwrap().rewards().len(), 2);
 
-        budget_system.update_team(team_id, updates).unwrap();
+        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        budget_system.close_epoch(None).unwrap();
 
-        let updated_team = budget_system.get_team(&team_id).unwrap();
-        assert_eq!(updated_team.name(), "Updated Team");
-        assert_eq!(updated_team.representative(), "Jane Doe");
-        assert!(matches!(updated_team.status(), TeamStatus::Supporter));
+        let epoch = budget_system.get_epoch(&epoch_id).unwrap();
+        let team_reward = epoch.team_rewards().get(&team_id).unwrap();
+        assert_eq!(team_reward.percentage(), 100.0);
+        assert_eq!(team_reward.amount("ETH"), 100.0);
+        assert_eq!(team_reward.amount("USDC"), 5000.0);
+
+        let json = budget_system.generate_epoch_payments_report("Test Epoch", None, ReportFormat::Json).unwrap();
+        let parsed: EpochPaymentsReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.total_rewards, vec![("ETH".to_string(), 100.0), ("USDC".to_string(), 5000.0)]);
+        let payments: Vec<(&str, f64)> = parsed.payments.iter().map(|p| (p.reward_token.as_str(), p.amount)).collect();
+        assert_eq!(payments, vec![("ETH", 100.0), ("USDC", 5000.0)]);
+
+        let markdown = budget_system.generate_epoch_payments_report("Test Epoch", None, ReportFormat::Markdown).unwrap();
+        assert!(markdown.contains("Total reward: 100.00 ETH, 5000.00 USDC"));
     }
 
     #[tokio::test]
-    async fn test_update_team_earner_status() {
+    async fn test_close_epoch_with_zero_and_three_reward_tokens() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        let team_id = budget_system.create_team("Test Team".to_string(), "John Doe".to_string(), Some(vec![1000]), None).unwrap();
+        // Without rewards the epoch closes and no team rewards are recorded
+        let epoch_id = create_test_epoch(&mut budget_system);
+        budget_system.close_epoch(None).unwrap();
+        let epoch = budget_system.get_epoch(&epoch_id).unwrap();
+        assert!(epoch.rewards().is_empty());
+        assert!(epoch.team_rewards().is_empty());
 
-        let updates = UpdateTeamDetails {
-            name: None,
-            representative: None,
-            status: Some("Earner".to_string()),
-            trailing_monthly_revenue: Some(vec![2000, 3000, 4000]),
-            address: None,
-        };
+        let start = Utc::now() + Duration::days(31);
+        let epoch_id = budget_system.create_epoch("Reward Epoch", start, start + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        for (token, amount) in [("ETH", 10.0), ("USDC", 2000.0), ("DAI", 500.0)] {
+            budget_system.set_epoch_reward(token, amount).unwrap();
+        }
 
-        budget_system.update_team(team_id, updates).unwrap();
+        let team_a = budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let team_b = budget_system.create_team("Team B".to_string(), "Rep".to_string(), None, None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_a, VoteChoice::Yes), (team_b, VoteChoice::No)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        budget_system.close_epoch(None).unwrap();
 
-        let updated_team = budget_system.get_team(&team_id).unwrap();
-        if let TeamStatus::Earner { trailing_monthly_revenue } = updated_team.status() {
-            assert_eq!(trailing_monthly_revenue, &[2000, 3000, 4000]);
-        } else {
-            panic!("Expected Earner status");
+        let epoch = budget_system.get_epoch(&epoch_id).unwrap();
+        for team_id in [team_a, team_b] {
+            let reward = epoch.team_rewards().get(&team_id).unwrap();
+            assert_eq!(reward.percentage(), 50.0);
+            assert_eq!(reward.amount("ETH"), 5.0);
+            assert_eq!(reward.amount("USDC"), 1000.0);
+            assert_eq!(reward.amount("DAI"), 250.0);
         }
+        for token in ["ETH", "USDC", "DAI"] {
+            assert_eq!(epoch.remaining_reward_amount(token), 0.0);
+        }
+
+        let summary = budget_system.generate_epoch_summary(epoch).unwrap();
+        assert!(summary.contains("ETH") && summary.contains("USDC") && summary.contains("DAI"));
+        let team_summary = budget_system.generate_team_summary(epoch).unwrap();
+        for amount in ["5 ETH", "1000 USDC", "250 DAI"] {
+            assert!(team_summary.contains(amount));
+        }
+        let json = budget_system.generate_epoch_payments_report("Reward Epoch", None, ReportFormat::Json).unwrap();
+        let parsed: EpochPaymentsReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.total_rewards.len(), 3);
+        assert_eq!(parsed.payments.len(), 6);
     }
 
     #[tokio::test]
-    async fn test_update_team_invalid_status() {
+    async fn test_get_teams_by_status() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        let team_id = budget_system.create_team("Test Team".to_string(), "John Doe".to_string(), Some(vec![1000]), None).unwrap();
-
-        let updates = UpdateTeamDetails {
-            name: None,
-            representative: None,
-            status: Some("InvalidStatus".to_string()),
-            trailing_monthly_revenue: None,
-            address: None,
-        };
-
-        assert!(budget_system.update_team(team_id, updates).is_err());
+        assert!(budget_system.get_teams_by_status("earner").unwrap().is_empty());
+        assert!(budget_system.get_active_teams().is_empty());
+        let output = budget_system.execute_command(Command::ListTeams { status_filter: None }).await.unwrap();
+        assert_eq!(output, "No teams found");
+
+        budget_system.create_team("Zeta".to_string(), "Rep Z".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Alpha".to_string(), "Rep A".to_string(), Some(vec![2000]), None).unwrap();
+        budget_system.create_team("Beta".to_string(), "Rep B".to_string(), None, None).unwrap();
+        let gamma = budget_system.create_team("Gamma".to_string(), "Rep G".to_string(), None, None).unwrap();
+        budget_system.state.get_team_mut(&gamma).unwrap().set_status(TeamStatus::Inactive).unwrap();
+
+        let names = |teams: Vec<&Team>| teams.iter().map(|t| t.name().to_string()).collect::<Vec<_>>();
+        assert_eq!(names(budget_system.get_teams_by_status("Earner").unwrap()), vec!["Alpha", "Zeta"]);
+        assert_eq!(names(budget_system.get_teams_by_status("SUPPORTER").unwrap()), vec!["Beta"]);
+        assert_eq!(names(budget_system.get_teams_by_status("inactive").unwrap()), vec!["Gamma"]);
+        assert_eq!(names(budget_system.get_active_teams()), vec!["Alpha", "Beta", "Zeta"]);
+
+        let output = budget_system.execute_command(Command::ListTeams {
+            status_filter: Some("earner".to_string())
+        }).await.unwrap();
+        assert_eq!(output, "Teams (earner):\n  - Alpha (Earner): Rep A\n  - Zeta (Earner): Rep Z\n");
+
+        assert!(matches!(
+            budget_system.get_teams_by_status("retired"),
+            Err(BudgetSystemError::Other(msg)) if msg.contains("Invalid team status")
+        ));
+        assert!(budget_system.execute_command(Command::ListTeams {
+            status_filter: Some("retired".to_string())
+        }).await.is_err());
     }
 
     #[tokio::test]
-    async fn test_proposal_management() {
+    async fn test_list_epochs() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Create an active epoch
-        let epoch_id = create_active_epoch(&mut budget_system).await;
-
-        // Test adding a new proposal
-        let proposal_id = budget_system.add_proposal(
-            "Test Proposal".to_string(),
-            Some("http://example.com".to_string()),
-            Some(BudgetRequestDetails::new(
-                None,
-                [("ETH".to_string(), 100.0)].iter().cloned().collect(),
-                Some(Utc::now().date_naive()),
-                Some((Utc::now() + Duration::days(30)).date_naive()),
-                Some(false),
-                None
-            ).unwrap()),
-            Some(Utc::now().date_naive()),
-            Some(Utc::now().date_naive()),
-            None
-        ).unwrap();
-
-        let proposal = budget_system.get_proposal(&proposal_id).unwrap();
-        assert_eq!(proposal.title(), "Test Proposal");
-
-        // Test updating a proposal
-        let updates = UpdateProposalDetails {
-            title: Some("Updated Proposal".to_string()),
-            url: None,
-            budget_request_details: None,
-            announced_at: None,
-            published_at: None,
-            resolved_at: None,
-        };
-        budget_system.update_proposal("Test Proposal", updates).unwrap();
-        let updated_proposal = budget_system.get_proposal(&proposal_id).unwrap();
-        assert_eq!(updated_proposal.title(), "Updated Proposal");
+        assert_eq!(budget_system.list_epochs(true), "No epochs found");
 
-        // Test closing a proposal
-        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
-        let closed_proposal = budget_system.get_proposal(&proposal_id).unwrap();
-        assert!(closed_proposal.is_closed());
-        assert_eq!(closed_proposal.resolution(), Some(Resolution::Approved));
+        let now = Utc::now();
+        let closed = budget_system.create_epoch("Epoch 1", now - Duration::days(60), now - Duration::days(31)).unwrap();
+        budget_system.activate_epoch(closed).unwrap();
+        budget_system.close_epoch(None).unwrap();
+        let planned = budget_system.create_epoch("Epoch 3", now + Duration::days(31), now + Duration::days(60)).unwrap();
+        let active = budget_system.create_epoch("Epoch 2", now - Duration::days(30), now + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(active).unwrap();
+        budget_system.set_epoch_reward("ETH", 100.0).unwrap();
 
-        // Test getting proposals for an epoch
-        let epoch_proposals = budget_system.get_proposals_for_epoch(epoch_id);
-        assert_eq!(epoch_proposals.len(), 1);
-        assert_eq!(epoch_proposals[0].id(), proposal_id);
+        let output = budget_system.execute_command(Command::ListEpochs { include_closed: true }).await.unwrap();
+        let rows: Vec<&str> = output.lines().skip(2).collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with(&format!("|  | Epoch 1 | {} | Closed |", closed)));
+        assert!(rows[0].ends_with("| 0 | No |"));
+        assert!(rows[1].starts_with(&format!("| ▶ | Epoch 2 | {} | Active |", active)));
+        assert!(rows[1].ends_with("| 0 | Yes |"));
+        assert!(rows[2].starts_with(&format!("|  | Epoch 3 | {} | Planned |", planned)));
+        assert!(rows[2].ends_with("| 0 | No |"));
+
+        let output = budget_system.list_epochs(false);
+        assert!(!output.contains("Epoch 1"));
+        assert!(output.contains("Epoch 2"));
+    }
 
-        // Test adding a proposal without an active epoch (should fail)
-        budget_system.close_epoch(None).unwrap();
-        assert!(budget_system.add_proposal(
-            "Failed Proposal".to_string(),
-            None,
-            None,
-            None,
-            None,
-            None
-        ).is_err());
+    /// Runs one formal vote in the active epoch in which `voters` vote Yes.
+    async fn run_formal_vote(budget_system: &mut BudgetSystem, proposal_name: &str, voters: &[Uuid]) -> Uuid {
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(budget_system, proposal_name).await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, voters.iter().map(|&team| (team, VoteChoice::Yes)).collect()).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        vote_id
     }
 
     #[tokio::test]
-    async fn test_raffle_management() {
+    async fn test_generate_vote_participation_trends() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let mut teams = Vec::new();
+        for name in ["Team A", "Team B", "Team C", "Team D"] {
+            teams.push(budget_system.create_team(name.to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap());
+        }
 
-        // Create an active epoch and a proposal
-        let _epoch_id = create_active_epoch(&mut budget_system).await;
-        let _proposal_id = budget_system.add_proposal(
-            "Test Proposal".to_string(),
-            None,
-            None,
-            None,
-            None,
-            None
-        ).unwrap();
-
-        // Create some teams
-        let team_id1 = budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
-        let team_id2 = budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), None, None).unwrap();
-
-        // Test preparing a raffle
-        let config = budget_system.config().clone();
-        let (raffle_id, tickets) = budget_system.prepare_raffle(
-            "Test Proposal",
-            None,
-            &config
-        ).unwrap();
-        assert!(!tickets.is_empty());
+        // Participation rises from 2 to 3 voters, then holds at 3
+        let now = Utc::now();
+        for (i, voters) in [2, 3, 3].into_iter().enumerate() {
+            let start = now - Duration::days(120 - 40 * i as i64);
+            let epoch_id = budget_system.create_epoch(&format!("Epoch {}", i + 1), start, start + Duration::days(30)).unwrap();
+            budget_system.activate_epoch(epoch_id).unwrap();
+            run_formal_vote(&mut budget_system, &format!("Proposal {}", i + 1), &teams[..voters]).await;
+            if i < 2 {
+                budget_system.state.get_epoch_mut(&epoch_id).unwrap().set_status(EpochStatus::Closed);
+                budget_system.state.set_current_epoch(None);
+            }
+        }
 
-        // Test finalizing a raffle
-        let raffle = budget_system.finalize_raffle(
-            raffle_id,
-            12345,
-            12355,
-            "mock_randomness".to_string()
-        ).await.unwrap();
-        assert!(raffle.result().is_some());
+        let seats = match budget_system.state.votes().values().next().unwrap().vote_type() {
+            VoteType::Formal { total_eligible_seats, .. } => *total_eligible_seats as f64,
+            _ => unreachable!(),
+        };
+        let rate = |voters: f64| format!("{:.1}%", voters / seats * 100.0);
+        let points = budget_system.config.counted_vote_points;
+
+        let report = budget_system.generate_vote_participation_trends(false).unwrap();
+        let rows: Vec<&str> = report.lines().filter(|l| l.starts_with("| Epoch ") && !l.starts_with("| Epoch |")).collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], format!("| Epoch 1 | Closed | 1 | {} | N/A | {} | - |", rate(2.0), 2 * points));
+        assert_eq!(rows[1], format!("| Epoch 2 | Closed | 1 | {} | N/A | {} | ↑ |", rate(3.0), 3 * points));
+        assert_eq!(rows[2], format!("| Epoch 3 | Active | 1 | {} | N/A | {} | → |", rate(3.0), 3 * points));
+
+        // A poorly attended second vote pulls the active epoch's average down
+        run_formal_vote(&mut budget_system, "Proposal 4", &teams[..1]).await;
+        let report = budget_system.generate_vote_participation_trends(false).unwrap();
+        assert!(report.contains(&format!("| Epoch 3 | Active | 2 | {} | N/A | {} | ↓ |", rate(2.0), 4 * points)));
+
+        // Most absent first; Team A voted every time
+        let absent = report.split("## Most Absent Teams\n\n").nth(1).unwrap();
+        let absent_rows: Vec<&str> = absent.lines().skip(2).collect();
+        assert_eq!(absent_rows, vec!["| Team D | 4 | 4 |", "| Team C | 2 | 4 |", "| Team B | 1 | 4 |"]);
+
+        let closed_only = budget_system.generate_vote_participation_trends(true).unwrap();
+        assert!(closed_only.contains("| Epoch 2 | Closed |"));
+        assert!(!closed_only.contains("Epoch 3"));
+
+        let output = budget_system.execute_command(Command::GenerateVoteTrendsReport {
+            only_closed: true,
+            output_path: Some("trends.md".to_string()),
+        }).await.unwrap();
+        assert!(output.contains("Vote trends report saved to"));
+        let saved = fs::read_to_string(temp_dir.path().join("reports").join("trends.md")).unwrap();
+        assert_eq!(saved, closed_only);
+    }
 
-        // Test importing a predefined raffle
-        let imported_raffle_id = budget_system.import_predefined_raffle(
-            "Test Proposal",
-            vec!["Team 1".to_string()],
-            vec!["Team 2".to_string()],
-            1,
-            1
-        ).unwrap();
-        let imported_raffle = budget_system.get_raffle(&imported_raffle_id).unwrap();
-        assert_eq!(imported_raffle.result().unwrap().counted(), &[team_id1]);
-        assert_eq!(imported_raffle.result().unwrap().uncounted(), &[team_id2]);
+    #[tokio::test]
+    async fn test_merge_teams() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+        let alpha = budget_system.create_team("Alpha".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
+        let beta = budget_system.create_team("Beta".to_string(), "Rep B".to_string(), Some(vec![1000]), None).unwrap();
+
+        let open = create_test_proposal(&mut budget_system, "Open Request", vec![10.0]);
+        let funded = create_test_proposal(&mut budget_system, "Funded Request", vec![10.0]);
+        for proposal_id in [open, funded] {
+            budget_system.state.get_proposal_mut(&proposal_id).unwrap()
+                .budget_request_details_mut().unwrap().set_team(Some(alpha));
+        }
+        budget_system.state.get_proposal_mut(&funded).unwrap().approve().unwrap();
 
-        // Test importing a historical raffle
-        let (_historical_raffle_id, historical_raffle) = budget_system.import_historical_raffle(
-            "Test Proposal",
-            12345,
-            12355,
-            Some(vec!["Team 1".to_string(), "Team 2".to_string()]),
-            None,
-            Some(2),
-            Some(1)
-        ).await.unwrap();
-        assert_eq!(historical_raffle.config().initiation_block(), 12345);
-        assert_eq!(historical_raffle.config().randomness_block(), 12355);
-        assert!(historical_raffle.result().is_some());
+        // Alpha is seated in an open vote
+        let (voted, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Voted").await;
+        let vote_id = budget_system.create_formal_vote(voted, raffle_id, None, None).unwrap();
+        let err = budget_system.merge_teams("Alpha", "Beta").unwrap_err();
+        assert_eq!(err.to_string(), "Team Alpha is part of an open vote");
 
-        // Test raffle exclusions
-        let excluded_raffle_id = budget_system.import_predefined_raffle(
-            "Test Proposal",
-            vec!["Team 1".to_string()],
-            vec![],
-            1,
-            1
-        ).unwrap();
-        let excluded_raffle = budget_system.get_raffle(&excluded_raffle_id).unwrap();
-        assert_eq!(excluded_raffle.result().unwrap().counted(), &[team_id1]);
-        assert!(excluded_raffle.result().unwrap().uncounted().is_empty());
+        budget_system.cast_votes(vote_id, vec![(alpha, VoteChoice::Yes), (beta, VoteChoice::No)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
+        let raffle_before = serde_json::to_value(budget_system.get_raffle(&raffle_id).unwrap()).unwrap();
+        let report_before = budget_system.generate_vote_report(vote_id).unwrap();
+
+        let output = budget_system.execute_command(Command::MergeTeams {
+            source_name: "Alpha".to_string(),
+            target_name: "Beta".to_string(),
+        }).await.unwrap();
+        assert_eq!(output, "Merged team Alpha into Beta (1 proposal(s) reassigned)");
+
+        let team_of = |bs: &BudgetSystem, id: Uuid| bs.get_proposal(&id).unwrap().budget_request_details().unwrap().team();
+        assert_eq!(team_of(&budget_system, open), Some(beta));
+        assert_eq!(team_of(&budget_system, funded), Some(alpha));
+        assert!(budget_system.state.get_team(&alpha).unwrap().is_inactive());
+        assert_eq!(budget_system.state.get_team(&beta).unwrap().aliases(), ["Alpha"]);
+        assert_eq!(budget_system.get_team_id_by_name("Alpha"), Some(beta));
+
+        // History still points at Alpha
+        assert_eq!(serde_json::to_value(budget_system.get_raffle(&raffle_id).unwrap()).unwrap(), raffle_before);
+        assert!(matches!(
+            budget_system.state.get_vote(&vote_id).unwrap().participation(),
+            VoteParticipation::Formal { counted, .. } if counted.contains(&alpha)
+        ));
+        assert_eq!(budget_system.generate_vote_report(vote_id).unwrap(), report_before);
+
+        assert_eq!(budget_system.merge_teams("Beta", "Alpha").unwrap_err().to_string(), "Cannot merge a team into itself");
+        let gamma = budget_system.create_team("Gamma".to_string(), "Rep G".to_string(), None, None).unwrap();
+        assert_eq!(budget_system.merge_teams("Alpha", "Gamma").unwrap_err().to_string(), "Team Alpha is inactive");
+        assert!(budget_system.merge_teams("Delta", "Gamma").is_err());
+
+        // Aliases carry over when the merged team is merged again
+        budget_system.merge_teams("Beta", "Gamma").unwrap();
+        assert_eq!(budget_system.state.get_team(&gamma).unwrap().aliases(), ["Beta", "Alpha"]);
+        assert!(budget_system.state.get_team(&beta).unwrap().aliases().is_empty());
+        assert_eq!(budget_system.get_team_id_by_name("Alpha"), Some(gamma));
+    }
 
-        // Test invalid raffle creation (non-existent proposal)
-        assert!(budget_system.prepare_raffle(
-            "Non-existent Proposal",
-            None,
-            &config
-        ).is_err());
+    #[tokio::test]
+    async fn test_team_aliases() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let metagov = budget_system.create_team("MetaGov".to_string(), "Rep M".to_string(), None, None).unwrap();
+        let beta = budget_system.create_team("Beta".to_string(), "Rep B".to_string(), None, None).unwrap();
+
+        // Renaming keeps the old name resolving, case-insensitively
+        budget_system.update_team(metagov, UpdateTeamDetails {
+            name: Some("Meta-Governance".to_string()), representative: None, status: None, trailing_monthly_revenue: None, address: None,
+        }).unwrap();
+        assert_eq!(budget_system.get_team_id_by_name("Meta-Governance"), Some(metagov));
+        assert_eq!(budget_system.get_team_id_by_name("MetaGov"), Some(metagov));
+        assert_eq!(budget_system.get_team_id_by_name("metagov"), Some(metagov));
+
+        let output = budget_system.execute_command(Command::AddTeamAlias {
+            team_name: "Beta".to_string(),
+            alias: "B-Team".to_string(),
+        }).await.unwrap();
+        assert_eq!(output, "Added alias B-Team to team Beta");
+        assert_eq!(budget_system.get_team_id_by_name("b-team"), Some(beta));
+
+        // Two teams can't claim the same alias
+        let err = budget_system.add_team_alias(beta, "METAGOV").unwrap_err();
+        assert_eq!(err.to_string(), "Alias METAGOV is already used by team Meta-Governance");
+        let err = budget_system.add_team_alias(metagov, "Beta").unwrap_err();
+        assert_eq!(err.to_string(), "Alias Beta is already used by team Beta");
+        let err = budget_system.update_team(metagov, UpdateTeamDetails {
+            name: Some("B-Team".to_string()), representative: None, status: None, trailing_monthly_revenue: None, address: None,
+        }).unwrap_err();
+        assert_eq!(err.to_string(), "Name B-Team is already an alias of team Beta");
+        assert!(budget_system.add_team_alias(beta, " ").is_err());
+    }
 
-        // Test invalid raffle finalization (non-existent raffle)
-        assert!(budget_system.finalize_raffle(
-            Uuid::new_v4(),
-            12345,
-            12355,
-            "mock_randomness".to_string()
-        ).await.is_err());
+    #[tokio::test]
+    async fn test_archive_team() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+        let alpha = budget_system.create_team("Alpha".to_string(), "Rep A".to_string(), Some(vec![4000]), None).unwrap();
+        let beta = budget_system.create_team("Beta".to_string(), "Rep B".to_string(), None, None).unwrap();
+
+        let (_, first_raffle) = create_proposal_with_raffle(&mut budget_system, "Before").await;
+        let output = budget_system.execute_command(Command::ArchiveTeam { team_name: "Alpha".to_string() }).await.unwrap();
+        assert_eq!(output, "Archived team: Alpha");
+        assert!(budget_system.archive_team(alpha).is_err());
+
+        // Archived teams get no snapshot or tickets in new raffles
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "After").await;
+        let raffle = budget_system.get_raffle(&raffle_id).unwrap();
+        assert!(raffle.team_snapshots().iter().all(|s| s.id() != alpha));
+        assert!(raffle.tickets().iter().all(|t| t.team_id() != alpha));
+        assert!(budget_system.get_raffle(&first_raffle).unwrap().team_snapshots().iter().any(|s| s.id() == alpha));
+
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        let err = budget_system.cast_votes(vote_id, vec![(alpha, VoteChoice::Yes)]).unwrap_err();
+        assert_eq!(err.to_string(), "Team Alpha is archived and cannot vote");
+        let err = budget_system.archive_team(beta).unwrap_err();
+        assert_eq!(err.to_string(), "Team Beta is part of an open vote");
+
+        let names = |teams: Vec<&Team>| teams.iter().map(|t| t.name().to_string()).collect::<Vec<_>>();
+        assert_eq!(names(budget_system.get_teams_by_status("active").unwrap()), vec!["Beta"]);
+        assert_eq!(names(budget_system.get_teams_by_status("archived").unwrap()), vec!["Alpha"]);
+
+        let report = budget_system.print_team_report();
+        let (current, archived) = report.split_once("Archived Teams:").unwrap();
+        assert!(current.contains("Name: Beta") && !current.contains("Name: Alpha"));
+        assert!(archived.contains("Name: Alpha"));
+
+        budget_system.execute_command(Command::UnarchiveTeam { team_name: "Alpha".to_string() }).await.unwrap();
+        let team = budget_system.get_team(&alpha).unwrap();
+        assert_eq!(team.status(), &TeamStatus::Earner { trailing_monthly_revenue: vec![4000] });
+        assert!(!budget_system.print_team_report().contains("Archived Teams:"));
+        assert!(budget_system.unarchive_team(alpha).is_err());
     }
 
     #[tokio::test]
-    async fn test_vote_management() {
+    async fn test_load_closes_expired_votes() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
+        budget_system.config.default_qualified_majority_threshold = 0.1;
+        create_test_epoch(&mut budget_system);
+        let team_id = budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
 
-        create_active_epoch(&mut budget_system).await;
-        let proposal_id = budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None).unwrap();
+        let past = Utc::now() - Duration::days(1);
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Expired").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, Some(past)).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        budget_system.save_state().unwrap();
+        let saved = fs::read_to_string(&state_file).unwrap();
+
+        let loaded = FileSystem::try_load_state(&state_file);
+        let budget_system = create_test_budget_system(&state_file, loaded).await;
+        let vote = budget_system.state.get_vote(&vote_id).unwrap();
+        assert!(vote.is_closed());
+        assert_eq!(vote.closed_at(), Some(past));
+        assert!(budget_system.get_proposal(&proposal_id).unwrap().is_approved());
+        // Loading alone doesn't write the state file
+        assert_eq!(fs::read_to_string(&state_file).unwrap(), saved);
+    }
 
-        // Create teams
-        let team_id1 = budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
-        let team_id2 = budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), Some(vec![2000]), None).unwrap();
+    #[tokio::test]
+    async fn test_close_expired_votes() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        budget_system.config.default_qualified_majority_threshold = 0.1;
+        create_test_epoch(&mut budget_system);
+        let mut teams = Vec::new();
+        for name in ["Team A", "Team B", "Team C"] {
+            teams.push(budget_system.create_team(name.to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap());
+        }
 
-        // Prepare and finalize raffle
-        let config = budget_system.config().clone();
-        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
-        let mock_randomness = "mock_randomness".to_string();
-        budget_system.finalize_raffle(raffle_id, 12345, 12355, mock_randomness).await.unwrap();
+        let past = Utc::now() - Duration::days(1);
+        let (expired, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Expired").await;
+        let expired_vote = budget_system.create_formal_vote(expired, raffle_id, None, Some(past)).unwrap();
+        budget_system.cast_votes(expired_vote, vec![(teams[0], VoteChoice::Yes)]).unwrap();
+
+        // Only one of three seats votes, so the quorum is missed
+        budget_system.config.default_quorum = 0.5;
+        let (quiet, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Quiet").await;
+        let quiet_vote = budget_system.create_formal_vote(quiet, raffle_id, None, Some(past)).unwrap();
+        budget_system.cast_votes(quiet_vote, vec![(teams[0], VoteChoice::Yes)]).unwrap();
+
+        let (pending, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Pending").await;
+        let pending_vote = budget_system.create_formal_vote(pending, raffle_id, None, Some(Utc::now() + Duration::days(1))).unwrap();
+        let (open, raffle_id) = create_proposal_with_raffle(&mut budget_system, "No Deadline").await;
+        let open_vote = budget_system.create_formal_vote(open, raffle_id, None, None).unwrap();
+
+        let output = budget_system.execute_command(Command::CloseExpiredVotes).await.unwrap();
+        assert!(output.starts_with("Closed 2 expired vote(s)"));
+        assert!(output.contains("- Expired: Approved"));
+        assert!(output.contains("- Quiet: Rejected"));
+
+        let vote = budget_system.state.get_vote(&expired_vote).unwrap();
+        assert!(vote.is_closed());
+        assert_eq!(vote.closed_at(), Some(past));
+        assert!(budget_system.get_proposal(&expired).unwrap().is_approved());
+        assert_eq!(budget_system.get_proposal(&expired).unwrap().resolved_at(), Some(past.date_naive()));
+        assert!(budget_system.get_proposal(&quiet).unwrap().is_rejected());
+        assert!(budget_system.generate_vote_report(quiet_vote).unwrap().contains("Failed: quorum not met"));
+        assert!(!budget_system.state.get_vote(&pending_vote).unwrap().is_closed());
+        assert!(!budget_system.state.get_vote(&open_vote).unwrap().is_closed());
+
+        assert!(budget_system.close_expired_votes().unwrap().is_empty());
+    }
 
-        // Create and process a formal vote
-        let formal_vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None).unwrap();
-        budget_system.cast_votes(formal_vote_id, vec![(team_id1, VoteChoice::Yes), (team_id2, VoteChoice::No)]).unwrap();
+    #[tokio::test]
+    async fn test_create_and_process_vote_with_delegation() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let team_ids: Vec<Uuid> = ["Team A", "Team B", "Team C"].iter()
+            .map(|name| budget_system.create_team(name.to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap())
+            .collect();
+        create_proposal_with_raffle(&mut budget_system, "Delegated").await;
+
+        let counted = HashMap::from([
+            ("Team A".to_string(), VoteChoice::Yes),
+            ("Team C".to_string(), VoteChoice::Yes),
+        ]);
+        let delegation = |delegator: &str, delegate: &str| HashMap::from([(delegator.to_string(), delegate.to_string())]);
+
+        // Rejected before any vote is created
+        let err = budget_system.create_and_process_vote("Delegated", counted.clone(), HashMap::new(), delegation("Team A", "Team A"), None, None, None).unwrap_err();
+        assert!(err.to_string().contains("cannot delegate its seat to itself"));
+        let chain = HashMap::from([
+            ("Team A".to_string(), "Team C".to_string()),
+            ("Team C".to_string(), "Team B".to_string()),
+        ]);
+        let err = budget_system.create_and_process_vote("Delegated", counted.clone(), HashMap::new(), chain, None, None, None).unwrap_err();
+        assert!(err.to_string().contains("A delegate cannot delegate its own seat"));
+        let err = budget_system.create_and_process_vote("Delegated", counted.clone(), HashMap::new(), delegation("Team B", "Team A"), None, None, None).unwrap_err();
+        assert!(err.to_string().contains("No vote from Team B to delegate"));
+        assert!(budget_system.state.votes().is_empty());
+
+        let report = budget_system.create_and_process_vote("Delegated", counted, HashMap::new(), delegation("Team A", "Team B"), None, None, None).unwrap();
+        assert!(report.contains("Delegated votes: Team A (voted by Team B)"));
+
+        // The seat's points go to the delegator
+        let points = budget_system.get_team_points_for_epoch(team_ids[0], epoch_id).unwrap();
+        assert!(points > 0);
+        assert_eq!(budget_system.get_team_points_for_epoch(team_ids[1], epoch_id).unwrap(), 0);
+        assert_eq!(budget_system.get_team_points_for_epoch(team_ids[2], epoch_id).unwrap(), points);
+        assert!(budget_system.verify_point_consistency().is_ok());
+    }
 
-        // Test closing a vote
-        let vote_result = budget_system.close_vote(formal_vote_id).unwrap();
-        let closed_vote = budget_system.get_vote(&formal_vote_id).unwrap();
-        assert!(closed_vote.is_closed());
-        assert!(matches!(closed_vote.result(), Some(VoteResult::Formal { .. })));
+    #[tokio::test]
+    async fn test_create_and_process_vote_past_deadline() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, _) = create_proposal_with_raffle(&mut budget_system, "Late").await;
+
+        let closed = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let deadline = Utc.with_ymd_and_hms(2024, 1, 9, 23, 59, 59).unwrap();
+        let counted = HashMap::from([("Team A".to_string(), VoteChoice::Yes)]);
+        let err = budget_system.create_and_process_vote("Late", counted.clone(), HashMap::new(), HashMap::new(), None, Some(closed), Some(deadline)).unwrap_err();
+        assert!(err.to_string().contains("past its deadline of 2024-01-09"));
+        assert!(budget_system.state.votes().is_empty());
+
+        let deadline = Utc.with_ymd_and_hms(2024, 1, 10, 23, 59, 59).unwrap();
+        budget_system.create_and_process_vote("Late", counted, HashMap::new(), HashMap::new(), None, Some(closed), Some(deadline)).unwrap();
+        let vote = budget_system.get_vote_for_proposal(proposal_id).unwrap();
+        assert_eq!(vote.deadline(), Some(deadline));
+    }
 
-        // Verify vote result
-        if let Some(VoteResult::Formal { counted, uncounted, passed }) = closed_vote.result() {
-            assert_eq!(counted.yes() + counted.no(), 2);
-            assert_eq!(uncounted.yes() + uncounted.no(), 0);
-            assert_eq!(*passed, vote_result);
-        } else {
-            panic!("Expected Formal vote result");
+    #[tokio::test]
+    async fn test_vote_fails_without_quorum() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        budget_system.config.default_quorum = 0.5;
+        budget_system.config.default_qualified_majority_threshold = 0.1;
+
+        create_test_epoch(&mut budget_system);
+        let mut teams = Vec::new();
+        for name in ["Team A", "Team B", "Team C", "Team D", "Team E", "Team F", "Team G"] {
+            teams.push(budget_system.create_team(name.to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap());
         }
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Quiet Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        let counted = budget_system.get_raffle(&raffle_id).unwrap().result().unwrap().counted().to_vec();
+        budget_system.cast_votes(vote_id, counted[..3].iter().map(|&team| (team, VoteChoice::Yes)).collect()).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
 
-        // Test error case: closing an already closed vote
-        assert!(budget_system.close_vote(formal_vote_id).is_err());
+        // Three Yes votes clear the 10% threshold, but 4 of 7 seats had to vote
+        let vote = budget_system.state.get_vote(&vote_id).unwrap();
+        assert_eq!(vote.quorum_status(), Some((4, 3)));
+        assert!(matches!(vote.result(), Some(VoteResult::Formal { passed: false, .. })));
+        let report = budget_system.generate_vote_report(vote_id).unwrap();
+        assert!(report.contains("**Status: Failed: quorum not met (4/7 required, 3 cast)**"));
     }
 
     #[tokio::test]
-    async fn test_reporting() {
+    async fn test_abstain_votes_earn_points() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
-    
-        let epoch_id = create_active_epoch(&mut budget_system).await;
-        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
-        
-        // Create proposal and raffle
-        let proposal_id = budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None).unwrap();
-        let config = budget_system.config().clone();
-        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
-        
-        // Finalize raffle with the team included
-        let mock_randomness = "mock_randomness".to_string();
-        budget_system.finalize_raffle(raffle_id, 12345, 12355, mock_randomness).await.unwrap();
-    
-        // Create and process a vote
-        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None).unwrap();
-        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let team1 = budget_system.create_team("Team A".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
+        let team2 = budget_system.create_team("Team B".to_string(), "Rep B".to_string(), Some(vec![1000]), None).unwrap();
+        let team3 = budget_system.create_team("Team C".to_string(), "Rep C".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team1, VoteChoice::Yes), (team2, VoteChoice::Abstain)]).unwrap();
         budget_system.close_vote(vote_id).unwrap();
-    
-        // Generate reports
-        let team_report = budget_system.print_team_report();
-        assert!(team_report.contains("Test Team"));
-    
-        let epoch_state = budget_system.print_epoch_state().unwrap();
-        assert!(epoch_state.contains("Test Proposal"));
-    
-        let proposal_report = budget_system.generate_proposal_report(proposal_id).unwrap();
-        assert!(proposal_report.contains("Test Proposal"));
-    
-        let point_report = budget_system.generate_point_report(None).unwrap();
-        assert!(point_report.contains("Test Team"));
-    
-        // Close proposal before closing epoch
-        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
-    
-        budget_system.close_epoch(None).unwrap();
-        budget_system.generate_end_of_epoch_report(&budget_system.get_epoch(&epoch_id).unwrap().name()).unwrap();
+
+        let report = budget_system.generate_vote_report(vote_id).unwrap();
+        assert!(report.contains("__1 in favor, 0 against, 1 abstained, "));
+
+        let points1 = budget_system.calculate_team_points_for_epoch(team1, epoch_id);
+        assert!(points1 > 0);
+        assert_eq!(budget_system.calculate_team_points_for_epoch(team2, epoch_id), points1);
+        assert_eq!(budget_system.calculate_team_points_for_epoch(team3, epoch_id), 0);
     }
 
     #[tokio::test]
-    async fn test_integration() {
+    async fn test_abstain_points_configurable() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
+        budget_system.config.abstain_points = 1;
 
-        // Create and activate an epoch
-        let epoch_id = create_active_epoch(&mut budget_system).await;
-        budget_system.set_epoch_reward("ETH", 1000.0).unwrap();
-
-        // Create teams
-        let team_id1 = budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
-        let team_id2 = budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), Some(vec![2000]), None).unwrap();
-        let team_id3 = budget_system.create_team("Team 3".to_string(), "Rep 3".to_string(), None, None).unwrap();
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let team1 = budget_system.create_team("Team A".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
+        let team2 = budget_system.create_team("Team B".to_string(), "Rep B".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team1, VoteChoice::No), (team2, VoteChoice::Abstain)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
 
-        // Create a proposal
-        let proposal_id = budget_system.add_proposal(
-            "Test Proposal".to_string(),
-            Some("http://example.com".to_string()),
-            Some(BudgetRequestDetails::new(
-                Some(team_id1),
-                [("ETH".to_string(), 100.0)].iter().cloned().collect(),
-                Some(Utc::now().date_naive()),
-                Some((Utc::now() + Duration::days(30)).date_naive()),
-                Some(false),
-                None,
-            ).unwrap()),
-            Some(Utc::now().date_naive()),
-            Some(Utc::now().date_naive()),
-            None
-        ).unwrap();
+        assert_eq!(budget_system.calculate_team_points_for_epoch(team1, epoch_id), 5);
+        assert_eq!(budget_system.calculate_team_points_for_epoch(team2, epoch_id), 1);
+        assert_eq!(budget_system.get_team_points_for_epoch(team2, epoch_id).unwrap(), 1);
 
-        // Conduct a raffle
-        let config = budget_system.config().clone();
-        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
-        budget_system.finalize_raffle(raffle_id, 12345, 12355, "mock_randomness".to_string()).await.unwrap();
-        
-        // Generate epoch report
-        let epoch_state = budget_system.print_epoch_state().unwrap();
-        assert!(epoch_state.contains("Test Proposal"));
+        let vote = budget_system.state.get_vote(&vote_id).unwrap();
+        let tables = budget_system.generate_vote_participation_tables(vote);
+        assert!(tables.contains("| Team A | 5 |"));
+        assert!(tables.contains("#### Abstentions\n"));
+        assert!(tables.contains("| Team B | Counted | 1 |"));
+        assert!(!tables.contains("| Team B | 5 |"));
+    }
 
-        // Create and process a vote
-        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None).unwrap();
-        budget_system.cast_votes(vote_id, vec![
-            (team_id1, VoteChoice::Yes),
-            (team_id2, VoteChoice::Yes),
-            (team_id3, VoteChoice::No)
-        ]).unwrap();
-        let vote_result = budget_system.close_vote(vote_id).unwrap();
-        
-        // Verify the actual vote result
-        let vote = budget_system.get_vote(&vote_id).unwrap();
-        if let Some(VoteResult::Formal { passed, .. }) = vote.result() {
-            assert_eq!(*passed, vote_result);
-        } else {
-            panic!("Expected Formal vote result");
-        }
+    #[tokio::test]
+    async fn test_close_epoch_summary() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Close the proposal
+        create_test_epoch(&mut budget_system);
+        budget_system.set_epoch_reward("ETH", 100.0).unwrap();
+        let team_id = budget_system.create_team("Test Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Idle Team".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Test Proposal").await;
+        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
+        budget_system.close_vote(vote_id).unwrap();
         budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
-        
-
-        // Close the epoch
-        budget_system.close_epoch(None).unwrap();
 
-        // Generate other report
-        let team_report = budget_system.print_team_report();
-        let proposal_report = budget_system.generate_proposal_report(proposal_id).unwrap();
-        let point_report = budget_system.generate_point_report(Some("Test Epoch")).unwrap();
-        budget_system.generate_end_of_epoch_report(&budget_system.get_epoch(&epoch_id).unwrap().name()).unwrap();
+        let output = budget_system.execute_command(Command::CloseEpoch { epoch_name: None }).await.unwrap();
+        let epoch_id = budget_system.get_epoch_id_by_name("Test Epoch").unwrap();
+        let points = budget_system.calculate_team_points_for_epoch(team_id, epoch_id);
+        assert!(output.starts_with("Successfully closed epoch: Active epoch\n\nClosed epoch: Test Epoch\n"));
+        assert!(output.contains("Rewards: 100 ETH\n"));
+        assert!(output.contains(&format!("  - Test Team: {} points (100.00%: 100.00 ETH)\n", points)));
+        assert!(!output.contains("Idle Team"));
 
-        // Verify the integrations
-        assert!(team_report.contains("Team 1") && team_report.contains("Team 2") && team_report.contains("Team 3"));
-        assert!(proposal_report.contains("Approved"));
-        assert!(point_report.contains("Team 1") && point_report.contains("Team 2") && point_report.contains("Team 3"));
+        // Force closing an already closed epoch is refused before anything is retracted
+        assert_eq!(budget_system.force_close_epoch(Some("Test Epoch")), Err(BudgetSystemError::EpochAlreadyClosed));
+    }
 
-        // Verify the final state
-        let closed_epoch = budget_system.get_epoch(&epoch_id).unwrap();
-        assert!(closed_epoch.is_closed());
-        let closed_proposal = budget_system.get_proposal(&proposal_id).unwrap();
-        assert!(closed_proposal.is_closed());
-        assert_eq!(closed_proposal.resolution(), Some(Resolution::Approved));
+    #[tokio::test]
+    async fn test_get_vote_and_raffle_for_proposal() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+        budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), None, None).unwrap();
+
+        let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, "With Raffle").await;
+        let (other_id, other_raffle_id) = create_proposal_with_raffle(&mut budget_system, "Other").await;
+        let bare_id = budget_system.add_proposal("Bare".to_string(), None, None, None, None, None, None).unwrap();
+
+        assert_eq!(budget_system.get_raffle_for_proposal(proposal_id).map(|r| r.id()), Some(raffle_id));
+        assert_eq!(budget_system.get_raffle_for_proposal(other_id).map(|r| r.id()), Some(other_raffle_id));
+        assert!(budget_system.get_raffle_for_proposal(bare_id).is_none());
+        assert!(budget_system.get_vote_for_proposal(proposal_id).is_none());
+        assert!(budget_system.get_latest_vote_for_proposal(proposal_id).is_none());
+
+        let informal_id = budget_system.create_informal_vote(proposal_id).unwrap();
+        budget_system.get_vote_for_proposal_mut(proposal_id).unwrap()
+            .set_opened_at(Utc::now() - chrono::Duration::days(1));
+        let formal_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+        assert_eq!(budget_system.get_latest_vote_for_proposal(proposal_id).map(|v| v.id()), Some(formal_id));
+        assert!(budget_system.get_vote(&informal_id).unwrap().opened_at() < budget_system.get_vote(&formal_id).unwrap().opened_at());
+        assert!(budget_system.get_vote_for_proposal(other_id).is_none());
+        assert!(budget_system.get_vote_for_proposal_mut(bare_id).is_none());
+
+        budget_system.get_raffle_for_proposal_mut(proposal_id).unwrap().config_mut().set_initiation_block(42);
+        assert_eq!(budget_system.get_raffle(&raffle_id).unwrap().config().initiation_block(), 42);
+        assert_ne!(budget_system.get_raffle(&other_raffle_id).unwrap().config().initiation_block(), 42);
+        assert!(budget_system.get_raffle_for_proposal_mut(bare_id).is_none());
     }
 
     #[tokio::test]
-    async fn test_error_handling_and_edge_cases() {
+    async fn test_get_raffle_exclusions() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Test handling of non-existent entities
-        assert!(budget_system.get_team(&Uuid::new_v4()).is_none());
-        assert!(budget_system.get_proposal(&Uuid::new_v4()).is_none());
-        assert!(budget_system.get_epoch(&Uuid::new_v4()).is_none());
-        assert!(budget_system.get_raffle(&Uuid::new_v4()).is_none());
-        assert!(budget_system.get_vote(&Uuid::new_v4()).is_none());
+        create_active_epoch(&mut budget_system).await;
+        let proposal_id = budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), None, None).unwrap();
 
-        // Test behavior with empty state
-        assert!(budget_system.print_epoch_state().is_err());
-        assert!(budget_system.generate_point_report(None).is_err());
+        let config = budget_system.config().clone();
+        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", Some(vec!["Team 2".to_string()]), &config).unwrap();
+        assert_eq!(budget_system.get_raffle_exclusions(raffle_id), vec!["Team 2".to_string()]);
+        assert!(budget_system.get_raffle_exclusions(Uuid::new_v4()).is_empty());
+
+        let report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(report.contains("- **Excluded Teams**: Team 2\n"));
+
+        // An excluded team that is no longer known anywhere
+        let removed_id = Uuid::new_v4();
+        budget_system.state.get_raffle_mut(&raffle_id).unwrap().config_mut()
+            .set_excluded_teams(vec![removed_id]);
+        assert_eq!(
+            budget_system.get_raffle_exclusions(raffle_id),
+            vec![format!("Unknown Team ({})", removed_id)]
+        );
+        let report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(report.contains(&format!("- **Excluded Teams**: Unknown Team ({})\n", removed_id)));
+    }
 
-        // Test invalid inputs
-        assert!(budget_system.create_epoch("", Utc::now(), Utc::now()).is_err());
-        assert!(budget_system.create_team("".to_string(), "Rep".to_string(), None, None).is_err());
-        assert!(budget_system.set_epoch_reward("ETH", -100.0).is_err());
+    async fn setup_approved_payment_proposal(budget_system: &mut BudgetSystem) -> Uuid {
+        let epoch_id = budget_system.create_epoch("Test Epoch", Utc::now(), Utc::now() + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        let proposal_id = create_test_proposal(budget_system, "Proposal1", vec![1000.0]);
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        proposal_id
+    }
 
-        // Test overlapping epochs
-        let epoch1_id = budget_system.create_epoch("Epoch 1", Utc::now(), Utc::now() + Duration::days(30)).unwrap();
-        assert!(budget_system.create_epoch("Epoch 2", Utc::now() + Duration::days(15), Utc::now() + Duration::days(45)).is_err());
+    #[tokio::test]
+    async fn test_record_payments_recipient_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let proposal_id = setup_approved_payment_proposal(&mut budget_system).await;
 
-        // Test activating multiple epochs
-        budget_system.activate_epoch(epoch1_id).unwrap();
-        let epoch2_id = budget_system.create_epoch("Epoch 2", Utc::now() + Duration::days(31), Utc::now() + Duration::days(61)).unwrap();
-        assert!(budget_system.activate_epoch(epoch2_id).is_err());
+        let other = "0x1111111111111111111111111111111111111111".parse::<Address>().unwrap();
+        get_mock_service(&budget_system).unwrap().set_transaction_recipient(Some(other));
 
-        // Test closing an epoch with open proposals
-        let _proposal_id = budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None).unwrap();
-        assert!(budget_system.close_epoch(None).is_err());
+        let tx = format!("0x{}", "ab".repeat(32));
+        let result = budget_system.record_payments(&tx, Utc::now().date_naive(), &["Proposal1".to_string()], false).await;
 
-        // Test updating a non-existent proposal
-        let updates = UpdateProposalDetails {
-            title: Some("Updated Title".to_string()),
-            url: None,
-            budget_request_details: None,
-            announced_at: None,
-            published_at: None,
-            resolved_at: None,
-        };
-        assert!(budget_system.update_proposal("Non-existent Proposal", updates).is_err());
+        assert!(matches!(result, Err(BudgetSystemError::PaymentVerificationFailed(ref msg)) if msg.contains("does not match")));
+        assert!(!budget_system.get_proposal(&proposal_id).unwrap().budget_request_details().unwrap().is_paid());
+    }
 
-        // Test creating a raffle for a non-existent proposal
-        let config = budget_system.config().clone();
-        assert!(budget_system.prepare_raffle("Non-existent Proposal", None, &config).is_err());
+    #[tokio::test]
+    async fn test_record_payments_token_and_batch_transfers() {
+        use crate::services::ethereum::{TokenTransfer, MOCK_TX_RECIPIENT};
+        use ethers::types::U256;
 
-        // Test casting votes for a non-existent vote
-        assert!(budget_system.cast_votes(Uuid::new_v4(), vec![(Uuid::new_v4(), VoteChoice::Yes)]).is_err());
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        setup_approved_payment_proposal(&mut budget_system).await;
 
-        // Test closing a non-existent vote
-        assert!(budget_system.close_vote(Uuid::new_v4()).is_err());
+        let second_address = "0x2222222222222222222222222222222222222222";
+        let details = BudgetRequestDetails::new(
+            None, HashMap::from([("ETH".to_string(), 50.0)]), None, None, Some(false), Some(second_address.to_string())
+        ).unwrap();
+        let second_id = budget_system.add_proposal("Proposal2".to_string(), None, Some(details), None, None, None, None).unwrap();
+        budget_system.close_with_reason(second_id, &Resolution::Approved).unwrap();
+
+        // A Safe batch moves ETH through a contract call, which leaves nothing to check
+        let safe = "0x3333333333333333333333333333333333333333".parse::<Address>().unwrap();
+        let mock = get_mock_service(&budget_system).unwrap();
+        mock.set_transaction_recipient(Some(safe));
+        mock.set_transaction_value(U256::zero());
+        let names = ["Proposal1".to_string(), "Proposal2".to_string()];
+        let tx = format!("0x{}", "ab".repeat(32));
+        let result = budget_system.record_payments(&tx, Utc::now().date_naive(), &names, false).await;
+        assert!(matches!(result, Err(BudgetSystemError::PaymentVerificationFailed(ref msg)) if msg.contains("cannot verify token/batch payment")));
+
+        // ERC-20 transfers to one recipient leave the other unverified
+        let token = "0x6b175474e89094c44da98b954eedeac495271d0f".parse::<Address>().unwrap();
+        let transfer = |to: &str| TokenTransfer { token, from: safe, to: to.parse().unwrap(), amount: U256::from(50) };
+        let mock = get_mock_service(&budget_system).unwrap();
+        mock.set_token_transfers(vec![transfer(MOCK_TX_RECIPIENT)]);
+        let result = budget_system.record_payments(&tx, Utc::now().date_naive(), &names, false).await;
+        assert!(matches!(result, Err(BudgetSystemError::PaymentVerificationFailed(ref msg)) if msg.contains("'Proposal2' does not match")));
+        assert!(!budget_system.get_proposal(&second_id).unwrap().budget_request_details().unwrap().is_paid());
+
+        get_mock_service(&budget_system).unwrap().set_token_transfers(vec![transfer(MOCK_TX_RECIPIENT), transfer(second_address)]);
+        budget_system.record_payments(&tx, Utc::now().date_naive(), &names, false).await.unwrap();
+        assert!(budget_system.get_proposal(&second_id).unwrap().budget_request_details().unwrap().is_paid());
     }
 
     #[tokio::test]
-    async fn test_ethereum_service_interaction() {
+    async fn test_record_payments_transaction_not_found() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
-        
         let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let proposal_id = setup_approved_payment_proposal(&mut budget_system).await;
 
-        // Test successful interactions
-        assert_eq!(budget_system.get_current_block().await.unwrap(), 12345);
-        assert_eq!(budget_system.get_randomness(12355).await.unwrap(), "mock_randomness_for_block_12355");
-        
-        let (init_block, rand_block, randomness) = budget_system.get_raffle_randomness().await.unwrap();
-        assert_eq!(init_block, 12345);
-        assert_eq!(rand_block, 12355);
-        assert_eq!(randomness, "mock_randomness_for_block_12355");
+        get_mock_service(&budget_system).unwrap().set_transaction_recipient(None);
 
-        // Test raffle creation with Ethereum service interaction
-        create_active_epoch(&mut budget_system).await;
-        budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None).unwrap();
-        
-        let config = budget_system.config().clone();
-        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
-        
-        let raffle = budget_system.finalize_raffle(raffle_id, 12345, 12355, "mock_randomness".to_string()).await.unwrap();
-        
-        assert_eq!(raffle.config().initiation_block(), 12345);
-        assert_eq!(raffle.config().randomness_block(), 12355);
-        assert_eq!(raffle.config().block_randomness(), "mock_randomness");
+        let tx = format!("0x{}", "ab".repeat(32));
+        let result = budget_system.record_payments(&tx, Utc::now().date_naive(), &["Proposal1".to_string()], false).await;
+
+        assert!(matches!(result, Err(BudgetSystemError::PaymentVerificationFailed(ref msg)) if msg.contains("not found")));
+        assert!(!budget_system.get_proposal(&proposal_id).unwrap().budget_request_details().unwrap().is_paid());
     }
 
     #[tokio::test]
-    async fn test_raffle_creation_stream() {
-        use futures::pin_mut;
-        use std::time::Duration;
-        use std::sync::Arc;
-
-        // Create mock service
-        let mock_service = Arc::new(MockEthereumService::new());        
-
+    async fn test_record_payments_skip_verification() {
         let temp_dir = TempDir::new().unwrap();
-        
-        // Create budget system with our mock service
-        let mut budget_system = {
-            let config = AppConfig {
-                state_file: temp_dir.path().join("test_state.json").to_str().unwrap().to_string(),
-                ipc_path: "/tmp/test_reth.ipc".to_string(),
-                future_block_offset: 2, // Small offset for testing
-                script_file: "test_script.json".to_string(),
-                default_total_counted_seats: 7,
-                default_max_earner_seats: 5,
-                default_qualified_majority_threshold: 0.7,
-                counted_vote_points: 5,
-                uncounted_vote_points: 2,
-                telegram: TelegramConfig {
-                    chat_id: "test_chat_id".to_string(),
-                    token: "test_token".to_string(),
-                },
-            };
-            BudgetSystem::new(config, mock_service, None).await.unwrap()
-        };
-        
-        // Setup block progression before executing command
-        if let Some(mock_service) = get_mock_service(&budget_system) {
-            setup_block_progression(mock_service).await;
-        }
-
-        // Setup test data
-        create_active_epoch(&mut budget_system).await;
-        
-        // Add test teams
-        budget_system.create_team("Team 1".to_string(), "Rep 1".to_string(), Some(vec![1000]), None).unwrap();
-        budget_system.create_team("Team 2".to_string(), "Rep 2".to_string(), Some(vec![2000]), None).unwrap();
-        
-        budget_system.add_proposal(
-            "Test Proposal".to_string(),
-            None,
-            None,
-            Some(Utc::now().date_naive()),
-            Some(Utc::now().date_naive()),
-            None
-        ).unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let proposal_id = setup_approved_payment_proposal(&mut budget_system).await;
 
-        // Create and pin the stream
-        let progress_stream = budget_system.create_raffle_with_progress(
-            "Test Proposal".to_string(),
-            Some(2), // Small offset for testing
-            None
-        ).await;
-        pin_mut!(progress_stream);
+        get_mock_service(&budget_system).unwrap().set_transaction_recipient(None);
 
-        // Collect updates with longer timeout
-        let mut updates = Vec::new();
-        while let Some(progress) = tokio::time::timeout(
-            Duration::from_secs(10), // Increased timeout
-            progress_stream.next()
-        ).await.unwrap() {
-            let progress = progress.unwrap();
-            println!("Received progress update: {:?}", progress);
-            updates.push(progress);
-            
-            if matches!(updates.last().unwrap(), RaffleProgress::Completed { .. }) {
-                break;
-            }
-        }
+        let result = budget_system.execute_command(Command::LogPayment {
+            payment_tx: format!("0x{}", "cd".repeat(32)),
+            payment_date: Utc::now().date_naive(),
+            proposal_names: vec!["Proposal1".to_string()],
+            skip_verification: true,
+        }).await;
 
-        // Verify states
-        assert!(!updates.is_empty(), "Should have received updates");
-        assert!(matches!(updates[0], RaffleProgress::Preparing { .. }), "First update should be Preparing");
-        
-        let has_waiting = updates.iter().any(|p| matches!(p, RaffleProgress::WaitingForBlock { .. }));
-        assert!(has_waiting, "Should have WaitingForBlock state");
-        
-        let has_randomness = updates.iter().any(|p| matches!(p, RaffleProgress::RandomnessAcquired { .. }));
-        assert!(has_randomness, "Should have RandomnessAcquired state");
-        
-        assert!(matches!(updates.last().unwrap(), RaffleProgress::Completed { .. }), "Should end with Completed state");
+        assert!(result.is_ok());
+        assert!(budget_system.get_proposal(&proposal_id).unwrap().budget_request_details().unwrap().is_paid());
+    }
 
-        if let RaffleProgress::Completed { counted, uncounted, .. } = updates.last().unwrap() {
-            assert!(!counted.is_empty() || !uncounted.is_empty(), "Raffle should contain teams");
-            println!("Final raffle result - Counted teams: {:?}, Uncounted teams: {:?}", counted, uncounted);
-        }
+    fn write_import_file(temp_dir: &TempDir, contents: &str) -> String {
+        let path = temp_dir.path().join("proposals.json");
+        fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
     }
 
     #[tokio::test]
-    async fn test_create_raffle_with_progress() {
+    async fn test_import_proposals_partial_failures() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
-        
         let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+
+        let file = write_import_file(&temp_dir, r#"[
+            {"title": "First", "url": "https://example.com/1"},
+            {"title": "Unknown Team", "budget_request_details": {"team": "Nobody", "request_amounts": {"ETH": 1.0}}},
+            {"url": "https://example.com/untitled"},
+            {"title": "Second", "budget_request_details": {"request_amounts": {"ETH": 5.0}}}
+        ]"#);
+
+        let summary = budget_system.import_proposals(&file, false).unwrap();
+
+        assert_eq!((summary.total, summary.imported, summary.skipped), (4, 2, 2));
+        assert_eq!(summary.errors.iter().map(|e| e.index).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(summary.errors[0].error.contains("Team not found: Nobody"));
+        assert_eq!(summary.errors[1].title, None);
+        assert!(budget_system.get_proposal_id_by_name("First").is_some());
+        assert!(budget_system.get_proposal_id_by_name("Second").is_some());
+        assert!(budget_system.get_proposal_id_by_name("Unknown Team").is_none());
+    }
 
-        // Setup required state
-        create_active_epoch(&mut budget_system).await;
-        budget_system.add_proposal(
-            "Test Proposal".to_string(),
-            None,
-            None,
-            Some(Utc::now().date_naive()),
-            Some(Utc::now().date_naive()),
-            None
-        ).unwrap();
+    #[tokio::test]
+    async fn test_import_proposals_duplicate_titles() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+        create_test_proposal(&mut budget_system, "Existing", vec![100.0]);
 
-        // Add some teams
-        budget_system.create_team("Team1".to_string(), "Rep1".to_string(), Some(vec![1000]), None).unwrap();
-        budget_system.create_team("Team2".to_string(), "Rep2".to_string(), Some(vec![2000]), None).unwrap();
+        let file = write_import_file(&temp_dir, r#"[
+            {"title": "Existing"},
+            {"title": "New"},
+            {"title": "New"}
+        ]"#);
 
-        // Setup block progression before executing command
-        if let Some(mock_service) = get_mock_service(&budget_system) {
-            setup_block_progression(mock_service).await;
-        }
+        let summary = budget_system.import_proposals(&file, false).unwrap();
 
-        // Create the progress stream and collect updates in their own scope
-        let updates = {
-            let progress_stream = budget_system.create_raffle_with_progress(
-                "Test Proposal".to_string(),
-                Some(1), // Small offset for testing
-                None,
-            ).await;
+        assert_eq!((summary.imported, summary.skipped), (1, 2));
+        assert!(summary.errors.iter().all(|e| e.error.contains("Duplicate proposal title")));
+        assert_eq!(summary.errors.iter().map(|e| e.index).collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(budget_system.state().proposals().values().filter(|p| p.title() == "New").count(), 1);
+    }
 
-            let mut updates = Vec::new();
-            pin_mut!(progress_stream);
-            
-            while let Some(progress) = progress_stream.next().await {
-                match progress {
-                    Ok(update) => {
-                        updates.push(update.clone());
-                        if matches!(update, RaffleProgress::Completed { .. }) {
-                            break;
-                        }
-                    },
-                    Err(e) => panic!("Unexpected error: {}", e),
-                }
-            }
-            updates
-        }; // progress_stream is dropped here, releasing the mutable borrow
+    #[tokio::test]
+    async fn test_import_proposals_missing_epoch() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Now we can borrow budget_system again
-        
-        // Verify progress sequence
-        assert!(matches!(updates[0], RaffleProgress::Preparing { .. }));
-        assert!(matches!(updates[1], RaffleProgress::WaitingForBlock { .. }));
-        assert!(matches!(updates[2], RaffleProgress::RandomnessAcquired { .. }));
-        assert!(matches!(updates[3], RaffleProgress::Completed { .. }));
+        let file = write_import_file(&temp_dir, r#"[{"title": "Orphan"}, {"title": "Another"}]"#);
 
-        // Verify final state
-        if let RaffleProgress::Completed { ref counted, ref uncounted, .. } = updates[3] {
-            assert_eq!(counted.len() + uncounted.len(), 2); // All teams should be assigned
-        } else {
-            panic!("Final update should be Completed");
-        }
+        let summary = budget_system.import_proposals(&file, false).unwrap();
 
-        // Verify raffle was created in system
-        assert_eq!(budget_system.state().raffles().len(), 1);
+        assert_eq!((summary.imported, summary.skipped), (0, 2));
+        assert!(summary.errors.iter().all(|e| e.error == BudgetSystemError::NoActiveEpoch.to_string()));
+        assert!(budget_system.state().proposals().is_empty());
     }
 
-    // Test error cases
     #[tokio::test]
-    async fn test_create_raffle_with_progress_invalid_proposal() {
+    async fn test_import_proposals_dry_run() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
-        
         let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
 
-        // Setup block progression before executing command
-        if let Some(mock_service) = get_mock_service(&budget_system) {
-            setup_block_progression(mock_service).await;
-        }
+        let file = write_import_file(&temp_dir, r#"[{"title": "Dry"}, {"title": "Dry"}]"#);
 
-        let progress_stream = budget_system.create_raffle_with_progress(
-            "NonExistent".to_string(),
-            None,
-            None,
-        ).await;
+        let output = budget_system.execute_command(Command::ImportProposals { file_path: file, dry_run: true }).await.unwrap();
+        let summary: ImportSummary = serde_json::from_str(&output).unwrap();
 
-        pin_mut!(progress_stream);
-        
-        // Should fail on first update
-        let first_update = progress_stream.next().await.unwrap();
-        assert!(first_update.is_err());
+        assert!(summary.dry_run);
+        assert_eq!((summary.total, summary.imported, summary.skipped), (2, 1, 1));
+        assert!(budget_system.state().proposals().is_empty());
     }
 
     #[tokio::test]
-    async fn test_generate_unpaid_requests_report() {
+    async fn test_list_proposals_paginated() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let epoch_id = create_test_epoch(&mut budget_system);
 
-        // Create an epoch
-        let _epoch_id = create_active_epoch(&mut budget_system).await;
+        let ids: Vec<Uuid> = (1..=12)
+            .map(|i| create_test_proposal(&mut budget_system, &format!("Proposal {:02}", i), vec![100.0 * i as f64]))
+            .collect();
+        budget_system.close_with_reason(ids[0], &Resolution::Approved).unwrap();
+        budget_system.close_with_reason(ids[1], &Resolution::Approved).unwrap();
+        budget_system.close_with_reason(ids[2], &Resolution::Rejected).unwrap();
+
+        let (page, total) = budget_system.list_proposals_paginated(Some(epoch_id), None, None, false, 2, PROPOSAL_PAGE_SIZE);
+        assert_eq!(total, 12);
+        let titles: Vec<_> = page.iter().map(|p| p.title()).collect();
+        assert_eq!(titles, vec!["Proposal 11", "Proposal 12"]);
+
+        let (page, total) = budget_system.list_proposals_paginated(Some(epoch_id), Some(ProposalListFilter::Approved), None, false, 1, PROPOSAL_PAGE_SIZE);
+        assert_eq!((page.len(), total), (2, 2));
+        let (_, total) = budget_system.list_proposals_paginated(None, Some(ProposalListFilter::Open), None, false, 1, PROPOSAL_PAGE_SIZE);
+        assert_eq!(total, 9);
+
+        let output = budget_system.execute_command(Command::ListProposals { epoch_name: None, status: None, team: None, unpaid_only: false, page: Some(2) }).await.unwrap();
+        assert!(output.starts_with("All proposals in epoch Test Epoch (12 total)"));
+        assert!(output.contains("11. Proposal 11 (Open)"));
+        assert!(output.contains("   URL: http://example.com"));
+        assert!(output.contains("   Requested: 1200 ETH0"));
+        assert!(output.contains("   Open for 0 days"));
+        assert!(output.ends_with("Page 2 of 2"));
+
+        let output = budget_system.execute_command(Command::ListProposals { epoch_name: None, status: Some(ProposalListFilter::Rejected), team: None, unpaid_only: false, page: None }).await.unwrap();
+        assert!(output.contains("1. Proposal 03 (Rejected)"));
+        assert!(!output.contains("Open for"));
+
+        assert!(budget_system.execute_command(Command::ListProposals { epoch_name: None, status: None, team: None, unpaid_only: false, page: Some(3) }).await.is_err());
+    }
 
-        // Create a team
-        let team_id = budget_system.create_team(
-            "Test Team".to_string(),
-            "Representative".to_string(),
-            Some(vec![1000]),
-            None
-        ).unwrap();
+    #[tokio::test]
+    async fn test_list_proposals_by_team_and_payment() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let team_id = budget_system.create_team("Alpha".to_string(), "Rep".to_string(), Some(vec![1000]), None).unwrap();
+
+        let paid_id = create_test_proposal(&mut budget_system, "Paid Proposal", vec![100.0]);
+        let unpaid_id = create_test_proposal(&mut budget_system, "Unpaid Proposal", vec![200.0]);
+        let open_id = create_test_proposal(&mut budget_system, "Open Proposal", vec![300.0]);
+        for id in [paid_id, unpaid_id] {
+            budget_system.close_with_reason(id, &Resolution::Approved).unwrap();
+        }
+        for id in [unpaid_id, open_id] {
+            budget_system.state.get_proposal_mut(&id)
+                .and_then(|p| p.budget_request_details_mut())
+                .unwrap()
+                .set_team(Some(team_id));
+        }
+        let payment_tx = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e";
+        budget_system.record_payments(payment_tx, Utc::now().date_naive(), &["Paid Proposal".to_string()], true).await.unwrap();
 
-        // Create a proposal with budget request
-        let mut amounts = HashMap::new();
-        amounts.insert("ETH".to_string(), 100.0);
-        
-        let proposal_id = budget_system.add_proposal(
-            "Test Proposal".to_string(),
-            None,
-            Some(BudgetRequestDetails::new(
-                Some(team_id),
-                amounts,
-                None,
-                None,
-                Some(false),
-                Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string()),
-            ).unwrap()),
-            Some(Utc::now().date_naive()),
-            Some(Utc::now().date_naive()),
-            None,
-        ).unwrap();
+        let titles = |filter, team_id, unpaid_only, bs: &BudgetSystem| -> Vec<String> {
+            bs.list_proposals_paginated(Some(epoch_id), filter, team_id, unpaid_only, 1, PROPOSAL_PAGE_SIZE).0
+                .iter().map(|p| p.title().to_string()).collect()
+        };
+        assert_eq!(titles(Some(ProposalListFilter::Paid), None, false, &budget_system), vec!["Paid Proposal"]);
+        assert_eq!(titles(Some(ProposalListFilter::Unpaid), None, false, &budget_system), vec!["Unpaid Proposal"]);
+        assert_eq!(titles(None, None, true, &budget_system), vec!["Unpaid Proposal"]);
+        assert!(titles(Some(ProposalListFilter::Paid), None, true, &budget_system).is_empty());
+        assert_eq!(titles(None, Some(team_id), false, &budget_system).len(), 2);
+        assert_eq!(titles(Some(ProposalListFilter::Open), Some(team_id), false, &budget_system), vec!["Open Proposal"]);
+
+        // Proposals split between teams match each allocated team
+        let beta_id = budget_system.create_team("Beta".to_string(), "Rep".to_string(), None, None).unwrap();
+        let split_id = create_test_proposal(&mut budget_system, "Split Proposal", vec![50.0]);
+        budget_system.state.get_proposal_mut(&split_id)
+            .and_then(|p| p.budget_request_details_mut())
+            .unwrap()
+            .set_team_allocations(HashMap::from([
+                (team_id, HashMap::from([("ETH0".to_string(), 20.0)])),
+                (beta_id, HashMap::from([("ETH0".to_string(), 30.0)])),
+            ]))
+            .unwrap();
+        assert_eq!(titles(Some(ProposalListFilter::Open), Some(team_id), false, &budget_system), vec!["Open Proposal", "Split Proposal"]);
+        assert_eq!(titles(None, Some(beta_id), false, &budget_system), vec!["Split Proposal"]);
+        assert_eq!(budget_system.get_proposals_for_team(beta_id).len(), 1);
+
+        let output = budget_system.execute_command(Command::ListProposals {
+            epoch_name: None, status: Some(ProposalListFilter::Approved), team: Some("Alpha".to_string()), unpaid_only: false, page: None,
+        }).await.unwrap();
+        assert!(output.starts_with("Approved proposals in epoch Test Epoch for Alpha (1 total)"));
+        assert!(output.contains("   Team: Alpha\n   Requested: 200 ETH0\n   Paid: no"));
+
+        let output = budget_system.execute_command(Command::ListProposals {
+            epoch_name: None, status: None, team: None, unpaid_only: true, page: None,
+        }).await.unwrap();
+        assert!(output.starts_with("Unpaid proposals in epoch Test Epoch (1 total)"));
+
+        let output = budget_system.execute_command(Command::ListProposals {
+            epoch_name: None, status: Some(ProposalListFilter::Paid), team: None, unpaid_only: false, page: None,
+        }).await.unwrap();
+        assert!(output.contains("   Paid: yes"));
+
+        let result = budget_system.execute_command(Command::ListProposals {
+            epoch_name: None, status: None, team: Some("Alpah".to_string()), unpaid_only: false, page: None,
+        }).await;
+        assert!(result.unwrap_err().to_string().contains("Did you mean: Alpha"));
+    }
 
-        // Approve the proposal
+    #[tokio::test]
+    async fn test_dry_run_discards_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        create_test_epoch(&mut budget_system);
+        let proposal_id = create_test_proposal(&mut budget_system, "Existing", vec![100.0]);
+        let saved = fs::read_to_string(&state_file).unwrap();
+
+        budget_system.start_dry_run();
+        assert!(budget_system.is_dry_run());
+        budget_system.execute_command(Command::AddTeam {
+            name: "New Team".to_string(),
+            representative: "Rep".to_string(),
+            trailing_monthly_revenue: None,
+            address: None,
+        }).await.unwrap();
         budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        assert_eq!(fs::read_to_string(&state_file).unwrap(), saved);
+
+        let diff = budget_system.finish_dry_run();
+        assert_eq!(diff.added_teams, vec!["New Team".to_string()]);
+        assert_eq!(diff.modified_proposals.len(), 1);
+        assert!(diff.change_lines().contains(&"create team New Team".to_string()));
+        assert!(budget_system.get_team_id_by_name("New Team").is_none());
+        assert!(!budget_system.get_proposal(&proposal_id).unwrap().is_closed());
+        assert!(budget_system.finish_dry_run().is_empty());
+    }
 
-        // Generate report
-        let output_path = temp_dir.path().join("test_report.json");
-        let result = budget_system.generate_unpaid_requests_report(
-            Some(output_path.to_str().unwrap()),
-            None,
-        );
+    #[tokio::test]
+    async fn test_validate_command_checks_preconditions() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        assert!(result.is_ok());
+        let add_proposal = Command::AddProposal {
+            title: "New Proposal".to_string(),
+            url: None,
+            budget_request_details: None,
+            announced_at: None,
+            published_at: None,
+            is_historical: None,
+        };
+        let err = budget_system.validate_command(&add_proposal).unwrap_err();
+        assert!(matches!(err.downcast_ref::<BudgetSystemError>(), Some(BudgetSystemError::NoActiveEpoch)));
+
+        let epoch_id = create_test_epoch(&mut budget_system);
+        let epoch = budget_system.get_epoch(&epoch_id).unwrap().clone();
+        let overlapping = Command::CreateEpoch {
+            name: "Overlap".to_string(),
+            start_date: epoch.start_date(),
+            end_date: epoch.end_date(),
+        };
+        let err = budget_system.validate_command(&overlapping).unwrap_err();
+        assert!(matches!(err.downcast_ref::<BudgetSystemError>(), Some(BudgetSystemError::EpochOverlap)));
+
+        let output = budget_system.validate_command(&add_proposal).unwrap();
+        assert!(output.starts_with("[dry-run] Would execute: AddProposal"));
+        assert!(budget_system.get_proposal_id_by_name("New Proposal").is_none());
+
+        let proposal_id = create_test_proposal(&mut budget_system, "Closed", vec![100.0]);
+        budget_system.close_with_reason(proposal_id, &Resolution::Rejected).unwrap();
+        let close = Command::CloseProposal { proposal_name: "Closed".to_string(), resolution: "Approved".to_string() };
+        let err = budget_system.validate_command(&close).unwrap_err();
+        assert!(matches!(err.downcast_ref::<BudgetSystemError>(), Some(BudgetSystemError::ProposalAlreadyClosed)));
+    }
 
-        // Verify report contents
-        let report_content = fs::read_to_string(output_path).unwrap();
-        let report: UnpaidRequestsReport = serde_json::from_str(&report_content).unwrap();
-        
-        assert_eq!(report.unpaid_requests.len(), 1);
-        assert_eq!(report.unpaid_requests[0].title, "Test Proposal");
-        assert_eq!(report.unpaid_requests[0].team_name, "Test Team");
+    #[tokio::test]
+    async fn test_import_proposals_rejects_non_array() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+
+        let file = write_import_file(&temp_dir, r#"{"title": "Not an array"}"#);
+
+        assert!(budget_system.import_proposals(&file, false).is_err());
     }
 
     #[tokio::test]
-   async fn test_record_payments_success() {
-       let temp_dir = TempDir::new().unwrap();
-       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
- 
-       let mut budget_system = create_test_budget_system(&state_file, None).await;
-    
-       // Create test epoch and activate it
-       let start_date = Utc::now();
-       let end_date = start_date + Duration::days(30);
-       let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
-       budget_system.activate_epoch(epoch_id).unwrap();
-       
-       // Create test proposals with budget requests
-       let proposal1_id = create_test_proposal(&mut budget_system, "Proposal1", vec![1000.0]);
-       let proposal2_id = create_test_proposal(&mut budget_system, "Proposal2", vec![2000.0]);
-       
-       // Approve the proposals
-       budget_system.close_with_reason(proposal1_id, &Resolution::Approved).unwrap();
-       budget_system.close_with_reason(proposal2_id, &Resolution::Approved).unwrap();
+    async fn test_get_team_payment_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-       // Record payments
-       let result = budget_system.record_payments(
-           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
-           Utc::now().date_naive(),
-           &vec!["Proposal1".to_string(), "Proposal2".to_string()]
-       );
+        let team_id = budget_system.create_team("Team A".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
+        let idle = budget_system.create_team("Idle Team".to_string(), "Rep B".to_string(), None, None).unwrap();
+        let approve_for_team = |budget_system: &mut BudgetSystem, proposal_id: Uuid, is_loan: bool| {
+            let details = budget_system.state.get_proposal_mut(&proposal_id).unwrap()
+                .budget_request_details_mut().unwrap();
+            details.set_team(Some(team_id));
+            details.set_is_loan(is_loan);
+            budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        };
 
-       assert!(result.is_ok());
-       
-       // Verify payments recorded
-       let proposal1 = budget_system.get_proposal(&proposal1_id).unwrap();
-       let proposal2 = budget_system.get_proposal(&proposal2_id).unwrap();
-       
-       assert!(proposal1.budget_request_details().unwrap().is_paid());
-       assert!(proposal2.budget_request_details().unwrap().is_paid());
-   }
+        let old_start = Utc::now() - Duration::days(60);
+        let old_epoch = budget_system.create_epoch("Old Epoch", old_start, old_start + Duration::days(30)).unwrap();
+        budget_system.activate_epoch(old_epoch).unwrap();
+        let old_grant = create_test_proposal(&mut budget_system, "Old Grant", vec![10.0]);
+        approve_for_team(&mut budget_system, old_grant, false);
+        budget_system.state.get_epoch_mut(&old_epoch).unwrap().set_status(EpochStatus::Closed);
+        budget_system.state.set_current_epoch(None);
+
+        create_test_epoch(&mut budget_system);
+        let unpaid_loan = create_test_proposal(&mut budget_system, "A Loan", vec![20.0]);
+        approve_for_team(&mut budget_system, unpaid_loan, true);
+        let paid_grant = create_test_proposal(&mut budget_system, "Z Grant", vec![30.0]);
+        approve_for_team(&mut budget_system, paid_grant, false);
+        let open = create_test_proposal(&mut budget_system, "Still Open", vec![40.0]);
+        budget_system.state.get_proposal_mut(&open).unwrap()
+            .budget_request_details_mut().unwrap()
+            .set_team(Some(team_id));
+
+        let tx = format!("0x{}", "ab".repeat(32));
+        budget_system.record_payments(&tx, Utc::now().date_naive(), &["Old Grant".to_string(), "Z Grant".to_string()], true).await.unwrap();
+
+        let history = budget_system.get_team_payment_history(team_id);
+        let titles: Vec<&str> = history.iter().map(|r| r.proposal_title.as_str()).collect();
+        assert_eq!(titles, vec!["Old Grant", "Z Grant", "A Loan"]);
+        assert_eq!(history[0].epoch_name, "Old Epoch");
+        assert_eq!(history[1].payment_tx, Some(tx.clone()));
+        assert_eq!(history[1].payment_date, Some(Utc::now().date_naive()));
+        assert!(!history[1].is_loan);
+        assert!(history[2].is_loan);
+        assert_eq!(history[2].payment_date, None);
+        assert_eq!(history[2].payment_tx, None);
+
+        assert!(budget_system.get_team_payment_history(idle).is_empty());
+
+        let output = budget_system.execute_command(Command::PrintTeamPaymentHistory {
+            team_name: "Team A".to_string(),
+        }).await.unwrap();
+        assert!(output.contains("| Epoch | Proposal | Amounts | Type | Paid | Transaction |"));
+        assert!(output.contains(&format!("| Test Epoch | Z Grant | 30 ETH0 | Grant | {} | {} |", Utc::now().date_naive().format("%Y-%m-%d"), tx)));
+        assert!(output.contains("| Test Epoch | A Loan | 20 ETH0 | Loan | Unpaid | - |"));
+        assert!(!output.contains("Still Open"));
+
+        let output = budget_system.execute_command(Command::PrintTeamPaymentHistory {
+            team_name: "Idle Team".to_string(),
+        }).await.unwrap();
+        assert_eq!(output, "No approved budget requests found for team Idle Team");
+
+        assert!(budget_system.execute_command(Command::PrintTeamPaymentHistory {
+            team_name: "Missing".to_string(),
+        }).await.is_err());
+    }
 
-   #[tokio::test]
-   async fn test_record_payments_future_date() {
-       let temp_dir = TempDir::new().unwrap();
-       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
- 
-       let mut budget_system = create_test_budget_system(&state_file, None).await;
-    
-       
-       let future_date = Utc::now().date_naive() + Duration::days(1);
-       
-       let result = budget_system.record_payments(
-           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
-           future_date,
-           &vec!["Proposal1".to_string()]
-       );
+    #[tokio::test]
+    async fn test_create_raffle_with_progress_reports_retries() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-       assert!(result.is_err());
-       assert!(result.unwrap_err().to_string().contains("future"));
-   }
+        create_active_epoch(&mut budget_system).await;
+        budget_system.add_proposal("Test Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        budget_system.add_proposal("Other Proposal".to_string(), None, None, None, None, None, None).unwrap();
+        budget_system.create_team("Team1".to_string(), "Rep1".to_string(), Some(vec![1000]), None).unwrap();
+        let mock_service = get_mock_service(&budget_system).unwrap();
 
-   #[tokio::test]
-   async fn test_record_payments_non_existent_proposal() {
-       let temp_dir = TempDir::new().unwrap();
-       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
- 
-       let mut budget_system = create_test_budget_system(&state_file, None).await;
-    
-       let result = budget_system.record_payments(
-           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
-           Utc::now().date_naive(),
-           &vec!["NonExistentProposal".to_string()]
-       );
+        // Two transient failures are retried and reported, then the raffle completes
+        mock_service.set_failure_count(2);
+        let updates: Vec<_> = {
+            let progress_stream = budget_system.create_raffle_with_progress("Test Proposal".to_string(), Some(0), None).await;
+            pin_mut!(progress_stream);
+            let mut updates = Vec::new();
+            while let Some(progress) = progress_stream.next().await {
+                updates.push(progress.unwrap());
+            }
+            updates
+        };
+        let attempts: Vec<u32> = updates.iter()
+            .filter_map(|p| match p {
+                RaffleProgress::Retrying { attempt, error } => {
+                    assert_eq!(error, "mock RPC failure");
+                    Some(*attempt)
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(attempts, vec![1, 2]);
+        assert!(matches!(updates[0], RaffleProgress::Preparing { .. }));
+        assert!(matches!(updates.last().unwrap(), RaffleProgress::Completed { .. }));
+        assert!(RaffleProgress::Retrying { attempt: 1, error: "timeout".to_string() }.raffle_id().is_none());
 
-       assert!(result.is_err());
-       assert!(result.unwrap_err().to_string().contains("not found"));
-   }
+        // Failing every attempt still ends the stream with a transient error
+        mock_service.set_failure_count(3);
+        let results: Vec<_> = {
+            let progress_stream = budget_system.create_raffle_with_progress("Other Proposal".to_string(), Some(0), None).await;
+            pin_mut!(progress_stream);
+            let mut results = Vec::new();
+            while let Some(progress) = progress_stream.next().await {
+                results.push(progress);
+            }
+            results
+        };
+        assert_eq!(results.iter().filter(|r| matches!(r, Ok(RaffleProgress::Retrying { .. }))).count(), 2);
+        assert!(results.last().unwrap().as_ref().unwrap_err().is_transient());
+    }
 
-   #[tokio::test]
-   async fn test_record_payments_not_approved() {
-       let temp_dir = TempDir::new().unwrap();
-       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
-    
-       let mut budget_system = create_test_budget_system(&state_file, None).await;
-       // Create test epoch and proposal but don't approve it
-       let _epoch_id = create_test_epoch(&mut budget_system);
-       let _proposal_id = create_test_proposal(&mut budget_system, "Proposal1", vec![1000.0]);
+    #[tokio::test]
+    async fn test_generate_team_performance_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-       let result = budget_system.record_payments(
-           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
-           Utc::now().date_naive(),
-           &vec!["Proposal1".to_string()]
-       );
+        let team_a = budget_system.create_team("Team A".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
+        let team_b = budget_system.create_team("Team B".to_string(), "Rep B".to_string(), None, None).unwrap();
 
-       assert!(result.is_err());
-       assert!(result.unwrap_err().to_string().contains("not approved"));
-   }
+        let now = Utc::now();
+        let epochs: Vec<Uuid> = (0..4)
+            .map(|i| {
+                let start = now - Duration::days(120 - 30 * i);
+                let end = if i == 3 { now + Duration::days(30) } else { start + Duration::days(29) };
+                budget_system.create_epoch(&format!("Epoch {}", i + 1), start, end).unwrap()
+            })
+            .collect();
+        budget_system.create_epoch("Epoch 5", now + Duration::days(31), now + Duration::days(60)).unwrap();
+
+        // Votes cast per epoch: one proposal each, two in the last epoch
+        let voters_per_epoch = [
+            vec![vec![team_a, team_b]],
+            vec![vec![team_b]],
+            vec![vec![team_a, team_b]],
+            vec![vec![team_a, team_b], vec![team_a]],
+        ];
 
-   #[tokio::test]
-   async fn test_record_payments_already_paid() {
-       let temp_dir = TempDir::new().unwrap();
-       let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
-    
-       let mut budget_system = create_test_budget_system(&state_file, None).await;
+        for (i, (epoch_id, votes)) in epochs.iter().zip(voters_per_epoch).enumerate() {
+            budget_system.activate_epoch(*epoch_id).unwrap();
+            for (j, voters) in votes.into_iter().enumerate() {
+                let (proposal_id, raffle_id) = create_proposal_with_raffle(&mut budget_system, &format!("Proposal {}-{}", i + 1, j + 1)).await;
+                let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None, None).unwrap();
+                budget_system.cast_votes(vote_id, voters.into_iter().map(|t| (t, VoteChoice::Yes)).collect()).unwrap();
+                budget_system.close_vote(vote_id).unwrap();
+                budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+            }
+            if i == 2 {
+                let grant = create_test_proposal(&mut budget_system, "Team A Grant", vec![10.0]);
+                budget_system.state.get_proposal_mut(&grant).unwrap()
+                    .budget_request_details_mut().unwrap()
+                    .set_team(Some(team_a));
+                budget_system.close_with_reason(grant, &Resolution::Approved).unwrap();
+                let tx = format!("0x{}", "ab".repeat(32));
+                budget_system.record_payments(&tx, now.date_naive(), &["Team A Grant".to_string()], true).await.unwrap();
+            }
+            if i < 3 {
+                budget_system.close_epoch(None).unwrap();
+            }
+        }
 
-       // Create and approve proposal
-       let _epoch_id = create_test_epoch(&mut budget_system);
-       let proposal_id = create_test_proposal(&mut budget_system, "Proposal1", vec![1000.0]);
-       budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        let report = budget_system.generate_team_performance_report("Team A").unwrap();
+        assert!(report.starts_with("# Team Performance Report: Team A\n"));
+        assert!(report.contains("| Epoch 1 | 5 | 1 | 1 | 0 | 100.0% | 100.0% | - |"));
+        assert!(report.contains("| Epoch 2 | 0 | 0 | 0 | 0 | 0.0% | 50.0% | - |"));
+        assert!(report.contains("| Epoch 3 | 5 | 1 | 1 | 0 | 100.0% | 66.7% | 10 ETH0 |"));
+        assert!(report.contains("| Epoch 4 | 10 | 2 | 2 | 0 | 100.0% | 66.7% | - |"));
+        assert!(!report.contains("Epoch 5"));
+        assert!(report.contains("- **Points**: 20\n"));
+        assert!(report.contains("- **Payments Received**: 10 ETH0\n"));
+        assert!(report.contains("- **Trend**: Improving\n"));
+
+        let report = budget_system.generate_team_performance_report("Team B").unwrap();
+        assert!(report.contains("| Epoch 4 | 5 | 1 | 1 | 0 | 50.0% | 83.3% | - |"));
+        assert!(report.contains("- **Payments Received**: None\n"));
+        assert!(report.contains("- **Trend**: Stable\n"));
+
+        assert!(budget_system.generate_team_performance_report("Missing").is_err());
+
+        let output = budget_system.execute_command(Command::GenerateTeamPerformanceReport {
+            team_name: "Team A".to_string(),
+            output_path: None,
+        }).await.unwrap();
+        let default_path = temp_dir.path().join("reports").join("Epoch_4").join("Team_A_performance.md");
+        assert!(output.contains("Team_A_performance.md"));
+        assert!(fs::read_to_string(&default_path).unwrap().contains("| Epoch 4 | 10 |"));
+
+        let custom_path = temp_dir.path().join("custom").join("team_b.md");
+        budget_system.execute_command(Command::GenerateTeamPerformanceReport {
+            team_name: "Team B".to_string(),
+            output_path: Some(custom_path.to_str().unwrap().to_string()),
+        }).await.unwrap();
+        assert!(fs::read_to_string(&custom_path).unwrap().starts_with("# Team Performance Report: Team B"));
+    }
 
-       // Record payment first time
-       budget_system.record_payments(
-           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
-           Utc::now().date_naive(),
-           &vec!["Proposal1".to_string()]
-       ).unwrap();
+    #[test]
+    fn test_points_trend() {
+        assert_eq!(BudgetSystem::points_trend(&[5, 5]), "Not enough data (needs 3 epochs)");
+        assert_eq!(BudgetSystem::points_trend(&[0, 10, 5, 0]), "Declining");
+        assert_eq!(BudgetSystem::points_trend(&[1, 20, 21, 21]), "Stable");
+        assert_eq!(BudgetSystem::points_trend(&[0, 0, 1]), "Improving");
+    }
 
-       // Try to record payment second time
-       let result = budget_system.record_payments(
-           "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e",
-           Utc::now().date_naive(),
-           &vec!["Proposal1".to_string()]
-       );
+    #[tokio::test]
+    async fn test_restore_backup_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-       assert!(result.is_err());
-       assert!(result.unwrap_err().to_string().contains("already paid"));
-   }
+        budget_system.create_team("Team A".to_string(), "Rep A".to_string(), None, None).unwrap();
+        budget_system.save_state().unwrap();
+        budget_system.create_team("Team B".to_string(), "Rep B".to_string(), None, None).unwrap();
+        budget_system.save_state().unwrap();
 
-   // Helper functions
+        // Newest backup taken while only Team A existed
+        let timestamp = FileSystem::list_backups(&state_file).unwrap().into_iter().rev()
+            .find(|(_, path)| FileSystem::load_state(path.to_str().unwrap()).unwrap().current_state().teams().len() == 1)
+            .map(|(timestamp, _)| timestamp)
+            .unwrap();
+
+        // Without confirmation nothing changes
+        let output = budget_system.execute_command(Command::RestoreBackup {
+            timestamp: Some(timestamp.clone()),
+            confirm: false,
+        }).await.unwrap();
+        assert!(output.contains("Run again with --confirm"));
+        assert_eq!(budget_system.state().current_state().teams().len(), 2);
+
+        let output = budget_system.execute_command(Command::RestoreBackup {
+            timestamp: Some(timestamp.clone()),
+            confirm: true,
+        }).await.unwrap();
+        assert!(output.starts_with(&format!("Restored Backup {}", timestamp)));
+        assert_eq!(budget_system.state().current_state().teams().len(), 1);
+        assert_eq!(FileSystem::load_state(&state_file).unwrap().current_state().teams().len(), 1);
+
+        // The replaced state was backed up, so the restore can be undone
+        let (undo_timestamp, _) = FileSystem::list_backups(&state_file).unwrap().pop().unwrap();
+        assert_ne!(undo_timestamp, timestamp);
+        budget_system.restore_backup(Some(&undo_timestamp), true).unwrap();
+        assert_eq!(budget_system.state().current_state().teams().len(), 2);
+        assert!(budget_system.get_team_id_by_name("Team B").is_some());
+
+        assert!(budget_system.restore_backup(Some("2024-01-01T00:00:00.000000Z"), true).is_err());
+    }
 
-   fn create_test_epoch(budget_system: &mut BudgetSystem) -> Uuid {
-       let start_date = Utc::now();
-       let end_date = start_date + Duration::days(30);
-       let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
-       budget_system.activate_epoch(epoch_id).unwrap();
-       epoch_id
-   }
+    #[tokio::test]
+    async fn test_restore_backup_from_bak_after_partial_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
+        let mut budget_system = create_test_budget_system(&state_file, None).await;
+        budget_system.config.state_backup_count = 0;
 
-   fn create_test_proposal(budget_system: &mut BudgetSystem, name: &str, amounts: Vec<f64>) -> Uuid {
-       let mut request_amounts = HashMap::new();
-       for (i, amount) in amounts.iter().enumerate() {
-           request_amounts.insert(format!("ETH{}", i), *amount);
-       }
-       
-       let budget_details = BudgetRequestDetails::new(
-           None,
-           request_amounts,
-           Some(Utc::now().date_naive()),
-           Some((Utc::now() + Duration::days(30)).date_naive()),
-           Some(false),
-           Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string())
-       ).unwrap();
+        // Each change saves the state, keeping the previous file as .bak
+        budget_system.create_team("Team A".to_string(), "Rep A".to_string(), None, None).unwrap();
+        budget_system.create_team("Team B".to_string(), "Rep B".to_string(), None, None).unwrap();
 
-       budget_system.add_proposal(
-           name.to_string(),
-           Some("http://example.com".to_string()),
-           Some(budget_details),
-           Some(Utc::now().date_naive()),
-           Some(Utc::now().date_naive()),
-           None
-       ).unwrap()
-   }
+        // Simulate a crash that left the state file half written
+        let json = fs::read_to_string(&state_file).unwrap();
+        fs::write(&state_file, &json[..json.len() / 2]).unwrap();
+        assert!(FileSystem::load_state(&state_file).is_err());
 
-   #[tokio::test]
-    async fn test_generate_epoch_payments_report() {
+        let output = budget_system.execute_command(Command::RestoreBackup { timestamp: None, confirm: false }).await.unwrap();
+        assert!(output.starts_with(&format!("Backup {}.bak: 1 teams", state_file)));
+
+        budget_system.execute_command(Command::RestoreBackup { timestamp: None, confirm: true }).await.unwrap();
+        assert_eq!(budget_system.state().current_state().teams().len(), 1);
+        assert_eq!(FileSystem::load_state(&state_file).unwrap().current_state().teams().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_end_of_epoch_report_csv_and_json() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Create and setup epoch
-        let start_date = Utc::now();
-        let end_date = start_date + Duration::days(30);
-        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
-        budget_system.activate_epoch(epoch_id).unwrap();
-        budget_system.set_epoch_reward("ETH", 1000.0).unwrap();
-
-        // Add team with payment address
-        let team_id = budget_system.create_team(
-            "Test Team".to_string(),
-            "Representative".to_string(),
-            Some(vec![1000]),
-            Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string())
-        ).unwrap();
+        create_test_epoch(&mut budget_system);
+        budget_system.set_epoch_reward("ETH", 100.0).unwrap();
+        let team_id = budget_system.create_team("Team A".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
 
-        // Create a proposal and setup voting to generate some team rewards
-        let proposal_id = budget_system.add_proposal(
-            "Test Proposal".to_string(),
+        let details = BudgetRequestDetails::new(
+            Some(team_id),
+            HashMap::from([("ETH".to_string(), 10.0), ("USD".to_string(), 50.0)]),
             None,
             None,
-            Some(Utc::now().date_naive()),
-            Some(Utc::now().date_naive()),
-            None
+            Some(false),
+            None,
         ).unwrap();
-
-        // Create and complete raffle
-        let config = budget_system.config().clone();
-        let (raffle_id, _) = budget_system.prepare_raffle("Test Proposal", None, &config).unwrap();
-        budget_system.finalize_raffle(
-            raffle_id,
-            12345,
-            12355,
-            "mock_randomness".to_string()
-        ).await.unwrap();
-
-        // Create and process vote
-        let vote_id = budget_system.create_formal_vote(proposal_id, raffle_id, None).unwrap();
+        let grant = budget_system.add_proposal("Grant, With Comma".to_string(), None, Some(details), None, None, None, None).unwrap();
+        budget_system.close_with_reason(grant, &Resolution::Approved).unwrap();
+        let plain = budget_system.add_proposal("Plain".to_string(), None, None, None, None, None, None).unwrap();
+        budget_system.close_with_reason(plain, &Resolution::Rejected).unwrap();
+        let (voted, raffle_id) = create_proposal_with_raffle(&mut budget_system, "Voted Proposal").await;
+        let vote_id = budget_system.create_formal_vote(voted, raffle_id, None, None).unwrap();
         budget_system.cast_votes(vote_id, vec![(team_id, VoteChoice::Yes)]).unwrap();
         budget_system.close_vote(vote_id).unwrap();
-
-        // Close proposal and epoch
-        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
+        budget_system.close_with_reason(voted, &Resolution::Rejected).unwrap();
         budget_system.close_epoch(None).unwrap();
 
-        // Generate report
-        let report = budget_system.generate_epoch_payments_report("Test Epoch", None).unwrap();
-        let parsed: EpochPaymentsReport = serde_json::from_str(&report).unwrap();
-
-        assert_eq!(parsed.epoch_name, "Test Epoch");
-        assert_eq!(parsed.reward_token, "ETH");
-        assert_eq!(parsed.total_reward, 1000.0);
-        assert_eq!(parsed.payments.len(), 1);
-        assert_eq!(parsed.payments[0].team_name, "Test Team");
-        assert!(parsed.payments[0].default_payment_address.is_some());
+        budget_system.execute_command(Command::GenerateEndOfEpochReport {
+            epoch_name: "Test Epoch".to_string(),
+            format: ReportFormat::Csv,
+        }).await.unwrap();
+
+        let report_dir = temp_dir.path().join("reports").join("Test_Epoch");
+        let markdown = fs::read_to_string(report_dir.join("end_of_epoch_report-Test_Epoch.md")).unwrap();
+        assert!(markdown.contains("| Name | URL | Team | Amounts | Start Date | End Date | Announced | Resolved | Paid | Report |\n|------|-----|------|---------|------------|----------|-----------|----------|------|--------|\n"));
+        assert!(markdown.contains("| Team Name | Status | Counted Votes | Uncounted Votes | Total Points | % of Total Points | Reward Amount |\n|-----------|--------|---------------|-----------------|--------------|-------------------|---------------|\n"));
+
+        let approved = fs::read_to_string(report_dir.join("proposals_approved-Test_Epoch.csv")).unwrap();
+        let lines: Vec<&str> = approved.lines().collect();
+        assert_eq!(lines[0], "Name,URL,Team,Amounts (ETH),Amounts (USD),Start Date,End Date,Announced,Resolved,Paid,Report");
+        assert!(lines[1].starts_with("\"Grant, With Comma\",N/A,Team A,10,50,N/A,N/A,N/A,"));
+        assert!(lines[1].contains(",Unpaid,"));
+        assert!(!lines[1].contains("[Report]"));
+
+        let rejected = fs::read_to_string(report_dir.join("proposals_rejected-Test_Epoch.csv")).unwrap();
+        let lines: Vec<&str> = rejected.lines().collect();
+        assert_eq!(lines[0], "Name,URL,Team,Start Date,End Date,Announced,Resolved,Report");
+        assert!(lines.iter().any(|l| l.starts_with("Plain,N/A,N/A,N/A,N/A,N/A,")));
+        assert!(!report_dir.join("proposals_retracted-Test_Epoch.csv").exists());
+
+        let summary = fs::read_to_string(report_dir.join("team_summary-Test_Epoch.csv")).unwrap();
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines[0], "Team Name,Status,Counted Votes,Uncounted Votes,Total Points,% of Total Points,Reward Amount (ETH)");
+        assert!(lines[1].starts_with("Team A,Earner,1,0,"));
+        assert!(lines[1].ends_with(",100.00%,100"));
+
+        budget_system.generate_end_of_epoch_report("Test Epoch", ReportFormat::Json).unwrap();
+        let json: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(report_dir.join("end_of_epoch_report-Test_Epoch.json")).unwrap()
+        ).unwrap();
+        assert_eq!(json["proposals_approved"][0]["Name"], "Grant, With Comma");
+        assert_eq!(json["proposals_approved"][0]["Amounts (USD)"], "50");
+        assert_eq!(json["team_summary"][0]["Team Name"], "Team A");
     }
 
     #[tokio::test]
-    async fn test_generate_epoch_payments_report_not_closed() {
+    async fn test_fuzzy_match_suggests_names() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Create active epoch but don't close it
-        let start_date = Utc::now();
-        let end_date = start_date + Duration::days(30);
-        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
-        budget_system.activate_epoch(epoch_id).unwrap();
-
-        let result = budget_system.generate_epoch_payments_report("Test Epoch", None);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("not closed"));
+        create_test_epoch(&mut budget_system);
+        let alpha = budget_system.create_team("Team Alpha".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
+        let proposal_id = budget_system.add_proposal("Grant Proposal".to_string(), None, None, None, None, None, None).unwrap();
+
+        assert_eq!(budget_system.find_closest_team_name("Team Alpha"), Some((alpha, "Team Alpha", 0)));
+        assert_eq!(budget_system.find_closest_team_name("Teem Alpha"), Some((alpha, "Team Alpha", 1)));
+        assert_eq!(budget_system.find_closest_proposal_name("Grant Propsal"), Some((proposal_id, "Grant Proposal", 1)));
+
+        let err = budget_system.execute_command(Command::UpdateTeam {
+            team_name: "Teem Alpha".to_string(),
+            updates: UpdateTeamDetails { name: None, representative: Some("Rep B".to_string()), status: None, trailing_monthly_revenue: None, address: None },
+        }).await.unwrap_err();
+        assert_eq!(err.to_string(), "Team 'Teem Alpha' not found. Did you mean: Team Alpha?");
+
+        let err = budget_system.execute_command(Command::CloseProposal {
+            proposal_name: "grant proposal".to_string(),
+            resolution: "Approved".to_string(),
+        }).await.unwrap_err();
+        assert_eq!(err.to_string(), "Proposal 'grant proposal' not found. Did you mean: Grant Proposal?");
+
+        budget_system.add_proposal("Grant Proposals".to_string(), None, None, None, None, None, None).unwrap();
+        let err = budget_system.execute_command(Command::UpdateProposal {
+            proposal_name: "Grant Propsal".to_string(),
+            updates: UpdateProposalDetails {
+                title: None,
+                url: None,
+                budget_request_details: None,
+                announced_at: None,
+                published_at: None,
+                resolved_at: None,
+                category: None,
+            },
+        }).await.unwrap_err();
+        assert_eq!(err.to_string(), "Proposal 'Grant Propsal' not found. Did you mean: Grant Proposal?");
+
+        let output = budget_system.execute_command(Command::CreateAndProcessVote {
+            proposal_name: "Grant Proposalz".to_string(),
+            counted_votes: HashMap::new(),
+            uncounted_votes: HashMap::new(),
+            delegations: HashMap::new(),
+            vote_opened: None,
+            vote_closed: None,
+            deadline: None,
+        }).await.unwrap();
+        // Names tied for closest are all suggested
+        assert!(output.contains("Proposal 'Grant Proposalz' not found. Did you mean: Grant Proposal, Grant Proposals?"));
+
+        // Too far from any existing name to suggest one
+        let err = budget_system.execute_command(Command::UpdateTeam {
+            team_name: "Gamma".to_string(),
+            updates: UpdateTeamDetails { name: None, representative: None, status: None, trailing_monthly_revenue: None, address: None },
+        }).await.unwrap_err();
+        assert_eq!(err.to_string(), "Team not found: Gamma");
+
+        budget_system.config.allow_fuzzy_match = false;
+        let err = budget_system.execute_command(Command::UpdateTeam {
+            team_name: "Teem Alpha".to_string(),
+            updates: UpdateTeamDetails { name: None, representative: None, status: None, trailing_monthly_revenue: None, address: None },
+        }).await.unwrap_err();
+        assert_eq!(err.to_string(), "Team not found: Teem Alpha");
     }
 
     #[tokio::test]
-    async fn test_generate_epoch_payments_report_no_reward() {
+    async fn test_fuzzy_match_ambiguous_team_name() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
 
-        // Create epoch and close it but don't set reward
-        let start_date = Utc::now();
-        let end_date = start_date + Duration::days(30);
-        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
-        budget_system.activate_epoch(epoch_id).unwrap();
-        budget_system.close_epoch(None).unwrap();
-
-        let result = budget_system.generate_epoch_payments_report("Test Epoch", None);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("no reward"));
-    }
+        budget_system.create_team("Team A".to_string(), "Rep A".to_string(), Some(vec![1000]), None).unwrap();
+        budget_system.create_team("Team B".to_string(), "Rep B".to_string(), Some(vec![1000]), None).unwrap();
 
-    #[test]
-    fn test_format_team_status() {
-        let earner_status = TeamStatus::Earner { 
-            trailing_monthly_revenue: vec![1000, 2000, 3000] 
-        };
-        assert_eq!(format_team_status(&earner_status), "Earner");
-        assert_eq!(format_team_status(&TeamStatus::Supporter), "Supporter");
-        assert_eq!(format_team_status(&TeamStatus::Inactive), "Inactive");
+        assert_eq!(budget_system.find_closest_team_name("Team C"), None);
+        let err = budget_system.execute_command(Command::UpdateTeam {
+            team_name: "Team C".to_string(),
+            updates: UpdateTeamDetails { name: None, representative: None, status: None, trailing_monthly_revenue: None, address: None },
+        }).await.unwrap_err();
+        assert_eq!(err.to_string(), "Team 'Team C' not found. Did you mean: Team A, Team B?");
     }
 
     #[tokio::test]
-    async fn test_end_of_epoch_report_filename() {
+    async fn test_payment_address_checksum_validation() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
-        
-        // Create and close an epoch
-        let _epoch_id = create_test_epoch(&mut budget_system);
-        budget_system.close_epoch(None).unwrap();
-        
-        budget_system.generate_end_of_epoch_report("Test Epoch").unwrap();
-        
-        let expected_path = temp_dir.path()
-            .join("reports")
-            .join("Test_Epoch")
-            .join("end_of_epoch_report-Test_Epoch.md");
-        
-        assert!(expected_path.exists());
+        create_test_epoch(&mut budget_system);
+
+        let add_team = |name: &str, address: &str| Command::AddTeam {
+            name: name.to_string(),
+            representative: "Rep".to_string(),
+            trailing_monthly_revenue: Some(vec![1000]),
+            address: Some(address.to_string()),
+        };
+        let add_proposal = |title: &str, address: &str| Command::AddProposal {
+            title: title.to_string(),
+            url: None,
+            budget_request_details: Some(crate::commands::common::BudgetRequestDetailsCommand {
+                team: None,
+                request_amounts: Some(HashMap::from([("ETH".to_string(), 1.0)])),
+                start_date: None,
+                end_date: None,
+                is_loan: None,
+                payment_address: Some(address.to_string()),
+                category: None,
+                ipfs_hash: None,
+            }),
+            announced_at: None,
+            published_at: None,
+            is_historical: None,
+        };
+
+        // Known-good checksummed and unchecksummed lowercase addresses are accepted
+        budget_system.execute_command(add_team("Checksummed", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")).await.unwrap();
+        budget_system.execute_command(add_team("Lowercase", "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed")).await.unwrap();
+        budget_system.execute_command(add_proposal("Checksummed Proposal", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")).await.unwrap();
+
+        // A single flipped letter breaks the checksum
+        let err = budget_system.execute_command(add_team("Typo", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD")).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid EIP-55 checksum"));
+        assert!(budget_system.get_team_id_by_name("Typo").is_none());
+
+        let err = budget_system.execute_command(add_proposal("Typo Proposal", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD")).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid EIP-55 checksum"));
+        assert!(budget_system.get_proposal_id_by_name("Typo Proposal").is_none());
     }
 
     #[tokio::test]
-    async fn test_generate_proposal_tables() {
+    async fn test_multi_team_allocations() {
         let temp_dir = TempDir::new().unwrap();
         let state_file = temp_dir.path().join("test_state.json").to_str().unwrap().to_string();
         let mut budget_system = create_test_budget_system(&state_file, None).await;
-        
-        let start_date = Utc::now();
-        let end_date = start_date + Duration::days(30);
-        let epoch_id = budget_system.create_epoch("Test Epoch", start_date, end_date).unwrap();
-        budget_system.activate_epoch(epoch_id).unwrap();
+        create_test_epoch(&mut budget_system);
+
+        let team_a = budget_system.create_team("Team A".to_string(), "Rep A".to_string(), Some(vec![1000]), Some(crate::services::ethereum::MOCK_TX_RECIPIENT.to_string())).unwrap();
+        let team_b = budget_system.create_team("Team B".to_string(), "Rep B".to_string(), Some(vec![1000]), Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string())).unwrap();
+        let proposal_id = create_test_proposal(&mut budget_system, "Shared", vec![1.0]);
+
+        budget_system.execute_command(Command::SetProposalTeamAllocations {
+            proposal_name: "Shared".to_string(),
+            allocations: HashMap::from([
+                ("Team A".to_string(), HashMap::from([("ETH".to_string(), 6.0)])),
+                ("Team B".to_string(), HashMap::from([("ETH".to_string(), 4.0), ("USD".to_string(), 50.0)])),
+            ]),
+        }).await.unwrap();
+        let details = budget_system.get_proposal(&proposal_id).unwrap().budget_request_details().unwrap();
+        assert_eq!(details.request_amounts().get("ETH"), Some(&10.0));
+        assert!(budget_system.set_proposal_team_allocations("Shared", HashMap::from([("Nobody".to_string(), HashMap::from([("ETH".to_string(), 1.0)]))])).is_err());
+
+        let tx = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e";
+        let today = Utc::now().date_naive();
+        let pay = |teams: &[&str], skip_verification: bool| Command::LogAllocationPayment {
+            payment_tx: tx.to_string(),
+            payment_date: today,
+            proposal_name: "Shared".to_string(),
+            team_names: teams.iter().map(|t| t.to_string()).collect(),
+            skip_verification,
+        };
 
-        // Create an approved proposal with payment
-         let proposal1 = create_test_proposal(&mut budget_system, "Approved Proposal", vec![1000.0]);
-         budget_system.close_with_reason(proposal1, &Resolution::Approved).unwrap();
-         
-         // Create a rejected proposal
-         let proposal2 = create_test_proposal(&mut budget_system, "Rejected Proposal", vec![500.0]);
-         budget_system.close_with_reason(proposal2, &Resolution::Rejected).unwrap();
-         
-         let epoch = budget_system.get_current_epoch().unwrap();
-         let tables = budget_system.generate_proposal_tables(epoch).unwrap();
-         
-        // Check approved proposals table has Paid column
-        assert!(tables.contains("| Name | URL | Team | Amounts | Start Date | End Date | Announced | Resolved | Paid | Report |"));
-        
-        // Check rejected proposals table doesn't have Paid column
-        assert!(tables.contains("| Name | URL | Team | Amounts | Start Date | End Date | Announced | Resolved | Report |"));
-    }
+        // Payments need an approved proposal
+        assert!(budget_system.execute_command(pay(&["Team A"], true)).await.is_err());
+        budget_system.close_with_reason(proposal_id, &Resolution::Approved).unwrap();
 
-}
\ No newline at end of file
+        let output = budget_system.execute_command(pay(&["Team A"], false)).await.unwrap();
+        assert_eq!(output, "Payment recorded for proposal 'Shared' allocations: Team A");
+        assert!(!budget_system.get_proposal(&proposal_id).unwrap().budget_request_details().unwrap().is_paid());
+        assert!(budget_system.execute_command(pay(&["Team A"], true)).await.is_err());
+
+        // The mock transaction does not go to Team B's address
+        let result = budget_system.execute_command(pay(&["Team B"], false)).await;
+        assert!(result.unwrap_err().to_string().contains("does not match any recipient"));
+
+        let report = budget_system.generate_proposal_report(proposal_id, ReportSections::all()).unwrap();
+        assert!(report.contains("- **Team Allocations**:\n"));
+        assert!(report.contains(&format!("  - Team A: 6 ETH, paid {} (", today.format("%Y-%m-%d"))));
+        assert!(report.contains("  - Team B: 4 ETH, 50 USD, unpaid\n"));
+
+        let output = budget_system.execute_command(pay(&["Team B"], true)).await.unwrap();
+        assert!(output.ends_with("(all allocations paid)"));
+        assert!(budget_system.get_proposal(&proposal_id).unwrap().budget_request_details().unwrap().is_paid());
+
+        let history = budget_system.get_team_payment_history(team_b);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].amounts, HashMap::from([("ETH".to_string(), 4.0), ("USD".to_string(), 50.0)]));
+        assert_eq!(history[0].payment_date, Some(today));
+        assert_eq!(budget_system.get_team_payment_history(team_a)[0].amounts.get("ETH"), Some(&6.0));
+    }
+}
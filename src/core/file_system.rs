@@ -1,50 +1,289 @@
 // src/core/file_system.rs
 use crate::core::budget_system::BudgetSystem;
 use crate::core::models::Proposal;
-use crate::core::state::BudgetSystemState;
+use crate::core::state::{BudgetSystemState, CURRENT_SCHEMA_VERSION};
 use crate::app_config::AppConfig;
 use crate::services::ethereum::EthereumServiceTrait;
 use crate::commands::common::Command;
 
-use serde_json;
+use serde_json::{self, Value};
+use chrono::{DateTime, SecondsFormat, Utc};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::error::Error;
+use std::fmt;
+use std::io::Write;
+
+const TEMP_SUFFIX: &str = ".tmp";
+const BACKUP_SUFFIX: &str = ".bak";
+const BACKUP_DIR: &str = "state_backups";
+
+/// State files written before versioning was introduced
+const UNVERSIONED_SCHEMA_VERSION: u32 = 1;
+
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// Ordered migrations; the entry at index `i` upgrades version `i + 1` to `i + 2`.
+const MIGRATIONS: &[Migration] = &[
+    migrate_v1_to_v2,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    NotAnObject,
+    InvalidVersion(String),
+    UnsupportedVersion(u32),
+    StepFailed { from: u32, reason: String },
+    Deserialize(String),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MigrationError::NotAnObject => write!(f, "State file must contain a JSON object"),
+            MigrationError::InvalidVersion(value) => write!(f, "Invalid schema_version in state file: {}", value),
+            MigrationError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported state schema version {} (this build supports up to {})",
+                version, CURRENT_SCHEMA_VERSION
+            ),
+            MigrationError::StepFailed { from, reason } => write!(
+                f,
+                "Migration from schema version {} to {} failed: {}",
+                from, from + 1, reason
+            ),
+            MigrationError::Deserialize(e) => write!(f, "Migrated state could not be read: {}", e),
+        }
+    }
+}
+
+impl Error for MigrationError {}
 
 pub struct FileSystem;
 
 impl FileSystem {
+    /// Writes the state to a temporary file next to `state_file`, syncs it and
+    /// renames it over the target, so a crash mid-write never truncates the
+    /// state file. The previous state file is kept as a `.bak` copy.
     pub fn save_state(state: &BudgetSystemState, state_file: &str) -> Result<(), Box<dyn Error>> {
         let json = serde_json::to_string_pretty(state)?;
-        
+        Self::write_state_json(&json, state_file)
+    }
+
+    fn write_state_json(json: &str, state_file: &str) -> Result<(), Box<dyn Error>> {
         if let Some(parent) = Path::new(state_file).parent() {
             fs::create_dir_all(parent)?;
         }
         
-        let temp_file = format!("{}.temp", state_file);
-        fs::write(&temp_file, &json)?;
+        let temp_file = format!("{}{}", state_file, TEMP_SUFFIX);
+        let mut file = fs::File::create(&temp_file)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        if Path::new(state_file).is_file() {
+            fs::copy(state_file, format!("{}{}", state_file, BACKUP_SUFFIX))?;
+        }
         fs::rename(&temp_file, state_file)?;
         
         Ok(())
     }
 
+    /// Copies the current state file to `state_backups/state_<RFC3339>.json`
+    /// next to it, prunes all but the `keep` most recent backups and then saves
+    /// the new state. A `keep` of 0 disables backups. Nothing is written when the
+    /// state file already holds this state, so read-only commands don't rotate
+    /// useful backups out.
+    pub fn save_state_with_backup(state: &BudgetSystemState, state_file: &str, keep: usize) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(state)?;
+        if Self::state_file_matches(&json, state_file) {
+            return Ok(());
+        }
+
+        if keep > 0 && Path::new(state_file).is_file() {
+            let backup_dir = Self::backup_dir(state_file);
+            fs::create_dir_all(&backup_dir)?;
+            let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true);
+            fs::copy(state_file, backup_dir.join(format!("state_{}.json", timestamp)))?;
+            Self::prune_backups(state_file, keep)?;
+        }
+
+        Self::write_state_json(&json, state_file)
+    }
+
+    /// Whether `state_file` holds the same JSON as `json`. Compared as parsed values,
+    /// since map fields don't serialize in a stable order.
+    fn state_file_matches(json: &str, state_file: &str) -> bool {
+        let Ok(on_disk) = fs::read_to_string(state_file) else {
+            return false;
+        };
+        match (serde_json::from_str::<Value>(&on_disk), serde_json::from_str::<Value>(json)) {
+            (Ok(on_disk), Ok(new)) => on_disk == new,
+            _ => false,
+        }
+    }
+
+    fn backup_dir(state_file: &str) -> PathBuf {
+        Path::new(state_file).parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(BACKUP_DIR)
+    }
+
+    /// Timestamped backups of `state_file`, oldest first.
+    pub fn list_backups(state_file: &str) -> Result<Vec<(String, PathBuf)>, Box<dyn Error>> {
+        let backup_dir = Self::backup_dir(state_file);
+        if !backup_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups: Vec<(DateTime<Utc>, String, PathBuf)> = fs::read_dir(&backup_dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let timestamp = name.strip_prefix("state_")?.strip_suffix(".json")?.to_string();
+                let parsed = DateTime::parse_from_rfc3339(&timestamp).ok()?.with_timezone(&Utc);
+                Some((parsed, timestamp, entry.path()))
+            })
+            .collect();
+        backups.sort_by_key(|(parsed, _, _)| *parsed);
+
+        Ok(backups.into_iter().map(|(_, timestamp, path)| (timestamp, path)).collect())
+    }
+
+    fn prune_backups(state_file: &str, keep: usize) -> Result<(), Box<dyn Error>> {
+        let backups = Self::list_backups(state_file)?;
+        let excess = backups.len().saturating_sub(keep);
+        for (_, path) in backups.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    pub fn find_backup(state_file: &str, timestamp: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let backups = Self::list_backups(state_file)?;
+        if let Some((_, path)) = backups.iter().find(|(t, _)| t == timestamp) {
+            return Ok(path.clone());
+        }
+
+        let available = if backups.is_empty() {
+            "none".to_string()
+        } else {
+            backups.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>().join(", ")
+        };
+        Err(format!("Backup not found: {}. Available backups: {}", timestamp, available).into())
+    }
+
+    /// Loads the `.bak` copy that `save_state` keeps of the previous state file.
+    pub fn restore_from_backup(state_file: &str) -> Result<BudgetSystemState, Box<dyn Error>> {
+        let backup = format!("{}{}", state_file, BACKUP_SUFFIX);
+        if !Path::new(&backup).is_file() {
+            return Err(format!("No backup found at {}", backup).into());
+        }
+        Self::load_state(&backup)
+    }
+
+    /// Returns the state file's size if it exceeds `warn_bytes`, logging a warning.
+    pub fn check_state_file_size(state_file: &str, warn_bytes: u64) -> Result<Option<u64>, Box<dyn Error>> {
+        let size = fs::metadata(state_file)?.len();
+        if size > warn_bytes {
+            log::warn!(
+                "State file {} is {} bytes (threshold {} bytes). Consider archiving closed epochs.",
+                state_file, size, warn_bytes
+            );
+            Ok(Some(size))
+        } else {
+            Ok(None)
+        }
+    }
+
     pub fn load_state(path: &str) -> Result<BudgetSystemState, Box<dyn Error>> {
+        Self::load_and_migrate_state(path).map(|(state, _)| state)
+    }
+
+    /// Loads the state file, migrating it in memory. Also returns the schema
+    /// version found on disk.
+    fn load_and_migrate_state(path: &str) -> Result<(BudgetSystemState, u32), Box<dyn Error>> {
         let json = fs::read_to_string(path)?;
-        let state: BudgetSystemState = serde_json::from_str(&json)?;
-        Ok(state)
+        let value: Value = serde_json::from_str(&json)?;
+        Ok(Self::migrate_with_version(value)?)
+    }
+
+    /// Upgrades raw serialized state to `CURRENT_SCHEMA_VERSION` and deserializes it.
+    pub fn migrate(raw: Value) -> Result<BudgetSystemState, MigrationError> {
+        Self::migrate_with_version(raw).map(|(state, _)| state)
+    }
+
+    fn migrate_with_version(mut raw: Value) -> Result<(BudgetSystemState, u32), MigrationError> {
+        let version = Self::migrate_state(&mut raw)?;
+        let state = serde_json::from_value(raw)
+            .map_err(|e| MigrationError::Deserialize(e.to_string()))?;
+        Ok((state, version))
+    }
+
+    /// Upgrades serialized state in place, applying each migration in order.
+    /// Returns the version the state was stored with.
+    pub fn migrate_state(value: &mut Value) -> Result<u32, MigrationError> {
+        let object = value.as_object_mut().ok_or(MigrationError::NotAnObject)?;
+        let version = match object.get("schema_version") {
+            None => UNVERSIONED_SCHEMA_VERSION,
+            Some(v) => v.as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| MigrationError::InvalidVersion(v.to_string()))?,
+        };
+
+        if version == 0 || version > CURRENT_SCHEMA_VERSION {
+            return Err(MigrationError::UnsupportedVersion(version));
+        }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+            let from = index as u32 + 1;
+            migration(value).map_err(|reason| MigrationError::StepFailed { from, reason })?;
+            log::info!("Migrated state from schema version {} to {}", from, from + 1);
+        }
+
+        value["schema_version"] = Value::from(CURRENT_SCHEMA_VERSION);
+        Ok(version)
     }
 
     pub fn try_load_state(path: &str) -> Option<BudgetSystemState> {
-        match Self::load_state(path) {
-            Ok(state) => Some(state),
+        match Self::load_and_migrate_state(path) {
+            Ok((state, version)) => {
+                if version < CURRENT_SCHEMA_VERSION {
+                    if let Err(e) = Self::save_state(&state, path) {
+                        log::warn!("Failed to write migrated state back to {}: {}", path, e);
+                    }
+                }
+                Some(state)
+            },
             Err(e) => {
+                if let Some((recovered_from, state)) = Self::recover_state(path) {
+                    log::warn!("Failed to load state from {}: {}. Recovered from {}.", path, e, recovered_from.display());
+                    return Some(state);
+                }
                 eprintln!("Failed to load state from {}: {}. Starting with a new state.", path, e);
                 None
             }
         }
     }
 
+    /// Loads the most recently modified readable copy among the leftover
+    /// temporary file and the backup of `path`.
+    pub fn recover_state(path: &str) -> Option<(PathBuf, BudgetSystemState)> {
+        let mut candidates: Vec<(PathBuf, std::time::SystemTime)> = [TEMP_SUFFIX, BACKUP_SUFFIX].iter()
+            .map(|suffix| PathBuf::from(format!("{}{}", path, suffix)))
+            .filter_map(|candidate| {
+                let modified = fs::metadata(&candidate).and_then(|m| m.modified()).ok()?;
+                Some((candidate, modified))
+            })
+            .collect();
+        candidates.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+        candidates.into_iter().find_map(|(candidate, _)| {
+            Self::load_state(candidate.to_str()?).ok().map(|state| (candidate, state))
+        })
+    }
+
     pub async fn initialize_budget_system(
         config: &AppConfig,
         ethereum_service: Arc<dyn EthereumServiceTrait>
@@ -130,6 +369,62 @@ impl FileSystem {
     }
 }
 
+/// v1 -> v2: epochs stored a single `reward` and one `amount` per team reward;
+/// both become per-token `rewards` and `amounts`. Teams get an explicit
+/// `payment_address`, which older files could omit.
+fn migrate_v1_to_v2(state: &mut Value) -> Result<(), String> {
+    if let Some(current_state) = state.get_mut("current_state") {
+        backfill_team_payment_addresses(current_state)?;
+    }
+    if let Some(history) = state.get_mut("history").and_then(Value::as_array_mut) {
+        for snapshot in history {
+            backfill_team_payment_addresses(snapshot)?;
+        }
+    }
+    let Some(epochs) = state.get_mut("epochs").and_then(Value::as_object_mut) else {
+        return Ok(());
+    };
+
+    for epoch in epochs.values_mut() {
+        let epoch = epoch.as_object_mut().ok_or("Epoch must be a JSON object")?;
+        let reward = epoch.remove("reward").filter(|r| !r.is_null());
+        let token = reward.as_ref()
+            .and_then(|r| r.get("token"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        let rewards = epoch.entry("rewards").or_insert_with(|| Value::Array(Vec::new()));
+        if let (Some(rewards), Some(reward)) = (rewards.as_array_mut(), reward) {
+            if rewards.is_empty() {
+                rewards.push(reward);
+            }
+        }
+
+        if let Some(team_rewards) = epoch.get_mut("team_rewards").and_then(Value::as_object_mut) {
+            for team_reward in team_rewards.values_mut() {
+                let team_reward = team_reward.as_object_mut().ok_or("Team reward must be a JSON object")?;
+                let amount = team_reward.remove("amount").filter(|a| !a.is_null());
+                let amounts = team_reward.entry("amounts").or_insert_with(|| Value::Object(Default::default()));
+                if let (Some(amounts), Some(amount), Some(token)) = (amounts.as_object_mut(), amount, &token) {
+                    amounts.entry(token.clone()).or_insert(amount);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn backfill_team_payment_addresses(snapshot: &mut Value) -> Result<(), String> {
+    if let Some(teams) = snapshot.get_mut("teams").and_then(Value::as_object_mut) {
+        for team in teams.values_mut() {
+            let team = team.as_object_mut().ok_or("Team must be a JSON object")?;
+            team.entry("payment_address").or_insert(Value::Null);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +477,20 @@ mod tests {
             assert!(state_file.metadata().unwrap().len() > 0);
         }
 
+        #[test]
+        fn test_check_state_file_size() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let state = create_mock_state();
+
+            FileSystem::save_state(&state, state_file.to_str().unwrap()).unwrap();
+            let size = fs::metadata(&state_file).unwrap().len();
+
+            assert_eq!(FileSystem::check_state_file_size(state_file.to_str().unwrap(), size).unwrap(), None);
+            assert_eq!(FileSystem::check_state_file_size(state_file.to_str().unwrap(), size - 1).unwrap(), Some(size));
+            assert!(FileSystem::check_state_file_size(temp_dir.path().join("missing.json").to_str().unwrap(), 0).is_err());
+        }
+
         #[test]
         fn test_load_state_from_file() {
             let temp_dir = setup_temp_dir();
@@ -249,6 +558,152 @@ mod tests {
             assert_eq!(new_state.current_state().teams().len(), loaded_state.current_state().teams().len());
             assert!(loaded_state.current_state().teams().values().any(|team| team.name() == "New Team"));
         }
+        #[test]
+        fn test_save_state_keeps_backup() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+
+            FileSystem::save_state(&BudgetSystemState::new(), path).unwrap();
+            assert!(!temp_dir.path().join("test_state.json.bak").exists());
+
+            FileSystem::save_state(&create_mock_state(), path).unwrap();
+            let backup = FileSystem::load_state(temp_dir.path().join("test_state.json.bak").to_str().unwrap()).unwrap();
+            assert!(backup.current_state().teams().is_empty());
+            assert!(!temp_dir.path().join("test_state.json.tmp").exists());
+        }
+
+        #[test]
+        fn test_try_load_state_recovers_from_partial_write() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+
+            FileSystem::save_state(&create_mock_state(), path).unwrap();
+            FileSystem::save_state(&BudgetSystemState::new(), path).unwrap();
+
+            // Simulate a write that was cut off halfway
+            let json = fs::read_to_string(&state_file).unwrap();
+            fs::write(&state_file, &json[..json.len() / 2]).unwrap();
+            assert!(FileSystem::load_state(path).is_err());
+
+            let recovered = FileSystem::try_load_state(path).unwrap();
+            assert_eq!(recovered.current_state().teams().len(), 1);
+        }
+
+        #[test]
+        fn test_restore_from_backup_after_partial_write() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+
+            assert!(FileSystem::restore_from_backup(path).is_err_and(|e| e.to_string().starts_with("No backup found")));
+
+            FileSystem::save_state(&create_mock_state(), path).unwrap();
+            FileSystem::save_state(&create_mock_state(), path).unwrap();
+            let json = fs::read_to_string(&state_file).unwrap();
+            fs::write(&state_file, &json[..json.len() / 3]).unwrap();
+
+            let restored = FileSystem::restore_from_backup(path).unwrap();
+            assert_eq!(restored.current_state().teams().len(), 1);
+        }
+
+        #[test]
+        fn test_recover_state_prefers_newest_readable_copy() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+            let temp_file = temp_dir.path().join("test_state.json.tmp");
+
+            FileSystem::save_state(&BudgetSystemState::new(), path).unwrap();
+            FileSystem::save_state(&BudgetSystemState::new(), path).unwrap();
+            fs::write(&temp_file, serde_json::to_string(&create_mock_state()).unwrap()).unwrap();
+            fs::write(&state_file, "{").unwrap();
+
+            let (source, state) = FileSystem::recover_state(path).unwrap();
+            assert_eq!(source, temp_file);
+            assert_eq!(state.current_state().teams().len(), 1);
+
+            // A truncated temporary file is skipped in favour of the backup
+            fs::write(&temp_file, "{\"current_state\"").unwrap();
+            let (source, state) = FileSystem::recover_state(path).unwrap();
+            assert_eq!(source, temp_dir.path().join("test_state.json.bak"));
+            assert!(state.current_state().teams().is_empty());
+        }
+        #[test]
+        fn test_save_state_with_backup_prunes_to_keep() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+
+            for _ in 0..6 {
+                FileSystem::save_state_with_backup(&create_mock_state(), path, 3).unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(2));
+            }
+
+            let backups = FileSystem::list_backups(path).unwrap();
+            assert_eq!(backups.len(), 3);
+            assert_eq!(fs::read_dir(temp_dir.path().join("state_backups")).unwrap().count(), 3);
+            assert!(backups.windows(2).all(|pair| pair[0].0 < pair[1].0));
+            for (timestamp, backup_path) in &backups {
+                assert_eq!(backup_path.file_name().unwrap().to_str().unwrap(), format!("state_{}.json", timestamp));
+                assert!(FileSystem::load_state(backup_path.to_str().unwrap()).is_ok());
+            }
+        }
+
+        #[test]
+        fn test_save_state_with_backup_skips_unchanged_state() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+
+            let state = create_mock_state();
+            FileSystem::save_state_with_backup(&BudgetSystemState::new(), path, 3).unwrap();
+            FileSystem::save_state_with_backup(&state, path, 3).unwrap();
+            assert_eq!(FileSystem::list_backups(path).unwrap().len(), 1);
+
+            // Saving the same state again, as a read-only command does, keeps the backups
+            // and the previous state in the `.bak` copy
+            let reloaded = FileSystem::load_state(path).unwrap();
+            for _ in 0..5 {
+                FileSystem::save_state_with_backup(&reloaded, path, 3).unwrap();
+            }
+            assert_eq!(FileSystem::list_backups(path).unwrap().len(), 1);
+            let previous = FileSystem::restore_from_backup(path).unwrap();
+            assert!(previous.current_state().teams().is_empty());
+        }
+
+        #[test]
+        fn test_save_state_with_backup_disabled() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+
+            FileSystem::save_state_with_backup(&create_mock_state(), path, 0).unwrap();
+            FileSystem::save_state_with_backup(&create_mock_state(), path, 0).unwrap();
+
+            assert!(!temp_dir.path().join("state_backups").exists());
+            assert!(FileSystem::list_backups(path).unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_find_backup() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+
+            let err = FileSystem::find_backup(path, "2024-01-01T00:00:00.000000Z").unwrap_err();
+            assert!(err.to_string().ends_with("Available backups: none"));
+
+            FileSystem::save_state_with_backup(&create_mock_state(), path, 5).unwrap();
+            FileSystem::save_state_with_backup(&create_mock_state(), path, 5).unwrap();
+            fs::write(temp_dir.path().join("state_backups").join("notes.txt"), "ignored").unwrap();
+
+            let (timestamp, backup_path) = FileSystem::list_backups(path).unwrap().pop().unwrap();
+            assert_eq!(FileSystem::find_backup(path, &timestamp).unwrap(), backup_path);
+            let err = FileSystem::find_backup(path, "2024-01-01T00:00:00.000000Z").unwrap_err();
+            assert!(err.to_string().contains(&timestamp));
+        }
     }
 
     mod file_path_generation_tests {
@@ -581,16 +1036,38 @@ mod tests {
             let config = AppConfig {
                 state_file: temp_dir.path().join("state.json").to_str().unwrap().to_string(),
                 ipc_path: "/tmp/test_reth.ipc".to_string(),
+                rpc_url: None,
                 future_block_offset: 10,
+                eth_retry: crate::app_config::RetryConfig { max_attempts: 1, base_delay_ms: 0, max_delay_ms: 0, jitter_ms: 0 },
                 script_file: "test_script.json".to_string(),
                 default_total_counted_seats: 7,
                 default_max_earner_seats: 5,
+                revenue_window_months: 3,
                 default_qualified_majority_threshold: 0.7,
+                default_quorum: 0.0,
                 counted_vote_points: 5,
                 uncounted_vote_points: 2,
+                abstain_points: 5,
+                state_file_warn_bytes: 10 * 1024 * 1024,
+                state_backup_count: 20,
+                require_reviewer_sign_off: false,
+                allow_vote_changes: false,
+                vote_change_window_hours: 24,
+                allow_fuzzy_match: true,
+                allow_similar_proposals: false,
+                discord_webhook_url: None,
+                epoch_budget_caps: std::collections::HashMap::new(),
+                token_addresses: std::collections::HashMap::new(),
+                safe_chain_id: 1,
                 telegram: crate::app_config::TelegramConfig {
                     chat_id: "test_chat_id".to_string(),
                     token: "test_token".to_string(),
+                    timezone: "UTC".to_string(),
+                    chat_timezones: std::collections::HashMap::new(),
+                    overdue_payment_days: 30,
+                    allow_absent_voter_pings: false,
+                    allowed_chat_ids: Vec::new(),
+                    public_read_only_commands: false,
                 },
             };
             let ethereum_service = Arc::new(MockEthereumService::new());
@@ -642,4 +1119,134 @@ mod tests {
             assert_eq!(budget_system.state().current_state().teams().len(), 0);
         }
     }
-}
\ No newline at end of file
+
+    mod migration_tests {
+        use super::*;
+
+        const TEAM_ID: &str = "a1cfc3d9-7fa1-477f-8b98-3f48cd7cf0ad";
+        const EPOCH_ID: &str = "5d354f06-44a4-49db-b33c-81e58bf71f3a";
+
+        /// A state file as written before schema versioning and multi-token rewards
+        const STATE_V1: &str = r#"{
+  "current_state": {
+    "teams": {
+      "a1cfc3d9-7fa1-477f-8b98-3f48cd7cf0ad": {
+        "id": "a1cfc3d9-7fa1-477f-8b98-3f48cd7cf0ad",
+        "name": "Test Team",
+        "representative": "John Doe",
+        "status": { "Earner": { "trailing_monthly_revenue": [1000, 2000, 3000] } }
+      }
+    },
+    "timestamp": "2024-01-15T00:00:00Z"
+  },
+  "history": [],
+  "proposals": {},
+  "raffles": {},
+  "votes": {},
+  "epochs": {
+    "5d354f06-44a4-49db-b33c-81e58bf71f3a": {
+      "id": "5d354f06-44a4-49db-b33c-81e58bf71f3a",
+      "name": "Epoch 1",
+      "start_date": "2024-01-01T00:00:00Z",
+      "end_date": "2024-01-31T00:00:00Z",
+      "status": "Closed",
+      "associated_proposals": [],
+      "reward": { "token": "ETH", "amount": 100.0 },
+      "team_rewards": {
+        "a1cfc3d9-7fa1-477f-8b98-3f48cd7cf0ad": { "percentage": 40.0, "amount": 40.0 }
+      }
+    }
+  },
+  "current_epoch": null
+}"#;
+
+        #[test]
+        fn test_migrate_v1_to_current() {
+            let mut value: Value = serde_json::from_str(STATE_V1).unwrap();
+
+            assert!(value["current_state"]["teams"][TEAM_ID].get("payment_address").is_none());
+            assert_eq!(FileSystem::migrate_state(&mut value).unwrap(), 1);
+            assert_eq!(value["schema_version"], CURRENT_SCHEMA_VERSION);
+            assert_eq!(value["current_state"]["teams"][TEAM_ID]["payment_address"], Value::Null);
+
+            let epoch = &value["epochs"][EPOCH_ID];
+            assert!(epoch.get("reward").is_none());
+            assert_eq!(epoch["rewards"], serde_json::json!([{ "token": "ETH", "amount": 100.0 }]));
+            assert!(epoch["team_rewards"][TEAM_ID].get("amount").is_none());
+            assert_eq!(epoch["team_rewards"][TEAM_ID]["amounts"], serde_json::json!({ "ETH": 40.0 }));
+        }
+
+        #[test]
+        fn test_try_load_state_migrates_v1_fixture_and_writes_back() {
+            let temp_dir = setup_temp_dir();
+            let state_file = temp_dir.path().join("test_state.json");
+            let path = state_file.to_str().unwrap();
+            fs::write(&state_file, STATE_V1).unwrap();
+
+            let state = FileSystem::try_load_state(path).unwrap();
+            assert_eq!(state.version(), BudgetSystemState::schema_version());
+            assert_eq!(state.current_state().teams().len(), 1);
+            let epoch = state.epochs().get(&Uuid::parse_str(EPOCH_ID).unwrap()).unwrap();
+            assert_eq!(epoch.reward("ETH").unwrap().amount(), 100.0);
+            let team_reward = epoch.team_rewards().get(&Uuid::parse_str(TEAM_ID).unwrap()).unwrap();
+            assert_eq!(team_reward.amount("ETH"), 40.0);
+
+            // The upgraded file is written back, keeping the original as a backup
+            let on_disk: Value = serde_json::from_str(&fs::read_to_string(&state_file).unwrap()).unwrap();
+            assert_eq!(on_disk["schema_version"], CURRENT_SCHEMA_VERSION);
+            let backup = fs::read_to_string(temp_dir.path().join("test_state.json.bak")).unwrap();
+            assert_eq!(backup, STATE_V1);
+        }
+
+        #[test]
+        fn test_current_state_is_not_migrated() {
+            let mut value = serde_json::to_value(create_mock_state()).unwrap();
+            let before = value.clone();
+
+            assert_eq!(FileSystem::migrate_state(&mut value).unwrap(), CURRENT_SCHEMA_VERSION);
+            assert_eq!(value, before);
+        }
+
+        #[test]
+        fn test_migrate_state_rejects_unknown_versions() {
+            let mut newer = serde_json::json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 });
+            assert!(FileSystem::migrate_state(&mut newer).unwrap_err().to_string().contains("Unsupported state schema version"));
+
+            let mut invalid = serde_json::json!({ "schema_version": "two" });
+            assert!(FileSystem::migrate_state(&mut invalid).is_err());
+
+            assert!(FileSystem::migrate_state(&mut serde_json::json!([])).is_err());
+        }
+
+        #[test]
+        fn test_migrate_v1_fixture_to_state() {
+            let state = FileSystem::migrate(serde_json::from_str(STATE_V1).unwrap()).unwrap();
+            assert_eq!(state.version(), BudgetSystemState::schema_version());
+            assert_eq!(BudgetSystemState::schema_version(), CURRENT_SCHEMA_VERSION);
+            let team = state.current_state().get_team(&Uuid::parse_str(TEAM_ID).unwrap()).unwrap();
+            assert_eq!(team.name(), "Test Team");
+            assert!(team.payment_address().is_none());
+        }
+
+        #[test]
+        fn test_migration_errors() {
+            assert_eq!(FileSystem::migrate(serde_json::json!([])).err(), Some(MigrationError::NotAnObject));
+            assert_eq!(
+                FileSystem::migrate(serde_json::json!({ "schema_version": 0 })).err(),
+                Some(MigrationError::UnsupportedVersion(0))
+            );
+            assert!(matches!(
+                FileSystem::migrate(serde_json::json!({ "schema_version": -1 })),
+                Err(MigrationError::InvalidVersion(_))
+            ));
+            assert!(matches!(
+                FileSystem::migrate(serde_json::json!({ "epochs": { EPOCH_ID: "not an epoch" } })),
+                Err(MigrationError::StepFailed { from: 1, .. })
+            ));
+            assert!(matches!(
+                FileSystem::migrate(serde_json::json!({ "schema_version": CURRENT_SCHEMA_VERSION })),
+                Err(MigrationError::Deserialize(_))
+            ));
+        }
+    }
+}
@@ -1,19 +1,72 @@
 use uuid::Uuid;
 use std::{collections::HashMap, str::FromStr};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Serialize, Deserialize};
 use ethers::types::{Address, H256};
+use super::proposal::ProposalReviewer;
 
 pub trait NameMatches {
     fn name_matches(&self, name: &str) -> bool;
+
+    /// Whether `name` is one of the item's alternative names; only consulted when no
+    /// item matches by name.
+    fn alias_matches(&self, _name: &str) -> bool {
+        false
+    }
 }
 
 pub fn get_id_by_name<T: NameMatches>(map: &HashMap<Uuid, T>, name: &str) -> Option<Uuid> {
     map.iter()
         .find(|(_, item)| item.name_matches(name))
+        .or_else(|| map.iter().find(|(_, item)| item.alias_matches(name)))
         .map(|(id, _)| *id)
 }
 
+/// Candidate name closest to `input` by case-insensitive edit distance, with that distance.
+/// Returns `None` when there are no candidates or two different names tie for closest.
+pub fn find_closest_name<'a>(candidates: impl IntoIterator<Item = (Uuid, &'a str)>, input: &str) -> Option<(Uuid, &'a str, usize)> {
+    let input = input.to_lowercase();
+    let mut best: Option<(Uuid, &'a str, usize)> = None;
+    let mut tied = false;
+    for (id, name) in candidates {
+        let distance = strsim::levenshtein(&input, &name.to_lowercase());
+        match best {
+            Some((_, best_name, best_distance)) if distance == best_distance => tied |= best_name != name,
+            Some((_, _, best_distance)) if distance > best_distance => {},
+            _ => {
+                best = Some((id, name, distance));
+                tied = false;
+            }
+        }
+    }
+    best.filter(|_| !tied)
+}
+
+/// Largest case-insensitive edit distance at which `suggest_names` offers a name.
+const SUGGESTION_MAX_DISTANCE: usize = 2;
+
+/// Largest edit distance at which a name is worth suggesting for `input`: at most
+/// `SUGGESTION_MAX_DISTANCE` and half the input's length, so short inputs don't pull
+/// in unrelated names.
+pub fn suggestion_max_distance(input: &str) -> usize {
+    SUGGESTION_MAX_DISTANCE.min(input.chars().count() / 2)
+}
+
+/// Up to `max` candidate names within `suggestion_max_distance` of `input`, closest
+/// first and ties in candidate order.
+pub fn suggest_names<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>, max: usize) -> Vec<&'a str> {
+    let max_distance = suggestion_max_distance(input);
+    let input = input.to_lowercase();
+    let mut matches: Vec<(usize, &'a str)> = Vec::new();
+    for name in candidates {
+        let distance = strsim::levenshtein(&input, &name.to_lowercase());
+        if distance <= max_distance && !matches.iter().any(|(_, seen)| *seen == name) {
+            matches.push((distance, name));
+        }
+    }
+    matches.sort_by_key(|(distance, _)| *distance);
+    matches.into_iter().take(max).map(|(_, name)| name).collect()
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UnpaidRequestsReport {
@@ -76,8 +129,9 @@ impl UnpaidRequest {
 pub struct EpochPaymentsReport {
     pub generated_at: DateTime<Utc>,
     pub epoch_name: String,
-    pub reward_token: String,
-    pub total_reward: f64,
+    /// Total reward per token, in the order the rewards were set
+    pub total_rewards: Vec<(String, f64)>,
+    /// One entry per team and reward token
     pub payments: Vec<TeamPayment>,
 }
 
@@ -86,6 +140,9 @@ pub struct TeamPayment {
     pub team_name: String,
     #[serde(with = "address_serde")]
     pub default_payment_address: Option<Address>,
+    #[serde(default)]
+    pub points: u32,
+    pub reward_token: String,
     pub amount: f64,
     pub percentage: f64,
 }
@@ -93,15 +150,13 @@ pub struct TeamPayment {
 impl EpochPaymentsReport {
     pub fn new(
         epoch_name: String,
-        reward_token: String,
-        total_reward: f64,
+        total_rewards: Vec<(String, f64)>,
         payments: Vec<TeamPayment>
     ) -> Self {
         Self {
             generated_at: Utc::now(),
             epoch_name,
-            reward_token,
-            total_reward,
+            total_rewards,
             payments,
         }
     }
@@ -111,18 +166,431 @@ impl TeamPayment {
     pub fn new(
         team_name: String,
         default_payment_address: Option<Address>,
+        points: u32,
+        reward_token: String,
         amount: f64,
         percentage: f64,
     ) -> Self {
         Self {
             team_name,
             default_payment_address,
+            points,
+            reward_token,
             amount,
             percentage,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamAbsenceInfo {
+    pub team_name: String,
+    pub current_streak_epochs: usize,
+    pub last_participated_epoch: Option<String>,
+    pub total_absences: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverduePayment {
+    pub proposal_id: Uuid,
+    pub title: String,
+    pub team_name: String,
+    /// Days elapsed since the proposal was approved
+    pub days_overdue: i64,
+    pub amounts: HashMap<String, f64>,
+    pub payment_address: Option<String>,
+}
+
+impl OverduePayment {
+    /// Lists overdue payments, one per line, under a heading naming the `days` threshold.
+    pub fn format_list(payments: &[OverduePayment], days: u64) -> String {
+        if payments.is_empty() {
+            return format!("No overdue payments (approved more than {} days ago)", days);
+        }
+
+        let mut output = format!("Overdue payments (approved more than {} days ago):\n", days);
+        for payment in payments {
+            let mut amounts: Vec<_> = payment.amounts.iter().collect();
+            amounts.sort_by_key(|(token, _)| *token);
+            let amounts = amounts.iter()
+                .map(|(token, amount)| format!("{} {}", amount, token))
+                .collect::<Vec<_>>()
+                .join(", ");
+            output.push_str(&format!(
+                "  {} ({}): {} days, {}, address: {}\n",
+                payment.title,
+                payment.team_name,
+                payment.days_overdue,
+                amounts,
+                payment.payment_address.as_deref().unwrap_or("N/A")
+            ));
+        }
+        output
+    }
+}
+
+/// A team whose points in an epoch differ between the epoch's proposal votes and a
+/// fresh recalculation from every vote in the state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointMismatch {
+    pub epoch_id: Uuid,
+    pub team_id: Uuid,
+    pub stored: u32,
+    pub recalculated: u32,
+}
+
+/// Outcome of replaying a raffle from its recorded randomness and team snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaffleVerification {
+    pub matches: bool,
+    /// One line per team whose recorded seat differs from the replayed draw
+    pub discrepancies: Vec<String>,
+}
+
+/// An approved budget request that has not been paid yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingPayment {
+    pub proposal_id: Uuid,
+    pub title: String,
+    pub team_name: String,
+    pub epoch_name: String,
+    pub amounts: HashMap<String, f64>,
+    pub payment_address: Option<String>,
+    pub approved_date: NaiveDate,
+    /// Days elapsed since `approved_date`
+    pub days_pending: i64,
+    pub is_loan: bool,
+}
+
+/// An approved budget request for a team, paid or not.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamPaymentRecord {
+    pub proposal_id: Uuid,
+    pub proposal_title: String,
+    pub epoch_name: String,
+    pub amounts: HashMap<String, f64>,
+    pub payment_date: Option<NaiveDate>,
+    pub payment_tx: Option<String>,
+    pub is_loan: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSizeEstimate {
+    pub serialized_bytes: usize,
+    pub teams: usize,
+    pub history_snapshots: usize,
+    pub epochs: usize,
+    pub proposals: usize,
+    pub raffles: usize,
+    pub votes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochInfographicData {
+    pub epoch_name: String,
+    pub team_rankings: Vec<TeamRanking>,
+    pub resolution_counts: ResolutionCounts,
+    pub vote_participation: Vec<VoteParticipationPoint>,
+    pub budget_allocation: Vec<TeamBudgetAllocation>,
+    /// Participation and approval signals combined into a score from 0 to 100
+    pub governance_health_score: Option<f64>,
+    /// Approval rate of resolved proposals in the epoch by the submitting team's status
+    #[serde(default)]
+    pub approval_rate_by_status: HashMap<String, f64>,
+    /// Approval rate per epoch for recent epochs up to this one, oldest first
+    #[serde(default)]
+    pub approval_rate_trend: Vec<(String, f64)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamRanking {
+    pub rank: usize,
+    pub team_name: String,
+    pub points: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResolutionCounts {
+    pub approved: usize,
+    pub rejected: usize,
+    pub retracted: usize,
+    pub invalid: usize,
+    pub duplicate: usize,
+    pub unresolved: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoteParticipationPoint {
+    pub proposal_title: String,
+    pub opened_at: DateTime<Utc>,
+    pub counted_participants: usize,
+    pub uncounted_participants: usize,
+    pub total_eligible_seats: u32,
+    pub participation_rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamBudgetAllocation {
+    pub team_name: String,
+    pub amounts: HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochStateReport {
+    pub epoch_id: Uuid,
+    pub epoch_name: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub status: String,
+    /// Reward per token, in the order the rewards were set
+    #[serde(default)]
+    pub rewards: Vec<(String, f64)>,
+    pub total_proposals: usize,
+    pub approved_count: usize,
+    pub rejected_count: usize,
+    pub retracted_count: usize,
+    pub open_proposals: Vec<OpenProposalSummary>,
+    /// Remaining budget per token, sorted by token; empty when no caps are configured
+    #[serde(default)]
+    pub budget_headroom: Vec<(String, f64)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenProposalSummary {
+    pub title: String,
+    pub url: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub amounts: HashMap<String, f64>,
+    pub days_open: i64,
+    #[serde(default)]
+    pub reviewers: Vec<ProposalReviewer>,
+}
+
+/// Money flow of one epoch per token. Approved requests are split into committed
+/// (not yet paid) and paid amounts; the reward pool is the epoch's point-based reward.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreasuryReport {
+    pub generated_at: DateTime<Utc>,
+    pub epoch_name: String,
+    /// One entry per token, sorted by token
+    pub tokens: Vec<TreasuryTokenFlow>,
+    /// Same totals with stablecoins summed into a single "Stables" entry
+    pub grouped: Vec<TreasuryTokenFlow>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TreasuryTokenFlow {
+    pub token: String,
+    /// Sum of the request amounts of approved proposals
+    pub approved: f64,
+    pub committed: f64,
+    pub paid: f64,
+    pub reward_pool: f64,
+}
+
+impl TreasuryTokenFlow {
+    /// Total leaving the treasury for the epoch once everything is paid out.
+    pub fn total_outflow(&self) -> f64 {
+        self.committed + self.paid + self.reward_pool
+    }
+
+    /// Whether committed and paid amounts add up to the approved requests.
+    pub fn reconciles(&self) -> bool {
+        (self.committed + self.paid - self.approved).abs() < 1e-9
+    }
+}
+
+/// Differences between two state files. Modified entries carry a
+/// `(name, change_summary)` pair.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateDiff {
+    pub added_epochs: Vec<String>,
+    pub removed_epochs: Vec<String>,
+    pub modified_epochs: Vec<(String, String)>,
+    pub added_teams: Vec<String>,
+    pub removed_teams: Vec<String>,
+    pub modified_teams: Vec<(String, String)>,
+    pub added_proposals: Vec<String>,
+    pub removed_proposals: Vec<String>,
+    pub modified_proposals: Vec<(String, String)>,
+    pub added_votes: Vec<String>,
+    pub removed_votes: Vec<String>,
+    pub modified_votes: Vec<(String, String)>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == StateDiff::default()
+    }
+
+    /// One line per change, e.g. "create epoch Q1" or "update proposal X (changed: status)".
+    pub fn change_lines(&self) -> Vec<String> {
+        let sections = [
+            ("epoch ", &self.added_epochs, &self.removed_epochs, &self.modified_epochs),
+            ("team ", &self.added_teams, &self.removed_teams, &self.modified_teams),
+            ("proposal ", &self.added_proposals, &self.removed_proposals, &self.modified_proposals),
+            // Vote labels already read "Vote on '<proposal>' (<id>)"
+            ("", &self.added_votes, &self.removed_votes, &self.modified_votes),
+        ];
+        let mut lines = Vec::new();
+        for (kind, added, removed, modified) in sections {
+            lines.extend(added.iter().map(|name| format!("create {}{}", kind, name)));
+            lines.extend(removed.iter().map(|name| format!("remove {}{}", kind, name)));
+            lines.extend(modified.iter().map(|(name, change)| format!("update {}{} ({})", kind, name, change)));
+        }
+        lines
+    }
+}
+
+/// Sections of a proposal report, combinable with `|`. Revision history is part of `DETAILS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportSections(u8);
+
+impl ReportSections {
+    pub const SUMMARY: Self = Self(1);
+    pub const DETAILS: Self = Self(1 << 1);
+    pub const BUDGET: Self = Self(1 << 2);
+    pub const RAFFLE: Self = Self(1 << 3);
+    pub const VOTING: Self = Self(1 << 4);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        Self(Self::SUMMARY.0 | Self::DETAILS.0 | Self::BUDGET.0 | Self::RAFFLE.0 | Self::VOTING.0)
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Default for ReportSections {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl std::ops::BitOr for ReportSections {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for ReportSections {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl FromStr for ReportSections {
+    type Err = String;
+
+    /// Parses a comma separated list such as "raffle,voting"; "all" selects every section.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sections = Self::empty();
+        for name in s.split(',').map(|n| n.trim().to_lowercase()).filter(|n| !n.is_empty()) {
+            sections |= match name.as_str() {
+                "summary" => Self::SUMMARY,
+                "details" => Self::DETAILS,
+                "budget" => Self::BUDGET,
+                "raffle" => Self::RAFFLE,
+                "voting" | "vote" => Self::VOTING,
+                "all" => Self::all(),
+                _ => return Err(format!("Unknown report section: {}. Use summary, details, budget, raffle or voting", name)),
+            };
+        }
+        if sections.is_empty() {
+            return Err("No report sections given".to_string());
+        }
+        Ok(sections)
+    }
+}
+
+/// Percentiles of the days between a proposal's announcement and its resolution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaitingTimeStats {
+    pub p50_days: f64,
+    pub p75_days: f64,
+    pub p90_days: f64,
+    pub p95_days: f64,
+    pub p99_days: f64,
+    pub sample_size: usize,
+}
+
+/// One row of an epoch budget burndown for a single token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurndownPoint {
+    pub date: NaiveDate,
+    pub token: String,
+    pub approved_cumulative: f64,
+    pub paid_cumulative: f64,
+    pub remaining: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BudgetFlowData {
+    pub nodes: Vec<FlowNode>,
+    pub links: Vec<FlowLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowNode {
+    pub name: String,
+    pub category: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowLink {
+    pub source: usize,
+    pub target: usize,
+    pub value: f64,
+}
+
+impl BudgetFlowData {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the node, adding it if it isn't present yet.
+    pub fn node_index(&mut self, name: &str, category: &str) -> usize {
+        if let Some(index) = self.nodes.iter().position(|n| n.name == name && n.category == category) {
+            return index;
+        }
+        self.nodes.push(FlowNode {
+            name: name.to_string(),
+            category: category.to_string(),
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Adds value to the link between two nodes, merging with an existing link.
+    pub fn add_flow(&mut self, source: usize, target: usize, value: f64) {
+        if let Some(link) = self.links.iter_mut().find(|l| l.source == source && l.target == target) {
+            link.value += value;
+        } else {
+            self.links.push(FlowLink { source, target, value });
+        }
+    }
+}
+
+impl Default for BudgetFlowData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Custom serialization for Ethereum address
 pub mod address_serde {
     use super::*;
@@ -185,6 +653,25 @@ pub mod tx_hash_serde {
     }
 }
 
+/// Outcome of a bulk proposal import. In a dry run `imported` counts the
+/// proposals that would have been added.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub total: usize,
+    pub imported: usize,
+    pub skipped: usize,
+    pub dry_run: bool,
+    pub errors: Vec<ImportError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportError {
+    /// Zero-based position of the entry in the imported array
+    pub index: usize,
+    pub title: Option<String>,
+    pub error: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,6 +680,20 @@ mod tests {
     use std::str::FromStr;
     use serde::{Serialize, Deserialize};
 
+    #[test]
+    fn test_report_sections_from_str() {
+        let sections: ReportSections = "raffle, Voting".parse().unwrap();
+        assert!(sections.contains(ReportSections::RAFFLE));
+        assert!(sections.contains(ReportSections::VOTING));
+        assert!(!sections.contains(ReportSections::SUMMARY));
+        assert_eq!(sections, ReportSections::RAFFLE | ReportSections::VOTING);
+
+        assert_eq!("all".parse::<ReportSections>().unwrap(), ReportSections::all());
+        assert_eq!(ReportSections::default(), ReportSections::all());
+        assert!("raffle,appendix".parse::<ReportSections>().is_err());
+        assert!("".parse::<ReportSections>().is_err());
+    }
+
     #[test]
     fn test_unpaid_request_serialization() {
         let mut amounts = HashMap::new();
@@ -295,12 +796,16 @@ mod tests {
             TeamPayment::new(
                 "Team A".to_string(),
                 Some(Address::from_str("0x742d35Cc6634C0532925a3b844Bc454e4438f44e").unwrap()),
+                10,
+                "ETH".to_string(),
                 100.0,
                 50.0,
             ),
             TeamPayment::new(
                 "Team B".to_string(),
                 None,
+                10,
+                "ETH".to_string(),
                 100.0,
                 50.0,
             ),
@@ -308,8 +813,7 @@ mod tests {
 
         let report = EpochPaymentsReport::new(
             "Test Epoch".to_string(),
-            "ETH".to_string(),
-            200.0,
+            vec![("ETH".to_string(), 200.0)],
             payments,
         );
 
@@ -317,8 +821,7 @@ mod tests {
         let deserialized: EpochPaymentsReport = serde_json::from_str(&json).unwrap();
 
         assert_eq!(deserialized.epoch_name, "Test Epoch");
-        assert_eq!(deserialized.reward_token, "ETH");
-        assert_eq!(deserialized.total_reward, 200.0);
+        assert_eq!(deserialized.total_rewards, vec![("ETH".to_string(), 200.0)]);
         assert_eq!(deserialized.payments.len(), 2);
     }
 
@@ -328,6 +831,8 @@ mod tests {
         let payment = TeamPayment::new(
             "Test Team".to_string(),
             Some(address),
+            20,
+            "ETH".to_string(),
             100.0,
             50.0,
         );
@@ -337,7 +842,68 @@ mod tests {
 
         assert_eq!(deserialized.team_name, "Test Team");
         assert_eq!(deserialized.default_payment_address, Some(address));
+        assert_eq!(deserialized.points, 20);
+        assert_eq!(deserialized.reward_token, "ETH");
         assert_eq!(deserialized.amount, 100.0);
         assert_eq!(deserialized.percentage, 50.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_budget_flow_data_merges_links() {
+        let mut flow = BudgetFlowData::new();
+        let eth = flow.node_index("ETH", "token");
+        let team = flow.node_index("Team A", "team");
+        assert_eq!(flow.node_index("ETH", "token"), eth);
+
+        flow.add_flow(eth, team, 10.0);
+        flow.add_flow(eth, team, 5.0);
+
+        assert_eq!(flow.nodes.len(), 2);
+        assert_eq!(flow.links, vec![FlowLink { source: eth, target: team, value: 15.0 }]);
+
+        let json = serde_json::to_value(&flow).unwrap();
+        assert_eq!(json["links"][0]["source"], 0);
+        assert_eq!(json["nodes"][1]["name"], "Team A");
+    }
+
+    #[test]
+    fn test_find_closest_name() {
+        let alpha = Uuid::new_v4();
+        let beta = Uuid::new_v4();
+        let candidates = [(alpha, "Team Alpha"), (beta, "Team Beta")];
+
+        assert_eq!(find_closest_name(candidates, "Team Alpha"), Some((alpha, "Team Alpha", 0)));
+        assert_eq!(find_closest_name(candidates, "teem alpha"), Some((alpha, "Team Alpha", 1)));
+        assert_eq!(find_closest_name(candidates, "Team Bet"), Some((beta, "Team Beta", 1)));
+        assert_eq!(find_closest_name(Vec::new(), "Team Alpha"), None);
+    }
+
+    #[test]
+    fn test_find_closest_name_ambiguous() {
+        let candidates = [(Uuid::new_v4(), "Team A"), (Uuid::new_v4(), "Team B")];
+        assert_eq!(find_closest_name(candidates, "Team C"), None);
+        assert_eq!(find_closest_name(candidates, "Team A").map(|(_, name, _)| name), Some("Team A"));
+    }
+
+    #[test]
+    fn test_suggest_names() {
+        let candidates = ["Team Alpha", "Team Beta", "Team Alpine", "Team Beta"];
+        assert_eq!(suggest_names("teem alpha", candidates, 3), vec!["Team Alpha"]);
+        assert_eq!(suggest_names("Team Alpin", candidates, 3), vec!["Team Alpine", "Team Alpha"]);
+        assert_eq!(suggest_names("Team Alpin", candidates, 1), vec!["Team Alpine"]);
+        assert_eq!(suggest_names("Team Bet", candidates, 3), vec!["Team Beta"]);
+        assert!(suggest_names("Gamma", candidates, 3).is_empty());
+
+        // Short inputs only tolerate edits proportional to their length
+        assert_eq!(suggest_names("AB", ["AC", "XY"], 3), vec!["AC"]);
+        assert!(suggest_names("A", ["B"], 3).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_names_ties() {
+        let candidates = ["Team A", "Team B"];
+        assert_eq!(suggest_names("Team C", candidates, 3), vec!["Team A", "Team B"]);
+        assert_eq!(suggest_names("Team A", candidates, 3), vec!["Team A", "Team B"]);
+        assert_eq!(suggest_names("Team A", candidates, 1), vec!["Team A"]);
+    }
+}
@@ -5,6 +5,7 @@ use std::collections::HashMap;
 use super::common::NameMatches;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "EpochRecord")]
 pub struct Epoch {
     id: Uuid,
     name: String,
@@ -12,8 +13,37 @@ pub struct Epoch {
     end_date: DateTime<Utc>,
     status: EpochStatus,
     associated_proposals: Vec<Uuid>,
-    reward: Option<EpochReward>,
+    rewards: Vec<EpochReward>,
     team_rewards: HashMap<Uuid, TeamReward>,
+    governance_config: EpochGovernanceConfig,
+}
+
+/// On-disk shape of an epoch. Older state files stored a single `reward` and
+/// one `amount` per team reward; both are folded into the per-token fields.
+#[derive(Deserialize)]
+struct EpochRecord {
+    id: Uuid,
+    name: String,
+    start_date: DateTime<Utc>,
+    end_date: DateTime<Utc>,
+    status: EpochStatus,
+    associated_proposals: Vec<Uuid>,
+    #[serde(default)]
+    reward: Option<EpochReward>,
+    #[serde(default)]
+    rewards: Vec<EpochReward>,
+    team_rewards: HashMap<Uuid, TeamRewardRecord>,
+    #[serde(default)]
+    governance_config: EpochGovernanceConfig,
+}
+
+#[derive(Deserialize)]
+struct TeamRewardRecord {
+    percentage: f64,
+    #[serde(default)]
+    amount: Option<f64>,
+    #[serde(default)]
+    amounts: HashMap<String, f64>,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
@@ -32,7 +62,13 @@ pub struct EpochReward {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TeamReward {
     percentage: f64,
-    amount: f64,
+    amounts: HashMap<String, f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EpochGovernanceConfig {
+    min_days_before_vote: Option<u64>,
+    max_days_to_vote: Option<u64>,
 }
 
 impl Epoch {
@@ -53,8 +89,9 @@ impl Epoch {
             end_date,
             status: EpochStatus::Planned,
             associated_proposals: Vec::new(),
-            reward: None,
+            rewards: Vec::new(),
             team_rewards: HashMap::new(),
+            governance_config: EpochGovernanceConfig::default(),
         })
     }
 
@@ -83,14 +120,22 @@ impl Epoch {
         &self.associated_proposals
     }
 
-    pub fn reward(&self) -> Option<&EpochReward> {
-        self.reward.as_ref()
+    pub fn rewards(&self) -> &[EpochReward] {
+        &self.rewards
+    }
+
+    pub fn reward(&self, token: &str) -> Option<&EpochReward> {
+        self.rewards.iter().find(|r| r.token == token)
     }
 
     pub fn team_rewards(&self) -> &HashMap<Uuid, TeamReward> {
         &self.team_rewards
     }
 
+    pub fn governance_config(&self) -> &EpochGovernanceConfig {
+        &self.governance_config
+    }
+
     // Setter methods
     pub fn set_name(&mut self, name: String) {
         self.name = name;
@@ -109,6 +154,10 @@ impl Epoch {
         self.status = status;
     }
 
+    pub fn set_governance_config(&mut self, config: EpochGovernanceConfig) {
+        self.governance_config = config;
+    }
+
     // Methods for managing associated proposals
     pub fn add_proposal(&mut self, proposal_id: Uuid) {
         if !self.associated_proposals.contains(&proposal_id) {
@@ -121,23 +170,32 @@ impl Epoch {
     }
 
     // Methods for managing rewards
+    /// Sets the reward for `token`, replacing any existing amount for that token.
     pub fn set_reward(&mut self, token: String, amount: f64) -> Result<(), &'static str> {
-        self.reward = Some(EpochReward::new(token, amount)?);
+        let reward = EpochReward::new(token, amount)?;
+        match self.rewards.iter_mut().find(|r| r.token == reward.token) {
+            Some(existing) => *existing = reward,
+            None => self.rewards.push(reward),
+        }
         Ok(())
     }
 
-    pub fn remove_reward(&mut self) {
-        self.reward = None;
+    /// Adds `amount` to the reward for `token`, creating the reward if the token has none yet.
+    pub fn add_reward(&mut self, token: String, amount: f64) -> Result<(), &'static str> {
+        let reward = EpochReward::new(token, amount)?;
+        match self.rewards.iter_mut().find(|r| r.token == reward.token) {
+            Some(existing) => existing.amount += reward.amount,
+            None => self.rewards.push(reward),
+        }
+        Ok(())
     }
 
-    pub fn set_team_reward(&mut self, team_id: Uuid, percentage: f64, amount: f64) -> Result<(), &'static str> {
-        if percentage < 0.0 || percentage > 100.0 {
-            return Err("Percentage must be between 0 and 100");
-        }
-        if amount < 0.0 {
-            return Err("Amount must be non-negative");
-        }
-        self.team_rewards.insert(team_id, TeamReward { percentage, amount });
+    pub fn remove_reward(&mut self, token: &str) {
+        self.rewards.retain(|r| r.token != token);
+    }
+
+    pub fn set_team_reward(&mut self, team_id: Uuid, percentage: f64, amounts: HashMap<String, f64>) -> Result<(), &'static str> {
+        self.team_rewards.insert(team_id, TeamReward::new(percentage, amounts)?);
         Ok(())
     }
 
@@ -168,16 +226,16 @@ impl Epoch {
         self.associated_proposals.contains(&proposal_id)
     }
 
-    pub fn total_reward_amount(&self) -> f64 {
-        self.reward.as_ref().map_or(0.0, |r| r.amount)
+    pub fn total_reward_amount(&self, token: &str) -> f64 {
+        self.reward(token).map_or(0.0, |r| r.amount)
     }
 
-    pub fn distributed_reward_amount(&self) -> f64 {
-        self.team_rewards.values().map(|r| r.amount).sum()
+    pub fn distributed_reward_amount(&self, token: &str) -> f64 {
+        self.team_rewards.values().map(|r| r.amount(token)).sum()
     }
 
-    pub fn remaining_reward_amount(&self) -> f64 {
-        self.total_reward_amount() - self.distributed_reward_amount()
+    pub fn remaining_reward_amount(&self, token: &str) -> f64 {
+        self.total_reward_amount(token) - self.distributed_reward_amount(token)
     }
 
     pub fn is_planned(&self) -> bool {
@@ -194,12 +252,57 @@ impl Epoch {
 
 }
 
+impl From<EpochRecord> for Epoch {
+    fn from(record: EpochRecord) -> Self {
+        let mut rewards = record.rewards;
+        let legacy_token = record.reward.as_ref().map(|r| r.token.clone());
+        if rewards.is_empty() {
+            rewards.extend(record.reward);
+        }
+
+        let team_rewards = record.team_rewards.into_iter()
+            .map(|(team_id, reward)| {
+                let mut amounts = reward.amounts;
+                if let (Some(amount), Some(token)) = (reward.amount, &legacy_token) {
+                    amounts.entry(token.clone()).or_insert(amount);
+                }
+                (team_id, TeamReward { percentage: reward.percentage, amounts })
+            })
+            .collect();
+
+        Self {
+            id: record.id,
+            name: record.name,
+            start_date: record.start_date,
+            end_date: record.end_date,
+            status: record.status,
+            associated_proposals: record.associated_proposals,
+            rewards,
+            team_rewards,
+            governance_config: record.governance_config,
+        }
+    }
+}
+
 impl NameMatches for Epoch {
     fn name_matches(&self, name: &str) -> bool {
         self.name() == name
     }
 }
 
+impl std::str::FromStr for EpochStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "planned" => Ok(EpochStatus::Planned),
+            "active" => Ok(EpochStatus::Active),
+            "closed" => Ok(EpochStatus::Closed),
+            _ => Err(format!("Invalid epoch status: {}. Use planned, active or closed", s)),
+        }
+    }
+}
+
 impl EpochReward {
     pub fn new(token: String, amount: f64) -> Result<Self, &'static str> {
         if amount < 0.0 {
@@ -218,22 +321,46 @@ impl EpochReward {
 }
 
 impl TeamReward {
-    pub fn new(percentage: f64, amount: f64) -> Result<Self, &'static str> {
+    pub fn new(percentage: f64, amounts: HashMap<String, f64>) -> Result<Self, &'static str> {
         if percentage < 0.0 || percentage > 100.0 {
             return Err("Percentage must be between 0 and 100");
         }
-        if amount < 0.0 {
+        if amounts.values().any(|&amount| amount < 0.0) {
             return Err("Amount must be non-negative");
         }
-        Ok(Self { percentage, amount })
+        Ok(Self { percentage, amounts })
     }
 
     pub fn percentage(&self) -> f64 {
         self.percentage
     }
 
-    pub fn amount(&self) -> f64 {
-        self.amount
+    pub fn amounts(&self) -> &HashMap<String, f64> {
+        &self.amounts
+    }
+
+    /// Amount of `token` owed to the team, zero when the epoch does not pay in that token.
+    pub fn amount(&self, token: &str) -> f64 {
+        self.amounts.get(token).copied().unwrap_or(0.0)
+    }
+}
+
+impl EpochGovernanceConfig {
+    pub fn new(min_days_before_vote: Option<u64>, max_days_to_vote: Option<u64>) -> Result<Self, &'static str> {
+        if let (Some(min), Some(max)) = (min_days_before_vote, max_days_to_vote) {
+            if min > max {
+                return Err("Minimum days before vote cannot exceed maximum days to vote");
+            }
+        }
+        Ok(Self { min_days_before_vote, max_days_to_vote })
+    }
+
+    pub fn min_days_before_vote(&self) -> Option<u64> {
+        self.min_days_before_vote
+    }
+
+    pub fn max_days_to_vote(&self) -> Option<u64> {
+        self.max_days_to_vote
     }
 }
 
@@ -253,7 +380,7 @@ mod tests {
         assert_eq!(epoch.end_date(), end_date);
         assert_eq!(epoch.status(), EpochStatus::Planned);
         assert!(epoch.associated_proposals().is_empty());
-        assert!(epoch.reward().is_none());
+        assert!(epoch.rewards().is_empty());
         assert!(epoch.team_rewards().is_empty());
     }
 
@@ -336,11 +463,27 @@ mod tests {
         let mut epoch = create_test_epoch();
         
         epoch.set_reward("ETH".to_string(), 100.0).unwrap();
-        assert_eq!(epoch.reward().unwrap().token(), "ETH");
-        assert_eq!(epoch.reward().unwrap().amount(), 100.0);
+        assert_eq!(epoch.reward("ETH").unwrap().token(), "ETH");
+        assert_eq!(epoch.reward("ETH").unwrap().amount(), 100.0);
+
+        // A second token is added alongside, the same token is replaced
+        epoch.set_reward("USDC".to_string(), 5000.0).unwrap();
+        epoch.set_reward("ETH".to_string(), 150.0).unwrap();
+        assert_eq!(epoch.rewards().len(), 2);
+        assert_eq!(epoch.reward("ETH").unwrap().amount(), 150.0);
+        assert_eq!(epoch.reward("USDC").unwrap().amount(), 5000.0);
 
-        epoch.remove_reward();
-        assert!(epoch.reward().is_none());
+        epoch.remove_reward("ETH");
+        assert!(epoch.reward("ETH").is_none());
+        assert_eq!(epoch.rewards().len(), 1);
+
+        // add_reward tops up an existing token
+        epoch.add_reward("USDC".to_string(), 250.0).unwrap();
+        epoch.add_reward("DAI".to_string(), 10.0).unwrap();
+        assert_eq!(epoch.reward("USDC").unwrap().amount(), 5250.0);
+        assert_eq!(epoch.reward("DAI").unwrap().amount(), 10.0);
+        assert!(epoch.add_reward("DAI".to_string(), -1.0).is_err());
+        assert_eq!(epoch.reward("DAI").unwrap().amount(), 10.0);
     }
 
     #[test]
@@ -355,9 +498,10 @@ mod tests {
         let mut epoch = create_test_epoch();
         let team_id = Uuid::new_v4();
 
-        epoch.set_team_reward(team_id, 10.0, 50.0).unwrap();
+        epoch.set_team_reward(team_id, 10.0, HashMap::from([("ETH".to_string(), 50.0)])).unwrap();
         assert_eq!(epoch.team_rewards().get(&team_id).unwrap().percentage(), 10.0);
-        assert_eq!(epoch.team_rewards().get(&team_id).unwrap().amount(), 50.0);
+        assert_eq!(epoch.team_rewards().get(&team_id).unwrap().amount("ETH"), 50.0);
+        assert_eq!(epoch.team_rewards().get(&team_id).unwrap().amount("USDC"), 0.0);
 
         epoch.remove_team_reward(&team_id);
         assert!(epoch.team_rewards().get(&team_id).is_none());
@@ -368,25 +512,53 @@ mod tests {
         let mut epoch = create_test_epoch();
         let team_id = Uuid::new_v4();
 
-        assert!(epoch.set_team_reward(team_id, -10.0, 50.0).is_err());
-        assert!(epoch.set_team_reward(team_id, 110.0, 50.0).is_err());
-        assert!(epoch.set_team_reward(team_id, 10.0, -50.0).is_err());
+        let amounts = |amount: f64| HashMap::from([("ETH".to_string(), amount)]);
+        assert!(epoch.set_team_reward(team_id, -10.0, amounts(50.0)).is_err());
+        assert!(epoch.set_team_reward(team_id, 110.0, amounts(50.0)).is_err());
+        assert!(epoch.set_team_reward(team_id, 10.0, amounts(-50.0)).is_err());
     }
 
     #[test]
     fn test_reward_calculations() {
         let mut epoch = create_test_epoch();
         epoch.set_reward("ETH".to_string(), 100.0).unwrap();
+        epoch.set_reward("USDC".to_string(), 1000.0).unwrap();
 
         let team1_id = Uuid::new_v4();
         let team2_id = Uuid::new_v4();
 
-        epoch.set_team_reward(team1_id, 60.0, 60.0).unwrap();
-        epoch.set_team_reward(team2_id, 30.0, 30.0).unwrap();
+        epoch.set_team_reward(team1_id, 60.0, HashMap::from([("ETH".to_string(), 60.0), ("USDC".to_string(), 600.0)])).unwrap();
+        epoch.set_team_reward(team2_id, 30.0, HashMap::from([("ETH".to_string(), 30.0), ("USDC".to_string(), 300.0)])).unwrap();
+
+        assert_eq!(epoch.total_reward_amount("ETH"), 100.0);
+        assert_eq!(epoch.distributed_reward_amount("ETH"), 90.0);
+        assert_eq!(epoch.remaining_reward_amount("ETH"), 10.0);
+        assert_eq!(epoch.remaining_reward_amount("USDC"), 100.0);
+    }
 
-        assert_eq!(epoch.total_reward_amount(), 100.0);
-        assert_eq!(epoch.distributed_reward_amount(), 90.0);
-        assert_eq!(epoch.remaining_reward_amount(), 10.0);
+    #[test]
+    fn test_legacy_single_reward_deserialization() {
+        let team_id = Uuid::new_v4();
+        let epoch = create_test_epoch();
+        let mut value = serde_json::to_value(&epoch).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("rewards");
+        object.insert("reward".to_string(), serde_json::json!({ "token": "ETH", "amount": 100.0 }));
+        object.insert("team_rewards".to_string(), serde_json::json!({
+            team_id.to_string(): { "percentage": 40.0, "amount": 40.0 }
+        }));
+
+        let migrated: Epoch = serde_json::from_value(value).unwrap();
+        assert_eq!(migrated.rewards().len(), 1);
+        assert_eq!(migrated.reward("ETH").unwrap().amount(), 100.0);
+        let team_reward = migrated.team_rewards().get(&team_id).unwrap();
+        assert_eq!(team_reward.percentage(), 40.0);
+        assert_eq!(team_reward.amount("ETH"), 40.0);
+
+        // Migrated epochs round-trip in the new format
+        let json = serde_json::to_string(&migrated).unwrap();
+        assert!(!json.contains("\"reward\""));
+        assert_eq!(serde_json::from_str::<Epoch>(&json).unwrap(), migrated);
     }
 
     fn create_test_epoch() -> Epoch {
@@ -394,4 +566,35 @@ mod tests {
         let end_date = start_date + chrono::Duration::days(30);
         Epoch::new("Test Epoch".to_string(), start_date, end_date).unwrap()
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_governance_config() {
+        let mut epoch = create_test_epoch();
+        assert_eq!(epoch.governance_config(), &EpochGovernanceConfig::default());
+
+        let config = EpochGovernanceConfig::new(Some(3), Some(14)).unwrap();
+        epoch.set_governance_config(config.clone());
+        assert_eq!(epoch.governance_config().min_days_before_vote(), Some(3));
+        assert_eq!(epoch.governance_config().max_days_to_vote(), Some(14));
+
+        assert!(EpochGovernanceConfig::new(Some(10), Some(5)).is_err());
+    }
+
+    #[test]
+    fn test_governance_config_defaults_when_missing() {
+        let epoch = create_test_epoch();
+        let mut json = serde_json::to_value(&epoch).unwrap();
+        json.as_object_mut().unwrap().remove("governance_config");
+
+        let deserialized: Epoch = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.governance_config(), &EpochGovernanceConfig::default());
+    }
+
+    #[test]
+    fn test_epoch_status_from_str() {
+        assert_eq!("Active".parse::<EpochStatus>().unwrap(), EpochStatus::Active);
+        assert_eq!("planned".parse::<EpochStatus>().unwrap(), EpochStatus::Planned);
+        assert_eq!("CLOSED".parse::<EpochStatus>().unwrap(), EpochStatus::Closed);
+        assert!("open".parse::<EpochStatus>().is_err());
+    }
+}
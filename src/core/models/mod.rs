@@ -6,10 +6,12 @@ pub mod epoch;
 pub mod proposal;
 pub mod raffle;
 pub mod vote;
+pub mod template;
 
 pub use common::*;
 pub use team::*;
 pub use epoch::*;
 pub use proposal::*;
 pub use raffle::*;
-pub use vote::*;
\ No newline at end of file
+pub use vote::*;
+pub use template::*;
\ No newline at end of file
@@ -1,11 +1,13 @@
 use crate::commands::common::{UpdateProposalDetails, BudgetRequestDetailsCommand};
 use super::common::NameMatches;
 use uuid::Uuid;
-use chrono::NaiveDate;
+use chrono::{DateTime, Months, NaiveDate, Utc};
 use std::{collections::HashMap, str::FromStr};
 use serde::{Serialize, Deserialize};
 use ethers::types::{Address, H256};
 use super::common::{address_serde, tx_hash_serde};
+use crate::services::ethereum::parse_ethereum_address;
+use crate::services::ipfs::validate_ipfs_hash;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Proposal {
@@ -20,6 +22,32 @@ pub struct Proposal {
     published_at: Option<NaiveDate>,
     resolved_at: Option<NaiveDate>,
     is_historical: bool,
+    #[serde(default)]
+    revision_history: Vec<ProposalRevision>,
+    #[serde(default)]
+    reviewers: Vec<ProposalReviewer>,
+    #[serde(default)]
+    category: Option<ProposalCategory>,
+    /// IPFS CID of the proposal document
+    #[serde(default)]
+    ipfs_hash: Option<String>,
+    /// Labels copied from the template the proposal was created from
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProposalReviewer {
+    name: String,
+    signed_off_at: Option<NaiveDate>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProposalRevision {
+    timestamp: DateTime<Utc>,
+    field_changed: String,
+    old_value: serde_json::Value,
+    new_value: serde_json::Value,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,6 +57,92 @@ pub enum ProposalStatus {
     Reopened,
 }
 
+/// Status filter for proposal listings. Open also matches reopened proposals;
+/// approved and rejected match the resolution of closed ones, and paid and unpaid
+/// split approved budget requests by whether the payment has been recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProposalListFilter {
+    Open,
+    Approved,
+    Rejected,
+    Paid,
+    Unpaid,
+}
+
+impl ProposalListFilter {
+    pub fn matches(&self, proposal: &Proposal) -> bool {
+        match self {
+            ProposalListFilter::Open => proposal.is_actionable(),
+            ProposalListFilter::Approved => proposal.is_approved(),
+            ProposalListFilter::Rejected => proposal.is_rejected(),
+            ProposalListFilter::Paid => proposal.is_approved()
+                && proposal.budget_request_details().is_some_and(|d| d.is_paid()),
+            ProposalListFilter::Unpaid => proposal.is_approved()
+                && proposal.budget_request_details().is_some_and(|d| !d.is_paid()),
+        }
+    }
+}
+
+impl FromStr for ProposalListFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(ProposalListFilter::Open),
+            "approved" => Ok(ProposalListFilter::Approved),
+            "rejected" => Ok(ProposalListFilter::Rejected),
+            "paid" => Ok(ProposalListFilter::Paid),
+            "unpaid" => Ok(ProposalListFilter::Unpaid),
+            _ => Err(format!("Invalid status filter: {}. Use open, approved, rejected, paid, unpaid or all", s)),
+        }
+    }
+}
+
+/// Kind of work a proposal's budget request funds.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ProposalCategory {
+    Infrastructure,
+    Research,
+    Marketing,
+    Operations,
+    Grant,
+    Loan,
+    Other(String),
+}
+
+impl std::fmt::Display for ProposalCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProposalCategory::Infrastructure => write!(f, "Infrastructure"),
+            ProposalCategory::Research => write!(f, "Research"),
+            ProposalCategory::Marketing => write!(f, "Marketing"),
+            ProposalCategory::Operations => write!(f, "Operations"),
+            ProposalCategory::Grant => write!(f, "Grant"),
+            ProposalCategory::Loan => write!(f, "Loan"),
+            ProposalCategory::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl FromStr for ProposalCategory {
+    type Err = String;
+
+    /// Known categories match case-insensitively; any other name becomes `Other`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        match name.to_lowercase().as_str() {
+            "" => Err("Category cannot be empty".to_string()),
+            "infrastructure" => Ok(ProposalCategory::Infrastructure),
+            "research" => Ok(ProposalCategory::Research),
+            "marketing" => Ok(ProposalCategory::Marketing),
+            "operations" => Ok(ProposalCategory::Operations),
+            "grant" => Ok(ProposalCategory::Grant),
+            "loan" => Ok(ProposalCategory::Loan),
+            _ => Ok(ProposalCategory::Other(name.to_string())),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Resolution {
     Approved,
@@ -50,6 +164,37 @@ pub struct BudgetRequestDetails {
     #[serde(with = "tx_hash_serde")]
     payment_tx: Option<H256>,
     payment_date: Option<NaiveDate>,
+    #[serde(default)]
+    loan_terms: Option<LoanTerms>,
+    #[serde(default)]
+    repaid_at: Option<NaiveDate>,
+    /// Per-team requested amounts when one proposal funds several teams
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    team_allocations: HashMap<Uuid, HashMap<String, f64>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    allocation_payments: HashMap<Uuid, AllocationPayment>,
+}
+
+/// Payment of one team's share of a multi-team budget request.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AllocationPayment {
+    payment_tx: H256,
+    payment_date: NaiveDate,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RepaymentSchedule {
+    AtMaturity,
+    Monthly,
+    Quarterly,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoanTerms {
+    interest_rate: f64,
+    due_date: NaiveDate,
+    repayment_schedule: RepaymentSchedule,
+    repaid_amounts: HashMap<String, f64>,
 }
 
 
@@ -76,6 +221,11 @@ impl Proposal {
             published_at,
             resolved_at: None,
             is_historical,
+            revision_history: Vec::new(),
+            reviewers: Vec::new(),
+            category: None,
+            ipfs_hash: None,
+            tags: Vec::new(),
         }
     }
 
@@ -108,6 +258,10 @@ impl Proposal {
         self.budget_request_details.as_ref()
     }
 
+    pub fn budget_request_details_mut(&mut self) -> Option<&mut BudgetRequestDetails> {
+        self.budget_request_details.as_mut()
+    }
+
     pub fn announced_at(&self) -> Option<NaiveDate> {
         self.announced_at
     }
@@ -124,6 +278,26 @@ impl Proposal {
         self.is_historical
     }
 
+    pub fn revision_history(&self) -> &[ProposalRevision] {
+        &self.revision_history
+    }
+
+    pub fn reviewers(&self) -> &[ProposalReviewer] {
+        &self.reviewers
+    }
+
+    pub fn category(&self) -> Option<&ProposalCategory> {
+        self.category.as_ref()
+    }
+
+    pub fn ipfs_hash(&self) -> Option<&str> {
+        self.ipfs_hash.as_deref()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
     // Setter methods
     pub fn set_title(&mut self, title: String) {
         self.title = title;
@@ -133,6 +307,23 @@ impl Proposal {
         self.url = url;
     }
 
+    pub fn set_category(&mut self, category: Option<ProposalCategory>) {
+        self.category = category;
+    }
+
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+    }
+
+    /// Sets the IPFS hash of the proposal document, rejecting malformed CIDs.
+    pub fn set_ipfs_hash(&mut self, ipfs_hash: Option<String>) -> Result<(), &'static str> {
+        if let Some(hash) = &ipfs_hash {
+            validate_ipfs_hash(hash)?;
+        }
+        self.ipfs_hash = ipfs_hash;
+        Ok(())
+    }
+
     pub fn set_status(&mut self, status: ProposalStatus) {
         self.status = status;
     }
@@ -186,7 +377,49 @@ impl Proposal {
         self.is_historical = is_historical;
     }
 
+    /// Replaces the reviewer list. Reviewers kept from the previous list keep their sign-off.
+    pub fn set_reviewers(&mut self, names: Vec<String>) -> Result<(), &'static str> {
+        let mut reviewers: Vec<ProposalReviewer> = Vec::new();
+        for name in names {
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return Err("Reviewer name cannot be empty");
+            }
+            if reviewers.iter().any(|r| r.name == name) {
+                return Err("Duplicate reviewer name");
+            }
+            let signed_off_at = self.reviewers.iter()
+                .find(|r| r.name == name)
+                .and_then(|r| r.signed_off_at);
+            reviewers.push(ProposalReviewer { name, signed_off_at });
+        }
+        self.reviewers = reviewers;
+        Ok(())
+    }
+
+    pub fn record_reviewer_sign_off(&mut self, name: &str, date: NaiveDate) -> Result<(), &'static str> {
+        let reviewer = self.reviewers.iter_mut()
+            .find(|r| r.name == name)
+            .ok_or("Reviewer is not assigned to this proposal")?;
+        if reviewer.signed_off_at.is_some() {
+            return Err("Reviewer has already signed off");
+        }
+        reviewer.signed_off_at = Some(date);
+        Ok(())
+    }
+
     // Helper methods
+    pub fn pending_reviewers(&self) -> Vec<&str> {
+        self.reviewers.iter()
+            .filter(|r| !r.is_signed_off())
+            .map(|r| r.name())
+            .collect()
+    }
+
+    pub fn all_reviewers_signed_off(&self) -> bool {
+        self.reviewers.iter().all(|r| r.is_signed_off())
+    }
+
     pub fn is_open(&self) -> bool {
         matches!(self.status, ProposalStatus::Open)
     }
@@ -241,12 +474,34 @@ impl Proposal {
     }
 
     pub fn update(&mut self, updates: UpdateProposalDetails, team_id: Option<Uuid>) -> Result<(), &'static str> {
+        let before = self.tracked_fields();
+        let result = self.apply_update(updates, team_id);
+        let after = self.tracked_fields();
+
+        // Record whatever actually changed, even if the update failed part way through
+        let timestamp = Utc::now();
+        for ((field, old_value), (_, new_value)) in before.into_iter().zip(after) {
+            if old_value != new_value {
+                self.revision_history.push(ProposalRevision::new(timestamp, field, old_value, new_value));
+            }
+        }
+
+        result
+    }
+
+    fn apply_update(&mut self, updates: UpdateProposalDetails, team_id: Option<Uuid>) -> Result<(), &'static str> {
         if let Some(title) = updates.title {
             self.set_title(title);
         }
         if let Some(url) = updates.url {
             self.set_url(Some(url));
         }
+        if let Some(category) = updates.category.or_else(|| updates.budget_request_details.as_ref().and_then(|d| d.category.clone())) {
+            self.set_category(Some(category));
+        }
+        if let Some(hash) = updates.budget_request_details.as_ref().and_then(|d| d.ipfs_hash.clone()) {
+            self.set_ipfs_hash(Some(hash))?;
+        }
         
         let new_announced_at = updates.announced_at.or(self.announced_at);
         let new_published_at = updates.published_at.or(self.published_at);
@@ -290,9 +545,72 @@ impl Proposal {
  
         Ok(())
     }
+
+    fn tracked_fields(&self) -> Vec<(&'static str, serde_json::Value)> {
+        let details = self.budget_request_details.as_ref();
+        vec![
+            ("title", serde_json::json!(self.title)),
+            ("url", serde_json::json!(self.url)),
+            ("category", serde_json::json!(self.category)),
+            ("ipfs_hash", serde_json::json!(self.ipfs_hash)),
+            ("announced_at", serde_json::json!(self.announced_at)),
+            ("published_at", serde_json::json!(self.published_at)),
+            ("resolved_at", serde_json::json!(self.resolved_at)),
+            ("team", serde_json::json!(details.and_then(|d| d.team()))),
+            ("request_amounts", serde_json::json!(details.map(|d| d.request_amounts()))),
+            ("start_date", serde_json::json!(details.and_then(|d| d.start_date()))),
+            ("end_date", serde_json::json!(details.and_then(|d| d.end_date()))),
+            ("is_loan", serde_json::json!(details.map(|d| d.is_loan()))),
+            ("payment_address", serde_json::json!(details.and_then(|d| d.payment_address()))),
+        ]
+    }
     
 }
 
+impl ProposalRevision {
+    pub fn new(timestamp: DateTime<Utc>, field_changed: &str, old_value: serde_json::Value, new_value: serde_json::Value) -> Self {
+        ProposalRevision {
+            timestamp,
+            field_changed: field_changed.to_string(),
+            old_value,
+            new_value,
+        }
+    }
+
+    // Getter methods
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    pub fn field_changed(&self) -> &str {
+        &self.field_changed
+    }
+
+    pub fn old_value(&self) -> &serde_json::Value {
+        &self.old_value
+    }
+
+    pub fn new_value(&self) -> &serde_json::Value {
+        &self.new_value
+    }
+}
+
+impl ProposalReviewer {
+    // Getter methods
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn signed_off_at(&self) -> Option<NaiveDate> {
+        self.signed_off_at
+    }
+
+    // Helper methods
+    pub fn is_signed_off(&self) -> bool {
+        self.signed_off_at.is_some()
+    }
+}
+
 impl NameMatches for Proposal {
     fn name_matches(&self, name: &str) -> bool {
         self.title() == name
@@ -311,7 +629,7 @@ impl BudgetRequestDetails {
     ) -> Result<Self, &'static str> {
         // Validate ethereum address if provided
         let payment_address = if let Some(addr) = payment_address {
-            Some(Address::from_str(&addr).map_err(|_| "Invalid Ethereum address")?)
+            Some(parse_ethereum_address(&addr)?)
         } else {
             None
         };
@@ -325,6 +643,10 @@ impl BudgetRequestDetails {
             payment_address,
             payment_tx: None,
             payment_date: None,
+            loan_terms: None,
+            repaid_at: None,
+            team_allocations: HashMap::new(),
+            allocation_payments: HashMap::new(),
         };
         brd.validate()?;
         Ok(brd)
@@ -365,7 +687,11 @@ impl BudgetRequestDetails {
             is_loan: None,
             payment_address: None,
             payment_tx: None,
-            payment_date: None
+            payment_date: None,
+            loan_terms: None,
+            repaid_at: None,
+            team_allocations: HashMap::new(),
+            allocation_payments: HashMap::new(),
         }
     }
 
@@ -402,6 +728,44 @@ impl BudgetRequestDetails {
         self.payment_date
     }
 
+    pub fn loan_terms(&self) -> Option<&LoanTerms> {
+        self.loan_terms.as_ref()
+    }
+
+    pub fn repaid_at(&self) -> Option<NaiveDate> {
+        self.repaid_at
+    }
+
+    /// Requested amounts per team. A single-team request is reported as a
+    /// one-entry allocation of its full amounts.
+    pub fn team_allocations(&self) -> HashMap<Uuid, HashMap<String, f64>> {
+        if !self.team_allocations.is_empty() {
+            return self.team_allocations.clone();
+        }
+        self.team.map(|team| HashMap::from([(team, self.request_amounts.clone())])).unwrap_or_default()
+    }
+
+    /// Whether the request is for `team_id`, either as its team or through an allocation.
+    pub fn includes_team(&self, team_id: Uuid) -> bool {
+        self.team == Some(team_id) || self.team_allocations.contains_key(&team_id)
+    }
+
+    pub fn has_team_allocations(&self) -> bool {
+        !self.team_allocations.is_empty()
+    }
+
+    /// Transaction and date of the payment covering `team_id`'s share, either a
+    /// per-team payment or the payment of the whole request.
+    pub fn team_payment(&self, team_id: Uuid) -> Option<(H256, NaiveDate)> {
+        if let Some(payment) = self.allocation_payments.get(&team_id) {
+            return Some((payment.payment_tx, payment.payment_date));
+        }
+        if !self.team_allocations().contains_key(&team_id) {
+            return None;
+        }
+        self.payment_tx.zip(self.payment_date)
+    }
+
     // Setter methods
     pub fn set_team(&mut self, team: Option<Uuid>) {
         self.team = team;
@@ -434,14 +798,51 @@ impl BudgetRequestDetails {
         self.is_loan = Some(is_loan);
     }
 
+    /// Splits the request between teams. The requested amounts become the
+    /// per-token totals of the allocations.
+    pub fn set_team_allocations(&mut self, allocations: HashMap<Uuid, HashMap<String, f64>>) -> Result<(), &'static str> {
+        if self.is_paid() || !self.allocation_payments.is_empty() {
+            return Err("Cannot change team allocations after a payment has been recorded");
+        }
+        if allocations.is_empty() || allocations.values().any(|amounts| amounts.is_empty()) {
+            return Err("Each team allocation needs at least one amount");
+        }
+        if allocations.values().flat_map(|amounts| amounts.values()).any(|&amount| amount <= 0.0) {
+            return Err("Allocated amounts must be positive");
+        }
+
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        for (token, amount) in allocations.values().flatten() {
+            *totals.entry(token.clone()).or_insert(0.0) += amount;
+        }
+        self.request_amounts = totals;
+        self.team_allocations = allocations;
+        Ok(())
+    }
+
     pub fn set_payment_address(&mut self, address: Option<String>) -> Result<(), &'static str> {
         self.payment_address = match address {
-            Some(addr) => Some(Address::from_str(&addr).map_err(|_| "Invalid Ethereum address")?),
+            Some(addr) => Some(parse_ethereum_address(&addr)?),
             None => None,
         };
         Ok(())
     }
 
+    pub fn set_loan_terms(&mut self, loan_terms: Option<LoanTerms>) -> Result<(), &'static str> {
+        if let Some(terms) = &loan_terms {
+            if !self.is_loan() {
+                return Err("Loan terms can only be set on loan requests");
+            }
+            if let Some(start) = self.start_date {
+                if terms.due_date() <= start {
+                    return Err("Loan due date must be after the request start date");
+                }
+            }
+        }
+        self.loan_terms = loan_terms;
+        Ok(())
+    }
+
     // Method for recording payment
     pub fn record_payment(&mut self, tx_hash: String, payment_date: NaiveDate) -> Result<(), &'static str> {
         // Validate transaction hash
@@ -455,6 +856,43 @@ impl BudgetRequestDetails {
     pub fn clear_payment(&mut self) {
         self.payment_tx = None;
         self.payment_date = None;
+        self.repaid_at = None;
+        self.allocation_payments.clear();
+    }
+
+    /// Records payment of one team's allocation. Once every allocation is paid
+    /// the whole request counts as paid, dated by the last payment.
+    pub fn record_allocation_payment(&mut self, team_id: Uuid, tx_hash: String, payment_date: NaiveDate) -> Result<(), &'static str> {
+        if !self.team_allocations.contains_key(&team_id) {
+            return Err("Team has no allocation in this budget request");
+        }
+        if self.team_payment(team_id).is_some() {
+            return Err("Payment already recorded for this team's allocation");
+        }
+        let tx = H256::from_str(&tx_hash).map_err(|_| "Invalid transaction hash")?;
+        self.allocation_payments.insert(team_id, AllocationPayment { payment_tx: tx, payment_date });
+
+        if self.team_allocations.keys().all(|team| self.allocation_payments.contains_key(team)) {
+            self.payment_tx = Some(tx);
+            self.payment_date = Some(payment_date);
+        }
+        Ok(())
+    }
+
+    /// Marks a paid-out loan as settled on `repaid_at`.
+    pub fn record_repayment(&mut self, repaid_at: NaiveDate) -> Result<(), &'static str> {
+        if !self.is_loan() {
+            return Err("Only loan requests can be repaid");
+        }
+        let payment_date = self.payment_date.ok_or("Loan has not been paid out yet")?;
+        if self.repaid_at.is_some() {
+            return Err("Loan has already been repaid");
+        }
+        if repaid_at < payment_date {
+            return Err("Repayment date cannot be before the payment date");
+        }
+        self.repaid_at = Some(repaid_at);
+        Ok(())
     }
 
 
@@ -464,11 +902,96 @@ impl BudgetRequestDetails {
         self.payment_tx.is_some() && self.payment_date.is_some()
     }
 
+    pub fn is_repaid(&self) -> bool {
+        self.repaid_at.is_some()
+    }
+
     pub fn total_request_amount(&self) -> f64 {
         self.request_amounts.values().sum()
     }
 }
 
+impl FromStr for RepaymentSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "atmaturity" | "at-maturity" | "maturity" => Ok(RepaymentSchedule::AtMaturity),
+            "monthly" => Ok(RepaymentSchedule::Monthly),
+            "quarterly" => Ok(RepaymentSchedule::Quarterly),
+            _ => Err(format!("Invalid repayment schedule: {}. Use AtMaturity, Monthly or Quarterly", s)),
+        }
+    }
+}
+
+impl LoanTerms {
+    pub fn new(
+        interest_rate: f64,
+        due_date: NaiveDate,
+        repayment_schedule: RepaymentSchedule,
+        repaid_amounts: HashMap<String, f64>,
+    ) -> Result<Self, &'static str> {
+        if interest_rate < 0.0 {
+            return Err("Interest rate cannot be negative");
+        }
+        if repaid_amounts.values().any(|&amount| amount < 0.0) {
+            return Err("Repaid amounts cannot be negative");
+        }
+        Ok(LoanTerms {
+            interest_rate,
+            due_date,
+            repayment_schedule,
+            repaid_amounts,
+        })
+    }
+
+    // Getter methods
+    pub fn interest_rate(&self) -> f64 {
+        self.interest_rate
+    }
+
+    pub fn due_date(&self) -> NaiveDate {
+        self.due_date
+    }
+
+    pub fn repayment_schedule(&self) -> &RepaymentSchedule {
+        &self.repayment_schedule
+    }
+
+    pub fn repaid_amounts(&self) -> &HashMap<String, f64> {
+        &self.repaid_amounts
+    }
+
+    // Helper methods
+    pub fn total_due(&self, principal: f64) -> f64 {
+        principal * (1.0 + self.interest_rate)
+    }
+
+    /// Share of the total due that should have been repaid by `as_of`, for a loan starting at `start`.
+    pub fn expected_repaid_fraction(&self, start: NaiveDate, as_of: NaiveDate) -> f64 {
+        let step = match self.repayment_schedule {
+            RepaymentSchedule::AtMaturity => return if as_of >= self.due_date { 1.0 } else { 0.0 },
+            RepaymentSchedule::Monthly => 1,
+            RepaymentSchedule::Quarterly => 3,
+        };
+
+        // Installments fall every `step` months after the start, with the last one on the due date
+        let mut installments = Vec::new();
+        let mut k = 1;
+        while let Some(date) = start.checked_add_months(Months::new(step * k)) {
+            if date >= self.due_date {
+                break;
+            }
+            installments.push(date);
+            k += 1;
+        }
+        installments.push(self.due_date);
+
+        let elapsed = installments.iter().filter(|&&date| date <= as_of).count();
+        elapsed as f64 / installments.len() as f64
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -496,6 +1019,93 @@ mod tests {
         assert!(proposal.resolution().is_none());
     }
 
+    #[test]
+    fn test_proposal_list_filter() {
+        let open = create_test_proposal();
+        let mut approved = create_test_proposal();
+        approved.approve().unwrap();
+        let mut rejected = create_test_proposal();
+        rejected.reject().unwrap();
+
+        assert_eq!("Open".parse::<ProposalListFilter>().unwrap(), ProposalListFilter::Open);
+        assert!("all".parse::<ProposalListFilter>().is_err());
+
+        assert!(ProposalListFilter::Open.matches(&open));
+        assert!(!ProposalListFilter::Open.matches(&approved));
+        assert!(ProposalListFilter::Approved.matches(&approved));
+        assert!(!ProposalListFilter::Approved.matches(&rejected));
+        assert!(ProposalListFilter::Rejected.matches(&rejected));
+    }
+
+    #[test]
+    fn test_proposal_category() {
+        assert_eq!("research".parse::<ProposalCategory>().unwrap(), ProposalCategory::Research);
+        assert_eq!(" LOAN ".parse::<ProposalCategory>().unwrap(), ProposalCategory::Loan);
+        let other = "Security Audit".parse::<ProposalCategory>().unwrap();
+        assert_eq!(other, ProposalCategory::Other("Security Audit".to_string()));
+        assert_eq!(other.to_string(), "Security Audit");
+        assert!("".parse::<ProposalCategory>().is_err());
+
+        // Proposals saved before categories existed load without one
+        let mut proposal = create_test_proposal();
+        let mut json = serde_json::to_value(&proposal).unwrap();
+        json.as_object_mut().unwrap().remove("category");
+        assert_eq!(serde_json::from_value::<Proposal>(json).unwrap().category(), None);
+
+        let updates = UpdateProposalDetails {
+            title: None,
+            url: None,
+            budget_request_details: None,
+            announced_at: None,
+            published_at: None,
+            resolved_at: None,
+            category: Some(ProposalCategory::Grant),
+        };
+        proposal.update(updates, None).unwrap();
+        assert_eq!(proposal.category(), Some(&ProposalCategory::Grant));
+        let revision = proposal.revision_history().last().unwrap();
+        assert_eq!(revision.field_changed(), "category");
+        assert_eq!(revision.new_value(), &serde_json::json!("Grant"));
+    }
+
+    #[test]
+    fn test_proposal_ipfs_hash() {
+        let cid = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+        let mut proposal = create_test_proposal();
+        assert_eq!(proposal.ipfs_hash(), None);
+        assert!(proposal.set_ipfs_hash(Some("not-a-cid".to_string())).is_err());
+        proposal.set_ipfs_hash(Some(cid.to_string())).unwrap();
+        assert_eq!(proposal.ipfs_hash(), Some(cid));
+
+        // Proposals saved before document hashes existed load without one
+        let mut json = serde_json::to_value(&proposal).unwrap();
+        json.as_object_mut().unwrap().remove("ipfs_hash");
+        assert_eq!(serde_json::from_value::<Proposal>(json).unwrap().ipfs_hash(), None);
+
+        let new_cid = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+        let updates = UpdateProposalDetails {
+            title: None,
+            url: None,
+            budget_request_details: Some(BudgetRequestDetailsCommand {
+                team: None,
+                request_amounts: Some(HashMap::from([("ETH".to_string(), 10.0)])),
+                start_date: None,
+                end_date: None,
+                is_loan: None,
+                payment_address: None,
+                category: None,
+                ipfs_hash: Some(new_cid.to_string()),
+            }),
+            announced_at: None,
+            published_at: None,
+            resolved_at: None,
+            category: None,
+        };
+        proposal.update(updates, None).unwrap();
+        assert_eq!(proposal.ipfs_hash(), Some(new_cid));
+        assert!(proposal.revision_history().iter().any(|r| r.field_changed() == "ipfs_hash"));
+    }
+
     #[test]
     fn test_proposal_status_changes() {
         let mut proposal = create_test_proposal();
@@ -575,10 +1185,13 @@ mod tests {
                 end_date: Some(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap()),
                 is_loan: None,
                 payment_address: None,
+                category: None,
+                ipfs_hash: None,
             }),
             announced_at: Some(NaiveDate::from_ymd_opt(2023, 3, 15).unwrap()),
             published_at: Some(NaiveDate::from_ymd_opt(2023, 3, 20).unwrap()),
             resolved_at: Some(NaiveDate::from_ymd_opt(2023, 3, 25).unwrap()),
+            category: None,
         };
         
         proposal.update(updates, Some(Uuid::new_v4())).unwrap();
@@ -595,6 +1208,80 @@ mod tests {
         assert_eq!(budget_details.end_date(), Some(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap()));
     }
 
+    #[test]
+    fn test_proposal_update_records_revisions() {
+        let mut proposal = create_test_proposal();
+        assert!(proposal.revision_history().is_empty());
+
+        let updates = UpdateProposalDetails {
+            title: Some("Renamed".to_string()),
+            url: Some("http://example.com".to_string()),
+            budget_request_details: Some(BudgetRequestDetailsCommand {
+                team: None,
+                request_amounts: Some([("ETH".to_string(), 50.0)].iter().cloned().collect()),
+                start_date: None,
+                end_date: None,
+                is_loan: None,
+                payment_address: None,
+                category: None,
+                ipfs_hash: None,
+            }),
+            announced_at: None,
+            published_at: None,
+            resolved_at: None,
+            category: None,
+        };
+        proposal.update(updates, None).unwrap();
+
+        let history = proposal.revision_history();
+        let fields: Vec<&str> = history.iter().map(|r| r.field_changed()).collect();
+        assert!(fields.contains(&"title"));
+        assert!(fields.contains(&"request_amounts"));
+        assert!(fields.contains(&"is_loan"));
+        // Unchanged url is not recorded
+        assert!(!fields.contains(&"url"));
+
+        let title_revision = history.iter().find(|r| r.field_changed() == "title").unwrap();
+        assert_eq!(title_revision.old_value(), &serde_json::json!("Test Proposal"));
+        assert_eq!(title_revision.new_value(), &serde_json::json!("Renamed"));
+    }
+
+    #[test]
+    fn test_loan_terms() {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let due = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let mut details = BudgetRequestDetails::new(
+            None,
+            [("ETH".to_string(), 100.0)].iter().cloned().collect(),
+            Some(start),
+            Some(due),
+            Some(false),
+            None,
+        ).unwrap();
+
+        let terms = LoanTerms::new(0.1, due, RepaymentSchedule::Quarterly, HashMap::new()).unwrap();
+        assert!(details.set_loan_terms(Some(terms.clone())).is_err());
+
+        details.set_is_loan(true);
+        let early = LoanTerms::new(0.1, start, RepaymentSchedule::AtMaturity, HashMap::new()).unwrap();
+        assert!(details.set_loan_terms(Some(early)).is_err());
+        details.set_loan_terms(Some(terms.clone())).unwrap();
+        assert_eq!(details.loan_terms(), Some(&terms));
+
+        assert!((terms.total_due(100.0) - 110.0).abs() < 1e-9);
+        assert_eq!(terms.expected_repaid_fraction(start, NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()), 0.0);
+        assert_eq!(terms.expected_repaid_fraction(start, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()), 0.5);
+        assert_eq!(terms.expected_repaid_fraction(start, due), 1.0);
+
+        let bullet = LoanTerms::new(0.0, due, RepaymentSchedule::AtMaturity, HashMap::new()).unwrap();
+        assert_eq!(bullet.expected_repaid_fraction(start, NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()), 0.0);
+        assert_eq!(bullet.expected_repaid_fraction(start, due), 1.0);
+
+        assert!(LoanTerms::new(-0.1, due, RepaymentSchedule::Monthly, HashMap::new()).is_err());
+        assert_eq!("monthly".parse::<RepaymentSchedule>(), Ok(RepaymentSchedule::Monthly));
+        assert!("weekly".parse::<RepaymentSchedule>().is_err());
+    }
+
     #[test]
     fn test_proposal_duration() {
         let mut proposal = create_test_proposal();
@@ -682,6 +1369,22 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_budget_request_details_address_validation() {
+        let details = |address: &str| BudgetRequestDetails::new(
+            None,
+            [("ETH".to_string(), 100.0)].into_iter().collect(),
+            None,
+            None,
+            None,
+            Some(address.to_string()),
+        );
+
+        assert!(details("0x742D35CC6634C0532925A3B844BC454E4438F44E").is_ok());
+        assert_eq!(details("0x742d35Cc6634C0532925a3b844Bc454e4438f44e1").unwrap_err(), "Ethereum address must be 42 characters long");
+        assert_eq!(details("0x0000000000000000000000000000000000000000").unwrap_err(), "Ethereum address cannot be the zero address");
+    }
+
     #[test]
     fn test_record_payment() {
         let mut amounts = HashMap::new();
@@ -732,6 +1435,82 @@ mod tests {
         assert!(!details.is_paid());
     }
 
+    #[test]
+    fn test_record_repayment() {
+        let paid_on = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let tx = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e";
+        let mut grant = BudgetRequestDetails::new(None, HashMap::from([("ETH".to_string(), 10.0)]), None, None, Some(false), None).unwrap();
+        grant.record_payment(tx.to_string(), paid_on).unwrap();
+        assert_eq!(grant.record_repayment(paid_on).unwrap_err(), "Only loan requests can be repaid");
+
+        let mut loan = BudgetRequestDetails::new(None, HashMap::from([("ETH".to_string(), 10.0)]), None, None, Some(true), None).unwrap();
+        assert_eq!(loan.record_repayment(paid_on).unwrap_err(), "Loan has not been paid out yet");
+
+        loan.record_payment(tx.to_string(), paid_on).unwrap();
+        assert_eq!(loan.record_repayment(paid_on.pred_opt().unwrap()).unwrap_err(), "Repayment date cannot be before the payment date");
+        assert!(!loan.is_repaid());
+
+        loan.record_repayment(paid_on).unwrap();
+        assert_eq!(loan.repaid_at(), Some(paid_on));
+        assert_eq!(loan.record_repayment(paid_on).unwrap_err(), "Loan has already been repaid");
+
+        loan.clear_payment();
+        assert!(!loan.is_repaid());
+    }
+
+    #[test]
+    fn test_team_allocations() {
+        let team_a = Uuid::new_v4();
+        let team_b = Uuid::new_v4();
+        let tx = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e4438f44e4438f44e4438f44e";
+        let paid_on = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        // Single-team requests read as a one-entry allocation
+        let mut details = BudgetRequestDetails::new(Some(team_a), HashMap::from([("ETH".to_string(), 10.0)]), None, None, None, None).unwrap();
+        assert!(!details.has_team_allocations());
+        assert_eq!(details.team_allocations(), HashMap::from([(team_a, HashMap::from([("ETH".to_string(), 10.0)]))]));
+        assert!(details.record_allocation_payment(team_a, tx.to_string(), paid_on).is_err());
+
+        assert!(details.set_team_allocations(HashMap::new()).is_err());
+        assert!(details.set_team_allocations(HashMap::from([(team_a, HashMap::from([("ETH".to_string(), -1.0)]))])).is_err());
+        details.set_team_allocations(HashMap::from([
+            (team_a, HashMap::from([("ETH".to_string(), 6.0), ("USD".to_string(), 100.0)])),
+            (team_b, HashMap::from([("ETH".to_string(), 4.0)])),
+        ])).unwrap();
+        assert_eq!(details.request_amounts(), &HashMap::from([("ETH".to_string(), 10.0), ("USD".to_string(), 100.0)]));
+        assert_eq!(details.team_allocations().len(), 2);
+
+        details.record_allocation_payment(team_a, tx.to_string(), paid_on).unwrap();
+        assert_eq!(details.team_payment(team_a).map(|(_, date)| date), Some(paid_on));
+        assert_eq!(details.team_payment(team_b), None);
+        assert!(!details.is_paid());
+        assert!(details.record_allocation_payment(team_a, tx.to_string(), paid_on).is_err());
+        assert!(details.record_allocation_payment(Uuid::new_v4(), tx.to_string(), paid_on).is_err());
+        assert!(details.set_team_allocations(HashMap::from([(team_a, HashMap::from([("ETH".to_string(), 1.0)]))])).is_err());
+
+        let last = paid_on.succ_opt().unwrap();
+        details.record_allocation_payment(team_b, tx.to_string(), last).unwrap();
+        assert!(details.is_paid());
+        assert_eq!(details.payment_date(), Some(last));
+
+        details.clear_payment();
+        assert_eq!(details.team_payment(team_a), None);
+    }
+
+    #[test]
+    fn test_team_allocations_absent_from_old_state() {
+        let team = Uuid::new_v4();
+        let json = format!(
+            r#"{{"team":"{}","request_amounts":{{"ETH":5.0}},"start_date":null,"end_date":null,"is_loan":false,"payment_address":null,"payment_tx":null,"payment_date":null}}"#,
+            team
+        );
+        let details: BudgetRequestDetails = serde_json::from_str(&json).unwrap();
+        assert_eq!(details.team_allocations(), HashMap::from([(team, HashMap::from([("ETH".to_string(), 5.0)]))]));
+
+        let serialized = serde_json::to_string(&details).unwrap();
+        assert!(!serialized.contains("team_allocations"));
+    }
+
     #[test]
     fn test_clear_payment() {
         let mut amounts = HashMap::new();
@@ -824,4 +1603,28 @@ mod tests {
         details.set_is_loan(false);
         assert!(!details.is_loan());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_reviewer_sign_off() {
+        let mut proposal = create_test_proposal();
+        assert!(proposal.all_reviewers_signed_off());
+
+        proposal.set_reviewers(vec!["Alice".to_string(), " Bob ".to_string()]).unwrap();
+        assert_eq!(proposal.pending_reviewers(), vec!["Alice", "Bob"]);
+        assert!(!proposal.all_reviewers_signed_off());
+
+        let date = NaiveDate::from_ymd_opt(2023, 1, 10).unwrap();
+        proposal.record_reviewer_sign_off("Alice", date).unwrap();
+        assert!(proposal.record_reviewer_sign_off("Alice", date).is_err());
+        assert!(proposal.record_reviewer_sign_off("Carol", date).is_err());
+        assert_eq!(proposal.pending_reviewers(), vec!["Bob"]);
+
+        // Retained reviewers keep their sign-off
+        proposal.set_reviewers(vec!["Alice".to_string(), "Carol".to_string()]).unwrap();
+        assert_eq!(proposal.reviewers()[0].signed_off_at(), Some(date));
+        assert_eq!(proposal.pending_reviewers(), vec!["Carol"]);
+
+        assert!(proposal.set_reviewers(vec!["Dan".to_string(), "Dan".to_string()]).is_err());
+        assert!(proposal.set_reviewers(vec!["".to_string()]).is_err());
+    }
+}
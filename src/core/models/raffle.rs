@@ -13,6 +13,11 @@ pub struct Raffle {
     team_snapshots: Vec<TeamSnapshot>,
     tickets: Vec<RaffleTicket>,
     result: Option<RaffleResult>,
+    #[serde(default)]
+    manual_overrides: Vec<RaffleSeatOverride>,
+    /// Earlier draws for the same proposal that this raffle replaced, oldest first
+    #[serde(default)]
+    superseded_raffles: Vec<SupersededRaffle>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -28,6 +33,9 @@ pub struct RaffleConfig {
     custom_allocation: Option<HashMap<Uuid, u64>>,
     custom_team_order: Option<Vec<Uuid>>,
     is_historical: bool,
+    /// Latest months of earner revenue counted for tickets; `None` uses all of them
+    #[serde(default)]
+    revenue_window_months: Option<usize>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -59,6 +67,32 @@ pub enum RaffleParticipationStatus {
     Excluded,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SeatOutcome {
+    Counted,
+    Uncounted,
+    Excluded,
+}
+
+/// Audit record of a manual change to a raffle result.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RaffleSeatOverride {
+    team_id: Uuid,
+    previous_outcome: SeatOutcome,
+    new_outcome: SeatOutcome,
+    reason: String,
+    timestamp: DateTime<Utc>,
+}
+
+/// Audit record of a raffle that was re-run and replaced.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SupersededRaffle {
+    raffle_id: Uuid,
+    randomness_block: u64,
+    block_randomness: String,
+    replaced_at: DateTime<Utc>,
+}
+
 impl Raffle {
     pub fn new(config: RaffleConfig, teams: &HashMap<Uuid, Team>) -> Result<Self, &'static str> {
 
@@ -97,14 +131,8 @@ impl Raffle {
             team_snapshots.push(snapshot);
 
             let ticket_count = match team.status() {
-                TeamStatus::Earner { trailing_monthly_revenue } => {
-                    let sum: u64 = trailing_monthly_revenue.iter().sum();
-                    let quarterly_average = sum as f64 / trailing_monthly_revenue.len() as f64;
-                    let scaled_average = quarterly_average / 1000.0;
-                    (scaled_average.sqrt().floor() as u64).max(1)
-                },
-                TeamStatus::Supporter => 1,
-                TeamStatus::Inactive => continue,
+                TeamStatus::Inactive | TeamStatus::Archived => continue,
+                status => Self::ticket_count_in_window(status, config.revenue_window_months()),
             };
 
             for _ in 0..ticket_count {
@@ -118,6 +146,8 @@ impl Raffle {
             team_snapshots,
             tickets,
             result: None,
+            manual_overrides: Vec::new(),
+            superseded_raffles: Vec::new(),
         })
     }
 
@@ -146,6 +176,14 @@ impl Raffle {
         self.result.as_ref()
     }
 
+    pub fn manual_overrides(&self) -> &[RaffleSeatOverride] {
+        &self.manual_overrides
+    }
+
+    pub fn superseded_raffles(&self) -> &[SupersededRaffle] {
+        &self.superseded_raffles
+    }
+
     pub fn deciding_teams(&self) -> Vec<Uuid> {
         self.result.as_ref()
             .map(|result| result.counted.clone())
@@ -167,18 +205,21 @@ impl Raffle {
         Ok(())
     }
 
+    /// Seats teams by their tickets' scores, highest first. Tickets with equal
+    /// scores are ordered by team UUID, then by ticket index, so the outcome never
+    /// depends on iteration order.
     pub fn select_deciding_teams(&mut self) {
         let mut earner_tickets: Vec<_> = self.tickets.iter()
             .filter(|t| !self.config.excluded_teams.contains(&t.team_id))
             .filter(|t| self.team_snapshots.iter().any(|s| s.id == t.team_id && matches!(s.status, TeamStatus::Earner { .. })))
             .collect();
-        earner_tickets.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        earner_tickets.sort_by(|a, b| Self::ticket_rank(a, b));
 
         let mut supporter_tickets: Vec<_> = self.tickets.iter()
             .filter(|t| !self.config.excluded_teams.contains(&t.team_id))
             .filter(|t| self.team_snapshots.iter().any(|s| s.id == t.team_id && matches!(s.status, TeamStatus::Supporter)))
             .collect();
-        supporter_tickets.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        supporter_tickets.sort_by(|a, b| Self::ticket_rank(a, b));
 
         let mut counted = Vec::new();
         let mut uncounted = Vec::new();
@@ -208,8 +249,103 @@ impl Raffle {
         self.result = Some(RaffleResult { counted, uncounted });
     }
 
+    /// Draw order of two tickets: higher score first, ties broken by team UUID
+    /// and then ticket index.
+    fn ticket_rank(a: &RaffleTicket, b: &RaffleTicket) -> std::cmp::Ordering {
+        b.score.total_cmp(&a.score)
+            .then_with(|| a.team_id.cmp(&b.team_id))
+            .then_with(|| a.index.cmp(&b.index))
+    }
+
+    /// Highest score among a team's tickets, or 0.0 if it has none.
+    pub fn best_score(&self, team_id: Uuid) -> f64 {
+        self.tickets.iter()
+            .filter(|t| t.team_id == team_id)
+            .map(|t| t.score)
+            .max_by(|a, b| a.total_cmp(b))
+            .unwrap_or(0.0)
+    }
+
+    /// Number of raffle tickets a team receives for its status; inactive and archived teams get none.
+    pub fn ticket_count(status: &TeamStatus) -> u64 {
+        Self::ticket_count_in_window(status, None)
+    }
+
+    /// Like `ticket_count`, but an earner's average covers only the last
+    /// `window_months` entries of its revenue, or all of them when it has fewer.
+    pub fn ticket_count_in_window(status: &TeamStatus, window_months: Option<usize>) -> u64 {
+        match status {
+            TeamStatus::Earner { trailing_monthly_revenue } => {
+                let months = trailing_monthly_revenue.len();
+                let window = window_months.unwrap_or(months).max(1).min(months);
+                let recent = &trailing_monthly_revenue[months - window..];
+                let sum: u64 = recent.iter().sum();
+                let quarterly_average = sum as f64 / recent.len() as f64;
+                let scaled_average = quarterly_average / 1000.0;
+                (scaled_average.sqrt().floor() as u64).max(1)
+            },
+            TeamStatus::Supporter => 1,
+            TeamStatus::Inactive | TeamStatus::Archived => 0,
+        }
+    }
+
+    /// Probability of each team landing one of `seats` places when teams are ranked
+    /// by their best ticket score, as in `select_deciding_teams`.
+    ///
+    /// A team's best score over `k` uniform tickets is distributed like `U^(1/k)`,
+    /// so the ranking is a weighted draw without replacement. Team `i` is seated
+    /// when fewer than `seats` other teams beat its score `x`; that count is
+    /// Poisson-binomial with `P(j beats x) = 1 - x^k_j`, integrated over `x`.
+    pub fn counted_seat_probabilities(ticket_counts: &[u64], seats: usize) -> Vec<f64> {
+        if seats >= ticket_counts.len() {
+            return vec![1.0; ticket_counts.len()];
+        }
+        if seats == 0 {
+            return vec![0.0; ticket_counts.len()];
+        }
+
+        const STEPS: usize = 2000;
+        ticket_counts.iter().enumerate()
+            .map(|(i, &own)| {
+                let mut probability = 0.0;
+                for step in 0..STEPS {
+                    let x = (step as f64 + 0.5) / STEPS as f64;
+                    // dist[c] = P(exactly c other teams beat x), tracked up to seats - 1
+                    let mut dist = vec![0.0; seats];
+                    dist[0] = 1.0;
+                    for (j, &other) in ticket_counts.iter().enumerate() {
+                        if j == i {
+                            continue;
+                        }
+                        let beats = 1.0 - x.powi(other as i32);
+                        for c in (0..seats).rev() {
+                            let carried = if c > 0 { dist[c - 1] * beats } else { 0.0 };
+                            dist[c] = dist[c] * (1.0 - beats) + carried;
+                        }
+                    }
+                    let density = own as f64 * x.powi(own as i32 - 1);
+                    probability += density * dist.iter().sum::<f64>() / STEPS as f64;
+                }
+                probability.min(1.0)
+            })
+            .collect()
+    }
+
+    /// Tickets in draw order, as ranked by `select_deciding_teams`.
+    pub fn ranked_tickets(&self) -> Vec<&RaffleTicket> {
+        let mut ranked: Vec<&RaffleTicket> = self.tickets.iter().collect();
+        ranked.sort_by(|a, b| Self::ticket_rank(a, b));
+        ranked
+    }
+
+    /// String hashed to produce a ticket's score: the block randomness and the
+    /// ticket index joined by an underscore.
+    pub fn ticket_seed(randomness: &str, index: u64) -> String {
+        format!("{}_{}", randomness, index)
+    }
+
     fn generate_random_score_from_seed(randomness: &str, index: u64) -> f64 {
-        let combined_seed = format!("{}_{}", randomness, index);
+        let combined_seed = Self::ticket_seed(randomness, index);
         let mut hasher = Sha256::new();
 
         hasher.update(combined_seed.as_bytes());
@@ -220,11 +356,46 @@ impl Raffle {
         hash_num as f64 / max_num
     }
 
+    /// Re-runs the draw on a fresh raffle built from the recorded team snapshots
+    /// and configuration, ignoring the stored tickets and result.
+    pub fn recompute_result(&self) -> Result<RaffleResult, &'static str> {
+        let mut tickets = Vec::new();
+        for snapshot in &self.team_snapshots {
+            for _ in 0..Self::ticket_count_in_window(&snapshot.status, self.config.revenue_window_months()) {
+                tickets.push(RaffleTicket::new(snapshot.id, tickets.len() as u64));
+            }
+        }
+
+        let mut replay = Raffle {
+            id: self.id,
+            config: self.config.clone(),
+            team_snapshots: self.team_snapshots.clone(),
+            tickets,
+            result: None,
+            manual_overrides: Vec::new(),
+            superseded_raffles: Vec::new(),
+        };
+        replay.generate_ticket_scores()?;
+        replay.select_deciding_teams();
+        replay.result.ok_or("Raffle replay produced no result")
+    }
+
     // Setter methods
     pub fn set_result(&mut self, result: RaffleResult) {
         self.result = Some(result);
     }
 
+    /// Records that this raffle replaces `previous`, carrying over anything `previous` replaced.
+    pub fn supersede(&mut self, previous: &Raffle) {
+        self.superseded_raffles.extend(previous.superseded_raffles.iter().cloned());
+        self.superseded_raffles.push(SupersededRaffle {
+            raffle_id: previous.id,
+            randomness_block: previous.config.randomness_block,
+            block_randomness: previous.config.block_randomness.clone(),
+            replaced_at: Utc::now(),
+        });
+    }
+
     // Helper methods
     pub fn is_historical(&self) -> bool {
         self.config.is_historical
@@ -233,6 +404,74 @@ impl Raffle {
     pub fn is_completed(&self) -> bool {
         self.result.is_some()
     }
+
+    pub fn is_manually_adjusted(&self) -> bool {
+        !self.manual_overrides.is_empty()
+    }
+
+    pub fn seat_outcome(&self, team_id: Uuid) -> Option<SeatOutcome> {
+        let result = self.result.as_ref()?;
+        if self.config.excluded_teams.contains(&team_id) {
+            Some(SeatOutcome::Excluded)
+        } else if result.counted.contains(&team_id) {
+            Some(SeatOutcome::Counted)
+        } else if result.uncounted.contains(&team_id) {
+            Some(SeatOutcome::Uncounted)
+        } else {
+            None
+        }
+    }
+
+    /// Moves a team to a different seat in the stored result and logs the change.
+    /// Excluded teams are listed as uncounted, as they are after a regular draw.
+    pub fn override_seat(&mut self, team_id: Uuid, new_outcome: SeatOutcome, reason: String) -> Result<(), &'static str> {
+        if reason.trim().is_empty() {
+            return Err("A reason is required to override a raffle seat");
+        }
+        let snapshot = self.team_snapshots.iter()
+            .find(|s| s.id == team_id)
+            .ok_or("Team did not take part in this raffle")?;
+        let is_earner = matches!(snapshot.status, TeamStatus::Earner { .. });
+        let previous_outcome = self.seat_outcome(team_id)
+            .ok_or("Raffle has no result to adjust")?;
+        if previous_outcome == new_outcome {
+            return Err("Team already has this raffle outcome");
+        }
+
+        let result = self.result.as_mut().ok_or("Raffle has no result to adjust")?;
+        if new_outcome == SeatOutcome::Counted {
+            if result.counted.len() >= self.config.total_counted_seats {
+                return Err("All counted seats are taken; move a team out first");
+            }
+            let counted_earners = result.counted.iter()
+                .filter(|id| self.team_snapshots.iter().any(|s| s.id == **id && matches!(s.status, TeamStatus::Earner { .. })))
+                .count();
+            if is_earner && counted_earners >= self.config.max_earner_seats {
+                return Err("All earner seats are taken; move an earner out first");
+            }
+        }
+
+        result.counted.retain(|id| *id != team_id);
+        result.uncounted.retain(|id| *id != team_id);
+        self.config.excluded_teams.retain(|id| *id != team_id);
+        match new_outcome {
+            SeatOutcome::Counted => result.counted.push(team_id),
+            SeatOutcome::Uncounted => result.uncounted.push(team_id),
+            SeatOutcome::Excluded => {
+                result.uncounted.push(team_id);
+                self.config.excluded_teams.push(team_id);
+            },
+        }
+
+        self.manual_overrides.push(RaffleSeatOverride {
+            team_id,
+            previous_outcome,
+            new_outcome,
+            reason,
+            timestamp: Utc::now(),
+        });
+        Ok(())
+    }
 }
 
 impl RaffleConfig {
@@ -261,6 +500,7 @@ impl RaffleConfig {
             custom_allocation,
             custom_team_order,
             is_historical,
+            revenue_window_months: None,
         }
     }
 
@@ -276,6 +516,7 @@ impl RaffleConfig {
     pub fn custom_allocation(&self) -> Option<&HashMap<Uuid, u64>> { self.custom_allocation.as_ref() }
     pub fn custom_team_order(&self) -> Option<&[Uuid]> { self.custom_team_order.as_deref() }
     pub fn is_historical(&self) -> bool { self.is_historical }
+    pub fn revenue_window_months(&self) -> Option<usize> { self.revenue_window_months }
 
     // Setter methods
     pub fn set_initiation_block(&mut self, block: u64) { self.initiation_block = block; }
@@ -284,6 +525,7 @@ impl RaffleConfig {
     pub fn set_excluded_teams(&mut self, teams: Vec<Uuid>) { self.excluded_teams = teams; }
     pub fn set_custom_allocation(&mut self, allocation: Option<HashMap<Uuid, u64>>) { self.custom_allocation = allocation; }
     pub fn set_custom_team_order(&mut self, order: Option<Vec<Uuid>>) { self.custom_team_order = order; }
+    pub fn set_revenue_window_months(&mut self, months: Option<usize>) { self.revenue_window_months = months; }
 }
 
 impl RaffleTicket {
@@ -342,7 +584,37 @@ impl RaffleResult {
     pub fn counted(&self) -> &[Uuid] { &self.counted }
     pub fn uncounted(&self) -> &[Uuid] { &self.uncounted }
 
-    // No setter methods as the result should not be modified after creation
+    // No setter methods: the result is only changed through Raffle::override_seat
+}
+
+impl std::str::FromStr for SeatOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "counted" => Ok(SeatOutcome::Counted),
+            "uncounted" => Ok(SeatOutcome::Uncounted),
+            "excluded" => Ok(SeatOutcome::Excluded),
+            _ => Err(format!("Invalid seat outcome: {}. Use counted, uncounted or excluded", s)),
+        }
+    }
+}
+
+impl RaffleSeatOverride {
+    // Getter methods
+    pub fn team_id(&self) -> Uuid { self.team_id }
+    pub fn previous_outcome(&self) -> SeatOutcome { self.previous_outcome }
+    pub fn new_outcome(&self) -> SeatOutcome { self.new_outcome }
+    pub fn reason(&self) -> &str { &self.reason }
+    pub fn timestamp(&self) -> DateTime<Utc> { self.timestamp }
+}
+
+impl SupersededRaffle {
+    // Getter methods
+    pub fn raffle_id(&self) -> Uuid { self.raffle_id }
+    pub fn randomness_block(&self) -> u64 { self.randomness_block }
+    pub fn block_randomness(&self) -> &str { &self.block_randomness }
+    pub fn replaced_at(&self) -> DateTime<Utc> { self.replaced_at }
 }
 
 #[cfg(test)]
@@ -510,6 +782,158 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_raffle_skips_archived_teams() {
+        let mut teams = create_mock_teams();
+        let mut archived = create_mock_team("Archived", TeamStatus::Supporter);
+        archived.archive().unwrap();
+        let archived_id = archived.id();
+        teams.insert(archived_id, archived);
+
+        let raffle = Raffle::new(create_test_config(), &teams).unwrap();
+
+        assert_eq!(raffle.team_snapshots.len(), 9);
+        assert!(raffle.team_snapshots.iter().all(|s| s.id() != archived_id));
+        assert!(raffle.tickets.iter().all(|t| t.team_id() != archived_id));
+        assert_eq!(Raffle::ticket_count(&TeamStatus::Archived), 0);
+    }
+
+    #[test]
+    fn test_counted_seat_probabilities() {
+        // Equal weights share the seats evenly
+        let probabilities = Raffle::counted_seat_probabilities(&[1, 1, 1, 1], 2);
+        for p in &probabilities {
+            assert!((p - 0.5).abs() < 1e-3);
+        }
+
+        // Two teams, one seat: P(A wins) = a / (a + b)
+        let probabilities = Raffle::counted_seat_probabilities(&[3, 1], 1);
+        assert!((probabilities[0] - 0.75).abs() < 1e-3);
+        assert!((probabilities[1] - 0.25).abs() < 1e-3);
+
+        // Probabilities add up to the number of seats
+        let probabilities = Raffle::counted_seat_probabilities(&[5, 3, 2, 1, 1], 3);
+        assert!((probabilities.iter().sum::<f64>() - 3.0).abs() < 1e-3);
+        assert!(probabilities[0] > probabilities[1] && probabilities[1] > probabilities[3]);
+
+        assert_eq!(Raffle::counted_seat_probabilities(&[2, 1], 5), vec![1.0, 1.0]);
+        assert_eq!(Raffle::counted_seat_probabilities(&[2, 1], 0), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_override_seat() {
+        let mut raffle = create_test_raffle();
+        let team_id = raffle.team_snapshots[0].id;
+        assert!(raffle.override_seat(team_id, SeatOutcome::Uncounted, "No result".to_string()).is_err());
+
+        raffle.generate_ticket_scores().unwrap();
+        raffle.select_deciding_teams();
+        let counted_id = raffle.result().unwrap().counted()[0];
+        let uncounted_id = raffle.result().unwrap().uncounted()[0];
+
+        // All counted seats are taken
+        assert!(raffle.override_seat(uncounted_id, SeatOutcome::Counted, "Swap".to_string()).is_err());
+        assert!(raffle.override_seat(counted_id, SeatOutcome::Uncounted, " ".to_string()).is_err());
+        assert!(raffle.override_seat(counted_id, SeatOutcome::Counted, "Same".to_string()).is_err());
+        assert!(!raffle.is_manually_adjusted());
+
+        raffle.override_seat(counted_id, SeatOutcome::Excluded, "Conflict of interest".to_string()).unwrap();
+        assert_eq!(raffle.seat_outcome(counted_id), Some(SeatOutcome::Excluded));
+        assert!(raffle.result().unwrap().uncounted().contains(&counted_id));
+        assert!(raffle.config().excluded_teams().contains(&counted_id));
+
+        raffle.override_seat(uncounted_id, SeatOutcome::Counted, "Fills the freed seat".to_string()).unwrap();
+        assert_eq!(raffle.seat_outcome(uncounted_id), Some(SeatOutcome::Counted));
+        assert_eq!(raffle.result().unwrap().counted().len(), 7);
+
+        assert!(raffle.is_manually_adjusted());
+        let overrides = raffle.manual_overrides();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].team_id(), counted_id);
+        assert_eq!(overrides[0].previous_outcome(), SeatOutcome::Counted);
+        assert_eq!(overrides[0].new_outcome(), SeatOutcome::Excluded);
+        assert_eq!(overrides[0].reason(), "Conflict of interest");
+    }
+
+    #[test]
+    fn test_ticket_count_revenue_window() {
+        let status = TeamStatus::Earner { trailing_monthly_revenue: vec![1000, 4000, 16000] };
+        assert_eq!(Raffle::ticket_count(&status), 2); // average 7000
+        assert_eq!(Raffle::ticket_count_in_window(&status, Some(1)), 4); // latest month 16000
+        assert_eq!(Raffle::ticket_count_in_window(&status, Some(2)), 3); // average 10000
+        // A window longer than the revenue history uses every month
+        assert_eq!(Raffle::ticket_count_in_window(&status, Some(6)), 2);
+
+        let new_team = TeamStatus::Earner { trailing_monthly_revenue: vec![9000] };
+        assert_eq!(Raffle::ticket_count_in_window(&new_team, Some(3)), 3);
+        assert_eq!(Raffle::ticket_count_in_window(&TeamStatus::Supporter, Some(1)), 1);
+    }
+
+    #[test]
+    fn test_raffle_uses_configured_revenue_window() {
+        let team = Team::new("Growing".to_string(), "Rep".to_string(), Some(vec![1000, 4000, 16000]), None).unwrap();
+        let teams = HashMap::from([(team.id(), team)]);
+
+        let raffle = Raffle::new(create_test_config(), &teams).unwrap();
+        assert_eq!(raffle.tickets().len(), 2);
+
+        let mut config = create_test_config();
+        config.set_revenue_window_months(Some(1));
+        let mut raffle = Raffle::new(config, &teams).unwrap();
+        assert_eq!(raffle.tickets().len(), 4);
+
+        raffle.generate_ticket_scores().unwrap();
+        raffle.select_deciding_teams();
+        let replayed = raffle.recompute_result().unwrap();
+        assert_eq!(replayed.counted(), raffle.result().unwrap().counted());
+    }
+
+    #[test]
+    fn test_equal_scores_break_ties_by_team_id() {
+        let mut raffle = create_test_raffle();
+        for ticket in &mut raffle.tickets {
+            ticket.set_score(0.5);
+        }
+        raffle.select_deciding_teams();
+        let first = raffle.result().unwrap().clone();
+
+        let mut supporters: Vec<Uuid> = raffle.team_snapshots.iter()
+            .filter(|s| matches!(s.status, TeamStatus::Supporter))
+            .map(|s| s.id)
+            .collect();
+        supporters.sort();
+        let counted_supporters: Vec<Uuid> = first.counted().iter()
+            .filter(|id| supporters.contains(id))
+            .copied()
+            .collect();
+        assert_eq!(counted_supporters, supporters[..2].to_vec());
+
+        // Iteration order of the tickets does not change the seats
+        raffle.tickets.reverse();
+        raffle.select_deciding_teams();
+        assert_eq!(raffle.result().unwrap().counted(), first.counted());
+    }
+
+    #[test]
+    fn test_recompute_result_ignores_stored_result() {
+        let mut raffle = create_test_raffle();
+        raffle.generate_ticket_scores().unwrap();
+        raffle.select_deciding_teams();
+        let original = raffle.result().unwrap().clone();
+
+        let recomputed = raffle.recompute_result().unwrap();
+        assert_eq!(recomputed.counted(), original.counted());
+        assert_eq!(recomputed.uncounted(), original.uncounted());
+
+        let mut swapped_counted = original.counted().to_vec();
+        let mut swapped_uncounted = original.uncounted().to_vec();
+        std::mem::swap(&mut swapped_counted[0], &mut swapped_uncounted[0]);
+        raffle.set_result(RaffleResult::new(swapped_counted, swapped_uncounted));
+
+        let recomputed = raffle.recompute_result().unwrap();
+        assert_eq!(recomputed.counted(), original.counted());
+    }
+
     // Helper function to create a test raffle
     fn create_test_raffle() -> Raffle {
         let teams = create_mock_teams();
@@ -531,6 +955,103 @@ mod tests {
             custom_allocation: None,
             custom_team_order: None,
             is_historical: false,
+            revenue_window_months: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn build_teams(earner_revenues: &[Vec<u64>], supporters: usize) -> HashMap<Uuid, Team> {
+        let mut teams = HashMap::new();
+        for (i, revenue) in earner_revenues.iter().enumerate() {
+            let team = Team::new(format!("Earner{}", i), "Rep".to_string(), Some(revenue.clone()), None).unwrap();
+            teams.insert(team.id(), team);
+        }
+        for i in 0..supporters {
+            let team = Team::new(format!("Supporter{}", i), "Rep".to_string(), None, None).unwrap();
+            teams.insert(team.id(), team);
+        }
+        teams
+    }
+
+    fn config_with_randomness(block_randomness: String) -> RaffleConfig {
+        RaffleConfig::new(Uuid::new_v4(), Uuid::new_v4(), 7, 5, Some(100), Some(110), Some(block_randomness), None, None, None, false)
+    }
+
+    fn tickets_for(raffle: &Raffle, team_id: Uuid) -> u64 {
+        raffle.tickets().iter().filter(|t| t.team_id() == team_id).count() as u64
+    }
+
+    proptest! {
+        // Earner tickets follow the square root of average revenue (in thousands), so
+        // they are not linearly proportional to revenue share; check the formula and
+        // that more revenue never yields fewer tickets instead.
+        #[test]
+        fn earner_tickets_follow_revenue(
+            earner_revenues in prop::collection::vec(prop::collection::vec(0u64..=1_000_000, 1..=3), 1..10),
+            supporters in 1usize..=20,
+        ) {
+            let teams = build_teams(&earner_revenues, supporters);
+            let raffle = Raffle::new(config_with_randomness("seed".to_string()), &teams).unwrap();
+
+            let mut earners: Vec<(f64, u64)> = Vec::new();
+            for team in teams.values() {
+                if let TeamStatus::Earner { trailing_monthly_revenue } = team.status() {
+                    let average = trailing_monthly_revenue.iter().sum::<u64>() as f64 / trailing_monthly_revenue.len() as f64;
+                    let expected = ((average / 1000.0).sqrt().floor() as u64).max(1);
+                    let actual = tickets_for(&raffle, team.id());
+                    prop_assert_eq!(actual, expected);
+                    earners.push((average, actual));
+                }
+            }
+
+            earners.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            for pair in earners.windows(2) {
+                prop_assert!(pair[0].1 <= pair[1].1);
+            }
+        }
+
+        #[test]
+        fn every_active_team_gets_a_ticket(
+            earner_revenues in prop::collection::vec(prop::collection::vec(0u64..=1_000_000, 1..=3), 0..10),
+            supporters in 1usize..=20,
+        ) {
+            let teams = build_teams(&earner_revenues, supporters);
+            let raffle = Raffle::new(config_with_randomness("seed".to_string()), &teams).unwrap();
+
+            for team in teams.values() {
+                prop_assert!(tickets_for(&raffle, team.id()) >= 1);
+            }
+        }
+
+        #[test]
+        fn total_tickets_match_team_counts(
+            earner_revenues in prop::collection::vec(prop::collection::vec(0u64..=1_000_000, 1..=3), 0..10),
+            supporters in 1usize..=20,
+        ) {
+            let teams = build_teams(&earner_revenues, supporters);
+            let raffle = Raffle::new(config_with_randomness("seed".to_string()), &teams).unwrap();
+
+            let expected: u64 = teams.values().map(|t| Raffle::ticket_count(t.status())).sum();
+            prop_assert_eq!(raffle.tickets().len() as u64, expected);
+            for (i, ticket) in raffle.tickets().iter().enumerate() {
+                prop_assert_eq!(ticket.index(), i as u64);
+            }
+        }
+
+        #[test]
+        fn ticket_scores_stay_in_unit_range(block_randomness in ".*") {
+            let teams = build_teams(&[vec![50_000, 80_000, 120_000]], 3);
+            let mut raffle = Raffle::new(config_with_randomness(block_randomness), &teams).unwrap();
+            raffle.generate_ticket_scores().unwrap();
+
+            for ticket in raffle.tickets() {
+                prop_assert!((0.0..=1.0).contains(&ticket.score()));
+            }
         }
     }
-}
\ No newline at end of file
+}
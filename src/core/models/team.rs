@@ -2,13 +2,16 @@ use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 use super::common::{NameMatches, address_serde};
 use ethers::types::Address;
-use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use crate::services::ethereum::parse_ethereum_address;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TeamStatus {
     Earner { trailing_monthly_revenue: Vec<u64>},
     Supporter,
     Inactive,
+    /// Kept for historical reports but excluded from raffles and votes
+    Archived,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -19,6 +22,27 @@ pub struct Team {
     status: TeamStatus,
     #[serde(with = "address_serde", default)]
     payment_address: Option<Address>,
+    #[serde(default)]
+    region: Option<String>,
+    #[serde(default)]
+    address_history: Vec<AddressChange>,
+    /// Telegram handles used to reach the team, stored without the leading '@'
+    #[serde(default)]
+    contacts: Vec<String>,
+    /// Names of teams merged into this one, which still resolve to it
+    #[serde(default)]
+    aliases: Vec<String>,
+    /// Status to restore when an archived team is unarchived
+    #[serde(default)]
+    status_before_archive: Option<TeamStatus>,
+}
+
+/// A payment address that took effect at `changed_at`. `None` means the address was cleared.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AddressChange {
+    #[serde(with = "address_serde", default)]
+    address: Option<Address>,
+    changed_at: DateTime<Utc>,
 }
 
 impl Team {
@@ -33,8 +57,7 @@ impl Team {
 
         let payment_address = match address {
             Some(addr) => Some(
-                Address::from_str(&addr)
-                    .map_err(|_| "Invalid Ethereum address")?
+                parse_ethereum_address(&addr)?
             ),
             None => None,
         };
@@ -52,12 +75,21 @@ impl Team {
             None => TeamStatus::Supporter,
         };
 
+        let address_history = payment_address
+            .map(|address| vec![AddressChange { address: Some(address), changed_at: Utc::now() }])
+            .unwrap_or_default();
+
         Ok(Team {
             id: Uuid::new_v4(),
             name,
             representative,
             status,
             payment_address,
+            region: None,
+            address_history,
+            contacts: Vec::new(),
+            aliases: Vec::new(),
+            status_before_archive: None,
         })
     }
 
@@ -82,9 +114,30 @@ impl Team {
         self.payment_address.as_ref()
     }
 
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    pub fn address_history(&self) -> &[AddressChange] {
+        &self.address_history
+    }
+
+    pub fn contacts(&self) -> &[String] {
+        &self.contacts
+    }
+
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
     // Setter methods
-    pub fn set_name(&mut self, name: String) {
-        self.name = name;
+    /// Renames the team. With `keep_old_as_alias`, the old name keeps resolving to it.
+    pub fn set_name(&mut self, name: String, keep_old_as_alias: bool) {
+        let old_name = std::mem::replace(&mut self.name, name);
+        self.aliases.retain(|alias| !alias.eq_ignore_ascii_case(&self.name));
+        if keep_old_as_alias {
+            self.add_alias(old_name);
+        }
     }
 
     pub fn set_representative(&mut self, representative: String) {
@@ -100,6 +153,9 @@ impl Team {
                 Err("Revenue data cannot exceed 3 entries")
             },
             _ => {
+                if new_status != TeamStatus::Archived {
+                    self.status_before_archive = None;
+                }
                 self.status = new_status;
                 Ok(())
             }
@@ -107,16 +163,98 @@ impl Team {
     }
 
     pub fn set_payment_address(&mut self, address: Option<String>) -> Result<(), &'static str> {
-        self.payment_address = match address {
-            Some(addr) => Some(Address::from_str(&addr).map_err(|_| "Invalid Ethereum address")?),
+        self.set_payment_address_at(address, Utc::now())
+    }
+
+    /// Sets the payment address, recording the change as taking effect at `changed_at`.
+    pub fn set_payment_address_at(&mut self, address: Option<String>, changed_at: DateTime<Utc>) -> Result<(), &'static str> {
+        let address = match address {
+            Some(addr) => Some(parse_ethereum_address(&addr)?),
             None => None,
         };
+        if address != self.payment_address {
+            // Teams saved before the history existed keep their current address for earlier times
+            if self.address_history.is_empty() && self.payment_address.is_some() {
+                self.address_history.push(AddressChange { address: self.payment_address, changed_at: DateTime::<Utc>::UNIX_EPOCH });
+            }
+            self.address_history.push(AddressChange { address, changed_at });
+        }
+        self.payment_address = address;
+        Ok(())
+    }
+
+    /// Sets the team's region. A blank value clears it.
+    pub fn set_region(&mut self, region: Option<String>) {
+        self.region = region
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+    }
+
+    /// Replaces the team's Telegram contacts. Handles are trimmed, stripped of a
+    /// leading '@' and deduplicated; blank entries are dropped.
+    pub fn set_contacts(&mut self, contacts: Vec<String>) {
+        let mut normalized: Vec<String> = Vec::new();
+        for contact in contacts {
+            let handle = contact.trim().trim_start_matches('@').to_string();
+            if !handle.is_empty() && !normalized.contains(&handle) {
+                normalized.push(handle);
+            }
+        }
+        self.contacts = normalized;
+    }
+
+    /// Adds an alternative name the team can be looked up by. Case-insensitive
+    /// duplicates of the name or an existing alias are ignored.
+    pub fn add_alias(&mut self, alias: String) {
+        if !self.answers_to(&alias) {
+            self.aliases.push(alias);
+        }
+    }
+
+    /// Whether `name` is the team's name or one of its aliases, ignoring case.
+    pub fn answers_to(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name) || self.alias_matches(name)
+    }
+
+    /// Removes and returns the team's aliases, e.g. to hand them to a team it merges into.
+    pub fn take_aliases(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.aliases)
+    }
+
+    /// Archives the team, remembering its status so `unarchive` can restore it.
+    pub fn archive(&mut self) -> Result<(), &'static str> {
+        if self.is_archived() {
+            return Err("Team is already archived");
+        }
+        self.status_before_archive = Some(std::mem::replace(&mut self.status, TeamStatus::Archived));
+        Ok(())
+    }
+
+    /// Restores the status the team had before it was archived.
+    pub fn unarchive(&mut self) -> Result<(), &'static str> {
+        if !self.is_archived() {
+            return Err("Team is not archived");
+        }
+        self.status = self.status_before_archive.take().unwrap_or(TeamStatus::Supporter);
         Ok(())
     }
 
     // Helper methods
     pub fn is_active(&self) -> bool {
-        !matches!(self.status, TeamStatus::Inactive)
+        !matches!(self.status, TeamStatus::Inactive | TeamStatus::Archived)
+    }
+
+    /// The payment address in effect at `at`, according to the recorded history.
+    /// Returns `None` before the first recorded change; teams saved before history
+    /// was kept fall back to their current address.
+    pub fn address_at(&self, at: DateTime<Utc>) -> Option<Address> {
+        if self.address_history.is_empty() {
+            return self.payment_address;
+        }
+        self.address_history.iter()
+            .filter(|change| change.changed_at <= at)
+            .max_by_key(|change| change.changed_at)
+            .and_then(|change| change.address)
     }
 
     pub fn is_earner(&self) -> bool {
@@ -131,18 +269,38 @@ impl Team {
         matches!(self.status, TeamStatus::Inactive)
     }
 
+    pub fn is_archived(&self) -> bool {
+        matches!(self.status, TeamStatus::Archived)
+    }
+
 }
 
 impl NameMatches for Team {
     fn name_matches(&self, name: &str) -> bool {
         self.name() == name
     }
+
+    fn alias_matches(&self, name: &str) -> bool {
+        self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+}
+
+impl AddressChange {
+    // Getter methods
+    pub fn address(&self) -> Option<&Address> {
+        self.address.as_ref()
+    }
+
+    pub fn changed_at(&self) -> DateTime<Utc> {
+        self.changed_at
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json;
+    use std::str::FromStr;
 
     #[test]
     fn test_create_valid_team() {
@@ -157,6 +315,38 @@ mod tests {
         assert!(matches!(supporter.status(), TeamStatus::Supporter));
     }
 
+    #[test]
+    fn test_aliases() {
+        let mut team = Team::new("Merged Team".to_string(), "Rep".to_string(), None, None).unwrap();
+        team.add_alias("Old Team".to_string());
+        team.add_alias("Old Team".to_string());
+        team.add_alias("Merged Team".to_string());
+        team.add_alias("OLD TEAM".to_string());
+        assert_eq!(team.aliases(), ["Old Team"]);
+        assert!(team.alias_matches("old team"));
+        assert!(!team.alias_matches("Merged Team"));
+
+        // Teams saved before aliases existed have none
+        let mut json = serde_json::to_value(&team).unwrap();
+        json.as_object_mut().unwrap().remove("aliases");
+        let loaded: Team = serde_json::from_value(json).unwrap();
+        assert!(loaded.aliases().is_empty());
+    }
+
+    #[test]
+    fn test_archive_and_unarchive() {
+        let mut team = Team::new("Earner Team".to_string(), "Rep".to_string(), Some(vec![1000, 2000, 3000]), None).unwrap();
+        team.archive().unwrap();
+        assert!(team.is_archived());
+        assert!(!team.is_active());
+        assert!(!team.is_inactive());
+        assert_eq!(team.archive(), Err("Team is already archived"));
+
+        team.unarchive().unwrap();
+        assert_eq!(team.status(), &TeamStatus::Earner { trailing_monthly_revenue: vec![1000, 2000, 3000] });
+        assert_eq!(team.unarchive(), Err("Team is not archived"));
+    }
+
     #[test]
     fn test_create_invalid_team() {
         assert!(Team::new("".to_string(), "John Doe".to_string(), None, None).is_err());
@@ -178,8 +368,14 @@ mod tests {
     fn test_setter_methods() {
         let mut team = Team::new("Old Name".to_string(), "Old Rep".to_string(), None, None).unwrap();
         
-        team.set_name("New Name".to_string());
+        team.set_name("New Name".to_string(), false);
         assert_eq!(team.name(), "New Name");
+        assert!(team.aliases().is_empty());
+
+        team.set_name("Newer Name".to_string(), true);
+        assert_eq!(team.aliases(), ["New Name"]);
+        team.set_name("new name".to_string(), true);
+        assert_eq!(team.aliases(), ["Newer Name"]);
 
         team.set_representative("New Rep".to_string());
         assert_eq!(team.representative(), "New Rep");
@@ -320,4 +516,95 @@ mod tests {
         );
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_team_region() {
+        let mut team = Team::new("Test Team".to_string(), "Rep".to_string(), None, None).unwrap();
+        assert_eq!(team.region(), None);
+
+        team.set_region(Some(" EMEA ".to_string()));
+        assert_eq!(team.region(), Some("EMEA"));
+
+        team.set_region(Some("  ".to_string()));
+        assert_eq!(team.region(), None);
+
+        // Teams saved before regions existed still load
+        let json = r#"{"id":"00000000-0000-0000-0000-000000000000","name":"Old","representative":"Rep","status":"Supporter"}"#;
+        let old: Team = serde_json::from_str(json).unwrap();
+        assert_eq!(old.region(), None);
+    }
+
+    #[test]
+    fn test_team_contacts() {
+        let mut team = Team::new("Test Team".to_string(), "Rep".to_string(), None, None).unwrap();
+        assert!(team.contacts().is_empty());
+
+        team.set_contacts(vec![" @alice ".to_string(), "bob".to_string(), "@alice".to_string(), " ".to_string()]);
+        assert_eq!(team.contacts(), &["alice".to_string(), "bob".to_string()]);
+
+        team.set_contacts(Vec::new());
+        assert!(team.contacts().is_empty());
+
+        let json = r#"{"id":"00000000-0000-0000-0000-000000000000","name":"Old","representative":"Rep","status":"Supporter"}"#;
+        let old: Team = serde_json::from_str(json).unwrap();
+        assert!(old.contacts().is_empty());
+    }
+
+    #[test]
+    fn test_team_address_history() {
+        let first = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+        let second = "0x1234567890123456789012345678901234567890";
+        let mut team = Team::new("Test Team".to_string(), "Rep".to_string(), None, Some(first.to_string())).unwrap();
+        assert_eq!(team.address_history().len(), 1);
+        let created_at = team.address_history()[0].changed_at();
+
+        let rotated_at = created_at + chrono::Duration::days(10);
+        let cleared_at = created_at + chrono::Duration::days(20);
+        team.set_payment_address_at(Some(second.to_string()), rotated_at).unwrap();
+        // Setting the same address again is not a change
+        team.set_payment_address_at(Some(second.to_string()), rotated_at + chrono::Duration::days(1)).unwrap();
+        team.set_payment_address_at(None, cleared_at).unwrap();
+        assert_eq!(team.address_history().len(), 3);
+
+        let first_addr = Address::from_str(first).unwrap();
+        let second_addr = Address::from_str(second).unwrap();
+        assert_eq!(team.address_at(created_at - chrono::Duration::days(1)), None);
+        assert_eq!(team.address_at(created_at + chrono::Duration::days(5)), Some(first_addr));
+        assert_eq!(team.address_at(rotated_at), Some(second_addr));
+        assert_eq!(team.address_at(cleared_at + chrono::Duration::days(1)), None);
+
+        assert!(team.set_payment_address(Some("invalid".to_string())).is_err());
+        assert_eq!(team.address_history().len(), 3);
+
+        // Teams saved before the history existed report their current address
+        let json = format!(r#"{{"id":"00000000-0000-0000-0000-000000000000","name":"Old","representative":"Rep","status":"Supporter","payment_address":"{}"}}"#, first);
+        let mut old: Team = serde_json::from_str(&json).unwrap();
+        assert!(old.address_history().is_empty());
+        assert_eq!(old.address_at(created_at), Some(first_addr));
+
+        // Their first change keeps the earlier address for times before it
+        old.set_payment_address_at(Some(second.to_string()), rotated_at).unwrap();
+        assert_eq!(old.address_history().len(), 2);
+        assert_eq!(old.address_at(created_at), Some(first_addr));
+        assert_eq!(old.address_at(rotated_at), Some(second_addr));
+    }
+
+    #[test]
+    fn test_team_address_validation() {
+        let new_team = |address: &str| Team::new("Team".to_string(), "Rep".to_string(), None, Some(address.to_string()));
+
+        assert!(new_team("0x742d35cc6634c0532925a3b844bc454e4438f44e").is_ok());
+        assert_eq!(new_team("742d35Cc6634C0532925a3b844Bc454e4438f44e").unwrap_err(), "Ethereum address must start with 0x");
+        assert_eq!(new_team("0x742d35Cc6634C0532925a3b844Bc454e4438").unwrap_err(), "Ethereum address must be 42 characters long");
+        assert_eq!(new_team("0x742d35Cc6634C0532925a3b844Bc454e4438f4gg").unwrap_err(), "Invalid hex characters in address");
+        assert_eq!(new_team("0x0000000000000000000000000000000000000000").unwrap_err(), "Ethereum address cannot be the zero address");
+        assert_eq!(new_team("0x742D35cc6634C0532925a3b844Bc454e4438f44e").unwrap_err(), "Invalid EIP-55 checksum in Ethereum address");
+
+        let mut team = new_team("0x742d35Cc6634C0532925a3b844Bc454e4438f44e").unwrap();
+        assert_eq!(
+            team.set_payment_address(Some("0x0000000000000000000000000000000000000000".to_string())),
+            Err("Ethereum address cannot be the zero address")
+        );
+        assert_eq!(team.payment_address(), Some(&Address::from_str("0x742d35Cc6634C0532925a3b844Bc454e4438f44e").unwrap()));
+    }
+}
@@ -0,0 +1,197 @@
+use uuid::Uuid;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProposalTemplate {
+    name: String,
+    title_pattern: String,
+    team_id: Option<Uuid>,
+    amount_pattern: HashMap<String, f64>,
+    is_loan: bool,
+    tags: Vec<String>,
+    recurrence: Option<RecurrenceConfig>,
+    #[serde(default)]
+    created_count: u32,
+    /// Epoch of the most recent proposal created from the template
+    #[serde(default)]
+    last_epoch_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecurrenceConfig {
+    interval_epochs: u32,
+    max_occurrences: Option<u32>,
+}
+
+/// Values that replace the template's defaults for a single proposal.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProposalOverrides {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub team_id: Option<Uuid>,
+    pub amounts: Option<HashMap<String, f64>>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub announced_at: Option<NaiveDate>,
+    pub published_at: Option<NaiveDate>,
+}
+
+impl ProposalTemplate {
+    pub fn new(
+        name: String,
+        title_pattern: String,
+        team_id: Option<Uuid>,
+        amount_pattern: HashMap<String, f64>,
+        is_loan: bool,
+        tags: Vec<String>,
+        recurrence: Option<RecurrenceConfig>,
+    ) -> Result<Self, &'static str> {
+        if name.trim().is_empty() {
+            return Err("Template name cannot be empty");
+        }
+        if title_pattern.trim().is_empty() {
+            return Err("Template title pattern cannot be empty");
+        }
+        if amount_pattern.values().any(|&amount| amount <= 0.0) {
+            return Err("Template amounts must be positive");
+        }
+
+        Ok(ProposalTemplate {
+            name,
+            title_pattern,
+            team_id,
+            amount_pattern,
+            is_loan,
+            tags,
+            recurrence,
+            created_count: 0,
+            last_epoch_id: None,
+        })
+    }
+
+    // Getter methods
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn title_pattern(&self) -> &str {
+        &self.title_pattern
+    }
+
+    pub fn team_id(&self) -> Option<Uuid> {
+        self.team_id
+    }
+
+    pub fn amount_pattern(&self) -> &HashMap<String, f64> {
+        &self.amount_pattern
+    }
+
+    pub fn is_loan(&self) -> bool {
+        self.is_loan
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn recurrence(&self) -> Option<&RecurrenceConfig> {
+        self.recurrence.as_ref()
+    }
+
+    pub fn created_count(&self) -> u32 {
+        self.created_count
+    }
+
+    pub fn last_epoch_id(&self) -> Option<Uuid> {
+        self.last_epoch_id
+    }
+
+    // Helper methods
+
+    /// Expands `{epoch}` and `{n}` (the 1-based occurrence number) in the title pattern.
+    pub fn render_title(&self, epoch_name: &str) -> String {
+        self.title_pattern
+            .replace("{epoch}", epoch_name)
+            .replace("{n}", &(self.created_count + 1).to_string())
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.recurrence.as_ref()
+            .and_then(|r| r.max_occurrences())
+            .is_some_and(|max| self.created_count >= max)
+    }
+
+    pub fn record_use(&mut self, epoch_id: Uuid) {
+        self.created_count += 1;
+        self.last_epoch_id = Some(epoch_id);
+    }
+}
+
+impl RecurrenceConfig {
+    pub fn new(interval_epochs: u32, max_occurrences: Option<u32>) -> Result<Self, &'static str> {
+        if interval_epochs == 0 {
+            return Err("Recurrence interval must be at least one epoch");
+        }
+        Ok(RecurrenceConfig { interval_epochs, max_occurrences })
+    }
+
+    // Getter methods
+    pub fn interval_epochs(&self) -> u32 {
+        self.interval_epochs
+    }
+
+    pub fn max_occurrences(&self) -> Option<u32> {
+        self.max_occurrences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_template(recurrence: Option<RecurrenceConfig>) -> ProposalTemplate {
+        ProposalTemplate::new(
+            "Ops Grant".to_string(),
+            "Ops Grant {epoch} #{n}".to_string(),
+            None,
+            [("ETH".to_string(), 10.0)].iter().cloned().collect(),
+            false,
+            vec!["ops".to_string()],
+            recurrence,
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_template_creation_validation() {
+        assert!(ProposalTemplate::new("".to_string(), "Title".to_string(), None, HashMap::new(), false, vec![], None).is_err());
+        assert!(ProposalTemplate::new("Name".to_string(), " ".to_string(), None, HashMap::new(), false, vec![], None).is_err());
+        let negative = [("ETH".to_string(), -1.0)].iter().cloned().collect();
+        assert!(ProposalTemplate::new("Name".to_string(), "Title".to_string(), None, negative, false, vec![], None).is_err());
+        assert!(RecurrenceConfig::new(0, None).is_err());
+    }
+
+    #[test]
+    fn test_render_title() {
+        let mut template = create_test_template(None);
+        assert_eq!(template.render_title("Q1"), "Ops Grant Q1 #1");
+        template.record_use(Uuid::new_v4());
+        assert_eq!(template.render_title("Q2"), "Ops Grant Q2 #2");
+    }
+
+    #[test]
+    fn test_max_occurrences() {
+        let mut template = create_test_template(Some(RecurrenceConfig::new(1, Some(2)).unwrap()));
+        assert!(!template.is_exhausted());
+        template.record_use(Uuid::new_v4());
+        let epoch_id = Uuid::new_v4();
+        template.record_use(epoch_id);
+        assert!(template.is_exhausted());
+        assert_eq!(template.last_epoch_id(), Some(epoch_id));
+
+        let mut unlimited = create_test_template(None);
+        unlimited.record_use(epoch_id);
+        assert!(!unlimited.is_exhausted());
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 use std::collections::HashMap;
 use super::common::NameMatches;
@@ -17,7 +17,29 @@ pub struct Vote {
     opened_at: DateTime<Utc>,
     closed_at: Option<DateTime<Utc>>,
     is_historical: bool,
-    votes: HashMap<Uuid, VoteChoice> // leave private, temporarily stored
+    votes: HashMap<Uuid, VoteChoice>, // leave private, temporarily stored
+    /// When each team last cast its vote, cleared with the votes on closing
+    #[serde(default)]
+    cast_at: HashMap<Uuid, DateTime<Utc>>,
+    #[serde(default)]
+    pending_vote_changes: Vec<VoteChangeRequest>,
+    /// Teams that abstained, kept after closing so they can be credited abstain points
+    #[serde(default)]
+    abstained: Vec<Uuid>,
+    /// When an open vote is closed automatically with whatever has been cast
+    #[serde(default)]
+    deadline: Option<DateTime<Utc>>,
+    /// Delegator to delegate for seats cast by another team, kept after closing for reports
+    #[serde(default)]
+    delegations: HashMap<Uuid, Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoteChangeRequest {
+    team_id: Uuid,
+    new_choice: VoteChoice,
+    reason: String,
+    requested_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -28,6 +50,13 @@ pub enum VoteType {
         threshold: f64,
         counted_points: u32,
         uncounted_points: u32,
+        /// Points for an abstention, capped at what a Yes/No vote from the same seat earns
+        #[serde(default)]
+        abstain_points: u32,
+        /// Share of eligible seats that must cast a counted vote, abstentions included;
+        /// 0.0 means no quorum
+        #[serde(default)]
+        quorum: f64,
     },
     Informal,
 }
@@ -42,6 +71,20 @@ pub enum VoteStatus {
 pub enum VoteChoice {
     Yes,
     No,
+    Abstain,
+}
+
+impl std::str::FromStr for VoteChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yes" => Ok(VoteChoice::Yes),
+            "no" => Ok(VoteChoice::No),
+            "abstain" => Ok(VoteChoice::Abstain),
+            _ => Err(format!("Invalid vote choice: {}. Must be Yes, No or Abstain", s)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +112,8 @@ pub enum VoteResult {
 pub struct VoteCount {
     yes: u32,
     no: u32,
+    #[serde(default)]
+    abstain: u32,
 }
 
 impl Vote {
@@ -99,6 +144,11 @@ impl Vote {
             closed_at: None,
             is_historical,
             votes: HashMap::new(),
+            cast_at: HashMap::new(),
+            pending_vote_changes: Vec::new(),
+            abstained: Vec::new(),
+            deadline: None,
+            delegations: HashMap::new(),
         }
     }
 
@@ -113,6 +163,11 @@ impl Vote {
     pub fn opened_at(&self) -> DateTime<Utc> { self.opened_at }
     pub fn closed_at(&self) -> Option<DateTime<Utc>> { self.closed_at }
     pub fn is_historical(&self) -> bool { self.is_historical }
+    pub fn pending_vote_changes(&self) -> &[VoteChangeRequest] { &self.pending_vote_changes }
+    pub fn abstained(&self) -> &[Uuid] { &self.abstained }
+    pub fn deadline(&self) -> Option<DateTime<Utc>> { self.deadline }
+    pub fn delegations(&self) -> &HashMap<Uuid, Uuid> { &self.delegations }
+    pub fn cast_at(&self, team_id: Uuid) -> Option<DateTime<Utc>> { self.cast_at.get(&team_id).copied() }
 
     pub fn vote_counts(&self) -> Option<(VoteCount, VoteCount)> {
         match &self.result {
@@ -126,6 +181,7 @@ impl Vote {
     pub fn set_result(&mut self, result: Option<VoteResult>) { self.result = result; }
     pub fn set_opened_at(&mut self, date: DateTime<Utc>) { self.opened_at = date; }
     pub fn set_closed_at(&mut self, date: Option<DateTime<Utc>>) { self.closed_at = date; }
+    pub fn set_deadline(&mut self, deadline: Option<DateTime<Utc>>) { self.deadline = deadline; }
 
     // Core functionality
     pub fn cast_vote(&mut self, team_id: Uuid, choice: VoteChoice, raffle_result: Option<&RaffleResult>) -> Result<(), &'static str> {
@@ -134,6 +190,7 @@ impl Vote {
         }
 
         self.votes.insert(team_id, choice);
+        self.cast_at.insert(team_id, Utc::now());
 
         match &mut self.participation {
             VoteParticipation::Formal { counted, uncounted } => {
@@ -163,6 +220,31 @@ impl Vote {
         Ok(())
     }
 
+    /// Casts the delegator's vote on behalf of the delegate and records the delegation.
+    /// The seat and its points stay with the delegator. A team that delegated its seat
+    /// cannot act as a delegate, and a delegate cannot delegate its own seat.
+    pub fn cast_delegated_vote(&mut self, delegator_id: Uuid, delegate_id: Uuid, choice: VoteChoice, raffle_result: Option<&RaffleResult>) -> Result<(), &'static str> {
+        if self.is_closed() {
+            return Err("Vote is closed");
+        }
+        if delegator_id == delegate_id {
+            return Err("A team cannot delegate its seat to itself");
+        }
+        if self.delegations.get(&delegator_id).is_some_and(|&existing| existing != delegate_id) {
+            return Err("Seat is already delegated to another team");
+        }
+        if self.delegations.contains_key(&delegate_id) {
+            return Err("Delegate has delegated its own seat");
+        }
+        if self.delegations.values().any(|&delegate| delegate == delegator_id) {
+            return Err("A delegate cannot delegate its own seat");
+        }
+
+        self.cast_vote(delegator_id, choice, raffle_result)?;
+        self.delegations.insert(delegator_id, delegate_id);
+        Ok(())
+    }
+
     pub fn close(&mut self) -> Result<(), &'static str> {
         if self.is_closed() {
             return Err("Vote is already closed");
@@ -172,11 +254,80 @@ impl Vote {
         self.closed_at = Some(Utc::now());
 
         self.calculate_result()?;
+        self.abstained = self.votes.iter()
+            .filter(|(_, &choice)| choice == VoteChoice::Abstain)
+            .map(|(&team_id, _)| team_id)
+            .collect();
         self.votes.clear();
+        self.cast_at.clear();
+        self.pending_vote_changes.clear();
 
         Ok(())
     }
 
+    /// Opens a closed vote again, dropping its result. Choices are discarded when a vote
+    /// closes, so every seat has to vote again.
+    pub fn reopen(&mut self) -> Result<(), &'static str> {
+        if !self.is_closed() {
+            return Err("Vote is not closed");
+        }
+        if self.is_historical {
+            return Err("Historical votes cannot be reopened");
+        }
+
+        self.status = VoteStatus::Open;
+        self.closed_at = None;
+        self.result = None;
+        self.abstained.clear();
+        self.delegations.clear();
+        Ok(())
+    }
+
+    /// Queues a change to a vote the team has already cast, no later than `window` after
+    /// casting it. The change is only applied once approved, and only while the vote is open.
+    /// Votes cast before cast times were stored count from the vote's opening.
+    pub fn request_vote_change(&mut self, team_id: Uuid, new_choice: VoteChoice, reason: String, window: Duration) -> Result<(), &'static str> {
+        if self.is_closed() {
+            return Err("Vote is closed");
+        }
+        if reason.trim().is_empty() {
+            return Err("A reason is required to request a vote change");
+        }
+        match self.votes.get(&team_id) {
+            None => return Err("Team has not cast a vote"),
+            Some(&current) if current == new_choice => return Err("New choice matches the current vote"),
+            Some(_) => {},
+        }
+        let cast_at = self.cast_at(team_id).unwrap_or(self.opened_at);
+        if Utc::now() - cast_at > window {
+            return Err("The window for changing this vote has passed");
+        }
+        if self.pending_vote_changes.iter().any(|r| r.team_id == team_id) {
+            return Err("Team already has a pending vote change request");
+        }
+
+        self.pending_vote_changes.push(VoteChangeRequest {
+            team_id,
+            new_choice,
+            reason,
+            requested_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    pub fn approve_vote_change(&mut self, team_id: Uuid) -> Result<VoteChoice, &'static str> {
+        if self.is_closed() {
+            return Err("Vote is closed");
+        }
+        let index = self.pending_vote_changes.iter()
+            .position(|r| r.team_id == team_id)
+            .ok_or("No pending vote change request for team")?;
+
+        let request = self.pending_vote_changes.remove(index);
+        self.votes.insert(team_id, request.new_choice);
+        Ok(request.new_choice)
+    }
+
     pub fn add_participant(&mut self, team_id: Uuid, is_counted: bool) -> Result<(), &'static str> {
         match &mut self.participation {
             VoteParticipation::Formal { counted, uncounted } => {
@@ -204,11 +355,64 @@ impl Vote {
         matches!(self.status, VoteStatus::Closed)
     }
 
+    /// Whether the vote is still open although its deadline has passed.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        !self.is_closed() && self.deadline.is_some_and(|deadline| deadline <= now)
+    }
+
+    pub fn has_abstained(&self, team_id: Uuid) -> bool {
+        self.abstained.contains(&team_id)
+    }
+
+    /// Points a team earned for taking part in a formal vote; 0 for informal votes
+    /// and teams that did not vote.
+    pub fn points_for_team(&self, team_id: Uuid) -> u32 {
+        if let (VoteType::Formal { counted_points, uncounted_points, abstain_points, .. }, VoteParticipation::Formal { counted, uncounted }) = (&self.vote_type, &self.participation) {
+            let seat_points = if counted.contains(&team_id) {
+                *counted_points
+            } else if uncounted.contains(&team_id) {
+                *uncounted_points
+            } else {
+                return 0;
+            };
+            if self.has_abstained(team_id) {
+                (*abstain_points).min(seat_points)
+            } else {
+                seat_points
+            }
+        } else {
+            0
+        }
+    }
+
+    /// Counted votes a formal vote needs to reach quorum and the number cast so far,
+    /// or `None` for informal votes and votes without a quorum.
+    pub fn quorum_status(&self) -> Option<(u32, u32)> {
+        match &self.vote_type {
+            VoteType::Formal { total_eligible_seats, quorum, .. } if *quorum > 0.0 => {
+                let required = (*quorum * *total_eligible_seats as f64).ceil() as u32;
+                // Individual votes are cleared on close, so closed votes use the tally
+                let (counted, _) = self.vote_counts().unwrap_or_else(|| self.count_formal_votes());
+                Some((required, counted.total()))
+            },
+            _ => None,
+        }
+    }
+
+    pub fn quorum_met(&self) -> bool {
+        self.quorum_status().is_none_or(|(required, cast)| cast >= required)
+    }
+
+    /// Formal votes pass when quorum is met and the counted Yes votes reach the
+    /// threshold share of all eligible seats, so abstentions and absences both
+    /// weigh against passing.
     fn calculate_result(&mut self) -> Result<(), &'static str> {
+        let quorum_met = self.quorum_met();
         self.result = Some(match &self.vote_type {
             VoteType::Formal { total_eligible_seats, threshold, .. } => {
                 let (counted, uncounted) = self.count_formal_votes();
-                let passed = (counted.yes() as f64 / *total_eligible_seats as f64) >= *threshold;
+                let passed = quorum_met
+                    && (counted.yes() as f64 / *total_eligible_seats as f64) >= *threshold;
                 VoteResult::Formal { counted, uncounted, passed }
             },
             VoteType::Informal => {
@@ -230,11 +434,13 @@ impl Vote {
                     match choice {
                         VoteChoice::Yes => counted.increment_yes(),
                         VoteChoice::No => counted.increment_no(),
+                        VoteChoice::Abstain => counted.increment_abstain(),
                     }
                 } else if uncounted_teams.contains(&team_id) {
                     match choice {
                         VoteChoice::Yes => uncounted.increment_yes(),
                         VoteChoice::No => uncounted.increment_no(),
+                        VoteChoice::Abstain => uncounted.increment_abstain(),
                     }
                 }
             }
@@ -250,6 +456,7 @@ impl Vote {
             match choice {
                 VoteChoice::Yes => count.increment_yes(),
                 VoteChoice::No => count.increment_no(),
+                VoteChoice::Abstain => count.increment_abstain(),
             }
         }
 
@@ -271,6 +478,14 @@ impl Vote {
     
 }
 
+impl VoteChangeRequest {
+    // Getter methods
+    pub fn team_id(&self) -> Uuid { self.team_id }
+    pub fn new_choice(&self) -> VoteChoice { self.new_choice }
+    pub fn reason(&self) -> &str { &self.reason }
+    pub fn requested_at(&self) -> DateTime<Utc> { self.requested_at }
+}
+
 impl NameMatches for Vote {
     fn name_matches(&self, name: &str) -> bool {
         self.id.to_string() == name
@@ -280,7 +495,7 @@ impl NameMatches for Vote {
 impl VoteCount {
     // Constructor
     pub fn new() -> Self {
-        Self { yes: 0, no: 0 }
+        Self { yes: 0, no: 0, abstain: 0 }
     }
 
     // Getter methods
@@ -292,6 +507,10 @@ impl VoteCount {
         self.no
     }
 
+    pub fn abstain(&self) -> u32 {
+        self.abstain
+    }
+
     // Increment methods
     pub fn increment_yes(&mut self) {
         self.yes += 1;
@@ -301,9 +520,14 @@ impl VoteCount {
         self.no += 1;
     }
 
+    pub fn increment_abstain(&mut self) {
+        self.abstain += 1;
+    }
+
     // Helper methods
+    /// All votes cast, abstentions included
     pub fn total(&self) -> u32 {
-        self.yes + self.no
+        self.yes + self.no + self.abstain
     }
 
     pub fn yes_percentage(&self) -> f64 {
@@ -344,6 +568,8 @@ mod tests {
             threshold: 0.5,
             counted_points: 2,
             uncounted_points: 1,
+            abstain_points: 1,
+            quorum: 0.0,
         });
         assert!(matches!(formal_vote.vote_type(), VoteType::Formal { .. }));
         assert_eq!(formal_vote.status(), &VoteStatus::Open);
@@ -370,6 +596,8 @@ mod tests {
             threshold: 0.5,
             counted_points: 2,
             uncounted_points: 1,
+            abstain_points: 1,
+            quorum: 0.0,
         });
 
         let team_id = Uuid::new_v4();
@@ -391,6 +619,8 @@ mod tests {
             threshold: 0.5,
             counted_points: 2,
             uncounted_points: 1,
+            abstain_points: 1,
+            quorum: 0.0,
         });
 
         let team_id = Uuid::new_v4();
@@ -424,6 +654,8 @@ mod tests {
             threshold: 0.5,
             counted_points: 2,
             uncounted_points: 1,
+            abstain_points: 1,
+            quorum: 0.0,
         });
 
         let raffle_result = RaffleResult::new(vec![Uuid::new_v4(), Uuid::new_v4()], vec![Uuid::new_v4()]);
@@ -447,6 +679,8 @@ mod tests {
             threshold: 0.5,
             counted_points: 2,
             uncounted_points: 1,
+            abstain_points: 1,
+            quorum: 0.0,
         });
 
         let raffle_result = RaffleResult::new(vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()], vec![]);
@@ -464,6 +698,86 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_abstain_votes() {
+        let mut vote = create_test_vote(VoteType::Formal {
+            raffle_id: Uuid::new_v4(),
+            total_eligible_seats: 3,
+            threshold: 0.5,
+            counted_points: 2,
+            uncounted_points: 1,
+            abstain_points: 1,
+            quorum: 0.0,
+        });
+
+        let raffle_result = RaffleResult::new(vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()], vec![Uuid::new_v4()]);
+
+        vote.cast_vote(raffle_result.counted()[0], VoteChoice::Yes, Some(&raffle_result)).unwrap();
+        vote.cast_vote(raffle_result.counted()[1], VoteChoice::Abstain, Some(&raffle_result)).unwrap();
+        vote.cast_vote(raffle_result.counted()[2], VoteChoice::Abstain, Some(&raffle_result)).unwrap();
+        vote.cast_vote(raffle_result.uncounted()[0], VoteChoice::Abstain, Some(&raffle_result)).unwrap();
+
+        // Abstaining teams still take part in the vote
+        if let VoteParticipation::Formal { counted, uncounted } = vote.participation() {
+            assert_eq!(counted.len(), 3);
+            assert_eq!(uncounted.len(), 1);
+        } else {
+            panic!("Expected Formal participation");
+        }
+
+        vote.close().unwrap();
+
+        if let Some(VoteResult::Formal { counted, uncounted, passed }) = vote.result() {
+            assert_eq!(counted.yes(), 1);
+            assert_eq!(counted.no(), 0);
+            assert_eq!(counted.abstain(), 2);
+            assert_eq!(counted.total(), 3);
+            assert_eq!(uncounted.abstain(), 1);
+            // One Yes out of three eligible seats misses the 50% threshold
+            assert!(!passed);
+        } else {
+            panic!("Expected Formal vote result");
+        }
+    }
+
+    #[test]
+    fn test_quorum() {
+        let vote_type = VoteType::Formal {
+            raffle_id: Uuid::new_v4(),
+            total_eligible_seats: 7,
+            threshold: 0.2,
+            counted_points: 2,
+            uncounted_points: 1,
+            abstain_points: 1,
+            quorum: 0.5,
+        };
+        let raffle_result = RaffleResult::new((0..7).map(|_| Uuid::new_v4()).collect(), vec![]);
+
+        let mut vote = create_test_vote(vote_type.clone());
+        vote.cast_vote(raffle_result.counted()[0], VoteChoice::Yes, Some(&raffle_result)).unwrap();
+        vote.cast_vote(raffle_result.counted()[1], VoteChoice::Yes, Some(&raffle_result)).unwrap();
+        vote.cast_vote(raffle_result.counted()[2], VoteChoice::No, Some(&raffle_result)).unwrap();
+        assert_eq!(vote.quorum_status(), Some((4, 3)));
+        vote.close().unwrap();
+        assert!(!vote.quorum_met());
+        assert!(matches!(vote.result(), Some(VoteResult::Formal { passed: false, .. })));
+
+        // Abstentions count towards quorum
+        let mut vote = create_test_vote(vote_type);
+        for (i, choice) in [VoteChoice::Yes, VoteChoice::Yes, VoteChoice::No, VoteChoice::Abstain].into_iter().enumerate() {
+            vote.cast_vote(raffle_result.counted()[i], choice, Some(&raffle_result)).unwrap();
+        }
+        vote.close().unwrap();
+        assert!(vote.quorum_met());
+        assert!(matches!(vote.result(), Some(VoteResult::Formal { passed: true, .. })));
+
+        // Votes saved before quorum existed have none
+        let json = r#"{"Formal":{"raffle_id":"00000000-0000-0000-0000-000000000000","total_eligible_seats":7,"threshold":0.7,"counted_points":5,"uncounted_points":2}}"#;
+        let old: VoteType = serde_json::from_str(json).unwrap();
+        assert!(matches!(old, VoteType::Formal { quorum, .. } if quorum == 0.0));
+        assert_eq!(create_test_vote(old).quorum_status(), None);
+    }
+
     #[test]
     fn test_edge_cases_and_error_handling() {
         let mut vote = create_test_vote(VoteType::Formal {
@@ -472,6 +786,8 @@ mod tests {
             threshold: 0.5,
             counted_points: 2,
             uncounted_points: 1,
+            abstain_points: 1,
+            quorum: 0.0,
         });
 
         // Attempt to cast vote without raffle result
@@ -490,4 +806,120 @@ mod tests {
         // Attempt to close an already closed vote
         assert!(vote.close().is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_vote_change_requests() {
+        let mut vote = create_test_vote(VoteType::Informal);
+        let team_id = Uuid::new_v4();
+        let reason = "Misread the proposal".to_string();
+        let window = Duration::hours(1);
+
+        // Team must have voted first
+        assert!(vote.request_vote_change(team_id, VoteChoice::No, reason.clone(), window).is_err());
+
+        vote.cast_vote(team_id, VoteChoice::Yes, None).unwrap();
+        assert!(vote.request_vote_change(team_id, VoteChoice::Yes, reason.clone(), window).is_err());
+        assert!(vote.request_vote_change(team_id, VoteChoice::No, " ".to_string(), window).is_err());
+        assert!(vote.approve_vote_change(team_id).is_err());
+
+        vote.request_vote_change(team_id, VoteChoice::No, reason.clone(), window).unwrap();
+        assert!(vote.request_vote_change(team_id, VoteChoice::No, reason.clone(), window).is_err());
+        assert_eq!(vote.pending_vote_changes().len(), 1);
+        assert_eq!(vote.pending_vote_changes()[0].reason(), "Misread the proposal");
+
+        assert_eq!(vote.approve_vote_change(team_id).unwrap(), VoteChoice::No);
+        assert!(vote.pending_vote_changes().is_empty());
+        assert_eq!(vote.count_informal_votes().no(), 1);
+
+        // Requests must follow the cast within the window
+        vote.cast_at.insert(team_id, Utc::now() - Duration::hours(2));
+        assert_eq!(
+            vote.request_vote_change(team_id, VoteChoice::Yes, reason.clone(), window).unwrap_err(),
+            "The window for changing this vote has passed"
+        );
+        vote.cast_vote(team_id, VoteChoice::No, None).unwrap();
+        assert!(vote.cast_at(team_id).is_some_and(|at| Utc::now() - at < window));
+
+        // Requests cannot be made or approved once the vote is closed
+        vote.request_vote_change(team_id, VoteChoice::Yes, reason.clone(), window).unwrap();
+        vote.close().unwrap();
+        assert!(vote.pending_vote_changes().is_empty());
+        assert!(vote.approve_vote_change(team_id).is_err());
+        assert!(vote.request_vote_change(team_id, VoteChoice::Yes, reason, window).is_err());
+    }
+
+    #[test]
+    fn test_vote_choice_from_str() {
+        assert_eq!("yes".parse::<VoteChoice>().unwrap(), VoteChoice::Yes);
+        assert_eq!("No".parse::<VoteChoice>().unwrap(), VoteChoice::No);
+        assert_eq!("ABSTAIN".parse::<VoteChoice>().unwrap(), VoteChoice::Abstain);
+        assert!("maybe".parse::<VoteChoice>().is_err());
+    }
+
+    #[test]
+    fn test_abstain_points_capped_by_seat() {
+        let mut vote = create_test_vote(VoteType::Formal {
+            raffle_id: Uuid::new_v4(),
+            total_eligible_seats: 2,
+            threshold: 0.5,
+            counted_points: 5,
+            uncounted_points: 2,
+            abstain_points: 3,
+            quorum: 0.0,
+        });
+
+        let raffle_result = RaffleResult::new(vec![Uuid::new_v4(), Uuid::new_v4()], vec![Uuid::new_v4()]);
+        let (yes_team, counted_abstainer) = (raffle_result.counted()[0], raffle_result.counted()[1]);
+        let uncounted_abstainer = raffle_result.uncounted()[0];
+
+        vote.cast_vote(yes_team, VoteChoice::Yes, Some(&raffle_result)).unwrap();
+        vote.cast_vote(counted_abstainer, VoteChoice::Abstain, Some(&raffle_result)).unwrap();
+        vote.cast_vote(uncounted_abstainer, VoteChoice::Abstain, Some(&raffle_result)).unwrap();
+        vote.close().unwrap();
+
+        assert_eq!(vote.abstained().len(), 2);
+        assert!(!vote.has_abstained(yes_team));
+        assert_eq!(vote.points_for_team(yes_team), 5);
+        assert_eq!(vote.points_for_team(counted_abstainer), 3);
+        assert_eq!(vote.points_for_team(uncounted_abstainer), 2);
+        assert_eq!(vote.points_for_team(Uuid::new_v4()), 0);
+    }
+
+    #[test]
+    fn test_cast_delegated_vote() {
+        let mut vote = create_test_vote(VoteType::Formal {
+            raffle_id: Uuid::new_v4(),
+            total_eligible_seats: 3,
+            threshold: 0.5,
+            counted_points: 5,
+            uncounted_points: 2,
+            abstain_points: 3,
+            quorum: 0.0,
+        });
+        let raffle_result = RaffleResult::new(vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()], vec![]);
+        let (team_a, team_b, team_c) = (raffle_result.counted()[0], raffle_result.counted()[1], raffle_result.counted()[2]);
+
+        assert_eq!(vote.cast_delegated_vote(team_a, team_a, VoteChoice::Yes, Some(&raffle_result)),
+            Err("A team cannot delegate its seat to itself"));
+
+        vote.cast_delegated_vote(team_a, team_b, VoteChoice::Yes, Some(&raffle_result)).unwrap();
+        assert_eq!(vote.delegations().get(&team_a), Some(&team_b));
+
+        // No chains in either direction
+        assert_eq!(vote.cast_delegated_vote(team_b, team_c, VoteChoice::No, Some(&raffle_result)),
+            Err("A delegate cannot delegate its own seat"));
+        assert_eq!(vote.cast_delegated_vote(team_c, team_a, VoteChoice::No, Some(&raffle_result)),
+            Err("Delegate has delegated its own seat"));
+        assert_eq!(vote.cast_delegated_vote(team_a, team_c, VoteChoice::No, Some(&raffle_result)),
+            Err("Seat is already delegated to another team"));
+
+        vote.cast_vote(team_b, VoteChoice::No, Some(&raffle_result)).unwrap();
+        vote.close().unwrap();
+
+        assert_eq!(vote.points_for_team(team_a), 5);
+        assert_eq!(vote.points_for_team(team_b), 5);
+        assert_eq!(vote.points_for_team(team_c), 0);
+        assert_eq!(vote.delegations().len(), 1);
+        assert!(vote.cast_delegated_vote(team_c, team_b, VoteChoice::Yes, Some(&raffle_result)).is_err());
+    }
+}
@@ -22,6 +22,11 @@ pub enum RaffleProgress {
         target_block: u64,
         randomness: String
     },
+    /// An Ethereum call failed and is about to be retried
+    Retrying {
+        attempt: u32,
+        error: String,
+    },
     Completed {
         proposal_name: String,
         raffle_id: Uuid,
@@ -54,6 +59,9 @@ impl RaffleProgress {
                      Etherscan URL: https://etherscan.io/block/{}#consensusinfo",
                     randomness, target_block)
             },
+            RaffleProgress::Retrying { attempt, error } => {
+                format!("Ethereum call failed (attempt {}): {}. Retrying...", attempt, error)
+            },
             RaffleProgress::Completed { proposal_name, raffle_id, counted, uncounted } => {
                 let mut msg = format!("Raffle results for proposal '{}' (Raffle ID: {})\n\n", proposal_name, raffle_id);
                 
@@ -125,6 +133,9 @@ impl RaffleProgress {
                      Etherscan URL: https://etherscan\\.io/block/{}\\#consensusinfo",
                     escape_markdown(randomness), target_block)
             },
+            RaffleProgress::Retrying { attempt, error } => {
+                format!("⚠️ Ethereum call failed \\(attempt {}\\): {}\\. Retrying\\.\\.\\.", attempt, escape_markdown(error))
+            },
             RaffleProgress::Completed { proposal_name, raffle_id, counted, uncounted } => {
                 let mut msg = format!("Raffle results for proposal '{}' \\(Raffle ID: {}\\)\n\n", 
                     escape_markdown(proposal_name), raffle_id);
@@ -178,6 +189,7 @@ impl RaffleProgress {
             RaffleProgress::WaitingForBlock { raffle_id, .. } |
             RaffleProgress::RandomnessAcquired { raffle_id, .. } |
             RaffleProgress::Completed { raffle_id, .. } => Some(*raffle_id),
+            RaffleProgress::Retrying { .. } |
             RaffleProgress::Failed(_) => None,
         }
     }
@@ -191,12 +203,36 @@ impl RaffleProgress {
     }
 }
 
-#[derive(Debug)]
-pub struct RaffleCreationError(pub String);
+#[derive(Debug, Clone, PartialEq)]
+pub enum RaffleCreationError {
+    NoActiveEpoch,
+    ProposalNotFound(String),
+    EthereumUnavailable(String),
+    InsufficientTeams,
+    Finalization(String),
+}
+
+impl RaffleCreationError {
+    /// Whether the failure came from the Ethereum node and may succeed on retry.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, RaffleCreationError::EthereumUnavailable(_))
+    }
+}
 
 impl std::fmt::Display for RaffleCreationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Raffle creation error: {}", self.0)
+        match self {
+            RaffleCreationError::NoActiveEpoch => 
+                write!(f, "Raffle creation error: No active epoch"),
+            RaffleCreationError::ProposalNotFound(name) => 
+                write!(f, "Raffle creation error: Proposal not found: {}", name),
+            RaffleCreationError::EthereumUnavailable(e) => 
+                write!(f, "Raffle creation error: Ethereum service unavailable: {}", e),
+            RaffleCreationError::InsufficientTeams => 
+                write!(f, "Raffle creation error: No eligible teams to participate in the raffle"),
+            RaffleCreationError::Finalization(e) => 
+                write!(f, "Raffle creation error: {}", e),
+        }
     }
 }
 
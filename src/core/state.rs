@@ -5,7 +5,7 @@ use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
-use crate::core::models::{Team, Proposal, Raffle, Vote, Epoch};
+use crate::core::models::{Team, Proposal, Raffle, Vote, Epoch, ProposalTemplate, StateDiff};
 
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -14,8 +14,15 @@ pub struct SystemState {
     timestamp: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize)]
+/// Version of the serialized state layout. Bump it together with a new entry
+/// in `FileSystem`'s migration list whenever a model change needs old state
+/// files rewritten.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BudgetSystemState {
+    #[serde(default = "current_schema_version")]
+    schema_version: u32,
     current_state: SystemState,
     history: Vec<SystemState>,
     proposals: HashMap<Uuid, Proposal>,
@@ -23,6 +30,12 @@ pub struct BudgetSystemState {
     votes: HashMap<Uuid, Vote>,
     epochs: HashMap<Uuid, Epoch>,
     current_epoch: Option<Uuid>,
+    #[serde(default)]
+    proposal_templates: HashMap<String, ProposalTemplate>,
+}
+
+fn current_schema_version() -> u32 {
+    CURRENT_SCHEMA_VERSION
 }
 
 impl SystemState {
@@ -84,6 +97,7 @@ impl SystemState {
 impl BudgetSystemState {
     pub fn new() -> Self {
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             current_state: SystemState::new(HashMap::new()),
             history: Vec::new(),
             proposals: HashMap::new(),
@@ -91,10 +105,21 @@ impl BudgetSystemState {
             votes: HashMap::new(),
             epochs: HashMap::new(),
             current_epoch: None,
+            proposal_templates: HashMap::new(),
         }
     }
 
     // Getters
+    /// Schema version this build reads and writes
+    pub fn schema_version() -> u32 {
+        CURRENT_SCHEMA_VERSION
+    }
+
+    /// Schema version of this state; always current once loaded
+    pub fn version(&self) -> u32 {
+        self.schema_version
+    }
+
     pub fn current_state(&self) -> &SystemState {
         &self.current_state
     }
@@ -123,6 +148,10 @@ impl BudgetSystemState {
         self.current_epoch
     }
 
+    pub fn proposal_templates(&self) -> &HashMap<String, ProposalTemplate> {
+        &self.proposal_templates
+    }
+
     // Setters and modifiers
     pub fn update_current_state(&mut self, new_state: SystemState) {
         self.history.push(self.current_state.clone());
@@ -194,6 +223,14 @@ impl BudgetSystemState {
         self.current_epoch = epoch_id;
     }
 
+    pub fn add_proposal_template(&mut self, template: ProposalTemplate) {
+        self.proposal_templates.insert(template.name().to_string(), template);
+    }
+
+    pub fn get_proposal_template_mut(&mut self, name: &str) -> Option<&mut ProposalTemplate> {
+        self.proposal_templates.get_mut(name)
+    }
+
     // Helper methods
     pub fn get_proposal(&self, id: &Uuid) -> Option<&Proposal> {
         self.proposals.get(id)
@@ -242,6 +279,92 @@ impl BudgetSystemState {
     pub fn epoch_count(&self) -> usize {
         self.epochs.len()
     }
+
+    /// Compares this state (before) with `other` (after), matching entities by ID.
+    pub fn diff(&self, other: &BudgetSystemState) -> StateDiff {
+        let (added_epochs, removed_epochs, modified_epochs) =
+            diff_entities(&self.epochs, &other.epochs, |e| e.name().to_string());
+        let (added_teams, removed_teams, modified_teams) =
+            diff_entities(self.current_state.teams(), other.current_state.teams(), |t| t.name().to_string());
+        let (added_proposals, removed_proposals, modified_proposals) =
+            diff_entities(&self.proposals, &other.proposals, |p| p.title().to_string());
+
+        let vote_label = |vote: &Vote| {
+            let title = other.proposals.get(&vote.proposal_id())
+                .or_else(|| self.proposals.get(&vote.proposal_id()))
+                .map_or("unknown proposal".to_string(), |p| p.title().to_string());
+            format!("Vote on '{}' ({})", title, vote.id())
+        };
+        let (added_votes, removed_votes, modified_votes) =
+            diff_entities(&self.votes, &other.votes, vote_label);
+
+        StateDiff {
+            added_epochs,
+            removed_epochs,
+            modified_epochs,
+            added_teams,
+            removed_teams,
+            modified_teams,
+            added_proposals,
+            removed_proposals,
+            modified_proposals,
+            added_votes,
+            removed_votes,
+            modified_votes,
+        }
+    }
+}
+
+type EntityDiff = (Vec<String>, Vec<String>, Vec<(String, String)>);
+
+fn diff_entities<T: Serialize>(
+    before: &HashMap<Uuid, T>,
+    after: &HashMap<Uuid, T>,
+    label: impl Fn(&T) -> String,
+) -> EntityDiff {
+    let mut added: Vec<String> = after.iter()
+        .filter(|(id, _)| !before.contains_key(id))
+        .map(|(_, entity)| label(entity))
+        .collect();
+    let mut removed: Vec<String> = before.iter()
+        .filter(|(id, _)| !after.contains_key(id))
+        .map(|(_, entity)| label(entity))
+        .collect();
+    let mut modified: Vec<(String, String)> = after.iter()
+        .filter_map(|(id, new)| {
+            let old = before.get(id)?;
+            let fields = changed_fields(old, new);
+            if fields.is_empty() {
+                None
+            } else {
+                Some((label(new), format!("changed: {}", fields.join(", "))))
+            }
+        })
+        .collect();
+
+    added.sort();
+    removed.sort();
+    modified.sort();
+    (added, removed, modified)
+}
+
+/// Names the top-level serialized fields that differ between two entities.
+fn changed_fields<T: Serialize>(old: &T, new: &T) -> Vec<String> {
+    let old = serde_json::to_value(old).unwrap_or_default();
+    let new = serde_json::to_value(new).unwrap_or_default();
+    match (old.as_object(), new.as_object()) {
+        (Some(old_fields), Some(new_fields)) => {
+            let mut keys: Vec<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter()
+                .filter(|key| old_fields.get(*key) != new_fields.get(*key))
+                .map(|key| key.to_string())
+                .collect()
+        },
+        _ if old != new => vec!["value".to_string()],
+        _ => Vec::new(),
+    }
 }
 
 #[cfg(test)]
@@ -807,4 +930,33 @@ mod tests {
         assert!(state.timestamp() > initial_timestamp);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_state_diff() {
+        let mut before = BudgetSystemState::new();
+        let kept_team = before.add_team(create_test_team("Kept Team"));
+        before.add_team(create_test_team("Removed Team"));
+        before.add_epoch(&create_test_epoch());
+        let proposal = Proposal::new(Uuid::new_v4(), "Proposal".to_string(), None, None, None, None, None);
+        let proposal_id = before.add_proposal(&proposal);
+
+        let mut after: BudgetSystemState = serde_json::from_str(&serde_json::to_string(&before).unwrap()).unwrap();
+        assert!(before.diff(&after).is_empty());
+
+        let removed_id = *after.current_state().teams().iter()
+            .find(|(_, t)| t.name() == "Removed Team").unwrap().0;
+        after.remove_team(removed_id);
+        after.add_team(create_test_team("New Team"));
+        after.get_team_mut(&kept_team).unwrap().set_representative("New Rep".to_string());
+        after.get_proposal_mut(&proposal_id).unwrap().set_url(Some("https://example.com".to_string()));
+        after.add_vote(&Vote::new(proposal_id, Uuid::new_v4(), VoteType::Informal, false));
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added_teams, vec!["New Team".to_string()]);
+        assert_eq!(diff.removed_teams, vec!["Removed Team".to_string()]);
+        assert_eq!(diff.modified_teams, vec![("Kept Team".to_string(), "changed: representative".to_string())]);
+        assert_eq!(diff.modified_proposals, vec![("Proposal".to_string(), "changed: url".to_string())]);
+        assert_eq!(diff.added_votes.len(), 1);
+        assert!(diff.added_votes[0].starts_with("Vote on 'Proposal'"));
+        assert!(diff.added_epochs.is_empty() && diff.removed_epochs.is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::core::budget_system::BudgetSystem;
-use crate::services::ethereum::EthereumService;
+use crate::services::ethereum::{EthereumService, RetryPolicy};
 use crate::app_config::AppConfig;
 use crate::commands::common::Command;
 use std::sync::Arc;
@@ -11,6 +11,9 @@ pub mod commands;
 pub mod app_config;
 pub mod lock;
 
+/// How often the Telegram bot closes votes and proposals past their deadlines.
+const DEADLINE_CHECK_SECS: u64 = 60;
+
 pub fn initialize_environment() {
     pretty_env_logger::init();
     dotenv().expect(".env file not found");
@@ -18,18 +21,45 @@ pub fn initialize_environment() {
 
 pub async fn initialize_system() -> Result<(BudgetSystem, AppConfig), Box<dyn std::error::Error>> {
     let config = AppConfig::new()?;
-    let ethereum_service = Arc::new(EthereumService::new(&config.ipc_path, config.future_block_offset).await?);
+    let retry_policy = RetryPolicy::new(
+        config.eth_retry.max_attempts,
+        std::time::Duration::from_millis(config.eth_retry.base_delay_ms),
+        std::time::Duration::from_millis(config.eth_retry.jitter_ms),
+    ).with_max_delay(std::time::Duration::from_millis(config.eth_retry.max_delay_ms));
+    let ethereum_service = Arc::new(EthereumService::new(config.rpc_endpoint(), config.future_block_offset, retry_policy).await?);
     let state = crate::core::file_system::FileSystem::try_load_state(&config.state_file);
     let budget_system = BudgetSystem::new(config.clone(), ethereum_service, state).await?;
     Ok((budget_system, config))
 }
 
-pub async fn run_script_commands(command: Command) -> Result<(), Box<dyn std::error::Error>> {
+/// Closes votes whose deadline has passed, then proposals left undecided past their
+/// epoch's voting window. Failures are logged so they don't block the command or
+/// bot that triggered the check.
+pub fn enforce_deadlines(budget_system: &mut BudgetSystem) {
+    match budget_system.close_expired_votes() {
+        Ok(closed) if !closed.is_empty() => log::info!("Closed {} vote(s) past their deadline", closed.len()),
+        Ok(_) => {},
+        Err(e) => log::warn!("Failed to close expired votes: {}", e),
+    }
+    match budget_system.enforce_announcement_windows() {
+        Ok(closed) if !closed.is_empty() => log::info!("Closed {} proposal(s) past their voting window", closed.len()),
+        Ok(_) => {},
+        Err(e) => log::warn!("Failed to enforce proposal voting windows: {}", e),
+    }
+}
+
+/// Runs `command` and saves the state; with `dry_run` nothing is saved and the
+/// changes the command would make are printed instead.
+pub async fn run_script_commands(command: Command, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
     let (mut budget_system, config) = initialize_system().await?;
-    lock::create_lock_file()?;
-    
     let mut stdout = std::io::stdout();
-    let result = commands::cli::execute_command(&mut budget_system, command, &config, &mut stdout).await;
+    if dry_run {
+        return commands::cli::execute_command(&mut budget_system, command, &config, &mut stdout, true).await;
+    }
+
+    lock::create_lock_file()?;
+    enforce_deadlines(&mut budget_system);
+    let result = commands::cli::execute_command(&mut budget_system, command, &config, &mut stdout, false).await;
     
     budget_system.save_state()?;
     lock::remove_lock_file()?;
@@ -42,9 +72,26 @@ pub async fn run_telegram_bot() -> Result<(), Box<dyn std::error::Error>> {
     let (command_sender, command_receiver) = tokio::sync::mpsc::channel(100);
     
     crate::services::telegram::spawn_command_executor(budget_system, command_receiver);
+    crate::services::telegram::spawn_deadline_check(
+        tokio::time::Duration::from_secs(DEADLINE_CHECK_SECS),
+        command_sender.clone(),
+    );
     
     let bot = teloxide::Bot::new(&config.telegram.token);
-    let telegram_bot = crate::services::telegram::TelegramBot::new(bot, command_sender);
+
+    match config.telegram.chat_id.parse::<i64>() {
+        Ok(chat_id) => crate::services::telegram::spawn_overdue_payment_check(
+            bot.clone(),
+            teloxide::types::ChatId(chat_id),
+            config.telegram.overdue_payment_days,
+            tokio::time::Duration::from_secs(24 * 60 * 60),
+            command_sender.clone(),
+        ),
+        Err(_) => log::warn!("No valid telegram.chat_id configured, overdue payment notifications disabled"),
+    }
+
+    let access = crate::services::telegram::ChatAccess::new(&config.telegram);
+    let telegram_bot = crate::services::telegram::TelegramBot::new(bot, command_sender, access);
     
     telegram_bot.run().await;
     Ok(())
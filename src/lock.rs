@@ -20,6 +20,8 @@ pub fn remove_lock_file() -> Result<(), Error> {
     remove_lock_file_at(&get_lock_file_path())
 }
 
+/// Creates the lock file with `create_new`, which fails atomically if another
+/// process already holds the lock.
 pub fn create_lock_file_at(path: &Path) -> Result<(), Error> {
     match OpenOptions::new().write(true).create_new(true).open(path) {
         Ok(_) => Ok(()),
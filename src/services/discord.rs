@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Embed colour for approved proposals and passed votes.
+pub const COLOR_GREEN: u32 = 0x2ECC71;
+/// Embed colour for rejected proposals and failed votes.
+pub const COLOR_RED: u32 = 0xE74C3C;
+/// Embed colour for events without an outcome, such as an epoch opening.
+pub const COLOR_BLUE: u32 = 0x3498DB;
+
+/// Body of a Discord webhook execution.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DiscordPayload {
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeds: Option<Vec<DiscordEmbed>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DiscordEmbed {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub color: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<DiscordEmbedField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DiscordEmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+impl DiscordPayload {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self { content: content.into(), embeds: None }
+    }
+
+    pub fn with_embed(mut self, embed: DiscordEmbed) -> Self {
+        self.embeds.get_or_insert_with(Vec::new).push(embed);
+        self
+    }
+}
+
+impl DiscordEmbed {
+    pub fn new(title: impl Into<String>, color: u32) -> Self {
+        Self { title: title.into(), description: None, color, fields: Vec::new() }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<String>, inline: bool) -> Self {
+        self.fields.push(DiscordEmbedField { name: name.into(), value: value.into(), inline });
+        self
+    }
+}
+
+#[async_trait]
+pub trait DiscordServiceTrait: Send + Sync {
+    async fn send_notification(&self, payload: DiscordPayload) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Posts notifications to a Discord channel through an incoming webhook.
+pub struct DiscordWebhook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl DiscordWebhook {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { client: reqwest::Client::new(), url: url.into() }
+    }
+}
+
+#[async_trait]
+impl DiscordServiceTrait for DiscordWebhook {
+    async fn send_notification(&self, payload: DiscordPayload) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.client.post(&self.url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Records every payload instead of sending it.
+#[derive(Default)]
+pub struct MockDiscordService {
+    sent: Mutex<Vec<DiscordPayload>>,
+}
+
+impl MockDiscordService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sent(&self) -> Vec<DiscordPayload> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl DiscordServiceTrait for MockDiscordService {
+    async fn send_notification(&self, payload: DiscordPayload) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.sent.lock().unwrap().push(payload);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_serialization() {
+        let payload = DiscordPayload::new("Vote closed")
+            .with_embed(DiscordEmbed::new("Proposal A", COLOR_GREEN).field("Result", "Approved", true));
+        let json = serde_json::to_value(&payload).unwrap();
+
+        assert_eq!(json["content"], "Vote closed");
+        assert_eq!(json["embeds"][0]["color"], COLOR_GREEN);
+        assert_eq!(json["embeds"][0]["fields"][0]["value"], "Approved");
+        assert!(json["embeds"][0].get("description").is_none());
+
+        let plain = serde_json::to_value(DiscordPayload::new("Epoch activated")).unwrap();
+        assert!(plain.get("embeds").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mock_records_payloads() {
+        let service = MockDiscordService::new();
+        service.send_notification(DiscordPayload::new("one")).await.unwrap();
+        service.send_notification(DiscordPayload::new("two")).await.unwrap();
+
+        let contents: Vec<_> = service.sent().into_iter().map(|p| p.content).collect();
+        assert_eq!(contents, vec!["one", "two"]);
+    }
+}
@@ -1,8 +1,12 @@
 use ethers::prelude::*;
-use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, atomic::{AtomicU32, AtomicU64, Ordering}};
+use std::time::{SystemTime, UNIX_EPOCH};
 use async_trait::async_trait;
 use tokio::{
     self,
+    sync::broadcast,
     time::Duration,
 };
 use downcast_rs::{impl_downcast, DowncastSync};
@@ -12,66 +16,362 @@ pub trait EthereumServiceTrait: DowncastSync {
     async fn get_current_block(&self) -> Result<u64, Box<dyn std::error::Error>>;
     async fn get_randomness(&self, block_number: u64) -> Result<String, Box<dyn std::error::Error>>;
     async fn get_raffle_randomness(&self) -> Result<(u64, u64, String), Box<dyn std::error::Error>>;
+    /// Looks up a transaction by hash; `Ok(None)` when the chain has no such transaction.
+    async fn get_transaction(&self, tx_hash: &str) -> Result<Option<TxInfo>, Box<dyn std::error::Error>>;
+    /// Receives an event each time a call fails and is about to be retried, if the service retries.
+    fn subscribe_retries(&self) -> Option<broadcast::Receiver<RetryEvent>> {
+        None
+    }
 }
 
 impl_downcast!(sync EthereumServiceTrait);
 
+/// How failed RPC calls are retried: up to `max_attempts` tries in total, waiting
+/// `base_delay * 2^(n-1)`, capped at `max_delay`, plus up to `jitter` after the n-th failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration, jitter: Duration) -> Self {
+        Self { max_attempts: max_attempts.max(1), base_delay, max_delay: Duration::MAX, jitter }
+    }
+
+    /// Caps the backoff, before jitter, at `max_delay`.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// A policy that gives up after the first failure.
+    pub fn no_retry() -> Self {
+        Self::new(1, Duration::ZERO, Duration::ZERO)
+    }
+
+    /// Delay before retrying after the `attempt`-th (1-based) failure.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let jitter_ms = self.jitter.as_millis() as u64;
+        if jitter_ms == 0 {
+            return backoff;
+        }
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos() as u64).unwrap_or(0);
+        backoff + Duration::from_millis(nanos % (jitter_ms + 1))
+    }
+}
+
+/// A failed call that is about to be retried.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryEvent {
+    /// Number of the attempt that failed, starting at 1
+    pub attempt: u32,
+    pub error: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EthereumError {
+    /// Every try of a call failed with a transient error
+    MaxRetriesExceeded { attempts: u32, last_error: String },
+}
+
+impl std::fmt::Display for EthereumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EthereumError::MaxRetriesExceeded { attempts, last_error } =>
+                write!(f, "Ethereum call failed after {} attempt(s): {}", attempts, last_error),
+        }
+    }
+}
+
+impl std::error::Error for EthereumError {}
+
+/// Whether a failed provider call may succeed if tried again. Transport failures
+/// (I/O, HTTP, dropped connections, timeouts) are; errors answered by the node are not.
+fn is_transient(error: &ProviderError) -> bool {
+    match error {
+        ProviderError::JsonRpcClientError(e) => e.as_error_response().is_none(),
+        ProviderError::HTTPError(_) => true,
+        _ => false,
+    }
+}
+
+/// Runs `op` until it succeeds, fails with an error `retryable` rejects, or
+/// `policy.max_attempts` is reached, announcing each retry on `events`.
+async fn call_with_retry<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    events: &broadcast::Sender<RetryEvent>,
+    retryable: impl Fn(&E) -> bool,
+    mut op: F,
+) -> Result<T, Box<dyn std::error::Error>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let mut attempt = 1;
+    loop {
+        let error = match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if !retryable(&e) => return Err(e.to_string().into()),
+            Err(e) => e.to_string(),
+        };
+        if attempt >= policy.max_attempts {
+            return Err(Box::new(EthereumError::MaxRetriesExceeded { attempts: attempt, last_error: error }));
+        }
+        let delay = policy.delay(attempt);
+        log::warn!(
+            "Ethereum call failed (attempt {} of {}), retrying in {:?}: {}",
+            attempt, policy.max_attempts, delay, error
+        );
+        let _ = events.send(RetryEvent { attempt, error });
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// On-chain details of a transaction, as needed to verify a recorded payment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxInfo {
+    pub hash: String,
+    pub from: Address,
+    pub to: Option<Address>,
+    pub block_number: Option<u64>,
+    /// ETH sent directly to `to`
+    pub value: U256,
+    /// ERC-20 transfers emitted while the transaction ran, in log order
+    pub token_transfers: Vec<TokenTransfer>,
+}
+
+impl TxInfo {
+    /// Addresses the transaction pays: `to` when it carries ETH, then every
+    /// ERC-20 transfer recipient. ETH moved by a contract call (e.g. a Safe
+    /// batch) leaves no trace here.
+    pub fn paid_addresses(&self) -> Vec<Address> {
+        let direct = self.to.filter(|_| !self.value.is_zero());
+        direct.into_iter()
+            .chain(self.token_transfers.iter().map(|transfer| transfer.to))
+            .collect()
+    }
+}
+
+/// An ERC-20 `Transfer` event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenTransfer {
+    /// Token contract that emitted the event
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: U256,
+}
+
+/// Decodes the ERC-20 `Transfer(address,address,uint256)` events among `logs`.
+/// ERC-721 transfers share the signature but index the token id, so they carry
+/// four topics and are skipped.
+pub fn decode_token_transfers(logs: &[Log]) -> Vec<TokenTransfer> {
+    let transfer_topic = H256::from(ethers::utils::keccak256("Transfer(address,address,uint256)"));
+    logs.iter()
+        .filter(|log| log.topics.len() == 3 && log.topics[0] == transfer_topic)
+        .map(|log| TokenTransfer {
+            token: log.address,
+            from: Address::from(log.topics[1]),
+            to: Address::from(log.topics[2]),
+            amount: U256::from_big_endian(&log.data),
+        })
+        .collect()
+}
+
+/// Parses a `0x`-prefixed, 40 hex digit address, rejecting the zero address.
+/// Mixed-case addresses must carry a valid EIP-55 checksum; all-lowercase and
+/// all-uppercase addresses are accepted as unchecksummed.
+pub fn validate_ethereum_address(addr: &str) -> Result<H160, String> {
+    parse_ethereum_address(addr).map_err(|e| format!("{}: {}", e, addr))
+}
+
+/// `validate_ethereum_address` for callers that report errors as `&'static str`.
+pub(crate) fn parse_ethereum_address(addr: &str) -> Result<H160, &'static str> {
+    let hex = addr.strip_prefix("0x").ok_or("Ethereum address must start with 0x")?;
+    if hex.len() != 40 {
+        return Err("Ethereum address must be 42 characters long");
+    }
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Invalid hex characters in address");
+    }
+    let address = H160::from_str(&hex.to_lowercase()).map_err(|_| "Invalid Ethereum address")?;
+    if address.is_zero() {
+        return Err("Ethereum address cannot be the zero address");
+    }
+    let mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && ethers::utils::to_checksum(&address, None)[2..] != *hex {
+        return Err("Invalid EIP-55 checksum in Ethereum address");
+    }
+    Ok(address)
+}
+
+/// Recipient of the canned transaction returned by `MockEthereumService`.
+pub const MOCK_TX_RECIPIENT: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
 pub struct EthereumService {
-    client: Arc<Provider<Ipc>>,
+    client: Arc<RpcProvider>,
     future_block_offset: u64,
+    retry_policy: RetryPolicy,
+    retry_events: broadcast::Sender<RetryEvent>,
+}
+
+/// Transport used to reach the node, chosen from the shape of the endpoint string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RpcTransport {
+    Ipc,
+    Http,
+    Ws,
+}
+
+impl RpcTransport {
+    /// `http(s)://` and `ws(s)://` URLs select the matching transport; anything else is
+    /// treated as a filesystem path to an IPC socket.
+    pub fn detect(endpoint: &str) -> Self {
+        let scheme = endpoint.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase());
+        match scheme.as_deref() {
+            Some("http") | Some("https") => RpcTransport::Http,
+            Some("ws") | Some("wss") => RpcTransport::Ws,
+            _ => RpcTransport::Ipc,
+        }
+    }
+}
+
+enum RpcProvider {
+    Ipc(Provider<Ipc>),
+    Http(Provider<Http>),
+    Ws(Provider<Ws>),
+}
+
+/// Runs the same provider call whichever transport is in use.
+macro_rules! with_provider {
+    ($client:expr, $provider:ident => $call:expr) => {
+        match $client {
+            RpcProvider::Ipc($provider) => $call,
+            RpcProvider::Http($provider) => $call,
+            RpcProvider::Ws($provider) => $call,
+        }
+    };
 }
 
 pub struct MockEthereumService {
     current_block: Arc<AtomicU64>,
+    transaction_recipient: Mutex<Option<Address>>,
+    transaction_value: Mutex<U256>,
+    token_transfers: Mutex<Vec<TokenTransfer>>,
+    failures_remaining: AtomicU32,
+    retry_policy: RetryPolicy,
+    retry_events: broadcast::Sender<RetryEvent>,
 }
 
 impl EthereumService {
-    pub async fn new(ipc_path: &str, future_block_offset: u64) -> Result<Self, Box<dyn std::error::Error>> {
-        let provider = Provider::connect_ipc(ipc_path).await?;
+    /// Connects to `endpoint`, which may be an `http(s)://` or `ws(s)://` URL or an IPC socket path.
+    pub async fn new(endpoint: &str, future_block_offset: u64, retry_policy: RetryPolicy) -> Result<Self, Box<dyn std::error::Error>> {
+        let provider = match RpcTransport::detect(endpoint) {
+            RpcTransport::Http => RpcProvider::Http(Provider::<Http>::try_from(endpoint)?),
+            RpcTransport::Ws => RpcProvider::Ws(Provider::<Ws>::connect(endpoint).await?),
+            RpcTransport::Ipc => RpcProvider::Ipc(Provider::connect_ipc(endpoint).await?),
+        };
         Ok(Self {
             client: Arc::new(provider),
             future_block_offset,
+            retry_policy,
+            retry_events: broadcast::channel(16).0,
         })
     }
 
     async fn get_current_block(&self) -> Result<u64, Box<dyn std::error::Error>> {
-        Ok(self.client.get_block_number().await?.as_u64())
+        let block_number = call_with_retry(&self.retry_policy, &self.retry_events, is_transient, || async {
+            with_provider!(&*self.client, p => p.get_block_number().await)
+        }).await?;
+        Ok(block_number.as_u64())
     }
 
     async fn get_randomness(&self, block_number: u64) -> Result<String, Box<dyn std::error::Error>> {
-        let block = self.client.get_block(block_number).await?
+        let block = call_with_retry(&self.retry_policy, &self.retry_events, is_transient, || async {
+            with_provider!(&*self.client, p => p.get_block(block_number).await)
+        }).await?
             .ok_or("Block not found")?;
         block.mix_hash
             .ok_or_else(|| "Randomness not found".into())
             .map(|hash| format!("0x{:x}", hash))
     }
 
+    async fn get_transaction(&self, tx_hash: &str) -> Result<Option<TxInfo>, Box<dyn std::error::Error>> {
+        let hash = tx_hash.parse::<H256>().map_err(|_| "Invalid transaction hash")?;
+        let transaction = call_with_retry(&self.retry_policy, &self.retry_events, is_transient, || async {
+            with_provider!(&*self.client, p => p.get_transaction(hash).await)
+        }).await?;
+        let Some(tx) = transaction else {
+            return Ok(None);
+        };
+        let receipt = call_with_retry(&self.retry_policy, &self.retry_events, is_transient, || async {
+            with_provider!(&*self.client, p => p.get_transaction_receipt(hash).await)
+        }).await?;
+        Ok(Some(TxInfo {
+            hash: tx_hash.to_string(),
+            from: tx.from,
+            to: tx.to,
+            block_number: tx.block_number.map(|n| n.as_u64()),
+            value: tx.value,
+            token_transfers: receipt.map(|r| decode_token_transfers(&r.logs)).unwrap_or_default(),
+        }))
+    }
 }
 
 impl MockEthereumService {
     pub fn new() -> Self {
         Self {
             current_block: Arc::new(AtomicU64::new(12345)),
+            transaction_recipient: Mutex::new(Some(MOCK_TX_RECIPIENT.parse().unwrap())),
+            transaction_value: Mutex::new(U256::exp10(18)),
+            token_transfers: Mutex::new(Vec::new()),
+            failures_remaining: AtomicU32::new(0),
+            retry_policy: RetryPolicy::new(3, Duration::from_millis(1), Duration::ZERO),
+            retry_events: broadcast::channel(16).0,
         }
     }
 
+    /// Makes the next `count` block number lookups fail transiently, to exercise retries.
+    pub fn set_failure_count(&self, count: u32) {
+        self.failures_remaining.store(count, Ordering::SeqCst);
+    }
+
     pub fn increment_block(&self) {
         self.current_block.fetch_add(1, Ordering::SeqCst);
     }
+
+    /// Sets the recipient of the canned transaction; `None` makes every lookup miss.
+    pub fn set_transaction_recipient(&self, recipient: Option<Address>) {
+        *self.transaction_recipient.lock().unwrap() = recipient;
+    }
+
+    /// Sets the ETH the canned transaction sends to its recipient; one ether by default.
+    pub fn set_transaction_value(&self, value: U256) {
+        *self.transaction_value.lock().unwrap() = value;
+    }
+
+    /// Sets the ERC-20 transfers the canned transaction emits; none by default.
+    pub fn set_token_transfers(&self, transfers: Vec<TokenTransfer>) {
+        *self.token_transfers.lock().unwrap() = transfers;
+    }
 }
 
 #[async_trait]
 impl EthereumServiceTrait for EthereumService {
     async fn get_current_block(&self) -> Result<u64, Box<dyn std::error::Error>> {
-        Ok(self.client.get_block_number().await?.as_u64())
+        EthereumService::get_current_block(self).await
     }
 
     async fn get_randomness(&self, block_number: u64) -> Result<String, Box<dyn std::error::Error>> {
-        let block = self.client.get_block(block_number).await?
-            .ok_or("Block not found")?;
-        block.mix_hash
-            .ok_or_else(|| "Randomness not found".into())
-            .map(|hash| format!("0x{:x}", hash))
+        EthereumService::get_randomness(self, block_number).await
     }
 
     async fn get_raffle_randomness(&self) -> Result<(u64, u64, String), Box<dyn std::error::Error>> {
@@ -86,12 +386,29 @@ impl EthereumServiceTrait for EthereumService {
 
         Ok((initiation_block, randomness_block, randomness))
     }
+
+    async fn get_transaction(&self, tx_hash: &str) -> Result<Option<TxInfo>, Box<dyn std::error::Error>> {
+        EthereumService::get_transaction(self, tx_hash).await
+    }
+
+    fn subscribe_retries(&self) -> Option<broadcast::Receiver<RetryEvent>> {
+        Some(self.retry_events.subscribe())
+    }
 }
 
 #[async_trait::async_trait]
 impl EthereumServiceTrait for MockEthereumService {
     async fn get_current_block(&self) -> Result<u64, Box<dyn std::error::Error>> {
-        Ok(self.current_block.load(Ordering::SeqCst))
+        call_with_retry(&self.retry_policy, &self.retry_events, |_: &&str| true, || async {
+            let failing = self.failures_remaining
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if failing {
+                Err("mock RPC failure")
+            } else {
+                Ok(self.current_block.load(Ordering::SeqCst))
+            }
+        }).await
     }
 
     async fn get_randomness(&self, block_number: u64) -> Result<String, Box<dyn std::error::Error>> {
@@ -102,4 +419,162 @@ impl EthereumServiceTrait for MockEthereumService {
         let current = self.current_block.load(Ordering::SeqCst);
         Ok((current, current + 10, format!("mock_randomness_for_block_{}", current + 10)))
     }
-}
\ No newline at end of file
+
+    async fn get_transaction(&self, tx_hash: &str) -> Result<Option<TxInfo>, Box<dyn std::error::Error>> {
+        let recipient = *self.transaction_recipient.lock().unwrap();
+        Ok(recipient.map(|to| TxInfo {
+            hash: tx_hash.to_string(),
+            from: Address::zero(),
+            to: Some(to),
+            block_number: Some(self.current_block.load(Ordering::SeqCst)),
+            value: *self.transaction_value.lock().unwrap(),
+            token_transfers: self.token_transfers.lock().unwrap().clone(),
+        }))
+    }
+
+    fn subscribe_retries(&self) -> Option<broadcast::Receiver<RetryEvent>> {
+        Some(self.retry_events.subscribe())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_transport_detect() {
+        assert_eq!(RpcTransport::detect("http://localhost:8545"), RpcTransport::Http);
+        assert_eq!(RpcTransport::detect("https://mainnet.infura.io/v3/key"), RpcTransport::Http);
+        assert_eq!(RpcTransport::detect("HTTPS://eth.example.com"), RpcTransport::Http);
+        assert_eq!(RpcTransport::detect("ws://localhost:8546"), RpcTransport::Ws);
+        assert_eq!(RpcTransport::detect("wss://eth.example.com/ws"), RpcTransport::Ws);
+        assert_eq!(RpcTransport::detect("/tmp/reth.ipc"), RpcTransport::Ipc);
+        assert_eq!(RpcTransport::detect("~/.reth/reth.ipc"), RpcTransport::Ipc);
+    }
+
+    #[tokio::test]
+    async fn test_http_endpoint_constructs_without_connecting() {
+        let service = EthereumService::new("http://127.0.0.1:1", 10, RetryPolicy::no_retry()).await.unwrap();
+        assert!(matches!(*service.client, RpcProvider::Http(_)));
+        assert!(service.get_current_block().await.is_err());
+    }
+
+    #[test]
+    fn test_decode_token_transfers() {
+        let token: Address = "0x6b175474e89094c44da98b954eedeac495271d0f".parse().unwrap();
+        let from: Address = "0x1111111111111111111111111111111111111111".parse().unwrap();
+        let to: Address = "0x2222222222222222222222222222222222222222".parse().unwrap();
+        let transfer_topic = H256::from(ethers::utils::keccak256("Transfer(address,address,uint256)"));
+        let mut amount = [0u8; 32];
+        U256::from(500).to_big_endian(&mut amount);
+
+        let erc20 = Log {
+            address: token,
+            topics: vec![transfer_topic, H256::from(from), H256::from(to)],
+            data: amount.to_vec().into(),
+            ..Default::default()
+        };
+        let erc721 = Log {
+            topics: vec![transfer_topic, H256::from(from), H256::from(to), H256::from_low_u64_be(7)],
+            ..erc20.clone()
+        };
+        let other = Log { topics: vec![H256::zero()], ..erc20.clone() };
+
+        assert_eq!(decode_token_transfers(&[erc20, erc721, other]), vec![TokenTransfer { token, from, to, amount: U256::from(500) }]);
+    }
+
+    #[test]
+    fn test_paid_addresses() {
+        let safe: Address = "0x3333333333333333333333333333333333333333".parse().unwrap();
+        let team: Address = "0x2222222222222222222222222222222222222222".parse().unwrap();
+        let mut tx = TxInfo {
+            hash: String::new(),
+            from: Address::zero(),
+            to: Some(safe),
+            block_number: None,
+            value: U256::zero(),
+            token_transfers: Vec::new(),
+        };
+        assert!(tx.paid_addresses().is_empty());
+
+        tx.token_transfers.push(TokenTransfer { token: Address::zero(), from: safe, to: team, amount: U256::one() });
+        assert_eq!(tx.paid_addresses(), vec![team]);
+
+        tx.value = U256::one();
+        assert_eq!(tx.paid_addresses(), vec![safe, team]);
+    }
+
+    #[test]
+    fn test_retry_policy_delay_backs_off() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100), Duration::ZERO);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+
+        let jittered = RetryPolicy::new(5, Duration::from_millis(100), Duration::from_millis(50));
+        let delay = jittered.delay(1);
+        assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(150));
+
+        let capped = policy.with_max_delay(Duration::from_millis(300));
+        assert_eq!(capped.delay(2), Duration::from_millis(200));
+        assert_eq!(capped.delay(3), Duration::from_millis(300));
+        assert_eq!(capped.delay(40), Duration::from_millis(300));
+
+        assert_eq!(RetryPolicy::new(0, Duration::ZERO, Duration::ZERO).max_attempts, 1);
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let node_error = ProviderError::from(HttpClientError::JsonRpcError(JsonRpcError {
+            code: -32000,
+            message: "header not found".to_string(),
+            data: None,
+        }));
+        assert!(!is_transient(&node_error));
+        assert!(!is_transient(&ProviderError::CustomError("bad input".to_string())));
+
+        let garbled = serde_json::from_str::<u64>("<html>").unwrap_err();
+        let transport_error = ProviderError::from(HttpClientError::SerdeJson { err: garbled, text: "<html>".to_string() });
+        assert!(is_transient(&transport_error));
+    }
+
+    #[tokio::test]
+    async fn test_mock_retries_transient_failures() {
+        let service = MockEthereumService::new();
+        let mut events = service.subscribe_retries().unwrap();
+
+        service.set_failure_count(2);
+        assert_eq!(service.get_current_block().await.unwrap(), 12345);
+        assert_eq!(events.recv().await.unwrap(), RetryEvent { attempt: 1, error: "mock RPC failure".to_string() });
+        assert_eq!(events.recv().await.unwrap().attempt, 2);
+
+        service.set_failure_count(3);
+        let error = service.get_current_block().await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<EthereumError>(),
+            Some(&EthereumError::MaxRetriesExceeded { attempts: 3, last_error: "mock RPC failure".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_validate_ethereum_address() {
+        let expected = H160::from_str("742d35cc6634c0532925a3b844bc454e4438f44e").unwrap();
+        assert_eq!(validate_ethereum_address("0x742d35Cc6634C0532925a3b844Bc454e4438f44e"), Ok(expected));
+        assert_eq!(validate_ethereum_address("0x742d35cc6634c0532925a3b844bc454e4438f44e"), Ok(expected));
+        assert_eq!(validate_ethereum_address("0x742D35CC6634C0532925A3B844BC454E4438F44E"), Ok(expected));
+
+        // Mixed case must match the EIP-55 checksum
+        let err = validate_ethereum_address("0x742d35cC6634C0532925a3b844Bc454e4438f44e").unwrap_err();
+        assert!(err.contains("EIP-55 checksum"));
+        assert!(validate_ethereum_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok());
+        assert!(validate_ethereum_address("0x5aAeb6053f3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+
+        let err = validate_ethereum_address("0x742d35Cc6634C0532925a3b844Bc454e4438f4").unwrap_err();
+        assert!(err.contains("42 characters"));
+        assert!(validate_ethereum_address("0x742d35Cc6634C0532925a3b844Bc454e4438f44e00").unwrap_err().contains("42 characters"));
+        assert!(validate_ethereum_address("0x742d35Cc6634C0532925a3b844Bc454e4438f4zz").unwrap_err().contains("hex"));
+        assert!(validate_ethereum_address("742d35Cc6634C0532925a3b844Bc454e4438f44e").unwrap_err().contains("start with 0x"));
+        assert!(validate_ethereum_address("0x0000000000000000000000000000000000000000").unwrap_err().contains("zero address"));
+        assert!(validate_ethereum_address("").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use std::io::{self, Write};
+use serde::{Serialize, Deserialize};
+
+/// Delimited text formats for spreadsheet exports. TSV pastes cleanly into
+/// Google Sheets, where amounts like "1,000" would otherwise split columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Tsv,
+}
+
+impl ExportFormat {
+    pub fn delimiter(&self) -> char {
+        match self {
+            ExportFormat::Csv => ',',
+            ExportFormat::Tsv => '\t',
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" | "," => Ok(ExportFormat::Csv),
+            "tsv" | "\t" | "tab" => Ok(ExportFormat::Tsv),
+            _ => Err(format!("Invalid export format: {}. Use csv or tsv", s)),
+        }
+    }
+}
+
+/// Writes rows separated by `delimiter`, one row per line. Fields containing the
+/// delimiter, a double quote or a line break are quoted, with embedded quotes doubled.
+pub fn write_delimited<W: Write>(rows: &[Vec<String>], delimiter: char, writer: &mut W) -> io::Result<()> {
+    for row in rows {
+        let line = row.iter()
+            .map(|field| quote_field(field, delimiter))
+            .collect::<Vec<_>>()
+            .join(&delimiter.to_string());
+        writeln!(writer, "{}", line)?;
+    }
+    Ok(())
+}
+
+fn quote_field(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter) || field.contains('"') || field.contains('\n') || field.contains('\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(rows: &[Vec<String>], delimiter: char) -> String {
+        let mut buffer = Vec::new();
+        write_delimited(rows, delimiter, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_write_delimited_quoting() {
+        let rows = vec![
+            vec!["Title".to_string(), "Amounts".to_string()],
+            vec!["Plain".to_string(), "100 ETH, 1000 USDC".to_string()],
+            vec!["Say \"hi\"".to_string(), "line\nbreak".to_string()],
+        ];
+
+        assert_eq!(
+            render(&rows, ','),
+            "Title,Amounts\nPlain,\"100 ETH, 1000 USDC\"\n\"Say \"\"hi\"\"\",\"line\nbreak\"\n"
+        );
+
+        // Commas need no quoting in TSV, tabs do
+        let rows = vec![vec!["100 ETH, 1000 USDC".to_string(), "a\tb".to_string()]];
+        assert_eq!(render(&rows, '\t'), "100 ETH, 1000 USDC\t\"a\tb\"\n");
+    }
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!("csv".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+        assert_eq!("TSV".parse::<ExportFormat>().unwrap(), ExportFormat::Tsv);
+        assert_eq!("\t".parse::<ExportFormat>().unwrap(), ExportFormat::Tsv);
+        assert!("xlsx".parse::<ExportFormat>().is_err());
+        assert_eq!(ExportFormat::Tsv.delimiter(), '\t');
+    }
+}
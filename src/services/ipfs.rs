@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Public gateway used for proposal document links and by default for fetching.
+pub const IPFS_GATEWAY_URL: &str = "https://ipfs.io";
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz234567";
+
+/// Checks that `hash` looks like an IPFS content identifier: a CIDv0 (`Qm` followed by
+/// base58, 46 characters) or a base32 CIDv1 of a dag-pb/sha2-256 document (`bafy`, 59 characters).
+pub fn validate_ipfs_hash(hash: &str) -> Result<(), &'static str> {
+    if hash.starts_with("Qm") {
+        if hash.len() != 46 {
+            return Err("CIDv0 IPFS hash must be 46 characters long");
+        }
+        if !hash.chars().all(|c| BASE58_ALPHABET.contains(c)) {
+            return Err("Invalid base58 characters in IPFS hash");
+        }
+        Ok(())
+    } else if hash.starts_with("bafy") {
+        if hash.len() != 59 {
+            return Err("CIDv1 IPFS hash must be 59 characters long");
+        }
+        if !hash.chars().all(|c| BASE32_ALPHABET.contains(c)) {
+            return Err("Invalid base32 characters in IPFS hash");
+        }
+        Ok(())
+    } else {
+        Err("IPFS hash must be a CIDv0 (Qm...) or CIDv1 (bafy...)")
+    }
+}
+
+/// Gateway link to an IPFS document.
+pub fn ipfs_gateway_url(hash: &str) -> String {
+    format!("{}/ipfs/{}", IPFS_GATEWAY_URL, hash)
+}
+
+#[async_trait]
+pub trait IpfsService: Send + Sync {
+    async fn fetch_document(&self, hash: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>;
+}
+
+/// Fetches documents through an HTTP gateway.
+pub struct IpfsGateway {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl IpfsGateway {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self { client: reqwest::Client::new(), base_url: base_url.into().trim_end_matches('/').to_string() }
+    }
+}
+
+impl Default for IpfsGateway {
+    fn default() -> Self {
+        Self::new(IPFS_GATEWAY_URL)
+    }
+}
+
+#[async_trait]
+impl IpfsService for IpfsGateway {
+    async fn fetch_document(&self, hash: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        validate_ipfs_hash(hash)?;
+        let bytes = self.client.get(format!("{}/ipfs/{}", self.base_url, hash))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        Ok(bytes.to_vec())
+    }
+}
+
+/// Serves documents added with `add_document`; anything else is not found.
+#[derive(Default)]
+pub struct MockIpfsService {
+    documents: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MockIpfsService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_document(&self, hash: impl Into<String>, content: impl Into<Vec<u8>>) {
+        self.documents.lock().unwrap().insert(hash.into(), content.into());
+    }
+}
+
+#[async_trait]
+impl IpfsService for MockIpfsService {
+    async fn fetch_document(&self, hash: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        validate_ipfs_hash(hash)?;
+        self.documents.lock().unwrap().get(hash)
+            .cloned()
+            .ok_or_else(|| format!("IPFS document not found: {}", hash).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CID_V0: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+    const CID_V1: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+    #[test]
+    fn test_validate_ipfs_hash() {
+        assert!(validate_ipfs_hash(CID_V0).is_ok());
+        assert!(validate_ipfs_hash(CID_V1).is_ok());
+
+        assert_eq!(validate_ipfs_hash(&CID_V0[..45]), Err("CIDv0 IPFS hash must be 46 characters long"));
+        assert_eq!(validate_ipfs_hash(&CID_V0.replace('Y', "0")), Err("Invalid base58 characters in IPFS hash"));
+        assert_eq!(validate_ipfs_hash(&CID_V1[..58]), Err("CIDv1 IPFS hash must be 59 characters long"));
+        assert_eq!(validate_ipfs_hash(&CID_V1.to_uppercase().replace("BAFY", "bafy")), Err("Invalid base32 characters in IPFS hash"));
+        assert!(validate_ipfs_hash("https://example.com/doc").is_err());
+        assert!(validate_ipfs_hash("").is_err());
+    }
+
+    #[test]
+    fn test_ipfs_gateway_url() {
+        assert_eq!(ipfs_gateway_url(CID_V0), format!("https://ipfs.io/ipfs/{}", CID_V0));
+    }
+
+    #[tokio::test]
+    async fn test_mock_serves_added_documents() {
+        let service = MockIpfsService::new();
+        service.add_document(CID_V0, "proposal text");
+
+        assert_eq!(service.fetch_document(CID_V0).await.unwrap(), b"proposal text");
+        assert!(service.fetch_document(CID_V1).await.unwrap_err().to_string().contains("not found"));
+        assert!(service.fetch_document("not-a-cid").await.is_err());
+    }
+}
@@ -1,2 +1,6 @@
+pub mod discord;
 pub mod ethereum;
+pub mod export;
+pub mod ipfs;
+pub mod safe;
 pub mod telegram;
\ No newline at end of file
@@ -0,0 +1,112 @@
+use ethers::abi::{self, Token};
+use ethers::types::{Address, U256};
+use ethers::utils::parse_units;
+use serde::Serialize;
+
+/// Selector of the ERC-20 `transfer(address,uint256)` function.
+const ERC20_TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+/// Batch in the format accepted by the Safe transaction builder app.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeBatch {
+    pub version: String,
+    pub chain_id: String,
+    pub transactions: Vec<SafeTransaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SafeTransaction {
+    pub to: String,
+    pub value: String,
+    pub data: String,
+    /// 0 for a call, 1 for a delegate call
+    pub operation: u8,
+}
+
+impl SafeBatch {
+    pub fn new(chain_id: u64, transactions: Vec<SafeTransaction>) -> Self {
+        Self { version: "1.0".to_string(), chain_id: chain_id.to_string(), transactions }
+    }
+}
+
+impl SafeTransaction {
+    /// Transfer of the chain's native token.
+    pub fn native_transfer(recipient: Address, amount: f64) -> Result<Self, String> {
+        Ok(Self {
+            to: format!("{:?}", recipient),
+            value: to_base_units(amount)?.to_string(),
+            data: "0x".to_string(),
+            operation: 0,
+        })
+    }
+
+    /// Call to `token`'s `transfer`, assuming the token has 18 decimals.
+    pub fn erc20_transfer(token: Address, recipient: Address, amount: f64) -> Result<Self, String> {
+        Ok(Self {
+            to: format!("{:?}", token),
+            value: "0".to_string(),
+            data: erc20_transfer_calldata(recipient, to_base_units(amount)?),
+            operation: 0,
+        })
+    }
+}
+
+/// Hex encoded calldata for `transfer(recipient, amount)`.
+pub fn erc20_transfer_calldata(recipient: Address, amount: U256) -> String {
+    let mut data = ERC20_TRANSFER_SELECTOR.to_vec();
+    data.extend(abi::encode(&[Token::Address(recipient), Token::Uint(amount)]));
+    format!("0x{}", hex_encode(&data))
+}
+
+/// Converts a token amount to its smallest unit with 18 decimals.
+fn to_base_units(amount: f64) -> Result<U256, String> {
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(format!("Invalid transfer amount: {}", amount));
+    }
+    parse_units(amount.to_string(), 18)
+        .map(Into::into)
+        .map_err(|e| format!("Invalid transfer amount {}: {}", amount, e))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erc20_transfer_calldata() {
+        let recipient: Address = "0x1111111111111111111111111111111111111111".parse().unwrap();
+        let data = erc20_transfer_calldata(recipient, U256::from(1_000_000u64));
+        assert_eq!(
+            data,
+            format!("0xa9059cbb{:0>64}{:0>64}", "1111111111111111111111111111111111111111", "f4240")
+        );
+    }
+
+    #[test]
+    fn test_transactions() {
+        let token: Address = "0x6b175474e89094c44da98b954eedeac495271d0f".parse().unwrap();
+        let recipient: Address = "0x2222222222222222222222222222222222222222".parse().unwrap();
+
+        let tx = SafeTransaction::erc20_transfer(token, recipient, 1.5).unwrap();
+        assert_eq!(tx.to, "0x6b175474e89094c44da98b954eedeac495271d0f");
+        assert_eq!(tx.value, "0");
+        assert!(tx.data.ends_with(&format!("{:0>64x}", 1_500_000_000_000_000_000u128)));
+
+        let tx = SafeTransaction::native_transfer(recipient, 0.25).unwrap();
+        assert_eq!(tx.value, "250000000000000000");
+        assert_eq!(tx.data, "0x");
+
+        assert!(SafeTransaction::native_transfer(recipient, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_batch_serialization() {
+        let json = serde_json::to_value(SafeBatch::new(1, Vec::new())).unwrap();
+        assert_eq!(json, serde_json::json!({ "version": "1.0", "chainId": "1", "transactions": [] }));
+    }
+}
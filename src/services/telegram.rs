@@ -1,37 +1,133 @@
 use crate::core::budget_system::BudgetSystem;
-use crate::commands::telegram::{TelegramCommand, execute_command};
+use crate::core::models::common::OverduePayment;
+use crate::app_config::TelegramConfig;
+use crate::commands::common::CommandExecutor;
+use crate::commands::telegram::{TelegramCommand, PendingVote, VoteCallback, execute_chat_command, command_failed};
 use teloxide::{
     prelude::*,
     utils::command::BotCommands,
-    types::{LinkPreviewOptions, ParseMode},
+    types::{LinkPreviewOptions, MessageId, ParseMode},
     dispatching::{
         UpdateFilterExt,
         dialogue::InMemStorage,
     },
 };
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::time::Duration;
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::Arc;
+
+/// Work for the command executor, which owns the budget system.
+pub enum ExecutorRequest {
+    /// A bot command sent from the given chat; the reply is escaped for MarkdownV2.
+    Command(TelegramCommand, ChatId, oneshot::Sender<String>),
+    /// Starts a `/start_vote` ballot for the named proposal.
+    StartVote(String, oneshot::Sender<Result<PendingVote, String>>),
+    /// Records a completed `/start_vote` ballot; the reply is plain text.
+    SubmitVote(PendingVote, oneshot::Sender<String>),
+    /// Closes votes and proposals past their deadlines; the reply signals completion.
+    EnforceDeadlines(oneshot::Sender<()>),
+    /// Lists payments approved more than the given number of days ago and still unpaid.
+    OverduePayments(u64, oneshot::Sender<Result<Vec<OverduePayment>, String>>),
+}
+
+/// In-progress `/start_vote` ballots, one per chat, shared between the bot's handlers.
+type PendingVotes = Arc<Mutex<ChatBallots>>;
+
+/// The open `/start_vote` ballot of each chat with the message carrying its keyboard.
+/// Button presses only count on that message, so an older keyboard in the same chat
+/// can't change or submit a newer ballot.
+#[derive(Debug, Default)]
+pub struct ChatBallots {
+    ballots: HashMap<ChatId, (MessageId, PendingVote)>,
+}
+
+impl ChatBallots {
+    /// Opens `vote` on `message_id`, replacing any ballot already open in the chat.
+    pub fn open(&mut self, chat_id: ChatId, message_id: MessageId, vote: PendingVote) {
+        self.ballots.insert(chat_id, (message_id, vote));
+    }
+
+    /// The chat's open ballot, if its keyboard is on `message_id`.
+    pub fn get_mut(&mut self, chat_id: ChatId, message_id: MessageId) -> Option<&mut PendingVote> {
+        self.ballots.get_mut(&chat_id)
+            .filter(|(id, _)| *id == message_id)
+            .map(|(_, vote)| vote)
+    }
+
+    /// Closes and returns the chat's open ballot, if its keyboard is on `message_id`.
+    pub fn remove(&mut self, chat_id: ChatId, message_id: MessageId) -> Option<PendingVote> {
+        self.get_mut(chat_id, message_id)?;
+        self.ballots.remove(&chat_id).map(|(_, vote)| vote)
+    }
+}
+
+/// Which chats may run which commands: `chat_id` and `allowed_chat_ids` may run
+/// everything, other chats only read-only commands and only when
+/// `public_read_only_commands` is set.
+#[derive(Clone, Debug)]
+pub struct ChatAccess {
+    allowed_chat_ids: Vec<String>,
+    public_read_only: bool,
+}
+
+impl ChatAccess {
+    pub fn new(config: &TelegramConfig) -> Self {
+        let mut allowed_chat_ids = config.allowed_chat_ids.clone();
+        if !config.chat_id.is_empty() && !allowed_chat_ids.contains(&config.chat_id) {
+            allowed_chat_ids.push(config.chat_id.clone());
+        }
+        Self { allowed_chat_ids, public_read_only: config.public_read_only_commands }
+    }
+
+    pub fn allows_chat(&self, chat_id: ChatId) -> bool {
+        self.allowed_chat_ids.contains(&chat_id.to_string())
+    }
+
+    pub fn allows(&self, chat_id: ChatId, command: &TelegramCommand) -> bool {
+        self.allows_chat(chat_id) || (self.public_read_only && command.is_read_only())
+    }
+}
 
 pub struct TelegramBot {
     bot: Bot,
-    command_sender: mpsc::Sender<(TelegramCommand, oneshot::Sender<String>)>,
+    command_sender: mpsc::Sender<ExecutorRequest>,
+    pending_votes: PendingVotes,
+    access: ChatAccess,
 }
 
 impl TelegramBot {
-    pub fn new(bot: Bot, command_sender: mpsc::Sender<(TelegramCommand, oneshot::Sender<String>)>) -> Self {
-        Self { bot, command_sender }
+    pub fn new(bot: Bot, command_sender: mpsc::Sender<ExecutorRequest>, access: ChatAccess) -> Self {
+        Self { bot, command_sender, pending_votes: PendingVotes::default(), access }
     }
 
     pub async fn run(self) {
-        let handler = Update::filter_message()
+        let callback_sender = self.command_sender.clone();
+        let callback_votes = self.pending_votes.clone();
+        let callback_access = self.access.clone();
+
+        let command_handler = Update::filter_message()
             .filter_command::<TelegramCommand>()
             .chain(dptree::endpoint(
                 move |bot: Bot, msg: Message, cmd: TelegramCommand| {
                     let command_sender = self.command_sender.clone();
+                    let pending_votes = self.pending_votes.clone();
+                    let access = self.access.clone();
                     async move {
+                        if !access.allows(msg.chat.id, &cmd) {
+                            log::warn!("Rejected command from chat {} not on the allowlist: {}", msg.chat.id, msg.text().unwrap_or_default());
+                            bot.send_message(msg.chat.id, "Sorry, this chat isn't allowed to run that command.").await?;
+                            return Ok(());
+                        }
+
+                        if let TelegramCommand::StartVote { args } = &cmd {
+                            return start_vote(&bot, msg.chat.id, args, &command_sender, &pending_votes).await;
+                        }
+
                         let (response_sender, response_receiver) = oneshot::channel();
                         
-                        if let Err(e) = command_sender.send((cmd, response_sender)).await {
+                        if let Err(e) = command_sender.send(ExecutorRequest::Command(cmd, msg.chat.id, response_sender)).await {
                             bot.send_message(
                                 msg.chat.id,
                                 format!("Error sending command: {}", e)
@@ -64,6 +160,27 @@ impl TelegramBot {
                     }
                 }
             ));
+
+        let callback_handler = Update::filter_callback_query()
+            .chain(dptree::endpoint(
+                move |bot: Bot, query: CallbackQuery| {
+                    let command_sender = callback_sender.clone();
+                    let pending_votes = callback_votes.clone();
+                    let access = callback_access.clone();
+                    async move {
+                        if !query.message.as_ref().is_some_and(|m| access.allows_chat(m.chat().id)) {
+                            log::warn!("Rejected vote button press from a chat not on the allowlist");
+                            bot.answer_callback_query(query.id).await?;
+                            return Ok(());
+                        }
+                        handle_vote_callback(&bot, query, &command_sender, &pending_votes).await
+                    }
+                }
+            ));
+
+        let handler = dptree::entry()
+            .branch(command_handler)
+            .branch(callback_handler);
     
         Dispatcher::builder(self.bot, handler)
             .dependencies(dptree::deps![InMemStorage::<()>::new()])
@@ -79,18 +196,123 @@ impl TelegramBot {
     }
 }
 
+/// Looks up the seated teams and posts the ballot keyboard, replacing any ballot already open in the chat.
+async fn start_vote(
+    bot: &Bot,
+    chat_id: ChatId,
+    args: &str,
+    command_sender: &mpsc::Sender<ExecutorRequest>,
+    pending_votes: &PendingVotes,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let proposal_name = match TelegramCommand::parse_start_vote(args) {
+        Ok(name) => name,
+        Err(e) => {
+            bot.send_message(chat_id, e).await?;
+            return Ok(());
+        }
+    };
+
+    let (response_sender, response_receiver) = oneshot::channel();
+    command_sender.send(ExecutorRequest::StartVote(proposal_name, response_sender)).await?;
+
+    match response_receiver.await? {
+        Ok(vote) => {
+            let message = bot.send_message(chat_id, vote.summary())
+                .reply_markup(vote.keyboard())
+                .await?;
+            pending_votes.lock().await.open(chat_id, message.id, vote);
+        },
+        Err(e) => {
+            bot.send_message(chat_id, e).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn handle_vote_callback(
+    bot: &Bot,
+    query: CallbackQuery,
+    command_sender: &mpsc::Sender<ExecutorRequest>,
+    pending_votes: &PendingVotes,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    let (Some(data), Some(message)) = (query.data.as_deref(), query.message.as_ref()) else {
+        return Ok(());
+    };
+    let Some(callback) = VoteCallback::parse(data) else {
+        return Ok(());
+    };
+    let (chat_id, message_id): (ChatId, MessageId) = (message.chat().id, message.id());
+
+    let mut votes = pending_votes.lock().await;
+    let Some(vote) = votes.get_mut(chat_id, message_id) else {
+        bot.edit_message_text(chat_id, message_id, "This vote is no longer open. Start again with /start_vote").await?;
+        return Ok(());
+    };
+
+    match callback {
+        VoteCallback::Choice(team_index, choice) => {
+            if vote.record_choice(team_index, choice).is_ok() {
+                bot.edit_message_text(chat_id, message_id, vote.summary())
+                    .reply_markup(vote.keyboard())
+                    .await?;
+            }
+        },
+        VoteCallback::Submit => {
+            let vote = votes.remove(chat_id, message_id).expect("pending vote checked above");
+            drop(votes);
+
+            let (response_sender, response_receiver) = oneshot::channel();
+            command_sender.send(ExecutorRequest::SubmitVote(vote, response_sender)).await?;
+            let response = response_receiver.await?;
+            bot.edit_message_text(chat_id, message_id, response).await?;
+        },
+        VoteCallback::Cancel => {
+            let vote = votes.remove(chat_id, message_id).expect("pending vote checked above");
+            bot.edit_message_text(chat_id, message_id, format!("Vote on {} cancelled", vote.proposal_name())).await?;
+        },
+    }
+    Ok(())
+}
+
 pub fn spawn_command_executor(
     mut budget_system: BudgetSystem,
-    mut command_receiver: mpsc::Receiver<(TelegramCommand, oneshot::Sender<String>)>,
+    mut command_receiver: mpsc::Receiver<ExecutorRequest>,
 ) {
     tokio::spawn(async move {
-        while let Some((telegram_command, response_sender)) = command_receiver.recv().await {
-            let response = match execute_command(telegram_command, &mut budget_system).await {
-                Ok(output) => crate::escape_markdown(&output),
-                Err(e) => format!("Error: {}", crate::escape_markdown(&e)),
+        while let Some(request) = command_receiver.recv().await {
+            let sent = match request {
+                ExecutorRequest::Command(telegram_command, chat_id, response_sender) => {
+                    let response = match execute_chat_command(telegram_command, &mut budget_system, &chat_id.to_string()).await {
+                        Ok(output) => crate::escape_markdown(&output),
+                        Err(e) => format!("Error: {}", crate::escape_markdown(&e)),
+                    };
+                    response_sender.send(response).is_ok()
+                },
+                ExecutorRequest::StartVote(proposal_name, response_sender) => {
+                    let vote = PendingVote::for_proposal(&budget_system, &proposal_name);
+                    response_sender.send(vote).is_ok()
+                },
+                ExecutorRequest::SubmitVote(vote, response_sender) => {
+                    let response = match budget_system.execute_command(vote.into_command()).await {
+                        Ok(output) => output,
+                        Err(e) => command_failed(e),
+                    };
+                    response_sender.send(response).is_ok()
+                },
+                ExecutorRequest::EnforceDeadlines(response_sender) => {
+                    crate::enforce_deadlines(&mut budget_system);
+                    response_sender.send(()).is_ok()
+                },
+                ExecutorRequest::OverduePayments(days_overdue, response_sender) => {
+                    let overdue = budget_system.get_overdue_payments(days_overdue)
+                        .map_err(|e| e.to_string());
+                    response_sender.send(overdue).is_ok()
+                },
             };
 
-            if let Err(_) = response_sender.send(response) {
+            if !sent {
                 log::error!("Failed to send response");
             }
 
@@ -101,35 +323,114 @@ pub fn spawn_command_executor(
     });
 }
 
+/// Posts a one-off message to the configured chat, outside of the command loop (e.g. from the CLI).
+/// The text must already be escaped for MarkdownV2.
+pub async fn post_to_chat(token: &str, chat_id: &str, text: String) -> Result<(), Box<dyn Error>> {
+    let chat_id = chat_id.parse::<i64>()
+        .map_err(|_| "No valid telegram.chat_id configured")?;
+    Bot::new(token)
+        .send_message(ChatId(chat_id), text)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+    Ok(())
+}
+
+/// Periodically asks the command executor for overdue payments and posts them to the admin chat.
+/// Nothing is posted when there are no overdue payments, and failed checks are only logged.
+pub fn spawn_overdue_payment_check(
+    bot: Bot,
+    chat_id: ChatId,
+    days_overdue: u64,
+    check_interval: Duration,
+    command_sender: mpsc::Sender<ExecutorRequest>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(check_interval);
+        loop {
+            interval.tick().await;
+
+            let (response_sender, response_receiver) = oneshot::channel();
+            if command_sender.send(ExecutorRequest::OverduePayments(days_overdue, response_sender)).await.is_err() {
+                log::error!("Command executor stopped, ending overdue payment check");
+                break;
+            }
+
+            match response_receiver.await {
+                Ok(Ok(overdue)) if overdue.is_empty() => {},
+                Ok(Ok(overdue)) => {
+                    let message = crate::escape_markdown(&OverduePayment::format_list(&overdue, days_overdue));
+                    if let Err(e) = bot.send_message(chat_id, message)
+                        .parse_mode(ParseMode::MarkdownV2)
+                        .await {
+                        log::error!("Failed to send overdue payment notification: {}", e);
+                    }
+                },
+                Ok(Err(e)) => log::error!("Overdue payment check failed: {}", e),
+                Err(e) => log::error!("Failed to receive overdue payment check response: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically asks the command executor to close votes and proposals past their
+/// deadlines, starting with an immediate check.
+pub fn spawn_deadline_check(
+    check_interval: Duration,
+    command_sender: mpsc::Sender<ExecutorRequest>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(check_interval);
+        loop {
+            interval.tick().await;
+
+            let (response_sender, response_receiver) = oneshot::channel();
+            if command_sender.send(ExecutorRequest::EnforceDeadlines(response_sender)).await.is_err() {
+                log::error!("Command executor stopped, ending deadline check");
+                break;
+            }
+            if let Err(e) = response_receiver.await {
+                log::error!("Failed to receive deadline check response: {}", e);
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::app_config::AppConfig;
+    use crate::core::models::VoteChoice;
     use crate::services::ethereum::MockEthereumService;
     use std::sync::Arc;
+    use tempfile::TempDir;
 
-    async fn create_test_budget_system() -> BudgetSystem {
-        let config = AppConfig::default();
+    async fn create_test_budget_system() -> (BudgetSystem, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = AppConfig {
+            state_file: temp_dir.path().join("test_state.json").to_str().unwrap().to_string(),
+            ..AppConfig::default()
+        };
         let ethereum_service = Arc::new(MockEthereumService::new());
-        BudgetSystem::new(config, ethereum_service, None).await.unwrap()
+        let budget_system = BudgetSystem::new(config, ethereum_service, None).await.unwrap();
+        (budget_system, temp_dir)
     }
 
     #[tokio::test]
     async fn test_command_execution() {
         let (tx, rx) = mpsc::channel(100);
-        let budget_system = create_test_budget_system().await;
+        let (budget_system, _temp_dir) = create_test_budget_system().await;
         
         spawn_command_executor(budget_system, rx);
 
         // Test help command
         let (response_tx, response_rx) = oneshot::channel();
-        tx.send((TelegramCommand::Help, response_tx)).await.unwrap();
+        tx.send(ExecutorRequest::Command(TelegramCommand::Help, ChatId(1), response_tx)).await.unwrap();
         let response = response_rx.await.unwrap();
         assert!(response.contains("Display this text"));
 
         // Test print team report
         let (response_tx, response_rx) = oneshot::channel();
-        tx.send((TelegramCommand::PrintTeamReport, response_tx)).await.unwrap();
+        tx.send(ExecutorRequest::Command(TelegramCommand::PrintTeamReport, ChatId(1), response_tx)).await.unwrap();
         let response = response_rx.await.unwrap();
         assert!(response.contains("Team Report"));
     }
@@ -137,21 +438,116 @@ mod tests {
     #[tokio::test]
     async fn test_error_handling() {
         let (tx, rx) = mpsc::channel(100);
-        let budget_system = create_test_budget_system().await;
+        let (budget_system, _temp_dir) = create_test_budget_system().await;
         
         spawn_command_executor(budget_system, rx);
 
         // Test command with non-existent team
         let (response_tx, response_rx) = oneshot::channel();
-        tx.send((
+        tx.send(ExecutorRequest::Command(
             TelegramCommand::PrintTeamParticipation {
                 team_name: "NonExistentTeam".to_string(),
                 epoch_name: "NonExistentEpoch".to_string()
             },
+            ChatId(1),
             response_tx
         )).await.unwrap();
 
         let response = response_rx.await.unwrap();
         assert!(response.contains("Error"));
     }
+
+    #[test]
+    fn test_chat_access() {
+        let mut config = AppConfig::default().telegram;
+        config.chat_id = "-100".to_string();
+        config.allowed_chat_ids = vec!["42".to_string()];
+
+        let close = TelegramCommand::CloseProposal { args: "name:P res:Approved".to_string() };
+        let access = ChatAccess::new(&config);
+        assert!(access.allows(ChatId(-100), &close));
+        assert!(access.allows(ChatId(42), &close));
+        assert!(!access.allows(ChatId(7), &close));
+        assert!(!access.allows(ChatId(7), &TelegramCommand::PrintTeamReport));
+
+        config.public_read_only_commands = true;
+        let access = ChatAccess::new(&config);
+        assert!(access.allows(ChatId(7), &TelegramCommand::PrintTeamReport));
+        assert!(!access.allows(ChatId(7), &close));
+        assert!(!access.allows(ChatId(7), &TelegramCommand::LogPayment { args: String::new() }));
+    }
+
+    #[tokio::test]
+    async fn test_enforce_deadlines_request() {
+        let (tx, rx) = mpsc::channel(100);
+        let (budget_system, _temp_dir) = create_test_budget_system().await;
+
+        spawn_command_executor(budget_system, rx);
+
+        let (response_tx, response_rx) = oneshot::channel();
+        tx.send(ExecutorRequest::EnforceDeadlines(response_tx)).await.unwrap();
+        assert!(response_rx.await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_overdue_payments_request() {
+        let (tx, rx) = mpsc::channel(100);
+        let (budget_system, _temp_dir) = create_test_budget_system().await;
+
+        spawn_command_executor(budget_system, rx);
+
+        let (response_tx, response_rx) = oneshot::channel();
+        tx.send(ExecutorRequest::OverduePayments(30, response_tx)).await.unwrap();
+        assert_eq!(response_rx.await.unwrap(), Ok(Vec::new()));
+    }
+
+    #[tokio::test]
+    async fn test_chat_ballots_follow_latest_keyboard() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = AppConfig {
+            state_file: temp_dir.path().join("state.json").to_str().unwrap().to_string(),
+            ..AppConfig::default()
+        };
+        let mut budget_system = BudgetSystem::new(config.clone(), Arc::new(MockEthereumService::new()), None).await.unwrap();
+
+        let start = chrono::Utc::now();
+        let epoch_id = budget_system.create_epoch("Epoch", start, start + chrono::Duration::days(30)).unwrap();
+        budget_system.activate_epoch(epoch_id).unwrap();
+        budget_system.create_team("Team A".to_string(), "Rep".to_string(), Some(vec![4000]), None).unwrap();
+        for name in ["First", "Second"] {
+            budget_system.add_proposal(name.to_string(), None, None, Some(start.date_naive()), Some(start.date_naive()), None, None).unwrap();
+            let (raffle_id, _) = budget_system.prepare_raffle(name, None, &config).unwrap();
+            budget_system.finalize_raffle(raffle_id, 12345, 12355, "mock_randomness".to_string()).await.unwrap();
+        }
+
+        let chat = ChatId(42);
+        let mut ballots = ChatBallots::default();
+        ballots.open(chat, MessageId(1), PendingVote::for_proposal(&budget_system, "First").unwrap());
+        ballots.open(chat, MessageId(2), PendingVote::for_proposal(&budget_system, "Second").unwrap());
+
+        // Buttons on the first keyboard no longer reach a ballot
+        assert!(ballots.get_mut(chat, MessageId(1)).is_none());
+        assert!(ballots.remove(chat, MessageId(1)).is_none());
+        assert!(ballots.get_mut(ChatId(7), MessageId(2)).is_none());
+
+        ballots.get_mut(chat, MessageId(2)).unwrap().record_choice(0, VoteChoice::Yes).unwrap();
+        let vote = ballots.remove(chat, MessageId(2)).unwrap();
+        assert_eq!(vote.proposal_name(), "Second");
+        assert!(vote.summary().contains("Team A: Yes"));
+        assert!(ballots.get_mut(chat, MessageId(2)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_start_vote_without_raffle() {
+        let (tx, rx) = mpsc::channel(100);
+        let (budget_system, _temp_dir) = create_test_budget_system().await;
+
+        spawn_command_executor(budget_system, rx);
+
+        let (response_tx, response_rx) = oneshot::channel();
+        tx.send(ExecutorRequest::StartVote("Missing Proposal".to_string(), response_tx)).await.unwrap();
+        let error = response_rx.await.unwrap().unwrap_err();
+        assert!(error.contains("Proposal not found"));
+        assert!(error.contains("/create_raffle"));
+    }
 }
\ No newline at end of file